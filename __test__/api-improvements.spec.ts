@@ -254,3 +254,249 @@ test('AudioDecoder: ondequeue getter returns function after setter', (t) => {
   t.is(decoder.ondequeue, callback)
   decoder.close()
 })
+
+// ============================================================================
+// Phase 4: isConfigSupported() config normalization
+// ============================================================================
+
+const audioEncoderNormalizationCases = [
+  {
+    comment: 'unknown keys are stripped and bitrateMode defaults to variable',
+    input: { codec: 'opus', sampleRate: 48000, numberOfChannels: 2, futureFeature: true },
+    expected: { codec: 'opus', sampleRate: 48000, numberOfChannels: 2, bitrateMode: 'variable' },
+  },
+  {
+    comment: 'opus block is kept for opus with defaults filled',
+    input: { codec: 'opus', sampleRate: 48000, numberOfChannels: 1, opus: { complexity: 5, bogus: 1 } },
+    expected: {
+      codec: 'opus',
+      sampleRate: 48000,
+      numberOfChannels: 1,
+      bitrateMode: 'variable',
+      opus: {
+        format: 'opus',
+        signal: 'auto',
+        application: 'audio',
+        frameDuration: 20000,
+        complexity: 5,
+        packetlossperc: 0,
+        useinbandfec: false,
+        usedtx: false,
+      },
+    },
+  },
+  {
+    comment: 'mismatched codec-specific blocks are dropped',
+    input: {
+      codec: 'mp4a.40.2',
+      sampleRate: 44100,
+      numberOfChannels: 2,
+      bitrate: 128000,
+      bitrateMode: 'constant',
+      opus: { complexity: 5 },
+      aac: {},
+      flac: { compressLevel: 8 },
+    },
+    expected: {
+      codec: 'mp4a.40.2',
+      sampleRate: 44100,
+      numberOfChannels: 2,
+      bitrate: 128000,
+      bitrateMode: 'constant',
+      aac: { format: 'aac' },
+    },
+  },
+]
+
+for (const entry of audioEncoderNormalizationCases) {
+  test(`AudioEncoder.isConfigSupported normalization: ${entry.comment}`, async (t) => {
+    const support = await AudioEncoder.isConfigSupported(entry.input as Parameters<typeof AudioEncoder.isConfigSupported>[0])
+    t.deepEqual({ ...support.config }, entry.expected)
+  })
+}
+
+test('AudioDecoder.isConfigSupported normalization: unknown keys stripped, description copied', async (t) => {
+  const description = new Uint8Array([0x12, 0x10])
+  const support = await AudioDecoder.isConfigSupported({
+    codec: 'mp4a.40.2',
+    sampleRate: 44100,
+    numberOfChannels: 2,
+    description,
+    futureFeature: 'x',
+  } as Parameters<typeof AudioDecoder.isConfigSupported>[0])
+  t.deepEqual(Object.keys(support.config).sort(), ['codec', 'description', 'numberOfChannels', 'sampleRate'])
+  t.deepEqual(Array.from(support.config.description as Uint8Array), [0x12, 0x10])
+  t.not(support.config.description, description, 'description must be a copy')
+})
+
+const videoEncoderNormalizationCases = [
+  {
+    comment: 'unknown keys are stripped and defaults (including bitrate) filled',
+    input: { codec: 'vp8', width: 640, height: 480, futureFeature: true },
+    expected: {
+      codec: 'vp8',
+      width: 640,
+      height: 480,
+      bitrate: 922_000,
+      hardwareAcceleration: 'no-preference',
+      latencyMode: 'quality',
+      bitrateMode: 'variable',
+      alpha: 'discard',
+    },
+  },
+  {
+    comment: 'avc block is kept for avc1 with format default',
+    input: { codec: 'avc1.42001E', width: 640, height: 480, latencyMode: 'realtime', avc: {}, hevc: {} },
+    expected: {
+      codec: 'avc1.42001E',
+      width: 640,
+      height: 480,
+      bitrate: 922_000,
+      hardwareAcceleration: 'no-preference',
+      latencyMode: 'realtime',
+      bitrateMode: 'variable',
+      alpha: 'discard',
+      avc: { format: 'avc' },
+    },
+  },
+  {
+    comment: 'avc block is dropped for non-avc codecs',
+    input: { codec: 'vp8', width: 320, height: 240, bitrate: 500_000, avc: { format: 'annexb' } },
+    expected: {
+      codec: 'vp8',
+      width: 320,
+      height: 240,
+      bitrate: 500_000,
+      hardwareAcceleration: 'no-preference',
+      latencyMode: 'quality',
+      bitrateMode: 'variable',
+      alpha: 'discard',
+    },
+  },
+]
+
+for (const entry of videoEncoderNormalizationCases) {
+  test(`VideoEncoder.isConfigSupported normalization: ${entry.comment}`, async (t) => {
+    const support = await VideoEncoder.isConfigSupported(entry.input as Parameters<typeof VideoEncoder.isConfigSupported>[0])
+    t.deepEqual({ ...support.config }, entry.expected)
+  })
+}
+
+test('VideoDecoder.isConfigSupported normalization: unknown keys stripped and defaults filled', async (t) => {
+  const support = await VideoDecoder.isConfigSupported({
+    codec: 'vp8',
+    futureFeature: true,
+  } as Parameters<typeof VideoDecoder.isConfigSupported>[0])
+  t.deepEqual(
+    { ...support.config },
+    { codec: 'vp8', hardwareAcceleration: 'no-preference', rotation: 0, flip: false },
+  )
+})
+
+// ============================================================================
+// Phase 5: configure() error timing (validity vs support)
+// ============================================================================
+
+// W3C spec: structurally invalid configs throw TypeError from configure() (and reject
+// isConfigSupported()), leaving the codec unconfigured. Valid but unsupported configs
+// leave the codec configured until a queued task closes it with NotSupportedError.
+type ErrorChannel = 'sync-type-error' | 'async-not-supported'
+
+const videoBase = { codec: 'vp8', width: 320, height: 240 }
+const audioBase = { codec: 'opus', sampleRate: 48000, numberOfChannels: 2 }
+
+const videoErrorTimingCases: Array<{ comment: string; config: Record<string, unknown>; channel: ErrorChannel }> = [
+  { comment: 'empty codec', config: { ...videoBase, codec: '' }, channel: 'sync-type-error' },
+  { comment: 'whitespace-only codec', config: { ...videoBase, codec: '  ' }, channel: 'sync-type-error' },
+  { comment: 'width is 0', config: { ...videoBase, width: 0 }, channel: 'sync-type-error' },
+  { comment: 'negative width', config: { ...videoBase, width: -320 }, channel: 'sync-type-error' },
+  { comment: 'displayHeight is 0', config: { ...videoBase, displayHeight: 0 }, channel: 'sync-type-error' },
+  { comment: 'bitrate is 0', config: { ...videoBase, bitrate: 0 }, channel: 'sync-type-error' },
+  { comment: 'negative framerate', config: { ...videoBase, framerate: -30 }, channel: 'sync-type-error' },
+  { comment: 'pass 3', config: { ...videoBase, pass: 3 }, channel: 'sync-type-error' },
+  { comment: 'unrecognized codec', config: { ...videoBase, codec: 'bogus' }, channel: 'async-not-supported' },
+  { comment: 'huge dimensions', config: { ...videoBase, width: 100000 }, channel: 'async-not-supported' },
+  {
+    comment: 'unknown scalability mode',
+    config: { ...videoBase, scalabilityMode: 'ABC' },
+    channel: 'async-not-supported',
+  },
+  {
+    comment: 'two-pass H.264',
+    config: { codec: 'avc1.42001E', width: 320, height: 240, pass: 1 },
+    channel: 'async-not-supported',
+  },
+]
+
+const audioErrorTimingCases: Array<{ comment: string; config: Record<string, unknown>; channel: ErrorChannel }> = [
+  { comment: 'sampleRate is 0', config: { ...audioBase, sampleRate: 0 }, channel: 'sync-type-error' },
+  { comment: 'negative numberOfChannels', config: { ...audioBase, numberOfChannels: -2 }, channel: 'sync-type-error' },
+  { comment: 'NaN bitrate', config: { ...audioBase, bitrate: NaN }, channel: 'sync-type-error' },
+  { comment: 'unrecognized codec', config: { ...audioBase, codec: 'bogus' }, channel: 'async-not-supported' },
+]
+
+function waitForErrorCallback() {
+  // Error callbacks are delivered through a non-blocking threadsafe function
+  return new Promise((resolve) => setTimeout(resolve, 50))
+}
+
+for (const entry of videoErrorTimingCases) {
+  test(`VideoEncoder.configure error timing: ${entry.comment}`, async (t) => {
+    const { encoder, errors } = createTestEncoder()
+    const config = entry.config as Parameters<VideoEncoder['configure']>[0]
+
+    if (entry.channel === 'sync-type-error') {
+      t.throws(() => encoder.configure(config), { instanceOf: TypeError })
+      t.is(encoder.state, 'unconfigured')
+      await t.throwsAsync(VideoEncoder.isConfigSupported(config), { instanceOf: TypeError })
+      await waitForErrorCallback()
+      t.is(errors.length, 0)
+      encoder.close()
+      return
+    }
+
+    t.notThrows(() => encoder.configure(config))
+    t.is(encoder.state, 'configured')
+    await t.throwsAsync(encoder.flush(), { message: /NotSupportedError/ })
+    await waitForErrorCallback()
+    t.is(errors.length, 1)
+    t.regex(errors[0].message, /NotSupportedError/)
+    t.is(encoder.state, 'closed')
+  })
+}
+
+for (const entry of audioErrorTimingCases) {
+  test(`AudioEncoder.configure error timing: ${entry.comment}`, async (t) => {
+    const errors: Error[] = []
+    const encoder = new AudioEncoder({ output: () => {}, error: (e) => errors.push(e) })
+    const config = entry.config as Parameters<AudioEncoder['configure']>[0]
+
+    if (entry.channel === 'sync-type-error') {
+      t.throws(() => encoder.configure(config), { instanceOf: TypeError })
+      t.is(encoder.state, 'unconfigured')
+      await t.throwsAsync(AudioEncoder.isConfigSupported(config), { instanceOf: TypeError })
+      await waitForErrorCallback()
+      t.is(errors.length, 0)
+      encoder.close()
+      return
+    }
+
+    t.notThrows(() => encoder.configure(config))
+    t.is(encoder.state, 'configured')
+    await t.throwsAsync(encoder.flush(), { message: /NotSupportedError/ })
+    await waitForErrorCallback()
+    t.is(errors.length, 1)
+    t.regex(errors[0].message, /NotSupportedError/)
+    t.is(encoder.state, 'closed')
+  })
+}
+
+test('VideoEncoder.configure error timing: reset() before the support check discards the error', async (t) => {
+  const { encoder, errors } = createTestEncoder()
+  encoder.configure({ ...videoBase, codec: 'bogus' })
+  encoder.reset()
+  await waitForErrorCallback()
+  t.is(errors.length, 0)
+  t.is(encoder.state, 'unconfigured')
+  encoder.close()
+})
@@ -0,0 +1,149 @@
+/**
+ * Audio Bitstream Tests
+ *
+ * Tests for adtsToRaw(), rawToAdts() and parseToc().
+ */
+
+import test from 'ava'
+
+import { AudioEncoder, adtsToRaw, parseToc, rawToAdts } from '../index.js'
+import { generateSineTone, type EncodedAudioChunk } from './helpers/index.js'
+
+/** Two AAC-LC 44.1 kHz stereo ADTS frames with 3- and 2-byte payloads */
+const ADTS = new Uint8Array([
+  0xff, 0xf1, 0x50, 0x80, 0x01, 0x5f, 0xfc, 0x21, 0x10, 0x04, 0xff, 0xf1, 0x50, 0x80, 0x01, 0x3f, 0xfc, 0x21, 0x1c,
+])
+
+function chunkBytes(chunk: EncodedAudioChunk): Uint8Array {
+  const data = new Uint8Array(chunk.byteLength)
+  chunk.copyTo(data)
+  return data
+}
+
+// ============================================================================
+// ADTS Tests
+// ============================================================================
+
+test('adtsToRaw: splits frames and derives the AudioSpecificConfig', (t) => {
+  const { asc, frames } = adtsToRaw(ADTS)
+
+  t.deepEqual(Array.from(asc), [0x12, 0x10])
+  t.is(frames.length, 2)
+  t.deepEqual(Array.from(frames[0]), [0x21, 0x10, 0x04])
+  t.deepEqual(Array.from(frames[1]), [0x21, 0x1c])
+})
+
+test('rawToAdts: reconstructs the original frames byte for byte', (t) => {
+  const { asc, frames } = adtsToRaw(ADTS)
+
+  const rebuilt = frames.map((frame) => rawToAdts(frame, asc))
+  t.deepEqual(Array.from(rebuilt[0]), Array.from(ADTS.subarray(0, 10)))
+  t.deepEqual(Array.from(rebuilt[1]), Array.from(ADTS.subarray(10)))
+})
+
+test('adtsToRaw: rejects truncated and unsynchronized data', (t) => {
+  t.throws(() => adtsToRaw(ADTS.subarray(0, 18)), { message: /invalid length/ })
+  t.throws(() => adtsToRaw(new Uint8Array(9)), { message: /syncword/ })
+  t.throws(() => adtsToRaw(new Uint8Array(0)), { message: /empty/ })
+})
+
+test('rawToAdts: rejects configs ADTS cannot carry', (t) => {
+  // Audio object type 5 (SBR)
+  t.throws(() => rawToAdts(new Uint8Array([0x21]), new Uint8Array([0x2a, 0x10])), { message: /object type 5/ })
+  t.throws(() => rawToAdts(new Uint8Array([0x21]), new Uint8Array([0x12])), { message: /truncated/ })
+})
+
+test('adtsToRaw: round trips AudioEncoder ADTS output', async (t) => {
+  const chunks: EncodedAudioChunk[] = []
+  const encoder = new AudioEncoder({
+    output: (chunk) => chunks.push(chunk),
+    error: (e) => t.fail(`Encoder error: ${e.message}`),
+  })
+  encoder.configure({
+    codec: 'mp4a.40.2',
+    sampleRate: 48000,
+    numberOfChannels: 2,
+    bitrate: 128000,
+    aac: { format: 'adts' },
+  })
+
+  for (let i = 0; i < 4; i++) {
+    const audio = generateSineTone(440, 1024, 2, 48000, 'f32', i * 21333)
+    encoder.encode(audio)
+    audio.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  t.true(chunks.length > 0)
+  for (const chunk of chunks) {
+    const data = chunkBytes(chunk)
+    const { asc, frames } = adtsToRaw(data)
+    // AAC-LC, 48 kHz (index 3), 2 channels
+    t.deepEqual(Array.from(asc), [0x11, 0x90])
+    t.is(frames.length, 1)
+    t.deepEqual(Array.from(rawToAdts(frames[0], asc)), Array.from(data))
+  }
+})
+
+// ============================================================================
+// Opus TOC Tests
+// ============================================================================
+
+test('parseToc: CELT fullband 20 ms stereo', (t) => {
+  const toc = parseToc(new Uint8Array([0xfc, 0x00]))
+
+  t.is(toc.config, 31)
+  t.is(toc.mode, 'celt')
+  t.is(toc.bandwidth, 'fullband')
+  t.true(toc.stereo)
+  t.is(toc.code, 0)
+  t.is(toc.frameCount, 1)
+  t.is(toc.frameDuration, 20000)
+  t.is(toc.duration, 20000)
+})
+
+test('parseToc: SILK and hybrid multi-frame packets', (t) => {
+  // SILK wideband 20 ms mono, code 3 with 3 frames
+  const silk = parseToc(new Uint8Array([0x4b, 0x03, 0x00]))
+  t.is(silk.mode, 'silk')
+  t.is(silk.bandwidth, 'wideband')
+  t.false(silk.stereo)
+  t.is(silk.frameCount, 3)
+  t.is(silk.duration, 60000)
+
+  // Hybrid super-wideband 10 ms, code 1 with two equal frames
+  const hybrid = parseToc(new Uint8Array([0x61, 0x00, 0x00]))
+  t.is(hybrid.mode, 'hybrid')
+  t.is(hybrid.bandwidth, 'superwideband')
+  t.is(hybrid.frameCount, 2)
+  t.is(hybrid.duration, 20000)
+})
+
+test('parseToc: rejects malformed packets', (t) => {
+  t.throws(() => parseToc(new Uint8Array(0)), { message: /empty/ })
+  // Code 3 with 7 x 20 ms exceeds the 120 ms packet limit
+  t.throws(() => parseToc(new Uint8Array([0x4b, 0x07])), { message: /exceeds/ })
+  // Code 3 without the frame count byte
+  t.throws(() => parseToc(new Uint8Array([0x4b])), { message: /frame count byte/ })
+})
+
+test('parseToc: matches AudioEncoder Opus output', async (t) => {
+  const chunks: EncodedAudioChunk[] = []
+  const encoder = new AudioEncoder({
+    output: (chunk) => chunks.push(chunk),
+    error: (e) => t.fail(`Encoder error: ${e.message}`),
+  })
+  encoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2, bitrate: 128000 })
+
+  const audio = generateSineTone(440, 960 * 5, 2, 48000, 'f32', 0)
+  encoder.encode(audio)
+  audio.close()
+  await encoder.flush()
+  encoder.close()
+
+  t.true(chunks.length > 0)
+  const toc = parseToc(chunkBytes(chunks[0]))
+  t.true(toc.stereo)
+  t.is(toc.duration, 20000)
+})
@@ -478,3 +478,219 @@ test('AudioDecoder: ondequeue can be set to null', (t) => {
 
   decoder.close()
 })
+
+// ============================================================================
+// Batch Decode Tests
+// ============================================================================
+
+test('AudioDecoder: decodeBatch produces the same output as per-chunk decode', async (t) => {
+  const { encoder, chunks } = createTestEncoder()
+  encoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2, bitrate: 64000 })
+  for (let i = 0; i < 25; i++) {
+    const audio = generateSineTone(440, 960, 2, 48000, 'f32', i * 20000)
+    encoder.encode(audio)
+    audio.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  t.true(chunks.length > 0)
+
+  const decodeAll = async (submit: (decoder: AudioDecoder) => void) => {
+    const { decoder, audioOutputs, errors } = createTestDecoder()
+    decoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2 })
+    submit(decoder)
+    t.is(decoder.decodeQueueSize, chunks.length)
+    await decoder.flush()
+    decoder.close()
+    t.is(errors.length, 0)
+    const outputs = audioOutputs.map((audio) => ({ timestamp: audio.timestamp, frames: audio.numberOfFrames }))
+    for (const audio of audioOutputs) {
+      audio.close()
+    }
+    return outputs
+  }
+
+  const oneByOne = await decodeAll((decoder) => {
+    for (const chunk of chunks) {
+      decoder.decode(chunk)
+    }
+  })
+  const batched = await decodeAll((decoder) => decoder.decodeBatch(chunks))
+
+  t.true(batched.length > 0)
+  t.deepEqual(batched, oneByOne)
+})
+
+// ============================================================================
+// Multi-Frame Packet Tests
+// ============================================================================
+
+/** Pack 20ms single-frame Opus packets into one code 3 (VBR) packet, or null if they differ in config */
+function packOpusFrames(packets: Uint8Array[]): Uint8Array | null {
+  const toc = packets[0][0]
+  if (packets.some((p) => p[0] !== toc || (p[0] & 0x03) !== 0)) {
+    return null
+  }
+  const header = [(toc & 0xfc) | 0x03, 0x80 | packets.length]
+  for (const packet of packets.slice(0, -1)) {
+    const size = packet.length - 1
+    if (size < 252) {
+      header.push(size)
+    } else {
+      const first = 252 + ((size - 252) & 0x03)
+      header.push(first, (size - first) >> 2)
+    }
+  }
+  const payloadSize = packets.reduce((sum, p) => sum + p.length - 1, 0)
+  const out = new Uint8Array(header.length + payloadSize)
+  out.set(header)
+  let offset = header.length
+  for (const packet of packets) {
+    out.set(packet.subarray(1), offset)
+    offset += packet.length - 1
+  }
+  return out
+}
+
+test('AudioDecoder: 120ms Opus packets advance timestamps by decoded samples and dequeue after output', async (t) => {
+  const { encoder, chunks } = createTestEncoder()
+  encoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2, bitrate: 64000 })
+  for (let i = 0; i < 30; i++) {
+    const audio = generateSineTone(440, 960, 2, 48000, 'f32', i * 20000)
+    encoder.encode(audio)
+    audio.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const frames = chunks.map((chunk) => {
+    const data = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(data)
+    return data
+  })
+  const packets: EncodedAudioChunk[] = []
+  for (let i = 0; i + 6 <= frames.length; i += 6) {
+    const data = packOpusFrames(frames.slice(i, i + 6))
+    if (!data) {
+      t.pass('Encoder output changed Opus config between frames')
+      return
+    }
+    packets.push(new EncodedAudioChunk({ type: 'key', timestamp: (i / 6) * 120_000, data }))
+  }
+  t.true(packets.length > 0)
+
+  const events: string[] = []
+  const queueSizes: number[] = []
+  const outputs: { timestamp: number; frames: number }[] = []
+  const decoder = new AudioDecoder({
+    output: (data) => {
+      events.push('output')
+      outputs.push({ timestamp: data.timestamp, frames: data.numberOfFrames })
+      data.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  let allDequeued: () => void
+  const dequeued = new Promise<void>((resolve) => (allDequeued = resolve))
+  decoder.ondequeue = () => {
+    events.push('dequeue')
+    queueSizes.push(decoder.decodeQueueSize)
+    if (queueSizes.length === packets.length) allDequeued()
+  }
+  decoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2 })
+
+  for (const packet of packets) {
+    decoder.decode(packet)
+  }
+  t.is(decoder.decodeQueueSize, packets.length)
+  await dequeued
+  await decoder.flush()
+  decoder.close()
+
+  // Each packet's dequeue comes after its output, with the queue counting down to 0
+  t.is(events[0], 'output')
+  t.deepEqual(queueSizes, packets.map((_, i) => packets.length - 1 - i))
+
+  t.is(outputs[0].timestamp, 0)
+  t.is(outputs.reduce((sum, o) => sum + o.frames, 0), packets.length * 5760)
+  for (let i = 1; i < outputs.length; i++) {
+    const expected = outputs[i - 1].timestamp + (outputs[i - 1].frames * 1_000_000) / 48000
+    t.true(Math.abs(outputs[i].timestamp - expected) <= 1, `output ${i} timestamp ${outputs[i].timestamp}`)
+  }
+})
+
+// ============================================================================
+// Output Timestamp Tests
+// ============================================================================
+
+/** Opus chunks re-stamped as if the stream started 1.4s into its container, with PTS jitter */
+async function createOffsetOpusChunks(): Promise<EncodedAudioChunk[]> {
+  const { encoder, chunks } = createTestEncoder()
+  encoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2, bitrate: 64000 })
+  for (let i = 0; i < 10; i++) {
+    const audio = generateSineTone(440, 960, 2, 48000, 'f32', i * 20000)
+    encoder.encode(audio)
+    audio.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  return chunks.map((chunk, i) => {
+    const data = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(data)
+    return new EncodedAudioChunk({
+      type: chunk.type,
+      timestamp: 1_400_000 + i * 20_000 + (i % 2) * 500,
+      data,
+    })
+  })
+}
+
+async function decodeOutputs(config: Parameters<AudioDecoder['configure']>[0], chunks: EncodedAudioChunk[]) {
+  const { decoder, audioOutputs, errors } = createTestDecoder()
+  decoder.configure(config)
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+  if (errors.length > 0) throw errors[0]
+  const outputs = audioOutputs.map((audio) => ({ timestamp: audio.timestamp, frames: audio.numberOfFrames }))
+  for (const audio of audioOutputs) {
+    audio.close()
+  }
+  return outputs
+}
+
+test('AudioDecoder: timestampMode "container" preserves a 1.4s start offset', async (t) => {
+  const chunks = await createOffsetOpusChunks()
+  const outputs = await decodeOutputs({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2 }, chunks)
+
+  t.is(outputs.length, chunks.length)
+  t.deepEqual(outputs.map((output) => output.timestamp), chunks.map((chunk) => chunk.timestamp))
+})
+
+test('AudioDecoder: startAtZero subtracts the first output timestamp', async (t) => {
+  const chunks = await createOffsetOpusChunks()
+  const outputs = await decodeOutputs(
+    { codec: 'opus', sampleRate: 48000, numberOfChannels: 2, startAtZero: true },
+    chunks,
+  )
+
+  t.is(outputs.length, chunks.length)
+  t.deepEqual(outputs.map((output) => output.timestamp), chunks.map((chunk) => chunk.timestamp - 1_400_000))
+})
+
+test('AudioDecoder: timestampMode "normalized" follows the decoded sample count', async (t) => {
+  const chunks = await createOffsetOpusChunks()
+  const outputs = await decodeOutputs(
+    { codec: 'opus', sampleRate: 48000, numberOfChannels: 2, timestampMode: 'normalized', startAtZero: true },
+    chunks,
+  )
+
+  let samples = 0
+  for (const output of outputs) {
+    t.is(output.timestamp, Math.floor((samples * 1_000_000) / 48000))
+    samples += output.frames
+  }
+})
@@ -7,7 +7,7 @@
 
 import test from 'ava'
 
-import { AudioEncoder } from '../index.js'
+import { AudioDecoder, AudioEncoder, type AudioData } from '../index.js'
 import { generateSineTone, generateSilence, type EncodedAudioChunk } from './helpers/index.js'
 
 // Helper to create encoder with callbacks that collect output
@@ -125,7 +125,7 @@ test('AudioEncoder: configure() with mono audio', (t) => {
   encoder.close()
 })
 
-test('AudioEncoder: configure() with invalid codec triggers error callback', (t) => {
+test('AudioEncoder: configure() with invalid codec triggers error callback', async (t) => {
   const { encoder } = createTestEncoder()
 
   encoder.configure({
@@ -134,7 +134,9 @@ test('AudioEncoder: configure() with invalid codec triggers error callback', (t)
     numberOfChannels: 2,
   })
 
-  // Error callback transitions to closed
+  // W3C spec: support is checked in a queued task, so the codec is closed asynchronously
+  t.is(encoder.state, 'configured')
+  await new Promise((resolve) => setTimeout(resolve, 0))
   t.is(encoder.state, 'closed')
 
   // Already closed by error callback, so close() throws InvalidStateError
@@ -404,6 +406,23 @@ test('AudioEncoder.isConfigSupported: invalid codec not supported', async (t) =>
   t.false(result.supported)
 })
 
+test('AudioEncoder.isConfigSupported: repeated probes give the same answers', async (t) => {
+  const configs = [
+    { codec: 'opus', sampleRate: 48000, numberOfChannels: 2 },
+    { codec: 'mp4a.40.2', sampleRate: 48000, numberOfChannels: 2 },
+    { codec: 'ulaw', sampleRate: 44100, numberOfChannels: 1 },
+    { codec: 'invalid-codec', sampleRate: 48000, numberOfChannels: 2 },
+  ]
+  const probe = () =>
+    Promise.all(configs.map(async (config) => (await AudioEncoder.isConfigSupported(config)).supported))
+
+  const first = await probe()
+  const cached = await probe()
+
+  t.deepEqual(cached, first)
+  t.deepEqual(first, [true, true, false, false])
+})
+
 // ============================================================================
 // Sample Rate Tests
 // ============================================================================
@@ -562,3 +581,162 @@ test('AudioEncoder: FLAC description magic bytes', async (t) => {
   const magic = view.getUint32(0, false) // big-endian
   t.is(magic, 0x664c6143, "FLAC description should start with 'fLaC' magic bytes")
 })
+
+// ============================================================================
+// Telephony Codec Tests
+// ============================================================================
+
+test('AudioEncoder: ulaw round-trip keeps the signal and uses 1 byte per sample', async (t) => {
+  const sampleRate = 8000
+  const numberOfFrames = 8000
+  const amplitude = 0.5
+
+  const chunks: EncodedAudioChunk[] = []
+  const encoder = new AudioEncoder({
+    output: (chunk) => chunks.push(chunk),
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure({ codec: 'ulaw', sampleRate, numberOfChannels: 1 })
+
+  const audio = generateSineTone(440, numberOfFrames, 1, sampleRate, 's16', 0, amplitude)
+  encoder.encode(audio)
+  audio.close()
+  await encoder.flush()
+  encoder.close()
+
+  t.true(chunks.length > 0)
+  // G.711 stores one byte per sample, 20ms chunks
+  t.is(chunks[0].byteLength, 160)
+  t.is(chunks.reduce((sum, chunk) => sum + chunk.byteLength, 0), numberOfFrames)
+
+  const decoded: AudioData[] = []
+  const decoder = new AudioDecoder({
+    output: (data) => decoded.push(data),
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure({ codec: 'ulaw', sampleRate, numberOfChannels: 1 })
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  const samples = new Int16Array(numberOfFrames)
+  let offset = 0
+  for (const data of decoded) {
+    t.is(data.format, 's16')
+    t.is(data.sampleRate, sampleRate)
+    const part = new Int16Array(data.numberOfFrames)
+    data.copyTo(part, { planeIndex: 0 })
+    samples.set(part.subarray(0, Math.min(part.length, numberOfFrames - offset)), offset)
+    offset += data.numberOfFrames
+    data.close()
+  }
+  t.is(offset, numberOfFrames)
+
+  let signalPower = 0
+  let noisePower = 0
+  for (let i = 0; i < numberOfFrames; i++) {
+    const expected = Math.sin((2 * Math.PI * 440 * i) / sampleRate) * amplitude * 32767
+    signalPower += expected * expected
+    noisePower += (samples[i] - expected) ** 2
+  }
+  const snrDb = 10 * Math.log10(signalPower / noisePower)
+  t.true(snrDb > 30, `SNR ${snrDb.toFixed(1)} dB`)
+})
+
+test('AudioEncoder: telephony codecs require their fixed sample rate', async (t) => {
+  for (const [codec, sampleRate] of [
+    ['ulaw', 8000],
+    ['alaw', 8000],
+    ['g722', 16000],
+  ] as const) {
+    const supported = await AudioEncoder.isConfigSupported({ codec, sampleRate, numberOfChannels: 1 })
+    t.true(supported.supported, codec)
+    const decoderSupport = await AudioDecoder.isConfigSupported({ codec, sampleRate, numberOfChannels: 1 })
+    t.true(decoderSupport.supported, codec)
+
+    const wrongRate = await AudioEncoder.isConfigSupported({ codec, sampleRate: 48000, numberOfChannels: 1 })
+    t.false(wrongRate.supported, codec)
+    const wrongDecoderRate = await AudioDecoder.isConfigSupported({ codec, sampleRate: 44100, numberOfChannels: 1 })
+    t.false(wrongDecoderRate.supported, codec)
+  }
+
+  // G.722 is mono only
+  const stereo = await AudioEncoder.isConfigSupported({ codec: 'g722', sampleRate: 16000, numberOfChannels: 2 })
+  t.false(stereo.supported)
+})
+
+test('AudioEncoder: configure() with a mismatched telephony sample rate reports NotSupportedError', async (t) => {
+  const errors: Error[] = []
+  const encoder = new AudioEncoder({
+    output: () => {},
+    error: (e) => errors.push(e),
+  })
+  encoder.configure({ codec: 'ulaw', sampleRate: 48000, numberOfChannels: 1 })
+  await new Promise((resolve) => setTimeout(resolve, 50))
+
+  t.is(errors.length, 1)
+  t.true(errors[0].message.includes('NotSupportedError'))
+  t.is(encoder.state, 'closed')
+})
+
+test('AudioEncoder: Opus re-slices 1024-sample AudioData into 960-sample frames', async (t) => {
+  const sampleRate = 48000
+  const inputFrames = 1024
+  const inputCount = 30
+  const totalSamples = inputFrames * inputCount
+
+  const chunks: EncodedAudioChunk[] = []
+  let description: BufferSource | undefined
+  const encoder = new AudioEncoder({
+    output: (chunk, metadata) => {
+      chunks.push(chunk)
+      description ??= metadata?.decoderConfig?.description
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure({ codec: 'opus', sampleRate, numberOfChannels: 2, bitrate: 64000 })
+
+  for (let i = 0; i < inputCount; i++) {
+    const timestamp = Math.round((i * inputFrames * 1_000_000) / sampleRate)
+    const audio = generateSineTone(440, inputFrames, 2, sampleRate, 'f32', 1_000_000 + timestamp)
+    encoder.encode(audio)
+    audio.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  t.truthy(description)
+  const head = ArrayBuffer.isView(description)
+    ? new Uint8Array(description.buffer, description.byteOffset, description.byteLength)
+    : new Uint8Array(description as ArrayBuffer)
+  const preSkip = head[10] | (head[11] << 8)
+
+  // The encoder delay (pre-skip) is encoded too; only the last packet is short
+  t.is(chunks.length, Math.ceil((totalSamples + preSkip) / 960))
+  t.is(chunks[0].timestamp, 1_000_000)
+  for (let i = 0; i < chunks.length - 1; i++) {
+    t.is(chunks[i].duration, 20_000, `chunk ${i}`)
+    t.is(chunks[i + 1].timestamp, chunks[i].timestamp + chunks[i].duration!)
+  }
+  const last = chunks[chunks.length - 1]
+  t.true(last.duration! > 0 && last.duration! < 20_000)
+
+  let decodedSamples = 0
+  const decoder = new AudioDecoder({
+    output: (data: AudioData) => {
+      decodedSamples += data.numberOfFrames
+      data.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure({ codec: 'opus', sampleRate, numberOfChannels: 2, description })
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.is(decodedSamples, totalSamples)
+})
@@ -0,0 +1,199 @@
+/**
+ * Audio Extraction Tests
+ *
+ * Tests for extractAudio() and AudioExtractor (PCM from the audio track of a media file),
+ * and extractTrack() (lossless single-track copy).
+ */
+
+import { promises as fs } from 'node:fs'
+import * as path from 'node:path'
+import { fileURLToPath } from 'node:url'
+
+import test from 'ava'
+
+import {
+  AudioExtractor,
+  extractAudio,
+  extractTrack,
+  MkvDemuxer,
+  Mp4Demuxer,
+  type AudioData,
+  type RawPacket,
+} from '../index.js'
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url))
+const MP4_FIXTURE = path.join(__dirname, 'fixtures', 'small_buck_bunny.mp4')
+
+function channelData(data: AudioData, planeIndex: number): Float32Array {
+  const out = new Float32Array(data.numberOfFrames)
+  data.copyTo(out, { planeIndex, format: 'f32-planar' })
+  return out
+}
+
+type Demuxer = Mp4Demuxer | MkvDemuxer
+
+async function audioPackets(demuxer: Demuxer, data: Uint8Array): Promise<RawPacket[]> {
+  await demuxer.loadBuffer(data)
+  const packets: RawPacket[] = []
+  let packet: RawPacket | null
+  while ((packet = await demuxer.readPacket()) !== null) {
+    if (packet.trackType === 'audio') packets.push(packet)
+  }
+  demuxer.close()
+  return packets
+}
+
+async function fixtureAudioTrackId(): Promise<number> {
+  const demuxer = new Mp4Demuxer({ error: () => {} })
+  await demuxer.load(MP4_FIXTURE)
+  const track = demuxer.tracks.find((track) => track.trackType === 'audio')!
+  demuxer.close()
+  return track.index
+}
+
+// ============================================================================
+// extractAudio Tests
+// ============================================================================
+
+test('extractAudio: 2s range resampled to 16 kHz mono', async (t) => {
+  const data = await extractAudio(MP4_FIXTURE, {
+    startUs: 1_000_000,
+    endUs: 3_000_000,
+    targetSampleRate: 16000,
+    mono: true,
+  })
+
+  t.is(data.sampleRate, 16000)
+  t.is(data.numberOfChannels, 1)
+  t.is(data.format, 'f32-planar')
+  t.true(Math.abs(data.numberOfFrames - 32000) <= 1)
+  t.true(Math.abs(data.duration - 2_000_000) <= 100)
+  t.true(Math.abs(data.timestamp - 1_000_000) <= 100)
+
+  // Buck Bunny has audible sound throughout this range
+  const samples = channelData(data, 0)
+  t.true(samples.some((s) => Math.abs(s) > 0.01))
+  data.close()
+})
+
+test('extractAudio: keeps source rate and channels by default', async (t) => {
+  const buffer = await fs.readFile(MP4_FIXTURE)
+  const data = await extractAudio(new Uint8Array(buffer), { endUs: 500_000 })
+
+  t.true(data.sampleRate > 0)
+  t.true(data.numberOfChannels >= 1)
+  t.is(data.timestamp, 0)
+  t.true(Math.abs(data.duration - 500_000) <= 100)
+  data.close()
+})
+
+test('extractAudio: rejects an empty or inverted range', async (t) => {
+  await t.throwsAsync(() => extractAudio(MP4_FIXTURE, { startUs: 2_000_000, endUs: 1_000_000 }), {
+    message: /endUs must be greater than startUs/,
+  })
+})
+
+test('extractAudio: rejects input without an audio track', async (t) => {
+  const pngPath = path.join(__dirname, 'fixtures', 'test.png')
+  await t.throwsAsync(() => extractAudio(pngPath))
+})
+
+// ============================================================================
+// AudioExtractor Tests
+// ============================================================================
+
+test('AudioExtractor: yields contiguous chunks no longer than chunkDurationUs', async (t) => {
+  const extractor = new AudioExtractor(MP4_FIXTURE, {
+    startUs: 500_000,
+    endUs: 2_750_000,
+    targetSampleRate: 8000,
+    mono: true,
+    chunkDurationUs: 1_000_000,
+  })
+  t.is(extractor.sampleRate, 8000)
+  t.is(extractor.numberOfChannels, 1)
+
+  const frames: number[] = []
+  let expectedTimestamp = 500_000
+  for await (const chunk of extractor) {
+    t.true(chunk.numberOfFrames <= 8000)
+    t.true(Math.abs(chunk.timestamp - expectedTimestamp) <= 1)
+    expectedTimestamp = chunk.timestamp + chunk.duration
+    frames.push(chunk.numberOfFrames)
+    chunk.close()
+  }
+
+  t.deepEqual(frames.slice(0, 2), [8000, 8000])
+  t.true(Math.abs(frames.reduce((a, b) => a + b, 0) - 18000) <= 1)
+})
+
+test('AudioExtractor: rejects a non-positive chunkDurationUs', (t) => {
+  t.throws(() => new AudioExtractor(MP4_FIXTURE, { chunkDurationUs: 0 }), {
+    message: /chunkDurationUs must be greater than 0/,
+  })
+})
+
+// ============================================================================
+// extractTrack Tests
+// ============================================================================
+
+test('extractTrack: copies the AAC track into m4a with identical packets', async (t) => {
+  const trackId = await fixtureAudioTrackId()
+  const source = new Uint8Array(await fs.readFile(MP4_FIXTURE))
+  const m4a = await extractTrack(MP4_FIXTURE, trackId, { format: 'm4a' })
+
+  const expected = await audioPackets(new Mp4Demuxer({ error: (e) => t.fail(e.message) }), source)
+  const actual = await audioPackets(new Mp4Demuxer({ error: (e) => t.fail(e.message) }), m4a)
+
+  t.true(expected.length > 0)
+  t.is(actual.length, expected.length)
+  for (let i = 0; i < expected.length; i++) {
+    t.deepEqual(actual[i].data, expected[i].data, `packet ${i} payload`)
+    t.is(
+      (actual[i].pts! * actual[i].timeBaseNum) / actual[i].timeBaseDen,
+      (expected[i].pts! * expected[i].timeBaseNum) / expected[i].timeBaseDen,
+      `packet ${i} pts`,
+    )
+  }
+})
+
+test('extractTrack: defaults audio tracks to m4a and accepts a buffer', async (t) => {
+  const trackId = await fixtureAudioTrackId()
+  const source = new Uint8Array(await fs.readFile(MP4_FIXTURE))
+  const m4a = await extractTrack(source, trackId)
+
+  const demuxer = new Mp4Demuxer({ error: (e) => t.fail(e.message) })
+  await demuxer.loadBuffer(m4a)
+  t.deepEqual(demuxer.tracks.map((track) => track.trackType), ['audio'])
+  demuxer.close()
+})
+
+test('extractTrack: copies AAC into Matroska', async (t) => {
+  const trackId = await fixtureAudioTrackId()
+  const mkv = await extractTrack(MP4_FIXTURE, trackId, { format: 'mkv' })
+
+  const source = new Uint8Array(await fs.readFile(MP4_FIXTURE))
+  const expected = await audioPackets(new Mp4Demuxer({ error: (e) => t.fail(e.message) }), source)
+  const actual = await audioPackets(new MkvDemuxer({ error: (e) => t.fail(e.message) }), mkv)
+  t.is(actual.length, expected.length)
+  t.deepEqual(actual[0].data, expected[0].data)
+})
+
+test('extractTrack: rejects codecs the container cannot hold', async (t) => {
+  const trackId = await fixtureAudioTrackId()
+  await t.throwsAsync(() => extractTrack(MP4_FIXTURE, trackId, { format: 'webm' }), {
+    message: /NotSupportedError/,
+  })
+})
+
+test('extractTrack: rejects video into m4a and unknown tracks', async (t) => {
+  const demuxer = new Mp4Demuxer({ error: () => {} })
+  await demuxer.load(MP4_FIXTURE)
+  const videoTrack = demuxer.tracks.find((track) => track.trackType === 'video')!
+  demuxer.close()
+
+  await t.throwsAsync(() => extractTrack(MP4_FIXTURE, videoTrack.index, { format: 'm4a' }), {
+    message: /NotSupportedError/,
+  })
+  await t.throwsAsync(() => extractTrack(MP4_FIXTURE, 99), { message: /Track 99 not found/ })
+})
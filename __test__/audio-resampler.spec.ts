@@ -0,0 +1,152 @@
+/**
+ * AudioResampler Tests
+ *
+ * Tests for standalone sample rate, format and channel conversion of AudioData.
+ */
+
+import test from 'ava'
+
+import { AudioData, AudioResampler } from '../index.js'
+
+/** 1 kHz tone plus a 10 kHz tone, which is above the 8 kHz Nyquist limit of 16 kHz output */
+function toneChunk(start: number, frames: number, channels: number): AudioData {
+  const samples = new Float32Array(frames * channels)
+  for (let i = 0; i < frames; i++) {
+    const t = (start + i) / 48000
+    const value = 0.5 * Math.sin(2 * Math.PI * 1000 * t) + 0.3 * Math.sin(2 * Math.PI * 10000 * t)
+    for (let ch = 0; ch < channels; ch++) samples[i * channels + ch] = value
+  }
+  return new AudioData({
+    format: 'f32',
+    sampleRate: 48000,
+    numberOfFrames: frames,
+    numberOfChannels: channels,
+    timestamp: Math.round((start * 1_000_000) / 48000),
+    data: new Uint8Array(samples.buffer),
+  })
+}
+
+function readMono(data: AudioData): Float32Array {
+  const out = new Float32Array(data.numberOfFrames)
+  data.copyTo(new Uint8Array(out.buffer), { planeIndex: 0 })
+  return out
+}
+
+/** Signal power at `frequency` (Goertzel) */
+function power(samples: Float32Array, frequency: number, sampleRate: number): number {
+  const coeff = 2 * Math.cos((2 * Math.PI * frequency) / sampleRate)
+  let s1 = 0
+  let s2 = 0
+  for (const x of samples) {
+    const s0 = x + coeff * s1 - s2
+    s2 = s1
+    s1 = s0
+  }
+  return s1 * s1 + s2 * s2 - coeff * s1 * s2
+}
+
+test('AudioResampler: 48 kHz stereo to 16 kHz mono', (t) => {
+  const resampler = new AudioResampler({
+    inputSampleRate: 48000,
+    inputFormat: 'f32',
+    inputNumberOfChannels: 2,
+    outputSampleRate: 16000,
+    outputNumberOfChannels: 1,
+  })
+  t.is(resampler.sampleRate, 16000)
+  t.is(resampler.numberOfChannels, 1)
+  t.is(resampler.format, 'f32')
+
+  const outputs: AudioData[] = []
+  for (let start = 0; start < 48000; start += 4800) {
+    const input = toneChunk(start, 4800, 2)
+    const out = resampler.process(input)
+    input.close()
+    if (out) outputs.push(out)
+  }
+  t.true(resampler.delay > 0)
+  const tail = resampler.flush()
+  if (tail) outputs.push(tail)
+  t.is(resampler.delay, 0)
+
+  // One second in, one second out
+  const total = outputs.reduce((sum, data) => sum + data.numberOfFrames, 0)
+  t.true(Math.abs(total - 16000) <= 1, `got ${total} samples`)
+
+  // Each output starts where the previous one ended
+  let expected = 0
+  for (const data of outputs) {
+    t.is(data.sampleRate, 16000)
+    t.is(data.numberOfChannels, 1)
+    t.true(Math.abs(data.timestamp - expected) <= 1, `timestamp ${data.timestamp}, expected ${expected}`)
+    expected += (data.numberOfFrames * 1_000_000) / 16000
+  }
+
+  const samples = new Float32Array(total)
+  let offset = 0
+  for (const data of outputs) {
+    samples.set(readMono(data), offset)
+    offset += data.numberOfFrames
+    data.close()
+  }
+  // Skip the filter's start-up transient
+  const steady = samples.subarray(1000, 15000)
+  const tone = power(steady, 1000, 16000)
+  // 10 kHz would alias to 6 kHz without the anti-aliasing filter
+  const alias = power(steady, 6000, 16000)
+  t.true(tone > alias * 1000, `1 kHz power ${tone}, 6 kHz alias power ${alias}`)
+  resampler.close()
+})
+
+test('AudioResampler: timestamps continue from the first input', (t) => {
+  const resampler = new AudioResampler({
+    inputSampleRate: 48000,
+    inputFormat: 'f32',
+    inputNumberOfChannels: 1,
+    outputSampleRate: 44100,
+  })
+  t.is(resampler.nextTimestamp, null)
+
+  const first = toneChunk(48000, 1024, 1)
+  const out = resampler.process(first)
+  first.close()
+  t.truthy(out)
+  t.is(out!.timestamp, 1_000_000)
+  t.is(resampler.nextTimestamp, 1_000_000 + Math.floor((out!.numberOfFrames * 1_000_000) / 44100))
+  out!.close()
+
+  // reset() starts a new stream at the next input
+  resampler.reset()
+  const second = toneChunk(96000, 1024, 1)
+  const restarted = resampler.process(second)
+  second.close()
+  t.is(restarted!.timestamp, 2_000_000)
+  restarted!.close()
+  resampler.close()
+})
+
+test('AudioResampler: rejects mismatched input and use after close', (t) => {
+  const resampler = new AudioResampler({
+    inputSampleRate: 48000,
+    inputFormat: 'f32',
+    inputNumberOfChannels: 2,
+    outputSampleRate: 16000,
+  })
+  const mono = toneChunk(0, 480, 1)
+  t.throws(() => resampler.process(mono), { message: /does not match/ })
+
+  resampler.close()
+  t.throws(() => resampler.process(mono), { message: /closed/ })
+  mono.close()
+
+  t.throws(
+    () =>
+      new AudioResampler({
+        inputSampleRate: 0,
+        inputFormat: 'f32',
+        inputNumberOfChannels: 2,
+        outputSampleRate: 16000,
+      }),
+    { message: /inputSampleRate/ },
+  )
+})
@@ -0,0 +1,144 @@
+/**
+ * Bitstream Analysis Tests
+ *
+ * Tests for analyzeBitstream() (header-only frame type and GOP reporting).
+ */
+
+import * as path from 'node:path'
+import { fileURLToPath } from 'node:url'
+
+import test from 'ava'
+
+import {
+  Mp4Demuxer,
+  VideoEncoder,
+  analyzeBitstream,
+  type EncodedVideoChunk,
+  type VideoEncoderConfig,
+} from '../index.js'
+import { generateCheckerboardI420Frame } from './helpers/index.js'
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url))
+const MP4_FIXTURE = path.join(__dirname, 'fixtures', 'small_buck_bunny.mp4')
+
+/** Encode `count` checkerboard frames at 30fps */
+async function encodeChunks(config: VideoEncoderConfig, count: number): Promise<EncodedVideoChunk[]> {
+  const chunks: EncodedVideoChunk[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk) => {
+      chunks.push(chunk)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure(config)
+  for (let i = 0; i < count; i++) {
+    const frame = generateCheckerboardI420Frame(config.width, config.height, Math.round((i * 1_000_000) / 30))
+    encoder.encode(frame)
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return chunks
+}
+
+test('analyzeBitstream: realtime H.264 has 10-frame GOPs and no B-frames', async (t) => {
+  const chunks = await encodeChunks(
+    {
+      codec: 'avc1.42001E',
+      width: 320,
+      height: 240,
+      bitrate: 500_000,
+      framerate: 30,
+      latencyMode: 'realtime',
+      hardwareAcceleration: 'prefer-software',
+      avc: { format: 'annexb' },
+    },
+    30,
+  )
+  const analysis = await analyzeBitstream(chunks, 'avc1.42001E')
+
+  t.is(analysis.codec, 'h264')
+  t.is(analysis.nalFormat, 'annexb')
+  t.is(analysis.frames.length, chunks.length)
+  t.deepEqual(analysis.gopLengths, [10, 10, 10])
+  t.is(analysis.bFrameCount, 0)
+  t.deepEqual(analysis.frames.map((f) => f.key), chunks.map((c) => c.type === 'key'))
+  t.true(analysis.frames.every((f) => f.frameType === (f.key ? 'I' : 'P')))
+  // Annex B output repeats SPS/PPS in front of every IDR
+  t.deepEqual(analysis.parameterSetIndices, [0, 10, 20])
+  t.deepEqual(analysis.frames.map((f) => f.timestamp), chunks.map((c) => c.timestamp))
+})
+
+test('analyzeBitstream: quality-mode H.264 reports B-frame runs within the encoder limit', async (t) => {
+  const chunks = await encodeChunks(
+    {
+      codec: 'avc1.640028',
+      width: 320,
+      height: 240,
+      bitrate: 500_000,
+      framerate: 30,
+      hardwareAcceleration: 'prefer-software',
+    },
+    60,
+  )
+  const analysis = await analyzeBitstream(chunks, 'avc1.640028')
+
+  t.is(analysis.nalFormat, 'lengthPrefixed')
+  t.is(analysis.keyFrameCount, 1)
+  t.deepEqual(analysis.gopLengths, [60])
+  t.true(analysis.bFrameCount > 0)
+  // libx264 defaults to at most 3 consecutive B-frames
+  t.true(analysis.maxConsecutiveBFrames <= 3)
+  t.true(analysis.frames.every((f) => f.frameType !== 'unknown'))
+})
+
+test('analyzeBitstream: VP9 key frames match the encoder output', async (t) => {
+  const chunks = await encodeChunks(
+    {
+      codec: 'vp09.00.10.08',
+      width: 320,
+      height: 240,
+      bitrate: 500_000,
+      framerate: 30,
+      latencyMode: 'realtime',
+      hardwareAcceleration: 'prefer-software',
+    },
+    30,
+  )
+  const analysis = await analyzeBitstream(chunks, 'vp09.00.10.08')
+
+  t.is(analysis.codec, 'vp9')
+  t.is(analysis.nalFormat, undefined)
+  t.true(analysis.gopLengths.every((length) => length <= 10))
+  t.is(analysis.bFrameCount, 0)
+  t.deepEqual(analysis.frames.map((f) => f.key), chunks.map((c) => c.type === 'key'))
+})
+
+test('analyzeBitstream: reads the video track of a container file', async (t) => {
+  const analysis = await analyzeBitstream(MP4_FIXTURE)
+
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.load(MP4_FIXTURE)
+  const keys: boolean[] = []
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) keys.push(chunk.videoChunk.type === 'key')
+  }
+  demuxer.close()
+
+  t.is(analysis.codec, 'h264')
+  t.is(analysis.nalFormat, 'lengthPrefixed')
+  t.deepEqual(analysis.frames.map((f) => f.key), keys)
+  t.is(analysis.gopLengths.reduce((sum, length) => sum + length, 0), keys.length)
+  t.true(analysis.frames.every((f) => f.frameType !== 'unknown'))
+})
+
+test('analyzeBitstream: rejects chunks without a supported codec', async (t) => {
+  const chunks = await encodeChunks(
+    { codec: 'vp8', width: 64, height: 64, bitrate: 100_000, hardwareAcceleration: 'prefer-software' },
+    1,
+  )
+  await t.throwsAsync(() => analyzeBitstream(chunks), { message: /codec is required/ })
+  await t.throwsAsync(() => analyzeBitstream(chunks, 'vp8'), { message: /does not support vp8/ })
+})
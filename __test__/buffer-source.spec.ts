@@ -0,0 +1,213 @@
+/**
+ * BufferSource Input Tests
+ *
+ * Every byte-accepting API takes any ArrayBuffer or ArrayBufferView and reads
+ * exactly the bytes between its byteOffset and byteOffset + byteLength.
+ */
+
+import test from 'ava'
+import { readFileSync } from 'fs'
+import { join, dirname } from 'path'
+import { fileURLToPath } from 'url'
+
+import {
+  AudioData,
+  EncodedAudioChunk,
+  EncodedVideoChunk,
+  ImageDecoder,
+  Mp4Demuxer,
+  Mp4Muxer,
+  VideoFrame,
+  parseToc,
+  type RawPacket,
+} from '../index.js'
+
+const __dirname = dirname(fileURLToPath(import.meta.url))
+
+// Skip demuxer tests on Linux armv7 (QEMU emulation too slow, causes timeouts)
+const isLinuxArmv7 = process.platform === 'linux' && process.arch === 'arm'
+const runTest = isLinuxArmv7 ? test.skip : test
+
+/** Copy `bytes` into the middle of a larger Buffer and return the view over them */
+function embedded(bytes: Uint8Array, offset = 13): Buffer {
+  const backing = Buffer.alloc(offset + bytes.length + 7, 0xee)
+  backing.set(bytes, offset)
+  return backing.subarray(offset, offset + bytes.length)
+}
+
+/** The same bytes in every BufferSource shape, each offset into a larger buffer */
+function sources(bytes: Uint8Array): [string, BufferSource][] {
+  const view = embedded(bytes)
+  const exact = new ArrayBuffer(bytes.length)
+  new Uint8Array(exact).set(bytes)
+  const aligned = embedded(bytes, 16)
+  const sources: [string, BufferSource][] = [
+    ['Buffer subarray', view],
+    ['Uint8Array subarray', new Uint8Array(view.buffer, view.byteOffset, view.byteLength)],
+    ['DataView', new DataView(view.buffer, view.byteOffset, view.byteLength)],
+    ['ArrayBuffer', exact],
+  ]
+  if (bytes.length % 2 === 0) {
+    sources.push(['Uint16Array', new Uint16Array(aligned.buffer, aligned.byteOffset, bytes.length / 2)])
+  }
+  return sources
+}
+
+const PAYLOAD = new Uint8Array([0x00, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84, 0x21])
+
+test('EncodedVideoChunk: data honors byteOffset and byteLength', (t) => {
+  for (const [name, data] of sources(PAYLOAD)) {
+    const chunk = new EncodedVideoChunk({ type: 'key', timestamp: 0, data })
+    t.is(chunk.byteLength, PAYLOAD.length, name)
+    const out = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(out)
+    t.deepEqual(out, PAYLOAD, name)
+  }
+})
+
+test('EncodedAudioChunk: data honors byteOffset and byteLength', (t) => {
+  for (const [name, data] of sources(PAYLOAD)) {
+    const chunk = new EncodedAudioChunk({ type: 'key', timestamp: 0, data })
+    t.is(chunk.byteLength, PAYLOAD.length, name)
+    const out = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(out)
+    t.deepEqual(out, PAYLOAD, name)
+  }
+})
+
+test('chunk init rejects data that is not a BufferSource', (t) => {
+  t.throws(
+    () => new EncodedVideoChunk({ type: 'key', timestamp: 0, data: [1, 2, 3] as unknown as BufferSource }),
+    { instanceOf: TypeError, message: /data must be a BufferSource/ },
+  )
+})
+
+test('AudioData: data honors byteOffset and byteLength', (t) => {
+  const samples = new Float32Array([0.25, -0.5, 0.75, -1])
+  const bytes = new Uint8Array(samples.buffer)
+  const padded = new Float32Array(samples.length + 3)
+  padded.set(samples, 2)
+  const inputs: [string, BufferSource][] = [
+    ...sources(bytes),
+    ['Float32Array subarray', padded.subarray(2, 2 + samples.length)],
+  ]
+  for (const [name, data] of inputs) {
+    const audio = new AudioData({
+      format: 'f32',
+      sampleRate: 48000,
+      numberOfFrames: samples.length,
+      numberOfChannels: 1,
+      timestamp: 0,
+      data,
+    })
+    const out = new Float32Array(samples.length)
+    audio.copyTo(out, { planeIndex: 0 })
+    t.deepEqual(out, samples, name)
+    audio.close()
+  }
+})
+
+test('VideoFrame: buffer source honors byteOffset and byteLength', async (t) => {
+  const rgba = new Uint8Array(2 * 2 * 4).map((_, i) => i * 7)
+  for (const [name, data] of sources(rgba)) {
+    const frame = new VideoFrame(data, { format: 'RGBA', codedWidth: 2, codedHeight: 2, timestamp: 0 })
+    const out = new Uint8Array(frame.allocationSize())
+    await frame.copyTo(out)
+    t.deepEqual(out, rgba, name)
+    frame.close()
+  }
+})
+
+test('ImageDecoder: data and appendData honor byteOffset and byteLength', async (t) => {
+  const png = readFileSync(join(__dirname, 'fixtures/test.png'))
+  for (const [name, data] of sources(png)) {
+    const decoder = new ImageDecoder({ data, type: 'image/png' })
+    const { image } = await decoder.decode()
+    t.is(image.codedWidth, 8, name)
+    image.close()
+    decoder.close()
+
+    const appended = new ImageDecoder({ type: 'image/png' })
+    appended.appendData(data)
+    appended.endOfData()
+    const result = await appended.decode()
+    t.is(result.image.codedWidth, 8, name)
+    result.image.close()
+    appended.close()
+  }
+})
+
+test('parseToc: reads the TOC byte at byteOffset', (t) => {
+  const packet = new Uint8Array([0xfc, 0xff, 0xfe])
+  for (const [name, data] of sources(packet)) {
+    t.is(parseToc(data).config, 31, name)
+  }
+})
+
+runTest('Mp4Demuxer: loadBuffer honors byteOffset and byteLength', async (t) => {
+  const file = readFileSync(join(__dirname, 'fixtures/small_buck_bunny.mp4'))
+  for (const [name, data] of sources(file)) {
+    const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+    await demuxer.loadBuffer(data)
+    t.is(demuxer.state, 'ready', name)
+    t.true(demuxer.tracks.length > 0, name)
+    demuxer.close()
+  }
+})
+
+/** Read every video packet of the h264 fixture */
+async function readH264Packets(): Promise<{
+  config: NonNullable<Mp4Demuxer['videoDecoderConfig']>
+  packets: RawPacket[]
+}> {
+  const demuxer = new Mp4Demuxer({
+    error: (e: Error) => {
+      throw e
+    },
+  })
+  await demuxer.load(join(__dirname, 'fixtures/wpt/h264.mp4'))
+  const config = demuxer.videoDecoderConfig!
+  const packets: RawPacket[] = []
+  let packet: RawPacket | null
+  while ((packet = await demuxer.readPacket()) !== null) {
+    if (packet.trackType === 'video') packets.push(packet)
+  }
+  demuxer.close()
+  return { config, packets }
+}
+
+runTest('Mp4Muxer: description and writePacket data honor byteOffset and byteLength', async (t) => {
+  const { config, packets } = await readH264Packets()
+  const description = config.description!
+
+  const muxer = new Mp4Muxer({ fragmented: true })
+  muxer.addVideoTrack({
+    codec: config.codec,
+    width: config.codedWidth,
+    height: config.codedHeight,
+  })
+  const view = embedded(description)
+  muxer.updateVideoDescription(new DataView(view.buffer, view.byteOffset, view.byteLength))
+  for (const packet of packets) {
+    muxer.writePacket({ ...packet, data: embedded(packet.data) })
+  }
+  const output = muxer.finalize()
+  muxer.close()
+
+  // The fragmented output is read back through appendBuffer, one offset view per half
+  const half = Math.floor(output.length / 2)
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  const first = embedded(output.subarray(0, half))
+  demuxer.appendBuffer(new DataView(first.buffer, first.byteOffset, first.byteLength))
+  demuxer.appendBuffer(embedded(output.subarray(half)))
+  demuxer.endOfStream()
+
+  t.deepEqual(demuxer.videoDecoderConfig?.description, description)
+  const sizes: number[] = []
+  let packet: RawPacket | null
+  while ((packet = await demuxer.readPacket()) !== null) {
+    sizes.push(packet.data.length)
+  }
+  t.deepEqual(sizes, packets.map((p) => p.data.length))
+  demuxer.close()
+})
@@ -0,0 +1,148 @@
+/**
+ * CfrRegulator Tests
+ *
+ * Tests for variable to constant frame rate conversion of VideoFrames.
+ */
+
+import test from 'ava'
+
+import { CfrRegulator, VideoEncoder, type EncodedVideoChunk, type VideoFrame } from '../index.js'
+
+import { generateSolidColorI420Frame, TestColors } from './helpers/index.js'
+
+function pushAll(regulator: CfrRegulator, timestamps: number[]): VideoFrame[] {
+  const out: VideoFrame[] = []
+  for (const timestamp of timestamps) {
+    const frame = generateSolidColorI420Frame(64, 48, TestColors.red, timestamp)
+    out.push(...regulator.push(frame))
+    frame.close()
+  }
+  return out
+}
+
+test('CfrRegulator: bursty input maps to a regular 10 fps grid', (t) => {
+  const regulator = new CfrRegulator({ framerate: 10 })
+  // A burst of three frames, a gap, then irregular spacing
+  const out = pushAll(regulator, [0, 20_000, 40_000, 330_000, 360_000, 610_000])
+  out.push(...regulator.flush(800_000))
+
+  t.deepEqual(
+    out.map((f) => f.timestamp),
+    [0, 100_000, 200_000, 300_000, 400_000, 500_000, 600_000, 700_000],
+  )
+  t.true(out.every((f) => f.duration === 100_000))
+  t.is(regulator.inputFrames, 6)
+  t.is(regulator.outputFrames, 8)
+  t.is(regulator.droppedFrames, 2)
+  t.is(regulator.duplicatedFrames, 4)
+  for (const frame of out) frame.close()
+  regulator.close()
+})
+
+test('CfrRegulator: duplicates keep the pixels of the input after it is closed', (t) => {
+  const regulator = new CfrRegulator({ framerate: 30 })
+  const first = generateSolidColorI420Frame(64, 48, TestColors.blue, 0)
+  t.is(regulator.push(first).length, 0)
+  first.close()
+  const second = generateSolidColorI420Frame(64, 48, TestColors.green, 100_000)
+  const out = regulator.push(second)
+  second.close()
+
+  t.is(out.length, 3)
+  const expected = new Uint8Array(64 * 48 * 1.5)
+  out[0].copyTo(expected)
+  for (const frame of out) {
+    const data = new Uint8Array(64 * 48 * 1.5)
+    frame.copyTo(data)
+    t.deepEqual(data, expected)
+    frame.close()
+  }
+  for (const frame of regulator.flush()) frame.close()
+  regulator.close()
+})
+
+test('CfrRegulator: NTSC rate timestamps are exact and reproducible', (t) => {
+  const timestamps: number[] = []
+  let ts = 0
+  for (let i = 0; i < 120; i++) {
+    // Jittery capture around 30 fps with occasional stalls
+    ts += i % 17 === 0 ? 95_000 : 25_000 + ((i * 7919) % 17_000)
+    timestamps.push(ts)
+  }
+
+  const run = () => {
+    const regulator = new CfrRegulator({ framerate: 30000 / 1001, startTimestamp: 0 })
+    const out = pushAll(regulator, timestamps)
+    out.push(...regulator.flush())
+    const result = out.map((f) => [f.timestamp, f.duration])
+    for (const frame of out) frame.close()
+    t.is(regulator.outputFrames, regulator.inputFrames - regulator.droppedFrames + regulator.duplicatedFrames)
+    regulator.close()
+    return result
+  }
+
+  const first = run()
+  t.deepEqual(run(), first)
+  first.forEach(([timestamp, duration], n) => {
+    t.is(timestamp, Math.round((n * 1001 * 1_000_000) / 30000))
+    t.is(timestamp! + duration!, Math.round(((n + 1) * 1001 * 1_000_000) / 30000))
+  })
+})
+
+test('CfrRegulator: out-of-order frames are dropped', (t) => {
+  const regulator = new CfrRegulator({ framerate: 10 })
+  const out = pushAll(regulator, [0, 100_000, 50_000, 100_000, 200_000])
+  out.push(...regulator.flush())
+
+  t.deepEqual(
+    out.map((f) => f.timestamp),
+    [0, 100_000, 200_000],
+  )
+  t.is(regulator.droppedFrames, 2)
+  t.is(regulator.duplicatedFrames, 0)
+  for (const frame of out) frame.close()
+
+  regulator.reset()
+  t.is(regulator.inputFrames, 0)
+  const restarted = pushAll(regulator, [5_000_000, 5_100_000])
+  t.is(restarted[0].timestamp, 5_000_000)
+  for (const frame of restarted) frame.close()
+  regulator.close()
+})
+
+test('CfrRegulator: rejects invalid framerate', (t) => {
+  t.throws(() => new CfrRegulator({ framerate: 0 }), { message: /framerate/ })
+  t.throws(() => new CfrRegulator({ framerate: Number.NaN }), { message: /framerate/ })
+})
+
+test('CfrRegulator: encoder output chunks follow the grid', async (t) => {
+  const chunks: EncodedVideoChunk[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk) => chunks.push(chunk),
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 64,
+    height: 48,
+    framerate: 25,
+    latencyMode: 'realtime',
+    hardwareAcceleration: 'prefer-software',
+  })
+
+  const regulator = new CfrRegulator({ framerate: 25 })
+  const frames = pushAll(regulator, [0, 10_000, 15_000, 130_000, 170_000, 400_000])
+  frames.push(...regulator.flush(480_000))
+  for (const frame of frames) {
+    encoder.encode(frame)
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  regulator.close()
+
+  t.deepEqual(
+    chunks.map((c) => c.timestamp),
+    Array.from({ length: 12 }, (_, n) => n * 40_000),
+  )
+})
@@ -0,0 +1,640 @@
+/**
+ * WebCodecs Conformance Harness
+ *
+ * Runs a curated subset of the W3C Web Platform Tests vectors against the
+ * native classes outside of ava, so CI gets one pass/fail line per vector:
+ * - Decode vectors (vectors.json `video` / `audio`): chunk offsets into the
+ *   WPT fixtures, each run through several decoder scenarios
+ * - Roundtrip vectors (vectors.json `roundtrip`): encode synthetic frames,
+ *   decode the result (codecs without a checked-in fixture, e.g. VP8/VP9)
+ * - State-machine vectors: constructor, closed/unconfigured and config
+ *   validation checks from the WPT *-encoder/*-decoder tests
+ *
+ * Intentional deviations are listed in expectations.json; a vector listed
+ * there is reported as XFAIL instead of failing the run.
+ */
+
+import assert from 'node:assert/strict'
+import { readFileSync } from 'node:fs'
+import { dirname, join } from 'node:path'
+import { fileURLToPath } from 'node:url'
+
+import {
+  AudioDecoder,
+  AudioEncoder,
+  EncodedAudioChunk,
+  EncodedVideoChunk,
+  resetHardwareFallbackState,
+  VideoDecoder,
+  VideoEncoder,
+  type AudioData,
+  type VideoFrame,
+} from '../../index.js'
+import type { AudioDecoderConfig, VideoDecoderConfig, VideoEncoderConfig } from '../../standard.js'
+import { generateSineTone } from '../helpers/audio-generator.js'
+import { generateGradientI420Frame } from '../helpers/frame-generator.js'
+import { isErrorOfType } from '../helpers/wpt-utils.js'
+
+const __dirname = dirname(fileURLToPath(import.meta.url))
+const FIXTURES_DIR = join(__dirname, '../fixtures/wpt')
+
+/** Per-vector timeout so a stalled codec fails its vector instead of hanging CI */
+const VECTOR_TIMEOUT_MS = 30_000
+
+/** Frames encoded per roundtrip vector */
+const ROUNDTRIP_FRAMES = 10
+
+interface ByteRange {
+  offset: number
+  size: number
+}
+
+interface ChunkVector extends ByteRange {
+  type: 'key' | 'delta'
+  timestamp: number
+}
+
+interface DecodeVector<Config> {
+  id: string
+  wpt: string
+  fixture: string
+  config: Config
+  description?: ByteRange
+  chunks: ChunkVector[]
+}
+
+interface RoundtripVector {
+  id: string
+  wpt: string
+  config: VideoEncoderConfig
+}
+
+interface StateCase {
+  name: string
+  wpt: string
+  run: () => Promise<void>
+}
+
+interface VectorFile {
+  video: DecodeVector<Omit<VideoDecoderConfig, 'description'>>[]
+  audio: DecodeVector<Omit<AudioDecoderConfig, 'description'>>[]
+  roundtrip: RoundtripVector[]
+}
+
+export interface ConformanceCase {
+  /** `<group>/<vector>/<scenario>`, the key used in expectations.json */
+  id: string
+  /** WPT file (and variant) the case is taken from */
+  wpt: string
+  run: () => Promise<void>
+}
+
+export type ConformanceStatus = 'pass' | 'fail' | 'xfail' | 'xpass'
+
+export interface ConformanceResult {
+  id: string
+  wpt: string
+  status: ConformanceStatus
+  /** Failure message (fail / xfail) */
+  error?: string
+  /** Expected-failure annotation (xfail / xpass) */
+  reason?: string
+}
+
+const fixtureCache = new Map<string, Buffer>()
+
+function fixtureBytes(fixture: string, range: ByteRange): Uint8Array {
+  let data = fixtureCache.get(fixture)
+  if (!data) {
+    data = readFileSync(join(FIXTURES_DIR, fixture))
+    fixtureCache.set(fixture, data)
+  }
+  return new Uint8Array(data.subarray(range.offset, range.offset + range.size))
+}
+
+function expectDOMException(fn: () => unknown, name: string) {
+  assert.throws(fn, (e: Error) => e instanceof DOMException && e.name === name, `expected ${name}`)
+}
+
+async function expectRejection(promise: Promise<unknown>, name: string) {
+  await assert.rejects(promise, (e: Error) => isErrorOfType(e, name), `expected rejection with ${name}`)
+}
+
+// ============================================================================
+// Video decode scenarios
+// ============================================================================
+
+type VideoVector = VectorFile['video'][number]
+
+function videoConfig(vector: VideoVector): VideoDecoderConfig {
+  return {
+    ...vector.config,
+    description: vector.description ? fixtureBytes(vector.fixture, vector.description) : undefined,
+  }
+}
+
+function videoChunk(vector: VideoVector, chunk: ChunkVector): EncodedVideoChunk {
+  return new EncodedVideoChunk({
+    type: chunk.type,
+    timestamp: chunk.timestamp,
+    data: fixtureBytes(vector.fixture, chunk),
+  })
+}
+
+function collectingVideoDecoder() {
+  const frames: { timestamp: number; codedWidth: number; codedHeight: number }[] = []
+  const errors: Error[] = []
+  const decoder = new VideoDecoder({
+    output: (frame: VideoFrame) => {
+      frames.push({ timestamp: frame.timestamp, codedWidth: frame.codedWidth, codedHeight: frame.codedHeight })
+      frame.close()
+    },
+    error: (e) => {
+      errors.push(e)
+    },
+  })
+  return { decoder, frames, errors }
+}
+
+const videoScenarios: Record<string, (vector: VideoVector) => Promise<void>> = {
+  // WPT: "Decode a key frame"
+  'decode-key-frame': async (vector) => {
+    const { decoder, frames, errors } = collectingVideoDecoder()
+    decoder.configure(videoConfig(vector) as never)
+    decoder.decode(videoChunk(vector, vector.chunks[0]))
+    await decoder.flush()
+    decoder.close()
+
+    assert.deepEqual(errors, [])
+    assert.equal(frames.length, 1)
+    assert.equal(frames[0].timestamp, vector.chunks[0].timestamp)
+    assert.equal(frames[0].codedWidth, vector.config.codedWidth)
+    assert.equal(frames[0].codedHeight, vector.config.codedHeight)
+  },
+
+  // WPT: "Decode all frames" - outputs arrive in presentation order
+  'decode-all': async (vector) => {
+    const { decoder, frames, errors } = collectingVideoDecoder()
+    decoder.configure(videoConfig(vector) as never)
+    for (const chunk of vector.chunks) {
+      decoder.decode(videoChunk(vector, chunk))
+    }
+    await decoder.flush()
+    decoder.close()
+
+    assert.deepEqual(errors, [])
+    assert.deepEqual(
+      frames.map((f) => f.timestamp),
+      vector.chunks.map((c) => c.timestamp).sort((a, b) => a - b),
+    )
+  },
+
+  // WPT: "Decode a non key frame first fails"
+  'delta-first-throws': async (vector) => {
+    const { decoder } = collectingVideoDecoder()
+    decoder.configure(videoConfig(vector) as never)
+    const delta = vector.chunks.find((c) => c.type === 'delta')
+    assert.ok(delta, 'vector has a delta chunk')
+    expectDOMException(() => decoder.decode(videoChunk(vector, delta)), 'DataError')
+    decoder.close()
+  },
+
+  // WPT: "Decode frames after flush" - a key chunk is required again after flush
+  'decode-after-flush': async (vector) => {
+    const { decoder, frames, errors } = collectingVideoDecoder()
+    decoder.configure(videoConfig(vector) as never)
+    decoder.decode(videoChunk(vector, vector.chunks[0]))
+    await decoder.flush()
+    decoder.decode(videoChunk(vector, vector.chunks[0]))
+    await decoder.flush()
+    decoder.close()
+
+    assert.deepEqual(errors, [])
+    assert.equal(frames.length, 2)
+  },
+
+  // WPT: "Test reset during decode" - reset() drops queued work, reconfigure recovers
+  'reset-and-reconfigure': async (vector) => {
+    const { decoder, frames, errors } = collectingVideoDecoder()
+    decoder.configure(videoConfig(vector) as never)
+    for (const chunk of vector.chunks) {
+      decoder.decode(videoChunk(vector, chunk))
+    }
+    decoder.reset()
+    assert.equal(decoder.state, 'unconfigured')
+    assert.equal(decoder.decodeQueueSize, 0)
+
+    decoder.configure(videoConfig(vector) as never)
+    decoder.decode(videoChunk(vector, vector.chunks[0]))
+    await decoder.flush()
+    decoder.close()
+
+    assert.deepEqual(errors, [])
+    assert.ok(frames.length >= 1)
+    assert.equal(frames[frames.length - 1].timestamp, vector.chunks[0].timestamp)
+  },
+}
+
+// ============================================================================
+// Audio decode scenarios
+// ============================================================================
+
+type AudioVector = VectorFile['audio'][number]
+
+function audioConfig(vector: AudioVector): AudioDecoderConfig {
+  return {
+    ...vector.config,
+    description: vector.description ? fixtureBytes(vector.fixture, vector.description) : undefined,
+  }
+}
+
+function audioChunk(vector: AudioVector, chunk: ChunkVector): EncodedAudioChunk {
+  return new EncodedAudioChunk({
+    type: chunk.type,
+    timestamp: chunk.timestamp,
+    data: fixtureBytes(vector.fixture, chunk),
+  })
+}
+
+function collectingAudioDecoder() {
+  const outputs: { sampleRate: number; numberOfChannels: number; numberOfFrames: number }[] = []
+  const errors: Error[] = []
+  const decoder = new AudioDecoder({
+    output: (data: AudioData) => {
+      outputs.push({
+        sampleRate: data.sampleRate,
+        numberOfChannels: data.numberOfChannels,
+        numberOfFrames: data.numberOfFrames,
+      })
+      data.close()
+    },
+    error: (e) => {
+      errors.push(e)
+    },
+  })
+  return { decoder, outputs, errors }
+}
+
+const audioScenarios: Record<string, (vector: AudioVector) => Promise<void>> = {
+  // WPT: "Test decoding"
+  'decode-all': async (vector) => {
+    const { decoder, outputs, errors } = collectingAudioDecoder()
+    decoder.configure(audioConfig(vector) as never)
+    for (const chunk of vector.chunks) {
+      decoder.decode(audioChunk(vector, chunk))
+    }
+    await decoder.flush()
+    decoder.close()
+
+    assert.deepEqual(errors, [])
+    assert.ok(outputs.length > 0)
+    for (const output of outputs) {
+      assert.equal(output.sampleRate, vector.config.sampleRate)
+      assert.equal(output.numberOfChannels, vector.config.numberOfChannels)
+    }
+  },
+
+  // WPT: "Test decoding a with negative timestamp"
+  'negative-timestamp': async (vector) => {
+    const { decoder, outputs, errors } = collectingAudioDecoder()
+    decoder.configure(audioConfig(vector) as never)
+    const first = vector.chunks[0]
+    decoder.decode(
+      new EncodedAudioChunk({ type: 'key', timestamp: -42, data: fixtureBytes(vector.fixture, first) }),
+    )
+    for (const chunk of vector.chunks.slice(1)) {
+      decoder.decode(audioChunk(vector, chunk))
+    }
+    await decoder.flush()
+    decoder.close()
+
+    assert.deepEqual(errors, [])
+    assert.ok(outputs.length > 0)
+  },
+
+  // WPT: "Test reset during flush"
+  'reset-during-flush': async (vector) => {
+    const { decoder, errors } = collectingAudioDecoder()
+    decoder.configure(audioConfig(vector) as never)
+    for (const chunk of vector.chunks) {
+      decoder.decode(audioChunk(vector, chunk))
+    }
+    const flushPromise = decoder.flush()
+    decoder.reset()
+    await expectRejection(flushPromise, 'AbortError')
+    assert.equal(decoder.state, 'unconfigured')
+    decoder.close()
+
+    assert.deepEqual(errors, [])
+  },
+}
+
+// ============================================================================
+// Roundtrip scenarios
+// ============================================================================
+
+async function roundtrip(vector: RoundtripVector) {
+  const chunks: EncodedVideoChunk[] = []
+  let decoderConfig: VideoDecoderConfig | undefined
+  const encoderErrors: Error[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      chunks.push(chunk)
+      if (metadata?.decoderConfig) {
+        decoderConfig = metadata.decoderConfig as unknown as VideoDecoderConfig
+      }
+    },
+    error: (e) => {
+      encoderErrors.push(e)
+    },
+  })
+  encoder.configure(vector.config as never)
+  for (let i = 0; i < ROUNDTRIP_FRAMES; i++) {
+    const frame = generateGradientI420Frame(vector.config.width, vector.config.height, i * 33_333, 33_333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  assert.deepEqual(encoderErrors, [])
+  assert.equal(chunks.length, ROUNDTRIP_FRAMES)
+  assert.equal(chunks[0].type, 'key')
+  assert.ok(decoderConfig, 'first output carries decoderConfig')
+
+  const { decoder, frames, errors } = collectingVideoDecoder()
+  decoder.configure(decoderConfig as never)
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  assert.deepEqual(errors, [])
+  assert.equal(frames.length, ROUNDTRIP_FRAMES)
+  for (const frame of frames) {
+    assert.equal(frame.codedWidth, vector.config.width)
+    assert.equal(frame.codedHeight, vector.config.height)
+  }
+}
+
+// ============================================================================
+// State-machine vectors
+// ============================================================================
+
+const noopInit = { output: () => {}, error: () => {} }
+
+const stateCases: StateCase[] = [
+  {
+    name: 'video-decoder/missing-output-throws',
+    wpt: 'video-decoder.https.any.js',
+    run: async () => {
+      assert.throws(() => new VideoDecoder({ error: () => {} } as never), TypeError)
+    },
+  },
+  {
+    name: 'video-decoder/initial-state',
+    wpt: 'video-decoder.https.any.js',
+    run: async () => {
+      const decoder = new VideoDecoder(noopInit)
+      assert.equal(decoder.state, 'unconfigured')
+      assert.equal(decoder.decodeQueueSize, 0)
+      decoder.close()
+    },
+  },
+  {
+    name: 'video-decoder/closed-operations',
+    wpt: 'video-decoder.https.any.js',
+    run: async () => {
+      const decoder = new VideoDecoder(noopInit)
+      decoder.close()
+      assert.equal(decoder.state, 'closed')
+      expectDOMException(() => decoder.configure({ codec: 'vp8' }), 'InvalidStateError')
+      expectDOMException(() => decoder.reset(), 'InvalidStateError')
+      expectDOMException(() => decoder.close(), 'InvalidStateError')
+      expectDOMException(
+        () => decoder.decode(new EncodedVideoChunk({ type: 'key', timestamp: 0, data: new Uint8Array(1) })),
+        'InvalidStateError',
+      )
+      await expectRejection(decoder.flush(), 'InvalidStateError')
+    },
+  },
+  {
+    name: 'video-decoder/unconfigured-operations',
+    wpt: 'video-decoder.https.any.js',
+    run: async () => {
+      const decoder = new VideoDecoder(noopInit)
+      decoder.reset()
+      assert.equal(decoder.state, 'unconfigured')
+      expectDOMException(
+        () => decoder.decode(new EncodedVideoChunk({ type: 'key', timestamp: 0, data: new Uint8Array(1) })),
+        'InvalidStateError',
+      )
+      await expectRejection(decoder.flush(), 'InvalidStateError')
+      decoder.close()
+    },
+  },
+  {
+    name: 'video-decoder/empty-codec-throws',
+    wpt: 'video-decoder.https.any.js',
+    run: async () => {
+      const decoder = new VideoDecoder(noopInit)
+      assert.throws(() => decoder.configure({ codec: '' }), TypeError)
+      await assert.rejects(VideoDecoder.isConfigSupported({ codec: '' }), TypeError)
+      decoder.close()
+    },
+  },
+  {
+    name: 'video-decoder/unsupported-codec-not-supported',
+    wpt: 'video-decoder.https.any.js',
+    run: async () => {
+      const support = await VideoDecoder.isConfigSupported({ codec: 'bogus' })
+      assert.equal(support.supported, false)
+    },
+  },
+  {
+    name: 'audio-decoder/closed-operations',
+    wpt: 'audio-decoder.https.any.js',
+    run: async () => {
+      const decoder = new AudioDecoder(noopInit)
+      decoder.close()
+      assert.equal(decoder.state, 'closed')
+      expectDOMException(
+        () => decoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2 }),
+        'InvalidStateError',
+      )
+      expectDOMException(() => decoder.reset(), 'InvalidStateError')
+      expectDOMException(
+        () => decoder.decode(new EncodedAudioChunk({ type: 'key', timestamp: 0, data: new Uint8Array(1) })),
+        'InvalidStateError',
+      )
+      await expectRejection(decoder.flush(), 'InvalidStateError')
+    },
+  },
+  {
+    name: 'audio-decoder/unconfigured-operations',
+    wpt: 'audio-decoder.https.any.js',
+    run: async () => {
+      const decoder = new AudioDecoder(noopInit)
+      decoder.reset()
+      assert.equal(decoder.state, 'unconfigured')
+      expectDOMException(
+        () => decoder.decode(new EncodedAudioChunk({ type: 'key', timestamp: 0, data: new Uint8Array(1) })),
+        'InvalidStateError',
+      )
+      await expectRejection(decoder.flush(), 'InvalidStateError')
+      decoder.close()
+    },
+  },
+  {
+    name: 'audio-decoder/zero-channels-throws',
+    wpt: 'audio-decoder.https.any.js',
+    run: async () => {
+      const decoder = new AudioDecoder(noopInit)
+      assert.throws(() => decoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 0 }), TypeError)
+      decoder.close()
+    },
+  },
+  {
+    name: 'video-encoder/closed-operations',
+    wpt: 'video-encoder.https.any.js',
+    run: async () => {
+      const encoder = new VideoEncoder(noopInit)
+      encoder.close()
+      assert.equal(encoder.state, 'closed')
+      expectDOMException(() => encoder.configure({ codec: 'vp8', width: 64, height: 64 }), 'InvalidStateError')
+      expectDOMException(() => encoder.reset(), 'InvalidStateError')
+      const frame = generateGradientI420Frame(64, 64, 0)
+      expectDOMException(() => encoder.encode(frame), 'InvalidStateError')
+      frame.close()
+      await expectRejection(encoder.flush(), 'InvalidStateError')
+    },
+  },
+  {
+    name: 'video-encoder/invalid-dimensions-throw',
+    wpt: 'video-encoder-config.https.any.js',
+    run: async () => {
+      const encoder = new VideoEncoder(noopInit)
+      assert.throws(() => encoder.configure({ codec: 'vp8', width: 0, height: 64 }), TypeError)
+      await assert.rejects(VideoEncoder.isConfigSupported({ codec: 'vp8', width: 64, height: 0 }), TypeError)
+      encoder.close()
+    },
+  },
+  {
+    name: 'video-encoder/encode-closed-frame-throws',
+    wpt: 'video-encoder.https.any.js',
+    run: async () => {
+      const encoder = new VideoEncoder(noopInit)
+      encoder.configure({ codec: 'vp8', width: 64, height: 64 })
+      const frame = generateGradientI420Frame(64, 64, 0)
+      frame.close()
+      assert.throws(() => encoder.encode(frame), TypeError)
+      encoder.close()
+    },
+  },
+  {
+    name: 'audio-encoder/closed-operations',
+    wpt: 'audio-encoder.https.any.js',
+    run: async () => {
+      const encoder = new AudioEncoder(noopInit)
+      encoder.close()
+      assert.equal(encoder.state, 'closed')
+      expectDOMException(
+        () => encoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2 }),
+        'InvalidStateError',
+      )
+      expectDOMException(() => encoder.reset(), 'InvalidStateError')
+      const data = generateSineTone(440, 960, 2, 48000)
+      expectDOMException(() => encoder.encode(data), 'InvalidStateError')
+      data.close()
+      await expectRejection(encoder.flush(), 'InvalidStateError')
+    },
+  },
+  {
+    name: 'audio-encoder/channel-mismatch-flush-rejects',
+    wpt: 'audio-encoder.https.any.js',
+    run: async () => {
+      const encoder = new AudioEncoder(noopInit)
+      encoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2 })
+      const data = generateSineTone(440, 960, 1, 48000)
+      encoder.encode(data)
+      data.close()
+      await expectRejection(encoder.flush(), 'EncodingError')
+    },
+  },
+]
+
+// ============================================================================
+// Loading and running
+// ============================================================================
+
+/** Expand vectors.json and the state-machine list into individual cases */
+export function loadCases(): ConformanceCase[] {
+  const vectors = JSON.parse(readFileSync(join(__dirname, 'vectors.json'), 'utf8')) as VectorFile
+  const cases: ConformanceCase[] = []
+
+  for (const vector of vectors.video) {
+    for (const [scenario, run] of Object.entries(videoScenarios)) {
+      cases.push({ id: `video/${vector.id}/${scenario}`, wpt: vector.wpt, run: () => run(vector) })
+    }
+  }
+  for (const vector of vectors.audio) {
+    for (const [scenario, run] of Object.entries(audioScenarios)) {
+      cases.push({ id: `audio/${vector.id}/${scenario}`, wpt: vector.wpt, run: () => run(vector) })
+    }
+  }
+  for (const vector of vectors.roundtrip) {
+    cases.push({ id: `roundtrip/${vector.id}`, wpt: vector.wpt, run: () => roundtrip(vector) })
+  }
+  for (const { name, wpt, run } of stateCases) {
+    cases.push({ id: `state/${name}`, wpt, run })
+  }
+
+  return cases
+}
+
+/** Expected-failure annotations: case id -> reason */
+export function loadExpectations(): Record<string, string> {
+  const { expectedFailures } = JSON.parse(readFileSync(join(__dirname, 'expectations.json'), 'utf8')) as {
+    expectedFailures: Record<string, string>
+  }
+  return expectedFailures
+}
+
+function withTimeout(promise: Promise<void>, ms: number): Promise<void> {
+  let timer: NodeJS.Timeout | undefined
+  const timeout = new Promise<never>((_, reject) => {
+    timer = setTimeout(() => reject(new Error(`Timed out after ${ms}ms`)), ms)
+  })
+  return Promise.race([promise, timeout]).finally(() => clearTimeout(timer))
+}
+
+/** Run cases sequentially (FFmpeg hardware state is process-wide) */
+export async function runCases(
+  cases: ConformanceCase[],
+  expectations: Record<string, string>,
+  onResult?: (result: ConformanceResult) => void,
+): Promise<ConformanceResult[]> {
+  const results: ConformanceResult[] = []
+  for (const testCase of cases) {
+    resetHardwareFallbackState()
+    const reason = expectations[testCase.id]
+    let error: string | undefined
+    try {
+      await withTimeout(testCase.run(), VECTOR_TIMEOUT_MS)
+    } catch (e) {
+      error = e instanceof Error ? e.message : String(e)
+    }
+
+    let status: ConformanceStatus
+    if (error === undefined) {
+      status = reason === undefined ? 'pass' : 'xpass'
+    } else {
+      status = reason === undefined ? 'fail' : 'xfail'
+    }
+    const result: ConformanceResult = { id: testCase.id, wpt: testCase.wpt, status, error, reason }
+    results.push(result)
+    onResult?.(result)
+  }
+  return results
+}
@@ -0,0 +1,48 @@
+/**
+ * Conformance driver
+ *
+ * Runs the WPT conformance vectors (see harness.ts) and prints one line per
+ * case plus a summary. Exits non-zero on any unexpected failure; with
+ * `--strict`, unexpected passes (stale expectations.json entries) fail too.
+ *
+ * Run with: node --import @oxc-node/core/register __test__/conformance/run.ts [--filter <substring>] [--json] [--strict]
+ */
+
+import { loadCases, loadExpectations, runCases, type ConformanceResult } from './harness.js'
+
+const args = process.argv.slice(2)
+const json = args.includes('--json')
+const strict = args.includes('--strict')
+const filterIndex = args.indexOf('--filter')
+const filter = filterIndex === -1 ? undefined : args[filterIndex + 1]
+
+const LABELS: Record<ConformanceResult['status'], string> = {
+  pass: 'PASS ',
+  fail: 'FAIL ',
+  xfail: 'XFAIL',
+  xpass: 'XPASS',
+}
+
+const cases = loadCases().filter((c) => filter === undefined || c.id.includes(filter))
+const results = await runCases(cases, loadExpectations(), (result) => {
+  if (json) return
+  const detail = result.status === 'fail' ? `: ${result.error}` : result.reason ? ` (${result.reason})` : ''
+  console.log(`${LABELS[result.status]} ${result.id}${detail}`)
+})
+
+const count = (status: ConformanceResult['status']) => results.filter((r) => r.status === status).length
+const summary = { total: results.length, pass: count('pass'), fail: count('fail'), xfail: count('xfail'), xpass: count('xpass') }
+
+if (json) {
+  console.log(JSON.stringify({ summary, results }, null, 2))
+} else {
+  console.log(
+    `\n${summary.total} cases: ${summary.pass} passed, ${summary.fail} failed, ` +
+      `${summary.xfail} expected failures, ${summary.xpass} unexpected passes`,
+  )
+}
+
+if (summary.fail > 0 || (strict && summary.xpass > 0)) {
+  process.exit(1)
+}
+process.exit(0)
@@ -1,10 +1,10 @@
 /**
  * Demuxer Tests
  *
- * Tests for Mp4Demuxer, WebMDemuxer, and MkvDemuxer classes.
+ * Tests for Mp4Demuxer, WebMDemuxer, MkvDemuxer, and AudioFileDemuxer classes.
  */
 
-import test from 'ava'
+import test, { type ExecutionContext } from 'ava'
 import { promises as fs } from 'fs'
 import path from 'path'
 import { fileURLToPath } from 'url'
@@ -12,20 +12,31 @@ import { fileURLToPath } from 'url'
 // Skip demuxer tests on Linux armv7 (QEMU emulation too slow, causes timeouts)
 const isLinuxArmv7 = process.platform === 'linux' && process.arch === 'arm'
 const runTest = isLinuxArmv7 ? test.skip : test
+// Serial variant for tests that change process-wide state (e.g. decode limits)
+const runSerialTest = isLinuxArmv7 ? test.skip : test.serial
 
 import {
+  AudioFileDemuxer,
   Mp4Demuxer,
   WebMDemuxer,
   MkvDemuxer,
+  VideoDecoder,
   VideoEncoder,
   AudioEncoder,
+  Mp4Muxer,
   WebMMuxer,
   MkvMuxer,
   resetHardwareFallbackState,
-  type EncodedVideoChunk,
+  setDecodeLimits,
+  resetDecodeLimits,
+  EncodedVideoChunk,
   type EncodedAudioChunk,
   type EncodedVideoChunkMetadata,
   type EncodedAudioChunkMetadata,
+  type VideoFrame,
+  type AudioData,
+  type RawPacket,
+  type Mp4MuxerOptions,
 } from '../index.js'
 import { generateSolidColorI420Frame, generateSilence, TestColors } from './helpers/index.js'
 
@@ -286,6 +297,79 @@ runTest('Mp4Demuxer: load H.265 WPT fixture', async (t) => {
   demuxer.close()
 })
 
+/** Decode every video chunk using nothing but the demuxer's decoder config */
+async function decodeWithDemuxerConfig(t: ExecutionContext, demuxer: Mp4Demuxer) {
+  let frames = 0
+  let chunks = 0
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      frames++
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure(demuxer.videoDecoderConfig!)
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) {
+      chunks++
+      decoder.decode(chunk.videoChunk)
+    }
+  }
+  await decoder.flush()
+  decoder.close()
+  return { frames, chunks }
+}
+
+runTest('Mp4Demuxer: hvc1 track config carries hvcC and decodes', async (t) => {
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.load(path.join(FIXTURES_DIR, 'wpt', 'h265.mp4'))
+
+  const config = demuxer.videoDecoderConfig!
+  t.true(config.codec.startsWith('hvc1.'), `Expected hvc1 codec string, got ${config.codec}`)
+  t.truthy(config.description, 'hvc1 tracks must surface the hvcC description')
+  t.is(config.description![0], 1, 'Description should be hvcC (configurationVersion 1)')
+  t.false(config.parameterSetsInBand)
+
+  const { frames, chunks } = await decodeWithDemuxerConfig(t, demuxer)
+  t.true(chunks > 0)
+  t.is(frames, chunks)
+  demuxer.close()
+})
+
+runTest('Mp4Demuxer: hev1 track reports in-band parameter sets and decodes', async (t) => {
+  // Remux the hvc1 fixture into a hev1 sample entry
+  const source = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await source.load(path.join(FIXTURES_DIR, 'wpt', 'h265.mp4'))
+  const sourceConfig = source.videoDecoderConfig!
+  const muxer = new Mp4Muxer()
+  muxer.addVideoTrack({
+    codec: sourceConfig.codec.replace(/^hvc1/, 'hev1'),
+    width: sourceConfig.codedWidth,
+    height: sourceConfig.codedHeight,
+    description: sourceConfig.description,
+  })
+  let packet: RawPacket | null
+  while ((packet = await source.readPacket()) !== null) {
+    if (packet.trackType === 'video') muxer.writePacket(packet)
+  }
+  const data = muxer.finalize()
+  muxer.close()
+  source.close()
+
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+  const config = demuxer.videoDecoderConfig!
+  t.true(config.codec.startsWith('hev1.'), `Expected hev1 codec string, got ${config.codec}`)
+  t.is(config.codec.slice(4), sourceConfig.codec.slice(4), 'Profile, tier and level should be unchanged')
+  t.truthy(config.description, 'hvcC present in a hev1 sample entry is still surfaced')
+  t.true(config.parameterSetsInBand)
+
+  const { frames, chunks } = await decodeWithDemuxerConfig(t, demuxer)
+  t.true(chunks > 0)
+  t.is(frames, chunks)
+  demuxer.close()
+})
+
 runTest('Mp4Demuxer: load AV1 WPT fixture', async (t) => {
   const demuxer = new Mp4Demuxer({
     error: (e: Error) => t.fail(`Error: ${e.message}`),
@@ -340,6 +424,24 @@ runTest('Mp4Demuxer: error on invalid file path', async (t) => {
   demuxer.close()
 })
 
+runSerialTest('Mp4Demuxer: rejects tracks beyond decode limits', async (t) => {
+  const demuxer = new Mp4Demuxer({
+    error: (_e: Error) => {},
+  })
+
+  setDecodeLimits({ maxDimension: 64 })
+  try {
+    await t.throwsAsync(() => demuxer.load(path.join(FIXTURES_DIR, 'small_buck_bunny.mp4')), {
+      message: /NotSupportedError/,
+    })
+    t.is(demuxer.state, 'unloaded')
+  } finally {
+    resetDecodeLimits()
+  }
+
+  demuxer.close()
+})
+
 runTest('Mp4Demuxer: error on loading twice without close', async (t) => {
   const demuxer = new Mp4Demuxer({
     error: (_e: Error) => {},
@@ -1078,6 +1180,133 @@ runTest('MkvDemuxer: error on loading twice without close', async (t) => {
   demuxer.close()
 })
 
+// ============================================================================
+// MkvDemuxer Truncated File Recovery Tests
+// ============================================================================
+
+// Helper: Generate an MKV with several clusters (1 fps, so the muxer's 5s cluster limit splits it)
+async function generateMultiClusterMkv(): Promise<Uint8Array> {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: () => {},
+  })
+
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    bitrate: 500_000,
+    framerate: 1,
+  })
+
+  for (let i = 0; i < 20; i++) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.green, i * 1_000_000)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+
+  await encoder.flush()
+  encoder.close()
+
+  const muxer = new MkvMuxer()
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    framerate: 1,
+    description: videoMetadatas[0]?.decoderConfig?.description,
+  })
+  for (let i = 0; i < videoChunks.length; i++) {
+    muxer.addVideoChunk(videoChunks[i], videoMetadatas[i])
+  }
+
+  const data = muxer.finalize()
+  muxer.close()
+  return data
+}
+
+// Byte offsets of Matroska Cluster elements (ID 0x1F43B675)
+function findClusterOffsets(data: Uint8Array): number[] {
+  const offsets: number[] = []
+  for (let i = 0; i + 3 < data.length; i++) {
+    if (data[i] === 0x1f && data[i + 1] === 0x43 && data[i + 2] === 0xb6 && data[i + 3] === 0x75) {
+      offsets.push(i)
+    }
+  }
+  return offsets
+}
+
+async function demuxAllVideo(data: Uint8Array, allowTruncated: boolean) {
+  const demuxer = new MkvDemuxer({ error: () => {}, allowTruncated })
+  await demuxer.loadBuffer(data)
+  const chunks: EncodedVideoChunk[] = []
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) chunks.push(chunk.videoChunk)
+  }
+  const result = { chunks, duration: demuxer.duration, truncated: demuxer.truncated }
+  demuxer.close()
+  return result
+}
+
+runTest('MkvDemuxer: allowTruncated reports an intact file as not truncated', async (t) => {
+  const data = await generateMultiClusterMkv()
+
+  const { chunks, truncated } = await demuxAllVideo(data, true)
+
+  t.false(truncated)
+  t.is(chunks.length, 20)
+})
+
+runTest('MkvDemuxer: allowTruncated drops the damaged final cluster', async (t) => {
+  const data = await generateMultiClusterMkv()
+  const clusters = findClusterOffsets(data)
+  t.true(clusters.length >= 3, `expected several clusters, found ${clusters.length}`)
+
+  // Cut in the middle of the third cluster
+  const cut = Math.floor((clusters[2] + (clusters[3] ?? data.length)) / 2)
+  const full = await demuxAllVideo(data, false)
+  const recovered = await demuxAllVideo(data.subarray(0, cut), true)
+
+  t.true(recovered.truncated)
+  t.true(recovered.chunks.length > 0)
+  t.true(recovered.chunks.length < full.chunks.length)
+
+  // Recovered chunks are an intact prefix of the original stream
+  for (let i = 0; i < recovered.chunks.length; i++) {
+    t.is(recovered.chunks[i].timestamp, full.chunks[i].timestamp)
+    t.is(recovered.chunks[i].byteLength, full.chunks[i].byteLength)
+  }
+
+  // Nothing from the third cluster is emitted: the recovered chunks are exactly the first two clusters
+  const firstTwoClusters = await demuxAllVideo(data.subarray(0, clusters[2]), true)
+  t.is(recovered.chunks.length, firstTwoClusters.chunks.length)
+
+  // Duration reflects the usable data actually found
+  const last = recovered.chunks[recovered.chunks.length - 1]
+  t.is(recovered.duration, last.timestamp + (last.duration ?? 0))
+})
+
+runTest('MkvDemuxer: allowTruncated demuxes a file cut at an arbitrary byte', async (t) => {
+  const data = await generateMultiClusterMkv()
+  const full = await demuxAllVideo(data, false)
+
+  const recovered = await demuxAllVideo(data.subarray(0, Math.floor(data.length * 0.7)), true)
+
+  t.true(recovered.truncated)
+  t.true(recovered.chunks.length > 0)
+  t.true(recovered.chunks.length < full.chunks.length)
+  for (let i = 0; i < recovered.chunks.length; i++) {
+    t.is(recovered.chunks[i].timestamp, full.chunks[i].timestamp)
+  }
+  t.true(recovered.duration! <= full.duration!)
+})
+
 // ============================================================================
 // Async Iterator Tests
 // ============================================================================
@@ -1275,3 +1504,911 @@ runTest('MkvDemuxer: demuxAsync completes', async (t) => {
 
   demuxer.close()
 })
+
+// ============================================================================
+// Decoder Integration Tests
+// ============================================================================
+
+runTest('Mp4Demuxer: createVideoDecoder decodes H.264 track', async (t) => {
+  const demuxer = new Mp4Demuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.load(path.join(FIXTURES_DIR, 'small_buck_bunny.mp4'))
+
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'video')!
+  const frames: VideoFrame[] = []
+  const errors: Error[] = []
+  const decoder = demuxer.createVideoDecoder(track.index, {
+    output: (frame) => frames.push(frame),
+    error: (e) => errors.push(e),
+    hardwareAcceleration: 'prefer-software',
+  })
+  t.is(decoder.state, 'configured')
+
+  let count = 0
+  for await (const chunk of demuxer) {
+    if (chunk.chunkType === 'video' && chunk.videoChunk) {
+      decoder.decode(chunk.videoChunk)
+      if (++count >= 10) break
+    }
+  }
+  await decoder.flush()
+
+  t.is(errors.length, 0)
+  t.true(frames.length > 0, 'Should decode frames')
+  t.is(frames[0].codedWidth, track.codedWidth)
+  for (const frame of frames) frame.close()
+  decoder.close()
+  demuxer.close()
+})
+
+runTest('WebMDemuxer: createVideoDecoder decodes VP9 track', async (t) => {
+  const webmData = await generateWebMWithVP9()
+  const demuxer = new WebMDemuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.loadBuffer(webmData)
+
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'video')!
+  const frames: VideoFrame[] = []
+  const errors: Error[] = []
+  const decoder = demuxer.createVideoDecoder(track.index, {
+    output: (frame) => frames.push(frame),
+    error: (e) => errors.push(e),
+  })
+  t.is(decoder.state, 'configured')
+
+  for await (const chunk of demuxer) {
+    if (chunk.chunkType === 'video' && chunk.videoChunk) {
+      decoder.decode(chunk.videoChunk)
+    }
+  }
+  await decoder.flush()
+
+  t.is(errors.length, 0)
+  t.true(frames.length > 0, 'Should decode frames')
+  for (const frame of frames) frame.close()
+  decoder.close()
+  demuxer.close()
+})
+
+runTest('WebMDemuxer: createAudioDecoder decodes Opus track', async (t) => {
+  const webmData = await generateWebMWithVP9AndOpus()
+  const demuxer = new WebMDemuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.loadBuffer(webmData)
+
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'audio')!
+  const outputs: AudioData[] = []
+  const errors: Error[] = []
+  const decoder = demuxer.createAudioDecoder(track.index, {
+    output: (data) => outputs.push(data),
+    error: (e) => errors.push(e),
+  })
+  t.is(decoder.state, 'configured')
+
+  for await (const chunk of demuxer) {
+    if (chunk.chunkType === 'audio' && chunk.audioChunk) {
+      decoder.decode(chunk.audioChunk)
+    }
+  }
+  await decoder.flush()
+
+  t.is(errors.length, 0)
+  t.true(outputs.length > 0, 'Should decode audio')
+  t.is(outputs[0].sampleRate, 48000)
+  for (const data of outputs) data.close()
+  decoder.close()
+  demuxer.close()
+})
+
+runTest('WebMDemuxer: createVideoDecoder rejects non-video track', async (t) => {
+  const webmData = await generateWebMWithVP9AndOpus()
+  const demuxer = new WebMDemuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.loadBuffer(webmData)
+
+  const audioTrack = demuxer.tracks.find((tr) => tr.trackType === 'audio')!
+  t.throws(() => demuxer.createVideoDecoder(audioTrack.index, { output: () => {}, error: () => {} }), {
+    message: /not a video track/,
+  })
+
+  demuxer.close()
+})
+
+// ============================================================================
+// Color Metadata Tests
+// ============================================================================
+
+// Helper: Encode a short H.264 clip for muxing
+async function encodeH264Clip() {
+  const chunks: EncodedVideoChunk[] = []
+  const metadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      chunks.push(chunk)
+      metadatas.push(metadata)
+    },
+    error: () => {},
+  })
+  encoder.configure({ codec: 'avc1.42001E', width: 320, height: 240, bitrate: 500_000 })
+  for (let i = 0; i < 5; i++) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.red, i * 33333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return { chunks, metadatas, description: metadatas[0]?.decoderConfig?.description }
+}
+
+// Helper: A small ICC-shaped blob ('acsp' signature at offset 36); the containers treat it as opaque
+function makeIccProfile(): Uint8Array {
+  const icc = new Uint8Array(132)
+  for (let i = 0; i < icc.length; i++) icc[i] = (i * 7) & 0xff
+  new DataView(icc.buffer).setUint32(0, icc.length)
+  icc.set(new TextEncoder().encode('acsp'), 36)
+  return icc
+}
+
+runTest('Mp4Demuxer: ICC profile round-trips through Mp4Muxer', async (t) => {
+  const { chunks, metadatas, description } = await encodeH264Clip()
+  const iccProfile = makeIccProfile()
+
+  const muxer = new Mp4Muxer()
+  muxer.addVideoTrack({ codec: 'avc1.42001E', width: 320, height: 240, description, iccProfile })
+  chunks.forEach((chunk, i) => muxer.addVideoChunk(chunk, metadatas[i]))
+  muxer.flush()
+  const data = muxer.finalize()
+  muxer.close()
+
+  const demuxer = new Mp4Demuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.loadBuffer(data)
+  const config = demuxer.videoDecoderConfig
+  t.truthy(config?.iccProfile, 'Should expose the colr/prof ICC profile')
+  t.deepEqual([...config!.iccProfile!], [...iccProfile])
+  demuxer.close()
+})
+
+runTest('Mp4Demuxer: tracks without colr report no ICC profile', async (t) => {
+  const demuxer = new Mp4Demuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.load(path.join(FIXTURES_DIR, 'small_buck_bunny.mp4'))
+  t.is(demuxer.videoDecoderConfig?.iccProfile, undefined)
+  demuxer.close()
+})
+
+runTest('MkvDemuxer: colorSpace round-trips through MkvMuxer', async (t) => {
+  const { chunks, metadatas, description } = await encodeH264Clip()
+
+  const muxer = new MkvMuxer()
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    description,
+    colorSpace: { primaries: 'bt709', transfer: 'bt709', matrix: 'bt709', fullRange: false },
+  })
+  chunks.forEach((chunk, i) => muxer.addVideoChunk(chunk, metadatas[i]))
+  muxer.flush()
+  const data = muxer.finalize()
+  muxer.close()
+
+  const demuxer = new MkvDemuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.loadBuffer(data)
+  const colorSpace = demuxer.videoDecoderConfig?.colorSpace
+  t.is(colorSpace?.primaries, 'bt709')
+  t.is(colorSpace?.transfer, 'bt709')
+  t.is(colorSpace?.matrix, 'bt709')
+  t.is(colorSpace?.fullRange, false)
+  demuxer.close()
+})
+
+// ============================================================================
+// AudioFileDemuxer Tests
+// ============================================================================
+
+// Helper: Demux a bare audio file and decode every chunk
+async function decodeAudioFile(t: ExecutionContext, file: string) {
+  const demuxer = new AudioFileDemuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.load(path.join(FIXTURES_DIR, 'wpt', file))
+  t.is(demuxer.state, 'ready')
+
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'audio')!
+  const config = demuxer.audioDecoderConfig!
+  const outputs: AudioData[] = []
+  const errors: Error[] = []
+  const decoder = demuxer.createAudioDecoder(track.index, {
+    output: (data) => outputs.push(data),
+    error: (e) => errors.push(e),
+  })
+
+  const timestamps: number[] = []
+  for await (const chunk of demuxer) {
+    t.is(chunk.chunkType, 'audio')
+    timestamps.push(chunk.audioChunk!.timestamp)
+    decoder.decode(chunk.audioChunk!)
+  }
+  await decoder.flush()
+
+  const frames = outputs.reduce((sum, data) => sum + data.numberOfFrames, 0)
+  const result = {
+    config,
+    duration: demuxer.duration,
+    timestamps,
+    errors,
+    sampleRate: outputs[0]?.sampleRate,
+    decodedDurationUs: outputs.length > 0 ? (frames / outputs[0].sampleRate) * 1_000_000 : 0,
+  }
+  for (const data of outputs) data.close()
+  decoder.close()
+  demuxer.close()
+  return result
+}
+
+function isIncreasing(values: number[]): boolean {
+  return values.every((v, i) => i === 0 || v > values[i - 1])
+}
+
+runTest('AudioFileDemuxer: MP3 round-trip decodes the whole file', async (t) => {
+  const result = await decodeAudioFile(t, 'sfx.mp3')
+
+  t.is(result.config.codec, 'mp3')
+  t.is(result.config.sampleRate, 48000)
+  t.is(result.config.numberOfChannels, 1)
+  t.is(result.errors.length, 0)
+
+  // 10 MPEG-1 Layer III frames of 1152 samples (the LAME info frame is not audio)
+  t.is(result.timestamps.length, 10)
+  t.true(isIncreasing(result.timestamps), 'timestamps should increase')
+  t.is(result.timestamps[1] - result.timestamps[0], 24_000)
+
+  // At most 10 frames of audio; encoder delay may be trimmed from the start
+  t.true(result.decodedDurationUs > 200_000, `decoded ${result.decodedDurationUs}us`)
+  t.true(result.decodedDurationUs <= 240_000, `decoded ${result.decodedDurationUs}us`)
+  t.truthy(result.duration)
+})
+
+runTest('AudioFileDemuxer: FLAC round-trip decodes the whole file', async (t) => {
+  const result = await decodeAudioFile(t, 'sfx.flac')
+
+  t.is(result.config.codec, 'flac')
+  t.is(result.config.sampleRate, 48000)
+  t.is(result.config.numberOfChannels, 1)
+  t.is(result.errors.length, 0)
+
+  // Description is the "fLaC" stream header plus STREAMINFO
+  const description = result.config.description!
+  t.is(description.length, 42)
+  t.is(new TextDecoder().decode(description.subarray(0, 4)), 'fLaC')
+
+  t.true(result.timestamps.length > 0)
+  t.is(result.timestamps[0], 0)
+  t.true(isIncreasing(result.timestamps), 'timestamps should increase')
+
+  // STREAMINFO declares 10240 samples at 48kHz
+  t.is(Math.round(result.decodedDurationUs), 213_333)
+  t.true(Math.abs(result.duration! - 213_333) < 1_000, `duration ${result.duration}us`)
+})
+
+runTest('AudioFileDemuxer: ADTS AAC reports codec string from the stream', async (t) => {
+  const result = await decodeAudioFile(t, 'sfx.adts')
+
+  t.is(result.config.codec, 'mp4a.40.2')
+  t.is(result.config.sampleRate, 48000)
+  t.is(result.config.numberOfChannels, 1)
+  t.is(result.errors.length, 0)
+
+  // 10 ADTS frames of 1024 samples
+  t.is(result.timestamps.length, 10)
+  t.true(isIncreasing(result.timestamps), 'timestamps should increase')
+  t.true(result.decodedDurationUs > 0, 'Should decode audio')
+})
+
+runTest('AudioFileDemuxer: loadBuffer matches load', async (t) => {
+  const data = await fs.readFile(path.join(FIXTURES_DIR, 'wpt', 'sfx.flac'))
+  const demuxer = new AudioFileDemuxer({
+    error: (e: Error) => t.fail(`Error: ${e.message}`),
+  })
+  await demuxer.loadBuffer(new Uint8Array(data))
+
+  t.is(demuxer.audioDecoderConfig?.codec, 'flac')
+  t.is(demuxer.tracks.filter((tr) => tr.trackType === 'audio').length, 1)
+  demuxer.close()
+  t.is(demuxer.state, 'closed')
+})
+
+runTest('AudioFileDemuxer: rejects files without a supported audio stream', async (t) => {
+  const demuxer = new AudioFileDemuxer({
+    error: (_e: Error) => {},
+  })
+  await t.throwsAsync(demuxer.load(path.join(FIXTURES_DIR, 'wpt', 'sfx-opus.ogg')), {
+    message: /Unsupported audio file codec/,
+  })
+})
+
+// ============================================================================
+// Track Analysis Tests
+// ============================================================================
+
+// Helper: Generate a WebM with VP8 frames at the given timestamps (microseconds)
+async function generateWebMWithTimestamps(timestamps: number[]): Promise<Uint8Array> {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: () => {},
+  })
+  encoder.configure({ codec: 'vp8', width: 160, height: 120, bitrate: 200_000 })
+
+  for (let i = 0; i < timestamps.length; i++) {
+    const frame = generateSolidColorI420Frame(160, 120, TestColors.green, timestamps[i])
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const muxer = new WebMMuxer()
+  muxer.addVideoTrack({ codec: 'vp8', width: 160, height: 120 })
+  for (let i = 0; i < videoChunks.length; i++) {
+    muxer.addVideoChunk(videoChunks[i], videoMetadatas[i])
+  }
+  const data = muxer.finalize()
+  muxer.close()
+  return data
+}
+
+runTest('WebMDemuxer: analyzeTrack detects VFR and reports interval stats', async (t) => {
+  // Screen-recording-like timing: bursts at 60 fps with idle gaps
+  const pattern = [16_000, 17_000, 17_000, 100_000, 250_000]
+  const timestamps = [0]
+  for (let i = 1; i < 60; i++) {
+    timestamps.push(timestamps[i - 1] + pattern[i % pattern.length])
+  }
+  const data = await generateWebMWithTimestamps(timestamps)
+
+  const demuxer = new WebMDemuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+  const trackId = demuxer.tracks.find((tr) => tr.trackType === 'video')!.index
+
+  const analysis = await demuxer.analyzeTrack(trackId)
+  t.is(analysis.trackId, trackId)
+  t.is(analysis.packetCount, 60)
+  t.true(analysis.complete)
+  t.true(analysis.isVariableFrameRate)
+  t.is(analysis.minIntervalUs, 16_000)
+  t.is(analysis.maxIntervalUs, 250_000)
+  const span = timestamps[timestamps.length - 1] - timestamps[0]
+  t.true(Math.abs(analysis.meanIntervalUs! - span / 59) < 1)
+
+  const histogram = new Map(analysis.intervalHistogram.map((b) => [b.intervalUs, b.count]))
+  t.deepEqual([...histogram.keys()], [16_000, 17_000, 100_000, 250_000])
+  t.is([...histogram.values()].reduce((a, b) => a + b, 0), 59)
+
+  t.true(analysis.averageBitrate! > 0)
+  t.true(analysis.bitrateWindows.length >= 5)
+  t.is(analysis.bitrateWindows[0].startUs, 0)
+  t.is(analysis.bitrateWindows[0].durationUs, 1_000_000)
+
+  // The scan uses its own view of the buffer: demuxing still starts at the first packet
+  const chunks: EncodedVideoChunk[] = []
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) chunks.push(chunk.videoChunk)
+  }
+  t.is(chunks.length, 60)
+  t.is(chunks[0].timestamp, 0)
+  demuxer.close()
+})
+
+runTest('WebMDemuxer: analyzeTrack reports CFR content and honors maxPackets', async (t) => {
+  const timestamps = Array.from({ length: 30 }, (_, i) => Math.round((i * 1_000_000) / 30))
+  const data = await generateWebMWithTimestamps(timestamps)
+
+  const demuxer = new WebMDemuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+  const trackId = demuxer.tracks.find((tr) => tr.trackType === 'video')!.index
+
+  const full = await demuxer.analyzeTrack(trackId)
+  t.false(full.isVariableFrameRate)
+  t.true(Math.abs(full.meanIntervalUs! - 33_333) < 100)
+
+  const partial = await demuxer.analyzeTrack(trackId, { maxPackets: 10 })
+  t.is(partial.packetCount, 10)
+  t.false(partial.complete)
+
+  await t.throwsAsync(demuxer.analyzeTrack(trackId, { maxPackets: 0 }), { message: /maxPackets/ })
+  await t.throwsAsync(demuxer.analyzeTrack(99), { message: /Invalid track index/ })
+  demuxer.close()
+})
+
+// ============================================================================
+// Key Frame Packet Tests
+// ============================================================================
+
+/** Encode 10s of 30fps baseline H.264 with a key frame every 15 frames into an MP4 */
+async function generateMP4WithKeyFrameInterval(options?: Mp4MuxerOptions): Promise<Uint8Array> {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 160,
+    height: 120,
+    bitrate: 200_000,
+    framerate: 30,
+    hardwareAcceleration: 'prefer-software',
+  })
+
+  for (let i = 0; i < 300; i++) {
+    const frame = generateSolidColorI420Frame(160, 120, TestColors.green, Math.round((i * 1_000_000) / 30))
+    encoder.encode(frame, { keyFrame: i % 15 === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const muxer = new Mp4Muxer(options)
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 160,
+    height: 120,
+    framerate: 30,
+    description: videoMetadatas[0]?.decoderConfig?.description,
+  })
+  for (let i = 0; i < videoChunks.length; i++) {
+    muxer.addVideoChunk(videoChunks[i], videoMetadatas[i])
+  }
+  const data = muxer.finalize()
+  muxer.close()
+  return data
+}
+
+runTest('Mp4Demuxer: keyFramePackets builds a 20-frame filmstrip with keyFramesOnly', async (t) => {
+  const data = await generateMP4WithKeyFrameInterval()
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+  const trackId = demuxer.tracks.find((tr) => tr.trackType === 'video')!.index
+  const config = demuxer.videoDecoderConfig!
+
+  // Full decode for comparison
+  const fullStart = performance.now()
+  let fullFrames = 0
+  const fullDecoder = new VideoDecoder({
+    output: (frame) => {
+      fullFrames++
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  fullDecoder.configure(config)
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) fullDecoder.decode(chunk.videoChunk)
+  }
+  await fullDecoder.flush()
+  fullDecoder.close()
+  const fullMs = performance.now() - fullStart
+  t.is(fullFrames, 300)
+
+  // Filmstrip: jump between sync samples and decode only those
+  const thumbnailCount = 20
+  const intervalUs = 10_000_000 / thumbnailCount
+  const filmstripStart = performance.now()
+  const thumbnails: number[] = []
+  const thumbnailDecoder = new VideoDecoder({
+    output: (frame) => {
+      thumbnails.push(frame.timestamp)
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  thumbnailDecoder.configure({ ...config, keyFramesOnly: true })
+  let chunksRead = 0
+  for await (const chunk of demuxer.keyFramePackets(trackId, { intervalUs })) {
+    t.is(chunk.type, 'key')
+    chunksRead++
+    thumbnailDecoder.decode(chunk)
+  }
+  await thumbnailDecoder.flush()
+  thumbnailDecoder.close()
+  const filmstripMs = performance.now() - filmstripStart
+  t.log(`full decode ${fullMs.toFixed(1)}ms, filmstrip ${filmstripMs.toFixed(1)}ms`)
+
+  t.is(chunksRead, thumbnailCount)
+  t.is(thumbnails.length, thumbnailCount)
+  for (let i = 0; i < thumbnailCount; i++) {
+    t.true(thumbnails[i] >= i * intervalUs, `thumbnail ${i} at ${thumbnails[i]}`)
+    t.true(thumbnails[i] < (i + 1) * intervalUs, `thumbnail ${i} at ${thumbnails[i]}`)
+  }
+  // Only the shown frames are decoded
+  t.true(thumbnails.length * 10 < fullFrames)
+  demuxer.close()
+})
+
+runTest('Mp4Demuxer: keyFramePackets honors startUs and rejects non-video tracks', async (t) => {
+  const data = await generateMP4WithKeyFrameInterval()
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+  const trackId = demuxer.tracks.find((tr) => tr.trackType === 'video')!.index
+
+  const timestamps: number[] = []
+  for await (const chunk of demuxer.keyFramePackets(trackId, { startUs: 8_100_000 })) {
+    timestamps.push(chunk.timestamp)
+  }
+  t.deepEqual(timestamps, [8_500_000, 9_000_000, 9_500_000])
+
+  t.throws(() => demuxer.keyFramePackets(99), { message: /Invalid track index/ })
+  t.throws(() => demuxer.keyFramePackets(trackId, { intervalUs: -1 }), { message: /intervalUs/ })
+  demuxer.close()
+})
+
+runTest('VideoDecoder: keyFramesOnly drops delta chunks without error', async (t) => {
+  const data = await generateMP4WithKeyFrameInterval()
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+
+  const timestamps: number[] = []
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      timestamps.push(frame.timestamp)
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure({ ...demuxer.videoDecoderConfig!, keyFramesOnly: true })
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) decoder.decode(chunk.videoChunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.deepEqual(timestamps, Array.from({ length: 20 }, (_, i) => i * 500_000))
+  demuxer.close()
+})
+
+/** Split a fragmented MP4 into its init segment and one buffer per moof/mdat fragment */
+function splitFragments(data: Uint8Array): { init: Uint8Array; fragments: Uint8Array[] } {
+  const view = new DataView(data.buffer, data.byteOffset, data.byteLength)
+  const starts: number[] = []
+  let initEnd = 0
+  for (let offset = 0; offset + 8 <= data.length; offset += view.getUint32(offset)) {
+    const type = String.fromCharCode(...data.subarray(offset + 4, offset + 8))
+    if (type === 'moov') initEnd = offset + view.getUint32(offset)
+    if (type === 'moof') starts.push(offset)
+  }
+  // Trailing boxes such as mfra stay with the last fragment
+  const fragments = starts.map((start, i) => data.subarray(start, starts[i + 1] ?? data.length))
+  return { init: data.subarray(0, initEnd), fragments }
+}
+
+runTest('Mp4Demuxer: lowLatency emits the first chunk of appended fMP4 within one fragment', async (t) => {
+  const data = await generateMP4WithKeyFrameInterval({ fragmented: true })
+  const { init, fragments } = splitFragments(data)
+  t.true(fragments.length > 1)
+
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message), lowLatency: true })
+  demuxer.appendBuffer(init)
+  t.is(demuxer.state, 'ready')
+  t.regex(demuxer.videoDecoderConfig?.codec ?? '', /^avc1\./)
+
+  // Only the first fragment has been appended when the first packet resolves
+  const first = demuxer.readPacket()
+  demuxer.appendBuffer(fragments[0])
+  const timeout = new Promise<'timeout'>((resolve) => setTimeout(() => resolve('timeout'), 5000))
+  const packet = await Promise.race([first, timeout])
+  if (packet === 'timeout' || packet === null) {
+    demuxer.close()
+    t.fail('first packet was not emitted before the second fragment')
+    return
+  }
+  t.is(packet.pts, 0)
+  t.is(packet.flags & 1, 1)
+
+  for (const fragment of fragments.slice(1)) {
+    demuxer.appendBuffer(fragment)
+  }
+  demuxer.endOfStream()
+  t.throws(() => demuxer.appendBuffer(fragments[0]), { message: /endOfStream/ })
+
+  let count = 1
+  while ((await demuxer.readPacket()) !== null) {
+    count++
+  }
+  t.is(count, 300)
+  demuxer.close()
+})
+
+// ============================================================================
+// Remux Tests
+// ============================================================================
+
+/** Encode 30 High-profile H.264 frames (with B-frames) at 25fps into an MP4 */
+async function generateMP4WithBFrames(): Promise<Uint8Array> {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec: 'avc1.64001F',
+    width: 320,
+    height: 240,
+    bitrate: 500_000,
+    framerate: 25,
+    hardwareAcceleration: 'prefer-software',
+  })
+
+  for (let i = 0; i < 30; i++) {
+    const color = i % 2 === 0 ? TestColors.blue : TestColors.red
+    const frame = generateSolidColorI420Frame(320, 240, color, i * 40_000)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const muxer = new Mp4Muxer()
+  muxer.addVideoTrack({
+    codec: 'avc1.64001F',
+    width: 320,
+    height: 240,
+    framerate: 25,
+    description: videoMetadatas[0]?.decoderConfig?.description,
+  })
+  for (let i = 0; i < videoChunks.length; i++) {
+    muxer.addVideoChunk(videoChunks[i], videoMetadatas[i])
+  }
+  const data = muxer.finalize()
+  muxer.close()
+  return data
+}
+
+/** Copy every packet from a loaded demuxer into a new muxer without decoding */
+async function remux(
+  demuxer: Mp4Demuxer | MkvDemuxer,
+  MuxerClass: typeof Mp4Muxer | typeof MkvMuxer,
+): Promise<{ data: Uint8Array; packets: RawPacket[] }> {
+  const config = demuxer.videoDecoderConfig!
+  const muxer = new MuxerClass()
+  muxer.addVideoTrack({
+    codec: config.codec,
+    width: config.codedWidth,
+    height: config.codedHeight,
+    framerate: 25,
+    description: config.description,
+  })
+
+  const packets: RawPacket[] = []
+  let packet: RawPacket | null
+  while ((packet = await demuxer.readPacket()) !== null) {
+    packets.push(packet)
+    muxer.writePacket(packet)
+  }
+  const data = muxer.finalize()
+  muxer.close()
+  demuxer.close()
+  return { data, packets }
+}
+
+const toMicros = (ts: number | undefined, p: RawPacket) =>
+  ts === undefined ? undefined : Math.round((ts * p.timeBaseNum * 1_000_000) / p.timeBaseDen)
+
+runTest('Mp4Demuxer: readPacket exposes DTS for B-frame reordering', async (t) => {
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(await generateMP4WithBFrames())
+
+  const packets: RawPacket[] = []
+  let packet: RawPacket | null
+  while ((packet = await demuxer.readPacket()) !== null) packets.push(packet)
+  demuxer.close()
+
+  t.is(packets.length, 30)
+  t.is(packets[0].trackType, 'video')
+  t.is(packets[0].flags & 1, 1, 'First packet should be a keyframe')
+  t.true(
+    packets.some((p) => p.pts !== p.dts),
+    'B-frames should give packets whose PTS differs from DTS',
+  )
+  for (let i = 1; i < packets.length; i++) {
+    t.true(packets[i].dts! > packets[i - 1].dts!, 'DTS should increase monotonically')
+  }
+})
+
+runTest('Mp4Muxer: writePacket remuxes MP4 with bit-exact timestamps', async (t) => {
+  const source = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await source.loadBuffer(await generateMP4WithBFrames())
+  const { data, packets: sourcePackets } = await remux(source, Mp4Muxer)
+
+  const copy = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await copy.loadBuffer(data)
+  const { packets: copiedPackets } = await remux(copy, Mp4Muxer)
+
+  t.is(copiedPackets.length, sourcePackets.length)
+  for (let i = 0; i < sourcePackets.length; i++) {
+    const [a, b] = [sourcePackets[i], copiedPackets[i]]
+    t.is(b.timeBaseDen / b.timeBaseNum, a.timeBaseDen / a.timeBaseNum)
+    t.is(b.pts, a.pts)
+    t.is(b.dts, a.dts)
+    t.is(b.duration, a.duration)
+    t.is(b.flags, a.flags)
+    t.deepEqual(Buffer.from(b.data), Buffer.from(a.data))
+  }
+})
+
+runTest('MkvMuxer: MP4 -> MKV -> MP4 remux keeps timestamps and stays decodable', async (t) => {
+  const source = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await source.loadBuffer(await generateMP4WithBFrames())
+  const { data: mkvData, packets: sourcePackets } = await remux(source, MkvMuxer)
+
+  const mkv = new MkvDemuxer({ error: (e: Error) => t.fail(e.message) })
+  await mkv.loadBuffer(mkvData)
+  const { data: mp4Data, packets: mkvPackets } = await remux(mkv, Mp4Muxer)
+
+  const final = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await final.loadBuffer(mp4Data)
+  const finalPackets: RawPacket[] = []
+  let packet: RawPacket | null
+  while ((packet = await final.readPacket()) !== null) finalPackets.push(packet)
+
+  for (const packets of [mkvPackets, finalPackets]) {
+    t.is(packets.length, sourcePackets.length)
+    for (let i = 0; i < sourcePackets.length; i++) {
+      const [a, b] = [sourcePackets[i], packets[i]]
+      t.is(toMicros(b.pts, b), toMicros(a.pts, a))
+      t.is(b.flags & 1, a.flags & 1)
+      t.deepEqual(Buffer.from(b.data), Buffer.from(a.data))
+    }
+  }
+  final.close()
+
+  // The round-tripped file must still decode every frame in presentation order
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(mp4Data)
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'video')!
+  const frames: VideoFrame[] = []
+  const decoder = demuxer.createVideoDecoder(track.index, {
+    output: (frame) => frames.push(frame),
+    error: (e) => t.fail(e.message),
+    hardwareAcceleration: 'prefer-software',
+  })
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) decoder.decode(chunk.videoChunk)
+  }
+  await decoder.flush()
+
+  t.is(frames.length, 30)
+  for (let i = 1; i < frames.length; i++) {
+    t.true(frames[i].timestamp > frames[i - 1].timestamp, 'Frames should be output in presentation order')
+  }
+  for (const frame of frames) frame.close()
+  decoder.close()
+  demuxer.close()
+})
+
+// ============================================================================
+// Demuxer chunks passed to VideoDecoder
+// ============================================================================
+
+/** Demux every video chunk of a 30-frame VP8 WebM, then close the demuxer */
+async function demuxVp8Chunks(t: ExecutionContext): Promise<EncodedVideoChunk[]> {
+  const data = await generateWebMWithTimestamps(Array.from({ length: 30 }, (_, i) => i * 33_333))
+  const demuxer = new WebMDemuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+  const chunks: EncodedVideoChunk[] = []
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) chunks.push(chunk.videoChunk)
+  }
+  // The chunks keep their packets alive on their own
+  demuxer.close()
+  return chunks
+}
+
+function createCountingDecoder(t: ExecutionContext) {
+  const counter = { frames: 0 }
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      counter.frames++
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure({ codec: 'vp8', codedWidth: 160, codedHeight: 120 })
+  return { decoder, counter }
+}
+
+runTest('VideoDecoder: demuxer chunks are decoded from their packets without a copy', async (t) => {
+  const chunks = await demuxVp8Chunks(t)
+  const before = chunks.map((chunk) => {
+    const bytes = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(bytes)
+    return bytes
+  })
+  const { decoder, counter } = createCountingDecoder(t)
+
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  const stats = decoder.inputStats()
+  decoder.close()
+
+  t.is(counter.frames, 30)
+  t.deepEqual(stats, { copiedChunks: 0, copiedBytes: 0, sharedChunks: 30 })
+  // decode() only references the packet: the chunks are still intact
+  chunks.forEach((chunk, i) => {
+    const bytes = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(bytes)
+    t.deepEqual(bytes, before[i])
+  })
+})
+
+runTest('VideoDecoder: decodeAndClose hands the packet over and closes the chunk', async (t) => {
+  const chunks = await demuxVp8Chunks(t)
+  const { decoder, counter } = createCountingDecoder(t)
+
+  for (const chunk of chunks) {
+    decoder.decodeAndClose(chunk)
+  }
+  await decoder.flush()
+  const stats = decoder.inputStats()
+
+  t.is(counter.frames, 30)
+  t.is(stats.copiedChunks, 0)
+  t.is(stats.copiedBytes, 0)
+  t.is(stats.sharedChunks, 30)
+
+  t.throws(() => chunks[0].copyTo(new Uint8Array(1 << 16)), { message: /InvalidStateError/ })
+  t.throws(() => chunks[0].byteLength, { message: /InvalidStateError/ })
+  t.throws(() => decoder.decode(chunks[0]), { name: 'InvalidStateError' })
+  t.is(decoder.state, 'configured')
+  decoder.close()
+})
+
+runTest('VideoDecoder: chunks created from JS bytes are copied once', async (t) => {
+  const chunks = (await demuxVp8Chunks(t)).map((chunk) => {
+    const data = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(data)
+    return new EncodedVideoChunk({ type: chunk.type, timestamp: chunk.timestamp, data })
+  })
+  const { decoder } = createCountingDecoder(t)
+
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  const stats = decoder.inputStats()
+  decoder.close()
+
+  t.is(stats.copiedChunks, 30)
+  t.is(stats.copiedBytes, chunks.reduce((total, chunk) => total + chunk.byteLength, 0))
+  t.is(stats.sharedChunks, 0)
+})
@@ -0,0 +1,109 @@
+/**
+ * Encode Estimate Tests
+ *
+ * Tests for estimateEncode() (projected size and QP from sampled segments).
+ */
+
+import { promises as fs } from 'node:fs'
+import * as path from 'node:path'
+import { fileURLToPath } from 'node:url'
+
+import test from 'ava'
+
+import { Mp4Demuxer, VideoDecoder, VideoEncoder, estimateEncode, type VideoEncoderConfig } from '../index.js'
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url))
+const MP4_FIXTURE = path.join(__dirname, 'fixtures', 'small_buck_bunny.mp4')
+
+async function fixtureConfig(): Promise<VideoEncoderConfig> {
+  const demuxer = new Mp4Demuxer({ error: () => {} })
+  await demuxer.load(MP4_FIXTURE)
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'video')!
+  demuxer.close()
+  return {
+    codec: 'avc1.42001E',
+    width: track.codedWidth!,
+    height: track.codedHeight!,
+    bitrate: 300_000,
+    bitrateMode: 'variable',
+    hardwareAcceleration: 'prefer-software',
+  }
+}
+
+/** Transcode the whole fixture and return the encoded video size */
+async function fullEncodeBytes(config: VideoEncoderConfig): Promise<number> {
+  let bytes = 0
+  const encoder = new VideoEncoder({
+    output: (chunk) => {
+      bytes += chunk.byteLength
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure(config)
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      encoder.encode(frame)
+      frame.close()
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+
+  const demuxer = new Mp4Demuxer({ error: () => {} })
+  await demuxer.load(MP4_FIXTURE)
+  decoder.configure(demuxer.videoDecoderConfig!)
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) decoder.decode(chunk.videoChunk)
+  }
+  await decoder.flush()
+  await encoder.flush()
+  decoder.close()
+  encoder.close()
+  demuxer.close()
+  return bytes
+}
+
+test('estimateEncode: projection is close to a full encode', async (t) => {
+  const config = await fixtureConfig()
+  const estimate = await estimateEncode(MP4_FIXTURE, config, { samples: 4, sampleDurationMs: 1000 })
+  const actual = await fullEncodeBytes(config)
+  t.log(`projected ${estimate.projectedBytes} bytes, full encode ${actual} bytes`)
+
+  t.true(estimate.durationUs > 0)
+  t.truthy(estimate.encoder)
+  t.true(estimate.samples.length >= 1 && estimate.samples.length <= 4)
+  t.true(Math.abs(estimate.projectedBytes - actual) / actual < 0.35)
+})
+
+test('estimateEncode: per-sample statistics', async (t) => {
+  const config = await fixtureConfig()
+  const buffer = await fs.readFile(MP4_FIXTURE)
+  const estimate = await estimateEncode(new Uint8Array(buffer), config, { samples: 2, sampleDurationMs: 500 })
+
+  let previousStart = -1
+  for (const sample of estimate.samples) {
+    t.true(sample.startUs > previousStart)
+    t.true(sample.frames > 0)
+    t.true(sample.bytes > 0)
+    t.true(sample.durationUs > 0)
+    t.true(sample.bitrate > 0)
+    if (sample.averageQp !== undefined) t.true(sample.averageQp >= 0)
+    previousStart = sample.startUs
+  }
+  const sampledBytes = estimate.samples.reduce((sum, sample) => sum + sample.bytes, 0)
+  t.true(estimate.projectedBytes >= sampledBytes)
+})
+
+test('estimateEncode: rejects invalid options', async (t) => {
+  const config = await fixtureConfig()
+  await t.throwsAsync(() => estimateEncode(MP4_FIXTURE, config, { samples: 0 }), {
+    message: /samples must be between 1 and 64/,
+  })
+  await t.throwsAsync(() => estimateEncode(MP4_FIXTURE, config, { sampleDurationMs: 120_000 }), {
+    message: /sampleDurationMs/,
+  })
+  await t.throwsAsync(() => estimateEncode(MP4_FIXTURE, { ...config, width: 0 }))
+})
@@ -10,9 +10,11 @@ import test from 'ava'
 import {
   getHardwareAccelerators,
   getAvailableHardwareAccelerators,
+  getHardwareSessionCounts,
   getPreferredHardwareAccelerator,
   isHardwareAcceleratorAvailable,
   resetHardwareFallbackState,
+  setHardwareDeviceSharing,
   VideoEncoder,
 } from '../index.js'
 import { generateSolidColorI420Frame, TestColors, hasHardwareAcceleration, type EncodedVideoChunk } from './helpers/index.js'
@@ -320,3 +322,113 @@ test('hardware encoding: no-preference fallback', async (t) => {
 
   encoder.close()
 })
+
+// ============================================================================
+// Device Sharing Tests
+// ============================================================================
+
+async function encodeConcurrently(count: number) {
+  const encoders = Array.from({ length: count }, () => createTestEncoder())
+  for (const { encoder } of encoders) {
+    encoder.configure(createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'no-preference' }))
+  }
+  for (const { encoder } of encoders) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.red, 0)
+    encoder.encode(frame, { keyFrame: true })
+    frame.close()
+  }
+  await Promise.all(encoders.map(({ encoder }) => encoder.flush()))
+  for (const { encoder } of encoders) {
+    encoder.close()
+  }
+  return encoders
+}
+
+test('device sharing: 20 simultaneous encoders all produce output', async (t) => {
+  const encoders = await encodeConcurrently(20)
+
+  for (const { chunks, errors } of encoders) {
+    t.is(errors.length, 0)
+    t.true(chunks.length > 0)
+  }
+})
+
+test('device sharing: isolated devices can be requested', async (t) => {
+  setHardwareDeviceSharing(false)
+  try {
+    const encoders = await encodeConcurrently(4)
+    for (const { chunks, errors } of encoders) {
+      t.is(errors.length, 0)
+      t.true(chunks.length > 0)
+    }
+  } finally {
+    setHardwareDeviceSharing(true)
+  }
+})
+
+// ============================================================================
+// Session Count Tests
+// ============================================================================
+
+function activeSessions(): number {
+  return getHardwareSessionCounts().reduce((sum, count) => sum + count.activeEncoders, 0)
+}
+
+test('session counts: entries describe known accelerators', (t) => {
+  const known = getHardwareAccelerators().map((a) => a.name)
+  for (const count of getHardwareSessionCounts()) {
+    t.true(known.includes(count.accelerator))
+    t.true(count.activeEncoders >= 0)
+    t.true(count.limitHits >= 0)
+    if (count.maxEncoders !== undefined) t.true(count.maxEncoders >= 1)
+  }
+})
+
+test.serial('session counts: software encoders hold no session', async (t) => {
+  const before = activeSessions()
+  const { encoder, chunks } = createTestEncoder()
+  encoder.configure(createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+  const frame = generateSolidColorI420Frame(320, 240, TestColors.red, 0)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+
+  t.true(chunks.length > 0)
+  t.is(activeSessions(), before)
+  encoder.close()
+})
+
+test.serial('session counts: no-preference encoders past the session cap fall back silently', async (t) => {
+  const before = activeSessions()
+  const count = 8
+  let fallbacks = 0
+  const encoders = Array.from({ length: count }, () => createTestEncoder())
+  for (const { encoder } of encoders) {
+    encoder.addEventListener('fallback', () => {
+      fallbacks++
+    })
+    encoder.configure(createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'no-preference' }))
+  }
+  for (const { encoder } of encoders) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.red, 0)
+    encoder.encode(frame, { keyFrame: true })
+    frame.close()
+  }
+  await Promise.all(encoders.map(({ encoder }) => encoder.flush()))
+
+  const open = activeSessions() - before
+  t.true(open >= 0 && open <= count)
+  for (const { chunks, errors } of encoders) {
+    t.is(errors.length, 0)
+    t.true(chunks.length > 0)
+  }
+  // Every encoder refused by a session cap switched to software
+  const limitHits = getHardwareSessionCounts().reduce((sum, c) => sum + c.limitHits, 0)
+  t.log(`${open} hardware sessions, ${fallbacks} fallbacks, ${limitHits} session limit hits`)
+  t.true(open + fallbacks <= count)
+
+  for (const { encoder } of encoders) {
+    encoder.close()
+  }
+  t.is(activeSessions(), before)
+})
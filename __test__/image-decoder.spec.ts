@@ -9,8 +9,9 @@ import test from 'ava'
 import { readFileSync } from 'fs'
 import { join, dirname } from 'path'
 import { fileURLToPath } from 'url'
+import { crc32, deflateSync } from 'zlib'
 
-import { ImageDecoder } from '../index.js'
+import { ImageDecoder, VideoEncoder, VideoFrame, type ImageDecodeOptions } from '../index.js'
 
 const __dirname = dirname(fileURLToPath(import.meta.url))
 
@@ -215,6 +216,46 @@ test('ImageDecoder reset() after close() throws', async (t) => {
   }
 })
 
+// ============================================================================
+// ICC Profile Tests
+// ============================================================================
+
+// Helper: Insert an iCCP chunk carrying `profile` right after the PNG IHDR chunk
+function withIccProfile(png: Buffer, profile: Uint8Array): Buffer {
+  const body = Buffer.concat([Buffer.from('test\0\0', 'latin1'), deflateSync(profile)])
+  const chunk = Buffer.alloc(12 + body.length)
+  chunk.writeUInt32BE(body.length, 0)
+  chunk.write('iCCP', 4, 'latin1')
+  body.copy(chunk, 8)
+  chunk.writeUInt32BE(crc32(chunk.subarray(4, 8 + body.length)), 8 + body.length)
+  // 8-byte signature + IHDR (4 length + 4 type + 13 data + 4 CRC)
+  const ihdrEnd = 8 + 25
+  return Buffer.concat([png.subarray(0, ihdrEnd), chunk, png.subarray(ihdrEnd)])
+}
+
+test('ImageDecoder exposes the embedded PNG ICC profile on the track', async (t) => {
+  const profile = new Uint8Array(96).map((_, i) => (i * 13) & 0xff)
+  const data = withIccProfile(readFileSync(join(__dirname, 'fixtures/test.png')), profile)
+  const decoder = new ImageDecoder({ data, type: 'image/png' })
+
+  await decoder.tracks.ready
+  const iccProfile = decoder.tracks.selectedTrack!.iccProfile
+  t.truthy(iccProfile)
+  t.deepEqual([...iccProfile!], [...profile])
+
+  decoder.close()
+})
+
+test('ImageDecoder iccProfile is null without an embedded profile', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/test.png'))
+  const decoder = new ImageDecoder({ data, type: 'image/png' })
+
+  await decoder.tracks.ready
+  t.is(decoder.tracks.selectedTrack!.iccProfile, null)
+
+  decoder.close()
+})
+
 // ============================================================================
 // MIME Type Support Tests
 // ============================================================================
@@ -300,6 +341,82 @@ test('ImageDecoder tracks.ready resolves for static images', async (t) => {
   decoder.close()
 })
 
+// ============================================================================
+// Incremental Data Tests (appendData/endOfData, non-standard)
+// ============================================================================
+
+/** Split a JPEG into the headers, the first scan and the remaining scans */
+function sliceJpeg(data: Buffer): Buffer[] {
+  const firstScan = data.indexOf(Buffer.from([0xff, 0xda]))
+  const secondScan = data.indexOf(Buffer.from([0xff, 0xda]), firstScan + 2)
+  const middleEnd = secondScan > 0 ? secondScan : Math.floor((firstScan + data.length) / 2)
+  return [data.subarray(0, firstScan), data.subarray(firstScan, middleEnd), data.subarray(middleEnd)]
+}
+
+test('ImageDecoder appendData resolves tracks after the header and decodes after endOfData', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.jpg'))
+  const [header, middle, rest] = sliceJpeg(data)
+
+  const reference = new ImageDecoder({ data, type: 'image/jpeg' })
+  const expected = (await reference.decode()).image
+  reference.close()
+
+  const decoder = new ImageDecoder({ type: 'image/jpeg' })
+  decoder.appendData(header)
+  await decoder.tracks.ready
+  t.false(decoder.complete)
+  const track = decoder.tracks.selectedTrack!
+  t.false(track.animated)
+  t.is(track.frameCount, 1)
+
+  decoder.appendData(middle)
+  decoder.appendData(rest)
+  decoder.endOfData()
+  await decoder.completed
+  t.true(decoder.complete)
+
+  const result = await decoder.decode()
+  t.true(result.complete)
+  t.is(result.image.codedWidth, expected.codedWidth)
+  t.is(result.image.codedHeight, expected.codedHeight)
+
+  result.image.close()
+  expected.close()
+  decoder.close()
+})
+
+test('ImageDecoder decode() waits for endOfData by default', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.jpg'))
+  const [header, middle, rest] = sliceJpeg(data)
+
+  const decoder = new ImageDecoder({ type: 'image/jpeg' })
+  decoder.appendData(header)
+  const pending = decoder.decode()
+  decoder.appendData(middle)
+  decoder.appendData(rest)
+  decoder.endOfData()
+
+  const result = await pending
+  t.true(result.complete)
+  result.image.close()
+  decoder.close()
+})
+
+test('ImageDecoder appendData throws after endOfData or with init data', (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.jpg'))
+
+  const appended = new ImageDecoder({ type: 'image/jpeg' })
+  appended.appendData(data)
+  appended.endOfData()
+  t.notThrows(() => appended.endOfData())
+  t.throws(() => appended.appendData(data), { name: 'InvalidStateError' })
+  appended.close()
+
+  const buffered = new ImageDecoder({ data, type: 'image/jpeg' })
+  t.throws(() => buffered.appendData(data), { name: 'InvalidStateError' })
+  buffered.close()
+})
+
 // ============================================================================
 // ImageDecoder Options Tests (W3C spec)
 // ============================================================================
@@ -442,3 +559,170 @@ test('ImageDecoder invalid colorSpaceConversion throws TypeError', (t) => {
     { message: /Invalid colorSpaceConversion value/ },
   )
 })
+
+// ============================================================================
+// preferredFormat (non-standard)
+// ============================================================================
+
+/** Decode a JPEG `count` times and encode the frames as one VP8 stream */
+async function encodeTimelapse(data: Buffer, count: number, options: ImageDecodeOptions) {
+  const chunks: Uint8Array[] = []
+  const errors: Error[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk) => {
+      const bytes = new Uint8Array(chunk.byteLength)
+      chunk.copyTo(bytes)
+      chunks.push(bytes)
+    },
+    error: (e) => errors.push(e),
+  })
+
+  const start = performance.now()
+  for (let i = 0; i < count; i++) {
+    const decoder = new ImageDecoder({ data, type: 'image/jpeg' })
+    const { image } = await decoder.decode(options)
+    if (i === 0) {
+      encoder.configure({
+        codec: 'vp8',
+        width: image.codedWidth,
+        height: image.codedHeight,
+        bitrate: 500_000,
+        hardwareAcceleration: 'prefer-software',
+      })
+    }
+    const frame = new VideoFrame(image, { timestamp: i * 33_333 })
+    encoder.encode(frame, { keyFrame: i % 30 === 0 })
+    frame.close()
+    image.close()
+    decoder.close()
+  }
+  await encoder.flush()
+  const elapsed = performance.now() - start
+  encoder.close()
+
+  if (errors.length > 0) {
+    throw errors[0]
+  }
+  return { chunks, elapsed }
+}
+
+test('ImageDecoder preferredFormat returns I420 frames', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.png'))
+  const decoder = new ImageDecoder({ data, type: 'image/png' })
+
+  const original = (await decoder.decode()).image
+  const converted = (await decoder.decode({ preferredFormat: 'I420' })).image
+  t.not(original.format, 'I420')
+  t.is(converted.format, 'I420')
+  t.is(converted.codedWidth, original.codedWidth)
+  t.is(converted.codedHeight, original.codedHeight)
+  t.is(converted.timestamp, original.timestamp)
+
+  original.close()
+  converted.close()
+  decoder.close()
+})
+
+test('ImageDecoder preferredFormat timelapse encodes identically without per-frame encoder conversion', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.jpg'))
+  const frameCount = 200
+
+  const baseline = await encodeTimelapse(data, frameCount, {})
+  const optimized = await encodeTimelapse(data, frameCount, { preferredFormat: 'I420' })
+
+  t.is(optimized.chunks.length, baseline.chunks.length)
+  for (let i = 0; i < baseline.chunks.length; i++) {
+    t.deepEqual(optimized.chunks[i], baseline.chunks[i], `chunk ${i} differs`)
+  }
+  t.log(
+    `${frameCount} frames: ${baseline.elapsed.toFixed(0)} ms converting in the encoder, ` +
+      `${optimized.elapsed.toFixed(0)} ms with preferredFormat`,
+  )
+})
+
+// ============================================================================
+// decodeAll (non-standard)
+// ============================================================================
+
+test('ImageDecoder.decodeAll decodes mixed formats in order with per-image errors', async (t) => {
+  const names = ['four-colors.jpg', 'four-colors.png', 'four-colors.gif', 'four-colors.webp']
+  const buffers = names.map((name) => readFileSync(join(__dirname, 'fixtures/wpt', name)))
+  const corrupt = new Uint8Array([0xff, 0xd8, 0xff, 0x00, 0x01, 0x02])
+
+  const results = await ImageDecoder.decodeAll([buffers[0], corrupt, ...buffers.slice(1)], { concurrency: 2 })
+
+  t.is(results.length, 5)
+  t.is(results[1].image, null)
+  t.truthy(results[1].error)
+  for (const [i, result] of [results[0], ...results.slice(2)].entries()) {
+    t.is(result.error, null, names[i])
+    const image = result.image!
+    const decoder = new ImageDecoder({ data: buffers[i], type: `image/${names[i].split('.')[1]}` })
+    const reference = await decoder.decode()
+    t.is(image.codedWidth, reference.image.codedWidth, names[i])
+    t.is(image.codedHeight, reference.image.codedHeight, names[i])
+    image.close()
+    reference.image.close()
+    decoder.close()
+  }
+})
+
+test('ImageDecoder.decodeAll applies desired size and preferredFormat', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.jpg'))
+  const results = await ImageDecoder.decodeAll([data, data, data], {
+    type: 'image/jpeg',
+    desiredWidth: 16,
+    desiredHeight: 12,
+    preferredFormat: 'I420',
+  })
+
+  for (const result of results) {
+    const image = result.image!
+    t.is(image.codedWidth, 16)
+    t.is(image.codedHeight, 12)
+    t.is(image.format, 'I420')
+    image.close()
+  }
+})
+
+test('ImageDecoder.decodeAll rejects invalid options', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.jpg'))
+  await t.throwsAsync(() => ImageDecoder.decodeAll([data], { concurrency: 0 }), {
+    message: /concurrency must be greater than 0/,
+  })
+  await t.throwsAsync(() => ImageDecoder.decodeAll([data], { desiredWidth: 16 }), {
+    message: /desiredWidth and desiredHeight/,
+  })
+  await t.throwsAsync(() => ImageDecoder.decodeAll([data], { type: 'image/x-unknown' }), {
+    message: /Unsupported image type/,
+  })
+  t.deepEqual(await ImageDecoder.decodeAll([]), [])
+})
+
+test('ImageDecoder.decodeAll: 500 thumbnails vs one ImageDecoder each', async (t) => {
+  const data = readFileSync(join(__dirname, 'fixtures/wpt/four-colors.jpg'))
+  const count = 500
+
+  let start = performance.now()
+  for (let i = 0; i < count; i++) {
+    const decoder = new ImageDecoder({ data, type: 'image/jpeg' })
+    const { image } = await decoder.decode()
+    image.close()
+    decoder.close()
+  }
+  const individual = performance.now() - start
+
+  start = performance.now()
+  const results = await ImageDecoder.decodeAll(Array.from({ length: count }, () => data), { type: 'image/jpeg' })
+  const batch = performance.now() - start
+
+  t.is(results.length, count)
+  for (const result of results) {
+    t.is(result.error, null)
+    result.image!.close()
+  }
+  t.log(
+    `${count} images: ${((individual * 1000) / count).toFixed(0)} µs each with separate decoders, ` +
+      `${((batch * 1000) / count).toFixed(0)} µs each with decodeAll`,
+  )
+})
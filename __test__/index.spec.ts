@@ -17,6 +17,10 @@ import {
   getAvailableHardwareAccelerators,
   getPreferredHardwareAccelerator,
   isHardwareAcceleratorAvailable,
+  // Build info
+  getBuildInfo,
+  getCodecCapabilities,
+  getFeatures,
 } from '../index.js'
 
 // ============================================================================
@@ -122,3 +126,112 @@ test('exports isHardwareAcceleratorAvailable function', (t) => {
   const result = isHardwareAcceleratorAvailable('videotoolbox')
   t.is(typeof result, 'boolean')
 })
+
+// ============================================================================
+// Build Info Tests
+// ============================================================================
+
+test('getBuildInfo reports FFmpeg version and configuration', (t) => {
+  const info = getBuildInfo()
+  t.true(info.ffmpegVersion.length > 0)
+  t.true(info.ffmpegConfiguration.includes('--enable-libx264'))
+
+  const libraryNames = info.libraries.map((lib) => lib.name)
+  for (const name of ['libavutil', 'libavcodec', 'libavformat', 'libswscale', 'libswresample']) {
+    t.true(libraryNames.includes(name), `missing ${name}`)
+  }
+  for (const lib of info.libraries) {
+    t.true(lib.version.length > 0, `${lib.name} has no version`)
+  }
+})
+
+test('getBuildInfo lists the codecs of our static build', (t) => {
+  const info = getBuildInfo()
+  const av1Encoder = process.platform === 'win32' && process.arch === 'x64' ? 'librav1e' : 'libaom-av1'
+  for (const encoder of ['libx264', 'libx265', 'libvpx', 'libvpx-vp9', av1Encoder]) {
+    t.true(info.encoders.includes(encoder), `missing encoder ${encoder}`)
+  }
+  for (const decoder of ['h264', 'hevc', 'vp8', 'vp9']) {
+    t.true(info.decoders.includes(decoder), `missing decoder ${decoder}`)
+  }
+})
+
+test('getBuildInfo reports runtime CPU features', (t) => {
+  const info = getBuildInfo()
+  t.true(Array.isArray(info.cpuFlags))
+  t.true(Array.isArray(info.hardwareDeviceTypes))
+  if (process.arch === 'x64') {
+    // Every x86-64 CPU supports SSE2
+    t.true(info.cpuFlags.includes('sse2'))
+  } else if (process.arch === 'arm64') {
+    t.true(info.cpuFlags.includes('neon'))
+  }
+})
+
+// ============================================================================
+// Feature Detection Tests
+// ============================================================================
+
+test('getFeatures reports every codec and container of our static build', (t) => {
+  const { hardwareAccelerators, ...flags } = getFeatures()
+  t.deepEqual(Object.keys(flags).sort(), [
+    'aacDecode',
+    'aacEncode',
+    'av1Decode',
+    'av1Encode',
+    'flacDecode',
+    'flacEncode',
+    'h264Decode',
+    'h264Encode',
+    'hevcDecode',
+    'hevcEncode',
+    'mkvDemux',
+    'mkvMux',
+    'mp3Decode',
+    'mp3Encode',
+    'mp4Demux',
+    'mp4Mux',
+    'opusDecode',
+    'opusEncode',
+    'vorbisDecode',
+    'vorbisEncode',
+    'vp8Decode',
+    'vp8Encode',
+    'vp9Decode',
+    'vp9Encode',
+    'webmDemux',
+    'webmMux',
+  ])
+  for (const [name, value] of Object.entries(flags)) {
+    t.true(value, `${name} should be available`)
+  }
+  t.deepEqual([...hardwareAccelerators], getBuildInfo().hardwareDeviceTypes)
+})
+
+test('getFeatures returns a frozen object', (t) => {
+  const features = getFeatures()
+  t.true(Object.isFrozen(features))
+  t.true(Object.isFrozen(features.hardwareAccelerators))
+  t.throws(() => {
+    ;(features as { h264Encode: boolean }).h264Encode = false
+  })
+  t.true(features.h264Encode)
+})
+
+test('getFeatures agrees with getCodecCapabilities', (t) => {
+  const features = getFeatures()
+  const codecs = {
+    h264Encode: 'avc1.42001f',
+    hevcEncode: 'hev1.1.6.L93.B0',
+    vp8Encode: 'vp8',
+    vp9Encode: 'vp09.00.10.08',
+    av1Encode: 'av01.0.04M.08',
+  } as const
+  for (const [flag, codec] of Object.entries(codecs)) {
+    if (features[flag as keyof typeof codecs]) {
+      t.notThrows(() => getCodecCapabilities(codec), codec)
+    } else {
+      t.throws(() => getCodecCapabilities(codec), { message: /NotSupportedError/ }, codec)
+    }
+  }
+})
@@ -375,6 +375,33 @@ test('throughput: H.264 640x480 FPS measurement', async (t) => {
   encoder.close()
 })
 
+test('throughput: 50 sequential isConfigSupported() calls', async (t) => {
+  const codecs = ['h264', 'vp8', 'vp9', 'av1', 'h265'] as const
+  const configs = Array.from({ length: 50 }, (_, i) => ({
+    ...createEncoderConfig(codecs[i % codecs.length], 320 + 16 * i, 240),
+    hardwareAcceleration: i % 2 === 0 ? ('prefer-hardware' as const) : ('no-preference' as const),
+  }))
+
+  const round = async () => {
+    const startTime = performance.now()
+    const results: boolean[] = []
+    for (const config of configs) {
+      results.push((await VideoEncoder.isConfigSupported(config)).supported)
+    }
+    return { elapsed: performance.now() - startTime, results }
+  }
+
+  // Start from an empty probe cache so the first round opens every encoder
+  resetHardwareFallbackState()
+  const cold = await round()
+  const warm = await round()
+
+  // Timings depend on the machine and its load, so they are only logged; the
+  // probe cache itself is covered by the encoder_probe unit tests
+  t.log(`cold round ${cold.elapsed.toFixed(1)}ms, cached round ${warm.elapsed.toFixed(1)}ms`)
+  t.deepEqual(warm.results, cold.results)
+})
+
 // ============================================================================
 // Queue Size Tests
 // ============================================================================
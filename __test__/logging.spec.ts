@@ -0,0 +1,123 @@
+/**
+ * Logging Tests
+ *
+ * Tests for runtime log level changes and the global log callback.
+ */
+
+import test from 'ava'
+
+import { getLogLevel, setLogCallback, setLogLevel, VideoEncoder, type LogMessage } from '../index.js'
+
+import { generateSolidColorI420Frame, TestColors } from './helpers/index.js'
+
+/** Configure an encoder with an option no codec defines, which logs a `webcodecs` warning */
+function configureWithUnknownOption() {
+  const encoder = new VideoEncoder({
+    output: () => {},
+    error: () => {},
+  })
+  encoder.configure({
+    codec: 'vp8',
+    width: 64,
+    height: 48,
+    hardwareAcceleration: 'prefer-software',
+    ffmpegOptions: { 'no-such-option': 1 },
+  })
+  encoder.close()
+}
+
+/** Encode a few H.264 frames, which makes libx264 log at info level */
+async function encodeH264() {
+  const encoder = new VideoEncoder({
+    output: () => {},
+    error: () => {},
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 64,
+    height: 48,
+    hardwareAcceleration: 'prefer-software',
+  })
+  for (let i = 0; i < 3; i++) {
+    const frame = generateSolidColorI420Frame(64, 48, TestColors.red, i * 33_333)
+    encoder.encode(frame)
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+}
+
+/** Let queued log callbacks run */
+const settle = () => new Promise((resolve) => setTimeout(resolve, 100))
+
+test.serial('setLogLevel: messages start and stop arriving at the log callback', async (t) => {
+  const messages: LogMessage[] = []
+  setLogCallback((message) => messages.push(message))
+  t.teardown(() => {
+    setLogCallback(null)
+    setLogLevel('webcodecs', 'off')
+  })
+
+  setLogLevel('webcodecs', 'off')
+  configureWithUnknownOption()
+  await settle()
+  t.is(messages.filter((m) => m.target === 'webcodecs').length, 0)
+
+  setLogLevel('webcodecs', 'warn')
+  t.is(getLogLevel('webcodecs'), 'warn')
+  configureWithUnknownOption()
+  await settle()
+  const warning = messages.find((m) => m.target === 'webcodecs' && m.message.includes('Skipping FFmpeg option'))
+  t.truthy(warning)
+  t.is(warning!.level, 'warn')
+  t.true(warning!.message.includes('no-such-option'))
+
+  setLogLevel('webcodecs', 'error')
+  const count = messages.length
+  configureWithUnknownOption()
+  await settle()
+  t.is(messages.length, count)
+})
+
+test.serial('setLogLevel: FFmpeg messages follow the ffmpeg target', async (t) => {
+  const messages: LogMessage[] = []
+  setLogCallback((message) => messages.push(message))
+  t.teardown(() => {
+    setLogCallback(null)
+    setLogLevel('ffmpeg', 'off')
+  })
+
+  setLogLevel('ffmpeg', 'info')
+  await encodeH264()
+  await settle()
+  t.true(messages.some((m) => m.target === 'ffmpeg'), 'expected libx264 info messages')
+
+  setLogLevel('ffmpeg', 'off')
+  t.is(getLogLevel('ffmpeg'), 'off')
+  messages.length = 0
+  await encodeH264()
+  await settle()
+  t.is(messages.filter((m) => m.target === 'ffmpeg').length, 0)
+})
+
+test.serial('setLogLevel: "*" sets the default level', (t) => {
+  t.teardown(() => setLogLevel('*', 'off'))
+  setLogLevel('*', 'debug')
+  t.is(getLogLevel(), 'debug')
+  t.is(getLogLevel('some-other-target'), 'debug')
+  setLogLevel('webcodecs', 'error')
+  t.is(getLogLevel('webcodecs'), 'error')
+  t.throws(() => setLogLevel('', 'info'), { instanceOf: TypeError, message: /target/ })
+})
+
+test.serial('setLogCallback: null stops delivery', async (t) => {
+  const messages: LogMessage[] = []
+  setLogCallback((message) => messages.push(message))
+  setLogLevel('webcodecs', 'warn')
+  t.teardown(() => setLogLevel('webcodecs', 'off'))
+
+  setLogCallback(null)
+  configureWithUnknownOption()
+  await settle()
+  t.is(messages.length, 0)
+})
@@ -4,12 +4,23 @@
  * Tests for Mp4Muxer, WebMMuxer, and MkvMuxer classes.
  */
 
-import test from 'ava'
+import * as fs from 'node:fs/promises'
+import * as os from 'node:os'
+import * as path from 'node:path'
+
+import test, { type ExecutionContext } from 'ava'
 
 import {
+  EncodedVideoChunk as EncodedVideoChunkClass,
+  EncodedAudioChunk as EncodedAudioChunkClass,
   Mp4Muxer,
+  SegmentedMp4Muxer,
   WebMMuxer,
   MkvMuxer,
+  Mp4Demuxer,
+  MkvDemuxer,
+  WebMDemuxer,
+  VideoDecoder,
   VideoEncoder,
   AudioEncoder,
   resetHardwareFallbackState,
@@ -17,6 +28,11 @@ import {
   type EncodedAudioChunk,
   type EncodedVideoChunkMetadata,
   type EncodedAudioChunkMetadata,
+  type Mp4Segment,
+  type RawPacket,
+  type DemuxerTrackInfo,
+  type TrackSideData,
+  type SubtitleCue,
 } from '../index.js'
 import { generateSolidColorI420Frame, generateSilence, TestColors } from './helpers/index.js'
 
@@ -975,3 +991,1010 @@ test('Mp4Muxer: muxes both video and audio', async (t) => {
   // Solid color video and silence audio compress very well, so the output is smaller than expected
   t.true(mp4Data.length > 1000, 'MP4 with audio+video should have minimum size')
 })
+
+// ============================================================================
+// Bitrate Summary Tests
+// ============================================================================
+
+test('WebMMuxer: bitrateSummary reports average and 1-second peak bitrate', (t) => {
+  const muxer = new WebMMuxer()
+  muxer.addVideoTrack({ codec: 'vp8', width: 320, height: 240 })
+
+  // 2 seconds at 30fps: 1000-byte chunks with a burst of ten 5000-byte chunks
+  const frameDuration = 33_333
+  for (let i = 0; i < 60; i++) {
+    muxer.addVideoChunk(
+      new EncodedVideoChunkClass({
+        type: i === 0 ? 'key' : 'delta',
+        timestamp: i * frameDuration,
+        duration: frameDuration,
+        data: new Uint8Array(i >= 30 && i < 40 ? 5000 : 1000),
+      }),
+    )
+  }
+  muxer.finalize()
+
+  const { video, audio } = muxer.bitrateSummary()
+  muxer.close()
+
+  t.is(audio, undefined)
+  t.truthy(video)
+  t.is(video!.bytes, 100_000)
+  t.is(video!.durationUs, 60 * frameDuration)
+  t.is(Math.round(video!.avgBitrate), Math.round((100_000 * 8 * 1_000_000) / (60 * frameDuration)))
+  // A 1-second window spans 31 chunk timestamps: the ten large chunks plus 21 small ones
+  t.is(video!.peakBitrate1s, (10 * 5000 + 21 * 1000) * 8)
+})
+
+// ============================================================================
+// Description Validation Tests
+// ============================================================================
+
+test('Mp4Muxer: addVideoTrack rejects Annex B description', (t) => {
+  const muxer = new Mp4Muxer()
+
+  const error = t.throws(
+    () =>
+      muxer.addVideoTrack({
+        codec: 'avc1.42001E',
+        width: 320,
+        height: 240,
+        description: new Uint8Array([0x00, 0x00, 0x00, 0x01, 0x67, 0x42, 0xc0, 0x1e]),
+      }),
+    { instanceOf: TypeError },
+  )
+  t.regex(error!.message, /Annex B/)
+
+  muxer.close()
+})
+
+test('MkvMuxer: addVideoTrack rejects truncated avcC description', (t) => {
+  const muxer = new MkvMuxer()
+
+  // avcC header declaring one SPS of 4 bytes, but only 2 bytes follow
+  const error = t.throws(
+    () =>
+      muxer.addVideoTrack({
+        codec: 'avc1.42001E',
+        width: 320,
+        height: 240,
+        description: new Uint8Array([0x01, 0x42, 0xc0, 0x1e, 0xff, 0xe1, 0x00, 0x04, 0x67, 0x42]),
+      }),
+    { instanceOf: TypeError },
+  )
+  t.regex(error!.message, /avcC description is truncated.*SPS/)
+
+  muxer.close()
+})
+
+test('Mp4Muxer: addVideoTrack rejects avcC shorter than its header', (t) => {
+  const muxer = new Mp4Muxer()
+
+  const error = t.throws(
+    () =>
+      muxer.addVideoTrack({
+        codec: 'avc1.42001E',
+        width: 320,
+        height: 240,
+        description: new Uint8Array([0x01, 0x42, 0xc0]),
+      }),
+    { instanceOf: TypeError },
+  )
+  t.regex(error!.message, /avcC description is truncated: 3 bytes/)
+
+  muxer.close()
+})
+
+test('Mp4Muxer: updateVideoDescription before first chunk', async (t) => {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: (e) => t.fail(`Encoder error: ${e.message}`),
+  })
+
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    bitrate: 1_000_000,
+  })
+
+  for (let i = 0; i < 10; i++) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.blue, i * 33333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+
+  await encoder.flush()
+  encoder.close()
+
+  const description = videoMetadatas[0]?.decoderConfig?.description
+  t.truthy(description, 'First keyframe should carry a description')
+
+  // Track added before the encoder produced its description
+  const muxer = new Mp4Muxer()
+  muxer.addVideoTrack({ codec: 'avc1.42001E', width: 320, height: 240 })
+
+  t.throws(() => muxer.updateVideoDescription(new Uint8Array([0x00, 0x00, 0x01, 0x67])), {
+    instanceOf: TypeError,
+    message: /Annex B/,
+  })
+  muxer.updateVideoDescription(description!)
+
+  for (const chunk of videoChunks) {
+    muxer.addVideoChunk(chunk)
+  }
+
+  t.throws(() => muxer.updateVideoDescription(description!), {
+    message: /after muxing has started/,
+  })
+
+  const mp4Data = muxer.finalize()
+  muxer.close()
+
+  t.true(mp4Data.length > 1000, 'MP4 should have reasonable size')
+})
+
+// ============================================================================
+// Display Size Tests
+// ============================================================================
+
+/** Offset of the first `type` box payload at or after `from`, or -1 */
+function findBox(data: Uint8Array, type: string, from = 0): number {
+  const bytes = new TextEncoder().encode(type)
+  for (let i = from; i + 4 <= data.length; i++) {
+    if (bytes.every((byte, j) => data[i + j] === byte)) {
+      return i + 4
+    }
+  }
+  return -1
+}
+
+test('Mp4Muxer: display size is written to tkhd while stsd keeps the coded size', async (t) => {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: (e) => t.fail(`Encoder error: ${e.message}`),
+  })
+
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 640,
+    height: 480,
+    displayWidth: 854,
+    displayHeight: 480,
+    bitrate: 1_000_000,
+  })
+
+  for (let i = 0; i < 10; i++) {
+    const frame = generateSolidColorI420Frame(640, 480, TestColors.green, i * 33333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+
+  await encoder.flush()
+  encoder.close()
+
+  t.is(videoMetadatas[0]?.decoderConfig?.displayAspectWidth, 854)
+
+  const muxer = new Mp4Muxer()
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 640,
+    height: 480,
+    displayWidth: 854,
+    displayHeight: 480,
+    description: videoMetadatas[0]?.decoderConfig?.description,
+  })
+  for (let i = 0; i < videoChunks.length; i++) {
+    muxer.addVideoChunk(videoChunks[i], videoMetadatas[i])
+  }
+  const mp4Data = muxer.finalize()
+  muxer.close()
+
+  const view = new DataView(mp4Data.buffer, mp4Data.byteOffset, mp4Data.byteLength)
+
+  // tkhd width/height are 16.16 fixed point after the matrix
+  const tkhd = findBox(mp4Data, 'tkhd', findBox(mp4Data, 'moov'))
+  t.true(tkhd > 0, 'Should have tkhd box')
+  const tkhdSize = mp4Data[tkhd] === 1 ? 88 : 76
+  t.is(Math.round(view.getUint32(tkhd + tkhdSize) / 65536), 854)
+  t.is(Math.round(view.getUint32(tkhd + tkhdSize + 4) / 65536), 480)
+
+  // Visual sample entry width/height are 24 bytes into the avc1 entry
+  const avc1 = findBox(mp4Data, 'avc1', findBox(mp4Data, 'stsd'))
+  t.true(avc1 > 0, 'Should have avc1 sample entry')
+  t.is(view.getUint16(avc1 + 24), 640)
+  t.is(view.getUint16(avc1 + 26), 480)
+})
+
+test('Mp4Muxer: addVideoTrack requires displayWidth and displayHeight together', (t) => {
+  const muxer = new Mp4Muxer()
+
+  t.throws(() => muxer.addVideoTrack({ codec: 'avc1.42001E', width: 640, height: 480, displayWidth: 854 }), {
+    instanceOf: TypeError,
+    message: /must be specified together/,
+  })
+
+  muxer.close()
+})
+
+// ============================================================================
+// Encoder Identification Tests
+// ============================================================================
+
+/** Encode 10 H.264 frames and mux them into an MP4 or MKV file */
+async function muxH264(
+  MuxerClass: typeof Mp4Muxer | typeof MkvMuxer,
+  omitEncoderTags: boolean,
+  encoderInfo?: string,
+): Promise<Uint8Array> {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    bitrate: 500_000,
+    hardwareAcceleration: 'prefer-software',
+    encoderInfo,
+  })
+
+  for (let i = 0; i < 10; i++) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.blue, i * 33333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const muxer = new MuxerClass({ omitEncoderTags })
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    description: videoMetadatas[0]?.decoderConfig?.description,
+  })
+  for (let i = 0; i < videoChunks.length; i++) {
+    muxer.addVideoChunk(videoChunks[i], videoMetadatas[i])
+  }
+  const data = muxer.finalize()
+  muxer.close()
+  return data
+}
+
+function containsText(data: Uint8Array, text: string | RegExp): boolean {
+  const latin1 = Buffer.from(data.buffer, data.byteOffset, data.byteLength).toString('latin1')
+  return typeof text === 'string' ? latin1.includes(text) : text.test(latin1)
+}
+
+test('Mp4Muxer: default output keeps the Lavf tag and x264 info SEI', async (t) => {
+  const mp4Data = await muxH264(Mp4Muxer, false)
+
+  t.true(containsText(mp4Data, 'Lavf'))
+  t.true(containsText(mp4Data, 'x264'))
+})
+
+test('Mp4Muxer: omitEncoderTags with empty encoderInfo removes encoder identification', async (t) => {
+  const mp4Data = await muxH264(Mp4Muxer, true, '')
+
+  t.false(containsText(mp4Data, 'Lavf'))
+  t.false(containsText(mp4Data, 'x264'))
+})
+
+test('MkvMuxer: omitEncoderTags drops the versioned Lavf identifiers and x264 info SEI', async (t) => {
+  const mkvData = await muxH264(MkvMuxer, true, '')
+
+  // Matroska requires a MuxingApp element; bitexact mode writes the bare "Lavf"
+  t.false(containsText(mkvData, /Lavf\d/))
+  t.false(containsText(mkvData, 'x264'))
+})
+
+test('VideoEncoder: encoderInfo replaces the x264 info SEI text', async (t) => {
+  const mp4Data = await muxH264(Mp4Muxer, true, 'my-app 1.0')
+
+  t.true(containsText(mp4Data, 'my-app 1.0'))
+  t.false(containsText(mp4Data, 'x264'))
+})
+
+test('Mp4Muxer/MkvMuxer: suppressed encoder identification gives byte-identical runs', async (t) => {
+  for (const MuxerClass of [Mp4Muxer, MkvMuxer]) {
+    const first = await muxH264(MuxerClass, true, '')
+    const second = await muxH264(MuxerClass, true, '')
+    t.deepEqual(Buffer.from(first), Buffer.from(second), `${MuxerClass.name} output should be reproducible`)
+  }
+})
+
+// ============================================================================
+// Track Timescale Tests
+// ============================================================================
+
+/** One hour of 29.97fps video */
+const NTSC_FRAMES_PER_HOUR = 107_892
+
+/** avcC description for a 320x240 H.264 track */
+async function h264Description(): Promise<Uint8Array> {
+  let description: Uint8Array | undefined
+  const encoder = new VideoEncoder({
+    output: (_chunk, metadata) => {
+      description ??= metadata?.decoderConfig?.description as Uint8Array | undefined
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({ codec: 'avc1.42001E', width: 320, height: 240, hardwareAcceleration: 'prefer-software' })
+  const frame = generateSolidColorI420Frame(320, 240, TestColors.blue, 0)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+  encoder.close()
+  return description!
+}
+
+/** Mux one hour of 29.97fps chunks and return the last packet read back */
+async function muxNtscHour(
+  MuxerClass: typeof Mp4Muxer | typeof MkvMuxer,
+  timescale?: number,
+): Promise<RawPacket> {
+  const muxer = new MuxerClass()
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    framerate: 29.97,
+    description: await h264Description(),
+    ...(timescale === undefined ? {} : { timescale }),
+  })
+  const data = new Uint8Array(4)
+  for (let i = 0; i < NTSC_FRAMES_PER_HOUR; i++) {
+    muxer.addVideoChunk(
+      new EncodedVideoChunkClass({
+        type: i === 0 ? 'key' : 'delta',
+        timestamp: Math.round((i * 1_001_000) / 30),
+        data,
+      }),
+    )
+  }
+  const output = muxer.finalize()
+  muxer.close()
+
+  const DemuxerClass = MuxerClass === Mp4Muxer ? Mp4Demuxer : MkvDemuxer
+  const demuxer = new DemuxerClass({
+    error: (e: Error) => {
+      throw e
+    },
+  })
+  await demuxer.loadBuffer(output)
+  let last: RawPacket | null = null
+  let packet: RawPacket | null
+  while ((packet = await demuxer.readPacket()) !== null) last = packet
+  demuxer.close()
+  return last!
+}
+
+/** Difference in ms between the packet's PTS and the exact time of the last 29.97fps frame */
+function ntscDriftMs(packet: RawPacket): number {
+  const ptsMs = (packet.pts! * packet.timeBaseNum * 1000) / packet.timeBaseDen
+  const expectedMs = ((NTSC_FRAMES_PER_HOUR - 1) * 1001) / 30
+  return Math.abs(ptsMs - expectedMs)
+}
+
+test('Mp4Muxer: default 29.97fps timescale keeps an hour of timestamps drift-free', async (t) => {
+  const last = await muxNtscHour(Mp4Muxer)
+
+  t.is(last.timeBaseDen / last.timeBaseNum, 30000)
+  t.is(last.duration, 1001)
+  t.true(ntscDriftMs(last) < 1, `drift ${ntscDriftMs(last)}ms`)
+})
+
+test('Mp4Muxer: explicit timescale is used for the video track', async (t) => {
+  const last = await muxNtscHour(Mp4Muxer, 90000)
+
+  t.is(last.timeBaseDen / last.timeBaseNum, 90000)
+  t.is(last.duration, 3003)
+  t.true(ntscDriftMs(last) < 1, `drift ${ntscDriftMs(last)}ms`)
+})
+
+test('MkvMuxer: millisecond timestamps round per frame without drifting over an hour', async (t) => {
+  const last = await muxNtscHour(MkvMuxer)
+
+  t.true(ntscDriftMs(last) < 1, `drift ${ntscDriftMs(last)}ms`)
+})
+
+/** Frame count for the variable frame rate transcode */
+const VFR_FRAME_COUNT = 10_000
+
+/** Exact VFR timestamps in µs, cycling through 23.976, 29.97 and 59.94fps frame periods */
+function vfrTimestamps(): number[] {
+  // Periods in 1/120000s ticks: 1001/24000, 1001/30000 and 1001/60000 seconds
+  const periods = [5005, 4004, 2002]
+  const timestamps: number[] = []
+  let ticks = 0
+  for (let i = 0; i < VFR_FRAME_COUNT; i++) {
+    timestamps.push(Math.round((ticks * 1_000_000) / 120_000))
+    ticks += periods[i % periods.length]
+  }
+  return timestamps
+}
+
+/** Mux H.264 encoder output into an MP4 */
+function muxEncoded(chunks: EncodedVideoChunk[], metadatas: (EncodedVideoChunkMetadata | undefined)[]): Uint8Array {
+  const muxer = new Mp4Muxer()
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 64,
+    height: 64,
+    description: metadatas.find((m) => m?.decoderConfig?.description)?.decoderConfig?.description,
+  })
+  chunks.forEach((chunk, i) => muxer.addVideoChunk(chunk, metadatas[i]))
+  const output = muxer.finalize()
+  muxer.close()
+  return output
+}
+
+/** Create a realtime H.264 encoder collecting its chunks */
+function vfrEncoder(chunks: EncodedVideoChunk[], metadatas: (EncodedVideoChunkMetadata | undefined)[]) {
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      chunks.push(chunk)
+      metadatas.push(metadata)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 64,
+    height: 64,
+    bitrate: 100_000,
+    framerate: 29.97,
+    latencyMode: 'realtime',
+    hardwareAcceleration: 'prefer-software',
+  })
+  return encoder
+}
+
+test('VideoEncoder: VFR transcode keeps timestamps within 1ms after 10,000 frames', async (t) => {
+  t.timeout(120_000)
+  const expected = vfrTimestamps()
+
+  // Source fixture: VFR frames encoded and muxed to MP4
+  const sourceChunks: EncodedVideoChunk[] = []
+  const sourceMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+  const sourceEncoder = vfrEncoder(sourceChunks, sourceMetadatas)
+  for (const timestamp of expected) {
+    const frame = generateSolidColorI420Frame(64, 64, TestColors.green, timestamp)
+    sourceEncoder.encode(frame)
+    frame.close()
+  }
+  await sourceEncoder.flush()
+  sourceEncoder.close()
+  const source = muxEncoded(sourceChunks, sourceMetadatas)
+
+  // Transcode: demux -> decode -> encode -> mux
+  const chunks: EncodedVideoChunk[] = []
+  const metadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+  const encoder = vfrEncoder(chunks, metadatas)
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      encoder.encode(frame)
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(source)
+  decoder.configure(demuxer.videoDecoderConfig!)
+  for await (const chunk of demuxer) {
+    if (chunk.videoChunk) decoder.decode(chunk.videoChunk)
+  }
+  demuxer.close()
+  await decoder.flush()
+  decoder.close()
+  await encoder.flush()
+  encoder.close()
+
+  t.deepEqual(chunks.map((c) => c.timestamp), expected)
+
+  // Read the transcoded MP4 back and compare presentation times
+  const output = new Mp4Demuxer({ error: (e: Error) => t.fail(e.message) })
+  await output.loadBuffer(muxEncoded(chunks, metadatas))
+  const ptsUs: number[] = []
+  let packet: RawPacket | null
+  while ((packet = await output.readPacket()) !== null) {
+    ptsUs.push((packet.pts! * packet.timeBaseNum * 1_000_000) / packet.timeBaseDen)
+  }
+  output.close()
+
+  t.is(ptsUs.length, VFR_FRAME_COUNT)
+  ptsUs.sort((a, b) => a - b)
+  const maxErrorUs = Math.max(...ptsUs.map((pts, i) => Math.abs(pts - expected[i])))
+  t.true(maxErrorUs < 1000, `max timestamp error ${maxErrorUs}µs`)
+})
+
+test('Mp4Muxer: addVideoTrack rejects a zero timescale', (t) => {
+  const muxer = new Mp4Muxer()
+  t.throws(() => muxer.addVideoTrack({ codec: 'avc1.42001E', width: 320, height: 240, timescale: 0 }), {
+    instanceOf: TypeError,
+    message: /timescale/,
+  })
+  muxer.close()
+})
+
+/** Mux a short H.264 track with the given side data and return the demuxed video track */
+async function sideDataRoundTrip(sideData: TrackSideData[]): Promise<DemuxerTrackInfo> {
+  const muxer = new Mp4Muxer()
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    description: await h264Description(),
+    sideData,
+  })
+  for (let i = 0; i < 5; i++) {
+    muxer.addVideoChunk(
+      new EncodedVideoChunkClass({ type: i === 0 ? 'key' : 'delta', timestamp: i * 33_333, data: new Uint8Array(4) }),
+    )
+  }
+  const output = muxer.finalize()
+  muxer.close()
+
+  const demuxer = new Mp4Demuxer({
+    error: (e: Error) => {
+      throw e
+    },
+  })
+  await demuxer.loadBuffer(output)
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'video')!
+  demuxer.close()
+  return track
+}
+
+test('Mp4Muxer: spherical side data survives an MP4 to MP4 remux', async (t) => {
+  // AVSphericalMapping: equirectangular, yaw 90 / pitch -10 / roll 5 (16.16), no bounds
+  const spherical = new Uint8Array(36)
+  const view = new DataView(spherical.buffer)
+  view.setInt32(0, 0, true)
+  view.setInt32(4, 90 * 65536, true)
+  view.setInt32(8, -10 * 65536, true)
+  view.setInt32(12, 5 * 65536, true)
+
+  const first = await sideDataRoundTrip([{ type: 'spherical', data: spherical }])
+  const firstSpherical = first.sideData.find((entry) => entry.type === 'spherical')
+  t.truthy(firstSpherical, 'demuxer should report the sv3d box')
+  t.deepEqual(Array.from(firstSpherical!.data.subarray(0, 16)), Array.from(spherical.subarray(0, 16)))
+
+  const second = await sideDataRoundTrip(first.sideData)
+  const secondSpherical = second.sideData.find((entry) => entry.type === 'spherical')
+  t.truthy(secondSpherical)
+  t.deepEqual(Array.from(secondSpherical!.data), Array.from(firstSpherical!.data))
+})
+
+test('Mp4Muxer: addVideoTrack rejects truncated side data', (t) => {
+  const muxer = new Mp4Muxer()
+  t.throws(() =>
+    muxer.addVideoTrack({
+      codec: 'avc1.42001E',
+      width: 320,
+      height: 240,
+      sideData: [{ type: 'spherical', data: new Uint8Array(4) }],
+    }),
+  )
+  muxer.close()
+})
+
+test('DemuxerTrackInfo: sideData is empty for plain tracks', async (t) => {
+  const track = await sideDataRoundTrip([])
+  t.deepEqual(track.sideData, [])
+})
+
+// ============================================================================
+// WebVTT Subtitle Tests
+// ============================================================================
+
+test('WebMMuxer: WebVTT cues with settings round-trip through WebMDemuxer', async (t) => {
+  const videoChunks: EncodedVideoChunk[] = []
+  const videoMetadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      videoChunks.push(chunk)
+      videoMetadatas.push(metadata)
+    },
+    error: (e) => t.fail(`Encoder error: ${e.message}`),
+  })
+  encoder.configure({ codec: 'vp8', width: 160, height: 120, bitrate: 200_000 })
+  for (let i = 0; i < 90; i++) {
+    const frame = generateSolidColorI420Frame(160, 120, TestColors.green, i * 33_333)
+    encoder.encode(frame, { keyFrame: i % 30 === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const cues: SubtitleCue[] = [
+    { timestamp: 0, duration: 1_000_000, text: 'Hello' },
+    {
+      timestamp: 1_000_000,
+      duration: 500_000,
+      text: '<v Narrator>Top left</v>\nsecond line',
+      settings: 'line:0 position:10% align:start',
+      identifier: 'cue-2',
+    },
+    { timestamp: 2_000_000, duration: 900_000, text: 'Bottom', settings: 'line:-1 size:50%' },
+  ]
+
+  const muxer = new WebMMuxer()
+  muxer.addVideoTrack({ codec: 'vp8', width: 160, height: 120 })
+  muxer.addSubtitleTrack({ kind: 'captions', language: 'eng' })
+  let cueIndex = 0
+  for (let i = 0; i < videoChunks.length; i++) {
+    while (cueIndex < cues.length && cues[cueIndex].timestamp <= videoChunks[i].timestamp) {
+      muxer.writeSubtitleChunk(cues[cueIndex++])
+    }
+    muxer.addVideoChunk(videoChunks[i], videoMetadatas[i])
+  }
+  const data = muxer.finalize()
+  muxer.close()
+
+  const demuxer = new WebMDemuxer({ error: (e: Error) => t.fail(e.message) })
+  await demuxer.loadBuffer(data)
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'subtitle')!
+  t.truthy(track, 'WebM should contain a subtitle track')
+  t.is(track.codec, 'webvtt')
+  t.is(track.subtitleKind, 'captions')
+  t.is(track.language, 'eng')
+
+  // Absent settings/identifier compare as undefined on both sides
+  const normalize = ({ timestamp, duration, text, settings, identifier }: SubtitleCue) => ({
+    timestamp,
+    duration,
+    text,
+    settings,
+    identifier,
+  })
+  const read = await demuxer.readSubtitleCues(track.index)
+  t.deepEqual(read.map(normalize), cues.map(normalize))
+
+  await t.throwsAsync(demuxer.readSubtitleCues(demuxer.tracks.find((tr) => tr.trackType === 'video')!.index), {
+    message: /not a WebVTT track/,
+  })
+  demuxer.close()
+})
+
+test('WebMMuxer: writeSubtitleChunk requires a subtitle track and a positive duration', (t) => {
+  const muxer = new WebMMuxer()
+  t.throws(() => muxer.writeSubtitleChunk({ timestamp: 0, duration: 1000, text: 'x' }), {
+    message: /No subtitle track/,
+  })
+  muxer.addSubtitleTrack()
+  t.throws(() => muxer.addSubtitleTrack(), { message: /already added/ })
+  t.throws(() => muxer.writeSubtitleChunk({ timestamp: 0, duration: 0, text: 'x' }), { message: /duration/ })
+  muxer.close()
+})
+
+// ============================================================================
+// SegmentedMp4Muxer Tests
+// ============================================================================
+
+const SEGMENT_FPS = 30
+
+/** Encode `frames` H.264 frames at 30fps with a key frame every second */
+async function encodeRecording(frames: number) {
+  const chunks: EncodedVideoChunk[] = []
+  const metadatas: (EncodedVideoChunkMetadata | undefined)[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      chunks.push(chunk)
+      metadatas.push(metadata)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    framerate: SEGMENT_FPS,
+    hardwareAcceleration: 'prefer-software',
+  })
+  const colors = [TestColors.red, TestColors.green, TestColors.blue]
+  for (let i = 0; i < frames; i++) {
+    const timestamp = Math.round((i * 1_000_000) / SEGMENT_FPS)
+    const frame = generateSolidColorI420Frame(320, 240, colors[i % colors.length], timestamp)
+    encoder.encode(frame, { keyFrame: i % SEGMENT_FPS === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return { chunks, metadatas }
+}
+
+/** Demux a segment and return its duration and video packets */
+async function readSegment(source: Uint8Array | string) {
+  const demuxer = new Mp4Demuxer({
+    error: (e: Error) => {
+      throw e
+    },
+  })
+  if (typeof source === 'string') {
+    await demuxer.load(source)
+  } else {
+    await demuxer.loadBuffer(source)
+  }
+  const packets: RawPacket[] = []
+  let packet: RawPacket | null
+  while ((packet = await demuxer.readPacket()) !== null) {
+    if (packet.trackType === 'video') packets.push(packet)
+  }
+  const duration = demuxer.duration!
+  demuxer.close()
+  return { duration, packets }
+}
+
+test('SegmentedMp4Muxer: splits a recording into independently playable segments', async (t) => {
+  const frames = 3 * SEGMENT_FPS
+  const { chunks, metadatas } = await encodeRecording(frames)
+
+  const muxer = new SegmentedMp4Muxer({ segmentDuration: 1_000_000 })
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    framerate: SEGMENT_FPS,
+    description: metadatas[0]?.decoderConfig?.description,
+  })
+  const segments: Mp4Segment[] = []
+  for (let i = 0; i < chunks.length; i++) {
+    const segment = muxer.addVideoChunk(chunks[i], metadatas[i])
+    if (segment) segments.push(segment)
+  }
+  segments.push(muxer.finalize()!)
+  t.is(muxer.state, 'closed')
+
+  t.deepEqual(segments.map((s) => s.index), [0, 1, 2])
+  t.deepEqual(segments.map((s) => s.startTimestamp), [0, 1_000_000, 2_000_000])
+
+  let totalDuration = 0
+  let totalPackets = 0
+  for (const segment of segments) {
+    const { duration, packets } = await readSegment(segment.data!)
+    t.is(packets[0].flags & 1, 1, `segment ${segment.index} should start with a key frame`)
+    t.is(packets[0].pts, 0, `segment ${segment.index} should start at zero`)
+    totalDuration += duration
+    totalPackets += packets.length
+  }
+  // No frame is lost or repeated at the boundaries
+  t.is(totalPackets, frames)
+  const inputDuration = (frames * 1_000_000) / SEGMENT_FPS
+  t.true(Math.abs(totalDuration - inputDuration) < 1000, `segments last ${totalDuration}us in total`)
+})
+
+test('SegmentedMp4Muxer: rotate() ends the segment at the next key frame and names files by index', async (t) => {
+  const { chunks, metadatas } = await encodeRecording(3 * SEGMENT_FPS)
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'webcodecs-segments-'))
+  t.teardown(() => fs.rm(dir, { recursive: true, force: true }))
+
+  const muxer = new SegmentedMp4Muxer({
+    segmentDuration: 3_600_000_000,
+    filename: (index) => path.join(dir, `part-${index}.mp4`),
+  })
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    framerate: SEGMENT_FPS,
+    description: metadatas[0]?.decoderConfig?.description,
+  })
+
+  const segments: Mp4Segment[] = []
+  for (let i = 0; i < chunks.length; i++) {
+    if (i === 40) {
+      muxer.rotate()
+      t.true(muxer.rotationPending)
+    }
+    const segment = muxer.addVideoChunk(chunks[i], metadatas[i])
+    if (segment) {
+      segments.push(segment)
+      t.false(muxer.rotationPending)
+    }
+  }
+  segments.push(muxer.finalize()!)
+
+  t.deepEqual(segments.map((s) => s.path), [path.join(dir, 'part-0.mp4'), path.join(dir, 'part-1.mp4')])
+  t.is(segments[0].data, undefined)
+  t.is(segments[1].startTimestamp, 2_000_000)
+
+  const first = await readSegment(segments[0].path!)
+  const second = await readSegment(segments[1].path!)
+  t.is(first.packets.length, 2 * SEGMENT_FPS)
+  t.is(second.packets.length, SEGMENT_FPS)
+  t.is(second.packets[0].flags & 1, 1)
+})
+
+test('SegmentedMp4Muxer: rejects a non-positive segment duration', (t) => {
+  t.throws(() => new SegmentedMp4Muxer({ segmentDuration: 0 }), {
+    instanceOf: TypeError,
+    message: /segmentDuration/,
+  })
+})
+
+// ============================================================================
+// fastStart Temporary File Tests
+// ============================================================================
+
+async function fastStartMuxer(t: ExecutionContext, maxTempBytes?: number) {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'webcodecs-faststart-'))
+  t.teardown(() => fs.rm(dir, { recursive: true, force: true }))
+  const { chunks, metadatas } = await encodeRecording(SEGMENT_FPS)
+  const muxer = new Mp4Muxer({ fastStart: true, tempDir: dir, maxTempBytes })
+  muxer.addVideoTrack({
+    codec: 'avc1.42001E',
+    width: 320,
+    height: 240,
+    framerate: SEGMENT_FPS,
+    description: metadatas[0]?.decoderConfig?.description,
+  })
+  return { dir, muxer, chunks, metadatas }
+}
+
+test('Mp4Muxer: fastStart spools output to a temporary file in tempDir', async (t) => {
+  const { dir, muxer, chunks, metadatas } = await fastStartMuxer(t)
+  for (let i = 0; i < chunks.length; i++) {
+    muxer.addVideoChunk(chunks[i], metadatas[i])
+  }
+  t.is((await fs.readdir(dir)).length, 1)
+
+  const data = muxer.finalize()
+  muxer.close()
+  t.deepEqual(await fs.readdir(dir), [])
+
+  // moov is moved in front of mdat
+  const moov = findBox(data, 'moov')
+  t.is(findBox(data, 'ftyp'), 8)
+  t.true(moov > 0 && moov < findBox(data, 'mdat'))
+  const { packets } = await readSegment(data)
+  t.is(packets.length, SEGMENT_FPS)
+})
+
+test('Mp4Muxer: maxTempBytes fails with QuotaExceededError and removes the temporary file', async (t) => {
+  const { dir, muxer, chunks, metadatas } = await fastStartMuxer(t, 1024)
+  t.throws(
+    () => {
+      for (let i = 0; i < chunks.length; i++) {
+        muxer.addVideoChunk(chunks[i], metadatas[i])
+      }
+      muxer.finalize()
+    },
+    { message: /QuotaExceededError/ },
+  )
+  t.deepEqual(await fs.readdir(dir), [])
+  muxer.close()
+})
+
+test('Mp4Muxer: rejects a non-positive maxTempBytes', (t) => {
+  t.throws(() => new Mp4Muxer({ fastStart: true, maxTempBytes: 0 }), { message: /maxTempBytes/ })
+})
+
+// ============================================================================
+// Gapless AAC
+// ============================================================================
+
+const GAPLESS_SAMPLES = 48_000 + 123
+
+/** Encode exactly `frames` samples of silence as AAC, in sizes unaligned to the AAC frame */
+async function encodeAac(t: ExecutionContext, frames: number) {
+  const chunks: EncodedAudioChunk[] = []
+  const metadatas: (EncodedAudioChunkMetadata | undefined)[] = []
+  const encoder = new AudioEncoder({
+    output: (chunk, metadata) => {
+      chunks.push(chunk)
+      metadatas.push(metadata)
+    },
+    error: (e) => t.fail(`Encoder error: ${e.message}`),
+  })
+  encoder.configure({ codec: 'mp4a.40.2', sampleRate: 48000, numberOfChannels: 2, bitrate: 128_000 })
+  for (let written = 0; written < frames; ) {
+    const size = Math.min(1000, frames - written)
+    const data = generateSilence(size, 2, 48000, 'f32', Math.round((written * 1_000_000) / 48000))
+    encoder.encode(data)
+    data.close()
+    written += size
+  }
+  await encoder.flush()
+  encoder.close()
+  return { chunks, metadatas }
+}
+
+/** Mux AAC chunks into an MP4 */
+function muxAac(
+  chunks: EncodedAudioChunk[],
+  metadatas: (EncodedAudioChunkMetadata | undefined)[],
+  options: { fastStart?: boolean; encoderDelay?: number; padding?: number } = {},
+): Uint8Array {
+  const muxer = new Mp4Muxer({ fastStart: options.fastStart })
+  muxer.addAudioTrack({
+    codec: 'mp4a.40.2',
+    sampleRate: 48000,
+    numberOfChannels: 2,
+    description: metadatas[0]?.decoderConfig?.description,
+    encoderDelay: options.encoderDelay,
+    padding: options.padding,
+  })
+  for (let i = 0; i < chunks.length; i++) {
+    muxer.addAudioChunk(chunks[i], metadatas[i])
+  }
+  const data = muxer.finalize()
+  muxer.close()
+  return data
+}
+
+/** Demux and decode the audio track of an MP4, returning the number of samples decoded */
+async function decodedSampleCount(t: ExecutionContext, data: Uint8Array): Promise<number> {
+  const demuxer = new Mp4Demuxer({ error: (e: Error) => t.fail(`Demuxer error: ${e.message}`) })
+  await demuxer.loadBuffer(data)
+  const track = demuxer.tracks.find((tr) => tr.trackType === 'audio')!
+  let samples = 0
+  const decoder = demuxer.createAudioDecoder(track.index, {
+    output: (audio) => {
+      samples += audio.numberOfFrames
+      audio.close()
+    },
+    error: (e) => t.fail(`Decoder error: ${e.message}`),
+  })
+  for await (const chunk of demuxer) {
+    if (chunk.audioChunk) decoder.decode(chunk.audioChunk)
+  }
+  await decoder.flush()
+  decoder.close()
+  demuxer.close()
+  return samples
+}
+
+test('Mp4Muxer: AAC encoder delay and padding give back exactly the samples encoded', async (t) => {
+  const { chunks, metadatas } = await encodeAac(t, GAPLESS_SAMPLES)
+
+  for (const fastStart of [false, true]) {
+    const data = muxAac(chunks, metadatas, { fastStart })
+    t.true(findBox(data, 'elst') > 0, 'edit list skips the priming samples')
+    t.true(findBox(data, 'iTunSMPB') > 0, 'iTunSMPB tag is written')
+    t.is(await decodedSampleCount(t, data), GAPLESS_SAMPLES, `fastStart: ${fastStart}`)
+  }
+})
+
+test('Mp4Muxer: encoderDelay and padding apply to chunks without skip marks', async (t) => {
+  const encoded = await encodeAac(t, GAPLESS_SAMPLES)
+  // Copies made from bytes lose the encoder's skip marks
+  const chunks = encoded.chunks.map((chunk) => {
+    const data = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(data)
+    return new EncodedAudioChunkClass({ type: 'key', timestamp: chunk.timestamp, duration: chunk.duration!, data })
+  })
+  const encoderDelay = 1024
+  const padding = chunks.length * 1024 - encoderDelay - GAPLESS_SAMPLES
+
+  const data = muxAac(chunks, encoded.metadatas, { encoderDelay, padding })
+  t.is(await decodedSampleCount(t, data), GAPLESS_SAMPLES)
+})
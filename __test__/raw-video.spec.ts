@@ -0,0 +1,222 @@
+/**
+ * Raw Video Tests
+ *
+ * Tests for RawVideoReader and RawVideoWriter (headerless .yuv files).
+ */
+
+import * as fs from 'node:fs/promises'
+import * as os from 'node:os'
+import * as path from 'node:path'
+
+import test from 'ava'
+
+import { RawVideoReader, RawVideoWriter, VideoFrame } from '../index.js'
+import { generateSolidColorI420Frame, TestColors } from './helpers/index.js'
+
+const WIDTH = 64
+const HEIGHT = 48
+const FRAME_SIZE = (WIDTH * HEIGHT * 3) / 2
+
+/** Build an I420 sequence where every byte of frame i is i * 10 + plane index */
+function generateRawI420(frameCount: number): Uint8Array {
+  const data = new Uint8Array(frameCount * FRAME_SIZE)
+  const lumaSize = WIDTH * HEIGHT
+  const chromaSize = lumaSize / 4
+  for (let i = 0; i < frameCount; i++) {
+    const base = i * FRAME_SIZE
+    data.fill(i * 10, base, base + lumaSize)
+    data.fill(i * 10 + 1, base + lumaSize, base + lumaSize + chromaSize)
+    data.fill(i * 10 + 2, base + lumaSize + chromaSize, base + FRAME_SIZE)
+  }
+  return data
+}
+
+async function readAll(reader: RawVideoReader): Promise<VideoFrame[]> {
+  const frames: VideoFrame[] = []
+  for await (const frame of reader) {
+    frames.push(frame)
+  }
+  return frames
+}
+
+async function copyFrame(frame: VideoFrame): Promise<Uint8Array> {
+  const data = new Uint8Array(frame.allocationSize())
+  await frame.copyTo(data)
+  return data
+}
+
+async function withTempDir(fn: (dir: string) => Promise<void>) {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'webcodecs-raw-'))
+  try {
+    await fn(dir)
+  } finally {
+    await fs.rm(dir, { recursive: true, force: true })
+  }
+}
+
+// ============================================================================
+// RawVideoReader Tests
+// ============================================================================
+
+test('RawVideoReader: reads frames from a file with generated timestamps', async (t) => {
+  await withTempDir(async (dir) => {
+    const file = path.join(dir, 'input.yuv')
+    const raw = generateRawI420(5)
+    await fs.writeFile(file, raw)
+
+    const reader = new RawVideoReader(file, { width: WIDTH, height: HEIGHT, format: 'I420', framerate: 25 })
+    t.is(reader.frameCount, 5)
+
+    const frames = await readAll(reader)
+    t.is(frames.length, 5)
+
+    for (let i = 0; i < frames.length; i++) {
+      const frame = frames[i]
+      t.is(frame.format, 'I420')
+      t.is(frame.codedWidth, WIDTH)
+      t.is(frame.codedHeight, HEIGHT)
+      t.is(frame.timestamp, i * 40_000)
+      t.is(frame.duration, 40_000)
+      t.deepEqual(await copyFrame(frame), raw.subarray(i * FRAME_SIZE, (i + 1) * FRAME_SIZE))
+      frame.close()
+    }
+  })
+})
+
+test('RawVideoReader: reads a frame range from a buffer', async (t) => {
+  const raw = generateRawI420(6)
+  // Trailing partial frame is ignored
+  const input = new Uint8Array(raw.length + 100)
+  input.set(raw)
+
+  const reader = new RawVideoReader(input, {
+    width: WIDTH,
+    height: HEIGHT,
+    format: 'I420',
+    startFrame: 2,
+    frameCount: 3,
+  })
+  t.is(reader.frameCount, 3)
+
+  const frames = await readAll(reader)
+  t.deepEqual(
+    frames.map((f) => f.timestamp),
+    [66_667, 100_000, 133_333],
+  )
+
+  const first = await copyFrame(frames[0])
+  t.is(first[0], 20)
+  t.is(first[WIDTH * HEIGHT], 21)
+  t.is(first[FRAME_SIZE - 1], 22)
+
+  for (const frame of frames) frame.close()
+})
+
+test('RawVideoReader: frame range is clamped to the input', async (t) => {
+  const reader = new RawVideoReader(generateRawI420(4), {
+    width: WIDTH,
+    height: HEIGHT,
+    format: 'I420',
+    startFrame: 3,
+    frameCount: 10,
+  })
+  t.is(reader.frameCount, 1)
+
+  const frames = await readAll(reader)
+  t.is(frames.length, 1)
+  frames[0].close()
+})
+
+test('RawVideoReader: rejects startFrame beyond the input', (t) => {
+  t.throws(
+    () => new RawVideoReader(generateRawI420(2), { width: WIDTH, height: HEIGHT, format: 'I420', startFrame: 3 }),
+    { message: /beyond the last frame/ },
+  )
+})
+
+// ============================================================================
+// RawVideoWriter Tests
+// ============================================================================
+
+test('RawVideoWriter: round-trips I420 frames', async (t) => {
+  await withTempDir(async (dir) => {
+    const file = path.join(dir, 'output.yuv')
+    const raw = generateRawI420(3)
+
+    const writer = new RawVideoWriter(file, { format: 'I420' })
+    for (const frame of await readAll(new RawVideoReader(raw, { width: WIDTH, height: HEIGHT, format: 'I420' }))) {
+      writer.write(frame)
+      frame.close()
+    }
+    t.is(writer.framesWritten, 3)
+    writer.close()
+
+    t.deepEqual(new Uint8Array(await fs.readFile(file)), raw)
+  })
+})
+
+test('RawVideoWriter: converts frames to the writer format', async (t) => {
+  await withTempDir(async (dir) => {
+    const file = path.join(dir, 'output.nv12')
+
+    const writer = new RawVideoWriter(file, { format: 'NV12' })
+    const frame = generateSolidColorI420Frame(WIDTH, HEIGHT, TestColors.red, 0)
+    writer.write(frame)
+    frame.close()
+    writer.close()
+
+    const data = await fs.readFile(file)
+    t.is(data.length, FRAME_SIZE)
+
+    const reader = new RawVideoReader(file, { width: WIDTH, height: HEIGHT, format: 'NV12' })
+    const [nv12] = await readAll(reader)
+    t.is(nv12.format, 'NV12')
+
+    // Luma and interleaved chroma match the source I420 planes
+    const source = generateSolidColorI420Frame(WIDTH, HEIGHT, TestColors.red, 0)
+    const i420 = await copyFrame(source)
+    source.close()
+    const lumaSize = WIDTH * HEIGHT
+    t.is(data[0], i420[0])
+    t.is(data[lumaSize], i420[lumaSize])
+    t.is(data[lumaSize + 1], i420[lumaSize + lumaSize / 4])
+    nv12.close()
+  })
+})
+
+test('RawVideoWriter: writes only the visible rect', async (t) => {
+  await withTempDir(async (dir) => {
+    const file = path.join(dir, 'cropped.yuv')
+    // Every byte differs from its neighbours, so a wrong crop offset shows up
+    const pixels = new Uint8Array(FRAME_SIZE).map((_, i) => (i * 7) & 0xff)
+    const frame = new VideoFrame(pixels, {
+      format: 'I420',
+      codedWidth: WIDTH,
+      codedHeight: HEIGHT,
+      timestamp: 0,
+      visibleRect: { x: 16, y: 8, width: 32, height: 32 },
+    })
+
+    const writer = new RawVideoWriter(file, { format: 'I420' })
+    writer.write(frame)
+    writer.close()
+
+    // Same bytes as copyTo(), which copies the visible rect by default
+    const expected = await copyFrame(frame)
+    frame.close()
+    const data = new Uint8Array(await fs.readFile(file))
+    t.is(data.length, (32 * 32 * 3) / 2)
+    t.deepEqual(data, expected)
+  })
+})
+
+test('RawVideoWriter: write after close throws', async (t) => {
+  await withTempDir(async (dir) => {
+    const writer = new RawVideoWriter(path.join(dir, 'closed.yuv'), { format: 'I420' })
+    writer.close()
+
+    const frame = generateSolidColorI420Frame(WIDTH, HEIGHT, TestColors.blue, 0)
+    t.throws(() => writer.write(frame), { message: /closed/ })
+    frame.close()
+  })
+})
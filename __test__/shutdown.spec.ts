@@ -0,0 +1,90 @@
+/**
+ * Shutdown Tests
+ *
+ * Verifies that pending codec work does not keep the Node.js process alive
+ * or stall its exit.
+ */
+
+import { spawn } from 'node:child_process'
+import { dirname, join } from 'node:path'
+import { fileURLToPath } from 'node:url'
+
+import test from 'ava'
+
+const __dirname = dirname(fileURLToPath(import.meta.url))
+const BINDING_PATH = join(__dirname, '..', 'index.js')
+
+/** Upper bound for the child to exit, well below the time to encode its queue */
+const EXIT_TIMEOUT_MS = 5000
+
+/**
+ * Run a script in a child Node.js process
+ *
+ * Resolves with the exit code, or null if the child had to be killed after `timeoutMs`.
+ */
+function runChild(script: string, timeoutMs: number): Promise<{ code: number | null; stderr: string }> {
+  return new Promise((resolve, reject) => {
+    const child = spawn(process.execPath, ['-e', script], { stdio: ['ignore', 'ignore', 'pipe'] })
+    let stderr = ''
+    child.stderr.on('data', (data) => {
+      stderr += data
+    })
+
+    const timer = setTimeout(() => {
+      child.kill('SIGKILL')
+      resolve({ code: null, stderr })
+    }, timeoutMs)
+
+    child.on('error', reject)
+    child.on('exit', (code) => {
+      clearTimeout(timer)
+      resolve({ code, stderr })
+    })
+  })
+}
+
+function queueFramesScript(codecSetup: string): string {
+  return `
+const { VideoEncoder, VideoFrame } = require(${JSON.stringify(BINDING_PATH)})
+const width = 1280
+const height = 720
+${codecSetup}
+const data = new Uint8Array((width * height * 3) / 2)
+for (let i = 0; i < 120; i++) {
+  data.fill(i % 256)
+  const frame = new VideoFrame(data, { format: 'I420', codedWidth: width, codedHeight: height, timestamp: i * 33333 })
+  encoder.encode(frame, { keyFrame: i === 0 })
+  frame.close()
+}
+// Exit with work still queued
+`
+}
+
+// ============================================================================
+// Process Exit Tests
+// ============================================================================
+
+test('Shutdown: process exits promptly with AV1 encodes queued', async (t) => {
+  const script = queueFramesScript(`
+const encoder = new VideoEncoder({ output: () => {}, error: () => {} })
+encoder.configure({ codec: 'av01.0.08M.08', width, height, bitrate: 2_000_000, hardwareAcceleration: 'prefer-software' })
+`)
+
+  const { code, stderr } = await runChild(script, EXIT_TIMEOUT_MS)
+  t.not(code, null, `child did not exit within ${EXIT_TIMEOUT_MS}ms`)
+  t.is(code, 0, stderr)
+})
+
+test('Shutdown: process.exit() with pending flush does not hang', async (t) => {
+  const script = queueFramesScript(`
+const encoder = new VideoEncoder({ output: () => {}, error: () => {} })
+encoder.configure({ codec: 'vp09.00.10.08', width, height, bitrate: 2_000_000, hardwareAcceleration: 'prefer-software' })
+`).concat(`
+encoder.flush().catch(() => {})
+setImmediate(() => process.exit(0))
+`)
+
+  const { code, stderr } = await runChild(script, EXIT_TIMEOUT_MS)
+  t.not(code, null, `child did not exit within ${EXIT_TIMEOUT_MS}ms`)
+  t.is(code, 0, stderr)
+})
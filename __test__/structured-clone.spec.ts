@@ -0,0 +1,138 @@
+/**
+ * Structured Clone Tests
+ *
+ * Verifies that encoder output metadata is plain data that survives
+ * structuredClone() and worker_threads postMessage(), including transfer of
+ * description buffers.
+ */
+
+import { Worker } from 'node:worker_threads'
+
+import test from 'ava'
+
+import {
+  AudioEncoder,
+  VideoEncoder,
+  type EncodedAudioChunkMetadata,
+  type EncodedVideoChunkMetadata,
+} from '../index.js'
+import { generateFrameSequence, generateSineTone } from './helpers/index.js'
+
+/** Post a message to a worker that echoes it back */
+async function echoThroughWorker<T>(message: T, transfer: ArrayBuffer[] = []): Promise<T> {
+  const worker = new Worker(
+    `const { parentPort } = require('node:worker_threads')
+parentPort.on('message', (message) => parentPort.postMessage(message))`,
+    { eval: true },
+  )
+  try {
+    const reply = new Promise<T>((resolve, reject) => {
+      worker.once('message', resolve)
+      worker.once('error', reject)
+    })
+    worker.postMessage(message, transfer)
+    return await reply
+  } finally {
+    await worker.terminate()
+  }
+}
+
+async function encodeVideoMetadata(codec: string, scalabilityMode?: string): Promise<EncodedVideoChunkMetadata[]> {
+  const metadata: EncodedVideoChunkMetadata[] = []
+  const encoder = new VideoEncoder({
+    output: (_chunk, meta) => {
+      metadata.push(meta)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec,
+    width: 320,
+    height: 240,
+    bitrate: 500_000,
+    scalabilityMode,
+    hardwareAcceleration: 'prefer-software',
+  })
+  for (const [i, frame] of generateFrameSequence(320, 240, 4).entries()) {
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return metadata
+}
+
+// ============================================================================
+// Video Metadata Tests
+// ============================================================================
+
+test('Structured clone: VideoDecoderConfigOutput survives postMessage', async (t) => {
+  const [first] = await encodeVideoMetadata('avc1.42001E')
+  const description = first.decoderConfig?.description
+  t.true(description instanceof Uint8Array)
+  t.true(description!.byteLength > 0)
+
+  t.deepEqual(structuredClone(first), first)
+
+  const echoed = await echoThroughWorker(first)
+  t.deepEqual(echoed, first)
+  t.true(echoed.decoderConfig?.description instanceof Uint8Array)
+  t.deepEqual([...echoed.decoderConfig!.description!], [...description!])
+})
+
+test('Structured clone: description buffers can be transferred', async (t) => {
+  const [first] = await encodeVideoMetadata('avc1.42001E')
+  const description = first.decoderConfig!.description!
+  const bytes = [...description]
+
+  const echoed = await echoThroughWorker(first, [description.buffer as ArrayBuffer])
+  t.is(description.byteLength, 0, 'transferred buffer should be detached')
+  t.deepEqual([...echoed.decoderConfig!.description!], bytes)
+})
+
+test('Structured clone: SvcOutputMetadata survives postMessage', async (t) => {
+  const metadata = await encodeVideoMetadata('vp09.00.10.08', 'L1T2')
+  t.true(metadata.every((meta) => meta.svc !== undefined))
+
+  const echoed = await echoThroughWorker(metadata)
+  t.deepEqual(echoed, metadata)
+  t.deepEqual(
+    echoed.map((meta) => meta.svc?.temporalLayerId),
+    metadata.map((meta) => meta.svc?.temporalLayerId),
+  )
+})
+
+// ============================================================================
+// Audio Metadata Tests
+// ============================================================================
+
+test('Structured clone: EncodedAudioChunkMetadata survives postMessage', async (t) => {
+  const metadata: EncodedAudioChunkMetadata[] = []
+  const encoder = new AudioEncoder({
+    output: (_chunk, meta) => {
+      metadata.push(meta)
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({ codec: 'mp4a.40.2', sampleRate: 48000, numberOfChannels: 2, bitrate: 128_000 })
+  const data = generateSineTone(440, 4096, 2, 48000)
+  encoder.encode(data)
+  data.close()
+  await encoder.flush()
+  encoder.close()
+
+  const withConfig = metadata.find((meta) => meta.decoderConfig !== undefined)
+  t.truthy(withConfig)
+  const description = withConfig!.decoderConfig!.description!
+  t.true(description instanceof Uint8Array)
+
+  t.deepEqual(structuredClone(withConfig), withConfig)
+
+  const echoed = await echoThroughWorker(withConfig!)
+  t.deepEqual(echoed, withConfig)
+  t.deepEqual([...echoed.decoderConfig!.description!], [...description])
+})
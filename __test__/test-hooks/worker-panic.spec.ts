@@ -0,0 +1,99 @@
+/**
+ * Worker Panic Recovery Tests
+ *
+ * Needs a build with the `test-hooks` cargo feature (`pnpm test:hooks`), which
+ * panics on the encoder worker thread while encoding the frame whose timestamp
+ * is in WEBCODECS_PANIC_AT_TIMESTAMP. Verifies the encoder is closed with an
+ * OperationError instead of leaving a poisoned lock behind.
+ */
+
+import test from 'ava'
+
+import { AudioEncoder, VideoEncoder } from '../../index.js'
+import { generateSineTone, generateSolidColorI420Frame, TestColors } from '../helpers/index.js'
+import { createEncoderConfig } from '../helpers/codec-matrix.js'
+
+/** Timestamp no other frame in this file uses */
+const PANIC_TIMESTAMP = 987_654_321
+
+process.env.WEBCODECS_PANIC_AT_TIMESTAMP = String(PANIC_TIMESTAMP)
+
+const PANIC_MESSAGE = new RegExp(`OperationError: Encoder worker panicked: injected at timestamp ${PANIC_TIMESTAMP}`)
+
+/** Create a video encoder whose `errored` promise resolves with the first error */
+function createVideoEncoder() {
+  let chunks = 0
+  let onError: (error: Error) => void
+  const errored = new Promise<Error>((resolve) => {
+    onError = resolve
+  })
+  const encoder = new VideoEncoder({
+    output: () => {
+      chunks++
+    },
+    error: (e) => onError(e),
+  })
+  return { encoder, errored, chunkCount: () => chunks }
+}
+
+test('VideoEncoder: a worker panic closes the encoder with an OperationError', async (t) => {
+  const { encoder, errored } = createVideoEncoder()
+  encoder.configure(createEncoderConfig('h264', 320, 240))
+
+  const frame = generateSolidColorI420Frame(320, 240, TestColors.red, PANIC_TIMESTAMP)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+
+  const error = await errored
+  t.regex(error.message, PANIC_MESSAGE)
+
+  // The panic poisoned the state lock; recovery cleared it, so the encoder
+  // reports a regular closed state instead of "Lock poisoned"
+  t.is(encoder.state, 'closed')
+  t.is(encoder.encodeQueueSize, 0)
+  t.throws(() => encoder.reset(), { name: 'InvalidStateError' })
+  t.throws(() => encoder.close(), { name: 'InvalidStateError' })
+})
+
+test('VideoEncoder: encoding works again after a worker panic', async (t) => {
+  const panicked = createVideoEncoder()
+  panicked.encoder.configure(createEncoderConfig('h264', 320, 240))
+  const first = generateSolidColorI420Frame(320, 240, TestColors.red, PANIC_TIMESTAMP)
+  panicked.encoder.encode(first, { keyFrame: true })
+  first.close()
+  await panicked.errored
+
+  const { encoder, chunkCount } = createVideoEncoder()
+  encoder.configure(createEncoderConfig('h264', 320, 240))
+  for (let i = 0; i < 5; i++) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.blue, i * 33_333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+
+  t.is(chunkCount(), 5)
+  t.is(encoder.state, 'configured')
+  encoder.close()
+})
+
+test('AudioEncoder: a worker panic closes the encoder with an OperationError', async (t) => {
+  let onError: (error: Error) => void
+  const errored = new Promise<Error>((resolve) => {
+    onError = resolve
+  })
+  const encoder = new AudioEncoder({
+    output: () => {},
+    error: (e) => onError(e),
+  })
+  encoder.configure({ codec: 'opus', sampleRate: 48000, numberOfChannels: 2 })
+
+  const audio = generateSineTone(440, 960, 2, 48000, 'f32', PANIC_TIMESTAMP)
+  encoder.encode(audio)
+  audio.close()
+
+  const error = await errored
+  t.regex(error.message, PANIC_MESSAGE)
+  t.is(encoder.state, 'closed')
+  t.throws(() => encoder.close(), { name: 'InvalidStateError' })
+})
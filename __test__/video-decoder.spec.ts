@@ -7,9 +7,29 @@
 
 import test from 'ava'
 
-import { resetHardwareFallbackState, VideoEncoder, VideoDecoder, VideoFrame, EncodedVideoChunk } from '../index.js'
-import type { EncodedVideoChunkMetadata, VideoDecoderConfig } from '../index.js'
-import { generateFrameSequence } from './helpers/index.js'
+import {
+  resetHardwareFallbackState,
+  VideoEncoder,
+  VideoDecoder,
+  VideoFrame,
+  EncodedVideoChunk,
+  getDecodeLimits,
+  setDecodeLimits,
+  resetDecodeLimits,
+} from '../index.js'
+import type {
+  EncodedVideoChunkMetadata,
+  HardwareAcceleration,
+  ParameterSetChange,
+  VideoDecoderConfig,
+} from '../index.js'
+import {
+  generateFrameSequence,
+  generateSolidColorI420AFrame,
+  hasHardwareAcceleration,
+  hasHevcAlphaSupport,
+  TestColors,
+} from './helpers/index.js'
 import { createEncoderConfig, createDecoderConfig } from './helpers/codec-matrix.js'
 
 // Reset hardware fallback state before each test to ensure test isolation
@@ -501,3 +521,885 @@ test('VideoDecoder: ondequeue can be set to null', (t) => {
 
   decoder.close()
 })
+
+// ============================================================================
+// Output Sink Tests
+// ============================================================================
+
+test('VideoDecoder: addOutputSink() tees every frame to each sink', async (t) => {
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 5)
+
+  const { decoder, frames } = createTestDecoder()
+  const sinkA: VideoFrame[] = []
+  const sinkB: VideoFrame[] = []
+  const idA = decoder.addOutputSink((frame) => sinkA.push(frame))
+  const idB = decoder.addOutputSink((frame) => sinkB.push(frame))
+  t.not(idA, idB)
+
+  decoder.configure({
+    ...createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 }),
+    description: decoderConfig?.description,
+  })
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+
+  t.is(frames.length, chunks.length)
+  t.deepEqual(
+    sinkA.map((f) => f.timestamp),
+    frames.map((f) => f.timestamp),
+  )
+  t.deepEqual(
+    sinkB.map((f) => f.timestamp),
+    frames.map((f) => f.timestamp),
+  )
+
+  // Each consumer owns its frame: closing one leaves the others readable
+  for (const frame of frames) {
+    frame.close()
+  }
+  for (const frame of sinkA) {
+    t.is(frame.codedWidth, 320)
+    const buffer = new Uint8Array(frame.allocationSize())
+    await t.notThrowsAsync(() => frame.copyTo(buffer))
+    frame.close()
+  }
+  for (const frame of sinkB) {
+    t.is(frame.codedHeight, 240)
+    frame.close()
+  }
+
+  decoder.close()
+})
+
+test('VideoDecoder: removeOutputSink() is safe mid-stream', async (t) => {
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 5)
+
+  const { decoder, frames } = createTestDecoder()
+  const kept: VideoFrame[] = []
+  const removed: VideoFrame[] = []
+  decoder.addOutputSink((frame) => kept.push(frame))
+  const removedId = decoder.addOutputSink((frame) => {
+    removed.push(frame)
+    // Remove itself from within its own callback
+    t.true(decoder.removeOutputSink(removedId))
+  })
+
+  decoder.configure({
+    ...createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 }),
+    description: decoderConfig?.description,
+  })
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+
+  t.is(kept.length, frames.length)
+  // Frames already queued for the removed sink may still arrive, but never more than were decoded
+  t.true(removed.length >= 1)
+  t.true(removed.length <= frames.length)
+  t.false(decoder.removeOutputSink(removedId), 'removing twice returns false')
+
+  for (const frame of [...frames, ...kept, ...removed]) {
+    frame.close()
+  }
+
+  decoder.close()
+})
+
+// ============================================================================
+// Decode Limit Tests
+// ============================================================================
+
+test('VideoDecoder: configure() rejects absurd coded dimensions with NotSupportedError', async (t) => {
+  const { decoder, errors } = createTestDecoder()
+
+  // A crafted container declaring 50000x50000 must not trigger a ~10GB allocation
+  decoder.configure({
+    codec: 'avc1.42001E',
+    codedWidth: 50000,
+    codedHeight: 50000,
+    description: new Uint8Array([1, 0x42, 0x00, 0x1e, 0xff, 0xe0, 0x00]),
+  })
+
+  await new Promise((resolve) => setTimeout(resolve, 10))
+
+  t.is(decoder.state, 'closed')
+  t.is(errors.length, 1)
+  t.true(errors[0].message.includes('NotSupportedError'))
+})
+
+test('VideoDecoder: isConfigSupported() reports absurd dimensions as unsupported', async (t) => {
+  const support = await VideoDecoder.isConfigSupported({
+    codec: 'avc1.42001E',
+    codedWidth: 50000,
+    codedHeight: 50000,
+  })
+  t.false(support.supported)
+})
+
+test.serial('VideoDecoder: decode limits can be overridden', async (t) => {
+  t.deepEqual(getDecodeLimits(), { maxDimension: 16384, maxFrameBytes: 16384 * 16384 * 4 })
+
+  try {
+    setDecodeLimits({ maxDimension: 20000, maxFrameBytes: 20000 * 20000 * 4 })
+    const raised = await VideoDecoder.isConfigSupported({
+      codec: 'avc1.42001E',
+      codedWidth: 20000,
+      codedHeight: 20000,
+    })
+    t.true(raised.supported)
+
+    setDecodeLimits({ maxDimension: 640 })
+    const lowered = await VideoDecoder.isConfigSupported({
+      codec: 'avc1.42001E',
+      codedWidth: 1280,
+      codedHeight: 720,
+    })
+    t.false(lowered.supported)
+
+    t.throws(() => setDecodeLimits({ maxDimension: 0 }))
+  } finally {
+    resetDecodeLimits()
+  }
+
+  t.is(getDecodeLimits().maxDimension, 16384)
+})
+
+// ============================================================================
+// Alpha Discard Tests
+// ============================================================================
+
+test('VideoDecoder: alpha "discard" outputs opaque frames', async (t) => {
+  if (!(await hasHevcAlphaSupport())) {
+    t.pass('libx265 does not support alpha encoding, skipping')
+    return
+  }
+
+  const chunks: EncodedVideoChunk[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk) => {
+      chunks.push(chunk)
+    },
+    error: () => {},
+  })
+  encoder.configure({
+    codec: 'hev1.1.6.L93.B0',
+    width: 320,
+    height: 240,
+    alpha: 'keep',
+    hardwareAcceleration: 'prefer-software',
+  })
+  const input = generateSolidColorI420AFrame(320, 240, TestColors.red, 128, 0)
+  encoder.encode(input, { keyFrame: true })
+  input.close()
+  await encoder.flush()
+  encoder.close()
+
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure({
+    codec: 'hev1.1.6.L93.B0',
+    codedWidth: 320,
+    codedHeight: 240,
+    alpha: 'discard',
+  })
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+
+  t.is(errors.length, 0)
+  t.true(frames.length >= 1)
+  for (const frame of frames) {
+    t.is(frame.format, 'I420')
+    frame.close()
+  }
+  decoder.close()
+})
+
+test('VideoDecoder: isConfigSupported() echoes the alpha option', async (t) => {
+  const support = await VideoDecoder.isConfigSupported({
+    codec: 'vp09.00.10.08',
+    codedWidth: 320,
+    codedHeight: 240,
+    alpha: 'discard',
+  })
+  t.is(support.config.alpha, 'discard')
+})
+
+// ============================================================================
+// Batch Decode Tests
+// ============================================================================
+
+async function decodeAndCollect(
+  config: VideoDecoderConfig,
+  submit: (decoder: VideoDecoder) => void,
+): Promise<Array<{ timestamp: number; data: Uint8Array }>> {
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure(config)
+  submit(decoder)
+  await decoder.flush()
+  decoder.close()
+
+  if (errors.length > 0) throw errors[0]
+  const outputs = []
+  for (const frame of frames) {
+    const data = new Uint8Array(frame.allocationSize())
+    await frame.copyTo(data)
+    outputs.push({ timestamp: frame.timestamp, data })
+    frame.close()
+  }
+  return outputs
+}
+
+test('VideoDecoder: decodeBatch produces the same frames as per-chunk decode', async (t) => {
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 30)
+  const config = decoderConfig ?? createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 })
+
+  const oneByOne = await decodeAndCollect(config, (decoder) => {
+    for (const chunk of chunks) {
+      decoder.decode(chunk)
+    }
+  })
+  const batched = await decodeAndCollect(config, (decoder) => decoder.decodeBatch(chunks))
+
+  t.is(batched.length, chunks.length)
+  t.deepEqual(
+    batched.map((frame) => frame.timestamp),
+    oneByOne.map((frame) => frame.timestamp),
+  )
+  for (let i = 0; i < batched.length; i++) {
+    t.true(Buffer.from(batched[i].data).equals(Buffer.from(oneByOne[i].data)), `frame ${i} differs`)
+  }
+})
+
+test('VideoDecoder: decodeBatch counts the whole batch in decodeQueueSize', async (t) => {
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 10)
+  const { decoder, frames } = createTestDecoder()
+  decoder.configure(decoderConfig ?? createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 }))
+
+  let dequeueCount = 0
+  decoder.ondequeue = () => {
+    dequeueCount++
+  }
+
+  decoder.decodeBatch(chunks)
+  t.is(decoder.decodeQueueSize, chunks.length)
+
+  await decoder.flush()
+  await new Promise((resolve) => setTimeout(resolve, 50))
+
+  t.is(decoder.decodeQueueSize, 0)
+  t.true(dequeueCount > 0)
+  t.is(frames.length, chunks.length)
+
+  for (const frame of frames) {
+    frame.close()
+  }
+  decoder.close()
+})
+
+test('VideoDecoder: decodeBatch requires the first chunk to be a keyframe', async (t) => {
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 5)
+  const { decoder } = createTestDecoder()
+  decoder.configure(decoderConfig ?? createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 }))
+
+  t.throws(() => decoder.decodeBatch(chunks.slice(1)), { message: /keyframe/ })
+  t.is(decoder.decodeQueueSize, 0)
+
+  // An empty batch is a no-op
+  t.notThrows(() => decoder.decodeBatch([]))
+
+  decoder.close()
+})
+
+test('VideoDecoder: decodeBatch rejects a chunk closed by decodeAndClose', async (t) => {
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 5)
+  const { decoder } = createTestDecoder()
+  decoder.configure(decoderConfig ?? createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 }))
+
+  decoder.decodeAndClose(chunks[0])
+  await decoder.flush()
+
+  t.throws(() => decoder.decodeBatch(chunks), { name: 'InvalidStateError', message: /EncodedVideoChunk is closed/ })
+  t.is(decoder.decodeQueueSize, 0)
+  t.is(decoder.state, 'configured')
+
+  decoder.close()
+})
+
+test('VideoDecoder: decodeBatch submits chunks with less overhead than per-chunk decode', async (t) => {
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 30)
+  const config = decoderConfig ?? createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 })
+  const batch = Array.from({ length: 100 }, () => chunks).flat()
+
+  // Measure only the synchronous submission cost; reset() before the queued
+  // microtasks run keeps the worker from decoding anything
+  const submitTime = (submit: (decoder: VideoDecoder) => void) => {
+    let best = Infinity
+    for (let round = 0; round < 5; round++) {
+      const { decoder } = createTestDecoder()
+      decoder.configure(config)
+      const start = performance.now()
+      submit(decoder)
+      best = Math.min(best, performance.now() - start)
+      decoder.reset()
+      decoder.close()
+    }
+    return best
+  }
+
+  const perChunk = submitTime((decoder) => {
+    for (const chunk of batch) {
+      decoder.decode(chunk)
+    }
+  })
+  const batched = submitTime((decoder) => decoder.decodeBatch(batch))
+
+  t.log(`${batch.length} chunks: per-chunk ${perChunk.toFixed(2)}ms, batch ${batched.toFixed(2)}ms`)
+  t.true(batched < perChunk)
+})
+
+// ============================================================================
+// Outstanding Frame Cap Tests
+// ============================================================================
+
+async function createEncodedVp8Chunks(width: number, height: number, frameCount: number): Promise<EncodedVideoChunk[]> {
+  const { encoder, chunks } = createTestEncoder()
+  encoder.configure(createEncoderConfig('vp8', width, height))
+
+  const frames = generateFrameSequence(width, height, frameCount)
+  for (let i = 0; i < frames.length; i++) {
+    encoder.encode(frames[i], { keyFrame: i === 0 })
+    frames[i].close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return chunks
+}
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms))
+
+test('VideoDecoder: maxOutstandingFrames stalls decoding until held frames are closed', async (t) => {
+  const chunks = await createEncodedVp8Chunks(64, 48, 200)
+  t.is(chunks.length, 200)
+
+  const held: VideoFrame[] = []
+  let outputCount = 0
+  const errors: Error[] = []
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      outputCount++
+      // Never close the first 16 frames, close everything after immediately
+      if (held.length < 16) {
+        held.push(frame)
+      } else {
+        frame.close()
+      }
+    },
+    error: (e) => errors.push(e),
+  })
+  decoder.configure({ ...createDecoderConfig('vp8', { codedWidth: 64, codedHeight: 48 }), maxOutstandingFrames: 16 })
+
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+
+  // The worker stops pulling chunks once 16 frames are held
+  await sleep(300)
+  const stalledAt = outputCount
+  await sleep(200)
+  t.is(outputCount, stalledAt)
+  t.is(held.length, 16)
+  t.true(outputCount < chunks.length)
+  t.true(decoder.decodeQueueSize > 0)
+
+  for (const frame of held) {
+    frame.close()
+  }
+  await decoder.flush()
+
+  t.is(errors.length, 0)
+  t.is(outputCount, chunks.length)
+  t.is(decoder.decodeQueueSize, 0)
+  decoder.close()
+})
+
+test('VideoDecoder: maxOutstandingFrames does not deadlock flush()', async (t) => {
+  const chunks = await createEncodedVp8Chunks(64, 48, 40)
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure({ ...createDecoderConfig('vp8', { codedWidth: 64, codedHeight: 48 }), maxOutstandingFrames: 4 })
+
+  // Frames queued for a pending flush can't be closed before it resolves
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+
+  t.is(errors.length, 0)
+  t.is(frames.length, chunks.length)
+  for (const frame of frames) {
+    frame.close()
+  }
+  decoder.close()
+})
+
+test('VideoDecoder: maxOutstandingFrames must be greater than 0', async (t) => {
+  const { decoder } = createTestDecoder()
+  const config = { ...createDecoderConfig('vp8', { codedWidth: 64, codedHeight: 48 }), maxOutstandingFrames: 0 }
+  t.throws(() => decoder.configure(config), { name: 'TypeError' })
+  decoder.close()
+
+  const support = await VideoDecoder.isConfigSupported({ codec: 'vp8', maxOutstandingFrames: 8 })
+  t.is(support.config.maxOutstandingFrames, 8)
+})
+
+// ============================================================================
+// Close Resource Release Tests
+// ============================================================================
+
+test('VideoDecoder: close() releases decoded 4K frame memory without GC', async (t) => {
+  const chunks = await createEncodedVp8Chunks(3840, 2160, 10)
+  const baselineRss = process.memoryUsage().rss
+
+  // 100 decoded 4K frames (~12 MB of I420 each) across 10 decoders
+  let outputCount = 0
+  for (let round = 0; round < 10; round++) {
+    const { decoder, frames, errors } = createTestDecoder()
+    decoder.configure(createDecoderConfig('vp8', { codedWidth: 3840, codedHeight: 2160 }))
+    for (const chunk of chunks) {
+      decoder.decode(chunk)
+    }
+    await decoder.flush()
+    t.is(errors.length, 0)
+    outputCount += frames.length
+    for (const frame of frames) {
+      frame.close()
+    }
+    decoder.close()
+  }
+
+  t.is(outputCount, 100)
+  const growthMb = (process.memoryUsage().rss - baselineRss) / (1024 * 1024)
+  t.true(growthMb < 300, `RSS grew by ${growthMb.toFixed(0)} MB`)
+})
+
+test('VideoDecoder: close({ detachFrames: true }) closes frames still held', async (t) => {
+  const chunks = await createEncodedVp8Chunks(64, 48, 5)
+  const { decoder, frames } = createTestDecoder()
+  decoder.configure(createDecoderConfig('vp8', { codedWidth: 64, codedHeight: 48 }))
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  t.is(frames.length, 5)
+
+  const kept = frames[0].clone()
+  decoder.close({ detachFrames: true })
+
+  for (const frame of frames) {
+    t.throws(() => frame.allocationSize(), { name: 'InvalidStateError' })
+  }
+  // Clones are owned by the application, not the decoder
+  t.is(kept.codedWidth, 64)
+  kept.close()
+})
+
+test('VideoDecoder: close() without detachFrames leaves held frames usable', async (t) => {
+  const chunks = await createEncodedVp8Chunks(64, 48, 2)
+  const { decoder, frames } = createTestDecoder()
+  decoder.configure(createDecoderConfig('vp8', { codedWidth: 64, codedHeight: 48 }))
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.is(frames.length, 2)
+  for (const frame of frames) {
+    t.true(frame.allocationSize() > 0)
+    frame.close()
+  }
+})
+
+test('VideoDecoder: close({ detachFrames: true }) makes copyTo throw InvalidStateError', async (t) => {
+  const chunks = await createEncodedVp8Chunks(64, 48, 3)
+  const { decoder, frames } = createTestDecoder()
+  decoder.configure(createDecoderConfig('vp8', { codedWidth: 64, codedHeight: 48 }))
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  t.is(frames.length, 3)
+
+  const buffer = new Uint8Array(frames[0].allocationSize())
+  decoder.close({ detachFrames: true })
+
+  for (const frame of frames) {
+    await t.throwsAsync(async () => frame.copyTo(buffer), { message: /InvalidStateError/ })
+  }
+})
+
+test('VideoDecoder: hardware output frames stay readable after close()', async (t) => {
+  if (!hasHardwareAcceleration()) {
+    t.pass('No hardware acceleration available')
+    return
+  }
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 240, 3)
+  const { decoder, frames } = createTestDecoder()
+  decoder.configure({
+    ...createDecoderConfig('h264', { codedWidth: 320, codedHeight: 240 }),
+    description: decoderConfig?.description,
+    hardwareAcceleration: 'prefer-hardware',
+  })
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  t.is(frames.length, 3)
+
+  decoder.close()
+
+  for (const frame of frames) {
+    const buffer = new Uint8Array(frame.allocationSize())
+    await t.notThrowsAsync(async () => frame.copyTo(buffer))
+    t.true(buffer.some((byte) => byte !== 0))
+    frame.close()
+  }
+})
+
+test('VideoDecoder: hardware frames of a cropped stream download only the visible rows', async (t) => {
+  if (!hasHardwareAcceleration()) {
+    t.pass('No hardware acceleration available')
+    return
+  }
+  // 180 rows are coded as 192 (12 rows of macroblock padding)
+  const { chunks, decoderConfig } = await createEncodedH264Chunks(320, 180, 3)
+  const decodeLuma = async (hardwareAcceleration: HardwareAcceleration) => {
+    const { decoder, frames } = createTestDecoder()
+    decoder.configure({
+      ...createDecoderConfig('h264', { codedWidth: 320, codedHeight: 180 }),
+      description: decoderConfig?.description,
+      hardwareAcceleration,
+    })
+    for (const chunk of chunks) {
+      decoder.decode(chunk)
+    }
+    await decoder.flush()
+    decoder.close()
+    const lumaPlanes = []
+    for (const frame of frames) {
+      t.is(frame.codedHeight, 180)
+      const data = new Uint8Array(frame.allocationSize())
+      await frame.copyTo(data)
+      // Y comes first in both I420 and NV12
+      lumaPlanes.push(data.subarray(0, 320 * 180))
+      frame.close()
+    }
+    return lumaPlanes
+  }
+
+  const software = await decodeLuma('prefer-software')
+  const hardware = await decodeLuma('prefer-hardware')
+  t.is(hardware.length, software.length)
+  hardware.forEach((luma, i) => t.deepEqual(luma, software[i], `frame ${i}`))
+})
+
+// ============================================================================
+// Output Timestamp Tests
+// ============================================================================
+
+const START_OFFSET_US = 1_400_000
+
+/** Re-stamp chunks as if the stream started 1.4s into its container, with PTS jitter */
+function withStartOffset(chunks: EncodedVideoChunk[], offsetUs = START_OFFSET_US): EncodedVideoChunk[] {
+  return chunks.map((chunk, i) => {
+    const data = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(data)
+    return new EncodedVideoChunk({
+      type: chunk.type,
+      timestamp: offsetUs + i * 33_333 + (i % 2) * 1_000,
+      duration: 33_333,
+      data,
+    })
+  })
+}
+
+async function decodeTimestamps(decoder: VideoDecoder, frames: VideoFrame[], chunks: EncodedVideoChunk[]) {
+  frames.length = 0
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  const timestamps = frames.map((frame) => frame.timestamp)
+  for (const frame of frames) {
+    frame.close()
+  }
+  return timestamps
+}
+
+test('VideoDecoder: timestampMode "container" preserves a 1.4s start offset', async (t) => {
+  const chunks = withStartOffset(await createEncodedVp8Chunks(64, 48, 10))
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure({ codec: 'vp8', codedWidth: 64, codedHeight: 48, timestampMode: 'container' })
+
+  const timestamps = await decodeTimestamps(decoder, frames, chunks)
+  decoder.close()
+
+  t.is(errors.length, 0)
+  t.deepEqual(timestamps, chunks.map((chunk) => chunk.timestamp))
+})
+
+test('VideoDecoder: startAtZero subtracts the first frame timestamp', async (t) => {
+  const chunks = withStartOffset(await createEncodedVp8Chunks(64, 48, 10))
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure({ codec: 'vp8', codedWidth: 64, codedHeight: 48, startAtZero: true })
+
+  const timestamps = await decodeTimestamps(decoder, frames, chunks)
+  decoder.close()
+
+  t.is(errors.length, 0)
+  t.deepEqual(timestamps, chunks.map((chunk) => chunk.timestamp - START_OFFSET_US))
+})
+
+test('VideoDecoder: timestampMode "normalized" renumbers frames by duration', async (t) => {
+  const chunks = withStartOffset(await createEncodedVp8Chunks(64, 48, 10))
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure({ codec: 'vp8', codedWidth: 64, codedHeight: 48, timestampMode: 'normalized', startAtZero: true })
+
+  const timestamps = await decodeTimestamps(decoder, frames, chunks)
+  decoder.close()
+
+  t.is(errors.length, 0)
+  t.deepEqual(timestamps, chunks.map((_, i) => i * 33_333))
+})
+
+test('VideoDecoder: startAtZero origin is kept across flush() and reset()', async (t) => {
+  const encoded = await createEncodedVp8Chunks(64, 48, 10)
+  const config: VideoDecoderConfig = { codec: 'vp8', codedWidth: 64, codedHeight: 48, startAtZero: true }
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure(config)
+
+  const first = await decodeTimestamps(decoder, frames, withStartOffset(encoded))
+  // A seek to 1s later in the same stream
+  decoder.reset()
+  decoder.configure(config)
+  const afterSeek = await decodeTimestamps(decoder, frames, withStartOffset(encoded, START_OFFSET_US + 1_000_000))
+  decoder.close()
+
+  t.is(errors.length, 0)
+  t.is(first[0], 0)
+  t.is(afterSeek[0], 1_000_000)
+})
+
+// ============================================================================
+// inputGranularity Tests
+// ============================================================================
+
+/** Split an Annex B chunk into one chunk per NAL unit, keeping type and timing */
+function splitNalUnits(chunk: EncodedVideoChunk): EncodedVideoChunk[] {
+  const data = new Uint8Array(chunk.byteLength)
+  chunk.copyTo(data)
+  const starts: number[] = []
+  for (let i = 0; i + 2 < data.length; i++) {
+    if (data[i] === 0 && data[i + 1] === 0 && data[i + 2] === 1) {
+      starts.push(i > 0 && data[i - 1] === 0 ? i - 1 : i)
+      i += 2
+    }
+  }
+  return starts.map(
+    (start, i) =>
+      new EncodedVideoChunk({
+        type: chunk.type,
+        timestamp: chunk.timestamp,
+        duration: chunk.duration ?? undefined,
+        data: data.subarray(start, starts[i + 1] ?? data.length),
+      }),
+  )
+}
+
+test('VideoDecoder: inputGranularity "nal" decodes one frame per picture from slice-per-chunk input', async (t) => {
+  const width = 128
+  const height = 96
+  const { encoder, chunks } = createTestEncoder()
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width,
+    height,
+    avc: { format: 'annexb' },
+    hardwareAcceleration: 'prefer-software',
+    ffmpegOptions: { slices: 4 },
+  })
+  for (const frame of generateFrameSequence(width, height, 10)) {
+    encoder.encode(frame)
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const nalChunks = chunks.flatMap(splitNalUnits)
+  // Each picture arrives as several chunks
+  t.true(nalChunks.length >= chunks.length * 4)
+
+  const { decoder, frames, errors } = createTestDecoder()
+  decoder.configure({ codec: 'avc1.42001E', codedWidth: width, codedHeight: height, inputGranularity: 'nal' })
+  for (const chunk of nalChunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.is(errors.length, 0)
+  t.is(frames.length, chunks.length)
+  t.deepEqual(frames.map((frame) => frame.timestamp), chunks.map((chunk) => chunk.timestamp))
+  for (const frame of frames) {
+    frame.close()
+  }
+})
+
+test('VideoDecoder: inputGranularity "nal" is only supported for H.264', async (t) => {
+  const h264 = await VideoDecoder.isConfigSupported({ codec: 'avc1.42001E', inputGranularity: 'nal' })
+  t.true(h264.supported)
+  t.is(h264.config.inputGranularity, 'nal')
+
+  const vp8 = await VideoDecoder.isConfigSupported({ codec: 'vp8', inputGranularity: 'nal' })
+  t.false(vp8.supported)
+})
+
+// ============================================================================
+// In-band parameter sets
+// ============================================================================
+
+/** Encode `frameCount` frames as H.264 with key frames at `keyFrames` */
+async function encodeH264Segment(
+  width: number,
+  height: number,
+  frameCount: number,
+  keyFrames: number[],
+  format: 'avc' | 'annexb',
+): Promise<EncodedChunksResult> {
+  const { encoder, chunks, getDecoderConfig } = createTestEncoder()
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width,
+    height,
+    avc: { format },
+    hardwareAcceleration: 'prefer-software',
+  })
+  generateFrameSequence(width, height, frameCount).forEach((frame, i) => {
+    encoder.encode(frame, { keyFrame: keyFrames.includes(i) })
+    frame.close()
+  })
+  await encoder.flush()
+  encoder.close()
+  return { chunks, decoderConfig: getDecoderConfig() }
+}
+
+/**
+ * Re-stamp an Annex B chunk after `offsetUs` and convert it to length-prefixed
+ * NAL units, dropping SPS/PPS unless `keepParameterSets` is set
+ */
+function toSplicedAvcc(chunk: EncodedVideoChunk, offsetUs: number, keepParameterSets: boolean): EncodedVideoChunk {
+  const data = new Uint8Array(chunk.byteLength)
+  chunk.copyTo(data)
+  const nals: Uint8Array[] = []
+  let start = -1
+  for (let i = 0; i + 2 < data.length; i++) {
+    if (data[i] === 0 && data[i + 1] === 0 && data[i + 2] === 1) {
+      if (start >= 0) nals.push(data.subarray(start, i > 0 && data[i - 1] === 0 ? i - 1 : i))
+      start = i + 3
+      i += 2
+    }
+  }
+  nals.push(data.subarray(start))
+
+  const kept = nals.filter((nal) => keepParameterSets || ![7, 8].includes(nal[0] & 0x1f))
+  const out = new Uint8Array(kept.reduce((size, nal) => size + 4 + nal.length, 0))
+  let offset = 0
+  for (const nal of kept) {
+    new DataView(out.buffer).setUint32(offset, nal.length)
+    out.set(nal, offset + 4)
+    offset += 4 + nal.length
+  }
+  return new EncodedVideoChunk({
+    type: chunk.type,
+    timestamp: chunk.timestamp + offsetUs,
+    duration: chunk.duration ?? undefined,
+    data: out,
+  })
+}
+
+test('VideoDecoder: in-band parameter sets after a splice override the description', async (t) => {
+  const first = await encodeH264Segment(128, 96, 5, [0], 'avc')
+  const second = await encodeH264Segment(160, 120, 10, [0, 5], 'annexb')
+  const spliceUs = 5 * 33333
+  // Only the first key frame after the splice carries the new SPS/PPS
+  const spliced = second.chunks.map((chunk, i) => toSplicedAvcc(chunk, spliceUs, i === 0))
+
+  const changes: ParameterSetChange[] = []
+  const frames: VideoFrame[] = []
+  const errors: Error[] = []
+  const decoder = new VideoDecoder({
+    output: (frame) => frames.push(frame),
+    error: (e) => errors.push(e),
+    parameterSetChange: (change) => changes.push(change),
+  })
+  decoder.configure({ ...first.decoderConfig!, hardwareAcceleration: 'prefer-software' })
+  for (const chunk of [...first.chunks, ...spliced]) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.is(errors.length, 0)
+  t.deepEqual(
+    frames.map((frame) => [frame.codedWidth, frame.codedHeight]),
+    [...Array(5).fill([128, 96]), ...Array(10).fill([160, 120])],
+  )
+  for (const frame of frames) {
+    frame.close()
+  }
+
+  t.is(changes.length, 1)
+  const [change] = changes
+  t.is(change.codec, 'avc1.42001E')
+  t.is(change.timestamp, spliceUs)
+  t.is(change.previousSource, 'description')
+  t.notDeepEqual(change.previous, change.current)
+  t.is(change.description?.[0], 1)
+
+  // The reported description configures a decoder for the new segment
+  const { decoder: fresh, frames: freshFrames, errors: freshErrors } = createTestDecoder()
+  fresh.configure({ codec: change.codec, description: change.description, hardwareAcceleration: 'prefer-software' })
+  for (const chunk of spliced) {
+    fresh.decode(chunk)
+  }
+  await fresh.flush()
+  fresh.close()
+  t.is(freshErrors.length, 0)
+  t.is(freshFrames.length, spliced.length)
+  for (const frame of freshFrames) {
+    frame.close()
+  }
+})
+
+test('VideoDecoder: matching in-band parameter sets report no change', async (t) => {
+  const { chunks } = await encodeH264Segment(128, 96, 5, [0], 'annexb')
+  const withDescription = await encodeH264Segment(128, 96, 5, [0], 'avc')
+
+  const changes: ParameterSetChange[] = []
+  const decoder = new VideoDecoder({
+    output: (frame) => frame.close(),
+    error: (e) => t.fail(e.message),
+    parameterSetChange: (change) => changes.push(change),
+  })
+  decoder.configure({ ...withDescription.decoderConfig!, hardwareAcceleration: 'prefer-software' })
+  for (const chunk of chunks) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.deepEqual(changes, [])
+})
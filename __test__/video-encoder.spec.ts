@@ -5,19 +5,35 @@
  * Uses callback-based constructor per W3C WebCodecs spec.
  */
 
-import test from 'ava'
+import test, { type ExecutionContext } from 'ava'
 
-import { resetHardwareFallbackState, VideoEncoder, type EncodedVideoChunkMetadata } from '../index.js'
 import {
+  getCodecCapabilities,
+  getDefaultEncoderOptions,
+  isHardwareAcceleratorAvailable,
+  resetHardwareFallbackState,
+  setDefaultEncoderOptions,
+  VideoDecoder,
+  VideoEncoder,
+  VideoFrame,
+  type EncodedVideoChunkMetadata,
+  type HdrDynamicMetadata,
+  type RateControlStats,
+} from '../index.js'
+import {
+  calculateI420Size,
   generateSolidColorI420Frame,
   generateSolidColorI420AFrame,
+  generateCheckerboardI420Frame,
   generateFrameSequence,
+  calculatePSNR,
   TestColors,
   hasHardwareAcceleration,
   hasHevcAlphaSupport,
   type EncodedVideoChunk,
 } from './helpers/index.js'
-import { createEncoderConfig } from './helpers/codec-matrix.js'
+import { createEncoderConfig, type CodecType } from './helpers/codec-matrix.js'
+import type { VideoEncoderConfig } from '../standard.js'
 
 // Reset hardware fallback state before each test to ensure test isolation
 test.beforeEach(() => {
@@ -142,6 +158,39 @@ test('VideoEncoder: configure() can be called multiple times', (t) => {
   encoder.close()
 })
 
+test('VideoEncoder: configureAsync() resolves once the encoder is ready', async (t) => {
+  const { encoder, chunks, errors } = createTestEncoder()
+
+  const configured = encoder.configureAsync(createEncoderConfig('h264', 320, 240))
+  t.is(encoder.state, 'configured')
+  await t.notThrowsAsync(configured)
+
+  // Reconfiguring resolves after the worker has applied the new settings
+  await t.notThrowsAsync(encoder.configureAsync(createEncoderConfig('h264', 640, 480)))
+
+  const frame = generateSolidColorI420Frame(640, 480, TestColors.red, 0)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+
+  t.is(errors.length, 0)
+  t.true(chunks.length > 0)
+  encoder.close()
+})
+
+test('VideoEncoder: configureAsync() rejects with NotSupportedError for an unsupported codec', async (t) => {
+  const { encoder, errors } = createTestEncoder()
+
+  const configured = encoder.configureAsync({ codec: 'unsupported-codec', width: 320, height: 240 })
+  // Same state transition as configure(): configured until the error closes the encoder
+  t.is(encoder.state, 'configured')
+  await t.throwsAsync(configured, { message: /NotSupportedError/ })
+
+  t.is(encoder.state, 'closed')
+  t.is(errors.length, 1, 'error callback should still be called')
+  t.regex(errors[0].message, /NotSupportedError/)
+})
+
 // ============================================================================
 // encode() Tests
 // ============================================================================
@@ -327,6 +376,489 @@ test('VideoEncoder: reset() then reconfigure allows new encoding', async (t) =>
   encoder.close()
 })
 
+test('VideoEncoder: encode -> reconfigure -> flush delivers old chunks first', async (t) => {
+  const { encoder, chunks, errors } = createTestEncoder()
+  encoder.configure(createEncoderConfig('h264', 320, 240))
+
+  // Encodes queued before configure() belong to the old configuration
+  const firstFrames = generateFrameSequence(320, 240, 5)
+  for (const frame of firstFrames) {
+    encoder.encode(frame)
+    frame.close()
+  }
+
+  encoder.configure(createEncoderConfig('h264', 640, 480))
+
+  const secondStart = 1_000_000
+  for (let i = 0; i < 3; i++) {
+    const frame = generateSolidColorI420Frame(640, 480, TestColors.green, secondStart + i * 33333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+
+  await encoder.flush()
+
+  t.is(errors.length, 0)
+  const firstIndexes = chunks.flatMap((chunk, i) => (chunk.timestamp < secondStart ? [i] : []))
+  const secondIndexes = chunks.flatMap((chunk, i) => (chunk.timestamp >= secondStart ? [i] : []))
+  t.is(firstIndexes.length, 5, 'All chunks from the old configuration should be delivered')
+  t.true(secondIndexes.length >= 1)
+  t.true(
+    Math.max(...firstIndexes) < Math.min(...secondIndexes),
+    'Old configuration chunks should arrive before new configuration chunks',
+  )
+
+  encoder.close()
+})
+
+test('VideoEncoder: encode() tags are returned in metadata of the matching chunk', async (t) => {
+  const outputs: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+  const errors: Error[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      outputs.push({ chunk, metadata })
+    },
+    error: (e) => {
+      errors.push(e)
+    },
+  })
+
+  // Quality mode with a software encoder keeps the encoder's B-frame defaults (reordered output)
+  encoder.configure(createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+
+  const expectedTags = new Map<number, string>()
+  const frames = generateFrameSequence(320, 240, 12)
+  frames.forEach((frame, i) => {
+    const tag = `frame-${i}`
+    expectedTags.set(frame.timestamp, tag)
+    encoder.encode(frame, { keyFrame: i === 0, tag })
+    frame.close()
+  })
+
+  await encoder.flush()
+
+  t.is(errors.length, 0)
+  t.is(outputs.length, frames.length)
+  for (const { chunk, metadata } of outputs) {
+    t.is(metadata?.tag, expectedTags.get(chunk.timestamp), `tag mismatch at timestamp ${chunk.timestamp}`)
+  }
+  t.is(new Set(outputs.map(({ metadata }) => metadata?.tag)).size, frames.length)
+
+  encoder.close()
+})
+
+test('VideoEncoder: HDR dynamic metadata passes through encode and decode per frame', async (t) => {
+  // Synthetic blobs: an HDR10+-style T.35 header followed by a per-frame marker, plus an opaque RPU
+  const blobsFor = (i: number): HdrDynamicMetadata[] => [
+    { type: 'itu-t-t35', data: new Uint8Array([0xb5, 0x00, 0x3c, 0x00, 0x01, 0x04, 0x00, i, 0xaa, i * 3]) },
+    { type: 'dolby-vision-rpu', data: new Uint8Array([0x7c, 0x01, 0x19, 0x08, i, 0x55]) },
+  ]
+
+  const outputs: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      outputs.push({ chunk, metadata })
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(createEncoderConfig('vp9', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+
+  const expected = new Map<number, HdrDynamicMetadata[]>()
+  for (let i = 0; i < 6; i++) {
+    const source = generateSolidColorI420Frame(320, 240, TestColors.red, i * 33333)
+    const frame = new VideoFrame(source, { metadata: { hdrDynamicMetadata: blobsFor(i) } })
+    source.close()
+    expected.set(frame.timestamp, blobsFor(i))
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  t.is(outputs.length, expected.size)
+  for (const { chunk, metadata } of outputs) {
+    t.deepEqual(metadata?.hdrDynamicMetadata, expected.get(chunk.timestamp))
+    t.deepEqual(chunk.hdrDynamicMetadata, expected.get(chunk.timestamp))
+  }
+
+  const decoded = new Map<number, HdrDynamicMetadata[] | undefined>()
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      decoded.set(frame.timestamp, frame.metadata().hdrDynamicMetadata)
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure({ codec: outputs[0].metadata?.decoderConfig?.codec ?? 'vp09.00.10.08' })
+  for (const { chunk } of outputs) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.is(decoded.size, expected.size)
+  for (const [timestamp, blobs] of expected) {
+    t.deepEqual(decoded.get(timestamp), blobs, `metadata mismatch at timestamp ${timestamp}`)
+  }
+})
+
+/** SMPTE ST 2094-40 (HDR10+) T.35 payload: one window, one MaxRGB percentile; `marker` varies the levels */
+function hdr10PlusT35(marker: number): Uint8Array {
+  const fields: Array<[value: number, bits: number]> = [
+    [1, 8], // application_version
+    [1, 2], // num_windows
+    [1000, 27], // targeted_system_display_maximum_luminance
+    [0, 1], // targeted_system_display_actual_peak_luminance_flag
+    [10_000 + marker, 17], // maxscl[0..2]
+    [9_000 + marker, 17],
+    [8_000 + marker, 17],
+    [4_000 + marker, 17], // average_maxrgb
+    [1, 4], // num_distribution_maxrgb_percentiles
+    [50, 7], // distribution_maxrgb_percentages[0]
+    [2_000 + marker, 17], // distribution_maxrgb_percentiles[0]
+    [0, 10], // fraction_bright_pixels
+    [0, 1], // mastering_display_actual_peak_luminance_flag
+    [0, 1], // tone_mapping_flag
+    [0, 1], // color_saturation_mapping_flag
+  ]
+  const bits = fields.flatMap(([value, width]) =>
+    Array.from({ length: width }, (_, i) => (value >> (width - 1 - i)) & 1),
+  )
+  const payload = new Uint8Array(Math.ceil(bits.length / 8))
+  bits.forEach((bit, i) => {
+    payload[i >> 3] |= bit << (7 - (i & 7))
+  })
+  // itu_t_t35_country_code, terminal_provider_code, terminal_provider_oriented_code, application_identifier
+  return new Uint8Array([0xb5, 0x00, 0x3c, 0x00, 0x01, 0x04, ...payload])
+}
+
+/** ITU-T T.35 payloads of the prefix SEI NAL units in a length-prefixed HEVC chunk */
+function hevcT35Payloads(chunk: EncodedVideoChunk): Uint8Array[] {
+  const data = new Uint8Array(chunk.byteLength)
+  chunk.copyTo(data)
+  const view = new DataView(data.buffer)
+  const payloads: Uint8Array[] = []
+  for (let offset = 0; offset + 4 <= data.byteLength; ) {
+    const size = view.getUint32(offset)
+    const nal = data.subarray(offset + 4, offset + 4 + size)
+    offset += 4 + size
+    // PREFIX_SEI_NUT, then strip emulation prevention bytes after the 2-byte header
+    if (((nal[0] >> 1) & 0x3f) !== 39) continue
+    const rbsp: number[] = []
+    for (let i = 2; i < nal.length; i++) {
+      if (i >= 4 && nal[i] === 3 && nal[i - 1] === 0 && nal[i - 2] === 0) continue
+      rbsp.push(nal[i])
+    }
+    for (let i = 0; i < rbsp.length && rbsp[i] !== 0x80; ) {
+      let type = 0
+      while (rbsp[i] === 0xff) type += rbsp[i++]
+      type += rbsp[i++]
+      let length = 0
+      while (rbsp[i] === 0xff) length += rbsp[i++]
+      length += rbsp[i++]
+      // user_data_registered_itu_t_t35
+      if (type === 4) payloads.push(new Uint8Array(rbsp.slice(i, i + length)))
+      i += length
+    }
+  }
+  return payloads
+}
+
+test('VideoEncoder: HEVC writes HDR10+ metadata into the bitstream as a T.35 SEI', async (t) => {
+  const outputs: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      outputs.push({ chunk, metadata })
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(createEncoderConfig('h265', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+
+  const expected = new Map<number, Uint8Array>()
+  for (let i = 0; i < 6; i++) {
+    const source = generateSolidColorI420Frame(320, 240, TestColors.red, i * 33333)
+    const frame = new VideoFrame(source, {
+      metadata: { hdrDynamicMetadata: [{ type: 'itu-t-t35', data: hdr10PlusT35(i) }] },
+    })
+    source.close()
+    expected.set(frame.timestamp, hdr10PlusT35(i))
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  t.is(outputs.length, expected.size)
+  for (const { chunk } of outputs) {
+    // libx265 writes the frame's HDR10+ metadata as-is, not just the side channel
+    t.deepEqual(hevcT35Payloads(chunk), [expected.get(chunk.timestamp)!], `SEI at timestamp ${chunk.timestamp}`)
+  }
+})
+
+test('VideoEncoder: A53 closed captions survive a decode and re-encode frame for frame', async (t) => {
+  // cc_data triplets: CEA-608 field 1 control codes with a per-frame marker
+  const captionsFor = (i: number) => new Uint8Array([0xfc, 0x94, 0x20 + i, 0xfc, 0x94, 0x2c])
+  const config = createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'prefer-software' })
+  const GA94 = Buffer.from('GA94')
+
+  /** Chunk bytes, checked to carry the caption SEI for the frame's captions */
+  const assertCaptionSei = (chunk: EncodedVideoChunk, captions: Uint8Array, label: string) => {
+    const data = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(data)
+    const bytes = Buffer.from(data)
+    t.true(bytes.includes(GA94), `${label}: no A53 SEI at timestamp ${chunk.timestamp}`)
+    t.true(bytes.includes(Buffer.from(captions)), `${label}: caption bytes at timestamp ${chunk.timestamp}`)
+  }
+
+  const expected = new Map<number, Uint8Array>()
+  const captioned: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => captioned.push({ chunk, metadata }),
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(config)
+  for (let i = 0; i < 6; i++) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.blue, i * 33333)
+    expected.set(frame.timestamp, captionsFor(i))
+    encoder.encode(frame, { keyFrame: i === 0, closedCaptions: captionsFor(i) })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  t.is(captioned.length, expected.size)
+  for (const { chunk } of captioned) {
+    assertCaptionSei(chunk, expected.get(chunk.timestamp)!, 'captioned source')
+  }
+
+  // Transcode: decoded frames carry the captions in their metadata and the
+  // second encoder writes them back without any encode option
+  const decodedCaptions = new Map<number, Uint8Array | undefined>()
+  const transcoded: EncodedVideoChunk[] = []
+  const reencoder = new VideoEncoder({
+    output: (chunk) => transcoded.push(chunk),
+    error: (e) => t.fail(e.message),
+  })
+  reencoder.configure(config)
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      decodedCaptions.set(frame.timestamp, frame.metadata().closedCaptions)
+      reencoder.encode(frame, { keyFrame: frame.timestamp === 0 })
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure(captioned[0].metadata!.decoderConfig!)
+  for (const { chunk } of captioned) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+  await reencoder.flush()
+  reencoder.close()
+
+  t.is(decodedCaptions.size, expected.size)
+  for (const [timestamp, captions] of expected) {
+    t.deepEqual(decodedCaptions.get(timestamp), captions, `captions mismatch at timestamp ${timestamp}`)
+  }
+  t.is(transcoded.length, expected.size)
+  for (const chunk of transcoded) {
+    assertCaptionSei(chunk, expected.get(chunk.timestamp)!, 'transcoded')
+  }
+})
+
+test('VideoEncoder: maxChunkBytes re-encodes all-intra VP8 key frames to fit the budget', async (t) => {
+  const config = createEncoderConfig('vp8', 320, 240, {
+    bitrate: 20_000_000,
+    hardwareAcceleration: 'prefer-software',
+    latencyMode: 'realtime',
+  })
+  t.true(VideoEncoder.isMaxChunkBytesSupported(config).supported)
+
+  const encodeAll = async (maxChunkBytes?: number) => {
+    const outputs: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+    const encoder = new VideoEncoder({
+      output: (chunk, metadata) => {
+        outputs.push({ chunk, metadata })
+      },
+      error: (e) => t.fail(e.message),
+    })
+    encoder.configure(config)
+    for (let i = 0; i < 4; i++) {
+      const frame = generateCheckerboardI420Frame(320, 240, i * 33333, 3 + i)
+      encoder.encode(frame, { keyFrame: true, maxChunkBytes })
+      frame.close()
+    }
+    await encoder.flush()
+    encoder.close()
+    return outputs
+  }
+
+  // Budget well below what the encoder produces on its own at this bitrate
+  const uncapped = await encodeAll()
+  const maxChunkBytes = Math.floor(Math.min(...uncapped.map(({ chunk }) => chunk.byteLength)) / 2)
+  t.true(uncapped.every(({ metadata }) => metadata?.chunkBudget === undefined))
+
+  const capped = await encodeAll(maxChunkBytes)
+  t.is(capped.length, uncapped.length)
+  for (const { chunk, metadata } of capped) {
+    t.is(chunk.type, 'key')
+    t.true(chunk.byteLength <= maxChunkBytes, `${chunk.byteLength} exceeds ${maxChunkBytes}`)
+    t.true((metadata?.chunkBudget?.retries ?? 0) >= 1)
+    t.false(metadata?.chunkBudget?.exceeded)
+  }
+
+  // Re-encoded chunks are standalone key frames that still decode
+  let decoded = 0
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      decoded++
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure({ codec: 'vp8' })
+  for (const { chunk } of capped) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+  t.is(decoded, capped.length)
+})
+
+test('VideoEncoder: isMaxChunkBytesSupported resolves no-preference like configure', (t) => {
+  // VideoToolbox has no VP8/VP9 encoder, and without a VAAPI/D3D11 device there is
+  // no hardware encoder at all, so configure falls back to libvpx by default
+  const platformHwType = { linux: 'vaapi', win32: 'd3d11va' }[process.platform as string]
+  const softwareByDefault = platformHwType === undefined || !isHardwareAcceleratorAvailable(platformHwType)
+
+  for (const codec of ['vp8', 'vp9'] as const) {
+    const config = createEncoderConfig(codec, 320, 240)
+    t.is(config.hardwareAcceleration, undefined)
+    t.true(VideoEncoder.isMaxChunkBytesSupported({ ...config, hardwareAcceleration: 'prefer-software' }).supported)
+    if (softwareByDefault) {
+      t.true(VideoEncoder.isMaxChunkBytesSupported(config).supported, codec)
+      t.true(
+        VideoEncoder.isMaxChunkBytesSupported({ ...config, hardwareAcceleration: 'no-preference' }).supported,
+        codec,
+      )
+    }
+    t.false(VideoEncoder.isMaxChunkBytesSupported({ ...config, hardwareAcceleration: 'prefer-hardware' }).supported)
+  }
+})
+
+test('VideoEncoder: maxChunkBytes flags oversized chunks when re-encoding is unsupported', async (t) => {
+  const config = createEncoderConfig('vp8', 320, 240, { hardwareAcceleration: 'prefer-software' })
+  t.false(VideoEncoder.isMaxChunkBytesSupported({ ...config, codec: 'avc1.42001E' }).supported)
+
+  const outputs: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      outputs.push({ chunk, metadata })
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(config)
+  for (let i = 0; i < 3; i++) {
+    const frame = generateCheckerboardI420Frame(320, 240, i * 33333, 3)
+    // Delta frames depend on encoder state and are never re-encoded
+    encoder.encode(frame, { keyFrame: i === 0, maxChunkBytes: 1 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  t.is(outputs.length, 3)
+  for (const { chunk, metadata } of outputs.slice(1)) {
+    t.is(chunk.type, 'delta')
+    t.deepEqual(metadata?.chunkBudget, { retries: 0, exceeded: true })
+  }
+
+  const unconfigured = new VideoEncoder({ output: () => {}, error: () => {} })
+  const frame = generateCheckerboardI420Frame(320, 240, 0)
+  t.throws(() => unconfigured.encode(frame, { maxChunkBytes: 0 }), { name: 'TypeError' })
+  frame.close()
+  unconfigured.close()
+})
+
+test('VideoEncoder: long-term reference lets VP8 recover after dropped frames', async (t) => {
+  const config = createEncoderConfig('vp8', 320, 240, {
+    hardwareAcceleration: 'prefer-software',
+    latencyMode: 'realtime',
+  })
+  t.true(VideoEncoder.isReferenceControlSupported(config).supported)
+  t.false(VideoEncoder.isReferenceControlSupported({ ...config, codec: 'avc1.42001E' }).supported)
+
+  const outputs: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      outputs.push({ chunk, metadata })
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(config)
+  for (let i = 0; i < 12; i++) {
+    const frame = generateCheckerboardI420Frame(320, 240, i * 33333, 8 + (i % 4))
+    if (i === 0) {
+      encoder.encode(frame, { keyFrame: true })
+    } else if (i === 1) {
+      encoder.encode(frame, { markAsLongTermReference: 7 })
+    } else if (i === 10) {
+      // Recovery frame: predicted from frame 1 only
+      encoder.encode(frame, { referenceOnly: [7] })
+    } else {
+      encoder.encode(frame)
+    }
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  t.is(outputs.length, 12)
+  t.is(outputs[1].metadata?.referenceControl?.longTermReference, 7)
+  t.true(outputs[5].metadata?.referenceControl?.referencesPreviousFrame)
+  const recovery = outputs[10]
+  t.is(recovery.chunk.type, 'delta')
+  t.deepEqual(recovery.metadata?.referenceControl?.references, [7])
+  t.false(recovery.metadata?.referenceControl?.referencesPreviousFrame)
+
+  // Decoding the recovery frame right after the long-term reference gives the same picture
+  const decodeLast = async (indices: number[]) => {
+    let last: VideoFrame | undefined
+    const decoder = new VideoDecoder({
+      output: (frame) => {
+        last?.close()
+        last = frame
+      },
+      error: (e) => t.fail(e.message),
+    })
+    decoder.configure({ codec: 'vp8', codedWidth: 320, codedHeight: 240 })
+    for (const index of indices) {
+      decoder.decode(outputs[index].chunk)
+    }
+    await decoder.flush()
+    decoder.close()
+    if (!last) return undefined
+    const data = new Uint8Array(last.allocationSize())
+    await last.copyTo(data)
+    last.close()
+    return data
+  }
+  const full = await decodeLast([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+  const lossy = await decodeLast([0, 1, 10])
+  t.truthy(full)
+  t.true(Buffer.from(lossy!).equals(Buffer.from(full!)))
+})
+
+test('VideoEncoder: reference control options are rejected where unsupported', async (t) => {
+  const encoder = new VideoEncoder({ output: () => {}, error: () => {} })
+  encoder.configure(createEncoderConfig('h264', 320, 240))
+  const frame = generateCheckerboardI420Frame(320, 240, 0)
+  t.throws(() => encoder.encode(frame, { markAsLongTermReference: 1 }), { name: 'NotSupportedError' })
+  t.throws(() => encoder.encode(frame, { referenceOnly: [] }), { name: 'TypeError' })
+  frame.close()
+  encoder.close()
+})
+
 // ============================================================================
 // isConfigSupported() Tests
 // ============================================================================
@@ -365,6 +897,48 @@ test('VideoEncoder: isConfigSupported() returns false for unknown codec', async
   t.false(result.supported)
 })
 
+test('VideoEncoder: isConfigSupported() returns false for inconsistent display size', async (t) => {
+  const stretched = await VideoEncoder.isConfigSupported({
+    codec: 'avc1.42001E',
+    width: 640,
+    height: 480,
+    displayWidth: 6400,
+    displayHeight: 480,
+  })
+  t.false(stretched.supported)
+
+  const anamorphic = await VideoEncoder.isConfigSupported({
+    codec: 'avc1.42001E',
+    width: 640,
+    height: 480,
+    displayWidth: 854,
+    displayHeight: 480,
+  })
+  t.true(anamorphic.supported)
+})
+
+test('VideoEncoder: isConfigSupported() gives the same answers from the probe cache', async (t) => {
+  const configs: VideoEncoderConfig[] = [
+    createEncoderConfig('h264', 320, 240),
+    createEncoderConfig('vp9', 320, 240),
+    { ...createEncoderConfig('h264', 320, 240), hardwareAcceleration: 'prefer-hardware' },
+    { codec: 'hvc1.1.6.L93.B0', width: 16, height: 16 },
+    { codec: 'avc1.64001F', width: 321, height: 240 },
+    { codec: 'unknown-codec', width: 320, height: 240 },
+  ]
+  const probe = () =>
+    Promise.all(configs.map(async (config) => (await VideoEncoder.isConfigSupported(config)).supported))
+
+  const first = await probe()
+  const cached = await probe()
+  resetHardwareFallbackState()
+  const reprobed = await probe()
+
+  t.deepEqual(cached, first)
+  t.deepEqual(reprobed, first)
+  t.deepEqual(first.slice(3), [false, false, false])
+})
+
 test('VideoEncoder: isConfigSupported() rejects zero width', async (t) => {
   await t.throwsAsync(
     VideoEncoder.isConfigSupported({
@@ -653,6 +1227,44 @@ test('VideoEncoder: VP9 alpha encoding works correctly', async (t) => {
   encoder.close()
 })
 
+test('VideoEncoder: VP9 alpha discard drops alpha side data', async (t) => {
+  const chunks: EncodedVideoChunk[] = []
+  const metadatas: Array<EncodedVideoChunkMetadata> = []
+  const errors: Error[] = []
+
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      chunks.push(chunk)
+      if (metadata) metadatas.push(metadata)
+    },
+    error: (e) => {
+      errors.push(e)
+    },
+  })
+
+  encoder.configure({
+    codec: 'vp09.00.10.08',
+    width: 320,
+    height: 240,
+    alpha: 'discard',
+  })
+
+  // I420A input is encoded as opaque I420 without a conversion pass
+  const frame = generateSolidColorI420AFrame(320, 240, TestColors.blue, 128, 0)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+
+  await encoder.flush()
+
+  t.is(errors.length, 0, 'No errors should occur')
+  t.true(chunks.length >= 1, 'Should produce at least one encoded chunk')
+  for (const metadata of metadatas) {
+    t.is(metadata.alphaSideData, undefined)
+  }
+
+  encoder.close()
+})
+
 test('VideoEncoder: alpha discard is the default', async (t) => {
   const chunks: EncodedVideoChunk[] = []
   const errors: Error[] = []
@@ -688,3 +1300,1088 @@ test('VideoEncoder: alpha discard is the default', async (t) => {
 
   encoder.close()
 })
+
+// ============================================================================
+// Visible Rect Tests
+// ============================================================================
+
+// 320x248 coded I420 frame: a vertical luma gradient in the top 240 rows and
+// bright padding (with green chroma) in the bottom 8 rows
+function createPaddedFrame(init: { displayWidth?: number; displayHeight?: number } = {}) {
+  const width = 320
+  const codedHeight = 248
+  const visibleHeight = 240
+  const data = new Uint8Array(width * codedHeight * 1.5)
+  for (let y = 0; y < codedHeight; y++) {
+    data.fill(y < visibleHeight ? 16 + y : 255, y * width, (y + 1) * width)
+  }
+  const chromaOffset = width * codedHeight
+  const chromaSize = (width / 2) * (codedHeight / 2)
+  for (let y = 0; y < codedHeight / 2; y++) {
+    const value = y < visibleHeight / 2 ? 128 : 0
+    data.fill(value, chromaOffset + y * (width / 2), chromaOffset + (y + 1) * (width / 2))
+    data.fill(value, chromaOffset + chromaSize + y * (width / 2), chromaOffset + chromaSize + (y + 1) * (width / 2))
+  }
+  const reference = data.slice(0, width * visibleHeight)
+  const frame = new VideoFrame(data, {
+    format: 'I420',
+    codedWidth: width,
+    codedHeight,
+    timestamp: 0,
+    visibleRect: { x: 0, y: 0, width, height: visibleHeight },
+    ...init,
+  })
+  return { frame, reference }
+}
+
+test('VideoEncoder: encodes only the visible rect of a padded frame', async (t) => {
+  const { frame, reference } = createPaddedFrame()
+  const outputs: { chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => outputs.push({ chunk, metadata }),
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(createEncoderConfig('vp8', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+  encoder.close()
+
+  t.is(outputs.length, 1)
+  t.is(outputs[0].metadata?.decoderConfig?.codedHeight, 240)
+
+  const decoded: VideoFrame[] = []
+  const decoder = new VideoDecoder({
+    output: (output) => decoded.push(output),
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure({ codec: 'vp8' })
+  decoder.decode(outputs[0].chunk)
+  await decoder.flush()
+  decoder.close()
+
+  t.is(decoded.length, 1)
+  t.is(decoded[0].codedHeight, 240)
+  const buffer = new Uint8Array(decoded[0].allocationSize())
+  await decoded[0].copyTo(buffer)
+  decoded[0].close()
+
+  // Padding rows leaking in (or being squeezed into the frame) ruins the bottom rows
+  const psnr = calculatePSNR(reference, buffer.subarray(0, reference.length))
+  t.true(psnr > 35, `PSNR ${psnr.toFixed(2)} dB`)
+})
+
+test('VideoEncoder: derives displayAspect metadata from the input frame', async (t) => {
+  const { frame } = createPaddedFrame({ displayWidth: 640, displayHeight: 240 })
+  const metadata: EncodedVideoChunkMetadata[] = []
+  const encoder = new VideoEncoder({
+    output: (_chunk, meta) => {
+      if (meta) metadata.push(meta)
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(createEncoderConfig('vp8', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+  encoder.close()
+
+  t.is(metadata[0]?.decoderConfig?.displayAspectWidth, 640)
+  t.is(metadata[0]?.decoderConfig?.displayAspectHeight, 240)
+})
+
+// ============================================================================
+// Two-Pass Encoding Tests
+// ============================================================================
+
+async function encodeSequence(config: VideoEncoderConfig, frameCount: number) {
+  const { encoder, chunks, errors } = createTestEncoder()
+  encoder.configure(config)
+
+  for (const frame of generateFrameSequence(config.width, config.height, frameCount)) {
+    encoder.encode(frame, { keyFrame: frame.timestamp === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+
+  const totalBytes = chunks.reduce((sum, chunk) => sum + chunk.byteLength, 0)
+  const stats = encoder.stats()
+  encoder.close()
+  return { chunks, errors, totalBytes, stats }
+}
+
+test('VideoEncoder: two-pass VP9 hits the bitrate target more closely than single-pass VBR', async (t) => {
+  const frameCount = 60
+  const framerate = 30
+  const config: VideoEncoderConfig = {
+    ...createEncoderConfig('vp9', 320, 240, { bitrate: 200_000, framerate, hardwareAcceleration: 'prefer-software' }),
+    bitrateMode: 'variable',
+  }
+  const targetBytes = (config.bitrate! / 8) * (frameCount / framerate)
+
+  const single = await encodeSequence(config, frameCount)
+  t.is(single.errors.length, 0)
+  t.is(single.stats, null)
+
+  // Pass 1 only analyzes the input
+  const first = await encodeSequence({ ...config, pass: 1 }, frameCount)
+  t.is(first.errors.length, 0)
+  t.is(first.chunks.length, 0)
+  t.truthy(first.stats)
+  t.true(first.stats!.length > 0)
+
+  const second = await encodeSequence({ ...config, pass: 2, statsData: first.stats! }, frameCount)
+  t.is(second.errors.length, 0)
+  t.is(second.chunks.length, frameCount)
+
+  const singleError = Math.abs(single.totalBytes - targetBytes)
+  const twoPassError = Math.abs(second.totalBytes - targetBytes)
+  t.true(
+    twoPassError <= singleError,
+    `two-pass ${second.totalBytes} bytes vs single-pass ${single.totalBytes} bytes (target ${targetBytes})`,
+  )
+})
+
+test('VideoEncoder: pass 2 requires statsData', (t) => {
+  const { encoder } = createTestEncoder()
+
+  t.throws(() => encoder.configure({ ...createEncoderConfig('vp9', 320, 240), pass: 2 }), {
+    name: 'TypeError',
+    message: /statsData/,
+  })
+
+  encoder.close()
+})
+
+test('VideoEncoder: two-pass avc1 encode exchanges x264 statistics', async (t) => {
+  const frameCount = 60
+  const config: VideoEncoderConfig = {
+    ...createEncoderConfig('h264', 320, 240, {
+      bitrate: 200_000,
+      framerate: 30,
+      hardwareAcceleration: 'prefer-software',
+    }),
+    bitrateMode: 'variable',
+  }
+
+  // Pass 1 only analyzes the input
+  const first = await encodeSequence({ ...config, pass: 1 }, frameCount)
+  t.is(first.errors.length, 0)
+  t.is(first.chunks.length, 0)
+  t.truthy(first.stats)
+  // x264 stats start with the options line followed by one line per frame
+  const lines = first.stats!.toString('utf8').trim().split('\n')
+  t.regex(lines[0], /^#options:/)
+  t.is(lines.length, frameCount + 1)
+
+  const second = await encodeSequence({ ...config, pass: 2, statsData: first.stats! }, frameCount)
+  t.is(second.errors.length, 0)
+  t.is(second.chunks.length, frameCount)
+  t.is(second.stats, null)
+  t.is(second.chunks[0].type, 'key')
+})
+
+test('VideoEncoder: two-pass H.264 requires a target bitrate', async (t) => {
+  const { encoder, errors } = createTestEncoder()
+
+  encoder.configure({
+    ...createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'prefer-software' }),
+    bitrateMode: 'quantizer',
+    pass: 1,
+  })
+
+  // Wait for error callback (async due to ThreadsafeFunctionCallMode::NonBlocking)
+  await new Promise((resolve) => setTimeout(resolve, 100))
+
+  t.is(errors.length, 1)
+  t.regex(errors[0].message, /NotSupportedError/)
+  t.is(encoder.state, 'closed')
+})
+
+// ============================================================================
+// Batch Encode Tests
+// ============================================================================
+
+async function encodeAndCollect(submit: (encoder: VideoEncoder, frames: VideoFrame[]) => void) {
+  const { encoder, chunks, errors } = createTestEncoder()
+  encoder.configure(createEncoderConfig('vp8', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+  const frames = generateFrameSequence(320, 240, 20)
+  submit(encoder, frames)
+  const queued = encoder.encodeQueueSize
+  for (const frame of frames) {
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  return {
+    errors,
+    queued,
+    chunks: chunks.map((chunk) => {
+      const data = new Uint8Array(chunk.byteLength)
+      chunk.copyTo(data)
+      return { type: chunk.type, timestamp: chunk.timestamp, data: Buffer.from(data).toString('base64') }
+    }),
+  }
+}
+
+test('VideoEncoder: encodeBatch produces the same chunks as per-frame encode', async (t) => {
+  const keyFrameAt = (i: number) => ({ keyFrame: i % 10 === 0 })
+
+  const oneByOne = await encodeAndCollect((encoder, frames) => {
+    frames.forEach((frame, i) => encoder.encode(frame, keyFrameAt(i)))
+  })
+  const batched = await encodeAndCollect((encoder, frames) =>
+    encoder.encodeBatch(frames, frames.map((_, i) => keyFrameAt(i))),
+  )
+
+  t.is(batched.errors.length, 0)
+  t.is(batched.queued, 20)
+  t.is(batched.chunks.length, 20)
+  t.is(batched.chunks[0].type, 'key')
+  t.is(batched.chunks[10].type, 'key')
+  t.deepEqual(batched.chunks, oneByOne.chunks)
+})
+
+test('VideoEncoder: encodeBatch validates frames and options before enqueueing', (t) => {
+  const { encoder } = createTestEncoder()
+  encoder.configure(createEncoderConfig('vp8', 320, 240))
+  const frames = generateFrameSequence(320, 240, 3)
+
+  t.throws(() => encoder.encodeBatch(frames, [{ keyFrame: true }]), {
+    name: 'TypeError',
+    message: /one entry per frame/,
+  })
+  t.throws(() => encoder.encodeBatch(frames, [null, { maxChunkBytes: 0 }, null]), {
+    name: 'TypeError',
+    message: /maxChunkBytes/,
+  })
+
+  frames[2].close()
+  t.throws(() => encoder.encodeBatch(frames), { name: 'TypeError', message: /closed VideoFrame/ })
+  t.is(encoder.encodeQueueSize, 0)
+
+  for (const frame of frames) {
+    frame.close()
+  }
+  encoder.close()
+})
+
+// ============================================================================
+// Bitrate Summary Tests
+// ============================================================================
+
+test('VideoEncoder: bitrateSummary counts every output chunk', async (t) => {
+  const chunks: EncodedVideoChunk[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk) => chunks.push(chunk),
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure({ codec: 'vp8', width: 320, height: 240, bitrate: 500_000, framerate: 30 })
+
+  for (let i = 0; i < 30; i++) {
+    const frame = generateSolidColorI420Frame(320, 240, TestColors.red, i * 33_333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+
+  const summary = encoder.bitrateSummary()
+  encoder.close()
+
+  t.is(summary.bytes, chunks.reduce((sum, chunk) => sum + chunk.byteLength, 0))
+  t.true(summary.durationUs > 0)
+  t.true(summary.avgBitrate > 0)
+  t.true(summary.peakBitrate1s > 0)
+})
+
+// ============================================================================
+// Quality Metrics Tests
+// ============================================================================
+
+async function encodeWithQuantizer(quantizer: number) {
+  const metadata: EncodedVideoChunkMetadata[] = []
+  const encoder = new VideoEncoder({
+    output: (_chunk, meta) => metadata.push(meta!),
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec: 'avc1.42001f',
+    width: 320,
+    height: 240,
+    framerate: 30,
+    bitrateMode: 'quantizer',
+    hardwareAcceleration: 'prefer-software',
+    computeQualityMetrics: true,
+  })
+  const frames = generateFrameSequence(320, 240, 10, 33_333, 'colorbars')
+  for (const frame of frames) {
+    encoder.encode(frame, { avc: { quantizer } })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return metadata
+}
+
+test('VideoEncoder: computeQualityMetrics reports lower PSNR for higher quantizers', async (t) => {
+  const meanPsnr: number[] = []
+  for (const quantizer of [20, 35, 50]) {
+    const metadata = await encodeWithQuantizer(quantizer)
+    t.is(metadata.length, 10)
+    for (const meta of metadata) {
+      t.truthy(meta.quality)
+      t.true(meta.quality!.ssim > 0 && meta.quality!.ssim <= 1)
+    }
+    meanPsnr.push(metadata.reduce((sum, meta) => sum + meta.quality!.psnr, 0) / metadata.length)
+  }
+
+  t.true(meanPsnr[0] > meanPsnr[1], `PSNR ${meanPsnr.join(' > ')}`)
+  t.true(meanPsnr[1] > meanPsnr[2], `PSNR ${meanPsnr.join(' > ')}`)
+})
+
+test('VideoEncoder: quality metrics are off by default', async (t) => {
+  const metadata: EncodedVideoChunkMetadata[] = []
+  const encoder = new VideoEncoder({
+    output: (_chunk, meta) => metadata.push(meta!),
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(createEncoderConfig('vp8', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+  for (const frame of generateFrameSequence(320, 240, 3)) {
+    encoder.encode(frame)
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  t.is(metadata.length, 3)
+  t.true(metadata.every((meta) => meta.quality === undefined))
+})
+
+// ============================================================================
+// Scene Cut Detection Tests
+// ============================================================================
+
+/** Gradient shot, hard cut to color bars at frame 30, hard cut back at frame 60 */
+function sceneCutFixture(): VideoFrame[] {
+  const gradient = generateFrameSequence(320, 240, 90, 33_333, 'gradient')
+  const bars = generateFrameSequence(320, 240, 90, 33_333, 'colorbars')
+  return gradient.map((frame, n) => {
+    const [keep, drop] = n >= 30 && n < 60 ? [bars[n], frame] : [frame, bars[n]]
+    drop.close()
+    return keep
+  })
+}
+
+async function encodeSceneCutFixture(codec: CodecType, sceneCutDetection?: boolean) {
+  const outputs: Array<{ type: string; timestamp: number; sceneCut?: boolean }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, meta) => outputs.push({ type: chunk.type, timestamp: chunk.timestamp, sceneCut: meta?.sceneCut }),
+    error: (e) => {
+      throw e
+    },
+  })
+  // Quality mode keeps the encoder's long default GOP, so no periodic key frame lands on a cut
+  encoder.configure({
+    ...createEncoderConfig(codec, 320, 240, { hardwareAcceleration: 'prefer-software' }),
+    sceneCutDetection,
+  })
+  for (const frame of sceneCutFixture()) {
+    encoder.encode(frame)
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return outputs
+}
+
+for (const codec of ['h264', 'vp9'] as const) {
+  test(`VideoEncoder: sceneCutDetection places ${codec} key frames at hard cuts`, async (t) => {
+    const outputs = await encodeSceneCutFixture(codec, true)
+    t.is(outputs.length, 90)
+
+    const cuts = outputs.filter((out) => out.sceneCut)
+    t.deepEqual(
+      cuts.map((out) => out.timestamp),
+      [30 * 33_333, 60 * 33_333],
+    )
+    t.true(cuts.every((out) => out.type === 'key'))
+    // The first frame is a key frame anyway and is not reported as a cut
+    const first = outputs.find((out) => out.timestamp === 0)!
+    t.is(first.type, 'key')
+    t.is(first.sceneCut, undefined)
+  })
+}
+
+test('VideoEncoder: scene cuts are not reported by default', async (t) => {
+  const outputs = await encodeSceneCutFixture('h264')
+  t.is(outputs.length, 90)
+  t.true(outputs.every((out) => out.sceneCut === undefined))
+})
+
+// ============================================================================
+// Unsupported Input Format Tests
+// ============================================================================
+
+function createI444P12Frame(timestamp: number) {
+  const samples = new Uint16Array(320 * 240 * 3).fill(2048)
+  return new VideoFrame(new Uint8Array(samples.buffer), {
+    format: 'I444P12',
+    codedWidth: 320,
+    codedHeight: 240,
+    timestamp,
+  })
+}
+
+test('VideoEncoder: 12-bit frame to an 8-bit encoder reports NotSupportedError', async (t) => {
+  const { encoder, errors } = createTestEncoder()
+  encoder.configure({
+    codec: 'avc1.42001f',
+    width: 320,
+    height: 240,
+    hardwareAcceleration: 'prefer-software',
+  })
+
+  const frame = createI444P12Frame(0)
+  encoder.encode(frame)
+  frame.close()
+  await encoder.flush().catch(() => {})
+
+  t.is(errors.length, 1)
+  t.true(errors[0].message.startsWith('NotSupportedError'), errors[0].message)
+  t.true(errors[0].message.includes('I444P12'), errors[0].message)
+  t.true(errors[0].message.includes('avc1.42001f'), errors[0].message)
+  t.true(errors[0].message.includes('12-bit samples would be truncated to 8 bits'), errors[0].message)
+  t.is(encoder.state, 'closed')
+})
+
+test('VideoEncoder: skipUnsupportedFrames drops the frame and keeps encoding', async (t) => {
+  const { encoder, chunks, errors } = createTestEncoder()
+  encoder.configure({
+    codec: 'avc1.42001f',
+    width: 320,
+    height: 240,
+    hardwareAcceleration: 'prefer-software',
+    skipUnsupportedFrames: true,
+  })
+
+  const unsupported = createI444P12Frame(0)
+  encoder.encode(unsupported)
+  unsupported.close()
+  const frame = generateSolidColorI420Frame(320, 240, TestColors.red, 33_333)
+  encoder.encode(frame)
+  frame.close()
+  await encoder.flush()
+
+  t.is(errors.length, 1)
+  t.true(errors[0].message.startsWith('NotSupportedError'))
+  t.is(encoder.state, 'configured')
+  t.is(chunks.length, 1)
+  encoder.close()
+})
+
+test('getCodecCapabilities lists the formats an 8-bit encoder accepts', (t) => {
+  const capabilities = getCodecCapabilities('avc1.42001f')
+  t.is(capabilities.encoderFormat, 'I420')
+  t.is(capabilities.bitDepth, 8)
+  t.true(capabilities.inputFormats.includes('I420'))
+  t.true(capabilities.inputFormats.includes('NV12'))
+  t.false(capabilities.inputFormats.includes('I444P12'))
+  t.throws(() => getCodecCapabilities('not-a-codec'), { message: /NotSupportedError/ })
+})
+
+/** 10-bit 4:2:2 frame with a horizontal luma ramp and neutral chroma */
+function createI422P10Frame(timestamp: number) {
+  const luma = 320 * 240
+  const samples = new Uint16Array(luma * 2)
+  for (let i = 0; i < luma; i++) {
+    samples[i] = 64 + (i % 320) * 2
+  }
+  samples.fill(512, luma)
+  return new VideoFrame(new Uint8Array(samples.buffer), {
+    format: 'I422P10',
+    codedWidth: 320,
+    codedHeight: 240,
+    timestamp,
+  })
+}
+
+test('VideoEncoder: HEVC Main 4:2:2 10 keeps 4:2:2 10-bit through encode and decode', async (t) => {
+  // Main 4:2:2 10: Range Extensions profile with max_12bit, max_10bit and max_422chroma set
+  const codec = 'hvc1.4.10.L120.9D.08'
+  const config = { codec, width: 320, height: 240, hardwareAcceleration: 'prefer-software' as const }
+  t.true((await VideoEncoder.isConfigSupported(config)).supported)
+  t.is(getCodecCapabilities(codec).encoderFormat, 'I422P10')
+
+  const outputs: Array<{ chunk: EncodedVideoChunk; metadata?: EncodedVideoChunkMetadata }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, metadata) => {
+      outputs.push({ chunk, metadata })
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure(config)
+  for (let i = 0; i < 5; i++) {
+    const frame = createI422P10Frame(i * 33_333)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+
+  const decoderConfig = outputs[0].metadata?.decoderConfig
+  t.truthy(decoderConfig)
+  const formats: Array<string | null> = []
+  const decoder = new VideoDecoder({
+    output: (frame) => {
+      formats.push(frame.format)
+      frame.close()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  decoder.configure(decoderConfig!)
+  for (const { chunk } of outputs) {
+    decoder.decode(chunk)
+  }
+  await decoder.flush()
+  decoder.close()
+
+  t.is(formats.length, 5)
+  t.true(formats.every((format) => format === 'I422P10'))
+})
+
+test('VideoEncoder: 4:4:4 AV1 and HEVC profiles select 4:4:4 encoder formats', (t) => {
+  // AV1 High, 10-bit, chroma subsampling 000 (4:4:4)
+  t.is(getCodecCapabilities('av01.1.08M.10.0.000').encoderFormat, 'I444P10')
+  // HEVC Main 4:4:4 (8-bit)
+  t.is(getCodecCapabilities('hvc1.4.10.L120.9E.08').encoderFormat, 'I444')
+  // 4:2:0 profiles keep the default format
+  t.is(getCodecCapabilities('hvc1.1.6.L93.B0').encoderFormat, 'I420')
+})
+
+// ============================================================================
+// Queue Stats Tests
+// ============================================================================
+
+test('VideoEncoder: queueStats is empty before any encode', (t) => {
+  const { encoder } = createTestEncoder()
+
+  t.deepEqual(encoder.queueStats(), {
+    samples: 0,
+    queueWaitP50: 0,
+    queueWaitP95: 0,
+    processingP50: 0,
+    processingP95: 0,
+  })
+
+  encoder.close()
+})
+
+test('VideoEncoder: queueStats reports percentiles after encoding 100 frames', async (t) => {
+  const { encoder, errors } = createTestEncoder()
+  encoder.configure(createEncoderConfig('vp8', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+
+  for (const frame of generateFrameSequence(320, 240, 100)) {
+    encoder.encode(frame)
+    frame.close()
+  }
+  await encoder.flush()
+
+  const stats = encoder.queueStats()
+  t.is(errors.length, 0)
+  t.is(stats.samples, 100)
+  t.true(stats.processingP50 > 0)
+  t.true(stats.processingP95 >= stats.processingP50)
+  t.true(stats.queueWaitP95 >= stats.queueWaitP50)
+
+  encoder.reset()
+  t.is(encoder.queueStats().samples, 0)
+
+  encoder.close()
+})
+
+test('VideoEncoder: queueStats queue wait grows when frames arrive faster than the worker', async (t) => {
+  const config = createEncoderConfig('vp8', 640, 480, { hardwareAcceleration: 'prefer-software' })
+  const frameCount = 30
+
+  // Paced: wait for the worker to drain before each encode
+  const paced = createTestEncoder()
+  paced.encoder.configure(config)
+  for (const frame of generateFrameSequence(640, 480, frameCount)) {
+    paced.encoder.encode(frame)
+    frame.close()
+    while (paced.encoder.encodeQueueSize > 0) {
+      await new Promise((resolve) => setTimeout(resolve, 1))
+    }
+  }
+  await paced.encoder.flush()
+
+  // Burst: queue every frame at once
+  const burst = createTestEncoder()
+  burst.encoder.configure(config)
+  for (const frame of generateFrameSequence(640, 480, frameCount)) {
+    burst.encoder.encode(frame)
+    frame.close()
+  }
+  await burst.encoder.flush()
+
+  const pacedStats = paced.encoder.queueStats()
+  const burstStats = burst.encoder.queueStats()
+  t.is(pacedStats.samples, frameCount)
+  t.is(burstStats.samples, frameCount)
+  t.true(
+    burstStats.queueWaitP95 > pacedStats.queueWaitP95,
+    `burst p95 ${burstStats.queueWaitP95}ms vs paced p95 ${pacedStats.queueWaitP95}ms`,
+  )
+
+  paced.encoder.close()
+  burst.encoder.close()
+})
+
+// ============================================================================
+// Encoder Parameter Validation Tests
+// ============================================================================
+
+test('VideoEncoder: HEVC picture larger than the codec string level is not supported', async (t) => {
+  // Level 2.0 allows at most 991x991
+  const config = { codec: 'hev1.1.6.L60.90', width: 1280, height: 720, bitrate: 1_000_000 }
+
+  const support = await VideoEncoder.isConfigSupported(config)
+  t.false(support.supported)
+
+  const { encoder, errors } = createTestEncoder()
+  encoder.configure(config)
+
+  // Wait for error callback (async due to ThreadsafeFunctionCallMode::NonBlocking)
+  await new Promise((resolve) => setTimeout(resolve, 100))
+
+  t.is(errors.length, 1)
+  t.regex(errors[0].message, /NotSupportedError: 1280x720 exceeds the maximum picture size of level 2\.0/)
+  t.is(encoder.state, 'closed')
+})
+
+test('VideoEncoder: AV1 picture larger than the codec string level is not supported', async (t) => {
+  // Level 2.0 allows at most 2048x1152
+  const support = await VideoEncoder.isConfigSupported({
+    codec: 'av01.0.00M.08',
+    width: 2560,
+    height: 1440,
+    bitrate: 2_000_000,
+  })
+  t.false(support.supported)
+})
+
+/** Smallest-picture cases per software encoder: [codec, width, height, supported] */
+const TINY_PICTURE_CASES: Array<[string, number, number, boolean]> = [
+  // libx264 only needs 4:2:0 chroma alignment
+  ['avc1.42001f', 2, 2, true],
+  ['avc1.42001f', 16, 2, true],
+  ['avc1.42001f', 1, 1, false],
+  ['avc1.42001f', 3, 2, false],
+  ['avc1.42001f', 16, 15, false],
+  // libx265 needs one 32x32 CTU
+  ['hev1.1.6.L93.B0', 32, 32, true],
+  ['hev1.1.6.L93.B0', 16, 16, false],
+  ['hev1.1.6.L93.B0', 64, 2, false],
+  ['hev1.1.6.L93.B0', 33, 32, false],
+  // libvpx and libaom accept any size
+  ['vp8', 1, 1, true],
+  ['vp8', 3, 3, true],
+  ['vp8', 16, 2, true],
+  ['vp09.00.10.08', 1, 1, true],
+  ['vp09.00.10.08', 3, 5, true],
+  ['av01.0.04M.08', 2, 2, true],
+  ['av01.0.04M.08', 16, 2, true],
+]
+
+/** Mid-grey I420 frame of any size, including odd ones */
+function createGreyI420Frame(width: number, height: number) {
+  const chroma = Math.ceil(width / 2) * Math.ceil(height / 2)
+  return new VideoFrame(new Uint8Array(width * height + chroma * 2).fill(128), {
+    format: 'I420',
+    codedWidth: width,
+    codedHeight: height,
+    timestamp: 0,
+  })
+}
+
+for (const [codec, width, height, supported] of TINY_PICTURE_CASES) {
+  test(`VideoEncoder: ${codec} at ${width}x${height} is ${supported ? 'encoded' : 'rejected'}`, async (t) => {
+    const config = { codec, width, height, hardwareAcceleration: 'prefer-software' as const }
+    t.is((await VideoEncoder.isConfigSupported(config)).supported, supported)
+
+    const { encoder, chunks, errors } = createTestEncoder()
+    encoder.configure(config)
+    if (!supported) {
+      // Wait for error callback (async due to ThreadsafeFunctionCallMode::NonBlocking)
+      await new Promise((resolve) => setTimeout(resolve, 100))
+      t.is(errors.length, 1)
+      t.regex(errors[0].message, new RegExp(`NotSupportedError: .*${width}x${height}`))
+      return
+    }
+    const frame = createGreyI420Frame(width, height)
+    encoder.encode(frame, { keyFrame: true })
+    frame.close()
+    await encoder.flush()
+    encoder.close()
+    t.deepEqual(errors, [])
+    t.true(chunks.length > 0)
+    t.is(chunks[0].type, 'key')
+  })
+}
+
+test('VideoEncoder: no-preference encodes pictures below the hardware minimum in software', async (t) => {
+  const { encoder, chunks, errors } = createTestEncoder()
+  encoder.configure({ codec: 'avc1.42001f', width: 16, height: 16 })
+  const frame = createGreyI420Frame(16, 16)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+  encoder.close()
+  t.deepEqual(errors, [])
+  t.is(chunks.length, 1)
+})
+
+// ============================================================================
+// Default FFmpeg Options Tests
+// ============================================================================
+
+async function encodeX264Info(config: VideoEncoderConfig): Promise<string> {
+  const { encoder, chunks, errors } = createTestEncoder()
+  encoder.configure(config)
+  const frame = generateSolidColorI420Frame(320, 240, TestColors.red, 0)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+  encoder.close()
+  if (errors.length > 0) {
+    throw errors[0]
+  }
+  const data = new Uint8Array(chunks[0].byteLength)
+  chunks[0].copyTo(data)
+  return Buffer.from(data).toString('latin1')
+}
+
+// Serial: the defaults are process-wide and would leak into concurrent tests
+test.serial('VideoEncoder: setDefaultEncoderOptions reaches the codec context', async (t) => {
+  setDefaultEncoderOptions({ video: { threads: 3, 'not-an-option': 'x' } })
+  t.teardown(() => setDefaultEncoderOptions({}))
+
+  t.deepEqual(getDefaultEncoderOptions(), {
+    video: { threads: '3', 'not-an-option': 'x' },
+    audio: {},
+  })
+
+  const info = await encodeX264Info(
+    createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'prefer-software' }),
+  )
+  t.true(info.includes('threads=3'))
+})
+
+test.serial('VideoEncoder: ffmpegOptions override setDefaultEncoderOptions', async (t) => {
+  setDefaultEncoderOptions({ video: { threads: 3 } })
+  t.teardown(() => setDefaultEncoderOptions({}))
+
+  const info = await encodeX264Info({
+    ...createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'prefer-software' }),
+    ffmpegOptions: { threads: 2 },
+  })
+  t.true(info.includes('threads=2'))
+  t.false(info.includes('threads=3'))
+})
+
+test.serial('VideoEncoder: setDefaultEncoderOptions does not affect configured encoders', async (t) => {
+  const { encoder, chunks, errors } = createTestEncoder()
+  await encoder.configureAsync(createEncoderConfig('h264', 320, 240, { hardwareAcceleration: 'prefer-software' }))
+
+  setDefaultEncoderOptions({ video: { threads: 3 } })
+  t.teardown(() => setDefaultEncoderOptions({}))
+
+  const frame = generateSolidColorI420Frame(320, 240, TestColors.red, 0)
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+  encoder.close()
+
+  t.is(errors.length, 0)
+  const data = new Uint8Array(chunks[0].byteLength)
+  chunks[0].copyTo(data)
+  t.false(Buffer.from(data).toString('latin1').includes('threads=3'))
+})
+
+// ============================================================================
+// Default Bitrate Tests
+// ============================================================================
+
+test('VideoEncoder: default bitrate scales with resolution and framerate', async (t) => {
+  const small = await VideoEncoder.isConfigSupported({ codec: 'avc1.42001E', width: 320, height: 240 })
+  const large = await VideoEncoder.isConfigSupported({
+    codec: 'hvc1.1.6.L153.B0',
+    width: 3840,
+    height: 2160,
+    framerate: 60,
+  })
+
+  const smallBitrate = small.config.bitrate!
+  const largeBitrate = large.config.bitrate!
+  t.true(smallBitrate >= 100_000 && smallBitrate < 1_000_000, `240p default ${smallBitrate}`)
+  t.true(largeBitrate > 10_000_000, `2160p60 default ${largeBitrate}`)
+
+  const fast = await VideoEncoder.isConfigSupported({ codec: 'avc1.42001E', width: 320, height: 240, framerate: 60 })
+  t.true(fast.config.bitrate! > smallBitrate)
+})
+
+test('VideoEncoder: explicit bitrate and quantizer mode are not given a default bitrate', async (t) => {
+  const explicit = await VideoEncoder.isConfigSupported({
+    codec: 'avc1.42001E',
+    width: 3840,
+    height: 2160,
+    bitrate: 123_456,
+  })
+  t.is(explicit.config.bitrate, 123_456)
+
+  const quantizer = await VideoEncoder.isConfigSupported({
+    codec: 'vp09.00.10.08',
+    width: 640,
+    height: 480,
+    bitrateMode: 'quantizer',
+  })
+  t.is(quantizer.config.bitrate, undefined)
+})
+
+test('VideoEncoder: activeConfiguration reflects the default bitrate', async (t) => {
+  const { encoder } = createTestEncoder()
+  t.is(encoder.activeConfiguration, null)
+
+  encoder.configure({ codec: 'vp8', width: 320, height: 240, hardwareAcceleration: 'prefer-software' })
+  const support = await VideoEncoder.isConfigSupported({ codec: 'vp8', width: 320, height: 240 })
+  t.is(encoder.activeConfiguration?.bitrate, support.config.bitrate)
+
+  encoder.configure({ codec: 'vp8', width: 320, height: 240, bitrate: 250_000 })
+  t.is(encoder.activeConfiguration?.bitrate, 250_000)
+
+  encoder.close()
+})
+
+// ============================================================================
+// Output Buffer Tests
+// ============================================================================
+
+/** Walk 4-byte length prefixes, returning the NAL count or -1 if they don't add up */
+function countAvccNals(data: Uint8Array): number {
+  const view = new DataView(data.buffer, data.byteOffset, data.byteLength)
+  let offset = 0
+  let count = 0
+  while (offset + 4 <= data.byteLength) {
+    offset += 4 + view.getUint32(offset)
+    count++
+  }
+  return offset === data.byteLength ? count : -1
+}
+
+test('VideoEncoder: 1000-frame AVC encode outputs valid length-prefixed chunks', async (t) => {
+  const chunks: EncodedVideoChunk[] = []
+  const encoder = new VideoEncoder({
+    output: (chunk) => chunks.push(chunk),
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 160,
+    height: 120,
+    bitrate: 200_000,
+    framerate: 60,
+    latencyMode: 'realtime',
+    hardwareAcceleration: 'prefer-software',
+  })
+
+  const start = performance.now()
+  for (let i = 0; i < 1000; i++) {
+    const color = i % 2 === 0 ? TestColors.red : TestColors.blue
+    const frame = generateSolidColorI420Frame(160, 120, color, Math.round((i * 1_000_000) / 60))
+    encoder.encode(frame, { keyFrame: i % 60 === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  const stats = encoder.outputStats()
+  encoder.close()
+  t.log(`1000 frames encoded in ${(performance.now() - start).toFixed(1)}ms`)
+
+  t.is(chunks.length, 1000)
+  // x264 output is converted inside its own packet buffer, never copied
+  t.is(stats.rewrittenChunks, 1000)
+  t.is(stats.copiedChunks, 0)
+  t.is(stats.copiedBytes, 0)
+  for (const chunk of chunks) {
+    const data = new Uint8Array(chunk.byteLength)
+    chunk.copyTo(data)
+    t.true(countAvccNals(data) > 0, `chunk at ${chunk.timestamp} is not length-prefixed`)
+  }
+  t.is(chunks[0].type, 'key')
+})
+
+test('VideoEncoder: encode() from inside the output callback keeps order', async (t) => {
+  const FRAMES = 100
+  const timestamps: number[] = []
+  let submitted = 0
+  const encodeNext = () => {
+    const frame = generateSolidColorI420Frame(64, 48, TestColors.green, submitted * 33_333)
+    submitted++
+    encoder.encode(frame, { keyFrame: submitted === 1 })
+    frame.close()
+  }
+  const encoder = new VideoEncoder({
+    output: (chunk) => {
+      timestamps.push(chunk.timestamp)
+      // Each output submits the next frame, reentering the encoder
+      if (submitted < FRAMES) encodeNext()
+    },
+    error: (e) => t.fail(e.message),
+  })
+  encoder.configure({
+    codec: 'avc1.42001E',
+    width: 64,
+    height: 48,
+    framerate: 30,
+    latencyMode: 'realtime',
+    hardwareAcceleration: 'prefer-software',
+  })
+
+  encodeNext()
+  // Outputs held back by the encoder are released by flush(), whose resolver
+  // runs the output callback (and so the reentrant encodes) synchronously
+  while (timestamps.length < FRAMES) {
+    await encoder.flush()
+  }
+  encoder.close()
+
+  t.is(submitted, FRAMES)
+  t.deepEqual(timestamps, Array.from({ length: FRAMES }, (_, n) => n * 33_333))
+})
+
+// ============================================================================
+// Parameter Set Change Tests
+// ============================================================================
+
+async function encodeWithLevelChange(t: ExecutionContext, format: 'avc' | 'annexb') {
+  const configs: NonNullable<EncodedVideoChunkMetadata['decoderConfig']>[] = []
+  const encoder = new VideoEncoder({
+    output: (_chunk, metadata) => {
+      if (metadata?.decoderConfig) configs.push(metadata.decoderConfig)
+    },
+    error: (e) => t.fail(e.message),
+  })
+  const configure = (level: number) =>
+    encoder.configure({
+      codec: 'avc1.42001E',
+      width: 64,
+      height: 48,
+      framerate: 30,
+      hardwareAcceleration: 'prefer-software',
+      avc: { format },
+      ffmpegOptions: { level },
+    })
+
+  configure(30)
+  for (let i = 0; i < 10; i++) {
+    const frame = generateSolidColorI420Frame(64, 48, TestColors.green, i * 33_333)
+    encoder.encode(frame, { keyFrame: i % 5 === 0 })
+    frame.close()
+  }
+  // The SPS level changes mid-stream
+  configure(31)
+  for (let i = 10; i < 20; i++) {
+    const frame = generateSolidColorI420Frame(64, 48, TestColors.green, i * 33_333)
+    encoder.encode(frame, { keyFrame: i % 5 === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return configs
+}
+
+test('VideoEncoder: a new SPS delivers a second decoderConfig with a fresh description', async (t) => {
+  const configs = await encodeWithLevelChange(t, 'avc')
+
+  // Repeated key frames with unchanged parameter sets don't repeat the config
+  t.is(configs.length, 2)
+  // avcC byte 3 is AVCLevelIndication
+  t.is(configs[0].description?.[3], 30)
+  t.is(configs[1].description?.[3], 31)
+})
+
+test('VideoEncoder: Annex B streams get a decoderConfig per parameter set change', async (t) => {
+  const configs = await encodeWithLevelChange(t, 'annexb')
+  t.is(configs.length, 2)
+})
+
+// ============================================================================
+// Rate Control Stats Tests
+// ============================================================================
+
+/** I420 frame of seeded noise, which no encoder can squeeze below its bit budget */
+function createNoiseFrame(width: number, height: number, timestamp: number, seed: number) {
+  const data = new Uint8Array(calculateI420Size(width, height))
+  let state = seed
+  for (let i = 0; i < data.length; i++) {
+    state = (state * 1103515245 + 12345) >>> 0
+    data[i] = state >>> 24
+  }
+  return new VideoFrame(data, { format: 'I420', codedWidth: width, codedHeight: height, timestamp, duration: 33_333 })
+}
+
+async function encodeNoise(reportRateControlStats?: boolean) {
+  const outputs: Array<{ byteLength: number; rateControl?: RateControlStats }> = []
+  const encoder = new VideoEncoder({
+    output: (chunk, meta) => outputs.push({ byteLength: chunk.byteLength, rateControl: meta?.rateControl }),
+    error: (e) => {
+      throw e
+    },
+  })
+  encoder.configure({
+    codec: 'avc1.42001f',
+    width: 320,
+    height: 240,
+    bitrate: 1_000_000,
+    framerate: 30,
+    bitrateMode: 'constant',
+    hardwareAcceleration: 'prefer-software',
+    reportRateControlStats,
+  })
+  for (let i = 0; i < 120; i++) {
+    const frame = createNoiseFrame(320, 240, i * 33_333, i + 1)
+    encoder.encode(frame, { keyFrame: i === 0 })
+    frame.close()
+  }
+  await encoder.flush()
+  encoder.close()
+  return outputs
+}
+
+test('VideoEncoder: reportRateControlStats tracks CBR actual bits against the configured bitrate', async (t) => {
+  const outputs = await encodeNoise(true)
+  t.is(outputs.length, 120)
+
+  for (const { byteLength, rateControl } of outputs) {
+    t.truthy(rateControl)
+    t.is(rateControl!.actualBits, byteLength * 8)
+    t.true(rateControl!.qp! > 0)
+    // libx264 does not report a per-picture CPB delay
+    t.false('targetBits' in rateControl!)
+    t.is(rateControl!.bufferFullness, undefined)
+  }
+
+  // After the first second, every 1-second window lands near the 1 Mbit/s bitrate
+  for (let start = 30; start + 30 <= outputs.length; start += 30) {
+    const window = outputs.slice(start, start + 30).map((out) => out.rateControl!)
+    const actual = window.reduce((sum, rc) => sum + rc.actualBits, 0)
+    t.true(Math.abs(actual / 1_000_000 - 1) < 0.35, `window at frame ${start}: ${actual} bits`)
+  }
+})
+
+test('VideoEncoder: rate control stats are not reported by default', async (t) => {
+  const outputs = await encodeNoise()
+  t.true(outputs.every((out) => out.rateControl === undefined))
+})
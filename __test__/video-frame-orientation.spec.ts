@@ -555,3 +555,87 @@ test('Roundtrip: rotation preserved through encode/decode cycle', async (t) => {
 
   decodedFrames.forEach((f) => f.close())
 })
+
+// ============================================================================
+// VideoEncoder applyRotation Tests
+// ============================================================================
+
+/**
+ * Encode a 64x32 frame whose left half is bright and right half dark with rotation 90,
+ * decode it, and return the decoded frame's size, luma plane and the encoder's rotation metadata
+ */
+async function encodeRotatedFrame(applyRotation: boolean) {
+  const width = 64
+  const height = 32
+  const data = new Uint8Array((width * height * 3) / 2).fill(128)
+  for (let y = 0; y < height; y++) {
+    data.fill(235, y * width, y * width + width / 2)
+    data.fill(16, y * width + width / 2, (y + 1) * width)
+  }
+  const frame = new VideoFrame(data, {
+    format: 'I420',
+    codedWidth: width,
+    codedHeight: height,
+    timestamp: 0,
+    rotation: 90,
+  } as VideoFrameBufferInitWithOrientation)
+
+  const chunks: EncodedVideoChunk[] = []
+  let rotation: number | undefined
+  const encoder = new VideoEncoder({
+    output: (chunk, meta) => {
+      chunks.push(chunk)
+      if (meta?.decoderConfig) rotation = meta.decoderConfig.rotation
+    },
+    error: (e) => {
+      throw e
+    },
+  })
+  // With applyRotation the configured size is the upright picture's
+  encoder.configure({
+    codec: 'vp8',
+    width: applyRotation ? height : width,
+    height: applyRotation ? width : height,
+    bitrate: 1_000_000,
+    applyRotation,
+  })
+  encoder.encode(frame, { keyFrame: true })
+  frame.close()
+  await encoder.flush()
+  encoder.close()
+
+  const decoded: VideoFrame[] = []
+  const decoder = new VideoDecoder({
+    output: (f) => decoded.push(f),
+    error: (e) => {
+      throw e
+    },
+  })
+  decoder.configure({ codec: 'vp8' })
+  for (const chunk of chunks) decoder.decode(chunk)
+  await decoder.flush()
+  decoder.close()
+
+  const output = decoded[0]
+  const luma = new Uint8Array(output.allocationSize())
+  await output.copyTo(luma)
+  const result = { width: output.displayWidth, height: output.displayHeight, luma, rotation }
+  decoded.forEach((f) => f.close())
+  return result
+}
+
+test('VideoEncoder: applyRotation bakes rotation into the encoded pixels', async (t) => {
+  const passthrough = await encodeRotatedFrame(false)
+  t.is(passthrough.rotation, 90)
+  t.deepEqual([passthrough.width, passthrough.height], [64, 32])
+  // Bright left half, dark right half, as in the source
+  t.true(passthrough.luma[16 * 64 + 8] > 200)
+  t.true(passthrough.luma[16 * 64 + 56] < 50)
+
+  const baked = await encodeRotatedFrame(true)
+  t.falsy(baked.rotation)
+  t.deepEqual([baked.width, baked.height], [32, 64])
+  // Rotated 90 degrees clockwise, the bright left half ends up on top
+  t.true(baked.luma[8 * 32 + 16] > 200)
+  t.true(baked.luma[56 * 32 + 16] < 50)
+})
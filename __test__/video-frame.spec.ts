@@ -328,3 +328,230 @@ test('VideoFrame: gradient pattern creates varied data', async (t) => {
 
   frame.close()
 })
+
+// ============================================================================
+// Conversion Quality Tests
+// ============================================================================
+
+/** Shallow 10-bit luma ramp (one 10-bit step per 2 pixels) with neutral chroma */
+function create10BitRampFrame(width: number, height: number): VideoFrame {
+  const luma = width * height
+  const chroma = (width / 2) * (height / 2)
+  const samples = new Uint16Array(luma + chroma * 2)
+  for (let y = 0; y < height; y++) {
+    for (let x = 0; x < width; x++) {
+      samples[y * width + x] = 256 + (x >> 1)
+    }
+  }
+  samples.fill(512, luma)
+  return new VideoFrame(new Uint8Array(samples.buffer), {
+    format: 'I420P10',
+    codedWidth: width,
+    codedHeight: height,
+    timestamp: 0,
+  })
+}
+
+/** Count runs of 4+ consecutive columns whose red channel averages are identical */
+function countBandingPlateaus(rgba: Uint8Array, width: number, height: number): number {
+  const columnMeans: number[] = []
+  for (let x = 0; x < width; x++) {
+    let sum = 0
+    for (let y = 0; y < height; y++) {
+      sum += rgba[(y * width + x) * 4]
+    }
+    columnMeans.push(sum / height)
+  }
+  let plateaus = 0
+  let run = 1
+  for (let x = 1; x <= width; x++) {
+    if (x < width && columnMeans[x] === columnMeans[x - 1]) {
+      run++
+    } else {
+      if (run >= 4) plateaus++
+      run = 1
+    }
+  }
+  return plateaus
+}
+
+function meanRed(rgba: Uint8Array): number {
+  let sum = 0
+  for (let i = 0; i < rgba.length; i += 4) {
+    sum += rgba[i]
+  }
+  return sum / (rgba.length / 4)
+}
+
+test('VideoFrame: copyTo() dithering removes banding from 10-bit to RGBA conversion', async (t) => {
+  const width = 512
+  const height = 16
+  const frame = create10BitRampFrame(width, height)
+
+  const plain = new Uint8Array(width * height * 4)
+  await frame.copyTo(plain, { format: 'RGBA' })
+  const plainPlateaus = countBandingPlateaus(plain, width, height)
+  t.true(plainPlateaus > 20, `undithered ramp should band, got ${plainPlateaus} plateaus`)
+
+  for (const dither of ['bayer', 'error-diffusion'] as const) {
+    const dithered = new Uint8Array(width * height * 4)
+    await frame.copyTo(dithered, { format: 'RGBA', dither })
+    const plateaus = countBandingPlateaus(dithered, width, height)
+    t.true(plateaus <= plainPlateaus / 4, `${dither}: ${plateaus} plateaus vs ${plainPlateaus} undithered`)
+    // Dithering redistributes rounding error without shifting the overall level
+    t.true(Math.abs(meanRed(dithered) - meanRed(plain)) < 0.5, `${dither} shifted the mean level`)
+  }
+
+  frame.close()
+})
+
+test('VideoFrame: copyTo() dither to BGRA keeps channel order', async (t) => {
+  const width = 64
+  const height = 8
+  const frame = create10BitRampFrame(width, height)
+
+  const rgba = new Uint8Array(width * height * 4)
+  const bgra = new Uint8Array(width * height * 4)
+  await frame.copyTo(rgba, { format: 'RGBA', dither: 'bayer' })
+  await frame.copyTo(bgra, { format: 'BGRA', dither: 'bayer' })
+  for (let i = 0; i < rgba.length; i += 4) {
+    t.is(bgra[i], rgba[i + 2])
+    t.is(bgra[i + 2], rgba[i])
+    t.is(bgra[i + 3], 255)
+  }
+
+  frame.close()
+})
+
+test('VideoFrame: copyTo() quality options default to the plain conversion', async (t) => {
+  const frame = generateGradientI420Frame(320, 240, 0)
+  const size = 320 * 240 * 4
+
+  const plain = new Uint8Array(size)
+  const explicit = new Uint8Array(size)
+  await frame.copyTo(plain, { format: 'RGBA' })
+  await frame.copyTo(explicit, { format: 'RGBA', scaleAlgorithm: 'bilinear', dither: 'none' })
+  t.deepEqual(explicit, plain)
+
+  // Dithering only applies to >8-bit sources
+  const dithered = new Uint8Array(size)
+  await frame.copyTo(dithered, { format: 'RGBA', dither: 'error-diffusion' })
+  t.deepEqual(dithered, plain)
+
+  frame.close()
+})
+
+test('VideoFrame: copyTo() accepts swscale quality flags and chroma location', async (t) => {
+  const frame = generateGradientI420Frame(320, 240, 0)
+  const size = 320 * 240 * 4
+
+  const plain = new Uint8Array(size)
+  await frame.copyTo(plain, { format: 'RGBA' })
+
+  const tuned = new Uint8Array(size)
+  await frame.copyTo(tuned, {
+    format: 'RGBA',
+    scaleAlgorithm: 'lanczos',
+    chromaLocation: 'top-left',
+    accurateRounding: true,
+    fullChromaInterpolation: true,
+  })
+  // Same picture within rounding/interpolation differences
+  let maxDiff = 0
+  for (let i = 0; i < size; i++) {
+    maxDiff = Math.max(maxDiff, Math.abs(tuned[i] - plain[i]))
+  }
+  t.true(maxDiff < 32, `max channel difference ${maxDiff}`)
+
+  frame.close()
+})
+
+// ============================================================================
+// Frame Clone Constructor Tests
+// ============================================================================
+
+test('VideoFrame: clone constructor with a smaller visibleRect leaves the parent untouched', async (t) => {
+  const parent = generateGradientI420Frame(64, 48, 0)
+  const rect = { x: 16, y: 8, width: 32, height: 16 }
+
+  const cropped = new VideoFrame(parent, { timestamp: 0, visibleRect: rect })
+
+  t.is(cropped.codedWidth, 64)
+  t.is(cropped.codedHeight, 48)
+  t.is(cropped.visibleRect?.width, 32)
+  t.is(cropped.visibleRect?.height, 16)
+  t.is(cropped.displayWidth, 32)
+  t.is(cropped.displayHeight, 16)
+  t.is(cropped.allocationSize(), 32 * 16 * 1.5)
+
+  const fromClone = new Uint8Array(cropped.allocationSize())
+  await cropped.copyTo(fromClone)
+  const fromParent = new Uint8Array(parent.allocationSize({ rect }))
+  await parent.copyTo(fromParent, { rect })
+  t.deepEqual(fromClone, fromParent)
+
+  // Parent keeps its full window
+  t.is(parent.visibleRect?.width, 64)
+  t.is(parent.displayWidth, 64)
+  t.is(parent.allocationSize(), calculateI420Size(64, 48))
+
+  cropped.close()
+  parent.close()
+})
+
+test('VideoFrame: constructing from a frame scales the display size and follows rotation', (t) => {
+  const parent = generateSolidColorRGBAFrame(8, 4, TestColors.red, 0)
+
+  const rotated = new VideoFrame(parent, { timestamp: 0, rotation: 90 })
+  t.is(rotated.displayWidth, 4)
+  t.is(rotated.displayHeight, 8)
+
+  // The visible rect is in coded orientation; display size stays rotated
+  const cropped = new VideoFrame(rotated, { timestamp: 0, visibleRect: { x: 0, y: 0, width: 4, height: 2 } })
+  t.is(cropped.rotation, 90)
+  t.is(cropped.displayWidth, 2)
+  t.is(cropped.displayHeight, 4)
+
+  cropped.close()
+  rotated.close()
+  parent.close()
+})
+
+test('VideoFrame: constructing from a frame validates visibleRect and display size', (t) => {
+  const parent = generateSolidColorI420Frame(64, 48, TestColors.green, 0)
+
+  t.throws(() => new VideoFrame(parent, { timestamp: 0, visibleRect: { x: 1, y: 0, width: 32, height: 16 } }), {
+    message: /not aligned/,
+  })
+  t.throws(() => new VideoFrame(parent, { timestamp: 0, visibleRect: { x: 48, y: 0, width: 32, height: 16 } }), {
+    message: /exceeds codedWidth/,
+  })
+  t.throws(() => new VideoFrame(parent, { timestamp: 0, displayWidth: 100 }), {
+    message: /must be specified together/,
+  })
+
+  parent.close()
+})
+
+test('VideoFrame: alpha discard switches the format while sharing the parent pixels', async (t) => {
+  const width = 4
+  const height = 2
+  const data = new Uint8Array(width * height * 4)
+  for (let i = 0; i < data.length; i++) {
+    data[i] = i * 3
+  }
+  const parent = new VideoFrame(data, { format: 'RGBA', codedWidth: width, codedHeight: height, timestamp: 0 })
+
+  const opaque = new VideoFrame(parent, { timestamp: 0, alpha: 'discard' })
+  t.is(opaque.format, 'RGBX')
+  t.is(parent.format, 'RGBA')
+
+  const copied = new Uint8Array(opaque.allocationSize())
+  await opaque.copyTo(copied)
+  for (let i = 0; i < copied.length; i += 4) {
+    t.deepEqual(Array.from(copied.subarray(i, i + 3)), Array.from(data.subarray(i, i + 3)))
+  }
+
+  opaque.close()
+  parent.close()
+})
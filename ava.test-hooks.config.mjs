@@ -0,0 +1,9 @@
+// Runs the fault-injection specs against a `--features test-hooks` build (`pnpm test:hooks`)
+import { readFileSync } from 'node:fs'
+
+const { ava } = JSON.parse(readFileSync(new URL('./package.json', import.meta.url), 'utf-8'))
+
+export default {
+  ...ava,
+  files: ['__test__/test-hooks/**/*.spec.ts'],
+}
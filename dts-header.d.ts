@@ -77,6 +77,14 @@ export interface WebMDemuxerInit {
   error: (error: Error) => void
 }
 
+/** Init options for AudioFileDemuxer */
+export interface AudioFileDemuxerInit {
+  /** Callback for audio chunks */
+  audioOutput?: (chunk: EncodedAudioChunk) => void
+  /** Error callback (required) */
+  error: (error: Error) => void
+}
+
 /** Init options for MkvDemuxer */
 export interface MkvDemuxerInit {
   /** Callback for video chunks */
@@ -85,6 +93,13 @@ export interface MkvDemuxerInit {
   audioOutput?: (chunk: EncodedAudioChunk) => void
   /** Error callback (required) */
   error: (error: Error) => void
+  /**
+   * Open truncated or damaged files best-effort (default: false)
+   *
+   * Corrupt packets are dropped, demuxing stops before a damaged final cluster,
+   * and `truncated`/`duration` report what was recovered.
+   */
+  allowTruncated?: boolean
 }
 
 /** Video track config for muxer */
@@ -137,6 +152,23 @@ export interface MkvMuxerInit {
   streaming?: { bufferCapacity?: number }
 }
 
+// ============================================================================
+// HDR Dynamic Metadata Types
+// ============================================================================
+
+/**
+ * Opaque per-frame HDR dynamic metadata blob.
+ *
+ * Carried unchanged from demuxer and decoder output (`EncodedVideoChunk.hdrDynamicMetadata`,
+ * `VideoFrame.metadata().hdrDynamicMetadata`) back through VideoEncoder and the muxers.
+ */
+export interface HdrDynamicMetadata {
+  /** Metadata kind */
+  type: HdrDynamicMetadataType
+  /** Complete payload: the T.35 message from the country code on, or the Dolby Vision RPU */
+  data: Uint8Array
+}
+
 // ============================================================================
 // Async Iterator Types
 // ============================================================================
@@ -190,3 +222,27 @@ export interface WebMDemuxer {
 export interface MkvDemuxer {
   [Symbol.asyncIterator](): AsyncGenerator<DemuxerChunk, void, void>
 }
+
+/**
+ * Adds async iterator support to AudioFileDemuxer.
+ * Declaration merging allows using `for await...of` with the demuxer.
+ */
+export interface AudioFileDemuxer {
+  [Symbol.asyncIterator](): AsyncGenerator<DemuxerChunk, void, void>
+}
+
+/**
+ * Adds async iterator support to RawVideoReader.
+ * Declaration merging allows using `for await...of` with the reader.
+ */
+export interface RawVideoReader {
+  [Symbol.asyncIterator](): AsyncGenerator<VideoFrame, void, void>
+}
+
+/**
+ * Adds async iterator support to AudioExtractor.
+ * Declaration merging allows using `for await...of` with the extractor.
+ */
+export interface AudioExtractor {
+  [Symbol.asyncIterator](): AsyncGenerator<AudioData, void, void>
+}
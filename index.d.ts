@@ -65,6 +65,8 @@ export interface Mp4DemuxerInit {
   audioOutput?: (chunk: EncodedAudioChunk) => void
   /** Error callback (required) */
   error: (error: Error) => void
+  /** Read appended input without probing ahead (see `Mp4Demuxer.appendBuffer`, default: false) */
+  lowLatency?: boolean
 }
 
 /** Init options for WebMDemuxer */
@@ -77,6 +79,14 @@ export interface WebMDemuxerInit {
   error: (error: Error) => void
 }
 
+/** Init options for AudioFileDemuxer */
+export interface AudioFileDemuxerInit {
+  /** Callback for audio chunks */
+  audioOutput?: (chunk: EncodedAudioChunk) => void
+  /** Error callback (required) */
+  error: (error: Error) => void
+}
+
 /** Init options for MkvDemuxer */
 export interface MkvDemuxerInit {
   /** Callback for video chunks */
@@ -85,6 +95,13 @@ export interface MkvDemuxerInit {
   audioOutput?: (chunk: EncodedAudioChunk) => void
   /** Error callback (required) */
   error: (error: Error) => void
+  /**
+   * Open truncated or damaged files best-effort (default: false)
+   *
+   * Corrupt packets are dropped, demuxing stops before a damaged final cluster,
+   * and `truncated`/`duration` report what was recovered.
+   */
+  allowTruncated?: boolean
 }
 
 /** Video track config for muxer */
@@ -96,7 +113,7 @@ export interface MuxerVideoTrackConfig {
   /** Video height */
   height: number
   /** Codec description (e.g., avcC for H.264) */
-  description?: Uint8Array
+  description?: BufferSource
 }
 
 /** Audio track config for muxer */
@@ -108,7 +125,7 @@ export interface MuxerAudioTrackConfig {
   /** Number of channels */
   numberOfChannels: number
   /** Codec description */
-  description?: Uint8Array
+  description?: BufferSource
 }
 
 /** Init options for Mp4Muxer */
@@ -117,6 +134,8 @@ export interface Mp4MuxerInit {
   fastStart?: boolean
   /** Use fragmented MP4 for streaming */
   fragmented?: boolean
+  /** Omit the "Lavf" encoder tag and write in bitexact mode (default: false) */
+  omitEncoderTags?: boolean
   /** Enable streaming output mode */
   streaming?: { bufferCapacity?: number }
 }
@@ -137,6 +156,35 @@ export interface MkvMuxerInit {
   streaming?: { bufferCapacity?: number }
 }
 
+/** Init options for SegmentedMp4Muxer */
+export interface SegmentedMp4MuxerInit {
+  /** Target segment duration in microseconds; a segment ends at the first key chunk past it */
+  segmentDuration: number
+  /** Path to write segment `index` to; without it, segments are returned as data */
+  filename?: (index: number) => string
+  /** Move the moov atom of every segment to the beginning */
+  fastStart?: boolean
+  /** Omit the "Lavf" encoder tag and write in bitexact mode (default: false) */
+  omitEncoderTags?: boolean
+}
+
+// ============================================================================
+// HDR Dynamic Metadata Types
+// ============================================================================
+
+/**
+ * Opaque per-frame HDR dynamic metadata blob.
+ *
+ * Carried unchanged from demuxer and decoder output (`EncodedVideoChunk.hdrDynamicMetadata`,
+ * `VideoFrame.metadata().hdrDynamicMetadata`) back through VideoEncoder and the muxers.
+ */
+export interface HdrDynamicMetadata {
+  /** Metadata kind */
+  type: HdrDynamicMetadataType
+  /** Complete payload: the T.35 message from the country code on, or the Dolby Vision RPU */
+  data: Uint8Array
+}
+
 // ============================================================================
 // Async Iterator Types
 // ============================================================================
@@ -191,6 +239,38 @@ export interface MkvDemuxer {
   [Symbol.asyncIterator](): AsyncGenerator<DemuxerChunk, void, void>
 }
 
+/**
+ * Adds async iterator support to AudioFileDemuxer.
+ * Declaration merging allows using `for await...of` with the demuxer.
+ */
+export interface AudioFileDemuxer {
+  [Symbol.asyncIterator](): AsyncGenerator<DemuxerChunk, void, void>
+}
+
+/**
+ * Adds async iterator support to RawVideoReader.
+ * Declaration merging allows using `for await...of` with the reader.
+ */
+export interface RawVideoReader {
+  [Symbol.asyncIterator](): AsyncGenerator<VideoFrame, void, void>
+}
+
+/**
+ * Adds async iterator support to KeyFramePacketIterator.
+ * Declaration merging allows using `for await...of` with the iterator.
+ */
+export interface KeyFramePacketIterator {
+  [Symbol.asyncIterator](): AsyncGenerator<EncodedVideoChunk, void, void>
+}
+
+/**
+ * Adds async iterator support to AudioExtractor.
+ * Declaration merging allows using `for await...of` with the extractor.
+ */
+export interface AudioExtractor {
+  [Symbol.asyncIterator](): AsyncGenerator<AudioData, void, void>
+}
+
 export type TypedArray =
   | Int8Array
   | Uint8Array
@@ -314,6 +394,14 @@ export declare class AudioDecoder {
   configure(config: AudioDecoderConfig): void
   /** Decode an encoded audio chunk */
   decode(chunk: EncodedAudioChunk): void
+  /**
+   * Decode several encoded audio chunks in one call
+   *
+   * Equivalent to calling `decode()` for each chunk in order, with one native
+   * call and one microtask hop for the whole batch. `decodeQueueSize` grows by
+   * the batch size and a dequeue event still fires per decoded chunk.
+   */
+  decodeBatch(chunks: EncodedAudioChunk[]): void
   /**
    * Flush the decoder
    * Returns a Promise that resolves when flushing is complete
@@ -442,6 +530,192 @@ export declare class AudioEncoder {
   dispatchEvent(eventType: string): boolean
 }
 
+/**
+ * Streams the audio track of a media file as AudioData chunks
+ *
+ * Accepts the same options as extractAudio(); each chunk holds at most
+ * `chunkDurationUs` of audio, so memory stays bounded for long ranges.
+ *
+ * ```javascript
+ * const extractor = new AudioExtractor('movie.mp4', { targetSampleRate: 16000, mono: true });
+ * for await (const chunk of extractor) {
+ *   recognizer.feed(chunk);
+ *   chunk.close();
+ * }
+ * ```
+ */
+export declare class AudioExtractor {
+  /** Open a media file path or in-memory buffer and select its audio track */
+  constructor(input: string | BufferSource, options?: ExtractAudioOptions | undefined | null)
+  /** Output sample rate of the yielded AudioData */
+  get sampleRate(): number
+  /** Output channel count of the yielded AudioData */
+  get numberOfChannels(): number
+}
+
+/**
+ * Demuxer for bare audio files (MP3, ADTS AAC, FLAC)
+ *
+ * Only the audio stream is demuxed; embedded cover art is ignored.
+ *
+ * This type implements JavaScript's async iterable protocol.
+ * It can be used with `for await...of` loops.
+ *
+ * @see https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Iteration_protocols#the_async_iterator_and_async_iterable_protocols
+ */
+export declare class AudioFileDemuxer {
+  constructor(init: AudioFileDemuxerInit)
+  load(path: string): Promise<void>
+  /**
+   * Load an audio file from a buffer
+   *
+   * This method uses zero-copy buffer loading - the Uint8Array data is passed
+   * directly to the demuxer without an intermediate copy.
+   */
+  loadBuffer(data: BufferSource): Promise<void>
+  get tracks(): Array<DemuxerTrackInfo>
+  get duration(): number | null
+  get audioDecoderConfig(): DemuxerAudioDecoderConfig | null
+  selectAudioTrack(trackIndex: number): void
+  createAudioDecoder(trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>): AudioDecoder
+  /**
+   * Scan a track's packet timestamps and sizes without decoding
+   *
+   * Returns frame interval statistics, an interval histogram, bitrate per time
+   * window and whether the track is VFR. The scan reads a separate view of the
+   * source, so it does not move this demuxer's read position.
+   */
+  analyzeTrack(trackId: number, options?: TrackAnalysisOptions | undefined | null): Promise<TrackAnalysis>
+  /**
+   * Read the next packet of the selected tracks for remuxing without decoding
+   *
+   * Returns null at end of stream. The record keeps PTS, DTS, duration and
+   * flags in the track's time base; pass it to a muxer's `writePacket()`.
+   * Shares the read position with async iteration.
+   */
+  readPacket(): Promise<RawPacket | null>
+  demux(count?: number | undefined | null): void
+  /** Demux packets asynchronously (awaitable version of demux) */
+  demuxAsync(count?: number | undefined | null): Promise<void>
+  seek(timestampUs: number): void
+  close(): void
+  get state(): string
+}
+
+/**
+ * Converts AudioData between sample rates, sample formats and channel counts
+ *
+ * Feed the AudioData of one stream in order to process(); each call returns
+ * the converted samples available so far (or null while the filter is still
+ * filling). flush() returns the held-back tail and starts a new stream.
+ *
+ * ```javascript
+ * const resampler = new AudioResampler({
+ *   inputSampleRate: 48000, inputFormat: 'f32-planar', inputNumberOfChannels: 2,
+ *   outputSampleRate: 16000, outputNumberOfChannels: 1,
+ * });
+ * for (const data of decoded) {
+ *   const out = resampler.process(data);
+ *   if (out) recognizer.feed(out);
+ *   data.close();
+ * }
+ * const tail = resampler.flush();
+ * if (tail) recognizer.feed(tail);
+ * ```
+ */
+export declare class AudioResampler {
+  /** Create a resampler for one input/output conversion */
+  constructor(init: AudioResamplerInit)
+  /**
+   * Convert the next AudioData of the stream
+   *
+   * Returns null when the filter holds back every sample so far. The input
+   * must match the constructor's input rate, format and channel count.
+   */
+  process(data: AudioData): AudioData | null
+  /**
+   * Return the samples held back by the filter and end the stream
+   *
+   * The next process() starts a new stream with a fresh filter, timed from
+   * its own input timestamp. Returns null if nothing was held back.
+   */
+  flush(): AudioData | null
+  /** Discard the held-back samples and start a new stream */
+  reset(): void
+  /** Release the resampler */
+  close(): void
+  /**
+   * Samples held back by the filter, counted at the output sample rate
+   *
+   * flush() returns about this many samples. The timestamps of returned
+   * AudioData already account for them.
+   */
+  get delay(): number
+  /** Timestamp the next output sample will carry, in microseconds (null before the first input) */
+  get nextTimestamp(): number | null
+  /** Output sample rate in Hz */
+  get sampleRate(): number
+  /** Output channel count */
+  get numberOfChannels(): number
+  /** Output sample format */
+  get format(): AudioSampleFormat
+}
+
+/**
+ * Converts a variable frame rate stream of VideoFrames to a constant rate
+ *
+ * Each pushed frame returns the output frames it completes; their timestamps
+ * are exactly `startTimestamp + n / framerate` and their durations one slot.
+ * Output frames share pixel data with the input, so the caller may close the
+ * input as soon as push() returns, and must close each output frame.
+ *
+ * ```javascript
+ * const regulator = new CfrRegulator({ framerate: 30 });
+ * for (const frame of capturedFrames) {
+ *   for (const out of regulator.push(frame)) {
+ *     encoder.encode(out);
+ *     out.close();
+ *   }
+ *   frame.close();
+ * }
+ * for (const out of regulator.flush()) { encoder.encode(out); out.close(); }
+ * console.log(regulator.duplicatedFrames, regulator.droppedFrames);
+ * ```
+ */
+export declare class CfrRegulator {
+  /** Create a regulator for a target frame rate */
+  constructor(init: CfrRegulatorInit)
+  /**
+   * Add the next input frame and return the output frames it completes
+   *
+   * Frames must arrive in presentation order; a frame whose timestamp is not
+   * after the previous one is dropped.
+   */
+  push(frame: VideoFrame): Array<VideoFrame>
+  /**
+   * Fill the slots up to `endTimestamp` with the last frame and return them
+   *
+   * Without `endTimestamp`, the last frame is shown for its own duration, or
+   * for one slot if it has none. The grid continues after flush(), so later
+   * frames stay on the same timeline.
+   */
+  flush(endTimestamp?: number | undefined | null): Array<VideoFrame>
+  /** Drop the held frame and start a new timeline, clearing the counters */
+  reset(): void
+  /** Release the held frame */
+  close(): void
+  /** Target frame rate */
+  get framerate(): number
+  /** Number of frames pushed */
+  get inputFrames(): number
+  /** Number of frames returned */
+  get outputFrames(): number
+  /** Number of extra copies of input frames returned to fill slots */
+  get duplicatedFrames(): number
+  /** Number of input frames that filled no slot */
+  get droppedFrames(): number
+}
+
 /**
  * DOMRectReadOnly - W3C WebCodecs spec compliant rect class
  * Used for codedRect and visibleRect properties
@@ -513,6 +787,8 @@ export declare class EncodedVideoChunk {
   get duration(): number | null
   /** Get the byte length of the encoded data */
   get byteLength(): number
+  /** Get the per-frame HDR dynamic metadata carried by this chunk (extension) */
+  get hdrDynamicMetadata(): Array<HdrDynamicMetadata> | undefined
   /**
    * Copy the encoded data to a BufferSource
    * W3C spec: throws TypeError if destination is too small
@@ -520,6 +796,18 @@ export declare class EncodedVideoChunk {
   copyTo(destination: BufferSource): void
 }
 
+/**
+ * One image of an ImageDecoder.decodeAll() batch (extension)
+ *
+ * Holds either the decoded image or the reason it failed to decode.
+ */
+export declare class ImageDecodeAllResult {
+  /** The decoded image, or null if this image failed to decode */
+  get image(): VideoFrame | null
+  /** Why this image failed to decode, or null on success */
+  get error(): string | null
+}
+
 /**
  * ImageDecoder - WebCodecs-compliant image decoder
  *
@@ -553,6 +841,20 @@ export declare class ImageDecoder {
   get type(): string
   /** Get the track list */
   get tracks(): ImageTrackList
+  /**
+   * Append encoded bytes (non-standard, only when constructed without `data`)
+   *
+   * The tracks of a static image (JPEG, PNG, BMP) are ready as soon as its
+   * header has arrived. decode() waits for endOfData() unless
+   * `completeFramesOnly` is false.
+   */
+  appendData(data: BufferSource): void
+  /**
+   * Mark the appended data as complete (non-standard)
+   *
+   * Resolves `completed` once the image has been parsed. Further calls do nothing.
+   */
+  endOfData(): void
   /** Decode the image (or a specific frame) */
   decode(this: this, options?: ImageDecodeOptions | undefined | null): Promise<ImageDecodeResult>
   /**
@@ -566,6 +868,21 @@ export declare class ImageDecoder {
   get closed(): boolean
   /** Check if a MIME type is supported */
   static isTypeSupported(mimeType: string): Promise<boolean>
+  /**
+   * Decode a batch of independent images on a pool of threads (extension)
+   *
+   * Each thread keeps one decoder per image format and reuses it for every
+   * image of that format it picks up, so sprite sheets, thumbnails and burst
+   * sequences skip the per-ImageDecoder setup. Results are in input order.
+   * An image that fails to decode reports an `error` without failing the
+   * batch. Animated images yield their first frame.
+   *
+   * ```javascript
+   * const results = await ImageDecoder.decodeAll(jpegs, { type: 'image/jpeg', concurrency: 4 });
+   * const frames = results.map((result) => result.image);
+   * ```
+   */
+  static decodeAll(buffers: Array<Uint8Array>, options?: ImageDecodeAllOptions | undefined | null): Promise<Array<ImageDecodeAllResult>>
 }
 
 /**
@@ -588,6 +905,13 @@ export declare class ImageTrack {
   get frameCount(): number
   /** Number of times the animation repeats (Infinity for infinite) */
   get repetitionCount(): number
+  /**
+   * Embedded ICC profile (PNG iCCP, JPEG APP2, WebP ICCP), or null if none
+   *
+   * Available once `tracks.ready` resolves. Decoded frames are not color
+   * managed; applying the profile is left to the caller.
+   */
+  get iccProfile(): Uint8Array | null
   /** Whether this track is currently selected (W3C spec - writable) */
   get selected(): boolean
   /**
@@ -611,6 +935,23 @@ export declare class ImageTrackList {
   item(index: number): ImageTrack | null
 }
 
+/**
+ * Key frames of a video track, yielded as EncodedVideoChunks
+ *
+ * Returned by a demuxer's `keyFramePackets()`; use with `for await...of`.
+ *
+ * ```javascript
+ * const duration = demuxer.duration;
+ * const keyFrames = demuxer.keyFramePackets(trackId, { intervalUs: duration / 20 });
+ * for await (const chunk of keyFrames) {
+ *   thumbnailDecoder.decode(chunk);
+ * }
+ * ```
+ */
+export declare class KeyFramePacketIterator {
+
+}
+
 /**
  * MKV Demuxer for reading encoded video and audio from Matroska container
  *
@@ -630,13 +971,48 @@ export declare class MkvDemuxer {
    * This method uses zero-copy buffer loading - the Uint8Array data is passed
    * directly to the demuxer without an intermediate copy.
    */
-  loadBuffer(data: Uint8Array): Promise<void>
+  loadBuffer(data: BufferSource): Promise<void>
   get tracks(): Array<DemuxerTrackInfo>
   get duration(): number | null
+  /**
+   * Whether the file was found truncated or damaged (requires `allowTruncated`)
+   *
+   * When true, `duration` reports the usable duration and demuxing stops
+   * before the damaged cluster.
+   */
+  get truncated(): boolean
   get videoDecoderConfig(): DemuxerVideoDecoderConfig | null
   get audioDecoderConfig(): DemuxerAudioDecoderConfig | null
   selectVideoTrack(trackIndex: number): void
   selectAudioTrack(trackIndex: number): void
+  createVideoDecoder(trackId: number, init: { output: (frame: VideoFrame) => void; error: (error: Error) => void } & Partial<VideoDecoderConfig>): VideoDecoder
+  createAudioDecoder(trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>): AudioDecoder
+  /**
+   * Scan a track's packet timestamps and sizes without decoding
+   *
+   * Returns frame interval statistics, an interval histogram, bitrate per time
+   * window and whether the track is VFR. The scan reads a separate view of the
+   * source, so it does not move this demuxer's read position.
+   */
+  analyzeTrack(trackId: number, options?: TrackAnalysisOptions | undefined | null): Promise<TrackAnalysis>
+  /**
+   * Iterate a video track's key frames, seeking from one to the next
+   *
+   * Only sync samples are read, optionally no closer together than
+   * `intervalUs`. Pair with a `VideoDecoder` configured with
+   * `keyFramesOnly: true` to build thumbnail strips without decoding the
+   * frames in between. Uses a separate view of the source, so it does not
+   * move this demuxer's read position.
+   */
+  keyFramePackets(trackId: number, options?: KeyFramePacketsOptions | undefined | null): KeyFramePacketIterator
+  /**
+   * Read the next packet of the selected tracks for remuxing without decoding
+   *
+   * Returns null at end of stream. The record keeps PTS, DTS, duration and
+   * flags in the track's time base; pass it to a muxer's `writePacket()`.
+   * Shares the read position with async iteration.
+   */
+  readPacket(): Promise<RawPacket | null>
   demux(count?: number | undefined | null): void
   /** Demux packets asynchronously (awaitable version of demux) */
   demuxAsync(count?: number | undefined | null): Promise<void>
@@ -674,6 +1050,12 @@ export declare class MkvMuxer {
    * MKV supports H.264, H.265, VP8, VP9, AV1, and many other video codecs.
    */
   addVideoTrack(config: MkvVideoTrackConfig): void
+  /**
+   * Replace the video track description before the first chunk is added
+   *
+   * For encoders that only deliver the description with the first keyframe.
+   */
+  updateVideoDescription(description: BufferSource): void
   /**
    * Add an audio track to the muxer
    *
@@ -684,10 +1066,24 @@ export declare class MkvMuxer {
   addVideoChunk(chunk: EncodedVideoChunk, metadata?: EncodedVideoChunkMetadataJs | undefined | null): void
   /** Add an encoded audio chunk to the muxer */
   addAudioChunk(chunk: EncodedAudioChunk, metadata?: EncodedAudioChunkMetadataJs | undefined | null): void
+  /**
+   * Write a raw packet with explicit DTS, bypassing EncodedVideoChunk/EncodedAudioChunk
+   *
+   * Use with a demuxer's `readPacket()` to remux without decoding: PTS, DTS,
+   * duration and flags are kept as given (rescaled to the track's time base).
+   */
+  writePacket(packet: RawPacket): void
   /** Flush any buffered data */
   flush(): void
   /** Finalize the muxer and return the MKV data */
   finalize(): Uint8Array
+  /**
+   * Achieved bitrate per track over the chunks added so far
+   *
+   * Call after `finalize()` for the final numbers. The peak is the largest
+   * bit count over any 1-second window of chunk timestamps.
+   */
+  bitrateSummary(): MuxerBitrateSummary
   /**
    * Read available data from streaming buffer (streaming mode only)
    *
@@ -751,7 +1147,27 @@ export declare class Mp4Demuxer {
    * This method uses zero-copy buffer loading - the Uint8Array data is passed
    * directly to the demuxer without an intermediate copy.
    */
-  loadBuffer(data: Uint8Array): Promise<void>
+  loadBuffer(data: BufferSource): Promise<void>
+  /**
+   * Append fragmented MP4 data, e.g. live CMAF/LL-HLS segments
+   *
+   * Tracks and decoder configs are available once the init segment (`ftyp`
+   * and `moov`) has been appended. Reads then emit each fragment's samples
+   * as soon as its `moof` and `mdat` have arrived; with `lowLatency: true`
+   * nothing is read ahead, so the first chunk needs only the first fragment.
+   * Call endOfStream() after the last fragment.
+   *
+   * Reads wait for more data instead of ending, and hold the demuxer while
+   * they wait: keep appending while a read is pending, and avoid other
+   * demuxer calls until it resolves. Seeking is not supported.
+   */
+  appendBuffer(data: BufferSource): void
+  /**
+   * Signal that no more data will be appended
+   *
+   * Reading ends once the appended data has been consumed.
+   */
+  endOfStream(): void
   /** Get all tracks */
   get tracks(): Array<DemuxerTrackInfo>
   /** Get container duration in microseconds */
@@ -764,6 +1180,49 @@ export declare class Mp4Demuxer {
   selectVideoTrack(trackIndex: number): void
   /** Select an audio track by index */
   selectAudioTrack(trackIndex: number): void
+  /**
+   * Create a VideoDecoder configured for a video track
+   *
+   * The track's codec string, dimensions and description are converted for
+   * VideoDecoder automatically; any VideoDecoderConfig fields in `init`
+   * (e.g. `hardwareAcceleration`) override them. The track is also selected
+   * for demuxing.
+   */
+  createVideoDecoder(trackId: number, init: { output: (frame: VideoFrame) => void; error: (error: Error) => void } & Partial<VideoDecoderConfig>): VideoDecoder
+  /**
+   * Create an AudioDecoder configured for an audio track
+   *
+   * The track's codec string, sample rate, channel count and description are
+   * converted for AudioDecoder automatically; any AudioDecoderConfig fields in
+   * `init` override them. The track is also selected for demuxing.
+   */
+  createAudioDecoder(trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>): AudioDecoder
+  /**
+   * Scan a track's packet timestamps and sizes without decoding
+   *
+   * Returns frame interval statistics, an interval histogram, bitrate per time
+   * window and whether the track is VFR. The scan reads a separate view of the
+   * source, so it does not move this demuxer's read position.
+   */
+  analyzeTrack(trackId: number, options?: TrackAnalysisOptions | undefined | null): Promise<TrackAnalysis>
+  /**
+   * Iterate a video track's key frames, seeking from one to the next
+   *
+   * Only sync samples are read, optionally no closer together than
+   * `intervalUs`. Pair with a `VideoDecoder` configured with
+   * `keyFramesOnly: true` to build thumbnail strips without decoding the
+   * frames in between. Uses a separate view of the source, so it does not
+   * move this demuxer's read position.
+   */
+  keyFramePackets(trackId: number, options?: KeyFramePacketsOptions | undefined | null): KeyFramePacketIterator
+  /**
+   * Read the next packet of the selected tracks for remuxing without decoding
+   *
+   * Returns null at end of stream. The record keeps PTS, DTS, duration and
+   * flags in the track's time base; pass it to a muxer's `writePacket()`.
+   * Shares the read position with async iteration.
+   */
+  readPacket(): Promise<RawPacket | null>
   /**
    * Start demuxing packets
    *
@@ -820,6 +1279,12 @@ export declare class Mp4Muxer {
    * Must be called before adding any chunks.
    */
   addVideoTrack(config: Mp4VideoTrackConfig): void
+  /**
+   * Replace the video track description before the first chunk is added
+   *
+   * For encoders that only deliver the description with the first keyframe.
+   */
+  updateVideoDescription(description: BufferSource): void
   /**
    * Add an audio track to the muxer
    *
@@ -840,6 +1305,13 @@ export declare class Mp4Muxer {
    * The chunk should come from an AudioEncoder's output callback.
    */
   addAudioChunk(chunk: EncodedAudioChunk, metadata?: EncodedAudioChunkMetadataJs | undefined | null): void
+  /**
+   * Write a raw packet with explicit DTS, bypassing EncodedVideoChunk/EncodedAudioChunk
+   *
+   * Use with a demuxer's `readPacket()` to remux without decoding: PTS, DTS,
+   * duration and flags are kept as given (rescaled to the track's time base).
+   */
+  writePacket(packet: RawPacket): void
   /** Flush any buffered data */
   flush(): void
   /**
@@ -849,6 +1321,13 @@ export declare class Mp4Muxer {
    * Returns the complete MP4 file as a Uint8Array.
    */
   finalize(): Uint8Array
+  /**
+   * Achieved bitrate per track over the chunks added so far
+   *
+   * Call after `finalize()` for the final numbers. The peak is the largest
+   * bit count over any 1-second window of chunk timestamps.
+   */
+  bitrateSummary(): MuxerBitrateSummary
   /**
    * Read available data from streaming buffer (streaming mode only)
    *
@@ -871,55 +1350,173 @@ export declare class Mp4Muxer {
   get state(): string
 }
 
-/** Video color space parameters (WebCodecs spec) - as a class per spec */
-export declare class VideoColorSpace {
-  /** Create a new VideoColorSpace */
-  constructor(init?: VideoColorSpaceInit | undefined | null)
-  /** Get color primaries */
-  get primaries(): VideoColorPrimaries | null
-  /** Get transfer characteristics */
-  get transfer(): VideoTransferCharacteristics | null
-  /** Get matrix coefficients */
-  get matrix(): VideoMatrixCoefficients | null
-  /** Get full range flag */
-  get fullRange(): boolean | null
-  /**
-   * Convert to JSON-compatible object (W3C spec uses toJSON)
-   *
-   * Per W3C spec, toJSON() returns explicit null for unset fields.
-   */
-  toJSON(): object
+/**
+ * Reader for headerless raw video (e.g. `.yuv` test sequences)
+ *
+ * Frames are yielded as VideoFrames with timestamps generated from the frame
+ * index and frame rate, so a range selected with `startFrame` keeps the
+ * timestamps it has in the full sequence.
+ *
+ * ```javascript
+ * const reader = new RawVideoReader('foreman_cif.yuv', { width: 352, height: 288, format: 'I420' });
+ * for await (const frame of reader) {
+ *   encoder.encode(frame);
+ *   frame.close();
+ * }
+ * ```
+ */
+export declare class RawVideoReader {
+  /** Open raw video from a file path or an in-memory buffer */
+  constructor(source: string | BufferSource, init: RawVideoReaderInit)
+  /** Number of frames in the selected range */
+  get frameCount(): number
 }
 
 /**
- * VideoDecoder - WebCodecs-compliant video decoder
- *
- * Decodes EncodedVideoChunk objects into VideoFrame objects using FFmpeg.
+ * Writer for headerless raw video
  *
- * Per the WebCodecs spec, the constructor takes an init dictionary with callbacks.
+ * Appends the visible rect of each frame, tightly packed, in the configured
+ * format.
+ */
+export declare class RawVideoWriter {
+  /** Create (or truncate) a raw video file */
+  constructor(path: string, init: RawVideoWriterInit)
+  /** Append a frame's visible rect, converting it to the writer's format if needed */
+  write(frame: VideoFrame): void
+  /** Number of frames written so far */
+  get framesWritten(): number
+  /** Flush buffered data and close the file */
+  close(): void
+}
+
+/**
+ * MP4 muxer that rotates output into independent files of a fixed duration
  *
- * Example:
+ * Usage:
  * ```javascript
- * const decoder = new VideoDecoder({
- *   output: (frame) => { console.log('decoded frame', frame); },
- *   error: (e) => { console.error('error', e); }
+ * const muxer = new SegmentedMp4Muxer({
+ *   segmentDuration: 10 * 60 * 1_000_000,
+ *   filename: (index) => `recording-${index}.mp4`,
  * });
+ * muxer.addVideoTrack({ codec: 'avc1.42001E', width: 1920, height: 1080 });
  *
- * decoder.configure({
- *   codec: 'avc1.42001E'
+ * encoder.configure({
+ *   output: (chunk, metadata) => muxer.addVideoChunk(chunk, metadata)
  * });
+ * encoder.encode(frame, { keyFrame: muxer.rotationPending });
  *
- * decoder.decode(chunk);
- * await decoder.flush();
+ * // Write the last segment
+ * muxer.finalize();
  * ```
  */
-export declare class VideoDecoder {
+export declare class SegmentedMp4Muxer {
+  /** Create a new segmented MP4 muxer */
+  constructor(init: SegmentedMp4MuxerInit)
   /**
-   * Create a new VideoDecoder with init dictionary (per WebCodecs spec)
+   * Add a video track, repeated in every segment
+   *
+   * Must be called before adding any chunks.
+   */
+  addVideoTrack(config: Mp4VideoTrackConfig): void
+  /**
+   * Add an audio track, repeated in every segment
+   *
+   * Must be called before adding any chunks.
+   */
+  addAudioTrack(config: Mp4AudioTrackConfig): void
+  /**
+   * Add an encoded video chunk
+   *
+   * A key chunk at or past the segment duration (or after `rotate()`)
+   * finishes the open segment and starts the next one; the finished segment
+   * is returned. Returns null otherwise.
+   */
+  addVideoChunk(chunk: EncodedVideoChunk, metadata?: EncodedVideoChunkMetadataJs | undefined | null): Mp4Segment | null
+  /**
+   * Add an encoded audio chunk to the open segment
+   *
+   * With a video track, segments follow the video key chunks and audio
+   * chunks go to the segment that is open when they are added, so add them
+   * interleaved with the video in timestamp order. An audio-only recording
+   * rotates on audio chunks, which are all key chunks.
+   */
+  addAudioChunk(chunk: EncodedAudioChunk, metadata?: EncodedAudioChunkMetadataJs | undefined | null): Mp4Segment | null
+  /** Start a new segment at the next key chunk, regardless of duration */
+  rotate(): void
+  /**
+   * Whether the open segment is waiting for a key chunk to end
+   *
+   * True after `rotate()` or once the segment has reached its duration.
+   * Encode the next frame with `keyFrame: true` to end it right away.
+   */
+  get rotationPending(): boolean
+  /** Index of the open segment */
+  get segmentIndex(): number
+  /**
+   * Finish the open segment and return it
+   *
+   * Returns null when no chunk was added since the last rotation.
+   */
+  finalize(): Mp4Segment | null
+  /** Close the muxer and discard the open segment */
+  close(): void
+  /** Get the state of the open segment's muxer ("closed" after finalize or close) */
+  get state(): string
+}
+
+/** Video color space parameters (WebCodecs spec) - as a class per spec */
+export declare class VideoColorSpace {
+  /** Create a new VideoColorSpace */
+  constructor(init?: VideoColorSpaceInit | undefined | null)
+  /** Get color primaries */
+  get primaries(): VideoColorPrimaries | null
+  /** Get transfer characteristics */
+  get transfer(): VideoTransferCharacteristics | null
+  /** Get matrix coefficients */
+  get matrix(): VideoMatrixCoefficients | null
+  /** Get full range flag */
+  get fullRange(): boolean | null
+  /**
+   * Convert to JSON-compatible object (W3C spec uses toJSON)
+   *
+   * Per W3C spec, toJSON() returns explicit null for unset fields.
+   */
+  toJSON(): object
+}
+
+/**
+ * VideoDecoder - WebCodecs-compliant video decoder
+ *
+ * Decodes EncodedVideoChunk objects into VideoFrame objects using FFmpeg.
+ *
+ * Per the WebCodecs spec, the constructor takes an init dictionary with callbacks.
+ *
+ * Example:
+ * ```javascript
+ * const decoder = new VideoDecoder({
+ *   output: (frame) => { console.log('decoded frame', frame); },
+ *   error: (e) => { console.error('error', e); }
+ * });
+ *
+ * decoder.configure({
+ *   codec: 'avc1.42001E'
+ * });
+ *
+ * decoder.decode(chunk);
+ * await decoder.flush();
+ * ```
+ */
+export declare class VideoDecoder {
+  /**
+   * Create a new VideoDecoder with init dictionary (per WebCodecs spec)
    *
    * @param init - Init dictionary containing output and error callbacks
    */
-  constructor(init: { output: (frame: VideoFrame) => void; error: (error: Error) => void })
+  constructor(init: {
+    output: (frame: VideoFrame) => void
+    error: (error: Error) => void
+    parameterSetChange?: (change: ParameterSetChange) => void
+  })
   /** Get decoder state */
   get state(): CodecState
   /** Get number of pending decode operations (per WebCodecs spec) */
@@ -944,6 +1541,23 @@ export declare class VideoDecoder {
   configure(config: VideoDecoderConfig): void
   /** Decode an encoded video chunk */
   decode(chunk: EncodedVideoChunk): void
+  /**
+   * Decode an encoded video chunk and close it (extension)
+   *
+   * The chunk's reference to its data is transferred to the decoder, so a
+   * demuxer chunk's packet is decoded without copying its bytes. Afterwards
+   * the chunk is closed and its getters and `copyTo()` throw
+   * InvalidStateError.
+   */
+  decodeAndClose(chunk: EncodedVideoChunk): void
+  /**
+   * Decode several encoded video chunks in one call
+   *
+   * Equivalent to calling `decode()` for each chunk in order, with one native
+   * call and one microtask hop for the whole batch. `decodeQueueSize` grows by
+   * the batch size and a dequeue event still fires per decoded chunk.
+   */
+  decodeBatch(chunks: EncodedVideoChunk[]): void
   /**
    * Flush the decoder
    * Returns a Promise that resolves when flushing is complete
@@ -953,10 +1567,53 @@ export declare class VideoDecoder {
    * AFTER the callback returns, allowing flush() to return AbortError.
    */
   flush(): Promise<void>
+  /**
+   * How decode inputs have reached FFmpeg since the decoder was created (extension)
+   *
+   * Chunks from a demuxer are decoded from the packet they were read into;
+   * other chunks, and chunks whose bytes have to be rewritten (e.g. AVCC to
+   * Annex B), are copied into a new packet.
+   */
+  inputStats(): DecodeInputStats
+  /**
+   * Rolling queue latency statistics for the decode worker
+   *
+   * Covers the most recent 256 decode commands: time from `decode()` until
+   * the worker started on the chunk, and time spent decoding it. With
+   * `WEBCODECS_LOG=debug` each command is also logged in a `decode` span
+   * keyed by chunk timestamp.
+   */
+  queueStats(): CodecQueueStats
   /** Reset the decoder */
   reset(): void
-  /** Close the decoder */
-  close(): void
+  /**
+   * Close the decoder
+   *
+   * Decoder resources are freed before this returns. Output frames the application
+   * still holds stay valid: any frame still backed by a hardware surface is downloaded
+   * to CPU memory first. With `detachFrames`, those frames are closed instead and their
+   * pixel data freed, so a later copyTo() throws InvalidStateError.
+   */
+  close(options?: VideoDecoderCloseOptions | undefined | null): void
+  /**
+   * Add an additional output sink that receives every decoded frame
+   *
+   * Each sink gets its own VideoFrame referencing the same pixel data as the frame
+   * passed to the `output` callback, so every consumer must close its frame independently.
+   * Sinks persist across reset() and configure(), and are released by close().
+   *
+   * @returns Sink id to pass to removeOutputSink()
+   */
+  addOutputSink(callback: (frame: VideoFrame) => void): number
+  /**
+   * Remove an output sink added with addOutputSink()
+   *
+   * Safe to call at any time, including from within an output callback.
+   * Frames already queued for the sink may still be delivered.
+   *
+   * @returns true if the sink was registered
+   */
+  removeOutputSink(id: number): boolean
   /**
    * Check if a configuration is supported
    * Returns a Promise that resolves with support information
@@ -1032,10 +1689,39 @@ export declare class VideoEncoder {
   set ondequeue(callback: (() => unknown) | undefined | null)
   /** Get the dequeue event handler (per WebCodecs spec) */
   get ondequeue(): (() => unknown) | null
+  /**
+   * The config from the last configure(), with defaults such as `bitrate` filled in
+   * (extension; null while unconfigured)
+   */
+  get activeConfiguration(): VideoEncoderConfig | null
   /** Configure the encoder */
   configure(config: VideoEncoderConfig): void
-  /** Encode a frame */
+  /**
+   * Configure the encoder and wait until it is ready to encode
+   *
+   * Behaves exactly like `configure()`, including the state transitions and
+   * the error callback. The returned Promise resolves once the encoder context
+   * is open (after hardware frame setup and any software fallback), or rejects
+   * with the NotSupportedError/OperationError reported to the error callback.
+   */
+  configureAsync(config: VideoEncoderConfig): Promise<void>
+  /**
+   * Encode a frame
+   *
+   * May be called from inside the output callback: the frame is queued
+   * behind those already submitted, and its chunks are delivered after the
+   * chunk being handled. The encoder can only be used from the thread that
+   * created it.
+   */
   encode(frame: VideoFrame, options?: VideoEncoderEncodeOptions | undefined | null): void
+  /**
+   * Encode several frames in one call
+   *
+   * Equivalent to calling `encode()` for each frame in order, with one native
+   * call and one microtask hop for the whole batch. `options`, if given, holds
+   * the encode options for each frame by index and must match `frames` in length.
+   */
+  encodeBatch(frames: VideoFrame[], options?: Array<VideoEncoderEncodeOptions | undefined | null>): void
   /**
    * Flush the encoder
    * Returns a Promise that resolves when flushing is complete
@@ -1045,6 +1731,40 @@ export declare class VideoEncoder {
    * AFTER the callback returns, allowing flush() to return AbortError.
    */
   flush(): Promise<void>
+  /**
+   * How H.264/HEVC output has been converted to AVCC/HVCC chunks since the
+   * encoder was created (extension)
+   *
+   * Start codes are normally rewritten to length prefixes inside the
+   * encoder's packet buffer; chunks whose buffer was still shared, or whose
+   * NAL units could not be moved in place, are copied instead. Annex B
+   * output is not counted.
+   */
+  outputStats(): EncodeOutputStats
+  /**
+   * Get first-pass rate-control statistics (two-pass encoding)
+   *
+   * Available after `flush()` when configured with `pass: 1`. The data is
+   * opaque; pass it back as `statsData` together with `pass: 2`.
+   * Returns null if no statistics have been collected.
+   */
+  stats(): Buffer | null
+  /**
+   * Achieved bitrate of the chunks output since configure()
+   *
+   * The average covers the media duration of the output; the peak is the
+   * largest bit count over any 1-second window of chunk timestamps.
+   */
+  bitrateSummary(): BitrateSummary
+  /**
+   * Rolling queue latency statistics for the encode worker
+   *
+   * Covers the most recent 256 encode commands: time from `encode()` until
+   * the worker started on the frame, and time spent encoding it. With
+   * `WEBCODECS_LOG=debug` each command is also logged in an `encode` span
+   * keyed by frame timestamp.
+   */
+  queueStats(): CodecQueueStats
   /** Reset the encoder */
   reset(): void
   /** Close the encoder */
@@ -1052,6 +1772,10 @@ export declare class VideoEncoder {
   /**
    * Add an event listener for the specified event type
    * Uses separate RwLock to avoid blocking on encode operations
+   *
+   * Besides "dequeue", a "fallback" event fires when a `no-preference` encoder
+   * switches from a failing hardware encoder (e.g. one refused by the driver's
+   * session limit) to software.
    */
   addEventListener(
     eventType: string,
@@ -1077,6 +1801,21 @@ export declare class VideoEncoder {
    * native TypeError for missing required fields.
    */
   static isConfigSupported(config: VideoEncoderConfig): Promise<VideoEncoderSupport>
+  /**
+   * Check if oversized chunks can be re-encoded to meet `maxChunkBytes` (extension)
+   *
+   * Supported for software VP8, VP9 and AV1 without alpha, and only for frames
+   * encoded with `keyFrame: true` (all-intra streams). Otherwise oversized chunks
+   * are delivered as-is with `chunkBudget.exceeded` set in their metadata.
+   */
+  static isMaxChunkBytesSupported(config: VideoEncoderConfig): VideoEncoderSupport
+  /**
+   * Check if encode() accepts `markAsLongTermReference` and `referenceOnly` (extension)
+   *
+   * Supported for software VP8 and VP9 (libvpx) without temporal scalability.
+   * encode() throws NotSupportedError for these options otherwise.
+   */
+  static isReferenceControlSupported(config: VideoEncoderConfig): VideoEncoderSupport
 }
 
 /**
@@ -1093,7 +1832,7 @@ export declare class VideoFrame {
    * 2. `new VideoFrame(source, init?)` - from another VideoFrame with optional VideoFrameInit
    * 3. `new VideoFrame(canvas, init)` - from @napi-rs/canvas Canvas (requires timestamp in init)
    */
-  constructor(source: VideoFrame | Uint8Array | CanvasLike, init?: VideoFrameBufferInit | VideoFrameInit)
+  constructor(source: VideoFrame | BufferSource | CanvasLike, init?: VideoFrameBufferInit | VideoFrameInit)
   /** Get the pixel format */
   get format(): VideoPixelFormat | null
   /** Get the coded width in pixels (returns 0 when closed per W3C spec) */
@@ -1195,13 +1934,49 @@ export declare class WebMDemuxer {
    * This method uses zero-copy buffer loading - the Uint8Array data is passed
    * directly to the demuxer without an intermediate copy.
    */
-  loadBuffer(data: Uint8Array): Promise<void>
+  loadBuffer(data: BufferSource): Promise<void>
   get tracks(): Array<DemuxerTrackInfo>
   get duration(): number | null
   get videoDecoderConfig(): DemuxerVideoDecoderConfig | null
   get audioDecoderConfig(): DemuxerAudioDecoderConfig | null
   selectVideoTrack(trackIndex: number): void
   selectAudioTrack(trackIndex: number): void
+  createVideoDecoder(trackId: number, init: { output: (frame: VideoFrame) => void; error: (error: Error) => void } & Partial<VideoDecoderConfig>): VideoDecoder
+  createAudioDecoder(trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>): AudioDecoder
+  /**
+   * Scan a track's packet timestamps and sizes without decoding
+   *
+   * Returns frame interval statistics, an interval histogram, bitrate per time
+   * window and whether the track is VFR. The scan reads a separate view of the
+   * source, so it does not move this demuxer's read position.
+   */
+  analyzeTrack(trackId: number, options?: TrackAnalysisOptions | undefined | null): Promise<TrackAnalysis>
+  /**
+   * Read every cue of a WebVTT subtitle track
+   *
+   * Cue timestamps and durations are in microseconds, with the settings and
+   * identifier lines split from the text. Uses a separate view of the
+   * source, so it does not move this demuxer's read position.
+   */
+  readSubtitleCues(trackId: number): Promise<Array<SubtitleCue>>
+  /**
+   * Iterate a video track's key frames, seeking from one to the next
+   *
+   * Only sync samples are read, optionally no closer together than
+   * `intervalUs`. Pair with a `VideoDecoder` configured with
+   * `keyFramesOnly: true` to build thumbnail strips without decoding the
+   * frames in between. Uses a separate view of the source, so it does not
+   * move this demuxer's read position.
+   */
+  keyFramePackets(trackId: number, options?: KeyFramePacketsOptions | undefined | null): KeyFramePacketIterator
+  /**
+   * Read the next packet of the selected tracks for remuxing without decoding
+   *
+   * Returns null at end of stream. The record keeps PTS, DTS, duration and
+   * flags in the track's time base; pass it to a muxer's `writePacket()`.
+   * Shares the read position with async iteration.
+   */
+  readPacket(): Promise<RawPacket | null>
   demux(count?: number | undefined | null): void
   /** Demux packets asynchronously (awaitable version of demux) */
   demuxAsync(count?: number | undefined | null): Promise<void>
@@ -1239,20 +2014,50 @@ export declare class WebMMuxer {
    * WebM supports VP8, VP9, and AV1 video codecs.
    */
   addVideoTrack(config: WebMVideoTrackConfig): void
+  /**
+   * Replace the video track description before the first chunk is added
+   *
+   * For encoders that only deliver the description with the first keyframe.
+   */
+  updateVideoDescription(description: BufferSource): void
   /**
    * Add an audio track to the muxer
    *
    * WebM supports Opus and Vorbis audio codecs.
    */
   addAudioTrack(config: WebMAudioTrackConfig): void
+  /** Add a WebVTT subtitle track to the muxer */
+  addSubtitleTrack(config?: WebMSubtitleTrackConfig | undefined | null): void
   /** Add an encoded video chunk to the muxer */
   addVideoChunk(chunk: EncodedVideoChunk, metadata?: EncodedVideoChunkMetadataJs | undefined | null): void
   /** Add an encoded audio chunk to the muxer */
   addAudioChunk(chunk: EncodedAudioChunk, metadata?: EncodedAudioChunkMetadataJs | undefined | null): void
+  /**
+   * Write a WebVTT cue to the subtitle track
+   *
+   * Timestamp and duration are in microseconds. The cue settings and
+   * identifier are stored with the text in the WebM WebVTT block format.
+   * Write cues in timestamp order alongside the media chunks.
+   */
+  writeSubtitleChunk(cue: SubtitleCue): void
+  /**
+   * Write a raw packet with explicit DTS, bypassing EncodedVideoChunk/EncodedAudioChunk
+   *
+   * Use with a demuxer's `readPacket()` to remux without decoding: PTS, DTS,
+   * duration and flags are kept as given (rescaled to the track's time base).
+   */
+  writePacket(packet: RawPacket): void
   /** Flush any buffered data */
   flush(): void
   /** Finalize the muxer and return the WebM data */
   finalize(): Uint8Array
+  /**
+   * Achieved bitrate per track over the chunks added so far
+   *
+   * Call after `finalize()` for the final numbers. The peak is the largest
+   * bit count over any 1-second window of chunk timestamps.
+   */
+  bitrateSummary(): MuxerBitrateSummary
   /**
    * Read available data from streaming buffer (streaming mode only)
    *
@@ -1289,6 +2094,22 @@ export interface AddEventListenerOptions {
   passive?: boolean
 }
 
+/**
+ * Split ADTS-framed AAC into raw access units and an AudioSpecificConfig
+ *
+ * Every frame must carry the same profile, sample rate and channel
+ * configuration, and a single raw data block.
+ */
+export declare function adtsToRaw(data: BufferSource): AdtsToRawResult
+
+/** Raw AAC access units extracted from an ADTS stream */
+export interface AdtsToRawResult {
+  /** AudioSpecificConfig for the stream (usable as AudioDecoderConfig.description) */
+  asc: Uint8Array
+  /** Raw access units, one per ADTS frame, without headers */
+  frames: Array<Uint8Array>
+}
+
 /**
  * Alpha channel handling option (W3C WebCodecs spec)
  * Default is "discard" per spec
@@ -1298,6 +2119,21 @@ export type AlphaOption = /** Keep alpha channel if present */
   /** Discard alpha channel (default per W3C spec) */
   | 'discard'
 
+/**
+ * Report frame types, GOP lengths and parameter set locations of an
+ * encoded video stream by parsing headers only
+ *
+ * `input` is an array of EncodedVideoChunks (from an encoder or a demuxer),
+ * or a container file path or buffer whose best video track is read. The
+ * codec string is required for chunks and ignored for containers, whose
+ * codec is taken from the track. H.264, H.265, VP9 and AV1 are supported.
+ *
+ * ```javascript
+ * const { gopLengths, bFrameCount } = await analyzeBitstream(chunks, 'avc1.42001E');
+ * ```
+ */
+export declare function analyzeBitstream(input: EncodedVideoChunk[] | string | BufferSource, codec?: string): Promise<BitstreamAnalysis>
+
 /** Options for copyTo operation */
 export interface AudioDataCopyToOptions {
   /** The index of the audio plane to copy */
@@ -1326,7 +2162,7 @@ export interface AudioDecoderConfigJs {
   /** Number of channels */
   numberOfChannels?: number
   /** Codec-specific description */
-  description?: Uint8Array
+  description?: BufferSource
 }
 
 /** Decoder configuration output (for passing to decoder) */
@@ -1377,6 +2213,22 @@ export interface AudioEncoderSupport {
   config: AudioEncoderConfig
 }
 
+/** Options for AudioResampler */
+export interface AudioResamplerInit {
+  /** Sample rate of the input AudioData in Hz */
+  inputSampleRate: number
+  /** Sample format of the input AudioData */
+  inputFormat: AudioSampleFormat
+  /** Channel count of the input AudioData (default channel layout for the count) */
+  inputNumberOfChannels: number
+  /** Output sample rate in Hz */
+  outputSampleRate: number
+  /** Output sample format (default: the input format) */
+  outputFormat?: AudioSampleFormat
+  /** Output channel count (default: the input channel count); 1 downmixes to mono */
+  outputNumberOfChannels?: number
+}
+
 /** Audio sample format (WebCodecs spec) */
 export type AudioSampleFormat = /** Unsigned 8-bit integer samples| interleaved */
   | 'u8'
@@ -1413,6 +2265,142 @@ export type BitrateMode = /** Variable bitrate (default) */
   /** Constant bitrate */
   | 'constant'
 
+/** Achieved bitrate of an encoded stream */
+export interface BitrateSummary {
+  /** Media duration from the first chunk's timestamp to the end of the last chunk (microseconds) */
+  durationUs: number
+  /** Total encoded bytes */
+  bytes: number
+  /** Average bitrate over `durationUs` (bits per second) */
+  avgBitrate: number
+  /** Highest bit count over any 1-second window of chunk timestamps (bits per second) */
+  peakBitrate1s: number
+}
+
+/** Build and runtime information for the native addon */
+/** Bitrate of the packets presented in one time window */
+export interface BitrateWindow {
+  /** Window start in microseconds */
+  startUs: number
+  /** Window length in microseconds (the last window may be shorter) */
+  durationUs: number
+  /** Bits per second */
+  bitrate: number
+}
+
+/** Result of analyzeBitstream() */
+export interface BitstreamAnalysis {
+  /** Codec family: "h264", "h265", "vp9" or "av1" */
+  codec: string
+  /** NAL unit delimiting of the first H.264/H.265 chunk; unset for VP9/AV1 */
+  nalFormat?: BitstreamNalFormat
+  /** Every chunk, in input order */
+  frames: Array<BitstreamFrame>
+  keyFrameCount: number
+  bFrameCount: number
+  /** Longest run of consecutive B-frames in decode order */
+  maxConsecutiveBFrames: number
+  /**
+   * Frames from each key frame up to the next; the last GOP may be
+   * incomplete and frames before the first key frame are not counted
+   */
+  gopLengths: Array<number>
+  /**
+   * Indices of the frames carrying in-band parameter sets (H.264 SPS/PPS,
+   * H.265 VPS/SPS/PPS, AV1 sequence header)
+   */
+  parameterSetIndices: Array<number>
+}
+
+/** One analyzed chunk or packet */
+export interface BitstreamFrame {
+  /** Position in the input */
+  index: number
+  /** Presentation timestamp in microseconds, if known */
+  timestamp?: number
+  /** Size in bytes */
+  byteLength: number
+  /** Picture type of the frame */
+  frameType: BitstreamFrameType
+  /** Starts a GOP: H.264 IDR, H.265 IRAP, or a shown VP9/AV1 key frame */
+  key: boolean
+}
+
+/** Picture type of an analyzed frame */
+export type BitstreamFrameType = /** Intra-coded: H.264/H.265 I slice, VP9/AV1 key or intra-only frame */
+  | 'I'
+  /** Predicted from earlier frames */
+  | 'P'
+  /** Bi-predicted (H.264/H.265 only) */
+  | 'B'
+  /** No picture header could be parsed */
+  | 'unknown'
+
+/** How H.264/H.265 NAL units are delimited */
+export type BitstreamNalFormat = /** Start codes (0x000001 / 0x00000001) */
+  | 'annexb'
+  /** 4-byte length prefixes (AVCC/HVCC, as stored in MP4) */
+  | 'lengthPrefixed'
+
+export interface BuildInfo {
+  /** FFmpeg version (e.g., "7.1") */
+  ffmpegVersion: string
+  /** FFmpeg ./configure command line */
+  ffmpegConfiguration: string
+  /** FFmpeg and codec library versions */
+  libraries: Array<LibraryVersion>
+  /** Names of all compiled-in encoders */
+  encoders: Array<string>
+  /** Names of all compiled-in decoders */
+  decoders: Array<string>
+  /** Hardware device types compiled into FFmpeg (not necessarily usable on this machine) */
+  hardwareDeviceTypes: Array<string>
+  /** SIMD/CPU features detected at runtime (e.g., "avx2", "neon") */
+  cpuFlags: Array<string>
+}
+
+/** Options for CfrRegulator */
+export interface CfrRegulatorInit {
+  /** Target frame rate in frames per second (e.g. 30 or 30000 / 1001) */
+  framerate: number
+  /** Timestamp of the first output slot in microseconds (default: first frame's timestamp) */
+  startTimestamp?: number
+}
+
+/** Outcome of enforcing the `maxChunkBytes` encode option for one chunk */
+export interface ChunkBudgetMetadata {
+  /** Number of re-encodes at a higher quantizer */
+  retries: number
+  /** True if the chunk still exceeds the budget and was delivered anyway */
+  exceeded: boolean
+}
+
+/** Encoder capabilities of a codec string */
+export interface CodecCapabilities {
+  /** The codec string the capabilities describe */
+  codec: string
+  /** VideoFrame format the encoder is opened with (without alpha) */
+  encoderFormat: string
+  /** Bits per sample of `encoderFormat` */
+  bitDepth: number
+  /** VideoFrame formats encode() accepts, converting them to `encoderFormat` */
+  inputFormats: Array<string>
+}
+
+/** Rolling queue latency statistics for a codec's worker (milliseconds) */
+export interface CodecQueueStats {
+  /** Number of commands in the rolling window (up to 256) */
+  samples: number
+  /** Median time a command waited in the queue before the worker picked it up */
+  queueWaitP50: number
+  /** 95th percentile queue wait */
+  queueWaitP95: number
+  /** Median time the worker spent processing a command */
+  processingP50: number
+  /** 95th percentile processing time */
+  processingP95: number
+}
+
 /** Encoder state per WebCodecs spec */
 export type CodecState = /** Encoder not configured */
   | 'unconfigured'
@@ -1427,6 +2415,27 @@ export type ColorSpaceConversion = /** Apply default color space conversion (spe
   /** No color space conversion */
   | 'none'
 
+/** How decode() inputs reached FFmpeg (extension) */
+export interface DecodeInputStats {
+  /** Chunks whose bytes were copied into a new packet */
+  copiedChunks: number
+  /** Bytes copied for those chunks */
+  copiedBytes: number
+  /** Chunks decoded from the packet they were demuxed into, without a copy */
+  sharedChunks: number
+}
+
+/** Decode sanity limits */
+export interface DecodeLimits {
+  /** Maximum coded width or height in pixels (default: 16384) */
+  maxDimension?: number
+  /**
+   * Maximum estimated allocation per decoded frame in bytes, at 4 bytes per pixel
+   * (default: 1 GiB)
+   */
+  maxFrameBytes?: number
+}
+
 /** Audio decoder configuration exposed to JavaScript */
 export interface DemuxerAudioDecoderConfig {
   /** Codec string */
@@ -1443,7 +2452,7 @@ export interface DemuxerAudioDecoderConfig {
 export interface DemuxerTrackInfo {
   /** Track index */
   index: number
-  /** Track type ("video" or "audio") */
+  /** Track type ("video", "audio", "subtitle" or "data") */
   trackType: string
   /** Codec string (WebCodecs format) */
   codec: string
@@ -1457,6 +2466,12 @@ export interface DemuxerTrackInfo {
   sampleRate?: number
   /** Number of channels (audio only) */
   numberOfChannels?: number
+  /** Stream-level side data to pass to a muxer track's `sideData` when remuxing */
+  sideData: Array<TrackSideData>
+  /** Language tag from the container, if set */
+  language?: string
+  /** Subtitle kind (WebVTT subtitle tracks only) */
+  subtitleKind?: SubtitleKind
 }
 
 /** Video decoder configuration exposed to JavaScript */
@@ -1469,6 +2484,18 @@ export interface DemuxerVideoDecoderConfig {
   codedHeight: number
   /** Codec-specific description data (avcC/hvcC) */
   description?: Uint8Array
+  /** Color space signalled by the container (e.g. MP4 colr/nclx, Matroska Colour) */
+  colorSpace?: VideoColorSpaceInit
+  /** Raw ICC profile from the container (e.g. MP4 colr/prof); not applied by the decoder */
+  iccProfile?: Uint8Array
+  /**
+   * H.264/HEVC only: whether parameter sets are carried in the bitstream
+   *
+   * True for `avc3`/`hev1` sample entries and for tracks whose container has
+   * no avcC/hvcC. When true and `description` is absent, the absence is
+   * intentional: the decoder reads the parameter sets from the keyframes.
+   */
+  parameterSetsInBand?: boolean
 }
 
 /** DOMRectInit for specifying regions */
@@ -1505,6 +2532,23 @@ export interface EncodedVideoChunkMetadata {
   svc?: SvcOutputMetadata
   /** Alpha channel side data (when alpha option is "keep") */
   alphaSideData?: Uint8Array
+  /** Correlation tag passed to encode() for the frame this chunk encodes */
+  tag?: number | string
+  /** HDR dynamic metadata of the frame this chunk encodes */
+  hdrDynamicMetadata?: Array<HdrDynamicMetadata>
+  /** Byte budget outcome (only present when encode() was given maxChunkBytes) */
+  chunkBudget?: ChunkBudgetMetadata
+  /**
+   * References this chunk was stored as and predicted from (only present once
+   * encode() was given markAsLongTermReference or referenceOnly)
+   */
+  referenceControl?: ReferenceControlMetadata
+  /** PSNR/SSIM against the source frame (only present with computeQualityMetrics) */
+  quality?: QualityMetrics
+  /** True when sceneCutDetection made this chunk a key frame at a detected scene cut */
+  sceneCut?: boolean
+  /** Actual bits and encoder rate-control state (only present with reportRateControlStats) */
+  rateControl?: RateControlStats
 }
 
 /** JavaScript-facing metadata type for video chunks */
@@ -1518,7 +2562,7 @@ export interface EncodedVideoChunkMetadataJs {
    * This contains the encoded alpha channel data that should be written
    * as BlockAdditions in WebM/MKV containers.
    */
-  alphaSideData?: Uint8Array
+  alphaSideData?: BufferSource
 }
 
 /** Type of encoded video chunk */
@@ -1527,11 +2571,203 @@ export type EncodedVideoChunkType = /** Keyframe - can be decoded independently
   /** Delta frame - depends on previous frames */
   | 'delta'
 
+/** Result of estimateEncode() */
+export interface EncodeEstimate {
+  /** Per-segment statistics, in input order */
+  samples: Array<EncodeEstimateSample>
+  /** Duration of the input (microseconds) */
+  durationUs: number
+  /** Bitrate over all sampled segments (bits per second) */
+  projectedBitrate: number
+  /** Projected size of the encoded video track for the whole input */
+  projectedBytes: number
+  /** Mean quantizer over all sampled frames, absent if the encoder reports none */
+  averageQp?: number
+  /** FFmpeg encoder used for the samples (e.g. "libx264") */
+  encoder: string
+}
+
+/** Encode statistics of one sampled segment */
+export interface EncodeEstimateSample {
+  /** Timestamp of the segment's first frame (microseconds) */
+  startUs: number
+  /** Media duration covered by the segment (microseconds) */
+  durationUs: number
+  /** Number of frames encoded */
+  frames: number
+  /** Encoded bytes */
+  bytes: number
+  /** Encoded bitrate over `durationUs` (bits per second) */
+  bitrate: number
+  /** Mean quantizer reported by the encoder, absent if it reports none */
+  averageQp?: number
+}
+
+/** How H.264/HEVC output reached its AVCC/HVCC chunks (extension) */
+export interface EncodeOutputStats {
+  /** Chunks whose start codes were rewritten inside the encoder's packet buffer */
+  rewrittenChunks: number
+  /** Chunks whose bytes were copied into a new buffer during the conversion */
+  copiedChunks: number
+  /** Bytes copied for those chunks */
+  copiedBytes: number
+}
+
+/** Default FFmpeg options for encoders */
+export interface EncoderOptionDefaults {
+  /** AVOptions applied to every VideoEncoder */
+  video?: Record<string, string | number>
+  /** AVOptions applied to every AudioEncoder */
+  audio?: Record<string, string | number>
+}
+
+/**
+ * Estimate the encoded size and quantizer of a video for an encoder config
+ *
+ * Encodes `samples` segments of `sampleDurationMs` each, spread evenly over
+ * the input's video track, and projects the bitrate measured over them to
+ * the whole input. Samples are always encoded in software, so the estimate
+ * matches a software encode of the same config. Nothing is written to disk.
+ *
+ * ```javascript
+ * const estimate = await estimateEncode('movie.mp4', { codec: 'avc1.640028', width: 1920, height: 1080, bitrate: 4_000_000 }, { samples: 8 });
+ * console.log(estimate.projectedBytes, estimate.averageQp);
+ * ```
+ */
+export declare function estimateEncode(input: string | BufferSource, config: VideoEncoderConfig, options?: EstimateEncodeOptions | undefined | null): Promise<EncodeEstimate>
+
+/** Options for estimateEncode() */
+export interface EstimateEncodeOptions {
+  /** Number of segments to encode, spread evenly over the input (default: 5, max: 64) */
+  samples?: number
+  /** Length of each segment in milliseconds (default: 2000, max: 60000) */
+  sampleDurationMs?: number
+}
+
 /** Options for removeEventListener (W3C DOM spec) */
 export interface EventListenerOptions {
   capture?: boolean
 }
 
+/**
+ * Decode the audio track of a media file into a single AudioData
+ *
+ * The result is f32-planar at `targetSampleRate` (default: the track's rate),
+ * downmixed to one channel when `mono` is set, and trimmed to
+ * `[startUs, endUs)`. Ranges too large to hold in memory at once are rejected;
+ * read those with AudioExtractor instead.
+ *
+ * ```javascript
+ * const pcm = await extractAudio('movie.mp4', { startUs: 0, endUs: 2_000_000, targetSampleRate: 16000, mono: true });
+ * ```
+ */
+export declare function extractAudio(input: string | BufferSource, options?: ExtractAudioOptions | undefined | null): Promise<AudioData>
+
+/** Options for extractAudio() and AudioExtractor */
+export interface ExtractAudioOptions {
+  /** Start of the range in microseconds (default: 0) */
+  startUs?: number
+  /** End of the range in microseconds, exclusive (default: end of stream) */
+  endUs?: number
+  /** Output sample rate (default: the track's sample rate) */
+  targetSampleRate?: number
+  /** Downmix to a single channel (default: false) */
+  mono?: boolean
+  /** Length of each AudioData yielded by AudioExtractor, in microseconds (default: 1000000) */
+  chunkDurationUs?: number
+}
+
+/**
+ * Losslessly copy one track of a media file into a new container
+ *
+ * Packets are stream-copied without decoding, keeping their bytes, timing
+ * and the track's codec configuration. `trackId` is a track `index` as
+ * listed by a demuxer's `tracks`. Rejects with NotSupportedError when the
+ * codec cannot be stored in the requested format (e.g. AAC in WebM).
+ *
+ * ```javascript
+ * const m4a = await extractTrack('movie.mp4', audioTrack.index, { format: 'm4a' });
+ * ```
+ */
+export declare function extractTrack(input: string | Uint8Array, trackId: number, options?: ExtractTrackOptions | undefined | null): Promise<Uint8Array>
+
+/** Container written by extractTrack() */
+export type ExtractTrackFormat = /** MP4 (H.264, HEVC, AV1, AAC, MP3, FLAC, Opus) */
+  | 'mp4'
+  /** MP4 holding a single audio track */
+  | 'm4a'
+  /** Matroska (any codec) */
+  | 'mkv'
+  /** WebM (VP8, VP9, AV1, Opus, Vorbis) */
+  | 'webm'
+
+/** Options for extractTrack() */
+export interface ExtractTrackOptions {
+  /** Output container (default: "m4a" for audio tracks, "mp4" for video tracks) */
+  format?: ExtractTrackFormat
+}
+
+/** Codecs, containers and hardware accelerators compiled into this build */
+export interface Features {
+  /** An H.264 encoder is registered */
+  h264Encode: boolean
+  /** An H.264 decoder is registered */
+  h264Decode: boolean
+  /** An H.265/HEVC encoder is registered */
+  hevcEncode: boolean
+  /** An H.265/HEVC decoder is registered */
+  hevcDecode: boolean
+  /** A VP8 encoder is registered */
+  vp8Encode: boolean
+  /** A VP8 decoder is registered */
+  vp8Decode: boolean
+  /** A VP9 encoder is registered */
+  vp9Encode: boolean
+  /** A VP9 decoder is registered */
+  vp9Decode: boolean
+  /** An AV1 encoder is registered */
+  av1Encode: boolean
+  /** An AV1 decoder is registered */
+  av1Decode: boolean
+  /** An Opus encoder is registered */
+  opusEncode: boolean
+  /** An Opus decoder is registered */
+  opusDecode: boolean
+  /** An AAC encoder is registered */
+  aacEncode: boolean
+  /** An AAC decoder is registered */
+  aacDecode: boolean
+  /** An MP3 encoder is registered */
+  mp3Encode: boolean
+  /** An MP3 decoder is registered */
+  mp3Decode: boolean
+  /** A FLAC encoder is registered */
+  flacEncode: boolean
+  /** A FLAC decoder is registered */
+  flacDecode: boolean
+  /** A Vorbis encoder is registered */
+  vorbisEncode: boolean
+  /** A Vorbis decoder is registered */
+  vorbisDecode: boolean
+  /** An MP4 muxer is registered */
+  mp4Mux: boolean
+  /** An MP4 demuxer is registered */
+  mp4Demux: boolean
+  /** A WebM muxer is registered */
+  webmMux: boolean
+  /** A WebM demuxer is registered */
+  webmDemux: boolean
+  /** A Matroska muxer is registered */
+  mkvMux: boolean
+  /** A Matroska demuxer is registered */
+  mkvDemux: boolean
+  /**
+   * Hardware device types compiled into FFmpeg (e.g. "videotoolbox", "cuda");
+   * use getAvailableHardwareAccelerators() to check that a device can be opened
+   */
+  hardwareAccelerators: ReadonlyArray<string>
+}
+
 /** FLAC encoder configuration (W3C WebCodecs FLAC Registration) */
 export interface FlacEncoderConfig {
   /** Block size (0 = auto, default: 0) */
@@ -1540,12 +2776,60 @@ export interface FlacEncoderConfig {
   compressLevel?: number
 }
 
+/** Number of frame intervals in one histogram bucket */
+export interface FrameIntervalBucket {
+  /** Interval rounded to the nearest millisecond, in microseconds */
+  intervalUs: number
+  /** Number of intervals in this bucket */
+  count: number
+}
+
 /** Get available hardware accelerators (only those that can be used) */
 export declare function getAvailableHardwareAccelerators(): Array<string>
 
+/**
+ * Get build and runtime information (FFmpeg version/configuration, codec
+ * library versions, compiled-in codecs and hwaccels, detected CPU features)
+ */
+export declare function getBuildInfo(): BuildInfo
+
+/**
+ * Get the VideoFrame formats a video encoder codec accepts
+ *
+ * Throws NotSupportedError for codec strings VideoEncoder does not support
+ * and for codecs without an encoder in this build.
+ */
+export declare function getCodecCapabilities(codec: string): CodecCapabilities
+
+/** Get the current process-wide decode limits */
+export declare function getDecodeLimits(): DecodeLimits
+
+/** Get the process-wide default FFmpeg options for encoders */
+export declare function getDefaultEncoderOptions(): EncoderOptionDefaults
+
+/**
+ * Codecs, containers and hardware accelerators compiled into this build
+ *
+ * The flags are read from the FFmpeg registries once; every call returns a
+ * frozen copy. A codec flag is true when an encoder or decoder for it is
+ * registered; getCodecCapabilities() throws for exactly the video codecs
+ * whose encode flag is false.
+ */
+export declare function getFeatures(): Readonly<Features>
+
 /** Get list of all known hardware accelerators and their availability */
 export declare function getHardwareAccelerators(): Array<HardwareAccelerator>
 
+/**
+ * Get the hardware encoder sessions open in this process, per accelerator
+ *
+ * Only accelerators that opened an encoder or refused one are listed.
+ */
+export declare function getHardwareSessionCounts(): Array<HardwareSessionCount>
+
+/** Get the effective level of a tracing target (default: "*") */
+export declare function getLogLevel(target?: string | undefined | null): LogLevel
+
 /** Get the preferred hardware accelerator for the current platform */
 export declare function getPreferredHardwareAccelerator(): string | null
 
@@ -1567,6 +2851,24 @@ export interface HardwareAccelerator {
   available: boolean
 }
 
+/** Hardware encoder sessions of one accelerator */
+export interface HardwareSessionCount {
+  /** Accelerator name, as in getHardwareAccelerators() */
+  accelerator: string
+  /** Hardware encoders currently open in this process */
+  activeEncoders: number
+  /** Encoders that were open the last time the driver refused another session */
+  maxEncoders?: number
+  /** Number of encoder opens refused by the driver's session limit */
+  limitHits: number
+}
+
+/** Kind of per-frame HDR dynamic metadata */
+export type HdrDynamicMetadataType = /** ITU-T T.35 registered user data (e.g. HDR10+ / SMPTE ST 2094-40) */
+  | 'itu-t-t35'
+  /** Dolby Vision RPU NAL unit */
+  | 'dolby-vision-rpu'
+
 /** HEVC (H.265) bitstream format (W3C WebCodecs HEVC Registration) */
 export type HevcBitstreamFormat = /** HEVC format with parameter sets in description (ISO 14496-15) */
   | 'hevc'
@@ -1579,23 +2881,104 @@ export interface HevcEncoderConfig {
   format?: HevcBitstreamFormat
 }
 
+/** Options for ImageDecoder.decodeAll() (extension) */
+export interface ImageDecodeAllOptions {
+  /**
+   * MIME type of every image; when omitted, each image's format is detected
+   * from its signature (JPEG, PNG, GIF, WebP or BMP)
+   */
+  type?: string
+  /** Color space conversion mode (default: "default") */
+  colorSpaceConversion?: ColorSpaceConversion
+  /** Scale every image to this width - must be paired with desiredHeight */
+  desiredWidth?: number
+  /** Scale every image to this height - must be paired with desiredWidth */
+  desiredHeight?: number
+  /** Pixel format of the returned frames (see ImageDecodeOptions.preferredFormat) */
+  preferredFormat?: VideoPixelFormat
+  /**
+   * Maximum number of images decoded at once, which bounds the memory held
+   * by in-flight decodes (default: number of CPUs)
+   */
+  concurrency?: number
+}
+
 /** Image decode options */
 export interface ImageDecodeOptions {
   /** Frame index to decode (for animated images) */
   frameIndex?: number
   /** Whether to only decode complete frames */
   completeFramesOnly?: boolean
+  /**
+   * Pixel format of the returned frame, e.g. "I420" for frames passed to
+   * VideoEncoder (extension)
+   *
+   * The conversion runs once per frame and format on the decode thread and is
+   * the same one VideoEncoder.encode() applies, so the encoder can then use the
+   * frame without converting or copying it.
+   */
+  preferredFormat?: VideoPixelFormat
 }
 
+/** How encoded video chunks map to pictures (extension) */
+export type InputGranularity = /** Every chunk holds one complete access unit (one picture) */
+  | 'accessUnit'
+  /** Chunks hold one or more H.264 NAL units; pictures may span chunks */
+  | 'nal'
+
 /** Check if a specific hardware accelerator is available */
 export declare function isHardwareAcceleratorAvailable(name: string): boolean
 
+/** Options for keyFramePackets() */
+export interface KeyFramePacketsOptions {
+  /** Timestamp to start from in microseconds (default: 0) */
+  startUs?: number
+  /**
+   * Minimum distance between yielded key frames in microseconds (default: every key frame)
+   *
+   * Each key frame yielded is the first one at or after `startUs + n * intervalUs`.
+   */
+  intervalUs?: number
+}
+
 /** Latency mode for video encoding (W3C WebCodecs spec) */
 export type LatencyMode = /** Optimize for quality (default) */
   | 'quality'
   /** Optimize for low latency */
   | 'realtime'
 
+/** Version of a linked library */
+export interface LibraryVersion {
+  /** Library name (e.g., "libavcodec", "x264") */
+  name: string
+  /** Version string as reported by the library */
+  version: string
+}
+
+/** Log verbosity for a tracing target */
+export type LogLevel = /** No messages */
+  | 'off'
+  /** Errors only */
+  | 'error'
+  /** Warnings and errors */
+  | 'warn'
+  /** Informational messages and above */
+  | 'info'
+  /** Debug messages and above */
+  | 'debug'
+  /** Every message */
+  | 'trace'
+
+/** Log message delivered to the setLogCallback() function */
+export interface LogMessage {
+  /** Message level */
+  level: LogLevel
+  /** Tracing target, e.g. "ffmpeg" or "webcodecs" */
+  target: string
+  /** Message text, followed by any structured fields as `key=value` */
+  message: string
+}
+
 /** Audio track configuration for MKV muxer */
 export interface MkvAudioTrackConfig {
   /** Codec string (e.g., "mp4a.40.2", "opus", "flac", "vorbis", "ac3") */
@@ -1605,13 +2988,18 @@ export interface MkvAudioTrackConfig {
   /** Number of audio channels */
   numberOfChannels: number
   /** Codec-specific description data */
-  description?: Uint8Array
+  description?: BufferSource
 }
 
 /** MKV muxer options */
 export interface MkvMuxerOptions {
   /** Enable live streaming mode */
   live?: boolean
+  /**
+   * Omit the "Lavf" writing-application tag, write the bare "Lavf" muxing
+   * application and deterministic UIDs (default: false)
+   */
+  omitEncoderTags?: boolean
   /** Enable streaming output mode */
   streaming?: StreamingMuxerOptions
 }
@@ -1624,10 +3012,18 @@ export interface MkvVideoTrackConfig {
   width: number
   /** Video height in pixels */
   height: number
-  /** Frame rate (frames per second) */
+  /** Frame rate (frames per second); 29.97, 23.976 and 59.94 are taken as N*1000/1001 */
   framerate?: number
   /** Codec-specific description data */
-  description?: Uint8Array
+  description?: BufferSource
+  /** Color space to signal in the container (MP4 colr/nclx, Matroska Colour) */
+  colorSpace?: VideoColorSpaceInit
+  /** Display width in pixels (defaults to width); written as the track's presentation width */
+  displayWidth?: number
+  /** Display height in pixels (defaults to height); written as the track's presentation height */
+  displayHeight?: number
+  /** Stream-level side data, e.g. `DemuxerTrackInfo.sideData` when remuxing */
+  sideData?: Array<TrackSideData>
 }
 
 /** Audio track configuration for MP4 muxer */
@@ -1639,7 +3035,20 @@ export interface Mp4AudioTrackConfig {
   /** Number of audio channels */
   numberOfChannels: number
   /** Codec-specific description data (esds for AAC, etc.) */
-  description?: Uint8Array
+  description?: BufferSource
+  /**
+   * Priming samples at the start of the track, for gapless playback (AAC, MP3)
+   *
+   * Written as an edit list and iTunSMPB tag. Defaults to the encoder delay
+   * AudioEncoder marks on its first chunk.
+   */
+  encoderDelay?: number
+  /**
+   * Padding samples after the last real sample (AAC, MP3)
+   *
+   * Defaults to the padding AudioEncoder marks on its last chunk.
+   */
+  padding?: number
 }
 
 /** MP4 muxer options */
@@ -1656,6 +3065,30 @@ export interface Mp4MuxerOptions {
   fragmented?: boolean
   /** Enable streaming output mode */
   streaming?: StreamingMuxerOptions
+  /**
+   * Directory for the temporary file fastStart writes the output to before
+   * moving the moov atom (default: the OS temp directory)
+   */
+  tempDir?: string
+  /**
+   * Maximum bytes the temporary file may hold; a larger output fails with
+   * QuotaExceededError (default: unlimited)
+   */
+  maxTempBytes?: number
+}
+
+/** A finished segment of a SegmentedMp4Muxer recording */
+export interface Mp4Segment {
+  /** Segment index, starting at 0 */
+  index: number
+  /** Timestamp of the first chunk in microseconds, in the input's timeline */
+  startTimestamp: number
+  /** Segment duration in microseconds */
+  duration: number
+  /** File the segment was written to (when `filename` is set) */
+  path?: string
+  /** MP4 data of the segment (when `filename` is not set) */
+  data?: Uint8Array
 }
 
 /** Video track configuration for MP4 muxer */
@@ -1666,10 +3099,35 @@ export interface Mp4VideoTrackConfig {
   width: number
   /** Video height in pixels */
   height: number
-  /** Frame rate (frames per second) */
+  /** Frame rate (frames per second); 29.97, 23.976 and 59.94 are taken as N*1000/1001 */
   framerate?: number
+  /**
+   * Track timescale in ticks per second (e.g. 30000 or 90000)
+   *
+   * Defaults to a multiple of the framerate numerator, so every frame lasts a
+   * whole number of ticks and timestamps do not drift over long recordings.
+   */
+  timescale?: number
   /** Codec-specific description data (avcC/hvcC/av1C from encoder metadata) */
-  description?: Uint8Array
+  description?: BufferSource
+  /** Color space to signal in the container (MP4 colr/nclx, Matroska Colour) */
+  colorSpace?: VideoColorSpaceInit
+  /** ICC profile to write as an MP4 colr/prof box (takes precedence over colorSpace) */
+  iccProfile?: BufferSource
+  /** Display width in pixels (defaults to width); written as the track's presentation width */
+  displayWidth?: number
+  /** Display height in pixels (defaults to height); written as the track's presentation height */
+  displayHeight?: number
+  /** Stream-level side data, e.g. `DemuxerTrackInfo.sideData` when remuxing */
+  sideData?: Array<TrackSideData>
+}
+
+/** Per-track summaries returned by the muxers */
+export interface MuxerBitrateSummary {
+  /** Video track summary (absent without a video track) */
+  video?: BitrateSummary
+  /** Audio track summary (absent without an audio track) */
+  audio?: BitrateSummary
 }
 
 /** Opus application mode (W3C WebCodecs Opus Registration) */
@@ -1680,6 +3138,18 @@ export type OpusApplication = /** Optimize for VoIP (speech intelligibility) */
   /** Minimize coding delay */
   | 'lowdelay'
 
+/** Opus audio bandwidth selected by the TOC configuration number */
+export type OpusBandwidth = /** 4 kHz */
+  | 'narrowband'
+  /** 6 kHz */
+  | 'mediumband'
+  /** 8 kHz */
+  | 'wideband'
+  /** 12 kHz */
+  | 'superwideband'
+  /** 20 kHz */
+  | 'fullband'
+
 /** Opus bitstream format (W3C WebCodecs Opus Registration) */
 export type OpusBitstreamFormat = /** Opus packets (RFC 6716) - no metadata needed for decoding */
   | 'opus'
@@ -1709,6 +3179,14 @@ export interface OpusEncoderConfig {
   usedtx?: boolean
 }
 
+/** Opus coding mode selected by the TOC configuration number */
+export type OpusMode = /** SILK-only (speech, up to wideband) */
+  | 'silk'
+  /** SILK and CELT combined (super-wideband and fullband speech) */
+  | 'hybrid'
+  /** CELT-only (music and low-delay) */
+  | 'celt'
+
 /** Opus signal type hint (W3C WebCodecs Opus Registration) */
 export type OpusSignal = /** Auto-detect signal type */
   | 'auto'
@@ -1717,6 +3195,58 @@ export type OpusSignal = /** Auto-detect signal type */
   /** Voice/speech signal */
   | 'voice'
 
+/** Fields of an Opus packet's TOC byte */
+export interface OpusToc {
+  /** TOC configuration number (0-31) */
+  config: number
+  /** Coding mode */
+  mode: OpusMode
+  /** Audio bandwidth */
+  bandwidth: OpusBandwidth
+  /** Whether the packet is coded as stereo */
+  stereo: boolean
+  /** Frame count code (0-3) */
+  code: number
+  /** Number of Opus frames in the packet */
+  frameCount: number
+  /** Duration of each frame in microseconds */
+  frameDuration: number
+  /** Duration of the packet in microseconds */
+  duration: number
+}
+
+/**
+ * In-band parameter sets that replaced the active ones (extension)
+ *
+ * Passed to the `parameterSetChange` callback of the VideoDecoder init dictionary.
+ */
+export interface ParameterSetChange {
+  /** Codec string of the decoder config */
+  codec: string
+  /** Timestamp of the chunk carrying the new parameter sets, in microseconds */
+  timestamp: number
+  /** Where the replaced parameter sets came from */
+  previousSource: ParameterSetSource
+  /** Replaced parameter sets as Annex B NAL units */
+  previous: Uint8Array
+  /** New parameter sets as Annex B NAL units */
+  current: Uint8Array
+  /**
+   * avcC/hvcC record built from the new parameter sets, for a fresh decoder
+   * config or a muxer's `updateVideoDescription()`
+   */
+  description?: Uint8Array
+}
+
+/** Where the parameter sets a decoder used came from (extension) */
+export type ParameterSetSource = /** The `description` of the decoder config */
+  | 'description'
+  /** SPS/PPS carried in an earlier chunk */
+  | 'in-band'
+
+/** Parse the TOC byte of an Opus packet */
+export declare function parseToc(data: BufferSource): OpusToc
+
 /** Layout information for a single plane per WebCodecs spec */
 export interface PlaneLayout {
   /** Byte offset from the start of the buffer to the start of the plane */
@@ -1725,10 +3255,104 @@ export interface PlaneLayout {
   stride: number
 }
 
+/** Quality of an encoded chunk measured against its source frame */
+export interface QualityMetrics {
+  /** PSNR over the Y, U and V planes in dB (Infinity when lossless) */
+  psnr: number
+  /** PSNR of the luma plane in dB (Infinity when lossless) */
+  psnrY: number
+  /** Mean luma SSIM in [0, 1] (1 when lossless) */
+  ssim: number
+}
+
+/** Rate-control outcome of one encoded chunk */
+export interface RateControlStats {
+  /** Bits in the chunk */
+  actualBits: number
+  /** Quantizer the encoder reported for the frame */
+  qp?: number
+  /**
+   * VBV/CPB buffer fullness in [0, 1] when the frame is removed for decoding,
+   * if the encoder reported its buffer delay
+   */
+  bufferFullness?: number
+}
+
+/**
+ * Raw packet record for remuxing without decoding
+ *
+ * Timestamps stay in the track's time base (`timeBaseNum / timeBaseDen`
+ * seconds per tick) and include DTS, so packets copied between containers
+ * with `readPacket()` and `writePacket()` keep their timing bit-exact.
+ */
+export interface RawPacket {
+  /** Source track index */
+  trackId: number
+  /** Track type ("video" or "audio"); selects the muxer track in writePacket() */
+  trackType: string
+  /** Presentation timestamp in time base units */
+  pts?: number
+  /** Decode timestamp in time base units (writePacket() defaults it to pts) */
+  dts?: number
+  /** Duration in time base units */
+  duration?: number
+  /** Time base numerator */
+  timeBaseNum: number
+  /** Time base denominator */
+  timeBaseDen: number
+  /** Packet flags (1 = key, 2 = corrupt, 4 = discard, 16 = disposable) */
+  flags: number
+  /** Packet payload */
+  data: Uint8Array
+}
+
+/**
+ * Prepend an ADTS header to a raw AAC access unit
+ *
+ * `asc` is the stream's AudioSpecificConfig (e.g. the decoder config description).
+ */
+export declare function rawToAdts(frame: BufferSource, asc: BufferSource): Uint8Array
+
+/** Options for reading raw video */
+export interface RawVideoReaderInit {
+  /** Frame width in pixels */
+  width: number
+  /** Frame height in pixels */
+  height: number
+  /** Pixel format of the stored frames */
+  format: VideoPixelFormat
+  /** Frame rate used to generate timestamps (default: 30) */
+  framerate?: number
+  /** Index of the first frame to read (default: 0) */
+  startFrame?: number
+  /** Maximum number of frames to read (default: until end of input) */
+  frameCount?: number
+}
+
+/** Options for writing raw video */
+export interface RawVideoWriterInit {
+  /** Pixel format to store; frames in other formats are converted */
+  format: VideoPixelFormat
+}
+
+/** Reference frame usage of one chunk (reference control extension) */
+export interface ReferenceControlMetadata {
+  /** Long-term reference id this chunk was stored as (`markAsLongTermReference`) */
+  longTermReference?: number
+  /** Long-term reference ids this chunk is predicted from */
+  references: Array<number>
+  /** Whether this chunk is also predicted from the previous frame */
+  referencesPreviousFrame: boolean
+}
+
+/** Reset decode limits to their defaults */
+export declare function resetDecodeLimits(): void
+
 /**
  * Reset all hardware fallback state.
  *
- * This clears all failure counts and re-enables hardware acceleration.
+ * This clears all failure counts, re-enables hardware acceleration and
+ * forgets the encoders probed by isConfigSupported().
  * Useful for:
  * - Test isolation (call in beforeEach)
  * - Error recovery after fixing hardware issues
@@ -1736,12 +3360,75 @@ export interface PlaneLayout {
  */
 export declare function resetHardwareFallbackState(): void
 
+/**
+ * Override the process-wide decode limits
+ *
+ * Omitted fields keep their current value. Limits apply to decoders
+ * configured and files loaded after the call.
+ */
+export declare function setDecodeLimits(limits: DecodeLimits): void
+
+/**
+ * Replace the process-wide default FFmpeg options for encoders
+ *
+ * Only codecs configured afterwards use the new defaults. A missing `video`
+ * or `audio` member clears that kind's defaults.
+ */
+export declare function setDefaultEncoderOptions(options: EncoderOptionDefaults): void
+
+/**
+ * Share one hardware device per accelerator between all codecs (default: true)
+ *
+ * Call with false before configuring codecs that need an isolated device.
+ * Codecs configured earlier keep the device they already hold.
+ */
+export declare function setHardwareDeviceSharing(enabled: boolean): void
+
+/**
+ * Forward every message that passes the filter to `callback` (null to stop)
+ *
+ * Messages keep going to stderr as well. The callback runs on the main
+ * thread after the message was logged, so it cannot change the outcome of
+ * the operation that logged it.
+ */
+export declare function setLogCallback(callback: ((message: LogMessage) => void) | null): void
+
+/**
+ * Set the level of a tracing target at runtime
+ *
+ * `target` is a target prefix such as "ffmpeg" or "webcodecs", or "*" for
+ * the default level of targets without their own setting. Raising "ffmpeg"
+ * to debug or trace also raises FFmpeg's av_log level so those messages are
+ * produced at all.
+ */
+export declare function setLogLevel(target: string, level: LogLevel): void
+
 /** Streaming mode options for muxers */
 export interface StreamingMuxerOptions {
   /** Buffer capacity for streaming output (default: 256KB) */
   bufferCapacity?: number
 }
 
+/** A WebVTT cue, as written with `writeSubtitleChunk()` and read with `readSubtitleCues()` */
+export interface SubtitleCue {
+  /** Start time in microseconds */
+  timestamp: number
+  /** Display duration in microseconds */
+  duration: number
+  /** Cue payload (may contain WebVTT markup and line breaks) */
+  text: string
+  /** Cue settings following the timing line, e.g. "line:0 position:20% align:start" */
+  settings?: string
+  /** Optional cue identifier line */
+  identifier?: string
+}
+
+/** Kind of a WebVTT text track (WebM D_WEBVTT/SUBTITLES or D_WEBVTT/CAPTIONS) */
+export type SubtitleKind = /** Translation of the dialogue */
+  | 'subtitles'
+  /** Transcription including sound effects, for the hearing impaired */
+  | 'captions'
+
 /** SVC (Scalable Video Coding) output metadata (W3C WebCodecs spec) */
 export interface SvcOutputMetadata {
   /** Temporal layer ID for this frame */
@@ -1754,6 +3441,83 @@ export interface SvcOutputMetadataJs {
   temporalLayerId?: number
 }
 
+/** Source of decoder output timestamps (extension) */
+export type TimestampMode = /** Keep the timestamp of the chunk each output was decoded from */
+  | 'container'
+  /** Renumber outputs contiguously from the first output */
+  | 'normalized'
+
+/** Packet timing and bitrate statistics of a track */
+export interface TrackAnalysis {
+  /** Track index */
+  trackId: number
+  /** Number of packets scanned */
+  packetCount: number
+  /** Whether the scan reached the end of the track (false when stopped by maxPackets) */
+  complete: boolean
+  /** Shortest interval between consecutive presentation timestamps, in microseconds */
+  minIntervalUs?: number
+  /** Longest interval between consecutive presentation timestamps, in microseconds */
+  maxIntervalUs?: number
+  /** Mean interval between consecutive presentation timestamps, in microseconds */
+  meanIntervalUs?: number
+  /** Frame interval distribution, sorted by interval */
+  intervalHistogram: Array<FrameIntervalBucket>
+  /** Average bitrate over the scanned range in bits per second */
+  averageBitrate?: number
+  /** Bitrate per time window, in presentation order */
+  bitrateWindows: Array<BitrateWindow>
+  /** Whether frame intervals vary by more than timestamp rounding */
+  isVariableFrameRate: boolean
+}
+
+/** Options for analyzeTrack() */
+export interface TrackAnalysisOptions {
+  /** Maximum number of the track's packets to scan (default: 100000) */
+  maxPackets?: number
+  /** Length of each bitrate window in microseconds (default: 1000000) */
+  bitrateWindowUs?: number
+}
+
+/**
+ * Opaque stream-level side data blob
+ *
+ * `data` holds FFmpeg's struct for the kind (AVSphericalMapping, AVStereo3D, ...)
+ * and is only meant to be handed back to a muxer, not interpreted.
+ */
+export interface TrackSideData {
+  /** Side data kind */
+  type: TrackSideDataType
+  /** Raw side data bytes */
+  data: Uint8Array
+}
+
+/** Kind of stream-level side data */
+export type TrackSideDataType = /** 3x3 display transformation matrix (rotation/flip) */
+  | 'display-matrix'
+  /** Stereoscopic 3D packing (MP4 st3d, Matroska StereoMode) */
+  | 'stereo-3d'
+  /** Spherical video projection (MP4 sv3d, Matroska Projection) */
+  | 'spherical'
+  /** HDR mastering display color volume (MP4 mdcv) */
+  | 'mastering-display'
+  /** HDR content light level (MP4 clli) */
+  | 'content-light-level'
+
+/** Chroma sample siting relative to the luma grid */
+export type VideoChromaLocation = /** Co-sited horizontally, centered vertically (H.264/HEVC default) */
+  | 'left'
+  /** Centered (JPEG/MPEG-1) */
+  | 'center'
+  /** Co-sited with the top-left luma sample (BT.2020 4:2:0) */
+  | 'top-left'
+  /** Centered horizontally, co-sited with the top row */
+  | 'top'
+  /** Co-sited with the bottom-left luma sample */
+  | 'bottom-left'
+  /** Centered horizontally, co-sited with the bottom row */
+  | 'bottom'
+
 /** Video color primaries (W3C WebCodecs spec) */
 export type VideoColorPrimaries = /** BT.709 / sRGB primaries */
   | 'bt709'
@@ -1773,12 +3537,21 @@ export interface VideoDecoderAddEventListenerOptions {
   passive?: boolean
 }
 
+/** Options for close() (extension) */
+export interface VideoDecoderCloseOptions {
+  /**
+   * Invalidate every output VideoFrame of this decoder that has not been closed yet,
+   * freeing its pixel data now (default: false)
+   */
+  detachFrames?: boolean
+}
+
 /** JavaScript-facing decoder config type */
 export interface VideoDecoderConfigJs {
   /** Codec string */
   codec?: string
   /** Codec-specific description */
-  description?: Uint8Array
+  description?: BufferSource
   /** Coded width */
   codedWidth?: number
   /** Coded height */
@@ -1820,6 +3593,14 @@ export interface VideoDecoderSupport {
   config: VideoDecoderConfig
 }
 
+/** Dithering applied when reducing >8-bit frames to 8-bit RGBA/BGRA */
+export type VideoDitherMode = /** Round to nearest (default) */
+  | 'none'
+  /** 8x8 ordered dither */
+  | 'bayer'
+  /** Floyd-Steinberg error diffusion */
+  | 'error-diffusion'
+
 /** Bitrate mode for video encoding (W3C WebCodecs spec) */
 export type VideoEncoderBitrateMode = /** Variable bitrate (default) */
   | 'variable'
@@ -1840,6 +3621,38 @@ export interface VideoEncoderEncodeOptions {
   vp9?: VideoEncoderEncodeOptionsForVp9
   /** AV1 codec-specific options */
   av1?: VideoEncoderEncodeOptionsForAv1
+  /**
+   * Opaque correlation tag returned in the metadata of this frame's output chunk
+   * (extension, not part of W3C WebCodecs)
+   */
+  tag?: number | string
+  /**
+   * Maximum size of this frame's chunk in bytes (extension, not part of W3C WebCodecs)
+   *
+   * Oversized key frames are re-encoded at higher quantizers when
+   * `VideoEncoder.isMaxChunkBytesSupported()` reports support for the config.
+   */
+  maxChunkBytes?: number
+  /**
+   * Store this frame as the long-term reference with the given id
+   * (extension, see `VideoEncoder.isReferenceControlSupported()`)
+   */
+  markAsLongTermReference?: number
+  /**
+   * Predict this frame only from these long-term reference ids (extension)
+   *
+   * Ids the encoder no longer holds are skipped; if none is left the frame
+   * is encoded as a key frame.
+   */
+  referenceOnly?: Array<number>
+  /**
+   * A53 closed captions (cc_data triplets) to write as a caption SEI (extension)
+   *
+   * Defaults to the frame's `metadata().closedCaptions`. Written by H.264
+   * and H.265 encoders with A53 support (libx264, libx265, NVENC,
+   * VideoToolbox); other encoders drop them.
+   */
+  closedCaptions?: BufferSource
 }
 
 /** AV1 encode options (W3C WebCodecs AV1 Registration) */
@@ -1882,6 +3695,16 @@ export interface VideoFrameCopyToOptions {
   rect?: DOMRectInit
   /** Layout for output planes */
   layout?: Array<PlaneLayout>
+  /** Interpolation used for chroma upsampling during format conversion (default "bilinear") */
+  scaleAlgorithm?: VideoScaleAlgorithm
+  /** Dithering when converting >8-bit frames to RGBA/BGRA (default "none") */
+  dither?: VideoDitherMode
+  /** Chroma siting of the source frame (defaults to the format's conventional siting) */
+  chromaLocation?: VideoChromaLocation
+  /** Use exact rounding in the YUV to RGB conversion (default false) */
+  accurateRounding?: boolean
+  /** Interpolate chroma at full resolution instead of per 2-pixel pair (default false) */
+  fullChromaInterpolation?: boolean
 }
 
 /** Options for creating a VideoFrame from an image source (VideoFrameInit per spec) */
@@ -1908,9 +3731,21 @@ export interface VideoFrameInit {
 
 /**
  * VideoFrameMetadata - metadata associated with a VideoFrame (W3C spec)
- * Members defined in VideoFrame Metadata Registry - currently empty per spec
+ *
+ * The registry defines no members yet; `hdrDynamicMetadata` and
+ * `closedCaptions` are extensions carrying per-frame HDR10+ / Dolby Vision
+ * metadata and A53 captions through the pipeline.
  */
-export interface VideoFrameMetadata {}
+export interface VideoFrameMetadata {
+  /** Per-frame HDR dynamic metadata (HDR10+ T.35, Dolby Vision RPU) */
+  hdrDynamicMetadata?: Array<HdrDynamicMetadata>
+  /**
+   * A53 closed captions (CEA-608/708 cc_data triplets) from a caption SEI
+   *
+   * Encoding the frame writes them back as a caption SEI (H.264, H.265).
+   */
+  closedCaptions?: Uint8Array
+}
 
 /** Rectangle for specifying a region */
 export interface VideoFrameRect {
@@ -1976,6 +3811,18 @@ export type VideoPixelFormat = /** Planar YUV 4:2:0| 12bpp| (1 Cr & Cb sample pe
   /** BGRX 32bpp (alpha ignored) */
   | 'BGRX'
 
+/** Interpolation algorithm for format conversion */
+export type VideoScaleAlgorithm = /** Fast bilinear (fastest, lower quality) */
+  | 'fast-bilinear'
+  /** Bilinear (default) */
+  | 'bilinear'
+  /** Bicubic */
+  | 'bicubic'
+  /** Lanczos (highest quality, slowest) */
+  | 'lanczos'
+  /** Nearest neighbor */
+  | 'point'
+
 /** Video transfer characteristics (W3C WebCodecs spec) */
 export type VideoTransferCharacteristics = /** BT.709 transfer */
   | 'bt709'
@@ -2001,17 +3848,30 @@ export interface WebMAudioTrackConfig {
   /** Number of audio channels */
   numberOfChannels: number
   /** Codec-specific description data */
-  description?: Uint8Array
+  description?: BufferSource
 }
 
 /** WebM muxer options */
 export interface WebMMuxerOptions {
   /** Enable live streaming mode (cluster-at-a-time output) */
   live?: boolean
+  /**
+   * Omit the "Lavf" writing-application tag, write the bare "Lavf" muxing
+   * application and deterministic UIDs (default: false)
+   */
+  omitEncoderTags?: boolean
   /** Enable streaming output mode */
   streaming?: StreamingMuxerOptions
 }
 
+/** WebVTT subtitle track configuration for WebM muxer */
+export interface WebMSubtitleTrackConfig {
+  /** Track kind, written as D_WEBVTT/SUBTITLES or D_WEBVTT/CAPTIONS (default: "subtitles") */
+  kind?: SubtitleKind
+  /** Language tag (e.g. "eng") */
+  language?: string
+}
+
 /** Video track configuration for WebM muxer */
 export interface WebMVideoTrackConfig {
   /** Codec string (e.g., "vp8", "vp09.00.10.08", "av01.0.04M.08") */
@@ -2020,10 +3880,18 @@ export interface WebMVideoTrackConfig {
   width: number
   /** Video height in pixels */
   height: number
-  /** Frame rate (frames per second) */
+  /** Frame rate (frames per second); 29.97, 23.976 and 59.94 are taken as N*1000/1001 */
   framerate?: number
   /** Codec-specific description data */
-  description?: Uint8Array
+  description?: BufferSource
   /** Whether the video has alpha channel (VP9 alpha support) */
   alpha?: boolean
+  /** Color space to signal in the container (MP4 colr/nclx, Matroska Colour) */
+  colorSpace?: VideoColorSpaceInit
+  /** Display width in pixels (defaults to width); written as the track's presentation width */
+  displayWidth?: number
+  /** Display height in pixels (defaults to height); written as the track's presentation height */
+  displayHeight?: number
+  /** Stream-level side data, e.g. `DemuxerTrackInfo.sideData` when remuxing */
+  sideData?: Array<TrackSideData>
 }
@@ -738,17 +738,26 @@ module.exports = nativeBinding
 module.exports.AudioData = nativeBinding.AudioData
 module.exports.AudioDecoder = nativeBinding.AudioDecoder
 module.exports.AudioEncoder = nativeBinding.AudioEncoder
+module.exports.AudioExtractor = nativeBinding.AudioExtractor
+module.exports.AudioFileDemuxer = nativeBinding.AudioFileDemuxer
+module.exports.AudioResampler = nativeBinding.AudioResampler
+module.exports.CfrRegulator = nativeBinding.CfrRegulator
 module.exports.DOMRectReadOnly = nativeBinding.DOMRectReadOnly
 module.exports.EncodedAudioChunk = nativeBinding.EncodedAudioChunk
 module.exports.EncodedVideoChunk = nativeBinding.EncodedVideoChunk
+module.exports.ImageDecodeAllResult = nativeBinding.ImageDecodeAllResult
 module.exports.ImageDecoder = nativeBinding.ImageDecoder
 module.exports.ImageDecodeResult = nativeBinding.ImageDecodeResult
 module.exports.ImageTrack = nativeBinding.ImageTrack
 module.exports.ImageTrackList = nativeBinding.ImageTrackList
+module.exports.KeyFramePacketIterator = nativeBinding.KeyFramePacketIterator
 module.exports.MkvDemuxer = nativeBinding.MkvDemuxer
 module.exports.MkvMuxer = nativeBinding.MkvMuxer
 module.exports.Mp4Demuxer = nativeBinding.Mp4Demuxer
 module.exports.Mp4Muxer = nativeBinding.Mp4Muxer
+module.exports.RawVideoReader = nativeBinding.RawVideoReader
+module.exports.RawVideoWriter = nativeBinding.RawVideoWriter
+module.exports.SegmentedMp4Muxer = nativeBinding.SegmentedMp4Muxer
 module.exports.VideoColorSpace = nativeBinding.VideoColorSpace
 module.exports.VideoDecoder = nativeBinding.VideoDecoder
 module.exports.VideoEncoder = nativeBinding.VideoEncoder
@@ -757,15 +766,28 @@ module.exports.WebMDemuxer = nativeBinding.WebMDemuxer
 module.exports.WebMMuxer = nativeBinding.WebMMuxer
 module.exports.AacBitstreamFormat = nativeBinding.AacBitstreamFormat
 module.exports.AlphaOption = nativeBinding.AlphaOption
+module.exports.analyzeBitstream = nativeBinding.analyzeBitstream
 module.exports.AudioSampleFormat = nativeBinding.AudioSampleFormat
 module.exports.AvcBitstreamFormat = nativeBinding.AvcBitstreamFormat
 module.exports.BitrateMode = nativeBinding.BitrateMode
+module.exports.BitstreamFrameType = nativeBinding.BitstreamFrameType
+module.exports.BitstreamNalFormat = nativeBinding.BitstreamNalFormat
 module.exports.CodecState = nativeBinding.CodecState
 module.exports.ColorSpaceConversion = nativeBinding.ColorSpaceConversion
 module.exports.EncodedAudioChunkType = nativeBinding.EncodedAudioChunkType
 module.exports.EncodedVideoChunkType = nativeBinding.EncodedVideoChunkType
+module.exports.estimateEncode = nativeBinding.estimateEncode
+module.exports.extractAudio = nativeBinding.extractAudio
+module.exports.extractTrack = nativeBinding.extractTrack
 module.exports.getAvailableHardwareAccelerators = nativeBinding.getAvailableHardwareAccelerators
+module.exports.getBuildInfo = nativeBinding.getBuildInfo
+module.exports.getCodecCapabilities = nativeBinding.getCodecCapabilities
+module.exports.getDecodeLimits = nativeBinding.getDecodeLimits
+module.exports.getDefaultEncoderOptions = nativeBinding.getDefaultEncoderOptions
+module.exports.getFeatures = nativeBinding.getFeatures
 module.exports.getHardwareAccelerators = nativeBinding.getHardwareAccelerators
+module.exports.getHardwareSessionCounts = nativeBinding.getHardwareSessionCounts
+module.exports.getLogLevel = nativeBinding.getLogLevel
 module.exports.getPreferredHardwareAccelerator = nativeBinding.getPreferredHardwareAccelerator
 module.exports.HardwareAcceleration = nativeBinding.HardwareAcceleration
 module.exports.HevcBitstreamFormat = nativeBinding.HevcBitstreamFormat
@@ -774,7 +796,13 @@ module.exports.LatencyMode = nativeBinding.LatencyMode
 module.exports.OpusApplication = nativeBinding.OpusApplication
 module.exports.OpusBitstreamFormat = nativeBinding.OpusBitstreamFormat
 module.exports.OpusSignal = nativeBinding.OpusSignal
+module.exports.resetDecodeLimits = nativeBinding.resetDecodeLimits
 module.exports.resetHardwareFallbackState = nativeBinding.resetHardwareFallbackState
+module.exports.setDecodeLimits = nativeBinding.setDecodeLimits
+module.exports.setDefaultEncoderOptions = nativeBinding.setDefaultEncoderOptions
+module.exports.setHardwareDeviceSharing = nativeBinding.setHardwareDeviceSharing
+module.exports.setLogCallback = nativeBinding.setLogCallback
+module.exports.setLogLevel = nativeBinding.setLogLevel
 module.exports.VideoColorPrimaries = nativeBinding.VideoColorPrimaries
 module.exports.VideoEncoderBitrateMode = nativeBinding.VideoEncoderBitrateMode
 module.exports.VideoMatrixCoefficients = nativeBinding.VideoMatrixCoefficients
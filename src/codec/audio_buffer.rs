@@ -65,6 +65,10 @@ impl AudioSampleBuffer {
       960 // 20ms at 48kHz
     } else if codec_lower == "flac" || codec_lower == "vorbis" {
       4096 // Variable, but use a reasonable default
+    } else if codec_lower == "ulaw" || codec_lower == "alaw" {
+      160 // 20ms at 8kHz
+    } else if codec_lower == "g722" {
+      320 // 20ms at 16kHz
     } else {
       1024 // Default
     }
@@ -272,6 +276,28 @@ impl AudioSampleBuffer {
     Ok(Some(frame))
   }
 
+  /// Flush remaining samples as a full frame padded with silence
+  ///
+  /// For encoders that only accept `frame_size` samples per frame. Returns the
+  /// frame and the number of padding samples at its end, or None if the buffer
+  /// is empty.
+  pub fn flush_padded(&mut self) -> CodecResult<Option<(Frame, usize)>> {
+    if self.samples_in_buffer == 0 {
+      return Ok(None);
+    }
+
+    let padding = (self.frame_size - self.samples_in_buffer % self.frame_size) % self.frame_size;
+    let start = self.samples_in_buffer * self.channels as usize * self.bytes_per_sample;
+    let end = (self.samples_in_buffer + padding) * self.channels as usize * self.bytes_per_sample;
+    if end > self.buffer.len() {
+      self.buffer.resize(end, 0);
+    }
+    self.buffer[start..end].fill(0);
+    self.samples_in_buffer += padding;
+
+    Ok(self.take_frame()?.map(|frame| (frame, padding)))
+  }
+
   /// Clear the buffer
   pub fn clear(&mut self) {
     self.samples_in_buffer = 0;
@@ -334,5 +360,21 @@ mod tests {
     assert_eq!(AudioSampleBuffer::frame_size_for_codec("mp4a.40.2"), 1024);
     assert_eq!(AudioSampleBuffer::frame_size_for_codec("mp3"), 1152);
     assert_eq!(AudioSampleBuffer::frame_size_for_codec("opus"), 960);
+    assert_eq!(AudioSampleBuffer::frame_size_for_codec("ulaw"), 160);
+    assert_eq!(AudioSampleBuffer::frame_size_for_codec("g722"), 320);
+  }
+
+  #[test]
+  fn test_flush_padded_fills_a_whole_frame() {
+    let mut buffer = AudioSampleBuffer::new(960, 2, 48000, AVSampleFormat::S16);
+    buffer.add_samples(&vec![1u8; 1024 * 4], 1024).unwrap();
+    let frame = buffer.take_frame().unwrap().unwrap();
+    assert_eq!(frame.nb_samples(), 960);
+
+    let (frame, padding) = buffer.flush_padded().unwrap().unwrap();
+    assert_eq!(frame.nb_samples(), 960);
+    assert_eq!(padding, 960 - 64);
+    assert_eq!(buffer.samples_available(), 0);
+    assert!(buffer.flush_padded().unwrap().is_none());
   }
 }
@@ -1,8 +1,10 @@
 //! Custom I/O context wrapper for FFmpeg's AVIO system
 //!
-//! Provides safe wrappers for custom I/O operations (memory/streaming buffers).
+//! Provides safe wrappers for custom I/O operations (memory/streaming buffers,
+//! appended stream input and temporary files).
 
-use super::io_buffer::{BufferSource, MemoryBuffer, ReadOnlyBuffer, StreamingBuffer};
+use super::io_buffer::{BufferSource, MemoryBuffer, ReadOnlyBuffer, StreamInput, StreamingBuffer};
+use super::temp_file::TempFile;
 use crate::ffi::avformat::{
   AVIOContext, avio_alloc_context, avio_context_free, avio_flush, seek_whence,
 };
@@ -10,6 +12,7 @@ use crate::ffi::avutil::av_malloc;
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::os::raw::{c_int, c_void};
 use std::ptr::NonNull;
+use std::sync::Arc;
 
 /// Default buffer size for AVIO operations (32KB)
 const DEFAULT_BUFFER_SIZE: usize = 32 * 1024;
@@ -20,8 +23,12 @@ pub enum IoMode {
   BufferWrite(Box<MemoryBuffer>),
   /// Buffer-based input (demuxer reads from read-only buffer) - supports zero-copy
   BufferRead(Box<ReadOnlyBuffer>),
+  /// Streaming input (demuxer reads data as it is appended; not seekable)
+  StreamRead(Arc<StreamInput>),
   /// Streaming output (muxer writes to streaming buffer)
   StreamingWrite(Box<StreamingBuffer>),
+  /// File-backed output (muxer writes to a temporary file); None once taken
+  FileWrite(Option<TempFile>),
 }
 
 /// Custom I/O context wrapper
@@ -58,12 +65,25 @@ impl CustomIOContext {
     Self::create_read_context(IoMode::BufferRead(Box::new(buffer)))
   }
 
+  /// Create a new custom I/O context for reading appended data
+  ///
+  /// Reads block until data is appended or the input ends. The context is
+  /// not seekable, so demuxers only move forward through the data.
+  pub fn new_stream_read(input: Arc<StreamInput>) -> Result<Self, String> {
+    Self::create_read_context(IoMode::StreamRead(input))
+  }
+
   /// Create a new custom I/O context for streaming output
   pub fn new_streaming_write(capacity: usize) -> Result<Self, String> {
     let buffer = StreamingBuffer::new(capacity);
     Self::create_write_context(IoMode::StreamingWrite(Box::new(buffer)))
   }
 
+  /// Create a new custom I/O context for writing to a temporary file
+  pub fn new_file_write(file: TempFile) -> Result<Self, String> {
+    Self::create_write_context(IoMode::FileWrite(Some(file)))
+  }
+
   /// Create a write context with the given mode
   fn create_write_context(mode: IoMode) -> Result<Self, String> {
     let buffer_size = DEFAULT_BUFFER_SIZE;
@@ -76,7 +96,7 @@ impl CustomIOContext {
 
     // Determine if we need read callback (for faststart support in buffer mode)
     // Check before boxing since Box::new() moves the value
-    let needs_read = matches!(mode, IoMode::BufferWrite(_));
+    let needs_read = matches!(mode, IoMode::BufferWrite(_) | IoMode::FileWrite(_));
 
     // Box the mode to get a stable pointer
     let mut boxed_mode = Box::new(mode);
@@ -108,7 +128,7 @@ impl CustomIOContext {
     }
 
     // Set seekable flag for buffer mode (required for future faststart support in MP4)
-    // BufferWrite mode supports seeking (and reading back), StreamingWrite does not
+    // BufferWrite and FileWrite support seeking (and reading back), StreamingWrite does not
     if needs_read {
       unsafe { fffio_set_seekable(ptr, AVIO_SEEKABLE_NORMAL) };
     }
@@ -132,6 +152,13 @@ impl CustomIOContext {
       return Err("Failed to allocate AVIO buffer".to_string());
     }
 
+    // Without a seek callback FFmpeg treats the input as non-seekable
+    let seek_cb: Option<crate::ffi::avformat::SeekFn> = if matches!(mode, IoMode::StreamRead(_)) {
+      None
+    } else {
+      Some(seek_callback_read)
+    };
+
     // Box the mode to get a stable pointer
     let mut boxed_mode = Box::new(mode);
     let opaque = boxed_mode.as_mut() as *mut IoMode as *mut c_void;
@@ -143,9 +170,9 @@ impl CustomIOContext {
         buffer_size as c_int,
         0, // write_flag = 0 for reading
         opaque,
-        Some(read_callback),      // read_packet
-        None,                     // write_packet - not needed for reading
-        Some(seek_callback_read), // seek
+        Some(read_callback), // read_packet
+        None,                // write_packet - not needed for reading
+        seek_cb,             // seek
       )
     };
 
@@ -201,6 +228,26 @@ impl CustomIOContext {
     }
   }
 
+  /// Take the output file (for file write mode)
+  ///
+  /// Flushes pending output first. Returns None if not in file write mode or
+  /// if the file was already taken.
+  pub fn take_temp_file(&mut self) -> Option<TempFile> {
+    self.flush();
+    unsafe {
+      let opaque = get_avio_opaque(self.ptr.as_ptr());
+      if !opaque.is_null() {
+        let mode = &mut *(opaque as *mut IoMode);
+        match mode {
+          IoMode::FileWrite(file) => file.take(),
+          _ => None,
+        }
+      } else {
+        None
+      }
+    }
+  }
+
   /// Get a handle to the streaming buffer (for streaming write mode)
   ///
   /// Returns None if not in streaming write mode.
@@ -244,7 +291,8 @@ impl CustomIOContext {
         match mode {
           IoMode::BufferWrite(buf) => Some(buf.len()),
           IoMode::BufferRead(buf) => Some(buf.len()),
-          IoMode::StreamingWrite(_) => None,
+          IoMode::FileWrite(file) => file.as_ref().map(|file| file.len() as usize),
+          IoMode::StreamingWrite(_) | IoMode::StreamRead(_) => None,
         }
       } else {
         None
@@ -321,7 +369,8 @@ unsafe extern "C" fn write_callback(opaque: *mut c_void, buf: *const u8, buf_siz
   let result = match mode {
     IoMode::BufferWrite(buffer) => buffer.write(data),
     IoMode::StreamingWrite(buffer) => buffer.write_blocking(data),
-    IoMode::BufferRead(_) => return -1, // Can't write to read buffer
+    IoMode::FileWrite(Some(file)) => file.write(data),
+    IoMode::FileWrite(None) | IoMode::BufferRead(_) | IoMode::StreamRead(_) => return -1, // Can't write to read buffer
   };
 
   match result {
@@ -347,9 +396,12 @@ unsafe extern "C" fn read_callback(opaque: *mut c_void, buf: *mut u8, buf_size:
 
   let result = match mode {
     IoMode::BufferRead(buffer) => buffer.read(data),
+    // Blocks until data is appended; 0 at the end of the stream
+    IoMode::StreamRead(input) => Ok(input.read_blocking(data)),
     // BufferWrite also supports reading for faststart (FFmpeg needs to read back written data)
     IoMode::BufferWrite(buffer) => buffer.read(data),
-    IoMode::StreamingWrite(_) => return -1, // Streaming doesn't support read-back
+    IoMode::FileWrite(Some(file)) => file.read(data),
+    IoMode::FileWrite(None) | IoMode::StreamingWrite(_) => return -1, // Streaming doesn't support read-back
   };
 
   match result {
@@ -371,8 +423,9 @@ unsafe extern "C" fn seek_callback_write(opaque: *mut c_void, offset: i64, whenc
     let mode = unsafe { &*(opaque as *const IoMode) };
     return match mode {
       IoMode::BufferWrite(buffer) => buffer.len() as i64,
+      IoMode::FileWrite(Some(file)) => file.len() as i64,
       IoMode::StreamingWrite(_) => -1, // Streaming doesn't support size query
-      IoMode::BufferRead(_) => -1,
+      IoMode::FileWrite(None) | IoMode::BufferRead(_) | IoMode::StreamRead(_) => -1,
     };
   }
 
@@ -391,8 +444,12 @@ unsafe extern "C" fn seek_callback_write(opaque: *mut c_void, offset: i64, whenc
       Ok(pos) => pos as i64,
       Err(_) => -1,
     },
+    IoMode::FileWrite(Some(file)) => match file.seek(seek_from) {
+      Ok(pos) => pos as i64,
+      Err(_) => -1,
+    },
     IoMode::StreamingWrite(_) => -1, // Streaming doesn't support seeking
-    IoMode::BufferRead(_) => -1,
+    IoMode::FileWrite(None) | IoMode::BufferRead(_) | IoMode::StreamRead(_) => -1,
   }
 }
 
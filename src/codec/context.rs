@@ -5,32 +5,86 @@
 use crate::ffi::{
   self, AVCodec, AVCodecContext, AVCodecID, AVHWDeviceType, AVPixelFormat, AVRational,
   accessors::{
-    codec_flag, ffctx_get_extradata, ffctx_get_extradata_size, ffctx_get_flags,
-    ffctx_get_frame_size, ffctx_get_height, ffctx_get_pix_fmt, ffctx_get_qmax, ffctx_get_qmin,
-    ffctx_get_sample_rate, ffctx_get_time_base, ffctx_get_width, ffctx_set_bit_rate,
-    ffctx_set_channels, ffctx_set_flags, ffctx_set_framerate, ffctx_set_gop_size,
-    ffctx_set_has_b_frames, ffctx_set_height, ffctx_set_hw_device_ctx, ffctx_set_hw_frames_ctx,
-    ffctx_set_level, ffctx_set_max_b_frames, ffctx_set_pix_fmt, ffctx_set_profile, ffctx_set_qmax,
-    ffctx_set_qmin, ffctx_set_rc_buffer_size, ffctx_set_rc_max_rate, ffctx_set_sample_fmt,
-    ffctx_set_sample_rate, ffctx_set_thread_count, ffctx_set_thread_type, ffctx_set_time_base,
-    ffctx_set_width,
+    codec_flag, ffcodec_get_name, ffcodec_supports_pix_fmt, ffctx_accepts_partial_audio_frame,
+    ffctx_get_extradata, ffctx_get_extradata_size, ffctx_get_flags, ffctx_get_frame_size,
+    ffctx_get_height, ffctx_get_initial_padding, ffctx_get_pix_fmt, ffctx_get_qmax, ffctx_get_qmin,
+    ffctx_get_sample_rate, ffctx_get_stats_out, ffctx_get_time_base, ffctx_get_width,
+    ffctx_set_bit_rate, ffctx_set_channels, ffctx_set_flags, ffctx_set_framerate,
+    ffctx_set_gop_size, ffctx_set_has_b_frames, ffctx_set_height, ffctx_set_hw_device_ctx,
+    ffctx_set_hw_frames_ctx, ffctx_set_level, ffctx_set_max_b_frames, ffctx_set_pix_fmt,
+    ffctx_set_profile, ffctx_set_qmax, ffctx_set_qmin, ffctx_set_rc_buffer_size,
+    ffctx_set_rc_max_rate, ffctx_set_sample_aspect_ratio, ffctx_set_sample_fmt,
+    ffctx_set_sample_rate, ffctx_set_stats_in, ffctx_set_thread_count, ffctx_set_thread_type,
+    ffctx_set_time_base, ffctx_set_width,
   },
   avcodec::{
     avcodec_alloc_context3, avcodec_find_decoder, avcodec_find_encoder,
     avcodec_find_encoder_by_name, avcodec_flush_buffers, avcodec_free_context, avcodec_open2,
     avcodec_receive_frame, avcodec_receive_packet, avcodec_send_frame, avcodec_send_packet,
   },
-  avutil::{av_opt_set, av_opt_set_int, opt_flag},
+  avutil::{av_free, av_opt_get, av_opt_set, av_opt_set_int, opt_flag},
   error::{AVERROR_EAGAIN, AVERROR_EOF},
 };
-use std::ffi::CString;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
 use std::ptr::NonNull;
+use std::sync::{Mutex, OnceLock};
 
 use super::{
   AudioDecoderConfig, AudioEncoderConfig, BitrateMode, CodecError, CodecResult, DecoderConfig,
-  EncoderConfig, Frame, HwDeviceContext, HwFrameContext, Packet,
+  EncoderConfig, EncoderPass, Frame, HwDeviceContext, HwFrameContext, Packet,
+  pass_stats::PassStatsFile,
 };
 
+/// Fatal-level FFmpeg log messages by encoder context address
+///
+/// Only opened encoders are tracked; messages logged against any other
+/// context are ignored.
+static FATAL_LOGS: OnceLock<Mutex<HashMap<usize, Option<String>>>> = OnceLock::new();
+
+fn fatal_logs() -> &'static Mutex<HashMap<usize, Option<String>>> {
+  FATAL_LOGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record a fatal-level FFmpeg log message against the context that logged it
+///
+/// Called from the FFmpeg log callback. Encoder wrappers log through their
+/// AVCodecContext, so `ctx` matches the tracked context's address. The next
+/// open, send or receive on that encoder fails with the message instead of
+/// continuing on a codec that has reported itself unusable.
+pub fn record_fatal_log(ctx: *mut c_void, message: &str) {
+  if ctx.is_null() {
+    return;
+  }
+  if let Ok(mut logs) = fatal_logs().lock()
+    && let Some(slot) = logs.get_mut(&(ctx as usize))
+    && slot.is_none()
+  {
+    *slot = Some(message.to_string());
+  }
+}
+
+/// Record an error-level FFmpeg log message that reports the NVENC session cap
+///
+/// The cap surfaces from avcodec_open2() as a generic error code, so the
+/// logged text is the only way to tell it apart from an invalid configuration.
+pub fn record_error_log(ctx: *mut c_void, message: &str) {
+  if is_session_limit_message(message) {
+    record_fatal_log(ctx, message);
+  }
+}
+
+/// Whether a log line reports that no more NVENC sessions can be opened
+///
+/// Consumer GPUs allow a fixed number of concurrent encode sessions; opening
+/// one more fails in nvEncOpenEncodeSessionEx with "incompatible client key"
+/// on older drivers and "out of memory" on newer ones.
+fn is_session_limit_message(message: &str) -> bool {
+  message.contains("OpenEncodeSessionEx failed")
+    && (message.contains("incompatible client key") || message.contains("out of memory"))
+}
+
 /// Result of encoder creation with metadata about hardware acceleration
 pub struct EncoderCreationResult {
   /// The created codec context
@@ -77,6 +131,11 @@ pub struct CodecContext {
   codec_type: CodecType,
   hw_device: Option<HwDeviceContext>,
   hw_frames: Option<HwFrameContext>,
+  /// Second-pass statistics referenced by `stats_in` (FFmpeg does not free it)
+  stats_in: Option<CString>,
+  /// Stats file of a libx264/libx265 two-pass encode, removed after the
+  /// encoder is freed (fields drop after `Drop::drop`)
+  pass_stats_file: Option<PassStatsFile>,
 }
 
 impl CodecContext {
@@ -117,7 +176,7 @@ impl CodecContext {
       // Try to create and attach hardware device context
       // Some encoders (like VideoToolbox) don't need it, but VAAPI does
       if hw_encoder_needs_device_context(hw)
-        && let Ok(hw_device) = HwDeviceContext::new(hw)
+        && let Ok(hw_device) = HwDeviceContext::shared(hw)
       {
         ctx.set_hw_device(hw_device);
       }
@@ -152,7 +211,7 @@ impl CodecContext {
     {
       // Try to create and attach hardware device context
       if hw_encoder_needs_device_context(hw)
-        && let Ok(hw_device) = HwDeviceContext::new(hw)
+        && let Ok(hw_device) = HwDeviceContext::shared(hw)
       {
         ctx.set_hw_device(hw_device);
       }
@@ -194,7 +253,7 @@ impl CodecContext {
 
     // Attach hardware device context if requested
     if let Some(hw) = hw_type
-      && let Ok(hw_device) = HwDeviceContext::new(hw)
+      && let Ok(hw_device) = HwDeviceContext::shared(hw)
     {
       ctx.set_hw_device(hw_device);
     }
@@ -239,7 +298,7 @@ impl CodecContext {
       // AV_PIX_FMT_NONE is -1
       if pix_fmt_raw != -1 {
         // Hardware is supported by this codec
-        if let Ok(hw_device) = HwDeviceContext::new(hw) {
+        if let Ok(hw_device) = HwDeviceContext::shared(hw) {
           // Create context from codec
           let mut ctx = Self::from_codec(codec, CodecType::Decoder)?;
           ctx.set_hw_device(hw_device);
@@ -306,6 +365,8 @@ impl CodecContext {
         codec_type,
         hw_device: None,
         hw_frames: None,
+        stats_in: None,
+        pass_stats_file: None,
       })
       .ok_or(CodecError::AllocationFailed("AVCodecContext"))
   }
@@ -330,6 +391,11 @@ impl CodecContext {
       // Pixel format
       ffctx_set_pix_fmt(ctx, config.pixel_format.as_raw());
 
+      // Non-square pixels (anamorphic display size)
+      if let Some((num, den)) = config.sample_aspect_ratio {
+        ffctx_set_sample_aspect_ratio(ctx, num, den);
+      }
+
       // Rate control based on bitrate mode
       match config.bitrate_mode {
         BitrateMode::Constant => {
@@ -391,12 +457,10 @@ impl CodecContext {
         }
       }
 
-      // Time base (inverse of framerate for encoding)
-      ffctx_set_time_base(
-        ctx,
-        config.framerate_den as i32,
-        config.framerate_num as i32,
-      );
+      // Microsecond time base so WebCodecs timestamps map 1:1 onto pts;
+      // a 1/framerate time base would quantize variable frame rate input.
+      // Rate control reads the nominal rate from the framerate below.
+      ffctx_set_time_base(ctx, 1, 1_000_000);
 
       // Framerate
       ffctx_set_framerate(
@@ -432,6 +496,34 @@ impl CodecContext {
         ffctx_set_level(ctx, level);
       }
 
+      // Two-pass rate control: statistics are exchanged in memory, except for
+      // libx264/libx265 which only use a stats file (set up in open())
+      let uses_stats_file = matches!(self.name(), Some("libx264" | "libx265"));
+      match &config.pass {
+        Some(EncoderPass::First) => {
+          ffctx_set_flags(ctx, ffctx_get_flags(ctx) | codec_flag::PASS1);
+          if uses_stats_file {
+            self.pass_stats_file = Some(PassStatsFile::first_pass());
+          }
+        }
+        Some(EncoderPass::Second(stats)) if uses_stats_file => {
+          let file = PassStatsFile::second_pass(stats).map_err(|e| {
+            CodecError::InvalidConfig(format!("Failed to write two-pass statistics: {e}"))
+          })?;
+          ffctx_set_flags(ctx, ffctx_get_flags(ctx) | codec_flag::PASS2);
+          self.pass_stats_file = Some(file);
+        }
+        Some(EncoderPass::Second(stats)) => {
+          let stats_in = CString::new(stats.clone()).map_err(|_| {
+            CodecError::InvalidConfig("Two-pass statistics contain a NUL byte".into())
+          })?;
+          ffctx_set_flags(ctx, ffctx_get_flags(ctx) | codec_flag::PASS2);
+          ffctx_set_stats_in(ctx, stats_in.as_ptr() as *mut _);
+          self.stats_in = Some(stats_in);
+        }
+        None => {}
+      }
+
       // Note: Encoder-specific options (preset, tune, x265-params, etc.)
       // should be set via apply_sw_encoder_options() or apply_hw_encoder_options()
       // after configure_encoder() and before open().
@@ -451,6 +543,37 @@ impl CodecContext {
     }
   }
 
+  /// Set AVOptions (e.g. `threads`, `preset`) on the context and the codec's private options
+  ///
+  /// Options the codec does not define, and values it rejects, are skipped
+  /// with a warning so one option set can be shared by every codec.
+  pub fn set_options<'a>(&mut self, options: impl IntoIterator<Item = (&'a str, &'a str)>) {
+    let ctx = self.ptr.as_ptr() as *mut c_void;
+    for (key, value) in options {
+      let (Ok(c_key), Ok(c_value)) = (CString::new(key), CString::new(value)) else {
+        tracing::warn!(target: "webcodecs", key, "Skipping FFmpeg option containing a NUL byte");
+        continue;
+      };
+      let ret = unsafe {
+        av_opt_set(
+          ctx,
+          c_key.as_ptr(),
+          c_value.as_ptr(),
+          opt_flag::SEARCH_CHILDREN,
+        )
+      };
+      if ret < 0 {
+        tracing::warn!(
+          target: "webcodecs",
+          key,
+          value,
+          error = %ffi::error::get_error_message(ret),
+          "Skipping FFmpeg option"
+        );
+      }
+    }
+  }
+
   /// Set minimum quantizer value for rate control.
   ///
   /// For VP9/AV1, this should be in the 0-63 range (internal encoder range).
@@ -632,6 +755,7 @@ impl CodecContext {
   /// ## libx265
   /// - preset=ultrafast (constant, not latencyMode-dependent)
   /// - Note: tune=zerolatency causes conflicts with bframes, so we use ultrafast only
+  /// - a53cc=1 (write A53 closed captions from frame side data, as libx264 does by default)
   ///
   /// ## libvpx-vp8
   /// - cpu-used=-6 (constant, same as Chromium)
@@ -696,6 +820,7 @@ impl CodecContext {
           c"log-level=error:qpmax=40".as_ptr(),
           opt_flag::SEARCH_CHILDREN,
         );
+        av_opt_set_int(ctx, c"a53cc".as_ptr(), 1, opt_flag::SEARCH_CHILDREN);
       }
       // libvpx-vp8 - Chromium uses cpu-used=-6 constant
       else if encoder_name == "libvpx-vp8" {
@@ -797,7 +922,19 @@ impl CodecContext {
         ffctx_set_thread_count(ctx, 0);
       }
 
-      // TODO: Set extradata if provided
+      // Set extradata if provided (e.g., AudioSpecificConfig for AAC, OpusHead for Opus)
+      if let Some(ref extradata) = config.extradata
+        && !extradata.is_empty()
+      {
+        let ret =
+          ffi::accessors::ffctx_set_extradata(ctx, extradata.as_ptr(), extradata.len() as i32);
+        if ret < 0 {
+          return Err(CodecError::InvalidConfig(format!(
+            "Failed to set extradata: {}",
+            ffi::FFmpegError::from_code(ret)
+          )));
+        }
+      }
     }
 
     Ok(())
@@ -872,6 +1009,16 @@ impl CodecContext {
         ffctx_set_height(ctx, h as i32);
       }
 
+      // Cap per-frame pixel count so oversized streams fail instead of allocating
+      if let Some(max_pixels) = config.max_pixels {
+        av_opt_set_int(
+          ctx as *mut std::ffi::c_void,
+          c"max_pixels".as_ptr(),
+          max_pixels.min(i64::MAX as u64) as i64,
+          0,
+        );
+      }
+
       // Set extradata if provided (e.g., SPS/PPS for H.264, VPS/SPS/PPS for HEVC)
       // This is critical for hardware decoding - without extradata, the decoder
       // cannot determine stream parameters and may fail to produce output.
@@ -921,9 +1068,97 @@ impl CodecContext {
 
   /// Open the codec (must be called after configuration)
   pub fn open(&mut self) -> CodecResult<()> {
+    if self.codec_type == CodecType::Encoder
+      && let Ok(mut logs) = fatal_logs().lock()
+    {
+      logs.insert(self.ptr.as_ptr() as usize, None);
+    }
+    self.apply_pass_stats_file();
     let ret = unsafe { avcodec_open2(self.ptr.as_ptr(), self.codec, std::ptr::null_mut()) };
-    ffi::check_error(ret)?;
-    Ok(())
+    if let Err(e) = ffi::check_error(ret) {
+      // A logged session cap explains the failure better than the error code
+      self.check_fatal_log()?;
+      return Err(e.into());
+    }
+    self.check_fatal_log()
+  }
+
+  /// Point libx264/libx265 at the two-pass stats file
+  ///
+  /// Runs right before avcodec_open2() so the options survive
+  /// apply_sw_encoder_options() and user-supplied encoder options replacing
+  /// `x265-params`. Macroblock-tree/CU-tree data would go to a second file
+  /// next to the stats, so it is disabled.
+  fn apply_pass_stats_file(&self) {
+    let Some(file) = &self.pass_stats_file else {
+      return;
+    };
+    let Ok(path) = CString::new(file.path()) else {
+      return;
+    };
+    let ctx = self.ptr.as_ptr() as *mut c_void;
+    unsafe {
+      if self.name() == Some("libx265") {
+        let mut current: *mut u8 = std::ptr::null_mut();
+        av_opt_get(
+          ctx,
+          c"x265-params".as_ptr(),
+          opt_flag::SEARCH_CHILDREN,
+          &mut current,
+        );
+        let mut params = if current.is_null() {
+          String::new()
+        } else {
+          let params = CStr::from_ptr(current as *const _)
+            .to_string_lossy()
+            .into_owned();
+          av_free(current as *mut c_void);
+          params
+        };
+        if !params.is_empty() {
+          params.push(':');
+        }
+        // Quoted, since a Windows temp path contains the ':' separator
+        params.push_str(&format!(
+          "pass={}:stats='{}':cutree=0",
+          file.pass(),
+          file.path()
+        ));
+        if let Ok(params) = CString::new(params) {
+          av_opt_set(
+            ctx,
+            c"x265-params".as_ptr(),
+            params.as_ptr(),
+            opt_flag::SEARCH_CHILDREN,
+          );
+        }
+      } else {
+        av_opt_set(
+          ctx,
+          c"stats".as_ptr(),
+          path.as_ptr(),
+          opt_flag::SEARCH_CHILDREN,
+        );
+        av_opt_set_int(ctx, c"mbtree".as_ptr(), 0, opt_flag::SEARCH_CHILDREN);
+      }
+    }
+  }
+
+  /// Fail if FFmpeg logged a fatal-level message for this encoder
+  fn check_fatal_log(&self) -> CodecResult<()> {
+    if self.codec_type != CodecType::Encoder {
+      return Ok(());
+    }
+    let message = fatal_logs().lock().ok().and_then(|mut logs| {
+      logs
+        .get_mut(&(self.ptr.as_ptr() as usize))
+        .and_then(Option::take)
+    });
+    match message {
+      Some(message) if is_session_limit_message(&message) => Err(CodecError::SessionLimit(message)),
+      Some(message) => Err(CodecError::Fatal(message)),
+      None => Ok(()),
+    }
   }
 
   // ========================================================================
@@ -941,6 +1176,7 @@ impl CodecContext {
       return Ok(false);
     }
     ffi::check_error(ret)?;
+    self.check_fatal_log()?;
     Ok(true)
   }
 
@@ -950,6 +1186,7 @@ impl CodecContext {
   pub fn receive_packet(&mut self) -> CodecResult<Option<Packet>> {
     let mut pkt = Packet::new()?;
     let ret = unsafe { avcodec_receive_packet(self.ptr.as_ptr(), pkt.as_mut_ptr()) };
+    self.check_fatal_log()?;
 
     if ret == AVERROR_EAGAIN || ret == AVERROR_EOF {
       return Ok(None);
@@ -1141,6 +1378,11 @@ impl CodecContext {
     unsafe { avcodec_flush_buffers(self.ptr.as_ptr()) }
   }
 
+  /// Check if the codec accepts frames in `format` (encoders only)
+  pub fn supports_pixel_format(&self, format: AVPixelFormat) -> bool {
+    unsafe { ffcodec_supports_pix_fmt(self.codec, format.as_raw()) != 0 }
+  }
+
   /// Get raw pointer (for FFmpeg API calls)
   #[inline]
   pub fn as_ptr(&self) -> *const AVCodecContext {
@@ -1159,6 +1401,18 @@ impl CodecContext {
     self.codec_type
   }
 
+  /// Name of the FFmpeg codec implementation (e.g. "libx264")
+  pub fn name(&self) -> Option<&'static str> {
+    unsafe {
+      let name = ffcodec_get_name(self.codec);
+      if name.is_null() {
+        None
+      } else {
+        CStr::from_ptr(name).to_str().ok()
+      }
+    }
+  }
+
   /// Get configured width
   pub fn width(&self) -> u32 {
     unsafe { ffctx_get_width(self.as_ptr()) as u32 }
@@ -1193,6 +1447,18 @@ impl CodecContext {
     unsafe { ffctx_get_frame_size(self.as_ptr()) as u32 }
   }
 
+  /// Priming samples the encoder outputs before the first input sample
+  pub fn initial_padding(&self) -> u32 {
+    unsafe { ffctx_get_initial_padding(self.as_ptr()).max(0) as u32 }
+  }
+
+  /// Whether the encoder takes a final audio frame shorter than `frame_size()`
+  ///
+  /// Other encoders need the last frame padded to a full frame.
+  pub fn accepts_partial_audio_frame(&self) -> bool {
+    unsafe { ffctx_accepts_partial_audio_frame(self.as_ptr()) != 0 }
+  }
+
   /// Get codec extradata (e.g., SPS/PPS for H.264)
   pub fn extradata(&self) -> Option<&[u8]> {
     unsafe {
@@ -1206,6 +1472,25 @@ impl CodecContext {
     }
   }
 
+  /// Get first-pass rate-control statistics
+  ///
+  /// libvpx, libaom and rav1e publish the complete statistics once the
+  /// encoder has been flushed; libx264/libx265 statistics are read back from
+  /// their stats file.
+  pub fn stats_out(&self) -> Option<Vec<u8>> {
+    if let Some(file) = &self.pass_stats_file {
+      return file.first_pass_stats();
+    }
+    unsafe {
+      let ptr = ffctx_get_stats_out(self.as_ptr());
+      if ptr.is_null() {
+        None
+      } else {
+        Some(CStr::from_ptr(ptr).to_bytes().to_vec())
+      }
+    }
+  }
+
   /// Get configured time base
   pub fn time_base(&self) -> AVRational {
     unsafe {
@@ -1219,6 +1504,11 @@ impl CodecContext {
 
 impl Drop for CodecContext {
   fn drop(&mut self) {
+    if self.codec_type == CodecType::Encoder
+      && let Ok(mut logs) = fatal_logs().lock()
+    {
+      logs.remove(&(self.ptr.as_ptr() as usize));
+    }
     unsafe {
       // avcodec_free_context handles both closing the codec and freeing the context.
       // Do NOT call avcodec_close separately - it's deprecated and calling both
@@ -1327,3 +1617,48 @@ pub fn get_audio_encoder_name(codec_id: AVCodecID) -> Option<&'static str> {
     _ => None,
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn session_limit_log_lines() {
+    // NV_ENC_ERR_INCOMPATIBLE_CLIENT_KEY (21) on older drivers
+    assert!(is_session_limit_message(
+      "OpenEncodeSessionEx failed: incompatible client key (21): (no details)"
+    ));
+    // NV_ENC_ERR_OUT_OF_MEMORY (10) on newer drivers
+    assert!(is_session_limit_message(
+      "OpenEncodeSessionEx failed: out of memory (10): (no details)"
+    ));
+    assert!(!is_session_limit_message(
+      "OpenEncodeSessionEx failed: unsupported device (2): (no details)"
+    ));
+    assert!(!is_session_limit_message("out of memory"));
+  }
+
+  #[test]
+  fn fatal_log_fails_the_encoder_once() {
+    let ctx = CodecContext::new_encoder(AVCodecID::H264).unwrap();
+    let addr = ctx.ptr.as_ptr() as *mut c_void;
+
+    // Not tracked until opened
+    record_fatal_log(addr, "before open");
+    assert!(ctx.check_fatal_log().is_ok());
+
+    fatal_logs().lock().unwrap().insert(addr as usize, None);
+    record_fatal_log(addr, "Invalid level");
+    // Only the first fatal message is kept
+    record_fatal_log(addr, "second message");
+    assert!(matches!(
+      ctx.check_fatal_log(),
+      Err(CodecError::Fatal(message)) if message == "Invalid level"
+    ));
+    // Reported once, then cleared
+    assert!(ctx.check_fatal_log().is_ok());
+
+    drop(ctx);
+    assert!(!fatal_logs().lock().unwrap().contains_key(&(addr as usize)));
+  }
+}
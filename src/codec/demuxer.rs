@@ -4,22 +4,33 @@
 
 use super::CodecError;
 use super::avio_context::CustomIOContext;
-use super::io_buffer::BufferSource;
+use super::io_buffer::{BufferSource, StreamInput};
 use crate::ffi::accessors::{
-  ffcodecpar_get_channels, ffcodecpar_get_codec_id, ffcodecpar_get_codec_type,
-  ffcodecpar_get_extradata, ffcodecpar_get_extradata_size, ffcodecpar_get_format,
-  ffcodecpar_get_height, ffcodecpar_get_sample_rate, ffcodecpar_get_width, fffmt_get_duration,
+  ffcodecpar_get_channels, ffcodecpar_get_codec_id, ffcodecpar_get_codec_tag,
+  ffcodecpar_get_codec_type, ffcodecpar_get_color_primaries, ffcodecpar_get_color_range,
+  ffcodecpar_get_color_space, ffcodecpar_get_color_trc, ffcodecpar_get_extradata,
+  ffcodecpar_get_extradata_size, ffcodecpar_get_format, ffcodecpar_get_height,
+  ffcodecpar_get_icc_profile, ffcodecpar_get_profile, ffcodecpar_get_sample_rate,
+  ffcodecpar_get_stream_side_data, ffcodecpar_get_width, fffmt_get_duration, fffmt_get_metadata,
   fffmt_get_nb_streams, fffmt_get_stream, fffmt_set_pb, ffstream_get_codecpar_const,
-  ffstream_get_duration, ffstream_get_index, ffstream_get_time_base,
+  ffstream_get_disposition, ffstream_get_duration, ffstream_get_index, ffstream_get_metadata,
+  ffstream_get_time_base, stream_side_data_kind,
 };
 use crate::ffi::avformat::{
-  AVFormatContext, av_find_best_stream, av_read_frame, av_seek_frame, avformat_close_input,
-  avformat_find_stream_info, avformat_free_context, avformat_open_input, media_type, seek_flag,
+  AVCodecParameters, AVFormatContext, AVStream, av_find_best_stream, av_find_input_format,
+  av_read_frame, av_seek_frame, avformat_close_input, avformat_find_stream_info,
+  avformat_free_context, avformat_open_input, media_type, seek_flag,
 };
-use crate::ffi::{AVCodecID, AVPixelFormat, AVSampleFormat};
-use std::ffi::CString;
+use crate::ffi::avutil::{av_dict_free, av_dict_set};
+use crate::ffi::types::AVDictionary;
+use crate::ffi::{
+  AVCodecID, AVColorPrimaries, AVColorRange, AVColorSpace, AVColorTransferCharacteristic,
+  AVPixelFormat, AVSampleFormat,
+};
+use std::ffi::{CStr, CString};
 use std::os::raw::c_int;
 use std::ptr::{self, NonNull};
+use std::sync::Arc;
 
 /// Media type for stream identification
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -66,6 +77,8 @@ pub struct StreamInfo {
   pub media_type: MediaType,
   /// Codec ID
   pub codec_id: AVCodecID,
+  /// Codec tag, e.g. the MP4 sample entry fourcc (`hvc1` vs `hev1`); 0 if none
+  pub codec_tag: u32,
   /// Video width (if video)
   pub width: Option<u32>,
   /// Video height (if video)
@@ -84,6 +97,72 @@ pub struct StreamInfo {
   pub duration: Option<i64>,
   /// Codec extradata (avcC, hvcC, etc.)
   pub extradata: Option<Vec<u8>>,
+  /// Codec profile, if the container or stream probing reported one
+  pub profile: Option<i32>,
+  /// Color description from the container or bitstream (video only)
+  pub color: StreamColorInfo,
+  /// Raw ICC profile (e.g. MP4 colr/prof box), if present
+  pub icc_profile: Option<Vec<u8>>,
+  /// Stream-level side data blobs (spherical, stereo 3D, display matrix, HDR mastering)
+  pub side_data: Vec<StreamSideData>,
+  /// Disposition flags (`disposition::*`)
+  pub disposition: c_int,
+  /// Language tag from the stream metadata
+  pub language: Option<String>,
+}
+
+/// Stream-level side data blob, passed through remuxing without interpretation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamSideData {
+  /// Kind from `stream_side_data_kind`
+  pub kind: c_int,
+  /// FFmpeg struct bytes as stored in the coded side data
+  pub data: Vec<u8>,
+}
+
+/// Color description of a video stream (e.g. MP4 colr/nclx, Matroska Colour)
+///
+/// Fields the container leaves unset are `Unspecified`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamColorInfo {
+  pub primaries: AVColorPrimaries,
+  pub transfer: AVColorTransferCharacteristic,
+  pub matrix: AVColorSpace,
+  pub range: AVColorRange,
+}
+
+/// Read a stream metadata entry as a string
+fn stream_metadata(stream: *const AVStream, key: &str) -> Option<String> {
+  let key = CString::new(key).ok()?;
+  metadata_value(unsafe { ffstream_get_metadata(stream, key.as_ptr()) })
+}
+
+/// Copy a metadata dictionary value, if present
+fn metadata_value(value: *const std::ffi::c_char) -> Option<String> {
+  if value.is_null() {
+    None
+  } else {
+    Some(
+      unsafe { std::ffi::CStr::from_ptr(value) }
+        .to_string_lossy()
+        .into_owned(),
+    )
+  }
+}
+
+/// Collect the stream-level side data kinds present in a stream's codec parameters
+fn stream_side_data(codecpar: *const AVCodecParameters) -> Vec<StreamSideData> {
+  stream_side_data_kind::ALL
+    .iter()
+    .filter_map(|&kind| {
+      let mut size: usize = 0;
+      let ptr = unsafe { ffcodecpar_get_stream_side_data(codecpar, kind, &mut size) };
+      (!ptr.is_null() && size > 0).then(|| StreamSideData {
+        kind,
+        data: unsafe { std::slice::from_raw_parts(ptr, size).to_vec() },
+      })
+    })
+    .collect()
 }
 
 /// Demuxer context wrapper
@@ -101,12 +180,22 @@ pub struct DemuxerContext {
 impl DemuxerContext {
   /// Open a file for demuxing
   pub fn open_file(path: &str) -> Result<Self, CodecError> {
+    Self::open_file_with_options(path, false)
+  }
+
+  /// Open a file for demuxing, optionally tolerating damaged input
+  ///
+  /// In error-tolerant mode corrupt packets are discarded instead of emitted,
+  /// and stream probing failures are ignored as long as streams were found.
+  pub fn open_file_with_options(path: &str, error_tolerant: bool) -> Result<Self, CodecError> {
     let c_path =
       CString::new(path).map_err(|_| CodecError::InvalidConfig("Invalid path".to_string()))?;
 
+    let mut options = input_options(error_tolerant);
     let mut ctx_ptr: *mut AVFormatContext = ptr::null_mut();
     let ret =
-      unsafe { avformat_open_input(&mut ctx_ptr, c_path.as_ptr(), ptr::null(), ptr::null_mut()) };
+      unsafe { avformat_open_input(&mut ctx_ptr, c_path.as_ptr(), ptr::null(), &mut options) };
+    unsafe { av_dict_free(&mut options) };
 
     if ret < 0 || ctx_ptr.is_null() {
       return Err(CodecError::Ffmpeg(crate::ffi::FFmpegError::from_code(ret)));
@@ -119,7 +208,7 @@ impl DemuxerContext {
     };
 
     // Find stream information
-    ctx.find_stream_info()?;
+    ctx.find_stream_info(error_tolerant)?;
 
     Ok(ctx)
   }
@@ -129,6 +218,16 @@ impl DemuxerContext {
   /// This method accepts any type implementing `BufferSource`, enabling
   /// zero-copy buffer loading from `Uint8Array` without intermediate copies.
   pub fn open_buffer(source: impl BufferSource + 'static) -> Result<Self, CodecError> {
+    Self::open_buffer_with_options(source, false)
+  }
+
+  /// Open a buffer for demuxing, optionally tolerating damaged input
+  ///
+  /// See [`DemuxerContext::open_file_with_options`].
+  pub fn open_buffer_with_options(
+    source: impl BufferSource + 'static,
+    error_tolerant: bool,
+  ) -> Result<Self, CodecError> {
     // Create custom I/O context for reading
     let custom_io = CustomIOContext::new_buffer_read(source).map_err(CodecError::InvalidConfig)?;
 
@@ -147,9 +246,11 @@ impl DemuxerContext {
     }
 
     // Open input
+    let mut options = input_options(error_tolerant);
     let mut ctx_ptr_mut = ctx_ptr;
     let ret =
-      unsafe { avformat_open_input(&mut ctx_ptr_mut, ptr::null(), ptr::null(), ptr::null_mut()) };
+      unsafe { avformat_open_input(&mut ctx_ptr_mut, ptr::null(), ptr::null(), &mut options) };
+    unsafe { av_dict_free(&mut options) };
 
     if ret < 0 {
       // On failure, avformat_open_input frees the context
@@ -163,16 +264,81 @@ impl DemuxerContext {
     };
 
     // Find stream information
-    ctx.find_stream_info()?;
+    ctx.find_stream_info(error_tolerant)?;
+
+    Ok(ctx)
+  }
+
+  /// Open data appended to a `StreamInput` for demuxing
+  ///
+  /// `format` names the input format (e.g. `c"mp4"`), so nothing is probed
+  /// and opening only reads the container header. FFmpeg reads until the
+  /// header is complete, which for fragmented MP4 means the first fragment
+  /// header. In low-latency mode stream info comes from the header alone:
+  /// no packets are read ahead (and buffered) to fill in stream parameters,
+  /// so the first packet is available as soon as its fragment is.
+  pub fn open_stream(
+    input: Arc<StreamInput>,
+    format: &CStr,
+    low_latency: bool,
+  ) -> Result<Self, CodecError> {
+    let input_format = unsafe { av_find_input_format(format.as_ptr()) };
+    if input_format.is_null() {
+      return Err(CodecError::InvalidConfig(format!(
+        "Unknown input format: {}",
+        format.to_string_lossy()
+      )));
+    }
+
+    let custom_io = CustomIOContext::new_stream_read(input).map_err(CodecError::InvalidConfig)?;
+
+    let ctx_ptr = unsafe { crate::ffi::avformat::avformat_alloc_context() };
+    if ctx_ptr.is_null() {
+      return Err(CodecError::AllocationFailed("AVFormatContext"));
+    }
+    unsafe {
+      fffmt_set_pb(ctx_ptr, custom_io.as_ptr());
+    }
+
+    let mut options = input_options(false);
+    if low_latency {
+      unsafe {
+        av_dict_set(&mut options, c"fflags".as_ptr(), c"+nobuffer".as_ptr(), 0);
+      }
+    }
+    let mut ctx_ptr_mut = ctx_ptr;
+    let ret =
+      unsafe { avformat_open_input(&mut ctx_ptr_mut, ptr::null(), input_format, &mut options) };
+    unsafe { av_dict_free(&mut options) };
+
+    if ret < 0 {
+      // On failure, avformat_open_input frees the context
+      return Err(CodecError::Ffmpeg(crate::ffi::FFmpegError::from_code(ret)));
+    }
+
+    let mut ctx = Self {
+      ptr: unsafe { NonNull::new_unchecked(ctx_ptr_mut) },
+      custom_io: Some(custom_io),
+      streams: Vec::new(),
+    };
+
+    if low_latency {
+      ctx.parse_streams();
+    } else {
+      ctx.find_stream_info(false)?;
+    }
 
     Ok(ctx)
   }
 
   /// Find and parse stream information
-  fn find_stream_info(&mut self) -> Result<(), CodecError> {
+  fn find_stream_info(&mut self, error_tolerant: bool) -> Result<(), CodecError> {
     let ret = unsafe { avformat_find_stream_info(self.ptr.as_ptr(), ptr::null_mut()) };
 
-    if ret < 0 {
+    // Probing can run into the damaged tail of a short file; the headers
+    // already describe the streams, so keep going if there are any
+    let has_streams = unsafe { fffmt_get_nb_streams(self.ptr.as_ptr()) } > 0;
+    if ret < 0 && !(error_tolerant && has_streams) {
       return Err(CodecError::Ffmpeg(crate::ffi::FFmpegError::from_code(ret)));
     }
 
@@ -236,6 +402,14 @@ impl DemuxerContext {
         None
       };
 
+      // Get profile (FF_PROFILE_UNKNOWN = -99)
+      let profile_raw = unsafe { ffcodecpar_get_profile(codecpar) };
+      let profile = if profile_raw >= 0 {
+        Some(profile_raw)
+      } else {
+        None
+      };
+
       // Video-specific info
       let (width, height, pixel_format) = if media_type == MediaType::Video {
         let w = unsafe { ffcodecpar_get_width(codecpar) };
@@ -250,6 +424,28 @@ impl DemuxerContext {
         (None, None, None)
       };
 
+      // Color description and ICC profile (video only)
+      let (color, icc_profile) = if media_type == MediaType::Video {
+        let color = unsafe {
+          StreamColorInfo {
+            primaries: AVColorPrimaries::from_raw(ffcodecpar_get_color_primaries(codecpar)),
+            transfer: AVColorTransferCharacteristic::from_raw(ffcodecpar_get_color_trc(codecpar)),
+            matrix: AVColorSpace::from_raw(ffcodecpar_get_color_space(codecpar)),
+            range: AVColorRange::from_raw(ffcodecpar_get_color_range(codecpar)),
+          }
+        };
+        let mut icc_size: usize = 0;
+        let icc_ptr = unsafe { ffcodecpar_get_icc_profile(codecpar, &mut icc_size) };
+        let icc_profile = if !icc_ptr.is_null() && icc_size > 0 {
+          Some(unsafe { std::slice::from_raw_parts(icc_ptr, icc_size).to_vec() })
+        } else {
+          None
+        };
+        (color, icc_profile)
+      } else {
+        (StreamColorInfo::default(), None)
+      };
+
       // Audio-specific info
       let (sample_rate, channels, sample_format) = if media_type == MediaType::Audio {
         let sr = unsafe { ffcodecpar_get_sample_rate(codecpar) };
@@ -268,6 +464,7 @@ impl DemuxerContext {
         index,
         media_type,
         codec_id,
+        codec_tag: unsafe { ffcodecpar_get_codec_tag(codecpar) },
         width,
         height,
         pixel_format,
@@ -277,6 +474,12 @@ impl DemuxerContext {
         time_base: (time_base_num, time_base_den),
         duration,
         extradata,
+        profile,
+        color,
+        icc_profile,
+        side_data: stream_side_data(codecpar),
+        disposition: unsafe { ffstream_get_disposition(stream) },
+        language: stream_metadata(stream, "language"),
       });
     }
   }
@@ -356,6 +559,12 @@ impl DemuxerContext {
     if duration > 0 { Some(duration) } else { None }
   }
 
+  /// Get a container-level metadata entry (e.g. the MP4 iTunSMPB tag)
+  pub fn metadata(&self, key: &str) -> Option<String> {
+    let key = CString::new(key).ok()?;
+    metadata_value(unsafe { fffmt_get_metadata(self.ptr.as_ptr(), key.as_ptr()) })
+  }
+
   /// Get the number of streams
   pub fn num_streams(&self) -> usize {
     self.streams.len()
@@ -383,6 +592,23 @@ impl DemuxerContext {
   }
 }
 
+/// Build avformat_open_input options (caller frees with av_dict_free)
+fn input_options(error_tolerant: bool) -> *mut AVDictionary {
+  let mut dict: *mut AVDictionary = ptr::null_mut();
+  if error_tolerant {
+    // Drop packets the demuxer flags as corrupt and keep parsing past errors
+    // (resyncing on the next element) instead of aborting
+    for (key, value) in [("fflags", "+discardcorrupt"), ("err_detect", "ignore_err")] {
+      let key = CString::new(key).unwrap();
+      let value = CString::new(value).unwrap();
+      unsafe {
+        av_dict_set(&mut dict, key.as_ptr(), value.as_ptr(), 0);
+      }
+    }
+  }
+  dict
+}
+
 impl Drop for DemuxerContext {
   fn drop(&mut self) {
     if self.custom_io.is_some() {
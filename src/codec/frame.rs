@@ -7,17 +7,23 @@ use crate::ffi::{
   AVPictureType, AVPixelFormat, AVSampleFormat,
   accessors::{
     ff_get_audio_buffer_size,
+    ffframe_crop,
     ffframe_data,
+    ffframe_drop_alpha_plane,
     ffframe_extended_data_plane,
+    ffframe_get_a53_cc,
     ffframe_get_channel_layout,
     ffframe_get_channels,
     ffframe_get_color_primaries,
     ffframe_get_color_range,
     ffframe_get_color_trc,
     ffframe_get_colorspace,
+    ffframe_get_dovi_rpu,
     ffframe_get_duration,
     ffframe_get_format,
+    ffframe_get_hdr10plus_t35,
     ffframe_get_height,
+    ffframe_get_icc_profile,
     ffframe_get_key_frame,
     // Audio accessors
     ffframe_get_nb_samples,
@@ -27,6 +33,7 @@ use crate::ffi::{
     ffframe_get_sample_rate,
     ffframe_get_width,
     ffframe_linesize,
+    ffframe_set_a53_cc,
     ffframe_set_channel_layout,
     ffframe_set_channels,
     ffframe_set_color_primaries,
@@ -35,7 +42,9 @@ use crate::ffi::{
     ffframe_set_colorspace,
     ffframe_set_duration,
     ffframe_set_format,
+    ffframe_set_hdr10plus_t35,
     ffframe_set_height,
+    ffframe_set_metadata,
     ffframe_set_nb_samples,
     ffframe_set_pict_type,
     ffframe_set_pts,
@@ -45,10 +54,11 @@ use crate::ffi::{
   },
   avutil::{
     av_frame_alloc, av_frame_copy, av_frame_copy_props, av_frame_free, av_frame_get_buffer,
-    av_frame_ref, av_frame_unref,
+    av_frame_ref, av_frame_unref, av_free,
   },
 };
 use parking_lot::RwLock;
+use std::ffi::{CStr, CString};
 use std::ptr::NonNull;
 use std::sync::Arc;
 
@@ -172,6 +182,39 @@ impl Frame {
     unsafe { ffframe_set_format(self.as_mut_ptr(), format.as_raw()) }
   }
 
+  /// Drop the alpha plane of a planar YUVA frame in place (no pixel copy)
+  ///
+  /// Returns false (and leaves the frame untouched) for formats without a
+  /// planar non-alpha equivalent.
+  pub fn drop_alpha_plane(&mut self) -> bool {
+    match self.format().without_alpha() {
+      Some(format) => {
+        unsafe { ffframe_drop_alpha_plane(self.as_mut_ptr(), format.as_raw()) };
+        true
+      }
+      None => false,
+    }
+  }
+
+  /// Crop the frame to a window in place (no pixel copy)
+  ///
+  /// Only the data pointers and dimensions change, so crop a shallow clone to
+  /// leave the shared pixel data untouched. Chroma offsets round down for
+  /// subsampled formats.
+  pub fn crop(&mut self, left: u32, top: u32, width: u32, height: u32) -> Result<(), CodecError> {
+    let ret = unsafe {
+      ffframe_crop(
+        self.as_mut_ptr(),
+        left as i32,
+        top as i32,
+        width as i32,
+        height as i32,
+      )
+    };
+    ffi::check_error(ret)?;
+    Ok(())
+  }
+
   // ========================================================================
   // Timestamps
   // ========================================================================
@@ -255,6 +298,16 @@ impl Frame {
     unsafe { ffframe_set_quality(self.as_mut_ptr(), quality) }
   }
 
+  /// Set a frame metadata entry
+  ///
+  /// Some encoders read per-frame controls from here (e.g. libvpx "vp8-flags").
+  pub fn set_metadata(&mut self, key: &CStr, value: &str) -> bool {
+    let Ok(value) = CString::new(value) else {
+      return false;
+    };
+    unsafe { ffframe_set_metadata(self.as_mut_ptr(), key.as_ptr(), value.as_ptr()) >= 0 }
+  }
+
   // ========================================================================
   // Color Space
   // ========================================================================
@@ -746,6 +799,72 @@ impl Frame {
     Ok(total_size)
   }
 
+  // ========================================================================
+  // Side Data
+  // ========================================================================
+
+  /// Get HDR10+ dynamic metadata as a complete ITU-T T.35 payload
+  pub fn hdr10plus_t35(&self) -> Option<Vec<u8>> {
+    let mut data: *mut u8 = std::ptr::null_mut();
+    let mut size: usize = 0;
+    let ret = unsafe { ffframe_get_hdr10plus_t35(self.as_ptr(), &mut data, &mut size) };
+    if ret < 0 || data.is_null() {
+      return None;
+    }
+    let payload = unsafe { std::slice::from_raw_parts(data, size).to_vec() };
+    unsafe { av_free(data as *mut std::ffi::c_void) };
+    Some(payload)
+  }
+
+  /// Attach an HDR10+ ITU-T T.35 payload as frame side data
+  ///
+  /// Returns false if the payload is not a valid HDR10+ message.
+  pub fn set_hdr10plus_t35(&mut self, payload: &[u8]) -> bool {
+    unsafe { ffframe_set_hdr10plus_t35(self.as_mut_ptr(), payload.as_ptr(), payload.len()) >= 0 }
+  }
+
+  /// Get the Dolby Vision RPU NAL unit exported by the decoder
+  pub fn dovi_rpu(&self) -> Option<&[u8]> {
+    let mut size: usize = 0;
+    let data = unsafe { ffframe_get_dovi_rpu(self.as_ptr(), &mut size) };
+    if data.is_null() || size == 0 {
+      None
+    } else {
+      Some(unsafe { std::slice::from_raw_parts(data, size) })
+    }
+  }
+
+  /// Get the ICC profile embedded in the source image, if the decoder exported one
+  pub fn icc_profile(&self) -> Option<&[u8]> {
+    let mut size: usize = 0;
+    let data = unsafe { ffframe_get_icc_profile(self.as_ptr(), &mut size) };
+    if data.is_null() || size == 0 {
+      None
+    } else {
+      Some(unsafe { std::slice::from_raw_parts(data, size) })
+    }
+  }
+
+  /// Get the A53 closed caption data (CEA-608/708 cc_data triplets) exported by the decoder
+  pub fn a53_cc(&self) -> Option<&[u8]> {
+    let mut size: usize = 0;
+    let data = unsafe { ffframe_get_a53_cc(self.as_ptr(), &mut size) };
+    if data.is_null() || size == 0 {
+      None
+    } else {
+      Some(unsafe { std::slice::from_raw_parts(data, size) })
+    }
+  }
+
+  /// Replace the A53 closed caption side data; None or empty data removes it
+  ///
+  /// Encoders with A53 support (libx264, libx265 with `a53cc`, NVENC,
+  /// VideoToolbox) write it as a caption SEI.
+  pub fn set_a53_cc(&mut self, data: Option<&[u8]>) -> bool {
+    let data = data.unwrap_or_default();
+    unsafe { ffframe_set_a53_cc(self.as_mut_ptr(), data.as_ptr(), data.len()) >= 0 }
+  }
+
   // ========================================================================
   // Lifecycle
   // ========================================================================
@@ -786,6 +905,13 @@ impl Frame {
     Ok(new_frame)
   }
 
+  /// Copy frame properties (pts, duration, color info, side data) from `src`
+  pub fn copy_props_from(&mut self, src: &Frame) -> Result<(), CodecError> {
+    let ret = unsafe { av_frame_copy_props(self.as_mut_ptr(), src.as_ptr()) };
+    ffi::check_error(ret)?;
+    Ok(())
+  }
+
   /// Create a shallow clone that shares pixel buffers via FFmpeg's AVBufferRef.
   ///
   /// This uses `av_frame_ref()` to create a new AVFrame struct that references
@@ -874,4 +1000,22 @@ mod tests {
     assert!(!frame.data(1).is_null());
     assert!(!frame.data(2).is_null());
   }
+
+  #[test]
+  fn test_drop_alpha_plane() {
+    let mut frame = Frame::new_video(64, 48, AVPixelFormat::Yuva420p).unwrap();
+    let luma = frame.data(0);
+    assert!(!frame.data(3).is_null());
+
+    assert!(frame.drop_alpha_plane());
+    assert_eq!(frame.format(), AVPixelFormat::Yuv420p);
+    assert!(frame.data(3).is_null());
+    assert_eq!(frame.linesize(3), 0);
+    // Colour planes are untouched
+    assert_eq!(frame.data(0), luma);
+
+    let mut rgba = Frame::new_video(64, 48, AVPixelFormat::Rgba).unwrap();
+    assert!(!rgba.drop_alpha_plane());
+    assert_eq!(rgba.format(), AVPixelFormat::Rgba);
+  }
 }
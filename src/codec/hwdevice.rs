@@ -1,21 +1,101 @@
 //! Safe wrapper around FFmpeg hardware device context
 //!
 //! Provides hardware acceleration device management for VideoToolbox, CUDA, VAAPI, etc.
+//!
+//! Hardware codecs share one device per device type by default. Drivers cap
+//! how many devices can be open at once (VAAPI contexts, NVENC sessions per
+//! device), so twenty encoders each opening their own device run out long
+//! before the hardware is busy.
 
 use crate::ffi::{
   self, AVBufferRef, AVHWDeviceType,
-  avutil::av_buffer_unref,
+  avutil::{av_buffer_ref, av_buffer_unref},
   hwaccel::{av_hwdevice_ctx_create, av_hwdevice_get_type_name, av_hwdevice_iterate_types},
 };
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::ffi::CStr;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 
 use super::{CodecError, CodecResult};
 
+/// Whether `HwDeviceContext::shared` hands out the process-wide device
+static SHARING_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Process-wide devices handed out by `HwDeviceContext::shared`
+static SHARED_DEVICES: OnceLock<Mutex<SharedDeviceCache<HwDeviceContext>>> = OnceLock::new();
+
+fn shared_devices() -> &'static Mutex<SharedDeviceCache<HwDeviceContext>> {
+  SHARED_DEVICES.get_or_init(|| Mutex::new(SharedDeviceCache::default()))
+}
+
+/// Enable or disable device sharing for contexts created from now on
+///
+/// Codecs that already hold a shared device keep it until they are closed.
+pub fn set_device_sharing(enabled: bool) {
+  SHARING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Reference-counted cache holding one device per device type
+///
+/// A device is created by the first `acquire` and handed back by `release`
+/// once its last user is gone, so the caller can drop it outside the lock.
+struct SharedDeviceCache<D> {
+  entries: HashMap<AVHWDeviceType, (D, usize)>,
+}
+
+impl<D> Default for SharedDeviceCache<D> {
+  fn default() -> Self {
+    Self {
+      entries: HashMap::new(),
+    }
+  }
+}
+
+impl<D> SharedDeviceCache<D> {
+  /// Add a user of the device for `device_type`, creating the device if needed
+  fn acquire<E>(
+    &mut self,
+    device_type: AVHWDeviceType,
+    create: impl FnOnce() -> Result<D, E>,
+  ) -> Result<&D, E> {
+    match self.entries.entry(device_type) {
+      Entry::Occupied(entry) => {
+        let entry = entry.into_mut();
+        entry.1 += 1;
+        Ok(&entry.0)
+      }
+      Entry::Vacant(entry) => Ok(&entry.insert((create()?, 1)).0),
+    }
+  }
+
+  /// Remove a user, returning the device once it has no users left
+  fn release(&mut self, device_type: AVHWDeviceType) -> Option<D> {
+    let entry = self.entries.get_mut(&device_type)?;
+    entry.1 = entry.1.saturating_sub(1);
+    if entry.1 > 0 {
+      return None;
+    }
+    self.entries.remove(&device_type).map(|(device, _)| device)
+  }
+
+  #[cfg(test)]
+  fn users(&self, device_type: AVHWDeviceType) -> usize {
+    self
+      .entries
+      .get(&device_type)
+      .map_or(0, |(_, users)| *users)
+  }
+}
+
 /// Safe wrapper around FFmpeg hardware device context
 pub struct HwDeviceContext {
   ptr: NonNull<AVBufferRef>,
   device_type: AVHWDeviceType,
+  /// Whether this is a reference to the process-wide shared device
+  shared: bool,
 }
 
 impl HwDeviceContext {
@@ -36,12 +116,45 @@ impl HwDeviceContext {
     ffi::check_error(ret)?;
 
     NonNull::new(device_ctx)
-      .map(|ptr| Self { ptr, device_type })
+      .map(|ptr| Self {
+        ptr,
+        device_type,
+        shared: false,
+      })
       .ok_or(CodecError::HardwareError(
         "Failed to create hardware device context".into(),
       ))
   }
 
+  /// Get a reference to the process-wide device of this type
+  ///
+  /// The device is created on first use and freed once every context returned
+  /// here has been dropped (and FFmpeg has released its own references).
+  /// Creates an isolated device like `new()` when sharing is disabled.
+  pub fn shared(device_type: AVHWDeviceType) -> CodecResult<Self> {
+    if !SHARING_ENABLED.load(Ordering::Relaxed) {
+      return Self::new(device_type);
+    }
+
+    let mut cache = shared_devices()
+      .lock()
+      .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let device = cache.acquire(device_type, || Self::new(device_type))?;
+    match NonNull::new(unsafe { av_buffer_ref(device.as_ptr()) }) {
+      Some(ptr) => Ok(Self {
+        ptr,
+        device_type,
+        shared: true,
+      }),
+      None => {
+        let unused = cache.release(device_type);
+        drop(cache);
+        drop(unused);
+        Err(CodecError::AllocationFailed("hardware device reference"))
+      }
+    }
+  }
+
   /// Try to create the best available hardware device for the current platform
   pub fn new_best_available() -> Option<Self> {
     // Platform-specific priority
@@ -152,6 +265,15 @@ impl Drop for HwDeviceContext {
       let mut ptr = self.ptr.as_ptr();
       av_buffer_unref(&mut ptr);
     }
+
+    if self.shared {
+      let mut cache = shared_devices()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+      let unused = cache.release(self.device_type);
+      drop(cache);
+      drop(unused);
+    }
   }
 }
 
@@ -179,6 +301,46 @@ mod tests {
     // Don't assert on specific types as they're platform-dependent
   }
 
+  #[test]
+  fn test_shared_cache_refcounting() {
+    let mut cache = SharedDeviceCache::default();
+    let mut created = 0;
+    let mut create = || -> Result<u32, ()> {
+      created += 1;
+      Ok(created)
+    };
+
+    assert_eq!(cache.acquire(AVHWDeviceType::Vaapi, &mut create), Ok(&1));
+    assert_eq!(cache.acquire(AVHWDeviceType::Vaapi, &mut create), Ok(&1));
+    assert_eq!(cache.acquire(AVHWDeviceType::Cuda, &mut create), Ok(&2));
+    assert_eq!(cache.users(AVHWDeviceType::Vaapi), 2);
+
+    // The device is only handed back by the last release
+    assert_eq!(cache.release(AVHWDeviceType::Vaapi), None);
+    assert_eq!(cache.release(AVHWDeviceType::Vaapi), Some(1));
+    assert_eq!(cache.users(AVHWDeviceType::Vaapi), 0);
+    assert_eq!(cache.release(AVHWDeviceType::Vaapi), None);
+
+    // A new user after the last release gets a fresh device
+    assert_eq!(cache.acquire(AVHWDeviceType::Vaapi, &mut create), Ok(&3));
+    assert_eq!(cache.users(AVHWDeviceType::Cuda), 1);
+  }
+
+  #[test]
+  fn test_shared_cache_create_failure() {
+    let mut cache = SharedDeviceCache::<u32>::default();
+    assert_eq!(
+      cache.acquire(AVHWDeviceType::Qsv, || Err("no device")),
+      Err("no device")
+    );
+    assert_eq!(cache.users(AVHWDeviceType::Qsv), 0);
+    assert_eq!(
+      cache.acquire(AVHWDeviceType::Qsv, || Ok::<_, &str>(7)),
+      Ok(&7)
+    );
+    assert_eq!(cache.users(AVHWDeviceType::Qsv), 1);
+  }
+
   #[test]
   #[cfg(target_os = "macos")]
   fn test_videotoolbox() {
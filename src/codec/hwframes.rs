@@ -160,9 +160,9 @@ impl HwFrameContext {
       )));
     }
 
-    // Copy metadata from source frame
-    hw_frame.set_pts(sw_frame.pts());
-    hw_frame.set_duration(sw_frame.duration());
+    // Copy timing, picture type and side data (HDR10+, closed captions) from
+    // the source frame
+    hw_frame.copy_props_from(sw_frame)?;
 
     Ok(hw_frame)
   }
@@ -266,6 +266,11 @@ impl Drop for HwFrameContext {
 /// This function transfers pixel data from GPU memory to a software (CPU) frame.
 /// Required for hardware decoding since decoded frames reside in GPU memory.
 ///
+/// Only the visible region is transferred: surfaces from the decoder's pool
+/// have the coded size (e.g. 1920x1088), while the frame's width/height are
+/// already cropped (1920x1080). The destination is allocated at the cropped
+/// size, so padding rows are neither copied nor kept in CPU memory.
+///
 /// # Arguments
 /// * `hw_frame` - A hardware (GPU) frame from the decoder
 ///
@@ -280,17 +285,38 @@ pub fn download_hw_frame(hw_frame: &Frame) -> CodecResult<Frame> {
     ));
   }
 
-  let mut sw_frame = Frame::new()?;
-
-  // Transfer data from GPU to CPU
-  let ret = unsafe { av_hwframe_transfer_data(sw_frame.as_mut_ptr(), hw_frame.as_ptr(), 0) };
-
-  if ret < 0 {
-    return Err(CodecError::HardwareError(format!(
-      "Failed to download frame from GPU: {}",
-      FFmpegError::from_code(ret)
-    )));
-  }
+  // Backends copy the destination's dimensions, so a visible-size frame
+  // limits the transfer to the visible region. Formats outside AVPixelFormat
+  // (and backends refusing a preallocated frame) fall back to letting FFmpeg
+  // allocate a coded-size frame.
+  let sw_format = AVPixelFormat::from_raw(unsafe { ffhwframes_get_sw_format(hw_frames_ctx) });
+  let visible_frame = if sw_format == AVPixelFormat::None {
+    None
+  } else {
+    Frame::new_video(hw_frame.width(), hw_frame.height(), sw_format).ok()
+  };
+  let transferred = visible_frame.and_then(|mut sw_frame| {
+    let ret = unsafe { av_hwframe_transfer_data(sw_frame.as_mut_ptr(), hw_frame.as_ptr(), 0) };
+    (ret >= 0).then_some(sw_frame)
+  });
+
+  let mut sw_frame = match transferred {
+    Some(sw_frame) => sw_frame,
+    None => {
+      let mut sw_frame = Frame::new()?;
+
+      // Transfer data from GPU to CPU
+      let ret = unsafe { av_hwframe_transfer_data(sw_frame.as_mut_ptr(), hw_frame.as_ptr(), 0) };
+
+      if ret < 0 {
+        return Err(CodecError::HardwareError(format!(
+          "Failed to download frame from GPU: {}",
+          FFmpegError::from_code(ret)
+        )));
+      }
+      sw_frame
+    }
+  };
 
   // Copy timestamp metadata from source frame
   sw_frame.set_pts(hw_frame.pts());
@@ -302,7 +328,14 @@ pub fn download_hw_frame(hw_frame: &Frame) -> CodecResult<Frame> {
   sw_frame.set_colorspace(hw_frame.colorspace());
   sw_frame.set_color_range(hw_frame.color_range());
 
-  tracing::debug!(target: "webcodecs", "download_hw_frame: success, format={:?}", sw_frame.format());
+  tracing::debug!(
+    target: "webcodecs",
+    width = sw_frame.width(),
+    height = sw_frame.height(),
+    coded_height = unsafe { ffhwframes_get_height(hw_frames_ctx) },
+    "download_hw_frame: success, format={:?}",
+    sw_frame.format()
+  );
   Ok(sw_frame)
 }
 
@@ -2,6 +2,7 @@
 //!
 //! Provides memory and streaming buffers for FFmpeg's custom I/O system.
 
+use std::collections::VecDeque;
 use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::sync::{Arc, Condvar, Mutex};
 
@@ -33,6 +34,13 @@ impl BufferSource for Box<[u8]> {
   }
 }
 
+/// Shared sources, for opening the same buffer with more than one demuxer context
+impl<T: BufferSource + ?Sized> BufferSource for Arc<T> {
+  fn buffer_data(&self) -> (*const u8, usize) {
+    (**self).buffer_data()
+  }
+}
+
 // ============================================================================
 // Read-Only Buffer (for demuxing with zero-copy support)
 // ============================================================================
@@ -580,6 +588,83 @@ impl StreamingBufferHandle {
   }
 }
 
+// ============================================================================
+// Stream Input (for demuxing data that is appended while it is read)
+// ============================================================================
+
+#[derive(Default)]
+struct StreamInputState {
+  /// Appended bytes the demuxer has not read yet
+  pending: VecDeque<u8>,
+  /// No more data will be appended
+  ended: bool,
+}
+
+/// Input for demuxing data that arrives in pieces, e.g. live fMP4 fragments
+///
+/// The producer appends buffers and then ends the stream; the demuxer reads
+/// through FFmpeg's custom I/O, blocking until more data arrives. Read bytes
+/// are dropped, so only data the demuxer has not consumed is kept.
+#[derive(Default)]
+pub struct StreamInput {
+  state: Mutex<StreamInputState>,
+  data_ready: Condvar,
+}
+
+impl StreamInput {
+  /// Create an empty input
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Append data for the reader, waking it if it is waiting
+  ///
+  /// Data appended after the end of the stream is ignored.
+  pub fn append(&self, data: &[u8]) {
+    let mut state = self.state.lock().unwrap();
+    if !state.ended {
+      state.pending.extend(data);
+      self.data_ready.notify_all();
+    }
+  }
+
+  /// Signal that no more data will be appended
+  ///
+  /// The reader sees end of file once it has consumed the pending data.
+  pub fn end(&self) {
+    self.state.lock().unwrap().ended = true;
+    self.data_ready.notify_all();
+  }
+
+  /// End the stream and drop the pending data, so a waiting reader returns at once
+  pub fn close(&self) {
+    let mut state = self.state.lock().unwrap();
+    state.pending.clear();
+    state.ended = true;
+    self.data_ready.notify_all();
+  }
+
+  /// Whether the stream was ended or closed
+  pub fn is_ended(&self) -> bool {
+    self.state.lock().unwrap().ended
+  }
+
+  /// Read up to `buf.len()` bytes, blocking until data is available
+  ///
+  /// Returns 0 at the end of the stream.
+  pub fn read_blocking(&self, buf: &mut [u8]) -> usize {
+    let mut state = self.state.lock().unwrap();
+    while state.pending.is_empty() && !state.ended {
+      state = self.data_ready.wait(state).unwrap();
+    }
+    let n = buf.len().min(state.pending.len());
+    for (dst, src) in buf.iter_mut().zip(state.pending.drain(..n)) {
+      *dst = src;
+    }
+    n
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -675,4 +760,29 @@ mod tests {
     let data = handle.read_available().unwrap();
     assert_eq!(&data, b"5678");
   }
+
+  #[test]
+  fn test_stream_input_blocks_until_data_or_end() {
+    let input = Arc::new(StreamInput::new());
+    let reader = {
+      let input = input.clone();
+      std::thread::spawn(move || {
+        let mut buf = [0u8; 8];
+        let mut read = Vec::new();
+        loop {
+          let n = input.read_blocking(&mut buf);
+          if n == 0 {
+            return read;
+          }
+          read.extend_from_slice(&buf[..n]);
+        }
+      })
+    };
+
+    input.append(b"moof");
+    input.append(b"mdat");
+    input.end();
+    input.append(b"late");
+    assert_eq!(reader.join().unwrap(), b"moofmdat");
+  }
 }
@@ -0,0 +1,150 @@
+//! Byte-level Matroska helpers for recovering truncated files
+//!
+//! When a recording is interrupted, the file ends inside its last Cluster and
+//! has no Cues. FFmpeg can still demux what is there, but it cannot tell us
+//! which blocks belong to the damaged cluster. These helpers locate the last
+//! Cluster element directly so callers can stop before it.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// EBML ID of a Matroska Cluster element
+const CLUSTER_ID: [u8; 4] = [0x1f, 0x43, 0xb6, 0x75];
+
+/// Bytes read per step when scanning backwards from the end of the file
+const SCAN_CHUNK: u64 = 1 << 20;
+
+/// Location of a Cluster element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClusterInfo {
+  /// Byte offset of the Cluster ID
+  pub offset: u64,
+  /// Whether the declared size fits in the file (`None` for unknown-size clusters)
+  pub complete: Option<bool>,
+}
+
+/// Find the last Cluster element in a Matroska stream of `len` bytes
+pub fn find_last_cluster<R: Read + Seek>(
+  reader: &mut R,
+  len: u64,
+) -> io::Result<Option<ClusterInfo>> {
+  // Room for the ID plus the longest size VINT past the end of each window
+  const LOOKAHEAD: u64 = 12;
+
+  let mut end = len;
+  let mut buf = Vec::new();
+  while end > 0 {
+    let start = end.saturating_sub(SCAN_CHUNK);
+    let window_end = (end + LOOKAHEAD).min(len);
+    buf.resize((window_end - start) as usize, 0);
+    reader.seek(SeekFrom::Start(start))?;
+    reader.read_exact(&mut buf)?;
+
+    let candidates = (end - start) as usize;
+    for i in (0..candidates).rev() {
+      if !buf[i..].starts_with(&CLUSTER_ID) {
+        continue;
+      }
+      // Cluster IDs can appear by chance inside block payloads; require a valid size
+      let Some((size_len, size)) = read_vint_size(&buf[i + CLUSTER_ID.len()..]) else {
+        continue;
+      };
+      let offset = start + i as u64;
+      let data_start = offset + (CLUSTER_ID.len() + size_len) as u64;
+      return Ok(Some(ClusterInfo {
+        offset,
+        complete: size.map(|size| data_start.saturating_add(size) <= len),
+      }));
+    }
+    end = start;
+  }
+
+  Ok(None)
+}
+
+/// Parse an EBML element size VINT
+///
+/// Returns the encoded length and the size, with `None` for the reserved
+/// "unknown size" value used by live recordings.
+fn read_vint_size(bytes: &[u8]) -> Option<(usize, Option<u64>)> {
+  let first = *bytes.first()?;
+  if first == 0 {
+    return None;
+  }
+  let len = first.leading_zeros() as usize + 1;
+  let encoded = bytes.get(..len)?;
+
+  let mut value = (first & (0xff >> len)) as u64;
+  for &byte in &encoded[1..] {
+    value = (value << 8) | byte as u64;
+  }
+
+  let unknown = (1u64 << (7 * len)) - 1;
+  Some((len, if value == unknown { None } else { Some(value) }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn test_read_vint_size() {
+    assert_eq!(read_vint_size(&[0x81]), Some((1, Some(1))));
+    assert_eq!(read_vint_size(&[0x40, 0x02]), Some((2, Some(2))));
+    assert_eq!(read_vint_size(&[0xff]), Some((1, None)));
+    assert_eq!(
+      read_vint_size(&[0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+      Some((8, None))
+    );
+    assert_eq!(read_vint_size(&[0x00]), None);
+    assert_eq!(read_vint_size(&[0x40]), None);
+  }
+
+  fn cluster(payload_len: usize, declared: u8) -> Vec<u8> {
+    let mut data = CLUSTER_ID.to_vec();
+    data.push(0x80 | declared);
+    data.extend(std::iter::repeat_n(0xaa, payload_len));
+    data
+  }
+
+  #[test]
+  fn test_find_last_cluster() {
+    let mut data = vec![0u8; 16];
+    data.extend(cluster(10, 10));
+    let second = data.len() as u64;
+    data.extend(cluster(10, 10));
+
+    let len = data.len() as u64;
+    let found = find_last_cluster(&mut Cursor::new(&data), len).unwrap();
+    assert_eq!(
+      found,
+      Some(ClusterInfo {
+        offset: second,
+        complete: Some(true)
+      })
+    );
+
+    // Cut inside the last cluster's payload
+    data.truncate(data.len() - 4);
+    let len = data.len() as u64;
+    let found = find_last_cluster(&mut Cursor::new(&data), len).unwrap();
+    assert_eq!(
+      found.map(|c| (c.offset, c.complete)),
+      Some((second, Some(false)))
+    );
+  }
+
+  #[test]
+  fn test_find_last_cluster_unknown_size() {
+    let mut data = vec![0u8; 8];
+    data.extend(CLUSTER_ID);
+    data.push(0xff);
+    data.extend([0u8; 6]);
+    let len = data.len() as u64;
+    let found = find_last_cluster(&mut Cursor::new(&data), len).unwrap();
+    assert_eq!(found.map(|c| (c.offset, c.complete)), Some((8, None)));
+
+    let empty = find_last_cluster(&mut Cursor::new(&[0u8; 32]), 32).unwrap();
+    assert_eq!(empty, None);
+  }
+}
@@ -11,20 +11,26 @@ pub mod frame;
 pub mod hwdevice;
 pub mod hwframes;
 pub mod io_buffer;
+pub mod matroska;
 pub mod mp4_faststart;
+pub mod mp4_gapless;
 pub mod muxer;
+pub mod orientation;
 pub mod packet;
+pub mod pass_stats;
+pub mod pixel_convert;
 pub mod resampler;
 pub mod scaler;
+pub mod temp_file;
 
 pub use audio_buffer::AudioSampleBuffer;
 pub use context::{CodecContext, CodecType, DecoderCreationResult, EncoderCreationResult};
 pub use frame::Frame;
-pub use hwdevice::HwDeviceContext;
+pub use hwdevice::{HwDeviceContext, set_device_sharing};
 pub use hwframes::{HwFrameConfig, HwFrameContext, download_hw_frame};
 pub use packet::Packet;
 pub use resampler::Resampler;
-pub use scaler::{ScaleAlgorithm, Scaler};
+pub use scaler::{ChromaLocation, DitherMode, ScaleAlgorithm, ScaleOptions, Scaler};
 
 use crate::ffi::{AVCodecID, AVPixelFormat, AVSampleFormat};
 
@@ -40,6 +46,15 @@ pub enum BitrateMode {
   Quantizer,
 }
 
+/// Rate-control pass for two-pass encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncoderPass {
+  /// First pass - analyze the input and collect rate-control statistics
+  First,
+  /// Second pass - encode using statistics collected by a first pass
+  Second(Vec<u8>),
+}
+
 /// Encoder configuration
 #[derive(Debug, Clone)]
 pub struct EncoderConfig {
@@ -85,6 +100,12 @@ pub struct EncoderConfig {
   pub rc_buffer_size: Option<u32>,
   /// CRF/CQ value for quantizer mode (0-51 for x264/x265, 0-255 for VP9/AV1)
   pub crf: Option<u32>,
+  /// Two-pass rate control (None for single-pass encoding).
+  /// Statistics are exchanged as bytes via `stats_out`/`stats_in` for libvpx,
+  /// libaom and rav1e, and via a private stats file for libx264/libx265.
+  pub pass: Option<EncoderPass>,
+  /// Sample (pixel) aspect ratio signalled in the bitstream (None = square pixels)
+  pub sample_aspect_ratio: Option<(i32, i32)>,
 }
 
 impl Default for EncoderConfig {
@@ -105,10 +126,69 @@ impl Default for EncoderConfig {
       rc_max_rate: None,
       rc_buffer_size: None,
       crf: None,
+      pass: None,
+      sample_aspect_ratio: None,
     }
   }
 }
 
+/// Sample aspect ratio that stretches `width`x`height` to the display size
+///
+/// Returns None for square pixels. The ratio is reduced and, if needed,
+/// scaled down to fit the 16-bit fields H.264/HEVC VUI and MP4 pasp use.
+pub fn sample_aspect_ratio(
+  width: u32,
+  height: u32,
+  display_width: u32,
+  display_height: u32,
+) -> Option<(i32, i32)> {
+  if width == 0 || height == 0 || display_width == 0 || display_height == 0 {
+    return None;
+  }
+  let mut num = display_width as u64 * height as u64;
+  let mut den = display_height as u64 * width as u64;
+  let divisor = gcd(num, den);
+  num /= divisor;
+  den /= divisor;
+  while num > u16::MAX as u64 || den > u16::MAX as u64 {
+    num = num.div_ceil(2);
+    den = den.div_ceil(2);
+  }
+  if num == den {
+    return None;
+  }
+  Some((num as i32, den as i32))
+}
+
+/// Exact frame rate as a reduced `(num, den)` rational
+///
+/// NTSC rates given as decimals (29.97, 23.976, 59.94) map to N*1000/1001, so
+/// a timescale derived from the numerator has an integral frame duration.
+/// Other rates are kept to 1/1000 fps. Returns None for non-positive or
+/// non-finite rates.
+pub fn framerate_rational(fps: f64) -> Option<(i32, i32)> {
+  if !fps.is_finite() || fps <= 0.0 {
+    return None;
+  }
+  let ntsc = (fps * 1.001).round();
+  if fps.fract().abs() > 1e-3 && (fps * 1.001 - ntsc).abs() < 1e-3 {
+    return Some(((ntsc as i32).checked_mul(1000)?, 1001));
+  }
+  let num = (fps * 1000.0).round() as u64;
+  if num == 0 || num > i32::MAX as u64 {
+    return None;
+  }
+  let divisor = gcd(num, 1000);
+  Some(((num / divisor) as i32, (1000 / divisor) as i32))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+  while b != 0 {
+    (a, b) = (b, a % b);
+  }
+  a
+}
+
 /// Decoder configuration
 #[derive(Debug, Clone)]
 pub struct DecoderConfig {
@@ -125,6 +205,8 @@ pub struct DecoderConfig {
   pub width: Option<u32>,
   /// Video coded height (for hardware decoding - may be required for some platforms)
   pub height: Option<u32>,
+  /// Maximum pixels per frame (FFmpeg `max_pixels`), rejects oversized streams before allocation
+  pub max_pixels: Option<u64>,
 }
 
 impl Default for DecoderConfig {
@@ -136,6 +218,7 @@ impl Default for DecoderConfig {
       low_latency: false,
       width: None,
       height: None,
+      max_pixels: None,
     }
   }
 }
@@ -226,6 +309,38 @@ pub enum CodecError {
 
   #[error("Hardware acceleration error: {0}")]
   HardwareError(String),
+
+  #[error("FFmpeg fatal error: {0}")]
+  Fatal(String),
+
+  #[error("Hardware encoder session limit reached: {0}")]
+  SessionLimit(String),
 }
 
 pub type CodecResult<T> = Result<T, CodecError>;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sample_aspect_ratio() {
+    assert_eq!(sample_aspect_ratio(640, 480, 640, 480), None);
+    assert_eq!(sample_aspect_ratio(640, 480, 1280, 960), None);
+    assert_eq!(sample_aspect_ratio(720, 480, 640, 480), Some((8, 9)));
+    assert_eq!(sample_aspect_ratio(1440, 1080, 1920, 1080), Some((4, 3)));
+    assert_eq!(sample_aspect_ratio(640, 480, 0, 480), None);
+  }
+
+  #[test]
+  fn test_framerate_rational() {
+    assert_eq!(framerate_rational(30.0), Some((30, 1)));
+    assert_eq!(framerate_rational(12.5), Some((25, 2)));
+    assert_eq!(framerate_rational(29.97), Some((30000, 1001)));
+    assert_eq!(framerate_rational(30000.0 / 1001.0), Some((30000, 1001)));
+    assert_eq!(framerate_rational(23.976), Some((24000, 1001)));
+    assert_eq!(framerate_rational(59.94), Some((60000, 1001)));
+    assert_eq!(framerate_rational(0.0), None);
+    assert_eq!(framerate_rational(f64::NAN), None);
+  }
+}
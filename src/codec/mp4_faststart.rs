@@ -4,7 +4,8 @@
 //! of an MP4 file for faster streaming playback. This is necessary because
 //! FFmpeg's faststart option doesn't work with custom I/O contexts.
 
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use super::temp_file::TempFile;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
 
 /// Apply faststart post-processing to MP4 data
 ///
@@ -14,7 +15,7 @@ use std::io::{Cursor, Read, Seek, SeekFrom};
 /// Returns the modified MP4 data, or the original data if moov is already
 /// at the beginning or if parsing fails.
 pub fn apply_faststart(data: Vec<u8>) -> Vec<u8> {
-  match apply_faststart_inner(&data) {
+  match apply_faststart_inner(&mut Cursor::new(&data), data.len() as u64) {
     Ok(result) => result,
     Err(e) => {
       tracing::warn!(target: "ffmpeg", "faststart post-processing failed: {}, returning original data", e);
@@ -23,9 +24,31 @@ pub fn apply_faststart(data: Vec<u8>) -> Vec<u8> {
   }
 }
 
+/// Apply faststart post-processing to MP4 data written to a temporary file
+///
+/// Atoms are copied from the file straight into the returned buffer, so
+/// the unprocessed file is never held in memory. Returns the file contents
+/// unchanged if parsing fails.
+pub fn apply_faststart_file(file: &mut TempFile) -> io::Result<Vec<u8>> {
+  let len = file.len();
+  match apply_faststart_inner(file, len) {
+    Ok(result) => Ok(result),
+    Err(FastStartError::Io(e)) => Err(e),
+    Err(e) => {
+      tracing::warn!(target: "ffmpeg", "faststart post-processing failed: {}, returning original data", e);
+      let mut data = Vec::with_capacity(len as usize);
+      copy_range(file, 0, len, &mut data)?;
+      Ok(data)
+    }
+  }
+}
+
 /// Internal faststart implementation
-fn apply_faststart_inner(data: &[u8]) -> Result<Vec<u8>, FastStartError> {
-  let atoms = parse_atoms(data)?;
+fn apply_faststart_inner<R: Read + Seek>(
+  reader: &mut R,
+  len: u64,
+) -> Result<Vec<u8>, FastStartError> {
+  let atoms = parse_atoms(reader, len)?;
 
   // Find ftyp, moov, and mdat atoms
   let ftyp = atoms.iter().find(|a| &a.atom_type == b"ftyp");
@@ -35,18 +58,17 @@ fn apply_faststart_inner(data: &[u8]) -> Result<Vec<u8>, FastStartError> {
   let moov = moov.ok_or(FastStartError::MissingAtom("moov"))?;
   let mdat = mdat.ok_or(FastStartError::MissingAtom("mdat"))?;
 
+  let mut result = Vec::with_capacity(len as usize);
+
   // Check if moov is already before mdat
   if moov.offset < mdat.offset {
     tracing::trace!(target: "ffmpeg", "moov already before mdat, no faststart needed");
-    return Ok(data.to_vec());
+    copy_range(reader, 0, len, &mut result)?;
+    return Ok(result);
   }
 
   tracing::trace!(target: "ffmpeg", "applying faststart: moov at {}, mdat at {}", moov.offset, mdat.offset);
 
-  // Calculate the new positions
-  // Layout will be: ftyp | moov | mdat | other atoms
-  let _ftyp_end = ftyp.map(|f| f.offset + f.size).unwrap_or(0);
-
   // The offset adjustment for chunk offsets:
   // Original: chunks point to data in mdat at its old position
   // New: mdat moves forward by moov.size bytes (moov is now before mdat)
@@ -54,15 +76,16 @@ fn apply_faststart_inner(data: &[u8]) -> Result<Vec<u8>, FastStartError> {
   let offset_adjustment = moov.size as i64;
 
   // Extract moov data and update chunk offsets
-  let moov_data = &data[moov.offset..moov.offset + moov.size];
-  let updated_moov = update_chunk_offsets(moov_data, offset_adjustment)?;
+  let mut moov_data = Vec::with_capacity(moov.size as usize);
+  copy_range(reader, moov.offset, moov.size, &mut moov_data)?;
+  let updated_moov = update_chunk_offsets(&moov_data, offset_adjustment)?;
 
   // Build the new file
-  let mut result = Vec::with_capacity(data.len());
+  // Layout will be: ftyp | moov | mdat | other atoms
 
   // Write ftyp (if present)
   if let Some(ftyp) = ftyp {
-    result.extend_from_slice(&data[ftyp.offset..ftyp.offset + ftyp.size]);
+    copy_range(reader, ftyp.offset, ftyp.size, &mut result)?;
   }
 
   // Write updated moov
@@ -71,75 +94,94 @@ fn apply_faststart_inner(data: &[u8]) -> Result<Vec<u8>, FastStartError> {
   // Write all other atoms in their original order (except moov and ftyp)
   for atom in &atoms {
     if &atom.atom_type != b"moov" && &atom.atom_type != b"ftyp" {
-      result.extend_from_slice(&data[atom.offset..atom.offset + atom.size]);
+      copy_range(reader, atom.offset, atom.size, &mut result)?;
     }
   }
 
-  tracing::trace!(target: "ffmpeg", "faststart complete: {} -> {} bytes", data.len(), result.len());
+  tracing::trace!(target: "ffmpeg", "faststart complete: {} -> {} bytes", len, result.len());
   Ok(result)
 }
 
+/// Append `size` bytes starting at `offset` to `out`
+fn copy_range<R: Read + Seek>(
+  reader: &mut R,
+  offset: u64,
+  size: u64,
+  out: &mut Vec<u8>,
+) -> io::Result<()> {
+  reader.seek(SeekFrom::Start(offset))?;
+  let copied = reader.by_ref().take(size).read_to_end(out)?;
+  if (copied as u64) < size {
+    return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+  }
+  Ok(())
+}
+
 /// Parsed atom information
 #[derive(Debug)]
-struct AtomInfo {
-  atom_type: [u8; 4],
-  offset: usize,
-  size: usize,
+pub(super) struct AtomInfo {
+  pub atom_type: [u8; 4],
+  pub offset: u64,
+  pub size: u64,
 }
 
-/// Parse top-level atoms from MP4 data
-fn parse_atoms(data: &[u8]) -> Result<Vec<AtomInfo>, FastStartError> {
+/// Parse top-level atoms from the first `len` bytes of MP4 data
+pub(super) fn parse_atoms<R: Read + Seek>(
+  reader: &mut R,
+  len: u64,
+) -> Result<Vec<AtomInfo>, FastStartError> {
   let mut atoms = Vec::new();
-  let mut cursor = Cursor::new(data);
-
-  while cursor.position() < data.len() as u64 {
-    let offset = cursor.position() as usize;
+  let mut offset = reader.seek(SeekFrom::Start(0))?;
 
+  while offset < len {
     // Read atom size (4 bytes, big-endian)
     let mut size_buf = [0u8; 4];
-    if cursor.read_exact(&mut size_buf).is_err() {
+    if reader.read_exact(&mut size_buf).is_err() {
       break;
     }
     let mut size = u32::from_be_bytes(size_buf) as u64;
 
     // Read atom type (4 bytes)
     let mut atom_type = [0u8; 4];
-    if cursor.read_exact(&mut atom_type).is_err() {
+    if reader.read_exact(&mut atom_type).is_err() {
       break;
     }
 
     // Handle extended size (size == 1 means 64-bit size follows)
     if size == 1 {
       let mut ext_size_buf = [0u8; 8];
-      if cursor.read_exact(&mut ext_size_buf).is_err() {
+      if reader.read_exact(&mut ext_size_buf).is_err() {
         break;
       }
       size = u64::from_be_bytes(ext_size_buf);
     } else if size == 0 {
       // Size 0 means atom extends to end of file
-      size = (data.len() - offset) as u64;
+      size = len - offset;
     }
 
     // Validate size
-    if size < 8 || offset + size as usize > data.len() {
+    if size < 8 || size > len - offset {
       return Err(FastStartError::InvalidAtomSize);
     }
 
     atoms.push(AtomInfo {
       atom_type,
       offset,
-      size: size as usize,
+      size,
     });
 
     // Move to next atom
-    cursor.seek(SeekFrom::Start(offset as u64 + size))?;
+    offset = reader.seek(SeekFrom::Start(offset + size))?;
   }
 
   Ok(atoms)
 }
 
 /// Update chunk offsets in moov atom
-fn update_chunk_offsets(moov_data: &[u8], adjustment: i64) -> Result<Vec<u8>, FastStartError> {
+pub(super) fn update_chunk_offsets(
+  moov_data: &[u8],
+  adjustment: i64,
+) -> Result<Vec<u8>, FastStartError> {
   let mut result = moov_data.to_vec();
   update_chunk_offsets_recursive(&mut result, 8, adjustment)?;
   Ok(result)
@@ -290,7 +332,7 @@ mod tests {
     data.extend_from_slice(&8u32.to_be_bytes());
     data.extend_from_slice(b"moov");
 
-    let atoms = parse_atoms(&data).unwrap();
+    let atoms = parse_atoms(&mut Cursor::new(&data), data.len() as u64).unwrap();
     assert_eq!(atoms.len(), 3);
     assert_eq!(&atoms[0].atom_type, b"ftyp");
     assert_eq!(&atoms[1].atom_type, b"mdat");
@@ -0,0 +1,414 @@
+//! MP4 Gapless Playback Metadata
+//!
+//! Lossy audio codecs like AAC start with encoder delay (priming samples) and
+//! end with padding up to a whole frame. Gapless players drop both using the
+//! iTunSMPB tag (`moov/udta/meta/ilst/----`), and FFmpeg-based readers also
+//! honor an edit list skipping the priming samples.
+//!
+//! The muxer shifts audio timestamps back by the priming samples so FFmpeg
+//! writes the edit list, then adds iTunSMPB after the trailer since FFmpeg
+//! only writes freeform iTunes tags it read from another MP4.
+
+use super::Packet;
+use super::mp4_faststart::{FastStartError, parse_atoms, update_chunk_offsets};
+use crate::ffi::AVCodecID;
+use std::io::Cursor;
+
+/// Metadata key FFmpeg exposes the iTunSMPB tag under
+pub const ITUNSMPB_KEY: &str = "iTunSMPB";
+
+/// Encoder delay, padding and length of an audio track, in samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GaplessInfo {
+  /// Priming samples before the first real sample
+  pub priming: u32,
+  /// Padding samples after the last real sample
+  pub padding: u32,
+  /// Real samples, excluding priming and padding
+  pub samples: u64,
+}
+
+impl GaplessInfo {
+  /// Format as an iTunSMPB value
+  pub fn to_itunsmpb(&self) -> String {
+    format!(
+      " 00000000 {:08X} {:08X} {:016X}{}",
+      self.priming,
+      self.padding,
+      self.samples,
+      " 00000000".repeat(8)
+    )
+  }
+
+  /// Parse an iTunSMPB value
+  pub fn from_itunsmpb(value: &str) -> Option<Self> {
+    let mut fields = value.split_whitespace().skip(1);
+    let priming = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let padding = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let samples = u64::from_str_radix(fields.next()?, 16).ok()?;
+    Some(Self {
+      priming,
+      padding,
+      samples,
+    })
+  }
+}
+
+/// Samples each packet decodes to, for the codecs iTunSMPB is defined for
+pub fn gapless_frame_size(codec_id: AVCodecID) -> Option<u32> {
+  match codec_id {
+    AVCodecID::Aac => Some(1024),
+    AVCodecID::Mp3 => Some(1152),
+    _ => None,
+  }
+}
+
+/// Collects an audio track's gapless info while muxing
+///
+/// Priming and padding given in the track config win; otherwise they come
+/// from the skip-samples side data of the first and last packet, which
+/// AudioEncoder sets.
+#[derive(Debug, Clone)]
+pub struct GaplessTracker {
+  frame_size: u32,
+  priming: Option<u32>,
+  padding: Option<u32>,
+  last_padding: u32,
+  packets: u64,
+}
+
+impl GaplessTracker {
+  pub fn new(frame_size: u32, priming: Option<u32>, padding: Option<u32>) -> Self {
+    Self {
+      frame_size,
+      priming,
+      padding,
+      last_padding: 0,
+      packets: 0,
+    }
+  }
+
+  /// Record the next packet written, returning the priming samples its
+  /// timestamp must be shifted back by
+  pub fn record(&mut self, packet: &Packet) -> u32 {
+    let (start, end) = packet.skip_samples().unwrap_or((0, 0));
+    let priming = *self.priming.get_or_insert(start);
+    self.last_padding = end;
+    self.packets += 1;
+    priming
+  }
+
+  /// Gapless info for the packets written, or None if there is nothing to trim
+  pub fn info(&self) -> Option<GaplessInfo> {
+    let priming = self.priming.unwrap_or(0);
+    let padding = self.padding.unwrap_or(self.last_padding);
+    if priming == 0 && padding == 0 {
+      return None;
+    }
+    let total = self.packets * self.frame_size as u64;
+    let samples = total.checked_sub(priming as u64 + padding as u64)?;
+    Some(GaplessInfo {
+      priming,
+      padding,
+      samples,
+    })
+  }
+}
+
+/// Trims the padding an iTunSMPB tag declares while demuxing from the start
+///
+/// FFmpeg skips the priming samples through the edit list; the padding is
+/// marked on the packets past the last real sample as skip-samples side data,
+/// which the decoder applies.
+#[derive(Debug, Clone)]
+pub struct GaplessTrim {
+  info: GaplessInfo,
+  frame_size: u32,
+  /// Decoded samples covered by the packets seen so far
+  position: u64,
+}
+
+impl GaplessTrim {
+  pub fn new(info: GaplessInfo, frame_size: u32) -> Self {
+    Self {
+      info,
+      frame_size,
+      position: 0,
+    }
+  }
+
+  /// Mark the samples of the next packet that lie outside the real samples
+  pub fn apply(&mut self, packet: &mut Packet) {
+    let (start, end) = packet.skip_samples().unwrap_or((0, 0));
+    let packet_start = self.position;
+    self.position += self.frame_size as u64;
+
+    // FFmpeg signals the priming samples itself unless the edit list is missing
+    let skip_start = if packet_start == 0 && start == 0 {
+      self.info.priming
+    } else {
+      start
+    };
+    let last_sample = self.info.priming as u64 + self.info.samples;
+    let skip_end = self
+      .position
+      .saturating_sub(last_sample.max(packet_start))
+      .min(self.frame_size as u64) as u32;
+
+    if (skip_start, skip_end.max(end)) != (start, end)
+      && let Err(e) = packet.set_skip_samples(skip_start, skip_end.max(end))
+    {
+      tracing::warn!(target: "ffmpeg", "Failed to mark gapless audio trim: {}", e);
+    }
+  }
+}
+
+/// Add an iTunSMPB tag to the moov atom of a complete MP4 file
+///
+/// Missing udta/meta/ilst containers are created. If moov precedes mdat
+/// (fastStart), chunk offsets are moved by the growth of moov. Returns the
+/// original data if parsing fails.
+pub fn insert_itunsmpb(data: Vec<u8>, info: &GaplessInfo) -> Vec<u8> {
+  match insert_itunsmpb_inner(&data, info) {
+    Ok(result) => result,
+    Err(e) => {
+      tracing::warn!(target: "ffmpeg", "iTunSMPB tag insertion failed: {}, returning original data", e);
+      data
+    }
+  }
+}
+
+fn insert_itunsmpb_inner(data: &[u8], info: &GaplessInfo) -> Result<Vec<u8>, FastStartError> {
+  let atoms = parse_atoms(&mut Cursor::new(data), data.len() as u64)?;
+  let moov = atoms
+    .iter()
+    .find(|a| &a.atom_type == b"moov")
+    .ok_or(FastStartError::MissingAtom("moov"))?;
+  let mdat = atoms
+    .iter()
+    .find(|a| &a.atom_type == b"mdat")
+    .ok_or(FastStartError::MissingAtom("mdat"))?;
+
+  let start = moov.offset as usize;
+  let end = (moov.offset + moov.size) as usize;
+  let mut new_moov = data[start..end].to_vec();
+  append_child(
+    &mut new_moov,
+    &[b"udta", b"meta", b"ilst"],
+    &itunsmpb_atom(info),
+  )?;
+  if moov.offset < mdat.offset {
+    let growth = (new_moov.len() - (end - start)) as i64;
+    new_moov = update_chunk_offsets(&new_moov, growth)?;
+  }
+
+  let mut result = Vec::with_capacity(data.len() - (end - start) + new_moov.len());
+  result.extend_from_slice(&data[..start]);
+  result.extend_from_slice(&new_moov);
+  result.extend_from_slice(&data[end..]);
+  Ok(result)
+}
+
+/// Build an atom from its type and payload
+fn atom(atom_type: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+  let mut data = Vec::with_capacity(8 + payload.len());
+  data.extend_from_slice(&((8 + payload.len()) as u32).to_be_bytes());
+  data.extend_from_slice(atom_type);
+  data.extend_from_slice(payload);
+  data
+}
+
+/// Build a full atom (version 0, no flags) from its type and payload
+fn full_atom(atom_type: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+  atom(atom_type, &[&[0u8; 4][..], payload].concat())
+}
+
+/// Freeform `----` atom holding the iTunSMPB value
+fn itunsmpb_atom(info: &GaplessInfo) -> Vec<u8> {
+  // Data type 1 (UTF-8) followed by a zero locale
+  let value = [
+    &1u32.to_be_bytes()[..],
+    &[0u8; 4],
+    info.to_itunsmpb().as_bytes(),
+  ]
+  .concat();
+  let children = [
+    full_atom(b"mean", b"com.apple.iTunes"),
+    full_atom(b"name", ITUNSMPB_KEY.as_bytes()),
+    atom(b"data", &value),
+  ]
+  .concat();
+  atom(b"----", &children)
+}
+
+/// Header length of a container atom (meta is a full atom)
+fn header_len(atom_type: &[u8]) -> usize {
+  if atom_type == b"meta" { 12 } else { 8 }
+}
+
+/// Find a child atom by type, returning its offset and size
+fn find_child(container: &[u8], atom_type: &[u8; 4]) -> Option<(usize, usize)> {
+  let mut pos = header_len(&container[4..8]);
+  while pos + 8 <= container.len() {
+    let size = u32::from_be_bytes(container[pos..pos + 4].try_into().unwrap()) as usize;
+    if size < 8 || pos + size > container.len() {
+      return None;
+    }
+    if &container[pos + 4..pos + 8] == atom_type {
+      return Some((pos, size));
+    }
+    pos += size;
+  }
+  None
+}
+
+/// Append `child` inside the containers at `path` below `container`,
+/// creating missing containers and updating sizes on the way back up
+fn append_child(
+  container: &mut Vec<u8>,
+  path: &[&[u8; 4]],
+  child: &[u8],
+) -> Result<(), FastStartError> {
+  // 64-bit sizes are not expected below the top level
+  if container.len() < header_len(&container[4..8])
+    || u32::from_be_bytes(container[..4].try_into().unwrap()) as usize != container.len()
+  {
+    return Err(FastStartError::InvalidAtomSize);
+  }
+
+  match path.split_first() {
+    None => container.extend_from_slice(child),
+    Some((atom_type, rest)) => match find_child(container, atom_type) {
+      Some((offset, size)) => {
+        let mut existing = container[offset..offset + size].to_vec();
+        append_child(&mut existing, rest, child)?;
+        container.splice(offset..offset + size, existing);
+      }
+      None => container.extend_from_slice(&wrap(path, child)),
+    },
+  }
+
+  let size = u32::try_from(container.len()).map_err(|_| FastStartError::InvalidAtomSize)?;
+  container[..4].copy_from_slice(&size.to_be_bytes());
+  Ok(())
+}
+
+/// Wrap `child` in new containers, outermost first
+fn wrap(path: &[&[u8; 4]], child: &[u8]) -> Vec<u8> {
+  path.iter().rev().fold(child.to_vec(), |inner, atom_type| {
+    if *atom_type == b"meta" {
+      // iTunes metadata handler: pre_defined, "mdir", "appl" reserved, empty name
+      let handler = full_atom(b"hdlr", &[&[0u8; 4][..], b"mdirappl", &[0u8; 9]].concat());
+      full_atom(b"meta", &[handler, inner].concat())
+    } else {
+      atom(atom_type, &inner)
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn test_file(moov_first: bool) -> Vec<u8> {
+    // trak/mdia/minf/stbl/stco with one chunk at offset 0 of mdat's payload
+    let stco = full_atom(b"stco", &[1u32.to_be_bytes(), 0u32.to_be_bytes()].concat());
+    let stbl = atom(b"stbl", &stco);
+    let trak = atom(b"trak", &atom(b"mdia", &atom(b"minf", &stbl)));
+    let mut moov = atom(b"moov", &trak);
+    let ftyp = atom(b"ftyp", b"isom\0\0\x02\0");
+    let mdat_offset = if moov_first {
+      ftyp.len() + moov.len()
+    } else {
+      ftyp.len()
+    } as u32;
+    let stco_pos = moov.len() - 4;
+    moov[stco_pos..].copy_from_slice(&(mdat_offset + 8).to_be_bytes());
+    let mdat = atom(b"mdat", b"payload");
+    if moov_first {
+      [ftyp, moov, mdat].concat()
+    } else {
+      [ftyp, mdat, moov].concat()
+    }
+  }
+
+  fn chunk_offset(data: &[u8]) -> u32 {
+    let pos = data.windows(4).position(|w| w == b"stco").unwrap();
+    u32::from_be_bytes(data[pos + 12..pos + 16].try_into().unwrap())
+  }
+
+  #[test]
+  fn test_itunsmpb_round_trip() {
+    let info = GaplessInfo {
+      priming: 1024,
+      padding: 901,
+      samples: 48_123,
+    };
+    let value = info.to_itunsmpb();
+    assert!(value.starts_with(" 00000000 00000400 00000385 000000000000BBFB"));
+    assert_eq!(GaplessInfo::from_itunsmpb(&value), Some(info));
+    assert_eq!(GaplessInfo::from_itunsmpb(" 00000000 400"), None);
+  }
+
+  #[test]
+  fn test_insert_creates_containers_and_moves_chunk_offsets() {
+    let info = GaplessInfo {
+      priming: 1024,
+      padding: 901,
+      samples: 48_123,
+    };
+    for moov_first in [false, true] {
+      let original = test_file(moov_first);
+      let data = insert_itunsmpb(original.clone(), &info);
+      let mdat = data.windows(4).position(|w| w == b"mdat").unwrap() - 4;
+      assert_eq!(chunk_offset(&data) as usize, mdat + 8);
+      assert_eq!(&data[mdat + 8..mdat + 15], b"payload");
+
+      let atoms = parse_atoms(&mut Cursor::new(&data), data.len() as u64).unwrap();
+      let moov = atoms.iter().find(|a| &a.atom_type == b"moov").unwrap();
+      let moov = &data[moov.offset as usize..(moov.offset + moov.size) as usize];
+      let (udta, size) = find_child(moov, b"udta").unwrap();
+      let udta = &moov[udta..udta + size];
+      let (meta, size) = find_child(udta, b"meta").unwrap();
+      let meta = &udta[meta..meta + size];
+      assert!(find_child(meta, b"hdlr").is_some());
+      let (ilst, size) = find_child(meta, b"ilst").unwrap();
+      assert!(find_child(&meta[ilst..ilst + size], b"----").is_some());
+    }
+  }
+
+  #[test]
+  fn test_insert_appends_to_existing_ilst() {
+    let info = GaplessInfo {
+      priming: 1024,
+      padding: 0,
+      samples: 1,
+    };
+    let once = insert_itunsmpb(test_file(false), &info);
+    let twice = insert_itunsmpb(once.clone(), &info);
+    let tag_len = itunsmpb_atom(&info).len();
+    assert_eq!(twice.len(), once.len() + tag_len);
+    assert_eq!(twice.windows(4).filter(|w| *w == b"ilst").count(), 1);
+    assert_eq!(twice.windows(4).filter(|w| *w == b"----").count(), 2);
+  }
+
+  #[test]
+  fn test_trim_marks_priming_and_padding() {
+    let info = GaplessInfo {
+      priming: 1024,
+      padding: 901,
+      samples: 2 * 1024 - 901 + 1024,
+    };
+    let mut trim = GaplessTrim::new(info, 1024);
+    let mut marks = Vec::new();
+    for _ in 0..5 {
+      let mut packet = Packet::new().unwrap();
+      trim.apply(&mut packet);
+      marks.push(packet.skip_samples());
+    }
+    assert_eq!(
+      marks,
+      [Some((1024, 0)), None, None, Some((0, 901)), Some((0, 1024))]
+    );
+  }
+}
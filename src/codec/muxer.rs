@@ -4,13 +4,19 @@
 
 use super::CodecError;
 use super::avio_context::CustomIOContext;
+use super::demuxer::{StreamColorInfo, StreamSideData};
 use super::io_buffer::StreamingBufferHandle;
+use super::temp_file::TempFile;
 use crate::ffi::accessors::{
-  ffcodecpar_set_bit_rate, ffcodecpar_set_channels, ffcodecpar_set_codec_id,
-  ffcodecpar_set_codec_type, ffcodecpar_set_extradata, ffcodecpar_set_format,
-  ffcodecpar_set_frame_size, ffcodecpar_set_height, ffcodecpar_set_sample_rate,
-  ffcodecpar_set_width, fffmt_get_oformat_flags, fffmt_get_stream, fffmt_set_pb,
-  ffstream_get_codecpar, ffstream_get_index, ffstream_get_time_base, ffstream_set_time_base,
+  ffcodecpar_add_stream_side_data, ffcodecpar_set_bit_rate, ffcodecpar_set_channels,
+  ffcodecpar_set_codec_id, ffcodecpar_set_codec_tag, ffcodecpar_set_codec_type,
+  ffcodecpar_set_color_primaries, ffcodecpar_set_color_range, ffcodecpar_set_color_space,
+  ffcodecpar_set_color_trc, ffcodecpar_set_extradata, ffcodecpar_set_format,
+  ffcodecpar_set_frame_size, ffcodecpar_set_height, ffcodecpar_set_icc_profile,
+  ffcodecpar_set_sample_aspect_ratio, ffcodecpar_set_sample_rate, ffcodecpar_set_width,
+  fffmt_get_oformat_flags, fffmt_get_stream, fffmt_set_pb, ffstream_get_codecpar,
+  ffstream_get_index, ffstream_get_time_base, ffstream_set_disposition, ffstream_set_metadata,
+  ffstream_set_sample_aspect_ratio, ffstream_set_time_base, stream_side_data_kind,
 };
 use crate::ffi::avformat::{
   AVFormatContext, av_interleaved_write_frame, av_write_trailer, avfmt_flag,
@@ -59,6 +65,8 @@ pub enum MuxerOutput {
   Buffer,
   /// Write to streaming buffer with specified capacity
   Streaming(usize),
+  /// Write to a temporary file
+  TempFile(TempFile),
 }
 
 /// Video stream configuration
@@ -78,6 +86,16 @@ pub struct VideoStreamConfig {
   pub bitrate: Option<u64>,
   /// Codec extradata (avcC, hvcC, av1C, etc.)
   pub extradata: Option<Vec<u8>>,
+  /// Codec tag to write, e.g. the MP4 sample entry fourcc (None = container default)
+  pub codec_tag: Option<u32>,
+  /// Color description (MP4 colr/nclx, Matroska Colour)
+  pub color: StreamColorInfo,
+  /// ICC profile (MP4 colr/prof); other containers ignore it
+  pub icc_profile: Option<Vec<u8>>,
+  /// Sample aspect ratio (MP4 pasp/tkhd, Matroska DisplayWidth/DisplayHeight)
+  pub sample_aspect_ratio: Option<(i32, i32)>,
+  /// Stream-level side data to write back (e.g. from a demuxed track)
+  pub side_data: Vec<StreamSideData>,
 }
 
 /// Audio stream configuration
@@ -101,6 +119,19 @@ pub struct AudioStreamConfig {
   pub extradata: Option<Vec<u8>>,
 }
 
+/// Subtitle stream configuration
+#[derive(Debug, Clone)]
+pub struct SubtitleStreamConfig {
+  /// Codec ID (WebVTT)
+  pub codec_id: AVCodecID,
+  /// Time base for timestamps (Matroska uses 1/1000)
+  pub time_base: AVRational,
+  /// Stream disposition flags (e.g. `disposition::CAPTIONS`)
+  pub disposition: c_int,
+  /// BCP 47 / ISO 639-2 language tag
+  pub language: Option<String>,
+}
+
 /// Muxer options
 #[derive(Debug, Clone, Default)]
 pub struct MuxerOptions {
@@ -111,6 +142,12 @@ pub struct MuxerOptions {
   /// Enable live streaming mode for WebM/MKV
   /// When enabled, clusters are output as soon as complete (cluster-at-a-time)
   pub live: bool,
+  /// Write in bitexact mode: no "Lavf" encoder/writing-application tags and
+  /// deterministic Matroska UIDs
+  pub omit_encoder_tags: bool,
+  /// Keep negative timestamps instead of shifting every stream to start at 0
+  /// (used when remuxing raw packets so timestamps stay bit-exact)
+  pub preserve_timestamps: bool,
 }
 
 /// Muxer context wrapper
@@ -125,12 +162,19 @@ pub struct MuxerContext {
   video_stream_index: Option<i32>,
   /// Audio stream index
   audio_stream_index: Option<i32>,
+  /// Subtitle stream index
+  subtitle_stream_index: Option<i32>,
   /// Whether header has been written
   header_written: bool,
   /// Whether trailer has been written (finalized)
   finalized: bool,
   /// Container format
   format: ContainerFormat,
+  /// Write the ICC profile instead of nclx in the MP4 colr box
+  prefer_icc: bool,
+  /// A stream carries stereo 3D or spherical side data, which the MP4 muxer
+  /// only writes (st3d/sv3d) in unofficial compliance mode
+  has_spatial_side_data: bool,
 }
 
 impl MuxerContext {
@@ -171,6 +215,14 @@ impl MuxerContext {
           return Err(CodecError::InvalidConfig(e));
         }
       },
+      MuxerOutput::TempFile(file) => match CustomIOContext::new_file_write(file) {
+        Ok(ctx) => Some(ctx),
+        Err(e) => {
+          // Free the format context before returning error
+          unsafe { avformat_free_context(ctx_ptr) };
+          return Err(CodecError::InvalidConfig(e));
+        }
+      },
     };
 
     let ptr = unsafe { NonNull::new_unchecked(ctx_ptr) };
@@ -187,9 +239,12 @@ impl MuxerContext {
       io_ctx,
       video_stream_index: None,
       audio_stream_index: None,
+      subtitle_stream_index: None,
       header_written: false,
       finalized: false,
       format,
+      prefer_icc: false,
+      has_spatial_side_data: false,
     })
   }
 
@@ -230,6 +285,11 @@ impl MuxerContext {
       // Set pixel format
       ffcodecpar_set_format(codecpar, config.pixel_format as c_int);
 
+      // Set sample entry (e.g. hvc1 vs hev1) if the codec string chose one
+      if let Some(codec_tag) = config.codec_tag {
+        ffcodecpar_set_codec_tag(codecpar, codec_tag);
+      }
+
       // Set bitrate if provided
       if let Some(bitrate) = config.bitrate {
         ffcodecpar_set_bit_rate(codecpar, bitrate as i64);
@@ -243,6 +303,46 @@ impl MuxerContext {
         }
       }
 
+      // Set color description
+      ffcodecpar_set_color_primaries(codecpar, config.color.primaries as c_int);
+      ffcodecpar_set_color_trc(codecpar, config.color.transfer as c_int);
+      ffcodecpar_set_color_space(codecpar, config.color.matrix as c_int);
+      ffcodecpar_set_color_range(codecpar, config.color.range as c_int);
+
+      // Set ICC profile if provided
+      if let Some(ref icc_profile) = config.icc_profile {
+        let ret = ffcodecpar_set_icc_profile(codecpar, icc_profile.as_ptr(), icc_profile.len());
+        if ret < 0 {
+          return Err(CodecError::Ffmpeg(crate::ffi::FFmpegError::from_code(ret)));
+        }
+        self.prefer_icc = true;
+      }
+
+      for side_data in &config.side_data {
+        let ret = ffcodecpar_add_stream_side_data(
+          codecpar,
+          side_data.kind,
+          side_data.data.as_ptr(),
+          side_data.data.len(),
+        );
+        if ret < 0 {
+          return Err(CodecError::Ffmpeg(crate::ffi::FFmpegError::from_code(ret)));
+        }
+        if matches!(
+          side_data.kind,
+          stream_side_data_kind::STEREO_3D | stream_side_data_kind::SPHERICAL
+        ) {
+          self.has_spatial_side_data = true;
+        }
+      }
+
+      // Set sample aspect ratio: the codecpar value feeds MP4 pasp, the stream
+      // value the MP4 tkhd and Matroska display dimensions
+      if let Some((num, den)) = config.sample_aspect_ratio {
+        ffcodecpar_set_sample_aspect_ratio(codecpar, num, den);
+        ffstream_set_sample_aspect_ratio(stream, num, den);
+      }
+
       // Set time base on stream
       ffstream_set_time_base(stream, config.time_base.num, config.time_base.den);
     }
@@ -318,6 +418,59 @@ impl MuxerContext {
     Ok(index)
   }
 
+  /// Add a subtitle stream to the muxer
+  ///
+  /// Must be called before `write_header`.
+  pub fn add_subtitle_stream(&mut self, config: &SubtitleStreamConfig) -> Result<i32, CodecError> {
+    if self.header_written {
+      return Err(CodecError::InvalidState(
+        "Cannot add stream after header is written".to_string(),
+      ));
+    }
+
+    // Validate codec for format
+    self.validate_subtitle_codec(config.codec_id)?;
+
+    // Create new stream
+    let stream = unsafe { avformat_new_stream(self.ptr.as_ptr(), ptr::null()) };
+    if stream.is_null() {
+      return Err(CodecError::AllocationFailed("AVStream"));
+    }
+
+    // Configure codec parameters
+    let codecpar = unsafe { ffstream_get_codecpar(stream) };
+    if codecpar.is_null() {
+      return Err(CodecError::AllocationFailed("AVCodecParameters"));
+    }
+
+    unsafe {
+      ffcodecpar_set_codec_type(codecpar, media_type::SUBTITLE);
+      ffcodecpar_set_codec_id(codecpar, config.codec_id as c_int);
+
+      // The disposition selects the WebM codec ID (D_WEBVTT/SUBTITLES, D_WEBVTT/CAPTIONS, ...)
+      ffstream_set_disposition(stream, config.disposition);
+
+      if let Some(ref language) = config.language {
+        let key = CString::new("language").unwrap();
+        let value = CString::new(language.as_str())
+          .map_err(|_| CodecError::InvalidConfig("Invalid subtitle language".to_string()))?;
+        let ret = ffstream_set_metadata(stream, key.as_ptr(), value.as_ptr());
+        if ret < 0 {
+          return Err(CodecError::Ffmpeg(crate::ffi::FFmpegError::from_code(ret)));
+        }
+      }
+
+      // Set time base on stream
+      ffstream_set_time_base(stream, config.time_base.num, config.time_base.den);
+    }
+
+    // Get stream index
+    let index = unsafe { ffstream_get_index(stream) };
+    self.subtitle_stream_index = Some(index);
+
+    Ok(index)
+  }
+
   /// Write the container header
   ///
   /// Must be called after adding streams and before writing packets.
@@ -328,7 +481,10 @@ impl MuxerContext {
       ));
     }
 
-    if self.video_stream_index.is_none() && self.audio_stream_index.is_none() {
+    if self.video_stream_index.is_none()
+      && self.audio_stream_index.is_none()
+      && self.subtitle_stream_index.is_none()
+    {
       return Err(CodecError::InvalidConfig("No streams added".to_string()));
     }
 
@@ -347,6 +503,12 @@ impl MuxerContext {
         } else {
           "negative_cts_offsets"
         };
+        // MP4 has a single colr box: an explicit ICC profile wins over nclx
+        let movflags = if self.prefer_icc {
+          format!("{}+prefer_icc", movflags)
+        } else {
+          movflags.to_string()
+        };
 
         let key = CString::new("movflags").unwrap();
         let value = CString::new(movflags).unwrap();
@@ -365,6 +527,30 @@ impl MuxerContext {
       }
     }
 
+    if options.is_some_and(|opts| opts.omit_encoder_tags) {
+      let key = CString::new("fflags").unwrap();
+      let value = CString::new("+bitexact").unwrap();
+      unsafe {
+        crate::ffi::avutil::av_dict_set(&mut dict_ptr, key.as_ptr(), value.as_ptr(), 0);
+      }
+    }
+
+    if options.is_some_and(|opts| opts.preserve_timestamps) {
+      let key = CString::new("avoid_negative_ts").unwrap();
+      let value = CString::new("disabled").unwrap();
+      unsafe {
+        crate::ffi::avutil::av_dict_set(&mut dict_ptr, key.as_ptr(), value.as_ptr(), 0);
+      }
+    }
+
+    if self.format == ContainerFormat::Mp4 && self.has_spatial_side_data {
+      let key = CString::new("strict").unwrap();
+      let value = CString::new("unofficial").unwrap();
+      unsafe {
+        crate::ffi::avutil::av_dict_set(&mut dict_ptr, key.as_ptr(), value.as_ptr(), 0);
+      }
+    }
+
     // Write header
     let ret = unsafe { avformat_write_header(self.ptr.as_ptr(), &mut dict_ptr) };
 
@@ -462,6 +648,17 @@ impl MuxerContext {
     self.io_ctx.as_mut().and_then(|io| io.take_buffer_data())
   }
 
+  /// Take the output file (for temp file mode)
+  ///
+  /// Returns None if not finalized or not in temp file mode.
+  pub fn take_temp_file(&mut self) -> Option<TempFile> {
+    if !self.finalized {
+      return None;
+    }
+
+    self.io_ctx.as_mut().and_then(|io| io.take_temp_file())
+  }
+
   /// Get a handle to the streaming buffer (for streaming mode)
   ///
   /// Returns None if not in streaming mode.
@@ -494,10 +691,30 @@ impl MuxerContext {
   /// Get video stream time_base (after header is written)
   /// Returns None if no video stream or header not written yet
   pub fn video_time_base(&self) -> Option<AVRational> {
+    self.stream_time_base(self.video_stream_index?)
+  }
+
+  /// Get audio stream time_base (after header is written)
+  /// Returns None if no audio stream or header not written yet
+  pub fn audio_time_base(&self) -> Option<AVRational> {
+    self.stream_time_base(self.audio_stream_index?)
+  }
+
+  /// Get subtitle stream index
+  pub fn subtitle_stream_index(&self) -> Option<i32> {
+    self.subtitle_stream_index
+  }
+
+  /// Get subtitle stream time_base (after header is written)
+  /// Returns None if no subtitle stream or header not written yet
+  pub fn subtitle_time_base(&self) -> Option<AVRational> {
+    self.stream_time_base(self.subtitle_stream_index?)
+  }
+
+  fn stream_time_base(&self, stream_idx: i32) -> Option<AVRational> {
     if !self.header_written {
       return None;
     }
-    let stream_idx = self.video_stream_index?;
     unsafe {
       let stream = fffmt_get_stream(self.ptr.as_ptr(), stream_idx as u32);
       if stream.is_null() {
@@ -628,6 +845,23 @@ impl MuxerContext {
       )))
     }
   }
+
+  /// Validate subtitle codec for the container format
+  fn validate_subtitle_codec(&self, codec_id: AVCodecID) -> Result<(), CodecError> {
+    let valid = match self.format {
+      ContainerFormat::Mp4 => false,
+      ContainerFormat::WebM | ContainerFormat::Mkv => codec_id == AVCodecID::Webvtt,
+    };
+
+    if valid {
+      Ok(())
+    } else {
+      Err(CodecError::InvalidConfig(format!(
+        "Subtitle codec {:?} is not supported in {:?} container",
+        codec_id, self.format
+      )))
+    }
+  }
 }
 
 impl Drop for MuxerContext {
@@ -0,0 +1,252 @@
+//! Bake VideoFrame rotation and flip into pixel data
+//!
+//! WebCodecs orientation rotates the picture clockwise by `rotation` degrees
+//! and then mirrors it horizontally when `flip` is set. Encoders normally
+//! carry that as metadata; `orient_frame` instead produces an upright copy by
+//! moving every plane element to its oriented position, so no scaler or
+//! colour conversion is involved.
+//!
+//! Planes are walked in square tiles so the strided reads of a 90/270 degree
+//! transpose stay within a few cache lines. Chroma planes of 4:2:2 formats,
+//! whose subsampling is not symmetric, are resampled to the nearest element.
+
+use std::ptr;
+
+use crate::ffi::AVPixelFormat;
+
+use super::{CodecError, Frame};
+
+/// Side of the square tiles planes are copied in
+const TILE: usize = 64;
+
+/// Element size in bytes and log2 horizontal/vertical subsampling of a plane
+type PlaneLayout = (usize, u32, u32);
+
+const LUMA: PlaneLayout = (1, 0, 0);
+const LUMA_16: PlaneLayout = (2, 0, 0);
+
+/// Layout of every plane of `format`, None for formats that cannot be oriented
+fn plane_layouts(format: AVPixelFormat) -> Option<&'static [PlaneLayout]> {
+  use AVPixelFormat as F;
+  Some(match format {
+    F::Yuv420p => &[LUMA, (1, 1, 1), (1, 1, 1)],
+    F::Yuva420p => &[LUMA, (1, 1, 1), (1, 1, 1), LUMA],
+    F::Yuv422p => &[LUMA, (1, 1, 0), (1, 1, 0)],
+    F::Yuva422p => &[LUMA, (1, 1, 0), (1, 1, 0), LUMA],
+    F::Yuv444p => &[LUMA, LUMA, LUMA],
+    F::Yuva444p => &[LUMA, LUMA, LUMA, LUMA],
+    F::Yuv420p10le | F::Yuv420p12le => &[LUMA_16, (2, 1, 1), (2, 1, 1)],
+    F::Yuva420p10le => &[LUMA_16, (2, 1, 1), (2, 1, 1), LUMA_16],
+    F::Yuv422p10le | F::Yuv422p12le => &[LUMA_16, (2, 1, 0), (2, 1, 0)],
+    F::Yuva422p10le => &[LUMA_16, (2, 1, 0), (2, 1, 0), LUMA_16],
+    F::Yuv444p10le | F::Yuv444p12le => &[LUMA_16, LUMA_16, LUMA_16],
+    F::Yuva444p10le => &[LUMA_16, LUMA_16, LUMA_16, LUMA_16],
+    // The interleaved UV pair moves as one element
+    F::Nv12 | F::Nv21 => &[LUMA, (2, 1, 1)],
+    F::Rgb24 | F::Bgr24 => &[(3, 0, 0)],
+    F::Rgba | F::Bgra | F::Argb | F::Abgr => &[(4, 0, 0)],
+    F::Rgba64le => &[(8, 0, 0)],
+    _ => return None,
+  })
+}
+
+/// Clockwise rotation followed by an optional horizontal flip
+#[derive(Debug, Clone, Copy)]
+struct Orientation {
+  rotation: u32,
+  flip: bool,
+  /// Source picture size
+  width: usize,
+  height: usize,
+}
+
+impl Orientation {
+  fn swaps_dimensions(&self) -> bool {
+    self.rotation == 90 || self.rotation == 270
+  }
+
+  fn output_width(&self) -> usize {
+    if self.swaps_dimensions() {
+      self.height
+    } else {
+      self.width
+    }
+  }
+
+  /// Source pixel shown at `(x, y)` of the oriented picture
+  #[inline]
+  fn source(&self, x: usize, y: usize) -> (usize, usize) {
+    let x = if self.flip {
+      self.output_width() - 1 - x
+    } else {
+      x
+    };
+    match self.rotation {
+      90 => (y, self.height - 1 - x),
+      180 => (self.width - 1 - x, self.height - 1 - y),
+      270 => (self.width - 1 - y, x),
+      _ => (x, y),
+    }
+  }
+}
+
+/// Return an upright copy of `src` with `rotation` (0, 90, 180 or 270
+/// degrees clockwise) and `flip` applied to its pixels
+///
+/// The copy has the same pixel format and frame properties; its dimensions
+/// are swapped for 90 and 270 degrees. Without rotation or flip the pixel
+/// data is shared rather than copied.
+pub fn orient_frame(src: &Frame, rotation: u32, flip: bool) -> Result<Frame, CodecError> {
+  if rotation == 0 && !flip {
+    return src.shallow_clone();
+  }
+  if !matches!(rotation, 0 | 90 | 180 | 270) {
+    return Err(CodecError::InvalidConfig(format!(
+      "rotation must be 0, 90, 180 or 270, got {}",
+      rotation
+    )));
+  }
+  let format = src.format();
+  let layouts = plane_layouts(format).ok_or(CodecError::UnsupportedPixelFormat(format))?;
+
+  let orientation = Orientation {
+    rotation,
+    flip,
+    width: src.width() as usize,
+    height: src.height() as usize,
+  };
+  let (out_width, out_height) = if orientation.swaps_dimensions() {
+    (src.height(), src.width())
+  } else {
+    (src.width(), src.height())
+  };
+  let mut dst = Frame::new_video(out_width, out_height, format)?;
+  dst.copy_props_from(src)?;
+
+  for (plane, &(bytes, log2_w, log2_h)) in layouts.iter().enumerate() {
+    let src_data = src.data(plane);
+    if src_data.is_null() {
+      return Err(CodecError::InvalidState(format!(
+        "frame plane {} has no data",
+        plane
+      )));
+    }
+    let plane = PlaneCopy {
+      src: src_data,
+      src_stride: src.linesize(plane) as isize,
+      dst: dst.data_mut(plane),
+      dst_stride: dst.linesize(plane) as isize,
+      width: (out_width as usize).div_ceil(1 << log2_w),
+      height: (out_height as usize).div_ceil(1 << log2_h),
+      log2_w,
+      log2_h,
+    };
+    // SAFETY: both frames hold `format` planes sized for their dimensions, and
+    // `Orientation::source` maps every output position inside the source
+    unsafe {
+      match bytes {
+        1 => plane.copy::<1>(&orientation),
+        2 => plane.copy::<2>(&orientation),
+        3 => plane.copy::<3>(&orientation),
+        4 => plane.copy::<4>(&orientation),
+        _ => plane.copy::<8>(&orientation),
+      }
+    }
+  }
+
+  Ok(dst)
+}
+
+/// One plane to copy into its oriented position
+struct PlaneCopy {
+  src: *const u8,
+  src_stride: isize,
+  dst: *mut u8,
+  dst_stride: isize,
+  /// Output plane size in elements
+  width: usize,
+  height: usize,
+  log2_w: u32,
+  log2_h: u32,
+}
+
+impl PlaneCopy {
+  /// Copy `N`-byte elements tile by tile
+  unsafe fn copy<const N: usize>(&self, orientation: &Orientation) {
+    for tile_y in (0..self.height).step_by(TILE) {
+      for tile_x in (0..self.width).step_by(TILE) {
+        for y in tile_y..(tile_y + TILE).min(self.height) {
+          let row = unsafe { self.dst.offset(y as isize * self.dst_stride) };
+          for x in tile_x..(tile_x + TILE).min(self.width) {
+            // Map through picture coordinates so subsampled planes line up
+            let (u, v) = orientation.source(x << self.log2_w, y << self.log2_h);
+            let offset =
+              (v >> self.log2_h) as isize * self.src_stride + ((u >> self.log2_w) * N) as isize;
+            unsafe { ptr::copy_nonoverlapping(self.src.offset(offset), row.add(x * N), N) };
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Frame whose luma value at (x, y) is `y * width + x`
+  fn numbered_frame(width: u32, height: u32) -> Frame {
+    let mut frame = Frame::new_video(width, height, AVPixelFormat::Yuv420p).unwrap();
+    let linesize = frame.linesize(0) as usize;
+    let data = frame.data_mut(0);
+    for y in 0..height as usize {
+      for x in 0..width as usize {
+        unsafe { *data.add(y * linesize + x) = (y * width as usize + x) as u8 };
+      }
+    }
+    frame
+  }
+
+  fn luma_rows(frame: &Frame) -> Vec<Vec<u8>> {
+    let linesize = frame.linesize(0) as usize;
+    let data = frame.plane_data(0).unwrap();
+    (0..frame.height() as usize)
+      .map(|y| data[y * linesize..y * linesize + frame.width() as usize].to_vec())
+      .collect()
+  }
+
+  #[test]
+  fn rotates_clockwise_and_then_flips() {
+    // 0 1 2
+    // 3 4 5
+    let frame = numbered_frame(3, 2);
+
+    let rotated = orient_frame(&frame, 90, false).unwrap();
+    assert_eq!((rotated.width(), rotated.height()), (2, 3));
+    assert_eq!(
+      luma_rows(&rotated),
+      vec![vec![3, 0], vec![4, 1], vec![5, 2]]
+    );
+
+    let upside_down = orient_frame(&frame, 180, false).unwrap();
+    assert_eq!(luma_rows(&upside_down), vec![vec![5, 4, 3], vec![2, 1, 0]]);
+
+    let rotated_left = orient_frame(&frame, 270, false).unwrap();
+    assert_eq!(
+      luma_rows(&rotated_left),
+      vec![vec![2, 5], vec![1, 4], vec![0, 3]]
+    );
+
+    let mirrored = orient_frame(&frame, 90, true).unwrap();
+    assert_eq!(
+      luma_rows(&mirrored),
+      vec![vec![0, 3], vec![1, 4], vec![2, 5]]
+    );
+  }
+
+  #[test]
+  fn rejects_rotations_off_the_right_angles() {
+    let frame = Frame::new_video(4, 4, AVPixelFormat::Yuv420p).unwrap();
+    assert!(orient_frame(&frame, 45, false).is_err());
+  }
+}
@@ -5,16 +5,19 @@
 use crate::ffi::{
   self, AVPacket,
   accessors::{
-    ffpkt_data, ffpkt_dts, ffpkt_duration, ffpkt_flags, ffpkt_pts, ffpkt_set_dts,
-    ffpkt_set_duration, ffpkt_set_flags, ffpkt_set_pts, ffpkt_set_stream_index, ffpkt_size,
-    ffpkt_stream_index,
+    ffpkt_data, ffpkt_dts, ffpkt_duration, ffpkt_flags, ffpkt_get_hdr10plus_t35, ffpkt_pos,
+    ffpkt_pts, ffpkt_set_dts, ffpkt_set_duration, ffpkt_set_flags, ffpkt_set_hdr10plus_t35,
+    ffpkt_set_pts, ffpkt_set_stream_index, ffpkt_size, ffpkt_stream_index,
   },
   avcodec::{
-    av_new_packet, av_packet_alloc, av_packet_free, av_packet_get_side_data,
-    av_packet_new_side_data, av_packet_ref, av_packet_unref,
+    av_grow_packet, av_new_packet, av_packet_alloc, av_packet_free, av_packet_get_side_data,
+    av_packet_make_writable, av_packet_new_side_data, av_packet_ref, av_packet_unref,
+    av_shrink_packet,
   },
+  avutil::av_free,
   pkt_flag, pkt_side_data_type,
 };
+use std::os::raw::c_int;
 use std::ptr::NonNull;
 
 use super::CodecError;
@@ -87,6 +90,41 @@ impl Packet {
     unsafe { ffpkt_size(self.as_ptr()) }
   }
 
+  /// Make the packet data writable, copying it only if the buffer is shared
+  pub fn make_writable(&mut self) -> Result<(), CodecError> {
+    let ret = unsafe { av_packet_make_writable(self.as_mut_ptr()) };
+    ffi::check_error(ret)?;
+    Ok(())
+  }
+
+  /// Get packet data as a mutable slice
+  ///
+  /// Call `make_writable()` first; the buffer may otherwise be shared with
+  /// other references.
+  pub fn as_mut_slice(&mut self) -> &mut [u8] {
+    let ptr = self.data() as *mut u8;
+    let size = self.size();
+    if ptr.is_null() || size == 0 {
+      &mut []
+    } else {
+      unsafe { std::slice::from_raw_parts_mut(ptr, size as usize) }
+    }
+  }
+
+  /// Grow or shrink the packet data, keeping its leading bytes
+  ///
+  /// Growing reallocates the buffer only when it has no room left.
+  pub fn resize(&mut self, size: usize) -> Result<(), CodecError> {
+    let current = self.size().max(0) as usize;
+    if size > current {
+      let ret = unsafe { av_grow_packet(self.as_mut_ptr(), (size - current) as i32) };
+      ffi::check_error(ret)?;
+    } else if size < current {
+      unsafe { av_shrink_packet(self.as_mut_ptr(), size as i32) };
+    }
+    Ok(())
+  }
+
   /// Get stream index
   #[inline]
   pub fn stream_index(&self) -> i32 {
@@ -145,6 +183,12 @@ impl Packet {
     unsafe { ffpkt_set_duration(self.as_mut_ptr(), duration) }
   }
 
+  /// Get byte position in the source container (-1 if unknown)
+  #[inline]
+  pub fn pos(&self) -> i64 {
+    unsafe { ffpkt_pos(self.as_ptr()) }
+  }
+
   // ========================================================================
   // Flags
   // ========================================================================
@@ -219,6 +263,94 @@ impl Packet {
     Ok(())
   }
 
+  /// Get raw side data of a given type (`pkt_side_data_type`)
+  pub fn side_data(&self, kind: c_int) -> Option<&[u8]> {
+    let mut size: usize = 0;
+    let data = unsafe { av_packet_get_side_data(self.as_ptr(), kind, &mut size) };
+    if data.is_null() || size == 0 {
+      None
+    } else {
+      Some(unsafe { std::slice::from_raw_parts(data, size) })
+    }
+  }
+
+  /// Attach raw side data of a given type (`pkt_side_data_type`)
+  ///
+  /// Used for WebVTT cue identifier and settings lines.
+  pub fn add_side_data(&mut self, kind: c_int, data: &[u8]) -> Result<(), CodecError> {
+    let side_data = unsafe { av_packet_new_side_data(self.as_mut_ptr(), kind, data.len()) };
+    if side_data.is_null() {
+      return Err(CodecError::AllocationFailed("packet side data"));
+    }
+    unsafe {
+      std::slice::from_raw_parts_mut(side_data, data.len()).copy_from_slice(data);
+    }
+    Ok(())
+  }
+
+  /// Get the samples to skip at the start and discard at the end of the decoded packet
+  ///
+  /// Encoders attach this to the last packet when its frame was padded.
+  pub fn skip_samples(&self) -> Option<(u32, u32)> {
+    let mut size: usize = 0;
+    let data = unsafe {
+      av_packet_get_side_data(
+        self.as_ptr(),
+        pkt_side_data_type::AV_PKT_DATA_SKIP_SAMPLES,
+        &mut size,
+      )
+    };
+    if data.is_null() || size < 8 {
+      return None;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(data, 8) };
+    let start = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let end = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    Some((start, end))
+  }
+
+  /// Set the samples to skip at the start and discard at the end of the decoded packet
+  pub fn set_skip_samples(&mut self, start: u32, end: u32) -> Result<(), CodecError> {
+    let side_data = unsafe {
+      av_packet_new_side_data(
+        self.as_mut_ptr(),
+        pkt_side_data_type::AV_PKT_DATA_SKIP_SAMPLES,
+        10,
+      )
+    };
+    if side_data.is_null() {
+      return Err(CodecError::AllocationFailed("packet side data"));
+    }
+    let side_data = unsafe { std::slice::from_raw_parts_mut(side_data, 10) };
+    side_data[..4].copy_from_slice(&start.to_le_bytes());
+    side_data[4..8].copy_from_slice(&end.to_le_bytes());
+    side_data[8..].fill(0);
+    Ok(())
+  }
+
+  /// Get HDR10+ dynamic metadata side data as a complete ITU-T T.35 payload
+  ///
+  /// Demuxers attach this for containers that store HDR10+ outside the
+  /// bitstream (e.g. Matroska BlockAdditional).
+  pub fn hdr10plus_t35(&self) -> Option<Vec<u8>> {
+    let mut data: *mut u8 = std::ptr::null_mut();
+    let mut size: usize = 0;
+    let ret = unsafe { ffpkt_get_hdr10plus_t35(self.as_ptr(), &mut data, &mut size) };
+    if ret < 0 || data.is_null() {
+      return None;
+    }
+    let payload = unsafe { std::slice::from_raw_parts(data, size).to_vec() };
+    unsafe { av_free(data as *mut std::ffi::c_void) };
+    Some(payload)
+  }
+
+  /// Attach an HDR10+ ITU-T T.35 payload as packet side data
+  ///
+  /// Returns false if the payload is not a valid HDR10+ message.
+  pub fn set_hdr10plus_t35(&mut self, payload: &[u8]) -> bool {
+    unsafe { ffpkt_set_hdr10plus_t35(self.as_mut_ptr(), payload.as_ptr(), payload.len()) >= 0 }
+  }
+
   // ========================================================================
   // Lifecycle
   // ========================================================================
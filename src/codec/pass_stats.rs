@@ -0,0 +1,128 @@
+//! Private statistics files for libx264/libx265 two-pass encoding
+//!
+//! libvpx, libaom and rav1e hand their first-pass statistics to FFmpeg through
+//! `stats_out`/`stats_in`. libx264 and libx265 only read and write a stats
+//! file named by the `stats` option, defaulting to a log in the working
+//! directory. Each encoder gets its own file in the OS temp directory so the
+//! statistics can be exchanged as bytes like the other encoders' stats.
+//!
+//! Both encoders write the first pass to `<stats>.temp` and rename it to
+//! `<stats>` when they are closed. Every file is removed when the
+//! `PassStatsFile` is dropped, which happens after the encoder is freed.
+
+use std::fs;
+use std::io;
+use std::os::raw::{c_int, c_void};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+unsafe extern "C" {
+  /// C stdio flush; a null stream flushes every open output stream
+  fn fflush(stream: *mut c_void) -> c_int;
+}
+
+/// Sequence number keeping file names unique within the process
+static NEXT_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Stats file of one libx264/libx265 encoder
+#[derive(Debug)]
+pub struct PassStatsFile {
+  path: PathBuf,
+  pass: u8,
+}
+
+impl PassStatsFile {
+  /// Reserve a path for the statistics a first pass writes
+  pub fn first_pass() -> Self {
+    Self {
+      path: Self::next_path(),
+      pass: 1,
+    }
+  }
+
+  /// Write first-pass statistics for a second pass to read
+  pub fn second_pass(stats: &[u8]) -> io::Result<Self> {
+    let file = Self {
+      path: Self::next_path(),
+      pass: 2,
+    };
+    fs::write(&file.path, stats)?;
+    Ok(file)
+  }
+
+  fn next_path() -> PathBuf {
+    std::env::temp_dir().join(format!(
+      "webcodecs-2pass-{}-{}.log",
+      std::process::id(),
+      NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed)
+    ))
+  }
+
+  /// Path passed to the encoder's `stats` option
+  pub fn path(&self) -> &str {
+    // temp_dir() is not guaranteed to be UTF-8; FFmpeg options are C strings
+    // and both encoders fopen() the bytes as given
+    self.path.to_str().unwrap_or_default()
+  }
+
+  /// Pass the encoder runs (1 or 2)
+  pub fn pass(&self) -> u8 {
+    self.pass
+  }
+
+  /// Statistics written so far by a first pass
+  ///
+  /// The encoders write through buffered stdio, so this flushes every output
+  /// stream before reading the still-open `<stats>.temp` file.
+  pub fn first_pass_stats(&self) -> Option<Vec<u8>> {
+    if self.pass != 1 {
+      return None;
+    }
+    unsafe {
+      fflush(std::ptr::null_mut());
+    }
+    fs::read(self.temp_path())
+      .or_else(|_| fs::read(&self.path))
+      .ok()
+      .filter(|stats| !stats.is_empty())
+  }
+
+  fn temp_path(&self) -> PathBuf {
+    let mut path = self.path.clone().into_os_string();
+    path.push(".temp");
+    path.into()
+  }
+}
+
+impl Drop for PassStatsFile {
+  fn drop(&mut self) {
+    let _ = fs::remove_file(&self.path);
+    let _ = fs::remove_file(self.temp_path());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn second_pass_file_is_removed_on_drop() {
+    let file = PassStatsFile::second_pass(b"#options: 320x240\n").unwrap();
+    let path = PathBuf::from(file.path());
+    assert_eq!(fs::read(&path).unwrap(), b"#options: 320x240\n");
+    assert_eq!(file.first_pass_stats(), None);
+    drop(file);
+    assert!(!path.exists());
+  }
+
+  #[test]
+  fn first_pass_reads_the_temp_file() {
+    let file = PassStatsFile::first_pass();
+    assert_eq!(file.first_pass_stats(), None);
+    let temp = file.temp_path();
+    fs::write(&temp, b"in:0 out:0 type:I\n").unwrap();
+    assert_eq!(file.first_pass_stats().unwrap(), b"in:0 out:0 type:I\n");
+    drop(file);
+    assert!(!temp.exists());
+  }
+}
@@ -0,0 +1,240 @@
+//! Pure-Rust fast paths for 4:2:0 chroma layout conversions
+//!
+//! I420 ↔ NV12 ↔ NV21 at the same dimensions is a pure (de)interleave of the
+//! chroma planes, so there is no need to go through swscale's generic pipeline.
+//! The row loops are written over `chunks_exact` so LLVM can auto-vectorize them.
+//!
+//! Output is byte-identical to swscale's unscaled converters for these formats.
+
+use crate::ffi::AVPixelFormat;
+
+use super::{CodecError, CodecResult, Frame};
+
+/// Chroma plane layout of a supported 4:2:0 format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChromaLayout {
+  /// Separate U and V planes (I420)
+  Planar,
+  /// Interleaved UV plane (NV12)
+  Uv,
+  /// Interleaved VU plane (NV21)
+  Vu,
+}
+
+impl ChromaLayout {
+  fn of(format: AVPixelFormat) -> Option<Self> {
+    match format {
+      AVPixelFormat::Yuv420p => Some(ChromaLayout::Planar),
+      AVPixelFormat::Nv12 => Some(ChromaLayout::Uv),
+      AVPixelFormat::Nv21 => Some(ChromaLayout::Vu),
+      _ => None,
+    }
+  }
+}
+
+/// Check whether a same-size conversion between two formats has a fast path
+pub fn has_fast_path(src_format: AVPixelFormat, dst_format: AVPixelFormat) -> bool {
+  src_format != dst_format
+    && ChromaLayout::of(src_format).is_some()
+    && ChromaLayout::of(dst_format).is_some()
+}
+
+/// Convert `src` into the pre-allocated `dst` frame without swscale
+///
+/// Both frames must have the same dimensions and formats accepted by [`has_fast_path`].
+/// Only pixel data is written; frame properties are left to the caller.
+pub fn convert(src: &Frame, dst: &mut Frame) -> CodecResult<()> {
+  let (Some(src_layout), Some(dst_layout)) = (
+    ChromaLayout::of(src.format()),
+    ChromaLayout::of(dst.format()),
+  ) else {
+    return Err(CodecError::InvalidConfig(format!(
+      "No fast path from {:?} to {:?}",
+      src.format(),
+      dst.format()
+    )));
+  };
+
+  if src.width() != dst.width() || src.height() != dst.height() {
+    return Err(CodecError::InvalidConfig(
+      "Fast path conversion requires matching dimensions".into(),
+    ));
+  }
+
+  let width = src.width() as usize;
+  let height = src.height() as usize;
+  let chroma_width = width.div_ceil(2);
+  let chroma_height = height.div_ceil(2);
+
+  let src_planes = planes(src, src_layout, width, height, chroma_width, chroma_height)?;
+  let dst_planes = planes(dst, dst_layout, width, height, chroma_width, chroma_height)?;
+
+  // Luma plane is identical in all three layouts
+  for row in 0..height {
+    let src_row = unsafe { src_planes[0].row(row, width) };
+    let dst_row = unsafe { dst_planes[0].row_mut(row, width) };
+    dst_row.copy_from_slice(src_row);
+  }
+
+  for row in 0..chroma_height {
+    match (src_layout, dst_layout) {
+      (ChromaLayout::Planar, ChromaLayout::Uv | ChromaLayout::Vu) => {
+        let u = unsafe { src_planes[1].row(row, chroma_width) };
+        let v = unsafe { src_planes[2].row(row, chroma_width) };
+        let out = unsafe { dst_planes[1].row_mut(row, chroma_width * 2) };
+        if dst_layout == ChromaLayout::Uv {
+          interleave(u, v, out);
+        } else {
+          interleave(v, u, out);
+        }
+      }
+      (ChromaLayout::Uv | ChromaLayout::Vu, ChromaLayout::Planar) => {
+        let input = unsafe { src_planes[1].row(row, chroma_width * 2) };
+        let u = unsafe { dst_planes[1].row_mut(row, chroma_width) };
+        let v = unsafe { dst_planes[2].row_mut(row, chroma_width) };
+        if src_layout == ChromaLayout::Uv {
+          deinterleave(input, u, v);
+        } else {
+          deinterleave(input, v, u);
+        }
+      }
+      (ChromaLayout::Uv, ChromaLayout::Vu) | (ChromaLayout::Vu, ChromaLayout::Uv) => {
+        let input = unsafe { src_planes[1].row(row, chroma_width * 2) };
+        let out = unsafe { dst_planes[1].row_mut(row, chroma_width * 2) };
+        swap_pairs(input, out);
+      }
+      _ => {
+        return Err(CodecError::InvalidConfig(
+          "Fast path conversion requires differing formats".into(),
+        ));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Raw view of one plane: base pointer plus stride
+#[derive(Clone, Copy)]
+struct PlaneView {
+  ptr: *mut u8,
+  stride: usize,
+}
+
+impl PlaneView {
+  /// # Safety
+  /// `row` and `len` must lie within the plane validated by [`planes`]
+  unsafe fn row<'a>(self, row: usize, len: usize) -> &'a [u8] {
+    unsafe { std::slice::from_raw_parts(self.ptr.add(row * self.stride), len) }
+  }
+
+  /// # Safety
+  /// `row` and `len` must lie within the plane validated by [`planes`]
+  unsafe fn row_mut<'a>(self, row: usize, len: usize) -> &'a mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(self.ptr.add(row * self.stride), len) }
+  }
+}
+
+/// Collect plane views, rejecting null planes and strides too small for the row width
+/// (negative strides from flipped frames are left to swscale)
+fn planes(
+  frame: &Frame,
+  layout: ChromaLayout,
+  width: usize,
+  height: usize,
+  chroma_width: usize,
+  chroma_height: usize,
+) -> CodecResult<[PlaneView; 3]> {
+  let plane_count = if layout == ChromaLayout::Planar { 3 } else { 2 };
+  let chroma_row_bytes = if layout == ChromaLayout::Planar {
+    chroma_width
+  } else {
+    chroma_width * 2
+  };
+
+  let mut views = [PlaneView {
+    ptr: std::ptr::null_mut(),
+    stride: 0,
+  }; 3];
+
+  for (plane, view) in views.iter_mut().enumerate().take(plane_count) {
+    let ptr = frame.data(plane) as *mut u8;
+    let linesize = frame.linesize(plane);
+    let (row_bytes, rows) = if plane == 0 {
+      (width, height)
+    } else {
+      (chroma_row_bytes, chroma_height)
+    };
+    if ptr.is_null() || linesize <= 0 || (linesize as usize) < row_bytes || rows == 0 {
+      return Err(CodecError::InvalidState(format!(
+        "Plane {} is not suitable for fast path conversion",
+        plane
+      )));
+    }
+    *view = PlaneView {
+      ptr,
+      stride: linesize as usize,
+    };
+  }
+
+  Ok(views)
+}
+
+/// Interleave two planar chroma rows into one semi-planar row
+#[inline]
+fn interleave(first: &[u8], second: &[u8], out: &mut [u8]) {
+  for ((pair, &a), &b) in out.chunks_exact_mut(2).zip(first).zip(second) {
+    pair[0] = a;
+    pair[1] = b;
+  }
+}
+
+/// Split one semi-planar chroma row into two planar rows
+#[inline]
+fn deinterleave(input: &[u8], first: &mut [u8], second: &mut [u8]) {
+  for ((pair, a), b) in input
+    .chunks_exact(2)
+    .zip(first.iter_mut())
+    .zip(second.iter_mut())
+  {
+    *a = pair[0];
+    *b = pair[1];
+  }
+}
+
+/// Swap byte order within each chroma pair (NV12 ↔ NV21)
+#[inline]
+fn swap_pairs(input: &[u8], out: &mut [u8]) {
+  for (src, dst) in input.chunks_exact(2).zip(out.chunks_exact_mut(2)) {
+    dst[0] = src[1];
+    dst[1] = src[0];
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_has_fast_path() {
+    assert!(has_fast_path(AVPixelFormat::Yuv420p, AVPixelFormat::Nv12));
+    assert!(has_fast_path(AVPixelFormat::Nv12, AVPixelFormat::Nv21));
+    assert!(!has_fast_path(AVPixelFormat::Nv12, AVPixelFormat::Nv12));
+    assert!(!has_fast_path(AVPixelFormat::Rgba, AVPixelFormat::Nv12));
+  }
+
+  #[test]
+  fn test_row_helpers() {
+    let mut out = [0u8; 6];
+    interleave(&[1, 2, 3], &[4, 5, 6], &mut out);
+    assert_eq!(out, [1, 4, 2, 5, 3, 6]);
+
+    let (mut u, mut v) = ([0u8; 3], [0u8; 3]);
+    deinterleave(&out, &mut u, &mut v);
+    assert_eq!((u, v), ([1, 2, 3], [4, 5, 6]));
+
+    let mut swapped = [0u8; 6];
+    swap_pairs(&out, &mut swapped);
+    assert_eq!(swapped, [4, 1, 5, 2, 6, 3]);
+  }
+}
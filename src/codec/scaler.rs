@@ -4,11 +4,13 @@
 
 use crate::ffi::{
   AVPixelFormat, SwsContext,
-  swscale::{sws_freeContext, sws_getContext, sws_scale},
+  avutil::av_opt_set_int,
+  swscale::{sws_alloc_context, sws_freeContext, sws_getContext, sws_init_context, sws_scale},
 };
+use std::ffi::CStr;
 use std::ptr::NonNull;
 
-use super::{CodecError, CodecResult, Frame};
+use super::{CodecError, CodecResult, Frame, pixel_convert};
 
 /// Scaling algorithm
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -39,6 +41,88 @@ impl ScaleAlgorithm {
   }
 }
 
+/// Dithering applied when a >8-bit source is reduced to 8-bit RGBA/BGRA
+///
+/// swscale truncates to packed 32-bit RGB without dithering, so dithered
+/// conversions go through a 16-bit RGBA intermediate and are quantized here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DitherMode {
+  /// Round to nearest (default)
+  #[default]
+  None,
+  /// 8x8 ordered (Bayer) dither
+  Bayer,
+  /// Floyd-Steinberg error diffusion
+  ErrorDiffusion,
+}
+
+/// Chroma sample siting of the source, overriding swscale's per-format default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChromaLocation {
+  /// Co-sited horizontally, centered vertically (MPEG-2/H.264 default)
+  Left,
+  /// Centered in both directions (MPEG-1/JPEG)
+  Center,
+  /// Co-sited with the top-left luma sample (BT.2020 4:2:0)
+  TopLeft,
+  /// Centered horizontally, co-sited with the top row
+  Top,
+  /// Co-sited with the bottom-left luma sample
+  BottomLeft,
+  /// Centered horizontally, co-sited with the bottom row
+  Bottom,
+}
+
+impl ChromaLocation {
+  /// Horizontal and vertical position in 1/256 units (as av_chroma_location_enum_to_pos)
+  fn sws_position(self) -> (i64, i64) {
+    match self {
+      ChromaLocation::Left => (0, 128),
+      ChromaLocation::Center => (128, 128),
+      ChromaLocation::TopLeft => (0, 0),
+      ChromaLocation::Top => (128, 0),
+      ChromaLocation::BottomLeft => (0, 256),
+      ChromaLocation::Bottom => (128, 256),
+    }
+  }
+}
+
+/// Conversion quality settings beyond the scaling algorithm
+///
+/// The default matches `Scaler::new` with `ScaleAlgorithm::Bilinear`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScaleOptions {
+  pub algorithm: ScaleAlgorithm,
+  pub dither: DitherMode,
+  /// Source chroma siting (None keeps swscale's default for the format)
+  pub chroma_location: Option<ChromaLocation>,
+  /// SWS_ACCURATE_RND: exact rounding in the YUV->RGB path
+  pub accurate_rounding: bool,
+  /// SWS_FULL_CHR_H_INT | SWS_FULL_CHR_H_INP: full-resolution chroma interpolation
+  pub full_chroma_interpolation: bool,
+}
+
+impl ScaleOptions {
+  fn to_sws_flags(self) -> i32 {
+    use crate::ffi::swscale::*;
+    let mut flags = self.algorithm.to_sws_flags();
+    if self.accurate_rounding {
+      flags |= SWS_ACCURATE_RND;
+    }
+    if self.full_chroma_interpolation {
+      flags |= SWS_FULL_CHR_H_INT | SWS_FULL_CHR_H_INP;
+    }
+    flags
+  }
+
+  /// Whether this conversion needs the 16-bit intermediate and Rust-side quantization
+  fn needs_dither(self, src_format: AVPixelFormat, dst_format: AVPixelFormat) -> bool {
+    self.dither != DitherMode::None
+      && src_format.is_high_bit_depth()
+      && matches!(dst_format, AVPixelFormat::Rgba | AVPixelFormat::Bgra)
+  }
+}
+
 /// Safe wrapper around SwsContext for pixel format conversion and scaling
 pub struct Scaler {
   ptr: NonNull<SwsContext>,
@@ -48,6 +132,10 @@ pub struct Scaler {
   dst_width: u32,
   dst_height: u32,
   dst_format: AVPixelFormat,
+  /// Same-size conversion handled by the pure-Rust fast path instead of swscale
+  fast_path: bool,
+  /// When not None, the context outputs RGBA64 and `scale` quantizes to `dst_format`
+  dither: DitherMode,
 }
 
 impl Scaler {
@@ -61,18 +149,46 @@ impl Scaler {
     dst_format: AVPixelFormat,
     algorithm: ScaleAlgorithm,
   ) -> CodecResult<Self> {
+    Self::with_options(
+      src_width,
+      src_height,
+      src_format,
+      dst_width,
+      dst_height,
+      dst_format,
+      ScaleOptions {
+        algorithm,
+        ..Default::default()
+      },
+    )
+  }
+
+  /// Create a new scaler with explicit conversion quality settings
+  pub fn with_options(
+    src_width: u32,
+    src_height: u32,
+    src_format: AVPixelFormat,
+    dst_width: u32,
+    dst_height: u32,
+    dst_format: AVPixelFormat,
+    options: ScaleOptions,
+  ) -> CodecResult<Self> {
+    let dither = if options.needs_dither(src_format, dst_format) {
+      options.dither
+    } else {
+      DitherMode::None
+    };
+    let sws_dst_format = if dither == DitherMode::None {
+      dst_format
+    } else {
+      AVPixelFormat::Rgba64le
+    };
+
     let ptr = unsafe {
-      sws_getContext(
-        src_width as i32,
-        src_height as i32,
-        src_format.as_raw(),
-        dst_width as i32,
-        dst_height as i32,
-        dst_format.as_raw(),
-        algorithm.to_sws_flags(),
-        std::ptr::null_mut(),
-        std::ptr::null_mut(),
-        std::ptr::null(),
+      create_context(
+        [src_width as i32, src_height as i32, src_format.as_raw()],
+        [dst_width as i32, dst_height as i32, sws_dst_format.as_raw()],
+        options,
       )
     };
 
@@ -85,6 +201,12 @@ impl Scaler {
         dst_width,
         dst_height,
         dst_format,
+        fast_path: dither == DitherMode::None
+          && options.chroma_location.is_none()
+          && src_width == dst_width
+          && src_height == dst_height
+          && pixel_convert::has_fast_path(src_format, dst_format),
+        dither,
       })
       .ok_or(CodecError::InvalidConfig(format!(
         "Cannot create scaler from {:?} {}x{} to {:?} {}x{}",
@@ -125,14 +247,28 @@ impl Scaler {
       ));
     }
 
-    // Prepare source data pointers and strides
-    let src_data: [*const u8; 4] = [src.data(0), src.data(1), src.data(2), src.data(3)];
-    let src_linesize: [i32; 4] = [
-      src.linesize(0),
-      src.linesize(1),
-      src.linesize(2),
-      src.linesize(3),
-    ];
+    // I420/NV12/NV21 at the same size is a pure chroma (de)interleave.
+    // Fall back to swscale if the frame layout isn't suitable (e.g. negative strides).
+    if !(self.fast_path && pixel_convert::convert(src, dst).is_ok()) {
+      self.scale_swscale(src, dst)?;
+    }
+
+    // Copy metadata from source
+    dst.set_pts(src.pts());
+    dst.set_duration(src.duration());
+    dst.set_color_primaries(src.color_primaries());
+    dst.set_color_trc(src.color_trc());
+    dst.set_colorspace(src.colorspace());
+    dst.set_color_range(src.color_range());
+
+    Ok(())
+  }
+
+  /// Convert pixel data through swscale
+  fn scale_swscale(&self, src: &Frame, dst: &mut Frame) -> CodecResult<()> {
+    if self.dither != DitherMode::None {
+      return self.scale_dithered(src, dst);
+    }
 
     // Prepare destination data pointers and strides
     let dst_data: [*mut u8; 4] = [
@@ -148,6 +284,63 @@ impl Scaler {
       dst.linesize(3),
     ];
 
+    self.run_swscale(src, dst_data, dst_linesize)
+  }
+
+  /// Convert to the 16-bit RGBA intermediate, then dither down to 8-bit RGBA/BGRA
+  fn scale_dithered(&self, src: &Frame, dst: &mut Frame) -> CodecResult<()> {
+    let width = self.dst_width as usize;
+    let height = self.dst_height as usize;
+    let dst_stride = dst.linesize(0);
+    if dst_stride < 0 || (dst_stride as usize) < width * 4 {
+      return Err(CodecError::InvalidConfig(
+        "Dithered conversion requires a positive destination stride".into(),
+      ));
+    }
+
+    let mut wide = vec![0u16; width * 4 * height];
+    let wide_data: [*mut u8; 4] = [
+      wide.as_mut_ptr().cast(),
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+    ];
+    self.run_swscale(src, wide_data, [(width * 8) as i32, 0, 0, 0])?;
+
+    let dst_stride = dst_stride as usize;
+    let dst_len = if height == 0 {
+      0
+    } else {
+      dst_stride * (height - 1) + width * 4
+    };
+    // SAFETY: plane 0 of a packed RGBA frame holds `height` rows of `dst_stride` bytes
+    let dst_bytes = unsafe { std::slice::from_raw_parts_mut(dst.data_mut(0), dst_len) };
+    quantize_rgba64(
+      &wide,
+      width,
+      height,
+      dst_bytes,
+      dst_stride,
+      self.dst_format == AVPixelFormat::Bgra,
+      self.dither,
+    );
+    Ok(())
+  }
+
+  fn run_swscale(
+    &self,
+    src: &Frame,
+    dst_data: [*mut u8; 4],
+    dst_linesize: [i32; 4],
+  ) -> CodecResult<()> {
+    let src_data: [*const u8; 4] = [src.data(0), src.data(1), src.data(2), src.data(3)];
+    let src_linesize: [i32; 4] = [
+      src.linesize(0),
+      src.linesize(1),
+      src.linesize(2),
+      src.linesize(3),
+    ];
+
     let result = unsafe {
       sws_scale(
         self.ptr.as_ptr(),
@@ -167,14 +360,6 @@ impl Scaler {
       )));
     }
 
-    // Copy metadata from source
-    dst.set_pts(src.pts());
-    dst.set_duration(src.duration());
-    dst.set_color_primaries(src.color_primaries());
-    dst.set_color_trc(src.color_trc());
-    dst.set_colorspace(src.colorspace());
-    dst.set_color_range(src.color_range());
-
     Ok(())
   }
 
@@ -225,6 +410,124 @@ impl Scaler {
   }
 }
 
+/// Create an SwsContext for `[width, height, format]` source and destination
+///
+/// Chroma siting can only be set through AVOptions, so that case allocates the
+/// context and initializes it explicitly; everything else uses sws_getContext.
+unsafe fn create_context(src: [i32; 3], dst: [i32; 3], options: ScaleOptions) -> *mut SwsContext {
+  let flags = options.to_sws_flags();
+  let Some(chroma_location) = options.chroma_location else {
+    return unsafe {
+      sws_getContext(
+        src[0],
+        src[1],
+        src[2],
+        dst[0],
+        dst[1],
+        dst[2],
+        flags,
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+        std::ptr::null(),
+      )
+    };
+  };
+
+  let ctx = unsafe { sws_alloc_context() };
+  if ctx.is_null() {
+    return ctx;
+  }
+  let (h_chr_pos, v_chr_pos) = chroma_location.sws_position();
+  let settings: [(&CStr, i64); 9] = [
+    (c"srcw", i64::from(src[0])),
+    (c"srch", i64::from(src[1])),
+    (c"src_format", i64::from(src[2])),
+    (c"dstw", i64::from(dst[0])),
+    (c"dsth", i64::from(dst[1])),
+    (c"dst_format", i64::from(dst[2])),
+    (c"sws_flags", i64::from(flags)),
+    (c"src_h_chr_pos", h_chr_pos),
+    (c"src_v_chr_pos", v_chr_pos),
+  ];
+  for (name, value) in settings {
+    if unsafe { av_opt_set_int(ctx.cast(), name.as_ptr(), value, 0) } < 0 {
+      unsafe { sws_freeContext(ctx) };
+      return std::ptr::null_mut();
+    }
+  }
+  if unsafe { sws_init_context(ctx, std::ptr::null_mut(), std::ptr::null_mut()) } < 0 {
+    unsafe { sws_freeContext(ctx) };
+    return std::ptr::null_mut();
+  }
+  ctx
+}
+
+/// 8x8 ordered dither thresholds (0..64)
+const BAYER_8X8: [[u8; 8]; 8] = [
+  [0, 32, 8, 40, 2, 34, 10, 42],
+  [48, 16, 56, 24, 50, 18, 58, 26],
+  [12, 44, 4, 36, 14, 46, 6, 38],
+  [60, 28, 52, 20, 62, 30, 54, 22],
+  [3, 35, 11, 43, 1, 33, 9, 41],
+  [51, 19, 59, 27, 49, 17, 57, 25],
+  [15, 47, 7, 39, 13, 45, 5, 37],
+  [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Reduce tightly packed 16-bit RGBA to 8-bit RGBA (or BGRA when `swap_rb`)
+///
+/// Alpha is always rounded; only the color channels are dithered.
+fn quantize_rgba64(
+  src: &[u16],
+  width: usize,
+  height: usize,
+  dst: &mut [u8],
+  dst_stride: usize,
+  swap_rb: bool,
+  mode: DitherMode,
+) {
+  let order: [usize; 4] = if swap_rb { [2, 1, 0, 3] } else { [0, 1, 2, 3] };
+  let round = |value: u16| ((u32::from(value) + 128) / 257) as u8;
+
+  // Floyd-Steinberg error rows (one pixel of padding on each side)
+  let mut errors = vec![[0f32; 3]; width + 2];
+  let mut next_errors = vec![[0f32; 3]; width + 2];
+
+  for y in 0..height {
+    let src_row = &src[y * width * 4..(y + 1) * width * 4];
+    let dst_row = &mut dst[y * dst_stride..y * dst_stride + width * 4];
+    for x in 0..width {
+      let pixel = &src_row[x * 4..x * 4 + 4];
+      for channel in 0..3 {
+        let level = f32::from(pixel[channel]) / 257.0;
+        let out = match mode {
+          DitherMode::None => round(pixel[channel]),
+          DitherMode::Bayer => {
+            let threshold = (f32::from(BAYER_8X8[y % 8][x % 8]) + 0.5) / 64.0;
+            (level + threshold).floor().clamp(0.0, 255.0) as u8
+          }
+          DitherMode::ErrorDiffusion => {
+            let wanted = level + errors[x + 1][channel];
+            let out = wanted.round().clamp(0.0, 255.0);
+            let error = wanted - out;
+            errors[x + 2][channel] += error * 7.0 / 16.0;
+            next_errors[x][channel] += error * 3.0 / 16.0;
+            next_errors[x + 1][channel] += error * 5.0 / 16.0;
+            next_errors[x + 2][channel] += error / 16.0;
+            out as u8
+          }
+        };
+        dst_row[x * 4 + order[channel]] = out;
+      }
+      dst_row[x * 4 + 3] = round(pixel[3]);
+    }
+    if mode == DitherMode::ErrorDiffusion {
+      std::mem::swap(&mut errors, &mut next_errors);
+      next_errors.fill([0.0; 3]);
+    }
+  }
+}
+
 impl Drop for Scaler {
   fn drop(&mut self) {
     unsafe { sws_freeContext(self.ptr.as_ptr()) }
@@ -279,4 +582,129 @@ mod tests {
     assert!(converter.is_ok());
     assert!(converter.unwrap().is_converter_only());
   }
+
+  /// Fill every plane of a frame with a position-dependent pattern
+  fn patterned_frame(width: u32, height: u32, format: AVPixelFormat) -> Frame {
+    let mut frame = Frame::new_video(width, height, format).unwrap();
+    for plane in 0..format.num_planes() {
+      let linesize = frame.linesize(plane) as usize;
+      let rows = if plane == 0 {
+        height as usize
+      } else {
+        (height as usize).div_ceil(2)
+      };
+      let data = frame.data_mut(plane);
+      for row in 0..rows {
+        for col in 0..linesize {
+          unsafe { *data.add(row * linesize + col) = (row * 7 + col * 13 + plane * 31) as u8 };
+        }
+      }
+    }
+    frame
+  }
+
+  #[test]
+  fn test_fast_path_matches_swscale() {
+    let pairs = [
+      (AVPixelFormat::Yuv420p, AVPixelFormat::Nv12),
+      (AVPixelFormat::Nv12, AVPixelFormat::Yuv420p),
+      (AVPixelFormat::Nv12, AVPixelFormat::Nv21),
+      (AVPixelFormat::Nv21, AVPixelFormat::Nv12),
+      (AVPixelFormat::Yuv420p, AVPixelFormat::Nv21),
+      (AVPixelFormat::Nv21, AVPixelFormat::Yuv420p),
+    ];
+    // Include odd dimensions to cover the rounded-up chroma size
+    for (width, height) in [(64, 48), (33, 17)] {
+      for (src_format, dst_format) in pairs {
+        let src = patterned_frame(width, height, src_format);
+        let scaler = Scaler::new_converter(width, height, src_format, dst_format).unwrap();
+        assert!(scaler.fast_path);
+
+        let fast = scaler.scale_alloc(&src).unwrap();
+        let mut reference = Frame::new_video(width, height, dst_format).unwrap();
+        scaler.scale_swscale(&src, &mut reference).unwrap();
+
+        let size = (width * height) as usize * 2;
+        let mut fast_bytes = vec![0u8; size];
+        let mut reference_bytes = vec![0u8; size];
+        fast.copy_to_buffer(&mut fast_bytes).unwrap();
+        reference.copy_to_buffer(&mut reference_bytes).unwrap();
+        assert_eq!(
+          fast_bytes, reference_bytes,
+          "{:?} -> {:?} at {}x{}",
+          src_format, dst_format, width, height
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_dither_preserves_mean_level() {
+    // A flat 16-bit level halfway between two 8-bit codes
+    let (width, height) = (64, 16);
+    let level = 100 * 257 + 128;
+    let src = vec![level; width * height * 4];
+    for mode in [DitherMode::Bayer, DitherMode::ErrorDiffusion] {
+      let mut dst = vec![0u8; width * height * 4];
+      quantize_rgba64(&src, width, height, &mut dst, width * 4, false, mode);
+      let red: Vec<u8> = dst.chunks(4).map(|pixel| pixel[0]).collect();
+      assert!(
+        red.iter().all(|&value| value == 100 || value == 101),
+        "{:?}",
+        mode
+      );
+      let mean = red.iter().map(|&value| f64::from(value)).sum::<f64>() / red.len() as f64;
+      assert!((mean - 100.5).abs() < 0.05, "{:?} mean {}", mode, mean);
+      // Alpha is never dithered
+      assert!(
+        dst
+          .chunks(4)
+          .all(|pixel| pixel[3] == 100 || pixel[3] == 101)
+      );
+    }
+  }
+
+  #[test]
+  fn test_scaling_does_not_use_fast_path() {
+    let scaler = Scaler::new(
+      1920,
+      1080,
+      AVPixelFormat::Yuv420p,
+      1280,
+      720,
+      AVPixelFormat::Nv12,
+      ScaleAlgorithm::Bilinear,
+    )
+    .unwrap();
+    assert!(!scaler.fast_path);
+  }
+
+  /// Run with `cargo test --release -- --ignored bench_i420_to_nv12 --nocapture`
+  #[test]
+  #[ignore]
+  fn bench_i420_to_nv12() {
+    let src = patterned_frame(3840, 2160, AVPixelFormat::Yuv420p);
+    let scaler =
+      Scaler::new_converter(3840, 2160, AVPixelFormat::Yuv420p, AVPixelFormat::Nv12).unwrap();
+    let mut dst = Frame::new_video(3840, 2160, AVPixelFormat::Nv12).unwrap();
+    let iterations = 50;
+
+    let start = std::time::Instant::now();
+    for _ in 0..iterations {
+      scaler.scale_swscale(&src, &mut dst).unwrap();
+    }
+    let swscale = start.elapsed() / iterations;
+
+    let start = std::time::Instant::now();
+    for _ in 0..iterations {
+      pixel_convert::convert(&src, &mut dst).unwrap();
+    }
+    let fast = start.elapsed() / iterations;
+
+    println!(
+      "4K I420->NV12: swscale {:?}/frame, fast path {:?}/frame",
+      swscale, fast
+    );
+    assert!(fast < swscale);
+  }
 }
@@ -0,0 +1,257 @@
+//! Temporary files for muxer output that is rewritten before it is returned
+//!
+//! Post-processing passes such as MP4 faststart need the complete output
+//! before they can produce the final file. Instead of growing an in-memory
+//! buffer for the whole recording, the muxer writes into a temporary file in
+//! a per-muxer directory and reads it back once at finalize.
+//!
+//! Every file is removed when it is dropped, and a store can cap the bytes
+//! its files hold together. A write past the cap fails, marks the store as
+//! exceeded and deletes the file right away rather than waiting for the
+//! muxer to be dropped.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Sequence number keeping file names unique within the process
+static NEXT_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug)]
+struct StoreState {
+  dir: PathBuf,
+  max_bytes: Option<u64>,
+  used_bytes: AtomicU64,
+  quota_exceeded: AtomicBool,
+}
+
+/// Directory and size cap for the temporary files of one muxer
+#[derive(Debug, Clone)]
+pub struct TempFileStore {
+  state: Arc<StoreState>,
+}
+
+impl TempFileStore {
+  /// Create a store in `dir` (default: the OS temp directory)
+  ///
+  /// `max_bytes` caps the combined size of the store's files.
+  pub fn new(dir: Option<PathBuf>, max_bytes: Option<u64>) -> Self {
+    Self {
+      state: Arc::new(StoreState {
+        dir: dir.unwrap_or_else(std::env::temp_dir),
+        max_bytes,
+        used_bytes: AtomicU64::new(0),
+        quota_exceeded: AtomicBool::new(false),
+      }),
+    }
+  }
+
+  /// Directory the store creates its files in
+  pub fn dir(&self) -> &Path {
+    &self.state.dir
+  }
+
+  /// Create an empty file in the store's directory
+  pub fn create(&self) -> io::Result<TempFile> {
+    let path = self.state.dir.join(format!(
+      "webcodecs-{}-{}.tmp",
+      std::process::id(),
+      NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    let file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .create_new(true)
+      .open(&path)?;
+    Ok(TempFile {
+      file: Some(file),
+      path,
+      position: 0,
+      len: 0,
+      store: self.clone(),
+    })
+  }
+
+  /// Cap on the combined size of the store's files
+  pub fn max_bytes(&self) -> Option<u64> {
+    self.state.max_bytes
+  }
+
+  /// Bytes currently held by the store's files
+  pub fn used_bytes(&self) -> u64 {
+    self.state.used_bytes.load(Ordering::Relaxed)
+  }
+
+  /// Whether a write was refused because it would exceed `max_bytes`
+  pub fn quota_exceeded(&self) -> bool {
+    self.state.quota_exceeded.load(Ordering::Relaxed)
+  }
+
+  /// Account for `bytes` more, failing if that exceeds the cap
+  fn reserve(&self, bytes: u64) -> bool {
+    let reserved = self
+      .state
+      .used_bytes
+      .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+        let total = used.checked_add(bytes)?;
+        match self.state.max_bytes {
+          Some(max) if total > max => None,
+          _ => Some(total),
+        }
+      })
+      .is_ok();
+    if !reserved {
+      self.state.quota_exceeded.store(true, Ordering::Relaxed);
+    }
+    reserved
+  }
+
+  fn release(&self, bytes: u64) {
+    self.state.used_bytes.fetch_sub(bytes, Ordering::Relaxed);
+  }
+}
+
+/// Read/write temporary file, deleted on drop
+#[derive(Debug)]
+pub struct TempFile {
+  /// None once the file has been discarded
+  file: Option<File>,
+  path: PathBuf,
+  position: u64,
+  /// Bytes accounted to the store
+  len: u64,
+  store: TempFileStore,
+}
+
+impl TempFile {
+  /// Location of the file
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Current size in bytes
+  pub fn len(&self) -> u64 {
+    self.len
+  }
+
+  /// Whether nothing has been written
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Close and delete the file, releasing its bytes from the store
+  fn discard(&mut self) {
+    if let Some(file) = self.file.take() {
+      drop(file);
+      let _ = fs::remove_file(&self.path);
+      self.store.release(self.len);
+      self.len = 0;
+    }
+  }
+
+  fn file(&mut self) -> io::Result<&mut File> {
+    self
+      .file
+      .as_mut()
+      .ok_or_else(|| io::Error::other("temporary file was discarded"))
+  }
+}
+
+impl Write for TempFile {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let end = self.position + buf.len() as u64;
+    if end > self.len {
+      if !self.store.reserve(end - self.len) {
+        self.discard();
+        return Err(io::Error::other(format!(
+          "temporary file quota of {} bytes exceeded",
+          self.store.max_bytes().unwrap_or_default()
+        )));
+      }
+      self.len = end;
+    }
+    self.file()?.write_all(buf)?;
+    self.position = end;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.file()?.flush()
+  }
+}
+
+impl Read for TempFile {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let n = self.file()?.read(buf)?;
+    self.position += n as u64;
+    Ok(n)
+  }
+}
+
+impl Seek for TempFile {
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    self.position = self.file()?.seek(pos)?;
+    Ok(self.position)
+  }
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    self.discard();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+      "webcodecs-temp-test-{}-{}",
+      name,
+      std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn files_are_removed_on_drop() {
+    let dir = test_dir("drop");
+    let store = TempFileStore::new(Some(dir.clone()), None);
+    let mut file = store.create().unwrap();
+    file.write_all(b"hello").unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut read = String::new();
+    file.read_to_string(&mut read).unwrap();
+    assert_eq!(read, "hello");
+    assert!(file.path().starts_with(&dir));
+    assert_eq!(store.used_bytes(), 5);
+
+    drop(file);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    assert_eq!(store.used_bytes(), 0);
+    fs::remove_dir(&dir).unwrap();
+  }
+
+  #[test]
+  fn exceeding_the_quota_discards_the_file() {
+    let dir = test_dir("quota");
+    let store = TempFileStore::new(Some(dir.clone()), Some(8));
+    let mut file = store.create().unwrap();
+    file.write_all(b"12345678").unwrap();
+    // Overwriting in place does not grow the file
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.write_all(b"abcd").unwrap();
+    assert!(!store.quota_exceeded());
+
+    file.seek(SeekFrom::End(0)).unwrap();
+    assert!(file.write_all(b"9").is_err());
+    assert!(store.quota_exceeded());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    assert_eq!(store.used_bytes(), 0);
+    fs::remove_dir(&dir).unwrap();
+  }
+}
@@ -7,11 +7,15 @@
 
 #include <libavcodec/avcodec.h>
 #include <libavutil/frame.h>
+#include <libavutil/hdr_dynamic_metadata.h>
 #include <libavutil/hwcontext.h>
 #include <libavutil/imgutils.h>
+#include <libavutil/mastering_display_metadata.h>
 #include <libavutil/opt.h>
 #include <libavutil/channel_layout.h>
 #include <libavutil/samplefmt.h>
+#include <libavutil/spherical.h>
+#include <libavutil/stereo3d.h>
 
 /* ============================================================================
  * AVCodecContext Setters
@@ -124,6 +128,14 @@ void ffctx_set_flags(AVCodecContext* ctx, int flags) {
     ctx->flags = flags;
 }
 
+/**
+ * Set two-pass statistics input. The string is owned by the caller and must
+ * outlive the codec context (FFmpeg does not free stats_in).
+ */
+void ffctx_set_stats_in(AVCodecContext* ctx, char* stats_in) {
+    ctx->stats_in = stats_in;
+}
+
 void ffctx_set_flags2(AVCodecContext* ctx, int flags2) {
     ctx->flags2 = flags2;
 }
@@ -216,6 +228,10 @@ int ffctx_get_extradata_size(const AVCodecContext* ctx) {
     return ctx->extradata_size;
 }
 
+const char* ffctx_get_stats_out(const AVCodecContext* ctx) {
+    return ctx->stats_out;
+}
+
 /**
  * Set extradata on a codec context.
  * This allocates new memory with av_malloc and copies the data.
@@ -325,6 +341,10 @@ void ffframe_set_quality(AVFrame* frame, int quality) {
     frame->quality = quality;
 }
 
+int ffframe_set_metadata(AVFrame* frame, const char* key, const char* value) {
+    return av_dict_set(&frame->metadata, key, value, 0);
+}
+
 /* ============================================================================
  * AVFrame Getters
  * ============================================================================ */
@@ -432,6 +452,48 @@ void ffframe_set_linesize(AVFrame* frame, int plane, int linesize) {
     }
 }
 
+/**
+ * Crop a frame to the given window in place by offsetting its data pointers (no pixel copy).
+ *
+ * Returns 0 on success or a negative AVERROR if the window is outside the frame.
+ */
+int ffframe_crop(AVFrame* frame, int left, int top, int width, int height) {
+    if (left < 0 || top < 0 || width <= 0 || height <= 0 ||
+        left + width > frame->width || top + height > frame->height) {
+        return AVERROR(EINVAL);
+    }
+
+    frame->crop_left = left;
+    frame->crop_top = top;
+    frame->crop_right = frame->width - left - width;
+    frame->crop_bottom = frame->height - top - height;
+    return av_frame_apply_cropping(frame, AV_FRAME_CROP_UNALIGNED);
+}
+
+/**
+ * Switch a planar YUVA frame to its non-alpha format and release the alpha plane.
+ *
+ * The alpha buffer reference is only dropped when it backs plane 3 alone
+ * (frames allocated as a single buffer keep it, only data[3] is cleared).
+ */
+void ffframe_drop_alpha_plane(AVFrame* frame, int format) {
+    AVBufferRef* alpha_buf = frame->buf[3];
+
+    frame->format = format;
+    frame->data[3] = NULL;
+    frame->linesize[3] = 0;
+
+    if (alpha_buf) {
+        for (int i = 0; i < 3; i++) {
+            if (frame->data[i] >= alpha_buf->data &&
+                frame->data[i] < alpha_buf->data + alpha_buf->size) {
+                return;
+            }
+        }
+        av_buffer_unref(&frame->buf[3]);
+    }
+}
+
 /* ============================================================================
  * AVPacket Getters
  * ============================================================================ */
@@ -646,6 +708,22 @@ int ffctx_get_frame_size(const AVCodecContext* ctx) {
     return ctx->frame_size;
 }
 
+int ffctx_get_initial_padding(const AVCodecContext* ctx) {
+    return ctx->initial_padding;
+}
+
+/**
+ * Whether the opened encoder takes a final frame shorter than frame_size
+ * (AV_CODEC_CAP_SMALL_LAST_FRAME or AV_CODEC_CAP_VARIABLE_FRAME_SIZE).
+ */
+int ffctx_accepts_partial_audio_frame(const AVCodecContext* ctx) {
+    if (!ctx->codec) {
+        return 0;
+    }
+    return (ctx->codec->capabilities &
+            (AV_CODEC_CAP_SMALL_LAST_FRAME | AV_CODEC_CAP_VARIABLE_FRAME_SIZE)) != 0;
+}
+
 /* ============================================================================
  * Audio-specific AVFrame Setters
  * ============================================================================ */
@@ -861,6 +939,11 @@ void ffstream_get_time_base(const AVStream* stream, int* num, int* den) {
     *den = stream->time_base.den;
 }
 
+void ffstream_set_sample_aspect_ratio(AVStream* stream, int num, int den) {
+    stream->sample_aspect_ratio.num = num;
+    stream->sample_aspect_ratio.den = den;
+}
+
 void ffstream_set_time_base(AVStream* stream, int num, int den) {
     stream->time_base.num = num;
     stream->time_base.den = den;
@@ -883,6 +966,28 @@ int64_t ffstream_get_start_time(const AVStream* stream) {
     return stream->start_time;
 }
 
+int ffstream_get_disposition(const AVStream* stream) {
+    return stream->disposition;
+}
+
+void ffstream_set_disposition(AVStream* stream, int disposition) {
+    stream->disposition = disposition;
+}
+
+const char* fffmt_get_metadata(const AVFormatContext* ctx, const char* key) {
+    AVDictionaryEntry* entry = av_dict_get(ctx->metadata, key, NULL, 0);
+    return entry ? entry->value : NULL;
+}
+
+const char* ffstream_get_metadata(const AVStream* stream, const char* key) {
+    AVDictionaryEntry* entry = av_dict_get(stream->metadata, key, NULL, 0);
+    return entry ? entry->value : NULL;
+}
+
+int ffstream_set_metadata(AVStream* stream, const char* key, const char* value) {
+    return av_dict_set(&stream->metadata, key, value, 0);
+}
+
 /* ============================================================================
  * AVCodecParameters Accessors
  * ============================================================================ */
@@ -980,6 +1085,10 @@ void ffcodecpar_set_frame_size(AVCodecParameters* par, int frame_size) {
     par->frame_size = frame_size;
 }
 
+int ffcodecpar_get_profile(const AVCodecParameters* par) {
+    return par->profile;
+}
+
 const uint8_t* ffcodecpar_get_extradata(const AVCodecParameters* par) {
     return par->extradata;
 }
@@ -1050,6 +1159,121 @@ void ffcodecpar_set_sample_aspect_ratio(AVCodecParameters* par, int num, int den
     par->sample_aspect_ratio.den = den;
 }
 
+/**
+ * Get the ICC profile stored in the stream's coded side data (e.g. an MP4 colr/prof box).
+ * The returned pointer is owned by the codec parameters.
+ */
+const uint8_t* ffcodecpar_get_icc_profile(const AVCodecParameters* par, size_t* size) {
+#if LIBAVCODEC_VERSION_MAJOR >= 61 || (LIBAVCODEC_VERSION_MAJOR == 60 && LIBAVCODEC_VERSION_MINOR >= 31)
+    const AVPacketSideData* sd = av_packet_side_data_get(par->coded_side_data,
+                                                         par->nb_coded_side_data,
+                                                         AV_PKT_DATA_ICC_PROFILE);
+    if (sd) {
+        *size = sd->size;
+        return sd->data;
+    }
+#else
+    (void)par;
+#endif
+    *size = 0;
+    return NULL;
+}
+
+/**
+ * Store an ICC profile in the stream's coded side data so the muxer writes it.
+ *
+ * @return 0 on success, negative AVERROR on failure
+ */
+int ffcodecpar_set_icc_profile(AVCodecParameters* par, const uint8_t* data, size_t size) {
+#if LIBAVCODEC_VERSION_MAJOR >= 61 || (LIBAVCODEC_VERSION_MAJOR == 60 && LIBAVCODEC_VERSION_MINOR >= 31)
+    AVPacketSideData* sd = av_packet_side_data_new(&par->coded_side_data,
+                                                   &par->nb_coded_side_data,
+                                                   AV_PKT_DATA_ICC_PROFILE, size, 0);
+    if (!sd) {
+        return AVERROR(ENOMEM);
+    }
+    memcpy(sd->data, data, size);
+    return 0;
+#else
+    (void)par; (void)data; (void)size;
+    return AVERROR(ENOSYS);
+#endif
+}
+
+/*
+ * Stream-level side data passed through remuxing, by kind:
+ * 0 = display matrix, 1 = stereo 3D, 2 = spherical mapping,
+ * 3 = mastering display metadata, 4 = content light level.
+ * The kinds are a stable mapping for Rust (see `stream_side_data_kind`);
+ * the enum values themselves differ between FFmpeg versions.
+ */
+static int stream_side_data_type(int kind, enum AVPacketSideDataType* type, size_t* min_size) {
+    switch (kind) {
+    case 0: *type = AV_PKT_DATA_DISPLAYMATRIX; *min_size = 9 * sizeof(int32_t); return 0;
+    case 1: *type = AV_PKT_DATA_STEREO3D; *min_size = sizeof(AVStereo3D); return 0;
+    case 2: *type = AV_PKT_DATA_SPHERICAL; *min_size = sizeof(AVSphericalMapping); return 0;
+    case 3:
+        *type = AV_PKT_DATA_MASTERING_DISPLAY_METADATA;
+        *min_size = sizeof(AVMasteringDisplayMetadata);
+        return 0;
+    case 4:
+        *type = AV_PKT_DATA_CONTENT_LIGHT_LEVEL;
+        *min_size = sizeof(AVContentLightMetadata);
+        return 0;
+    default: return AVERROR(EINVAL);
+    }
+}
+
+/**
+ * Get stream-level side data of the given kind from the coded side data.
+ * The returned pointer is owned by the codec parameters.
+ */
+const uint8_t* ffcodecpar_get_stream_side_data(const AVCodecParameters* par, int kind, size_t* size) {
+    *size = 0;
+#if LIBAVCODEC_VERSION_MAJOR >= 61 || (LIBAVCODEC_VERSION_MAJOR == 60 && LIBAVCODEC_VERSION_MINOR >= 31)
+    enum AVPacketSideDataType type;
+    size_t min_size;
+    if (stream_side_data_type(kind, &type, &min_size) < 0) {
+        return NULL;
+    }
+    const AVPacketSideData* sd = av_packet_side_data_get(par->coded_side_data,
+                                                         par->nb_coded_side_data, type);
+    if (sd) {
+        *size = sd->size;
+        return sd->data;
+    }
+#else
+    (void)par; (void)kind;
+#endif
+    return NULL;
+}
+
+/**
+ * Store stream-level side data of the given kind so the muxer writes it.
+ *
+ * @return 0 on success, AVERROR(EINVAL) for an unknown kind or a blob smaller
+ *         than the FFmpeg struct it holds, other negative AVERROR on failure
+ */
+int ffcodecpar_add_stream_side_data(AVCodecParameters* par, int kind, const uint8_t* data, size_t size) {
+#if LIBAVCODEC_VERSION_MAJOR >= 61 || (LIBAVCODEC_VERSION_MAJOR == 60 && LIBAVCODEC_VERSION_MINOR >= 31)
+    enum AVPacketSideDataType type;
+    size_t min_size;
+    if (stream_side_data_type(kind, &type, &min_size) < 0 || size < min_size) {
+        return AVERROR(EINVAL);
+    }
+    AVPacketSideData* sd = av_packet_side_data_new(&par->coded_side_data,
+                                                   &par->nb_coded_side_data, type, size, 0);
+    if (!sd) {
+        return AVERROR(ENOMEM);
+    }
+    memcpy(sd->data, data, size);
+    return 0;
+#else
+    (void)par; (void)kind; (void)data; (void)size;
+    return AVERROR(ENOSYS);
+#endif
+}
+
 /* ============================================================================
  * AVIOContext Accessors
  * ============================================================================ */
@@ -1074,6 +1298,199 @@ void fffio_set_seekable(AVIOContext* ctx, int seekable) {
     }
 }
 
+/* ============================================================================
+ * HDR Dynamic Metadata Side Data
+ *
+ * HDR10+ metadata is carried by FFmpeg as a parsed AVDynamicHDRPlus struct.
+ * These helpers convert it to and from the complete ITU-T T.35 payload
+ * (country code through the end of the SMPTE ST 2094-40 message) so callers
+ * can pass it through as opaque bytes.
+ * ============================================================================ */
+
+/* itu_t_t35_country_code, terminal_provider_code, terminal_provider_oriented_code,
+ * application_identifier of an SMPTE ST 2094-40 (HDR10+) message */
+static const uint8_t HDR10PLUS_T35_HEADER[6] = {0xB5, 0x00, 0x3C, 0x00, 0x01, 0x04};
+
+#if LIBAVUTIL_VERSION_MAJOR >= 58
+/* av_dynamic_hdr_plus_to_t35() omits the country code; prepend it */
+static int hdr10plus_to_t35(const AVDynamicHDRPlus* hdr, uint8_t** out, size_t* out_size) {
+    uint8_t* payload = NULL;
+    size_t payload_size = 0;
+    int ret = av_dynamic_hdr_plus_to_t35(hdr, &payload, &payload_size);
+    if (ret < 0) {
+        return ret;
+    }
+
+    *out = av_malloc(payload_size + 1);
+    if (!*out) {
+        av_free(payload);
+        return AVERROR(ENOMEM);
+    }
+    (*out)[0] = HDR10PLUS_T35_HEADER[0];
+    memcpy(*out + 1, payload, payload_size);
+    *out_size = payload_size + 1;
+    av_free(payload);
+    return 0;
+}
+
+/* av_dynamic_hdr_plus_from_t35() expects the payload after application_identifier */
+static int hdr10plus_from_t35(AVDynamicHDRPlus* hdr, const uint8_t* data, size_t size) {
+    if (size <= sizeof(HDR10PLUS_T35_HEADER) ||
+        memcmp(data, HDR10PLUS_T35_HEADER, sizeof(HDR10PLUS_T35_HEADER)) != 0) {
+        return AVERROR(EINVAL);
+    }
+    return av_dynamic_hdr_plus_from_t35(hdr, data + sizeof(HDR10PLUS_T35_HEADER),
+                                        size - sizeof(HDR10PLUS_T35_HEADER));
+}
+#endif
+
+/**
+ * Get HDR10+ metadata attached to a decoded frame as an ITU-T T.35 payload.
+ * On success *out must be released with av_free().
+ *
+ * @return 0 on success, AVERROR(ENOENT) if the frame has none
+ */
+int ffframe_get_hdr10plus_t35(const AVFrame* frame, uint8_t** out, size_t* out_size) {
+#if LIBAVUTIL_VERSION_MAJOR >= 58
+    const AVFrameSideData* sd = av_frame_get_side_data(frame, AV_FRAME_DATA_DYNAMIC_HDR_PLUS);
+    if (!sd) {
+        return AVERROR(ENOENT);
+    }
+    return hdr10plus_to_t35((const AVDynamicHDRPlus*)sd->data, out, out_size);
+#else
+    (void)frame; (void)out; (void)out_size;
+    return AVERROR(ENOSYS);
+#endif
+}
+
+/**
+ * Attach an HDR10+ ITU-T T.35 payload to a frame for the encoder.
+ *
+ * @return 0 on success, AVERROR(EINVAL) if the payload is not HDR10+
+ */
+int ffframe_set_hdr10plus_t35(AVFrame* frame, const uint8_t* data, size_t size) {
+#if LIBAVUTIL_VERSION_MAJOR >= 58
+    AVDynamicHDRPlus* hdr = av_dynamic_hdr_plus_create_side_data(frame);
+    if (!hdr) {
+        return AVERROR(ENOMEM);
+    }
+    int ret = hdr10plus_from_t35(hdr, data, size);
+    if (ret < 0) {
+        av_frame_remove_side_data(frame, AV_FRAME_DATA_DYNAMIC_HDR_PLUS);
+    }
+    return ret;
+#else
+    (void)frame; (void)data; (void)size;
+    return AVERROR(ENOSYS);
+#endif
+}
+
+/**
+ * Get the raw Dolby Vision RPU NAL attached to a decoded frame.
+ * The returned pointer is owned by the frame.
+ */
+const uint8_t* ffframe_get_dovi_rpu(const AVFrame* frame, size_t* size) {
+    const AVFrameSideData* sd = av_frame_get_side_data(frame, AV_FRAME_DATA_DOVI_RPU_BUFFER);
+    if (!sd) {
+        *size = 0;
+        return NULL;
+    }
+    *size = sd->size;
+    return sd->data;
+}
+
+/**
+ * Get the embedded ICC profile attached to a decoded frame (PNG iCCP, JPEG APP2, WebP ICCP).
+ * The returned pointer is owned by the frame.
+ */
+const uint8_t* ffframe_get_icc_profile(const AVFrame* frame, size_t* size) {
+    const AVFrameSideData* sd = av_frame_get_side_data(frame, AV_FRAME_DATA_ICC_PROFILE);
+    if (!sd) {
+        *size = 0;
+        return NULL;
+    }
+    *size = sd->size;
+    return sd->data;
+}
+
+/**
+ * Get the A53 closed caption side data (cc_data triplets) attached to a decoded frame.
+ * The returned pointer is owned by the frame.
+ */
+const uint8_t* ffframe_get_a53_cc(const AVFrame* frame, size_t* size) {
+    const AVFrameSideData* sd = av_frame_get_side_data(frame, AV_FRAME_DATA_A53_CC);
+    if (!sd) {
+        *size = 0;
+        return NULL;
+    }
+    *size = sd->size;
+    return sd->data;
+}
+
+/**
+ * Replace the A53 closed caption side data of a frame; size 0 removes it.
+ *
+ * @return 0 on success, AVERROR(ENOMEM) on allocation failure
+ */
+int ffframe_set_a53_cc(AVFrame* frame, const uint8_t* data, size_t size) {
+    av_frame_remove_side_data(frame, AV_FRAME_DATA_A53_CC);
+    if (size == 0) {
+        return 0;
+    }
+    AVFrameSideData* sd = av_frame_new_side_data(frame, AV_FRAME_DATA_A53_CC, size);
+    if (!sd) {
+        return AVERROR(ENOMEM);
+    }
+    memcpy(sd->data, data, size);
+    return 0;
+}
+
+/**
+ * Get HDR10+ packet side data (e.g. Matroska BlockAdditional) as an ITU-T T.35 payload.
+ * On success *out must be released with av_free().
+ *
+ * @return 0 on success, AVERROR(ENOENT) if the packet has none
+ */
+int ffpkt_get_hdr10plus_t35(const AVPacket* pkt, uint8_t** out, size_t* out_size) {
+#if LIBAVUTIL_VERSION_MAJOR >= 58
+    size_t sd_size = 0;
+    const uint8_t* sd = av_packet_get_side_data(pkt, AV_PKT_DATA_DYNAMIC_HDR10_PLUS, &sd_size);
+    if (!sd || sd_size < sizeof(AVDynamicHDRPlus)) {
+        return AVERROR(ENOENT);
+    }
+    return hdr10plus_to_t35((const AVDynamicHDRPlus*)sd, out, out_size);
+#else
+    (void)pkt; (void)out; (void)out_size;
+    return AVERROR(ENOSYS);
+#endif
+}
+
+/**
+ * Attach an HDR10+ ITU-T T.35 payload to a packet for the muxer.
+ *
+ * @return 0 on success, AVERROR(EINVAL) if the payload is not HDR10+
+ */
+int ffpkt_set_hdr10plus_t35(AVPacket* pkt, const uint8_t* data, size_t size) {
+#if LIBAVUTIL_VERSION_MAJOR >= 58
+    size_t hdr_size = 0;
+    AVDynamicHDRPlus* hdr = av_dynamic_hdr_plus_alloc(&hdr_size);
+    if (!hdr) {
+        return AVERROR(ENOMEM);
+    }
+    int ret = hdr10plus_from_t35(hdr, data, size);
+    if (ret >= 0) {
+        ret = av_packet_add_side_data(pkt, AV_PKT_DATA_DYNAMIC_HDR10_PLUS, (uint8_t*)hdr, hdr_size);
+    }
+    if (ret < 0) {
+        av_free(hdr);
+    }
+    return ret;
+#else
+    (void)pkt; (void)data; (void)size;
+    return AVERROR(ENOSYS);
+#endif
+}
+
 /* ============================================================================
  * Hardware Decoding Support
  * ============================================================================ */
@@ -1170,3 +1587,105 @@ int ff_codec_get_hw_pix_fmt(const AVCodec* codec, int device_type) {
 
     return AV_PIX_FMT_NONE;
 }
+
+/**
+ * Check if an encoder accepts frames in a pixel format.
+ * Encoders that do not publish a list of formats are assumed to accept any.
+ *
+ * @param codec The encoder to check
+ * @param pix_fmt The AVPixelFormat to look for
+ * @return 1 if accepted, 0 otherwise
+ */
+int ffcodec_supports_pix_fmt(const AVCodec* codec, int pix_fmt) {
+    const enum AVPixelFormat* formats = NULL;
+    if (!codec) {
+        return 0;
+    }
+
+#if LIBAVCODEC_VERSION_MAJOR > 61 || (LIBAVCODEC_VERSION_MAJOR == 61 && LIBAVCODEC_VERSION_MINOR >= 13)
+    if (avcodec_get_supported_config(NULL, codec, AV_CODEC_CONFIG_PIX_FORMAT, 0,
+                                     (const void**)&formats, NULL) < 0) {
+        return 0;
+    }
+#else
+    formats = codec->pix_fmts;
+#endif
+
+    if (!formats) {
+        return 1;
+    }
+    for (; *formats != AV_PIX_FMT_NONE; formats++) {
+        if (*formats == (enum AVPixelFormat)pix_fmt) {
+            return 1;
+        }
+    }
+    return 0;
+}
+
+/**
+ * Get the short name of a codec (e.g., "libx264").
+ */
+const char* ffcodec_get_name(const AVCodec* codec) {
+    return codec ? codec->name : NULL;
+}
+
+/* ============================================================================
+ * Linked Codec Library Versions
+ *
+ * Codec libraries are linked statically from the same prefix as FFmpeg, so their
+ * headers are normally next to FFmpeg's. Guards keep the build working when a
+ * header is missing (the accessor then reports "unavailable").
+ * ============================================================================ */
+
+#if defined(__has_include)
+#  if __has_include(<x264.h>)
+#    include <stdint.h>
+#    include <x264.h>
+#    define WEBCODECS_HAVE_X264 1
+#  endif
+#  if __has_include(<x265.h>)
+#    include <x265.h>
+#    define WEBCODECS_HAVE_X265 1
+#  endif
+#  if __has_include(<vpx/vpx_codec.h>)
+#    include <vpx/vpx_codec.h>
+#    define WEBCODECS_HAVE_VPX 1
+#  endif
+/* libaom is not linked on Windows x64 MSVC (rav1e is used instead) */
+#  if __has_include(<aom/aom_codec.h>) && !(defined(_MSC_VER) && defined(_M_X64))
+#    include <aom/aom_codec.h>
+#    define WEBCODECS_HAVE_AOM 1
+#  endif
+#endif
+
+int ff_x264_build(void) {
+#ifdef WEBCODECS_HAVE_X264
+    return X264_BUILD;
+#else
+    return -1;
+#endif
+}
+
+const char* ff_x265_version(void) {
+#ifdef WEBCODECS_HAVE_X265
+    return x265_version_str;
+#else
+    return NULL;
+#endif
+}
+
+const char* ff_vpx_version(void) {
+#ifdef WEBCODECS_HAVE_VPX
+    return vpx_codec_version_str();
+#else
+    return NULL;
+#endif
+}
+
+const char* ff_aom_version(void) {
+#ifdef WEBCODECS_HAVE_AOM
+    return aom_codec_version_str();
+#else
+    return NULL;
+#endif
+}
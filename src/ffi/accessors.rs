@@ -6,7 +6,7 @@ use super::avformat::{
   AVCodecParameters, AVFormatContext, AVIOContext, AVInputFormat, AVOutputFormat, AVStream,
 };
 use super::types::*;
-use std::os::raw::{c_int, c_uint};
+use std::os::raw::{c_char, c_int, c_uint};
 
 unsafe extern "C" {
   // ========================================================================
@@ -38,6 +38,7 @@ unsafe extern "C" {
   pub fn ffctx_set_colorspace(ctx: *mut AVCodecContext, colorspace: c_int);
   pub fn ffctx_set_color_range(ctx: *mut AVCodecContext, color_range: c_int);
   pub fn ffctx_set_flags(ctx: *mut AVCodecContext, flags: c_int);
+  pub fn ffctx_set_stats_in(ctx: *mut AVCodecContext, stats_in: *mut c_char);
   pub fn ffctx_set_flags2(ctx: *mut AVCodecContext, flags2: c_int);
   pub fn ffctx_set_profile(ctx: *mut AVCodecContext, profile: c_int);
   pub fn ffctx_set_level(ctx: *mut AVCodecContext, level: c_int);
@@ -65,6 +66,25 @@ unsafe extern "C" {
   /// Returns the pixel format if supported, or AV_PIX_FMT_NONE if not.
   pub fn ff_codec_get_hw_pix_fmt(codec: *const AVCodec, device_type: c_int) -> c_int;
 
+  /// Check if an encoder accepts frames in a pixel format (1 = yes, 0 = no)
+  pub fn ffcodec_supports_pix_fmt(codec: *const AVCodec, pix_fmt: c_int) -> c_int;
+
+  /// Get the short name of a codec (e.g., "libx264")
+  pub fn ffcodec_get_name(codec: *const AVCodec) -> *const c_char;
+
+  // ========================================================================
+  // Linked Codec Library Versions
+  // ========================================================================
+
+  /// x264 build number (X264_BUILD), or -1 if x264 headers were unavailable
+  pub fn ff_x264_build() -> c_int;
+  /// x265 version string, or NULL if unavailable
+  pub fn ff_x265_version() -> *const c_char;
+  /// libvpx version string, or NULL if unavailable
+  pub fn ff_vpx_version() -> *const c_char;
+  /// libaom version string, or NULL if unavailable
+  pub fn ff_aom_version() -> *const c_char;
+
   // ========================================================================
   // AVCodecContext Getters
   // ========================================================================
@@ -84,6 +104,7 @@ unsafe extern "C" {
   pub fn ffctx_get_extradata(ctx: *const AVCodecContext) -> *const u8;
   pub fn ffctx_get_extradata_size(ctx: *const AVCodecContext) -> c_int;
   pub fn ffctx_set_extradata(ctx: *mut AVCodecContext, data: *const u8, size: c_int) -> c_int;
+  pub fn ffctx_get_stats_out(ctx: *const AVCodecContext) -> *const c_char;
   pub fn ffctx_get_flags(ctx: *const AVCodecContext) -> c_int;
 
   // ========================================================================
@@ -105,6 +126,10 @@ unsafe extern "C" {
   pub fn ffctx_get_channels(ctx: *const AVCodecContext) -> c_int;
   pub fn ffctx_get_channel_layout(ctx: *const AVCodecContext) -> u64;
   pub fn ffctx_get_frame_size(ctx: *const AVCodecContext) -> c_int;
+  /// Encoder delay in samples (e.g. 1024 priming samples for AAC)
+  pub fn ffctx_get_initial_padding(ctx: *const AVCodecContext) -> c_int;
+  /// Whether the opened encoder accepts a final frame shorter than frame_size
+  pub fn ffctx_accepts_partial_audio_frame(ctx: *const AVCodecContext) -> c_int;
 
   // ========================================================================
   // AVFrame Setters
@@ -125,8 +150,24 @@ unsafe extern "C" {
   pub fn ffframe_set_color_range(frame: *mut AVFrame, color_range: c_int);
   pub fn ffframe_set_sample_aspect_ratio(frame: *mut AVFrame, num: c_int, den: c_int);
   pub fn ffframe_set_quality(frame: *mut AVFrame, quality: c_int);
+  /// Set a frame metadata dictionary entry (copies key and value)
+  pub fn ffframe_set_metadata(
+    frame: *mut AVFrame,
+    key: *const c_char,
+    value: *const c_char,
+  ) -> c_int;
   pub fn ffframe_set_data(frame: *mut AVFrame, plane: c_int, data: *mut u8);
   pub fn ffframe_set_linesize(frame: *mut AVFrame, plane: c_int, linesize: c_int);
+  /// Switch a planar YUVA frame to `format` and release its alpha plane
+  pub fn ffframe_drop_alpha_plane(frame: *mut AVFrame, format: c_int);
+  /// Crop a frame to a window in place by offsetting its data pointers
+  pub fn ffframe_crop(
+    frame: *mut AVFrame,
+    left: c_int,
+    top: c_int,
+    width: c_int,
+    height: c_int,
+  ) -> c_int;
 
   // ========================================================================
   // AVFrame Getters
@@ -210,6 +251,35 @@ unsafe extern "C" {
   pub fn ffpkt_set_flags(pkt: *mut AVPacket, flags: c_int);
   pub fn ffpkt_set_stream_index(pkt: *mut AVPacket, stream_index: c_int);
 
+  // ========================================================================
+  // HDR Dynamic Metadata Side Data
+  // ========================================================================
+
+  /// Get HDR10+ frame side data as an ITU-T T.35 payload (free with av_free)
+  pub fn ffframe_get_hdr10plus_t35(
+    frame: *const AVFrame,
+    out: *mut *mut u8,
+    out_size: *mut usize,
+  ) -> c_int;
+  /// Attach an HDR10+ ITU-T T.35 payload as frame side data
+  pub fn ffframe_set_hdr10plus_t35(frame: *mut AVFrame, data: *const u8, size: usize) -> c_int;
+  /// Get the Dolby Vision RPU frame side data (owned by the frame)
+  pub fn ffframe_get_dovi_rpu(frame: *const AVFrame, size: *mut usize) -> *const u8;
+  /// Get the embedded ICC profile frame side data (owned by the frame)
+  pub fn ffframe_get_icc_profile(frame: *const AVFrame, size: *mut usize) -> *const u8;
+  /// Get the A53 closed caption frame side data (owned by the frame)
+  pub fn ffframe_get_a53_cc(frame: *const AVFrame, size: *mut usize) -> *const u8;
+  /// Replace the A53 closed caption frame side data (size 0 removes it)
+  pub fn ffframe_set_a53_cc(frame: *mut AVFrame, data: *const u8, size: usize) -> c_int;
+  /// Get HDR10+ packet side data as an ITU-T T.35 payload (free with av_free)
+  pub fn ffpkt_get_hdr10plus_t35(
+    pkt: *const AVPacket,
+    out: *mut *mut u8,
+    out_size: *mut usize,
+  ) -> c_int;
+  /// Attach an HDR10+ ITU-T T.35 payload as packet side data
+  pub fn ffpkt_set_hdr10plus_t35(pkt: *mut AVPacket, data: *const u8, size: usize) -> c_int;
+
   // ========================================================================
   // Hardware Frames Context
   // ========================================================================
@@ -280,6 +350,8 @@ unsafe extern "C" {
   pub fn fffmt_get_stream(ctx: *mut AVFormatContext, index: c_uint) -> *mut AVStream;
   pub fn fffmt_get_duration(ctx: *const AVFormatContext) -> i64;
   pub fn fffmt_get_bit_rate(ctx: *const AVFormatContext) -> i64;
+  /// Get a container metadata dictionary entry (null if absent)
+  pub fn fffmt_get_metadata(ctx: *const AVFormatContext, key: *const c_char) -> *const c_char;
   pub fn fffmt_get_oformat(ctx: *const AVFormatContext) -> *const AVOutputFormat;
   pub fn fffmt_get_iformat(ctx: *const AVFormatContext) -> *const AVInputFormat;
   pub fn fffmt_get_oformat_flags(ctx: *const AVFormatContext) -> c_int;
@@ -292,11 +364,22 @@ unsafe extern "C" {
   pub fn ffstream_get_codecpar(stream: *mut AVStream) -> *mut AVCodecParameters;
   pub fn ffstream_get_codecpar_const(stream: *const AVStream) -> *const AVCodecParameters;
   pub fn ffstream_get_time_base(stream: *const AVStream, num: *mut c_int, den: *mut c_int);
+  pub fn ffstream_set_sample_aspect_ratio(stream: *mut AVStream, num: c_int, den: c_int);
   pub fn ffstream_set_time_base(stream: *mut AVStream, num: c_int, den: c_int);
   pub fn ffstream_get_avg_frame_rate(stream: *const AVStream, num: *mut c_int, den: *mut c_int);
   pub fn ffstream_get_duration(stream: *const AVStream) -> i64;
   pub fn ffstream_get_nb_frames(stream: *const AVStream) -> i64;
   pub fn ffstream_get_start_time(stream: *const AVStream) -> i64;
+  pub fn ffstream_get_disposition(stream: *const AVStream) -> c_int;
+  pub fn ffstream_set_disposition(stream: *mut AVStream, disposition: c_int);
+  /// Get a stream metadata dictionary entry (null if absent)
+  pub fn ffstream_get_metadata(stream: *const AVStream, key: *const c_char) -> *const c_char;
+  /// Set a stream metadata dictionary entry (copies key and value)
+  pub fn ffstream_set_metadata(
+    stream: *mut AVStream,
+    key: *const c_char,
+    value: *const c_char,
+  ) -> c_int;
 
   // ========================================================================
   // AVCodecParameters Accessors
@@ -322,6 +405,7 @@ unsafe extern "C" {
   pub fn ffcodecpar_set_channels(par: *mut AVCodecParameters, channels: c_int);
   pub fn ffcodecpar_get_frame_size(par: *const AVCodecParameters) -> c_int;
   pub fn ffcodecpar_set_frame_size(par: *mut AVCodecParameters, frame_size: c_int);
+  pub fn ffcodecpar_get_profile(par: *const AVCodecParameters) -> c_int;
   pub fn ffcodecpar_get_extradata(par: *const AVCodecParameters) -> *const u8;
   pub fn ffcodecpar_get_extradata_size(par: *const AVCodecParameters) -> c_int;
   pub fn ffcodecpar_set_extradata(
@@ -343,6 +427,27 @@ unsafe extern "C" {
     den: *mut c_int,
   );
   pub fn ffcodecpar_set_sample_aspect_ratio(par: *mut AVCodecParameters, num: c_int, den: c_int);
+  /// Get the ICC profile from coded side data (owned by the codec parameters)
+  pub fn ffcodecpar_get_icc_profile(par: *const AVCodecParameters, size: *mut usize) -> *const u8;
+  /// Store an ICC profile as coded side data
+  pub fn ffcodecpar_set_icc_profile(
+    par: *mut AVCodecParameters,
+    data: *const u8,
+    size: usize,
+  ) -> c_int;
+  /// Get stream-level side data of a `stream_side_data_kind` (owned by the codec parameters)
+  pub fn ffcodecpar_get_stream_side_data(
+    par: *const AVCodecParameters,
+    kind: c_int,
+    size: *mut usize,
+  ) -> *const u8;
+  /// Store stream-level side data of a `stream_side_data_kind` as coded side data
+  pub fn ffcodecpar_add_stream_side_data(
+    par: *mut AVCodecParameters,
+    kind: c_int,
+    data: *const u8,
+    size: usize,
+  ) -> c_int;
 }
 
 // ============================================================================
@@ -355,6 +460,37 @@ pub const FF_THREAD_FRAME: c_int = 1;
 /// Decode more than one part of a single frame at once
 pub const FF_THREAD_SLICE: c_int = 2;
 
+// ============================================================================
+// Stream Side Data Kinds
+// ============================================================================
+
+/// Stream-level side data kinds understood by `ffcodecpar_*_stream_side_data`
+///
+/// A stable mapping owned by accessors.c, not FFmpeg's AVPacketSideDataType values.
+pub mod stream_side_data_kind {
+  use std::os::raw::c_int;
+
+  /// 3x3 display transformation matrix (MP4 tkhd matrix)
+  pub const DISPLAY_MATRIX: c_int = 0;
+  /// AVStereo3D (MP4 st3d, Matroska StereoMode)
+  pub const STEREO_3D: c_int = 1;
+  /// AVSphericalMapping (MP4 sv3d, Matroska Projection)
+  pub const SPHERICAL: c_int = 2;
+  /// AVMasteringDisplayMetadata (MP4 mdcv, Matroska MasteringMetadata)
+  pub const MASTERING_DISPLAY: c_int = 3;
+  /// AVContentLightMetadata (MP4 clli, Matroska MaxCLL/MaxFALL)
+  pub const CONTENT_LIGHT_LEVEL: c_int = 4;
+
+  /// Every kind, in the order demuxers report them
+  pub const ALL: [c_int; 5] = [
+    DISPLAY_MATRIX,
+    STEREO_3D,
+    SPHERICAL,
+    MASTERING_DISPLAY,
+    CONTENT_LIGHT_LEVEL,
+  ];
+}
+
 // ============================================================================
 // Codec Flags
 // ============================================================================
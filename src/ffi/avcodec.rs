@@ -3,7 +3,7 @@
 //! Provides encoding and decoding functionality.
 
 use super::types::*;
-use std::os::raw::{c_char, c_int};
+use std::os::raw::{c_char, c_int, c_uint, c_void};
 
 unsafe extern "C" {
   // ========================================================================
@@ -22,6 +22,27 @@ unsafe extern "C" {
   /// Find a decoder by name
   pub fn avcodec_find_decoder_by_name(name: *const c_char) -> *const AVCodec;
 
+  /// Iterate over all registered codecs
+  ///
+  /// `opaque` must point to a NULL-initialized pointer. Returns NULL when done.
+  pub fn av_codec_iterate(opaque: *mut *mut c_void) -> *const AVCodec;
+
+  /// Returns non-zero if the codec is an encoder
+  pub fn av_codec_is_encoder(codec: *const AVCodec) -> c_int;
+
+  /// Returns non-zero if the codec is a decoder
+  pub fn av_codec_is_decoder(codec: *const AVCodec) -> c_int;
+
+  // ========================================================================
+  // Version / Build Information
+  // ========================================================================
+
+  /// Get the libavcodec version (major << 16 | minor << 8 | micro)
+  pub fn avcodec_version() -> c_uint;
+
+  /// Get the FFmpeg ./configure command line used for this build
+  pub fn avcodec_configuration() -> *const c_char;
+
   // ========================================================================
   // Codec Context Lifecycle
   // ========================================================================
@@ -138,6 +159,9 @@ unsafe extern "C" {
   /// Increase packet size, correctly zeroing padding
   pub fn av_grow_packet(pkt: *mut AVPacket, grow_by: c_int) -> c_int;
 
+  /// Ensure the packet data is writable, copying it if the buffer is shared
+  pub fn av_packet_make_writable(pkt: *mut AVPacket) -> c_int;
+
   /// Get side data from a packet
   ///
   /// # Arguments
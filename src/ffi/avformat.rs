@@ -114,6 +114,9 @@ unsafe extern "C" {
     filename: *const c_char,
   ) -> c_int;
 
+  /// Get the libavformat version (major << 16 | minor << 8 | micro)
+  pub fn avformat_version() -> c_uint;
+
   /// Allocate an AVFormatContext
   ///
   /// Must be freed with avformat_free_context() or avformat_close_input()
@@ -399,6 +402,12 @@ unsafe extern "C" {
     mime_type: *const c_char,
   ) -> *const AVOutputFormat;
 
+  /// Find a registered input format by short name (e.g., "mp4", "matroska")
+  ///
+  /// # Returns
+  /// Pointer to input format, or NULL if not found
+  pub fn av_find_input_format(short_name: *const c_char) -> *const AVInputFormat;
+
   /// Probe the input buffer to determine the input format
   pub fn av_probe_input_buffer2(
     pb: *mut AVIOContext,
@@ -496,6 +505,20 @@ pub mod media_type {
   pub const ATTACHMENT: c_int = 4;
 }
 
+/// Stream disposition flags (AV_DISPOSITION_*)
+pub mod disposition {
+  use std::os::raw::c_int;
+
+  /// Default track of its type
+  pub const DEFAULT: c_int = 0x0001;
+  /// Subtitles for the hearing impaired (WebVTT captions)
+  pub const CAPTIONS: c_int = 0x10000;
+  /// Textual descriptions of the video (WebVTT descriptions)
+  pub const DESCRIPTIONS: c_int = 0x20000;
+  /// Timed metadata (WebVTT metadata)
+  pub const METADATA: c_int = 0x40000;
+}
+
 /// Format context flags
 pub mod avfmt_flag {
   use std::os::raw::c_int;
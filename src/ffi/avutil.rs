@@ -3,7 +3,7 @@
 //! Provides utility functions for memory, frames, and general helpers.
 
 use super::types::*;
-use std::os::raw::{c_char, c_int, c_void};
+use std::os::raw::{c_char, c_int, c_uint, c_void};
 
 unsafe extern "C" {
   // ========================================================================
@@ -261,6 +261,21 @@ unsafe extern "C" {
     search_flags: c_int,
   ) -> c_int;
 
+  /// Get an option value as a string allocated with av_malloc
+  ///
+  /// The caller frees `*out_val` with av_free.
+  ///
+  /// # Safety
+  /// - `obj` must be a valid pointer to an AVClass-based object
+  /// - `name` must be a valid null-terminated C string
+  /// - `out_val` must be a valid pointer
+  pub fn av_opt_get(
+    obj: *mut c_void,
+    name: *const c_char,
+    search_flags: c_int,
+    out_val: *mut *mut u8,
+  ) -> c_int;
+
   /// Set an option by double value
   ///
   /// # Safety
@@ -272,6 +287,19 @@ unsafe extern "C" {
     val: f64,
     search_flags: c_int,
   ) -> c_int;
+
+  // ========================================================================
+  // Version / Build Information
+  // ========================================================================
+
+  /// Get the FFmpeg version string (e.g., "7.1" or a git describe string)
+  pub fn av_version_info() -> *const c_char;
+
+  /// Get the libavutil version (major << 16 | minor << 8 | micro)
+  pub fn avutil_version() -> c_uint;
+
+  /// Get the CPU feature flags detected at runtime (AV_CPU_FLAG_*)
+  pub fn av_get_cpu_flags() -> c_int;
 }
 
 // ============================================================================
@@ -332,6 +360,70 @@ pub fn image_buffer_size(format: AVPixelFormat, width: i32, height: i32) -> i32
   unsafe { av_image_get_buffer_size(format.as_raw(), width, height, 1) }
 }
 
+// ============================================================================
+// CPU Feature Flags (returned by av_get_cpu_flags)
+// ============================================================================
+
+pub mod cpu_flag {
+  use std::os::raw::c_int;
+
+  /// Feature names and their AV_CPU_FLAG_* bits for the current architecture
+  #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+  pub const NAMES: &[(&str, c_int)] = &[
+    ("mmx", 0x0001),
+    ("mmxext", 0x0002),
+    ("sse", 0x0008),
+    ("sse2", 0x0010),
+    ("sse3", 0x0040),
+    ("ssse3", 0x0080),
+    ("sse4.1", 0x0100),
+    ("sse4.2", 0x0200),
+    ("avx", 0x4000),
+    ("avx2", 0x8000),
+    ("fma3", 0x10000),
+    ("bmi1", 0x20000),
+    ("bmi2", 0x40000),
+    ("aesni", 0x80000),
+    ("avx512", 0x100000),
+    ("avx512icl", 0x200000),
+  ];
+
+  /// Feature names and their AV_CPU_FLAG_* bits for the current architecture
+  #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+  pub const NAMES: &[(&str, c_int)] = &[
+    ("armv5te", 1 << 0),
+    ("armv6", 1 << 1),
+    ("armv6t2", 1 << 2),
+    ("vfp", 1 << 3),
+    ("vfpv3", 1 << 4),
+    ("neon", 1 << 5),
+    ("armv8", 1 << 6),
+    ("dotprod", 1 << 8),
+    ("i8mm", 1 << 9),
+    ("sve", 1 << 10),
+    ("sve2", 1 << 11),
+  ];
+
+  /// Feature names and their AV_CPU_FLAG_* bits for the current architecture
+  #[cfg(not(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64"
+  )))]
+  pub const NAMES: &[(&str, c_int)] = &[];
+}
+
+/// Format a packed FFmpeg library version (major << 16 | minor << 8 | micro)
+pub fn format_version(version: u32) -> String {
+  format!(
+    "{}.{}.{}",
+    version >> 16,
+    (version >> 8) & 0xff,
+    version & 0xff
+  )
+}
+
 // ============================================================================
 // Option Search Flags (for av_opt_set family)
 // ============================================================================
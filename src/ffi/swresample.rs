@@ -4,7 +4,7 @@
 //! This is the audio equivalent of libswscale for video.
 
 use super::types::*;
-use std::os::raw::c_int;
+use std::os::raw::{c_int, c_uint};
 
 unsafe extern "C" {
   // ========================================================================
@@ -214,6 +214,9 @@ unsafe extern "C" {
     fmt: c_int,
     search_flags: c_int,
   ) -> c_int;
+
+  /// Get the libswresample version (major << 16 | minor << 8 | micro)
+  pub fn swresample_version() -> c_uint;
 }
 
 // ============================================================================
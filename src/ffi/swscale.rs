@@ -3,7 +3,7 @@
 //! Provides pixel format conversion and image scaling functionality.
 
 use super::types::*;
-use std::os::raw::c_int;
+use std::os::raw::{c_int, c_uint};
 
 unsafe extern "C" {
   // ========================================================================
@@ -54,6 +54,22 @@ unsafe extern "C" {
     param: *const f64,
   ) -> *mut SwsContext;
 
+  /// Allocate an empty SwsContext
+  ///
+  /// Parameters are set through AVOptions (`srcw`, `sws_flags`, `sws_dither`, ...)
+  /// and the context is then initialized with sws_init_context.
+  pub fn sws_alloc_context() -> *mut SwsContext;
+
+  /// Initialize a context allocated with sws_alloc_context
+  ///
+  /// # Returns
+  /// Zero or positive on success, negative AVERROR on failure
+  pub fn sws_init_context(
+    swsContext: *mut SwsContext,
+    srcFilter: *mut SwsFilter,
+    dstFilter: *mut SwsFilter,
+  ) -> c_int;
+
   /// Free the swscaler context
   pub fn sws_freeContext(swsContext: *mut SwsContext);
 
@@ -108,6 +124,9 @@ unsafe extern "C" {
 
   /// Check if an endianness conversion is supported
   pub fn sws_isSupportedEndiannessConversion(pix_fmt: c_int) -> c_int;
+
+  /// Get the libswscale version (major << 16 | minor << 8 | micro)
+  pub fn swscale_version() -> c_uint;
 }
 
 // ============================================================================
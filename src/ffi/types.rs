@@ -61,28 +61,33 @@ pub enum AVCodecID {
   Hevc = 173, // H.265
   Av1 = 225,
   // Audio codecs (starting at 0x10000 = 65536)
-  PcmS16le = 65536, // PCM signed 16-bit little-endian
-  PcmS16be = 65537, // PCM signed 16-bit big-endian
-  PcmU16le = 65538, // PCM unsigned 16-bit little-endian
-  PcmU16be = 65539, // PCM unsigned 16-bit big-endian
-  PcmS8 = 65540,    // PCM signed 8-bit
-  PcmU8 = 65541,    // PCM unsigned 8-bit
-  PcmF32le = 65557, // PCM 32-bit float little-endian
-  PcmF32be = 65558, // PCM 32-bit float big-endian
-  PcmF64le = 65559, // PCM 64-bit double little-endian
-  PcmF64be = 65560, // PCM 64-bit double big-endian
-  PcmS32le = 65544, // PCM signed 32-bit little-endian
-  PcmS32be = 65545, // PCM signed 32-bit big-endian
-  PcmS24le = 65566, // PCM signed 24-bit little-endian
-  PcmS24be = 65567, // PCM signed 24-bit big-endian
-  Mp2 = 86016,      // MPEG Audio Layer 2
-  Mp3 = 86017,      // MPEG Audio Layer 3
-  Aac = 86018,      // Advanced Audio Coding
-  Ac3 = 86019,      // Dolby AC-3
-  Vorbis = 86021,   // Vorbis
-  Flac = 86028,     // Free Lossless Audio Codec
-  Opus = 86076,     // Opus
-  Alac = 86032,     // Apple Lossless
+  PcmS16le = 65536,  // PCM signed 16-bit little-endian
+  PcmS16be = 65537,  // PCM signed 16-bit big-endian
+  PcmU16le = 65538,  // PCM unsigned 16-bit little-endian
+  PcmU16be = 65539,  // PCM unsigned 16-bit big-endian
+  PcmS8 = 65540,     // PCM signed 8-bit
+  PcmU8 = 65541,     // PCM unsigned 8-bit
+  PcmMulaw = 65542,  // G.711 µ-law
+  PcmAlaw = 65543,   // G.711 A-law
+  PcmF32le = 65557,  // PCM 32-bit float little-endian
+  PcmF32be = 65558,  // PCM 32-bit float big-endian
+  PcmF64le = 65559,  // PCM 64-bit double little-endian
+  PcmF64be = 65560,  // PCM 64-bit double big-endian
+  PcmS32le = 65544,  // PCM signed 32-bit little-endian
+  PcmS32be = 65545,  // PCM signed 32-bit big-endian
+  PcmS24le = 65566,  // PCM signed 24-bit little-endian
+  PcmS24be = 65567,  // PCM signed 24-bit big-endian
+  AdpcmG722 = 69660, // G.722
+  Mp2 = 86016,       // MPEG Audio Layer 2
+  Mp3 = 86017,       // MPEG Audio Layer 3
+  Aac = 86018,       // Advanced Audio Coding
+  Ac3 = 86019,       // Dolby AC-3
+  Vorbis = 86021,    // Vorbis
+  Flac = 86028,      // Free Lossless Audio Codec
+  Opus = 86076,      // Opus
+  Alac = 86032,      // Apple Lossless
+  // Subtitle codecs (starting at 0x17000 = 94208)
+  Webvtt = 94226, // WebVTT
 }
 
 impl AVCodecID {
@@ -148,6 +153,16 @@ impl AVCodecID {
     if codec_lower == "pcm-f32" {
       return Some(Self::PcmF32le);
     }
+    // Telephony: G.711 µ-law / A-law, G.722
+    if codec_lower == "ulaw" {
+      return Some(Self::PcmMulaw);
+    }
+    if codec_lower == "alaw" {
+      return Some(Self::PcmAlaw);
+    }
+    if codec_lower == "g722" {
+      return Some(Self::AdpcmG722);
+    }
 
     None
   }
@@ -181,16 +196,36 @@ impl AVCodecID {
       Self::PcmU16le | Self::PcmU16be => "pcm-u16",
       Self::PcmS8 => "pcm-s8",
       Self::PcmU8 => "pcm-u8",
+      Self::PcmMulaw => "ulaw",
+      Self::PcmAlaw => "alaw",
+      Self::AdpcmG722 => "g722",
       Self::PcmF32le | Self::PcmF32be => "pcm-f32",
       Self::PcmF64le | Self::PcmF64be => "pcm-f64",
       Self::PcmS32le | Self::PcmS32be => "pcm-s32",
       Self::PcmS24le | Self::PcmS24be => "pcm-s24",
+      // Subtitle
+      Self::Webvtt => "webvtt",
+    }
+  }
+
+  /// Sample rate fixed by a telephony codec (G.711: 8 kHz, G.722: 16 kHz)
+  pub fn fixed_sample_rate(&self) -> Option<u32> {
+    match self {
+      Self::PcmMulaw | Self::PcmAlaw => Some(8000),
+      Self::AdpcmG722 => Some(16000),
+      _ => None,
     }
   }
 
   /// Check if this is an audio codec
   pub fn is_audio(&self) -> bool {
-    (*self as c_int) >= 65536
+    let raw = *self as c_int;
+    (65536..94208).contains(&raw)
+  }
+
+  /// Check if this is a subtitle codec
+  pub fn is_subtitle(&self) -> bool {
+    (*self as c_int) >= 94208
   }
 
   /// Check if this is a video codec
@@ -226,6 +261,8 @@ impl AVCodecID {
       65539 => Self::PcmU16be,
       65540 => Self::PcmS8,
       65541 => Self::PcmU8,
+      65542 => Self::PcmMulaw,
+      65543 => Self::PcmAlaw,
       65544 => Self::PcmS32le,
       65545 => Self::PcmS32be,
       65557 => Self::PcmF32le,
@@ -234,6 +271,7 @@ impl AVCodecID {
       65560 => Self::PcmF64be,
       65566 => Self::PcmS24le,
       65567 => Self::PcmS24be,
+      69660 => Self::AdpcmG722,
       86016 => Self::Mp2,
       86017 => Self::Mp3,
       86018 => Self::Aac,
@@ -242,6 +280,7 @@ impl AVCodecID {
       86028 => Self::Flac,
       86032 => Self::Alac,
       86076 => Self::Opus,
+      94226 => Self::Webvtt,
       _ => Self::None,
     }
   }
@@ -285,6 +324,8 @@ pub enum AVPixelFormat {
   Yuva420p10le = 87, // I420AP10
   Yuva422p10le = 89, // I422AP10
   Yuva444p10le = 91, // I444AP10
+  // 16-bit packed RGBA (intermediate for dithered RGBA/BGRA output)
+  Rgba64le = 105,
   // Hardware formats - FFmpeg 8.x (libavutil 60) values
   // Verified against ffmpeg-src/FFmpeg/libavutil/pixfmt.h
   Videotoolbox = 157,
@@ -374,10 +415,76 @@ impl AVPixelFormat {
       Self::Nv12 | Self::Nv21 => 2,
       // 1-plane packed formats
       Self::Rgb24 | Self::Bgr24 | Self::Rgba | Self::Bgra | Self::Argb | Self::Abgr => 1,
+      Self::Rgba64le => 1,
       _ => 0,
     }
   }
 
+  /// Planar YUV format with the alpha plane removed (same layout for planes 0-2)
+  pub fn without_alpha(&self) -> Option<Self> {
+    match self {
+      Self::Yuva420p => Some(Self::Yuv420p),
+      Self::Yuva422p => Some(Self::Yuv422p),
+      Self::Yuva444p => Some(Self::Yuv444p),
+      Self::Yuva420p10le => Some(Self::Yuv420p10le),
+      Self::Yuva422p10le => Some(Self::Yuv422p10le),
+      Self::Yuva444p10le => Some(Self::Yuv444p10le),
+      _ => None,
+    }
+  }
+
+  /// Log2 horizontal and vertical chroma subsampling of planar YUV formats
+  /// (0, 0 for everything else)
+  pub fn chroma_subsampling(&self) -> (u32, u32) {
+    match self {
+      Self::Yuv420p
+      | Self::Yuva420p
+      | Self::Yuv420p10le
+      | Self::Yuva420p10le
+      | Self::Yuv420p12le
+      | Self::Nv12
+      | Self::Nv21 => (1, 1),
+      Self::Yuv422p
+      | Self::Yuva422p
+      | Self::Yuv422p10le
+      | Self::Yuva422p10le
+      | Self::Yuv422p12le => (1, 0),
+      _ => (0, 0),
+    }
+  }
+
+  /// Whether samples carry more than 8 bits per component
+  pub fn is_high_bit_depth(&self) -> bool {
+    matches!(
+      self,
+      Self::Yuv420p10le
+        | Self::Yuv422p10le
+        | Self::Yuv444p10le
+        | Self::Yuv420p12le
+        | Self::Yuv422p12le
+        | Self::Yuv444p12le
+        | Self::Yuva420p10le
+        | Self::Yuva422p10le
+        | Self::Yuva444p10le
+        | Self::Rgba64le
+    )
+  }
+
+  /// Bits per sample component (8 for formats not listed as high bit depth)
+  pub fn bit_depth(&self) -> u32 {
+    match self {
+      Self::Yuv420p10le
+      | Self::Yuv422p10le
+      | Self::Yuv444p10le
+      | Self::Yuva420p10le
+      | Self::Yuva422p10le
+      | Self::Yuva444p10le => 10,
+      Self::Yuv420p12le | Self::Yuv422p12le | Self::Yuv444p12le => 12,
+      Self::Rgba64le => 16,
+      _ => 8,
+    }
+  }
+
   /// Whether this is a hardware pixel format
   pub fn is_hardware(&self) -> bool {
     matches!(
@@ -418,6 +525,7 @@ impl AVPixelFormat {
       87 => Self::Yuva420p10le,
       89 => Self::Yuva422p10le,
       91 => Self::Yuva444p10le,
+      105 => Self::Rgba64le,
       // Hardware formats - FFmpeg 8.x (libavutil 60) values
       // Verified against ffmpeg-src/FFmpeg/libavutil/pixfmt.h
       157 => Self::Videotoolbox,
@@ -628,6 +736,24 @@ pub enum AVColorSpace {
   Bt2020Cl = 10,
 }
 
+impl AVColorSpace {
+  /// Convert from raw FFmpeg colorspace value
+  pub fn from_raw(value: c_int) -> Self {
+    match value {
+      0 => Self::Rgb,
+      1 => Self::Bt709,
+      4 => Self::Fcc,
+      5 => Self::Bt470bg,
+      6 => Self::Smpte170m,
+      7 => Self::Smpte240m,
+      8 => Self::Ycgco,
+      9 => Self::Bt2020Ncl,
+      10 => Self::Bt2020Cl,
+      _ => Self::Unspecified,
+    }
+  }
+}
+
 /// Color primaries
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -647,6 +773,26 @@ pub enum AVColorPrimaries {
   JedecP22 = 22,
 }
 
+impl AVColorPrimaries {
+  /// Convert from raw FFmpeg color primaries value
+  pub fn from_raw(value: c_int) -> Self {
+    match value {
+      1 => Self::Bt709,
+      4 => Self::Bt470m,
+      5 => Self::Bt470bg,
+      6 => Self::Smpte170m,
+      7 => Self::Smpte240m,
+      8 => Self::Film,
+      9 => Self::Bt2020,
+      10 => Self::Smpte428,
+      11 => Self::Smpte431,
+      12 => Self::Smpte432,
+      22 => Self::JedecP22,
+      _ => Self::Unspecified,
+    }
+  }
+}
+
 /// Color transfer characteristics
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -671,6 +817,31 @@ pub enum AVColorTransferCharacteristic {
   AribStdB67 = 18, // HLG
 }
 
+impl AVColorTransferCharacteristic {
+  /// Convert from raw FFmpeg transfer characteristic value
+  pub fn from_raw(value: c_int) -> Self {
+    match value {
+      1 => Self::Bt709,
+      4 => Self::Gamma22,
+      5 => Self::Gamma28,
+      6 => Self::Smpte170m,
+      7 => Self::Smpte240m,
+      8 => Self::Linear,
+      9 => Self::Log,
+      10 => Self::LogSqrt,
+      11 => Self::Iec61966_2_4,
+      12 => Self::Bt1361Ecg,
+      13 => Self::Iec61966_2_1,
+      14 => Self::Bt2020_10,
+      15 => Self::Bt2020_12,
+      16 => Self::Smpte2084,
+      17 => Self::Smpte428,
+      18 => Self::AribStdB67,
+      _ => Self::Unspecified,
+    }
+  }
+}
+
 /// Color range
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -681,6 +852,17 @@ pub enum AVColorRange {
   Jpeg = 2, // Full range (0-255)
 }
 
+impl AVColorRange {
+  /// Convert from raw FFmpeg color range value
+  pub fn from_raw(value: c_int) -> Self {
+    match value {
+      1 => Self::Mpeg,
+      2 => Self::Jpeg,
+      _ => Self::Unspecified,
+    }
+  }
+}
+
 // ============================================================================
 // Picture Type
 // ============================================================================
@@ -880,6 +1062,15 @@ pub mod pkt_side_data_type {
   /// AV_PKT_DATA_QUALITY_STATS - quality statistics from encoder
   pub const AV_PKT_DATA_QUALITY_STATS: c_int = 8;
 
+  /// AVCPBProperties of the coded picture buffer: max, min and average
+  /// bitrate, buffer size (all int64) and vbv_delay (uint64, 27 MHz units)
+  pub const AV_PKT_DATA_CPB_PROPERTIES: c_int = 10;
+
+  /// Recommended number of samples to skip at the start (u32le) and to
+  /// discard as padding at the end (u32le) of the decoded packet, followed
+  /// by a u8 skip reason and a u8 discard reason.
+  pub const AV_PKT_DATA_SKIP_SAMPLES: c_int = 11;
+
   /// This side data contains Matroska BlockAdditional data. It is used to
   /// store additional data needed for proper playback of VP9 alpha encoded
   /// videos. The data is a raw byte buffer.
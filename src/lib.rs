@@ -75,9 +75,11 @@ unsafe extern "C" fn ffmpeg_log_callback(
   match level {
     l if l <= ffi::avutil::log_level::FATAL => {
       tracing::error!(target: "ffmpeg", "{}", msg);
+      codec::context::record_fatal_log(ptr, msg);
     }
     l if l <= ffi::avutil::log_level::ERROR => {
       tracing::error!(target: "ffmpeg", "{}", msg);
+      codec::context::record_error_log(ptr, msg);
     }
     l if l <= ffi::avutil::log_level::WARNING => {
       tracing::warn!(target: "ffmpeg", "{}", msg);
@@ -101,24 +103,26 @@ fn init() {
   use tracing_subscriber::prelude::*;
   use tracing_subscriber::util::SubscriberInitExt;
 
-  // Set FFmpeg log level to allow all messages through to our callback
-  // The tracing subscriber will handle actual filtering
+  // Route FFmpeg messages to our callback. The tracing subscriber handles
+  // the actual filtering; the av_log level follows the `ffmpeg` target
+  // (see webcodecs::logging)
   unsafe {
-    ffi::avutil::av_log_set_level(ffi::avutil::log_level::INFO);
     ffi::avutil::av_log_set_callback(Some(ffmpeg_log_callback));
   }
 
   // Usage without the `regex` feature.
   // <https://github.com/tokio-rs/tracing/issues/1436#issuecomment-918528013>
+  let targets = std::env::var("WEBCODECS_LOG").map_or_else(
+    |_| Targets::new(),
+    |env_var| {
+      use std::str::FromStr;
+      Targets::from_str(&env_var).unwrap()
+    },
+  );
   tracing_subscriber::registry()
-    .with(std::env::var("WEBCODECS_LOG").map_or_else(
-      |_| Targets::new(),
-      |env_var| {
-        use std::str::FromStr;
-        Targets::from_str(&env_var).unwrap()
-      },
-    ))
+    .with(webcodecs::logging::reloadable_filter(targets))
     .with(tracing_subscriber::fmt::layer())
+    .with(webcodecs::logging::CallbackLayer)
     .init();
 }
 
@@ -165,6 +169,7 @@ pub use webcodecs::{
   Mp4Muxer,
   Mp4MuxerOptions,
   Mp4VideoTrackConfig,
+  VideoChromaLocation,
   VideoColorPrimaries,
   VideoColorSpace,
   VideoColorSpaceInit,
@@ -172,6 +177,7 @@ pub use webcodecs::{
   VideoDecoderConfig,
   VideoDecoderConfigOutput,
   VideoDecoderSupport,
+  VideoDitherMode,
   VideoEncoder,
   VideoEncoderConfig,
   VideoEncoderEncodeOptions,
@@ -182,6 +188,7 @@ pub use webcodecs::{
   VideoFrameRect,
   VideoMatrixCoefficients,
   VideoPixelFormat,
+  VideoScaleAlgorithm,
   VideoTransferCharacteristics,
   WebMAudioTrackConfig,
   WebMDemuxer,
@@ -192,7 +199,9 @@ pub use webcodecs::{
   // Hardware acceleration utilities
   get_available_hardware_accelerators,
   get_hardware_accelerators,
+  get_hardware_session_counts,
   get_preferred_hardware_accelerator,
   is_hardware_accelerator_available,
   reset_hardware_fallback_state,
+  set_hardware_device_sharing,
 };
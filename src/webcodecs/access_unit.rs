@@ -0,0 +1,224 @@
+//! Access Unit Assembly - H.264 decoding from NAL-unit-sized chunks
+//!
+//! FFmpeg's H.264 decoder treats every packet as one picture, which is what
+//! a demuxer delivers. Sources such as RTP depacketizers hand out one NAL
+//! unit (often one slice) per chunk instead, so a picture split over several
+//! chunks decodes as several broken or duplicated frames.
+//!
+//! With `inputGranularity: "nal"` the decoder collects NAL units until the
+//! next access unit begins (H.264 7.4.1.2.3): an access unit delimiter, SPS,
+//! PPS or SEI after a slice, or a slice with `first_mb_in_slice == 0`. The
+//! collected units are then decoded as one packet stamped with the
+//! timestamp of the chunk that carried the first of them.
+
+use std::sync::{Arc, RwLock};
+
+use napi::Either;
+use napi_derive::napi;
+
+use crate::webcodecs::encoded_video_chunk::annexb_nal_ranges;
+use crate::webcodecs::{EncodedVideoChunkInner, EncodedVideoChunkType, is_avcc_format};
+
+/// How encoded video chunks map to pictures (extension)
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputGranularity {
+  /// Every chunk holds one complete access unit (one picture)
+  #[default]
+  #[napi(value = "accessUnit")]
+  AccessUnit,
+  /// Chunks hold one or more H.264 NAL units; pictures may span chunks
+  #[napi(value = "nal")]
+  Nal,
+}
+
+/// Complete access unit assembled from NAL units
+#[derive(Debug)]
+pub(crate) struct AccessUnit {
+  /// NAL units with start codes, or length prefixes if the input used them
+  pub data: Vec<u8>,
+  /// Timestamp of the chunk carrying the first NAL unit
+  pub timestamp: i64,
+  /// Duration of the chunk carrying the first NAL unit
+  pub duration: Option<i64>,
+  /// Contains an IDR slice
+  pub is_key: bool,
+}
+
+impl AccessUnit {
+  /// Wrap as the chunk state the decoder worker consumes
+  pub(crate) fn into_chunk(self) -> Arc<RwLock<Option<EncodedVideoChunkInner>>> {
+    Arc::new(RwLock::new(Some(EncodedVideoChunkInner {
+      data: Either::A(self.data),
+      chunk_type: if self.is_key {
+        EncodedVideoChunkType::Key
+      } else {
+        EncodedVideoChunkType::Delta
+      },
+      timestamp_us: self.timestamp,
+      duration_us: self.duration,
+      dts_us: None,
+      original_pts: None,
+      hdr_dynamic_metadata: Vec::new(),
+    })))
+  }
+}
+
+/// Collects H.264 NAL units into access units
+#[derive(Debug, Default)]
+pub(crate) struct AccessUnitAssembler {
+  /// NAL units of the access unit being collected, without start codes
+  nals: Vec<Vec<u8>>,
+  /// Timestamp and duration of the chunk carrying the first NAL unit
+  timing: Option<(i64, Option<i64>)>,
+  /// A slice of the current access unit has been seen
+  has_slice: bool,
+  /// A slice of the current access unit is an IDR slice
+  is_idr: bool,
+  /// The latest chunk used 4-byte length prefixes rather than start codes
+  length_prefixed: bool,
+}
+
+impl AccessUnitAssembler {
+  /// Add the NAL units of one chunk, returning the access units they complete
+  pub(crate) fn push(
+    &mut self,
+    data: &[u8],
+    timestamp: i64,
+    duration: Option<i64>,
+  ) -> Vec<AccessUnit> {
+    self.length_prefixed = is_avcc_format(data);
+    let nals: Vec<&[u8]> = if self.length_prefixed {
+      length_prefixed_nals(data)
+    } else {
+      annexb_nal_ranges(data)
+        .into_iter()
+        .map(|(start, end)| &data[start..end])
+        .collect()
+    };
+
+    let mut complete = Vec::new();
+    for nal in nals.into_iter().filter(|nal| !nal.is_empty()) {
+      let nal_type = nal[0] & 0x1f;
+      if self.has_slice && starts_access_unit(nal) {
+        complete.extend(self.finish());
+      }
+      if self.nals.is_empty() {
+        self.timing = Some((timestamp, duration));
+      }
+      if matches!(nal_type, 1 | 2 | 5) {
+        self.has_slice = true;
+      }
+      self.is_idr |= nal_type == 5;
+      self.nals.push(nal.to_vec());
+    }
+    complete
+  }
+
+  /// Return the buffered access unit, if it has a slice
+  ///
+  /// Called at flush, when no later NAL unit will mark its end.
+  pub(crate) fn finish(&mut self) -> Option<AccessUnit> {
+    let nals = std::mem::take(&mut self.nals);
+    let timing = self.timing.take();
+    let has_slice = std::mem::take(&mut self.has_slice);
+    let is_idr = std::mem::take(&mut self.is_idr);
+    let (timestamp, duration) = timing.filter(|_| has_slice)?;
+
+    let mut data = Vec::with_capacity(nals.iter().map(|nal| nal.len() + 4).sum());
+    for nal in &nals {
+      if self.length_prefixed {
+        data.extend_from_slice(&(nal.len() as u32).to_be_bytes());
+      } else {
+        data.extend_from_slice(&[0, 0, 0, 1]);
+      }
+      data.extend_from_slice(nal);
+    }
+    Some(AccessUnit {
+      data,
+      timestamp,
+      duration,
+      is_key: is_idr,
+    })
+  }
+}
+
+/// Whether `nal`, following a slice, begins the next access unit
+fn starts_access_unit(nal: &[u8]) -> bool {
+  match nal[0] & 0x1f {
+    // Slices: first_mb_in_slice is the first ue(v) of the slice header, and
+    // it is 0 exactly when the first bit is set
+    1 | 2 | 5 => nal.get(1).is_some_and(|b| b & 0x80 != 0),
+    // SEI, SPS, PPS, access unit delimiter, and types 14-18
+    6..=9 | 14..=18 => true,
+    _ => false,
+  }
+}
+
+/// Split 4-byte length-prefixed NAL units
+pub(crate) fn length_prefixed_nals(data: &[u8]) -> Vec<&[u8]> {
+  let mut nals = Vec::new();
+  let mut rest = data;
+  while rest.len() >= 4 {
+    let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+    if len > rest.len() - 4 {
+      break;
+    }
+    nals.push(&rest[4..4 + len]);
+    rest = &rest[4 + len..];
+  }
+  nals
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn annexb(nal: &[u8]) -> Vec<u8> {
+    [&[0, 0, 0, 1], nal].concat()
+  }
+
+  #[test]
+  fn slices_are_grouped_by_first_mb_in_slice() {
+    let sps = [0x67, 0x42];
+    let idr_first = [0x65, 0x88];
+    let idr_second = [0x65, 0x21];
+    let p_first = [0x41, 0x9a];
+    let p_second = [0x41, 0x03];
+
+    let mut assembler = AccessUnitAssembler::default();
+    assert!(assembler.push(&annexb(&sps), 0, None).is_empty());
+    assert!(assembler.push(&annexb(&idr_first), 0, None).is_empty());
+    assert!(assembler.push(&annexb(&idr_second), 0, None).is_empty());
+
+    let first = assembler.push(&annexb(&p_first), 33, Some(33));
+    assert_eq!(first.len(), 1);
+    assert!(first[0].is_key);
+    assert_eq!(first[0].timestamp, 0);
+    assert_eq!(
+      first[0].data,
+      [annexb(&sps), annexb(&idr_first), annexb(&idr_second)].concat()
+    );
+
+    assert!(assembler.push(&annexb(&p_second), 33, Some(33)).is_empty());
+    let last = assembler.finish().unwrap();
+    assert!(!last.is_key);
+    assert_eq!((last.timestamp, last.duration), (33, Some(33)));
+    assert_eq!(last.data, [annexb(&p_first), annexb(&p_second)].concat());
+    assert!(assembler.finish().is_none());
+  }
+
+  #[test]
+  fn delimiter_starts_an_access_unit_and_framing_is_kept() {
+    let mut assembler = AccessUnitAssembler::default();
+    let chunk = |nal: &[u8]| [&(nal.len() as u32).to_be_bytes()[..], nal].concat();
+    assert!(
+      assembler
+        .push(&chunk(&[0x65, 0x88, 0x80]), 0, None)
+        .is_empty()
+    );
+    let complete = assembler.push(&chunk(&[0x09, 0xf0]), 40, None);
+    assert_eq!(complete.len(), 1);
+    assert_eq!(complete[0].data, chunk(&[0x65, 0x88, 0x80]));
+  }
+}
@@ -0,0 +1,471 @@
+//! Audio Bitstream - ADTS and Opus packet utilities
+//!
+//! Helpers for moving encoded audio between WebCodecs chunks and transports
+//! that use a different framing: `adtsToRaw()`/`rawToAdts()` convert between
+//! ADTS-framed AAC (`aac: { format: 'adts' }`) and raw access units plus an
+//! AudioSpecificConfig, and `parseToc()` inspects the TOC byte of an Opus
+//! packet (RFC 6716 section 3.1) as carried in RTP payloads.
+
+use crate::webcodecs::buffer_view::BufferView;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// ADTS header length without CRC (protection_absent = 1)
+const ADTS_HEADER_LEN: usize = 7;
+
+/// ADTS frame_length is a 13-bit field that includes the header
+const ADTS_MAX_FRAME_LEN: usize = 0x1FFF;
+
+/// Longest Opus packet duration in microseconds (RFC 6716 section 3.2.5)
+const OPUS_MAX_PACKET_DURATION_US: u32 = 120_000;
+
+/// Raw AAC access units extracted from an ADTS stream
+#[napi(object)]
+pub struct AdtsToRawResult {
+  /// AudioSpecificConfig for the stream (usable as AudioDecoderConfig.description)
+  pub asc: Uint8Array,
+  /// Raw access units, one per ADTS frame, without headers
+  pub frames: Vec<Uint8Array>,
+}
+
+/// Opus coding mode selected by the TOC configuration number
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpusMode {
+  /// SILK-only (speech, up to wideband)
+  #[napi(value = "silk")]
+  Silk,
+  /// SILK and CELT combined (super-wideband and fullband speech)
+  #[napi(value = "hybrid")]
+  Hybrid,
+  /// CELT-only (music and low-delay)
+  #[napi(value = "celt")]
+  Celt,
+}
+
+/// Opus audio bandwidth selected by the TOC configuration number
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpusBandwidth {
+  /// 4 kHz
+  #[napi(value = "narrowband")]
+  Narrowband,
+  /// 6 kHz
+  #[napi(value = "mediumband")]
+  Mediumband,
+  /// 8 kHz
+  #[napi(value = "wideband")]
+  Wideband,
+  /// 12 kHz
+  #[napi(value = "superwideband")]
+  Superwideband,
+  /// 20 kHz
+  #[napi(value = "fullband")]
+  Fullband,
+}
+
+/// Fields of an Opus packet's TOC byte
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct OpusToc {
+  /// TOC configuration number (0-31)
+  pub config: u32,
+  /// Coding mode
+  pub mode: OpusMode,
+  /// Audio bandwidth
+  pub bandwidth: OpusBandwidth,
+  /// Whether the packet is coded as stereo
+  pub stereo: bool,
+  /// Frame count code (0-3)
+  pub code: u32,
+  /// Number of Opus frames in the packet
+  pub frame_count: u32,
+  /// Duration of each frame in microseconds
+  pub frame_duration: u32,
+  /// Duration of the packet in microseconds
+  pub duration: u32,
+}
+
+/// Split ADTS-framed AAC into raw access units and an AudioSpecificConfig
+///
+/// Every frame must carry the same profile, sample rate and channel
+/// configuration, and a single raw data block.
+#[napi]
+pub fn adts_to_raw(data: BufferView) -> Result<AdtsToRawResult> {
+  let (config, frames) = split_adts(&data).map_err(|e| Error::new(Status::InvalidArg, e))?;
+  Ok(AdtsToRawResult {
+    asc: Uint8Array::new(config.to_asc().to_vec()),
+    frames: frames
+      .into_iter()
+      .map(|frame| Uint8Array::new(frame.to_vec()))
+      .collect(),
+  })
+}
+
+/// Prepend an ADTS header to a raw AAC access unit
+///
+/// `asc` is the stream's AudioSpecificConfig (e.g. the decoder config description).
+#[napi]
+pub fn raw_to_adts(frame: BufferView, asc: BufferView) -> Result<Uint8Array> {
+  let config = AdtsConfig::from_asc(&asc).map_err(|e| Error::new(Status::InvalidArg, e))?;
+  let adts = config
+    .frame(&frame)
+    .map_err(|e| Error::new(Status::InvalidArg, e))?;
+  Ok(Uint8Array::new(adts))
+}
+
+/// Parse the TOC byte of an Opus packet
+#[napi]
+pub fn parse_toc(data: BufferView) -> Result<OpusToc> {
+  parse_opus_toc(&data).map_err(|e| Error::new(Status::InvalidArg, e))
+}
+
+// ============================================================================
+// ADTS
+// ============================================================================
+
+/// Stream parameters shared by ADTS headers and the AudioSpecificConfig
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AdtsConfig {
+  /// MPEG-4 audio object type (1 = Main, 2 = LC, 3 = SSR, 4 = LTP)
+  audio_object_type: u8,
+  sample_rate_index: u8,
+  channel_config: u8,
+}
+
+impl AdtsConfig {
+  /// Read the stream parameters from an AudioSpecificConfig
+  fn from_asc(asc: &[u8]) -> std::result::Result<Self, String> {
+    if asc.len() < 2 {
+      return Err(format!(
+        "AudioSpecificConfig is truncated: {} bytes",
+        asc.len()
+      ));
+    }
+    let audio_object_type = asc[0] >> 3;
+    let sample_rate_index = ((asc[0] & 0x07) << 1) | (asc[1] >> 7);
+    let channel_config = (asc[1] >> 3) & 0x0F;
+    if !(1..=4).contains(&audio_object_type) {
+      return Err(format!(
+        "Audio object type {} cannot be carried in ADTS",
+        audio_object_type
+      ));
+    }
+    if sample_rate_index > 12 {
+      return Err(format!(
+        "Sampling frequency index {} cannot be carried in ADTS",
+        sample_rate_index
+      ));
+    }
+    if channel_config > 7 {
+      return Err(format!(
+        "Channel configuration {} cannot be carried in ADTS",
+        channel_config
+      ));
+    }
+    Ok(Self {
+      audio_object_type,
+      sample_rate_index,
+      channel_config,
+    })
+  }
+
+  /// Two-byte AudioSpecificConfig (GASpecificConfig flags all zero)
+  fn to_asc(self) -> [u8; 2] {
+    [
+      (self.audio_object_type << 3) | (self.sample_rate_index >> 1),
+      ((self.sample_rate_index & 0x01) << 7) | (self.channel_config << 3),
+    ]
+  }
+
+  /// ADTS header followed by `raw`
+  fn frame(self, raw: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    if raw.is_empty() {
+      return Err("AAC frame is empty".to_string());
+    }
+    if raw.len() + ADTS_HEADER_LEN > ADTS_MAX_FRAME_LEN {
+      return Err(format!(
+        "AAC frame of {} bytes exceeds the ADTS frame length limit",
+        raw.len()
+      ));
+    }
+    let mut result = Vec::with_capacity(ADTS_HEADER_LEN + raw.len());
+    result.extend_from_slice(&create_adts_header(
+      raw.len(),
+      self.audio_object_type,
+      self.sample_rate_index,
+      self.channel_config,
+    ));
+    result.extend_from_slice(raw);
+    Ok(result)
+  }
+}
+
+/// Create an ADTS header for an AAC frame
+/// ADTS header is 7 bytes for no CRC (protection_absent = 1)
+pub(crate) fn create_adts_header(
+  frame_len: usize,
+  audio_object_type: u8,
+  sample_rate_index: u8,
+  channel_config: u8,
+) -> [u8; 7] {
+  let frame_length = frame_len + ADTS_HEADER_LEN; // Include header in length
+
+  // ADTS stores the profile as audio object type - 1 (AAC-LC = 1)
+  let profile = audio_object_type.wrapping_sub(1);
+
+  let mut header = [0u8; 7];
+
+  // Byte 0: syncword high byte (0xFF)
+  header[0] = 0xFF;
+
+  // Byte 1: syncword low 4 bits (0xF) + ID(0) + layer(00) + protection_absent(1) = 0xF1
+  header[1] = 0xF1;
+
+  // Byte 2: profile(2 bits) + sampling_frequency_index(4 bits) + private_bit(1) + channel_config high bit(1)
+  header[2] =
+    ((profile & 0x03) << 6) | ((sample_rate_index & 0x0F) << 2) | ((channel_config & 0x04) >> 2);
+
+  // Byte 3: channel_config low 2 bits + original_copy(0) + home(0) + copyright_id_bit(0) + copyright_id_start(0) + frame_length high 2 bits
+  header[3] = ((channel_config & 0x03) << 6) | ((frame_length >> 11) & 0x03) as u8;
+
+  // Byte 4: frame_length middle 8 bits
+  header[4] = ((frame_length >> 3) & 0xFF) as u8;
+
+  // Byte 5: frame_length low 3 bits + buffer_fullness high 5 bits (0x7FF for VBR)
+  header[5] = (((frame_length & 0x07) << 5) | 0x1F) as u8;
+
+  // Byte 6: buffer_fullness low 6 bits + number_of_raw_data_blocks(0)
+  header[6] = 0xFC;
+
+  header
+}
+
+/// Split an ADTS stream into its configuration and raw frame payloads
+fn split_adts(data: &[u8]) -> std::result::Result<(AdtsConfig, Vec<&[u8]>), String> {
+  let mut config: Option<AdtsConfig> = None;
+  let mut frames = Vec::new();
+  let mut offset = 0;
+
+  while offset < data.len() {
+    let header = &data[offset..];
+    if header.len() < ADTS_HEADER_LEN {
+      return Err(format!(
+        "ADTS header at offset {} is truncated: {} bytes",
+        offset,
+        header.len()
+      ));
+    }
+    if header[0] != 0xFF || header[1] & 0xF6 != 0xF0 {
+      return Err(format!("Missing ADTS syncword at offset {}", offset));
+    }
+    let protection_absent = header[1] & 0x01 == 1;
+    let frame_config = AdtsConfig {
+      audio_object_type: (header[2] >> 6) + 1,
+      sample_rate_index: (header[2] >> 2) & 0x0F,
+      channel_config: ((header[2] & 0x01) << 2) | (header[3] >> 6),
+    };
+    let frame_length = (((header[3] & 0x03) as usize) << 11)
+      | ((header[4] as usize) << 3)
+      | ((header[5] >> 5) as usize);
+    let raw_data_blocks = header[6] & 0x03;
+
+    if frame_config.sample_rate_index > 12 {
+      return Err(format!(
+        "Invalid sampling frequency index {} at offset {}",
+        frame_config.sample_rate_index, offset
+      ));
+    }
+    if raw_data_blocks != 0 {
+      return Err(format!(
+        "ADTS frame at offset {} has {} raw data blocks; only one is supported",
+        offset,
+        raw_data_blocks + 1
+      ));
+    }
+    let header_len = if protection_absent {
+      ADTS_HEADER_LEN
+    } else {
+      ADTS_HEADER_LEN + 2 // CRC
+    };
+    if frame_length <= header_len || frame_length > header.len() {
+      return Err(format!(
+        "ADTS frame at offset {} has invalid length {} ({} bytes remain)",
+        offset,
+        frame_length,
+        header.len()
+      ));
+    }
+    match config {
+      None => config = Some(frame_config),
+      Some(first) if first != frame_config => {
+        return Err(format!(
+          "ADTS frame at offset {} changes the stream configuration",
+          offset
+        ));
+      }
+      Some(_) => {}
+    }
+
+    frames.push(&header[header_len..frame_length]);
+    offset += frame_length;
+  }
+
+  match config {
+    Some(config) => Ok((config, frames)),
+    None => Err("ADTS data is empty".to_string()),
+  }
+}
+
+// ============================================================================
+// Opus TOC
+// ============================================================================
+
+/// Parse an Opus packet's TOC byte and frame count (RFC 6716 section 3.1)
+fn parse_opus_toc(data: &[u8]) -> std::result::Result<OpusToc, String> {
+  let Some(&toc) = data.first() else {
+    return Err("Opus packet is empty".to_string());
+  };
+  let config = (toc >> 3) as u32;
+  let stereo = toc & 0x04 != 0;
+  let code = (toc & 0x03) as u32;
+
+  let (mode, bandwidth, frame_duration) = match config {
+    0..=11 => {
+      let bandwidth = match config / 4 {
+        0 => OpusBandwidth::Narrowband,
+        1 => OpusBandwidth::Mediumband,
+        _ => OpusBandwidth::Wideband,
+      };
+      let duration = [10_000, 20_000, 40_000, 60_000][(config % 4) as usize];
+      (OpusMode::Silk, bandwidth, duration)
+    }
+    12..=15 => {
+      let bandwidth = if config < 14 {
+        OpusBandwidth::Superwideband
+      } else {
+        OpusBandwidth::Fullband
+      };
+      let duration = [10_000, 20_000][(config % 2) as usize];
+      (OpusMode::Hybrid, bandwidth, duration)
+    }
+    _ => {
+      let bandwidth = match (config - 16) / 4 {
+        0 => OpusBandwidth::Narrowband,
+        1 => OpusBandwidth::Wideband,
+        2 => OpusBandwidth::Superwideband,
+        _ => OpusBandwidth::Fullband,
+      };
+      let duration = [2_500, 5_000, 10_000, 20_000][(config % 4) as usize];
+      (OpusMode::Celt, bandwidth, duration)
+    }
+  };
+
+  let frame_count = match code {
+    0 => 1,
+    1 => {
+      // Two frames of equal size
+      if !(data.len() - 1).is_multiple_of(2) {
+        return Err(format!(
+          "Opus code 1 packet has an odd payload length: {} bytes",
+          data.len() - 1
+        ));
+      }
+      2
+    }
+    2 => {
+      // Two frames, the first length-prefixed
+      if data.len() < 2 {
+        return Err("Opus code 2 packet is missing the frame length".to_string());
+      }
+      2
+    }
+    _ => {
+      let Some(&frame_count_byte) = data.get(1) else {
+        return Err("Opus code 3 packet is missing the frame count byte".to_string());
+      };
+      let count = (frame_count_byte & 0x3F) as u32;
+      if count == 0 {
+        return Err("Opus code 3 packet has a frame count of 0".to_string());
+      }
+      count
+    }
+  };
+
+  let duration = frame_count * frame_duration;
+  if duration > OPUS_MAX_PACKET_DURATION_US {
+    return Err(format!(
+      "Opus packet duration {}us exceeds {}us",
+      duration, OPUS_MAX_PACKET_DURATION_US
+    ));
+  }
+
+  Ok(OpusToc {
+    config,
+    mode,
+    bandwidth,
+    stereo,
+    code,
+    frame_count,
+    frame_duration,
+    duration,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Two AAC-LC 44.1 kHz stereo ADTS frames with 3- and 2-byte payloads
+  const ADTS: [u8; 19] = [
+    0xFF, 0xF1, 0x50, 0x80, 0x01, 0x5F, 0xFC, 0x21, 0x10, 0x04, //
+    0xFF, 0xF1, 0x50, 0x80, 0x01, 0x3F, 0xFC, 0x21, 0x1C,
+  ];
+
+  #[test]
+  fn test_adts_round_trip() {
+    let (config, frames) = split_adts(&ADTS).unwrap();
+    assert_eq!(config.to_asc(), [0x12, 0x10]);
+    assert_eq!(frames, vec![&ADTS[7..10], &ADTS[17..19]]);
+
+    let config = AdtsConfig::from_asc(&[0x12, 0x10]).unwrap();
+    let mut rebuilt = config.frame(frames[0]).unwrap();
+    rebuilt.extend(config.frame(frames[1]).unwrap());
+    assert_eq!(rebuilt, ADTS);
+  }
+
+  #[test]
+  fn test_adts_rejects_invalid_frames() {
+    assert!(split_adts(&ADTS[..12]).unwrap_err().contains("truncated"));
+    assert!(
+      split_adts(&ADTS[..18])
+        .unwrap_err()
+        .contains("invalid length")
+    );
+    assert!(split_adts(&[0u8; 9]).unwrap_err().contains("syncword"));
+    assert!(AdtsConfig::from_asc(&[0x2A, 0x10]).is_err()); // object type 5 (SBR)
+  }
+
+  #[test]
+  fn test_opus_toc() {
+    // CELT fullband 20 ms stereo, single frame
+    let toc = parse_opus_toc(&[0xFC, 0x00]).unwrap();
+    assert_eq!(toc.config, 31);
+    assert_eq!(toc.mode, OpusMode::Celt);
+    assert_eq!(toc.bandwidth, OpusBandwidth::Fullband);
+    assert!(toc.stereo);
+    assert_eq!(toc.duration, 20_000);
+
+    // SILK wideband 20 ms mono, code 3 with 3 frames
+    let toc = parse_opus_toc(&[0x4B, 0x03, 0x00]).unwrap();
+    assert_eq!(toc.mode, OpusMode::Silk);
+    assert_eq!(toc.bandwidth, OpusBandwidth::Wideband);
+    assert_eq!(toc.frame_count, 3);
+    assert_eq!(toc.duration, 60_000);
+
+    // Code 3 exceeding 120 ms (7 x 20 ms)
+    assert!(parse_opus_toc(&[0x4B, 0x07]).is_err());
+    // Code 1 requires an even payload length
+    assert!(parse_opus_toc(&[0xFD, 0x00]).is_err());
+  }
+}
@@ -5,6 +5,7 @@
 
 use crate::codec::Frame;
 use crate::ffi::AVSampleFormat;
+use crate::webcodecs::buffer_view::required_buffer;
 use crate::webcodecs::error::{
   enforce_range_long_long, invalid_state_error, throw_invalid_state_error,
 };
@@ -173,40 +174,9 @@ impl FromNapiValue for AudioDataInit {
     };
 
     // Validate data - required field, accept BufferSource (ArrayBuffer, TypedArray, DataView)
-    let data: Vec<u8> = if let Ok(Some(buffer)) = obj.get::<Buffer>("data") {
-      buffer.to_vec()
-    } else if let Ok(Some(array)) = obj.get::<Uint8Array>("data") {
-      array.to_vec()
-    } else if let Ok(Some(array_buffer)) = obj.get::<ArrayBuffer>("data") {
-      array_buffer.to_vec()
-    } else {
-      // Check if data property exists but is undefined/null
-      let has_data = obj.has_named_property("data")?;
-      if !has_data {
-        return Err(throw_type_error(env, "data is required"));
-      }
-
-      // Try getting as object and check for buffer/byteLength properties (DataView, other TypedArrays)
-      if let Ok(Some(data_obj)) = obj.get::<Object>("data") {
-        let byte_length: Option<u32> = data_obj.get("byteLength").ok().flatten();
-        let byte_offset: u32 = data_obj.get("byteOffset").ok().flatten().unwrap_or(0);
-
-        if let (Some(len), Ok(Some(buffer))) = (byte_length, data_obj.get::<ArrayBuffer>("buffer"))
-        {
-          let full_data = buffer.to_vec();
-          let offset = byte_offset as usize;
-          let length = len as usize;
-          if offset + length <= full_data.len() {
-            full_data[offset..offset + length].to_vec()
-          } else {
-            return Err(throw_type_error(env, "data must be a valid BufferSource"));
-          }
-        } else {
-          return Err(throw_type_error(env, "data must be a BufferSource"));
-        }
-      } else {
-        return Err(throw_type_error(env, "data is required"));
-      }
+    let data: Vec<u8> = match required_buffer(&obj, "data") {
+      Ok(view) => view.to_vec(),
+      Err(msg) => return Err(throw_type_error(env, &msg)),
     };
 
     Ok(AudioDataInit {
@@ -5,9 +5,12 @@
 
 use crate::codec::{AudioDecoderConfig as InternalAudioDecoderConfig, CodecContext, Frame, Packet};
 use crate::ffi::AVCodecID;
+use crate::webcodecs::audio_encoder::telephony_config_error;
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunkInner;
 use crate::webcodecs::error::{DOMExceptionName, throw_invalid_state_error, throw_type_error_unit};
+use crate::webcodecs::output_timeline::OutputTimeline;
 use crate::webcodecs::promise_reject::{reject_with_dom_exception_async, reject_with_type_error};
+use crate::webcodecs::shutdown;
 use crate::webcodecs::{AudioData, AudioDecoderConfig, AudioDecoderSupport, EncodedAudioChunk};
 use crossbeam::channel::{self, Receiver, Sender};
 use napi::bindgen_prelude::*;
@@ -207,6 +210,8 @@ struct AudioDecoderInner {
   /// Queue of timestamps from input chunks (to preserve original timestamps)
   /// FFmpeg may return AV_NOPTS_VALUE for frame.pts(), so we track input timestamps
   timestamp_queue: std::collections::VecDeque<i64>,
+  /// timestampMode / startAtZero mapping of output timestamps
+  timeline: OutputTimeline,
 }
 
 /// AudioDecoder - WebCodecs-compliant audio decoder
@@ -251,16 +256,23 @@ pub struct AudioDecoder {
   worker_handle: Option<JoinHandle<()>>,
   /// Reset abort flag - set by reset() to signal worker to skip pending decodes
   reset_flag: Arc<AtomicBool>,
+  /// Environment teardown flag, shared with every worker this codec spawns
+  shutdown_flag: Arc<AtomicBool>,
 }
 
 impl Drop for AudioDecoder {
   fn drop(&mut self) {
-    // Signal worker to stop
+    // Signal worker to stop, skipping any queued work
+    self.reset_flag.store(true, Ordering::SeqCst);
     self.command_sender = None;
 
-    // Wait for worker to finish (brief block, necessary for safety)
-    if let Some(handle) = self.worker_handle.take() {
-      let _ = handle.join();
+    // Wait for worker to finish. The wait is bounded so a busy worker cannot
+    // hold up process exit; a detached worker still owns the codec state and
+    // releases it when its current command completes.
+    if let Some(handle) = self.worker_handle.take()
+      && !shutdown::join_worker(handle, "AudioDecoder")
+    {
+      return;
     }
 
     // Drain decoder to ensure codec threads finish before context drops.
@@ -282,6 +294,7 @@ impl AudioDecoder {
   /// @param init - Init dictionary containing output and error callbacks
   #[napi(constructor)]
   pub fn new(
+    env: Env,
     #[napi(ts_arg_type = "{ output: (data: AudioData) => void, error: (error: Error) => void }")]
     init: AudioDecoderInit,
   ) -> Result<Self> {
@@ -300,6 +313,7 @@ impl AudioDecoder {
       pending_data: Vec::new(),
       inside_flush: false,
       timestamp_queue: std::collections::VecDeque::new(),
+      timeline: OutputTimeline::new(),
     };
 
     let inner = Arc::new(Mutex::new(inner));
@@ -311,16 +325,21 @@ impl AudioDecoder {
     // Create reset abort flag
     let reset_flag = Arc::new(AtomicBool::new(false));
 
+    // Set when the environment tears down, so the worker skips queued work
+    let shutdown_flag = shutdown::env_shutdown_flag(&env)?;
+
     // Spawn worker thread
     let worker_inner = inner.clone();
     let worker_event_state = event_state.clone();
     let worker_reset_flag = reset_flag.clone();
+    let worker_shutdown_flag = shutdown_flag.clone();
     let worker_handle = std::thread::spawn(move || {
       Self::worker_loop(
         worker_inner,
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
       );
     });
 
@@ -333,6 +352,7 @@ impl AudioDecoder {
       command_sender: Some(Arc::new(sender)),
       worker_handle: Some(worker_handle),
       reset_flag,
+      shutdown_flag,
     })
   }
 
@@ -342,11 +362,12 @@ impl AudioDecoder {
     event_state: Arc<RwLock<EventListenerState>>,
     receiver: Receiver<DecoderCommand>,
     reset_flag: Arc<AtomicBool>,
+    shutdown_flag: Arc<AtomicBool>,
   ) {
     while let Ok(command) = receiver.recv() {
-      // Check reset flag before processing each command
-      // If reset() was called, skip remaining decode commands
-      if reset_flag.load(Ordering::SeqCst) {
+      // Check reset and shutdown flags before processing each command
+      // If reset() was called or the environment is shutting down, skip remaining decode commands
+      if reset_flag.load(Ordering::SeqCst) || shutdown_flag.load(Ordering::SeqCst) {
         // Still process flush commands to send responses, but skip decodes
         if let DecoderCommand::Flush(response_sender) = command {
           let _ = response_sender.send(Err(Error::new(
@@ -436,6 +457,11 @@ impl AudioDecoder {
       Either::A(data) => data.as_slice(),
       Either::B(packet) => packet.as_slice(),
     };
+    // Padding an encoder added to its last frame, so the decoder trims it
+    let skip_samples = match &inner.data {
+      Either::A(_) => None,
+      Either::B(packet) => packet.skip_samples(),
+    };
 
     // W3C spec: Empty data should trigger EncodingError
     if data.is_empty() {
@@ -472,7 +498,7 @@ impl AudioDecoder {
     };
 
     // Decode using the internal implementation
-    let frames = match decode_audio_chunk_data(context, data, timestamp) {
+    let frames = match decode_audio_chunk_data(context, data, timestamp, skip_samples) {
       Ok(f) => f,
       Err(e) => {
         let old_size = guard.decode_queue_size;
@@ -489,33 +515,26 @@ impl AudioDecoder {
 
     guard.frame_count += 1;
 
-    // Decrement queue size and fire dequeue event (only if queue was not empty)
-    let old_size = guard.decode_queue_size;
-    guard.decode_queue_size = old_size.saturating_sub(1);
-    if old_size > 0 {
-      let _ = Self::fire_dequeue_event(event_state);
-    }
-
     // Convert internal frames to AudioData and deliver
     // Pop the original timestamp (fallback to frame.pts() if queue empty)
     let output_timestamp = guard
       .timestamp_queue
       .pop_front()
       .unwrap_or_else(|| frames.first().map(|f| f.pts()).unwrap_or(0));
-    for (i, frame) in frames.into_iter().enumerate() {
-      // For the first frame, use the original input timestamp
-      // For subsequent frames, calculate based on sample count or use frame.pts()
-      let pts = if i == 0 {
-        output_timestamp
-      } else {
-        let frame_pts = frame.pts();
-        // Use frame.pts() if valid, otherwise estimate from first frame timestamp
-        if frame_pts != crate::ffi::types::AV_NOPTS_VALUE {
-          frame_pts
-        } else {
-          output_timestamp
-        }
-      };
+    // The first frame starts at the input timestamp; later frames from the same
+    // packet (e.g. a 120ms Opus packet) start where the decoded samples before
+    // them end, rather than repeating the packet timestamp
+    let mut samples_before = 0u64;
+    for frame in frames {
+      let offset_us = (samples_before * 1_000_000)
+        .checked_div(frame.sample_rate() as u64)
+        .unwrap_or(0);
+      let pts = guard.timeline.map_audio(
+        output_timestamp + offset_us as i64,
+        frame.nb_samples(),
+        frame.sample_rate(),
+      );
+      samples_before += frame.nb_samples() as u64;
       let audio_data = AudioData::from_internal(frame, pts);
 
       // During flush, queue data for synchronous delivery in resolver
@@ -525,9 +544,17 @@ impl AudioDecoder {
       } else {
         guard
           .output_callback
-          .call(audio_data, ThreadsafeFunctionCallMode::Blocking);
+          .call(audio_data, ThreadsafeFunctionCallMode::NonBlocking);
       }
     }
+
+    // The control message is done only once its outputs are queued: decrement the
+    // queue size and fire dequeue now, so backpressure sees the work in progress
+    let old_size = guard.decode_queue_size;
+    guard.decode_queue_size = old_size.saturating_sub(1);
+    if old_size > 0 {
+      let _ = Self::fire_dequeue_event(event_state);
+    }
   }
 
   /// Process a flush command on the worker thread
@@ -575,7 +602,9 @@ impl AudioDecoder {
 
     // Queue remaining frames for delivery (always queue during flush for synchronous delivery)
     for frame in frames {
-      let pts = frame.pts();
+      let pts = guard
+        .timeline
+        .map_audio(frame.pts(), frame.nb_samples(), frame.sample_rate());
       let audio_data = AudioData::from_internal(frame, pts);
       // Always queue during flush for synchronous delivery in resolver
       guard.pending_data.push(audio_data);
@@ -645,6 +674,10 @@ impl AudioDecoder {
         return;
       }
     };
+    if let Some(message) = telephony_config_error(codec_id, sample_rate, number_of_channels) {
+      Self::report_error(&mut guard, &message);
+      return;
+    }
 
     // W3C WebCodecs spec: FLAC codec requires description (contains STREAMINFO)
     let codec_lower = codec.to_lowercase();
@@ -689,6 +722,11 @@ impl AudioDecoder {
     guard.context = Some(context);
     guard.config = Some(decoder_config);
     guard.codec_string = codec;
+
+    // Data decoded before this point was mapped with the previous options
+    guard
+      .timeline
+      .configure(config.timestamp_mode, config.start_at_zero);
   }
 
   /// Report an error via callback and close the decoder
@@ -700,7 +738,7 @@ impl AudioDecoder {
     let error = Error::new(Status::GenericFailure, error_msg);
     inner
       .error_callback
-      .call(error, ThreadsafeFunctionCallMode::Blocking);
+      .call(error, ThreadsafeFunctionCallMode::NonBlocking);
     inner.had_error = true;
     inner.state = CodecState::Closed;
   }
@@ -873,7 +911,7 @@ impl AudioDecoder {
     // This ensures pending decode commands are processed before reconfiguration
     if inner.state == CodecState::Configured {
       // Validate codec synchronously before queueing
-      let _codec_id = match parse_audio_codec_string(&codec) {
+      let codec_id = match parse_audio_codec_string(&codec) {
         Ok(id) => id,
         Err(e) => {
           Self::report_error(
@@ -883,6 +921,10 @@ impl AudioDecoder {
           return Ok(());
         }
       };
+      if let Some(message) = telephony_config_error(codec_id, sample_rate, number_of_channels) {
+        Self::report_error(&mut inner, &message);
+        return Ok(());
+      }
 
       // Queue reconfigure via microtask (runs AFTER pending decode microtasks)
       // Use Weak reference to allow close() to immediately close channel without deadlock
@@ -911,6 +953,10 @@ impl AudioDecoder {
         return Ok(());
       }
     };
+    if let Some(message) = telephony_config_error(codec_id, sample_rate, number_of_channels) {
+      Self::report_error(&mut inner, &message);
+      return Ok(());
+    }
 
     // W3C WebCodecs spec: FLAC codec requires description (contains STREAMINFO)
     let codec_lower = codec.to_lowercase();
@@ -959,6 +1005,11 @@ impl AudioDecoder {
     inner.decode_queue_size = 0;
     inner.timestamp_queue.clear();
 
+    // Kept across reset() so a seek stays on the same output timeline
+    inner
+      .timeline
+      .configure(config.timestamp_mode, config.start_at_zero);
+
     // Create new channel and worker for decode operations
     if self.command_sender.is_none() {
       let (sender, receiver) = channel::unbounded();
@@ -966,6 +1017,7 @@ impl AudioDecoder {
       let worker_inner = self.inner.clone();
       let worker_event_state = self.event_state.clone();
       let worker_reset_flag = self.reset_flag.clone();
+      let worker_shutdown_flag = self.shutdown_flag.clone();
       drop(inner);
       self.worker_handle = Some(std::thread::spawn(move || {
         Self::worker_loop(
@@ -973,6 +1025,7 @@ impl AudioDecoder {
           worker_event_state,
           receiver,
           worker_reset_flag,
+          worker_shutdown_flag,
         );
       }));
     }
@@ -1039,6 +1092,77 @@ impl AudioDecoder {
     Ok(())
   }
 
+  /// Decode several encoded audio chunks in one call
+  ///
+  /// Equivalent to calling `decode()` for each chunk in order, with one native
+  /// call and one microtask hop for the whole batch. `decodeQueueSize` grows by
+  /// the batch size and a dequeue event still fires per decoded chunk.
+  #[napi(ts_args_type = "chunks: EncodedAudioChunk[]")]
+  pub fn decode_batch(
+    &self,
+    env: Env,
+    chunks: Vec<ClassInstance<'_, EncodedAudioChunk>>,
+  ) -> Result<()> {
+    if chunks.is_empty() {
+      return Ok(());
+    }
+
+    // Extract data and timestamps on main thread (brief lock)
+    let batch = {
+      let mut inner = self
+        .inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+      // W3C spec: throw InvalidStateError if not configured or closed
+      if inner.state == CodecState::Closed {
+        return throw_invalid_state_error(&env, "Cannot decode with a closed codec");
+      }
+      if inner.state != CodecState::Configured {
+        return throw_invalid_state_error(&env, "Cannot decode with an unconfigured codec");
+      }
+
+      let mut batch = Vec::with_capacity(chunks.len());
+      for chunk in &chunks {
+        match chunk.get_timestamp() {
+          Ok(timestamp) => batch.push((Arc::clone(&chunk.inner), timestamp)),
+          Err(e) => {
+            Self::report_error(&mut inner, &format!("Failed to get timestamp: {}", e));
+            return Ok(());
+          }
+        }
+      }
+
+      // Increment queue size (pending operations)
+      inner.decode_queue_size += batch.len() as u32;
+
+      batch
+    };
+
+    // Send all decode commands from a single microtask, preserving FIFO order
+    if let Some(ref sender) = self.command_sender {
+      let weak_sender = Arc::downgrade(sender);
+      let reset_flag = self.reset_flag.clone();
+      PromiseRaw::resolve(&env, ())?.then(move |_| {
+        if !reset_flag.load(Ordering::SeqCst)
+          && let Some(sender) = weak_sender.upgrade()
+        {
+          for (chunk, timestamp) in batch {
+            let _ = sender.send(DecoderCommand::Decode { chunk, timestamp });
+          }
+        }
+        Ok(())
+      })?;
+    } else {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Decoder has been closed",
+      ));
+    }
+
+    Ok(())
+  }
+
   /// Flush the decoder
   /// Returns a Promise that resolves when flushing is complete
   ///
@@ -1261,6 +1385,7 @@ impl AudioDecoder {
     let worker_inner = self.inner.clone();
     let worker_event_state = self.event_state.clone();
     let worker_reset_flag = self.reset_flag.clone();
+    let worker_shutdown_flag = self.shutdown_flag.clone();
 
     // Create synchronization channel to wait for worker to be ready
     let (ready_sender, ready_receiver) = channel::bounded::<()>(1);
@@ -1274,6 +1399,7 @@ impl AudioDecoder {
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
       );
     }));
 
@@ -1344,19 +1470,22 @@ impl AudioDecoder {
     };
 
     // Validate sample rate - must be present and greater than 0
-    match config.sample_rate {
-      Some(sr) if sr > 0.0 => {}
+    let sample_rate = match config.sample_rate {
+      Some(sr) if sr > 0.0 => sr,
       Some(_) => return reject_with_type_error(env, "sampleRate must be greater than 0"),
       None => return reject_with_type_error(env, "sampleRate is required"),
     };
 
     // Validate number of channels - must be present and greater than 0
-    match config.number_of_channels {
-      Some(nc) if nc > 0 => {}
+    let number_of_channels = match config.number_of_channels {
+      Some(nc) if nc > 0 => nc,
       Some(_) => return reject_with_type_error(env, "numberOfChannels must be greater than 0"),
       None => return reject_with_type_error(env, "numberOfChannels is required"),
     };
 
+    // W3C spec: the returned config only echoes recognized members, with defaults filled
+    let config = config.normalize();
+
     env.spawn_future(async move {
       // Parse codec string
       let codec_id = match parse_audio_codec_string(&codec) {
@@ -1368,6 +1497,12 @@ impl AudioDecoder {
           });
         }
       };
+      if telephony_config_error(codec_id, sample_rate, number_of_channels).is_some() {
+        return Ok(AudioDecoderSupport {
+          supported: false,
+          config,
+        });
+      }
 
       // Try to create decoder
       let result = CodecContext::new_decoder(codec_id);
@@ -1485,10 +1620,10 @@ impl AudioDecoder {
         // Call the listener with no arguments (like dequeue callback)
         match &entry.callback {
           EventListenerCallbackType::Weak(tsf) => {
-            tsf.call((), ThreadsafeFunctionCallMode::Blocking);
+            tsf.call((), ThreadsafeFunctionCallMode::NonBlocking);
           }
           EventListenerCallbackType::Strong(tsf) => {
-            tsf.call((), ThreadsafeFunctionCallMode::Blocking);
+            tsf.call((), ThreadsafeFunctionCallMode::NonBlocking);
           }
         }
         if entry.once {
@@ -1558,6 +1693,17 @@ fn parse_audio_codec_string(codec: &str) -> Result<AVCodecID> {
     return Ok(AVCodecID::Alac);
   }
 
+  // Telephony: G.711 µ-law / A-law, G.722
+  if codec_lower == "ulaw" {
+    return Ok(AVCodecID::PcmMulaw);
+  }
+  if codec_lower == "alaw" {
+    return Ok(AVCodecID::PcmAlaw);
+  }
+  if codec_lower == "g722" {
+    return Ok(AVCodecID::AdpcmG722);
+  }
+
   Err(Error::new(
     Status::GenericFailure,
     format!("Unsupported audio codec: {}", codec),
@@ -1569,6 +1715,7 @@ fn decode_audio_chunk_data(
   context: &mut CodecContext,
   data: &[u8],
   timestamp: i64,
+  skip_samples: Option<(u32, u32)>,
 ) -> Result<Vec<Frame>> {
   // Create a packet and fill it with data
   let mut packet = Packet::new().map_err(|e| {
@@ -1592,6 +1739,15 @@ fn decode_audio_chunk_data(
   packet.set_pts(timestamp);
   packet.set_dts(timestamp);
 
+  if let Some((start, end)) = skip_samples {
+    packet.set_skip_samples(start, end).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to set packet skip samples: {}", e),
+      )
+    })?;
+  }
+
   // Decode
   let frames = context
     .decode(Some(&packet))
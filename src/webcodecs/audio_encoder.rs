@@ -4,12 +4,21 @@
 //! See: https://w3c.github.io/webcodecs/#audioencoder-interface
 
 use crate::codec::{
-  AudioEncoderConfig as InternalAudioEncoderConfig, AudioSampleBuffer, CodecContext, Frame,
+  AudioEncoderConfig as InternalAudioEncoderConfig, AudioSampleBuffer, CodecContext, Frame, Packet,
   Resampler, context::get_audio_encoder_name,
 };
 use crate::ffi::{AVCodecID, AVSampleFormat};
-use crate::webcodecs::error::{DOMExceptionName, throw_invalid_state_error, throw_type_error_unit};
-use crate::webcodecs::promise_reject::{reject_with_dom_exception_async, reject_with_type_error};
+use crate::webcodecs::encoder_defaults::audio_encoder_options;
+use crate::webcodecs::encoder_probe::probe_audio_encoder;
+use crate::webcodecs::error::{
+  DOMExceptionName, check_injected_encoder_panic, panic_message, throw_invalid_state_error,
+  throw_type_error_unit,
+};
+use crate::webcodecs::plain_bytes::PlainUint8Array;
+use crate::webcodecs::promise_reject::{
+  reject_with_dom_exception_async, reject_with_type_error, resolve_with,
+};
+use crate::webcodecs::shutdown;
 use crate::webcodecs::{
   AacBitstreamFormat, AudioData, AudioEncoderConfig, AudioEncoderSupport, EncodedAudioChunk,
 };
@@ -20,9 +29,10 @@ use napi::threadsafe_function::{
 };
 use napi_derive::napi;
 use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use std::thread::JoinHandle;
 
 use super::video_encoder::CodecState;
@@ -195,7 +205,8 @@ pub struct AudioDecoderConfigOutput {
   /// Number of channels
   pub number_of_channels: Option<u32>,
   /// Codec description (e.g., AudioSpecificConfig for AAC) - Uint8Array per spec
-  pub description: Option<Uint8Array>,
+  #[napi(ts_type = "Uint8Array")]
+  pub description: Option<PlainUint8Array>,
 }
 
 /// Encode options for audio
@@ -222,7 +233,8 @@ struct AudioEncoderInner {
   context: Option<CodecContext>,
   resampler: Option<Resampler>,
   sample_buffer: Option<AudioSampleBuffer>,
-  frame_count: u64,
+  /// Samples sent to the encoder since `base_timestamp`
+  samples_submitted: u64,
   extradata_sent: bool,
   /// Target sample format for encoder
   target_format: AVSampleFormat,
@@ -234,9 +246,15 @@ struct AudioEncoderInner {
   error_callback: ErrorCallback,
   /// Pending flush response senders (for AbortError on reset)
   pending_flush_senders: Vec<Sender<Result<()>>>,
-  /// Queue of input timestamps for correlation with output packets
-  /// (needed because FFmpeg may buffer frames internally)
-  timestamp_queue: std::collections::VecDeque<i64>,
+  /// Samples covered by the packets output since `base_timestamp`
+  ///
+  /// Output timestamps are derived from this rather than from the input frame
+  /// a packet came from, so encoder delay and flush packets stay continuous.
+  samples_output: u64,
+  /// Encoder delay marked on the first output packet (AAC only)
+  ///
+  /// Opus carries its pre-skip in the description instead.
+  priming_samples: u32,
   /// Base timestamp from the first input AudioData (for timestamp calculation)
   base_timestamp: Option<i64>,
   /// Abort channel senders - reset() sends abort signal through these
@@ -267,6 +285,42 @@ struct AudioEncoderInner {
   cached_flac_decoder_config: Option<(String, f64, u32, Option<Vec<u8>>)>,
 }
 
+impl AudioEncoderInner {
+  /// Mark the encoder delay on the first output packet as skip-samples side
+  /// data, so decoders and muxers can drop the priming samples
+  fn mark_priming(&self, packet: &mut Packet) {
+    if self.samples_output > 0 || self.priming_samples == 0 {
+      return;
+    }
+    let end = packet.skip_samples().map_or(0, |(_, end)| end);
+    if let Err(e) = packet.set_skip_samples(self.priming_samples, end) {
+      tracing::warn!(target: "webcodecs", "Failed to mark audio encoder delay: {}", e);
+    }
+  }
+
+  /// Timestamp and duration (microseconds) of the next output packet
+  ///
+  /// Encoders report packet durations in samples (the time base is
+  /// 1/sample_rate), which is shorter than `frame_size` for a final partial or
+  /// padded frame. Packets without a duration count as a whole frame.
+  fn advance_output(&mut self, packet: &Packet, frame_size: u64, sample_rate: i64) -> (i64, i64) {
+    let samples = if packet.duration() > 0 {
+      packet.duration() as u64
+    } else {
+      frame_size
+    };
+    let to_us = |samples: u64| {
+      (samples as i64 * 1_000_000)
+        .checked_div(sample_rate)
+        .unwrap_or(0)
+    };
+    let timestamp = self.base_timestamp.unwrap_or(0) + to_us(self.samples_output);
+    self.samples_output += samples;
+    let end = self.base_timestamp.unwrap_or(0) + to_us(self.samples_output);
+    (timestamp, end - timestamp)
+  }
+}
+
 /// AudioEncoder - WebCodecs-compliant audio encoder
 ///
 /// Encodes AudioData objects into EncodedAudioChunk objects using FFmpeg.
@@ -311,16 +365,23 @@ pub struct AudioEncoder {
   worker_handle: Option<JoinHandle<()>>,
   /// Reset flag - checked by microtasks to skip sending if reset() was called
   reset_flag: Arc<AtomicBool>,
+  /// Environment teardown flag, shared with every worker this codec spawns
+  shutdown_flag: Arc<AtomicBool>,
 }
 
 impl Drop for AudioEncoder {
   fn drop(&mut self) {
-    // Signal worker to stop
+    // Signal worker to stop, skipping any queued work
+    self.reset_flag.store(true, Ordering::SeqCst);
     self.command_sender = None;
 
-    // Wait for worker to finish (brief block, necessary for safety)
-    if let Some(handle) = self.worker_handle.take() {
-      let _ = handle.join();
+    // Wait for worker to finish. The wait is bounded so a busy worker cannot
+    // hold up process exit; a detached worker still owns the codec state and
+    // releases it when its current command completes.
+    if let Some(handle) = self.worker_handle.take()
+      && !shutdown::join_worker(handle, "AudioEncoder")
+    {
+      return;
     }
 
     // Drain encoder to ensure codec threads finish before context drops.
@@ -342,6 +403,7 @@ impl AudioEncoder {
   /// @param init - Init dictionary containing output and error callbacks
   #[napi(constructor)]
   pub fn new(
+    env: Env,
     #[napi(
       ts_arg_type = "{ output: (chunk: EncodedAudioChunk, metadata?: EncodedAudioChunkMetadata) => void, error: (error: Error) => void }"
     )]
@@ -353,14 +415,15 @@ impl AudioEncoder {
       context: None,
       resampler: None,
       sample_buffer: None,
-      frame_count: 0,
+      samples_submitted: 0,
       extradata_sent: false,
       target_format: AVSampleFormat::Fltp,
       encode_queue_size: 0,
       output_callback: init.output,
       error_callback: init.error,
       pending_flush_senders: Vec::new(),
-      timestamp_queue: std::collections::VecDeque::new(),
+      samples_output: 0,
+      priming_samples: 0,
       base_timestamp: None,
       pending_abort_senders: Vec::new(),
       flush_abort_flag: None,
@@ -384,16 +447,21 @@ impl AudioEncoder {
     // Create reset flag for microtask synchronization
     let reset_flag = Arc::new(AtomicBool::new(false));
 
+    // Set when the environment tears down, so the worker skips queued work
+    let shutdown_flag = shutdown::env_shutdown_flag(&env)?;
+
     // Spawn worker thread
     let worker_inner = inner.clone();
     let worker_event_state = event_state.clone();
     let worker_reset_flag = reset_flag.clone();
+    let worker_shutdown_flag = shutdown_flag.clone();
     let worker_handle = std::thread::spawn(move || {
       Self::worker_loop(
         worker_inner,
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
       );
     });
 
@@ -406,6 +474,7 @@ impl AudioEncoder {
       command_sender: Some(Arc::new(sender)),
       worker_handle: Some(worker_handle),
       reset_flag,
+      shutdown_flag,
     })
   }
 
@@ -415,13 +484,14 @@ impl AudioEncoder {
     event_state: Arc<RwLock<EventListenerState>>,
     receiver: Receiver<EncoderCommand>,
     reset_flag: Arc<AtomicBool>,
+    shutdown_flag: Arc<AtomicBool>,
   ) {
     // Simple blocking recv - channel disconnects when all senders dropped
     // (including microtask cloned senders after they check reset_flag and skip sending)
     while let Ok(command) = receiver.recv() {
-      // Check reset flag before processing each command
-      // If reset() was called, skip remaining encode commands
-      if reset_flag.load(Ordering::SeqCst) {
+      // Check reset and shutdown flags before processing each command
+      // If reset() was called or the environment is shutting down, skip remaining encode commands
+      if reset_flag.load(Ordering::SeqCst) || shutdown_flag.load(Ordering::SeqCst) {
         // Still process flush commands to send responses, but skip encodes
         if let EncoderCommand::Flush(response_sender) = command {
           let _ = response_sender.send(Err(Error::new(
@@ -441,7 +511,13 @@ impl AudioEncoder {
         continue;
       }
 
-      match command {
+      // A panic while processing a command closes the encoder with an
+      // OperationError instead of unwinding out of the worker thread
+      let flush_responder = match &command {
+        EncoderCommand::Flush(response_sender) => Some(response_sender.clone()),
+        _ => None,
+      };
+      let outcome = panic::catch_unwind(AssertUnwindSafe(|| match command {
         EncoderCommand::Encode { frame, timestamp } => {
           Self::process_encode(&inner, &event_state, frame, timestamp);
         }
@@ -452,6 +528,16 @@ impl AudioEncoder {
         EncoderCommand::Reconfigure(config) => {
           Self::process_reconfigure(&inner, &config);
         }
+      }));
+      if let Err(payload) = outcome {
+        let message = format!(
+          "OperationError: Encoder worker panicked: {}",
+          panic_message(&*payload)
+        );
+        Self::recover_from_panic(&inner, &message);
+        if let Some(response_sender) = flush_responder {
+          let _ = response_sender.send(Err(Error::new(Status::GenericFailure, message)));
+        }
       }
     }
   }
@@ -467,6 +553,7 @@ impl AudioEncoder {
       Ok(g) => g,
       Err(_) => return, // Lock poisoned
     };
+    check_injected_encoder_panic(timestamp);
 
     // Check if encoder is still configured
     if guard.state != CodecState::Configured {
@@ -614,15 +701,9 @@ impl AudioEncoder {
         }
       };
 
-      // Calculate timestamp based on base_timestamp (from first input) + frame offset
-      // This ensures timestamps are continuous and the first output has the first input's timestamp
-      let base_ts = guard.base_timestamp.unwrap_or(0);
-      let frame_timestamp =
-        base_ts + (guard.frame_count as i64 * frame_size * 1_000_000) / sample_rate;
-      frame_to_encode.set_pts(frame_timestamp);
-
-      // Push timestamp to queue BEFORE encoding (for output correlation)
-      guard.timestamp_queue.push_back(frame_timestamp);
+      // The FIFO re-slices input AudioData into frame_size frames; the encoder
+      // time base is 1/sample_rate, so the pts is the sample position
+      frame_to_encode.set_pts(guard.samples_submitted as i64);
 
       // Encode the frame
       let context = match guard.context.as_mut() {
@@ -651,24 +732,22 @@ impl AudioEncoder {
         }
       };
 
-      guard.frame_count += 1;
-
-      // Calculate duration per frame in microseconds
-      let duration_us = (frame_size * 1_000_000) / sample_rate;
+      guard.samples_submitted += frame_size as u64;
 
       // Process output packets - call callback for each
-      // Pop timestamp from queue to preserve original input timestamp
       let adts_params = if guard.use_adts {
         guard.adts_params
       } else {
         None
       };
-      for packet in packets {
-        let output_timestamp = guard.timestamp_queue.pop_front();
+      for mut packet in packets {
+        guard.mark_priming(&mut packet);
+        let (output_timestamp, duration_us) =
+          guard.advance_output(&packet, frame_size as u64, sample_rate);
         let chunk = EncodedAudioChunk::from_packet_with_adts(
           packet,
           Some(duration_us),
-          output_timestamp,
+          Some(output_timestamp),
           adts_params,
         );
 
@@ -694,7 +773,7 @@ impl AudioEncoder {
               codec: codec_string.clone(),
               sample_rate: Some(target_sample_rate),
               number_of_channels: Some(target_channels),
-              description: extradata.clone().map(Uint8Array::from),
+              description: extradata.clone().map(PlainUint8Array::from),
             }),
           }
         } else if !guard.extradata_sent {
@@ -716,7 +795,7 @@ impl AudioEncoder {
               codec: codec_string.clone(),
               sample_rate: Some(target_sample_rate),
               number_of_channels: Some(target_channels),
-              description: extradata.clone().map(Uint8Array::from),
+              description: extradata.clone().map(PlainUint8Array::from),
             }),
           }
         } else {
@@ -800,81 +879,51 @@ impl AudioEncoder {
       let get_flac_extradata =
         |ctx: &CodecContext| -> Option<Vec<u8>> { ctx.extradata().map(prepend_flac_header) };
 
-      // Flush any remaining samples in buffer
-      if let Some(ref mut sample_buffer) = guard.sample_buffer
-        && let Ok(Some(mut frame)) = sample_buffer.flush()
-      {
-        // Set timestamp using base_timestamp
-        let frame_size = sample_buffer.frame_size() as i64;
-        let sample_rate = sample_buffer.sample_rate() as i64;
-        let base_ts = guard.base_timestamp.unwrap_or(0);
-        let frame_timestamp =
-          base_ts + (guard.frame_count as i64 * frame_size * 1_000_000) / sample_rate;
-        frame.set_pts(frame_timestamp);
-
-        // Push timestamp to queue for output correlation
-        guard.timestamp_queue.push_back(frame_timestamp);
-
-        let context = match guard.context.as_mut() {
-          Some(ctx) => ctx,
-          None => {
-            Self::report_error(&mut guard, "No encoder context");
-            return Ok(());
-          }
-        };
-
-        if let Ok(packets) = context.encode(Some(&frame)) {
-          let duration_us = (frame.nb_samples() as i64 * 1_000_000) / sample_rate;
-          let adts_params = if guard.use_adts {
-            guard.adts_params
-          } else {
-            None
+      let (frame_size, sample_rate) = match guard.sample_buffer.as_ref() {
+        Some(buf) => (buf.frame_size() as u64, buf.sample_rate() as i64),
+        None => (0, 0),
+      };
+      let accepts_partial_frame = guard
+        .context
+        .as_ref()
+        .is_some_and(|ctx| ctx.accepts_partial_audio_frame());
+
+      // Encode the samples left in the FIFO as one last frame. Encoders that
+      // only take whole frames get it padded with silence, and the padding is
+      // signalled on the final packet so decoders can trim it.
+      let remainder = match guard.sample_buffer.as_mut() {
+        Some(buf) if accepts_partial_frame => buf.flush().map(|f| f.map(|f| (f, 0))),
+        Some(buf) => buf.flush_padded(),
+        None => Ok(None),
+      };
+      let mut packets: Vec<Packet> = Vec::new();
+      let mut trailing_padding = 0;
+      match remainder {
+        Ok(Some((mut frame, padding))) => {
+          frame.set_pts(guard.samples_submitted as i64);
+          guard.samples_submitted += frame.nb_samples() as u64;
+          trailing_padding = padding;
+
+          let context = match guard.context.as_mut() {
+            Some(ctx) => ctx,
+            None => {
+              Self::report_error(&mut guard, "No encoder context");
+              return Ok(());
+            }
           };
-          for packet in packets {
-            let output_timestamp = guard.timestamp_queue.pop_front();
-            let chunk = EncodedAudioChunk::from_packet_with_adts(
-              packet,
-              Some(duration_us),
-              output_timestamp,
-              adts_params,
-            );
-            // Create decoderConfig: FLAC on every chunk, others only on first chunk
-            let decoder_config = if is_flac {
-              // FLAC: Always include decoderConfig with fresh extradata
-              let extradata = guard.context.as_ref().and_then(get_flac_extradata);
-              Some(AudioDecoderConfigOutput {
-                codec: codec_string.clone(),
-                sample_rate: Some(target_sample_rate),
-                number_of_channels: Some(target_channels),
-                description: extradata.map(Uint8Array::from),
-              })
-            } else if !guard.extradata_sent {
-              // First chunk: Include decoderConfig
-              guard.extradata_sent = true;
-              // ADTS format: No description (metadata in frame headers)
-              // Raw AAC: Include AudioSpecificConfig
-              let extradata = if guard.use_adts {
-                None
-              } else {
-                guard
-                  .context
-                  .as_ref()
-                  .and_then(|ctx| ctx.extradata().map(|d| d.to_vec()))
-              };
-              Some(AudioDecoderConfigOutput {
-                codec: codec_string.clone(),
-                sample_rate: Some(target_sample_rate),
-                number_of_channels: Some(target_channels),
-                description: extradata.map(Uint8Array::from),
-              })
-            } else {
-              None
-            };
-            let metadata = EncodedAudioChunkMetadata { decoder_config };
-            // Always queue during flush for synchronous delivery
-            guard.pending_chunks.push((chunk, metadata));
+          match context.encode(Some(&frame)) {
+            Ok(pkts) => packets.extend(pkts),
+            Err(e) => {
+              Self::report_error(&mut guard, &format!("Encode failed: {}", e));
+              return Ok(());
+            }
           }
         }
+        Ok(None) => {}
+        Err(e) => {
+          Self::report_error(&mut guard, &format!("Failed to get frame: {}", e));
+          return Ok(());
+        }
       }
 
       // Flush encoder
@@ -886,13 +935,29 @@ impl AudioEncoder {
         }
       };
 
-      let packets = match context.flush_encoder() {
-        Ok(pkts) => pkts,
+      match context.flush_encoder() {
+        Ok(pkts) => packets.extend(pkts),
         Err(e) => {
           Self::report_error(&mut guard, &format!("Flush failed: {}", e));
           return Ok(());
         }
-      };
+      }
+
+      if trailing_padding > 0
+        && let Some(last) = packets.last_mut()
+        && last.skip_samples().is_none()
+      {
+        let padding = trailing_padding as i64;
+        let duration = if last.duration() > 0 {
+          last.duration()
+        } else {
+          frame_size as i64
+        };
+        last.set_duration((duration - padding).max(0));
+        if let Err(e) = last.set_skip_samples(0, trailing_padding as u32) {
+          tracing::warn!(target: "webcodecs", "Failed to mark trailing audio padding: {}", e);
+        }
+      }
 
       // Queue remaining packets for synchronous delivery in resolver
       let adts_params = if guard.use_adts {
@@ -900,10 +965,16 @@ impl AudioEncoder {
       } else {
         None
       };
-      for packet in packets {
-        let output_timestamp = guard.timestamp_queue.pop_front();
-        let chunk =
-          EncodedAudioChunk::from_packet_with_adts(packet, None, output_timestamp, adts_params);
+      for mut packet in packets {
+        guard.mark_priming(&mut packet);
+        let (output_timestamp, duration_us) =
+          guard.advance_output(&packet, frame_size, sample_rate);
+        let chunk = EncodedAudioChunk::from_packet_with_adts(
+          packet,
+          Some(duration_us),
+          Some(output_timestamp),
+          adts_params,
+        );
         // Create decoderConfig: FLAC on every chunk, others only on first chunk
         let decoder_config = if is_flac {
           // FLAC: Always include decoderConfig with fresh extradata
@@ -912,7 +983,7 @@ impl AudioEncoder {
             codec: codec_string.clone(),
             sample_rate: Some(target_sample_rate),
             number_of_channels: Some(target_channels),
-            description: extradata.map(Uint8Array::from),
+            description: extradata.map(PlainUint8Array::from),
           })
         } else if !guard.extradata_sent {
           // First chunk: Include decoderConfig
@@ -931,7 +1002,7 @@ impl AudioEncoder {
             codec: codec_string.clone(),
             sample_rate: Some(target_sample_rate),
             number_of_channels: Some(target_channels),
-            description: extradata.map(Uint8Array::from),
+            description: extradata.map(PlainUint8Array::from),
           })
         } else {
           None
@@ -940,6 +1011,11 @@ impl AudioEncoder {
         // Always queue during flush for synchronous delivery
         guard.pending_chunks.push((chunk, metadata));
       }
+
+      // Input after the flush starts a new timeline at its own timestamp
+      guard.base_timestamp = None;
+      guard.samples_submitted = 0;
+      guard.samples_output = 0;
     } // mutex released here
 
     // Reset encoder state so it can accept more data (per W3C spec, flush should leave
@@ -973,8 +1049,8 @@ impl AudioEncoder {
 
     // Clear work-related state
     guard.encode_queue_size = 0;
-    guard.timestamp_queue.clear();
-    guard.frame_count = 0;
+    guard.samples_output = 0;
+    guard.samples_submitted = 0;
     guard.extradata_sent = false;
     guard.base_timestamp = None;
 
@@ -1013,6 +1089,10 @@ impl AudioEncoder {
         return;
       }
     };
+    if let Some(message) = telephony_config_error(codec_id, sample_rate, number_of_channels) {
+      Self::report_error(&mut guard, &message);
+      return;
+    }
 
     // Get encoder name (prefer external libraries for better quality)
     let encoder_name = get_audio_encoder_name(codec_id);
@@ -1046,6 +1126,12 @@ impl AudioEncoder {
       Self::report_error(&mut guard, &format!("Failed to configure encoder: {}", e));
       return;
     }
+    let ffmpeg_options = audio_encoder_options(config.ffmpeg_options.as_ref());
+    context.set_options(
+      ffmpeg_options
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str())),
+    );
 
     // Open the encoder
     if let Err(e) = context.open() {
@@ -1090,6 +1176,7 @@ impl AudioEncoder {
     };
 
     // Update state
+    guard.priming_samples = if is_aac { context.initial_padding() } else { 0 };
     guard.context = Some(context);
     guard.sample_buffer = Some(sample_buffer);
     guard.target_format = target_format;
@@ -1100,6 +1187,49 @@ impl AudioEncoder {
     guard.cached_flac_decoder_config = None;
   }
 
+  /// Fail configure() for a valid but unsupported config
+  ///
+  /// W3C spec: configure() only throws for invalid configs. Support is checked in
+  /// a queued task, so the encoder stays "configured" until that task closes it
+  /// with the error; a flush() issued in between rejects with the same error.
+  fn fail_configure(
+    &self,
+    env: &Env,
+    mut inner: MutexGuard<'_, AudioEncoderInner>,
+    error_msg: &str,
+  ) -> Result<()> {
+    inner.state = CodecState::Configured;
+    drop(inner);
+
+    let inner = self.inner.clone();
+    let error_msg = error_msg.to_string();
+    PromiseRaw::resolve(env, ())?.then(move |_| {
+      // reset()/close() before the task runs discards the pending configure
+      if let Ok(mut guard) = inner.lock()
+        && guard.state == CodecState::Configured
+      {
+        for sender in guard.pending_flush_senders.drain(..) {
+          let _ = sender.send(Err(Error::new(Status::GenericFailure, error_msg.clone())));
+        }
+        Self::report_error(&mut guard, &error_msg);
+      }
+      Ok(())
+    })?;
+    Ok(())
+  }
+
+  /// Close the encoder after a panic on the worker thread
+  ///
+  /// The panic may have interrupted an update of the shared state, so the
+  /// lock's poison is cleared and the codec context is dropped, not reused.
+  fn recover_from_panic(inner: &Mutex<AudioEncoderInner>, message: &str) {
+    inner.clear_poison();
+    if let Ok(mut guard) = inner.lock() {
+      guard.context = None;
+      Self::report_error(&mut guard, message);
+    }
+  }
+
   /// Report an error via callback and close the encoder
   fn report_error(inner: &mut AudioEncoderInner, error_msg: &str) {
     // Log the error at warn level for debugging (visible even if JS callback fails)
@@ -1244,32 +1374,14 @@ impl AudioEncoder {
     // W3C WebCodecs spec: Validate config synchronously, throw TypeError for invalid
     // https://w3c.github.io/webcodecs/#dom-audioencoder-configure
 
-    // Validate codec - must be present and not empty
-    let codec = match &config.codec {
-      Some(c) if !c.is_empty() => c.clone(),
-      _ => return throw_type_error_unit(&env, "codec is required"),
-    };
-
-    // Validate sample rate - must be present and greater than 0
-    let sample_rate = match config.sample_rate {
-      Some(sr) if sr > 0.0 => sr,
-      Some(_) => return throw_type_error_unit(&env, "sampleRate must be greater than 0"),
-      None => return throw_type_error_unit(&env, "sampleRate is required"),
-    };
-
-    // Validate number of channels - must be present and greater than 0
-    let number_of_channels = match config.number_of_channels {
-      Some(nc) if nc > 0 => nc,
-      Some(_) => return throw_type_error_unit(&env, "numberOfChannels must be greater than 0"),
-      None => return throw_type_error_unit(&env, "numberOfChannels is required"),
-    };
-
-    // Validate bitrate if specified
-    if let Some(bitrate) = config.bitrate
-      && bitrate <= 0.0
-    {
-      return throw_type_error_unit(&env, "bitrate must be greater than 0");
+    // Structural problems throw synchronously; support is checked below and
+    // reported asynchronously through the error callback
+    if let Some(message) = config.validity_error() {
+      return throw_type_error_unit(&env, &message);
     }
+    let codec = config.codec.clone().unwrap_or_default();
+    let sample_rate = config.sample_rate.unwrap_or_default();
+    let number_of_channels = config.number_of_channels.unwrap_or_default();
 
     let mut inner = self
       .inner
@@ -1285,16 +1397,19 @@ impl AudioEncoder {
     // This ensures pending encode commands are processed before reconfiguration
     if inner.state == CodecState::Configured {
       // Validate codec synchronously before queueing
-      let _codec_id = match parse_audio_codec_string(&codec) {
+      let codec_id = match parse_audio_codec_string(&codec) {
         Ok(id) => id,
         Err(e) => {
-          Self::report_error(
-            &mut inner,
+          return self.fail_configure(
+            &env,
+            inner,
             &format!("NotSupportedError: Invalid codec: {}", e),
           );
-          return Ok(());
         }
       };
+      if let Some(message) = telephony_config_error(codec_id, sample_rate, number_of_channels) {
+        return self.fail_configure(&env, inner, &message);
+      }
 
       // Store config for immediate property reads
       inner.config = Some(config.clone());
@@ -1319,13 +1434,16 @@ impl AudioEncoder {
     let codec_id = match parse_audio_codec_string(&codec) {
       Ok(id) => id,
       Err(e) => {
-        Self::report_error(
-          &mut inner,
+        return self.fail_configure(
+          &env,
+          inner,
           &format!("NotSupportedError: Invalid codec: {}", e),
         );
-        return Ok(());
       }
     };
+    if let Some(message) = telephony_config_error(codec_id, sample_rate, number_of_channels) {
+      return self.fail_configure(&env, inner, &message);
+    }
 
     // Get encoder name (prefer external libraries for better quality)
     let encoder_name = get_audio_encoder_name(codec_id);
@@ -1338,8 +1456,7 @@ impl AudioEncoder {
     } {
       Ok(ctx) => ctx,
       Err(e) => {
-        Self::report_error(&mut inner, &format!("Failed to create encoder: {}", e));
-        return Ok(());
+        return self.fail_configure(&env, inner, &format!("Failed to create encoder: {}", e));
       }
     };
 
@@ -1356,14 +1473,18 @@ impl AudioEncoder {
     };
 
     if let Err(e) = context.configure_audio_encoder(&encoder_config) {
-      Self::report_error(&mut inner, &format!("Failed to configure encoder: {}", e));
-      return Ok(());
+      return self.fail_configure(&env, inner, &format!("Failed to configure encoder: {}", e));
     }
+    let ffmpeg_options = audio_encoder_options(config.ffmpeg_options.as_ref());
+    context.set_options(
+      ffmpeg_options
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str())),
+    );
 
     // Open the encoder
     if let Err(e) = context.open() {
-      Self::report_error(&mut inner, &format!("Failed to open encoder: {}", e));
-      return Ok(());
+      return self.fail_configure(&env, inner, &format!("Failed to open encoder: {}", e));
     }
 
     // Get the actual frame size from the encoder
@@ -1383,17 +1504,19 @@ impl AudioEncoder {
       target_format,
     );
 
+    let is_aac = codec.to_lowercase().starts_with("mp4a.40") || codec.to_lowercase() == "aac";
+    inner.priming_samples = if is_aac { context.initial_padding() } else { 0 };
     inner.context = Some(context);
     inner.sample_buffer = Some(sample_buffer);
     inner.target_format = target_format;
     inner.state = CodecState::Configured;
     inner.extradata_sent = false;
-    inner.frame_count = 0;
+    inner.samples_submitted = 0;
+    inner.samples_output = 0;
     inner.resampler = None;
     inner.encode_queue_size = 0;
 
     // Check if AAC ADTS format is requested
-    let is_aac = codec.to_lowercase().starts_with("mp4a.40") || codec.to_lowercase() == "aac";
     inner.use_adts = is_aac
       && config
         .aac
@@ -1420,6 +1543,7 @@ impl AudioEncoder {
       let worker_inner = self.inner.clone();
       let worker_event_state = self.event_state.clone();
       let worker_reset_flag = self.reset_flag.clone();
+      let worker_shutdown_flag = self.shutdown_flag.clone();
       drop(inner); // Release lock before spawning thread
       self.worker_handle = Some(std::thread::spawn(move || {
         Self::worker_loop(
@@ -1427,6 +1551,7 @@ impl AudioEncoder {
           worker_event_state,
           receiver,
           worker_reset_flag,
+          worker_shutdown_flag,
         );
       }));
     }
@@ -1790,11 +1915,11 @@ impl AudioEncoder {
     inner.sample_buffer = None;
     inner.config = None;
     inner.state = CodecState::Unconfigured;
-    inner.frame_count = 0;
+    inner.samples_submitted = 0;
     inner.extradata_sent = false;
     inner.cached_flac_decoder_config = None;
     inner.encode_queue_size = 0;
-    inner.timestamp_queue.clear();
+    inner.samples_output = 0;
     inner.base_timestamp = None;
     // Clear any remaining abort senders (shouldn't be any, but just in case)
     inner.pending_abort_senders.clear();
@@ -1812,6 +1937,7 @@ impl AudioEncoder {
     let worker_inner = self.inner.clone();
     let worker_event_state = self.event_state.clone();
     let worker_reset_flag = self.reset_flag.clone();
+    let worker_shutdown_flag = self.shutdown_flag.clone();
     drop(inner); // Release lock before spawning thread
     self.worker_handle = Some(std::thread::spawn(move || {
       Self::worker_loop(
@@ -1819,6 +1945,7 @@ impl AudioEncoder {
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
       );
     }));
 
@@ -1880,58 +2007,36 @@ impl AudioEncoder {
     // W3C WebCodecs spec: Validate config, reject with TypeError for invalid
     // https://w3c.github.io/webcodecs/#dom-audioencoder-isconfigsupported
 
-    // Validate codec - must be present and not empty
-    let codec = match &config.codec {
-      Some(c) if !c.is_empty() => c.clone(),
-      Some(_) => return reject_with_type_error(env, "codec is required"),
-      None => return reject_with_type_error(env, "codec is required"),
-    };
-
-    // Validate sample rate - must be present and greater than 0
-    match config.sample_rate {
-      Some(sr) if sr > 0.0 => {}
-      Some(_) => return reject_with_type_error(env, "sampleRate must be greater than 0"),
-      None => return reject_with_type_error(env, "sampleRate is required"),
+    // W3C spec: structurally invalid configs reject with TypeError
+    if let Some(message) = config.validity_error() {
+      return reject_with_type_error(env, &message);
     }
+    let codec = config.codec.clone().unwrap_or_default();
+    let sample_rate = config.sample_rate.unwrap_or_default();
+    let number_of_channels = config.number_of_channels.unwrap_or_default();
 
-    // Validate number of channels - must be present and greater than 0
-    match config.number_of_channels {
-      Some(nc) if nc > 0 => {}
-      Some(_) => return reject_with_type_error(env, "numberOfChannels must be greater than 0"),
-      None => return reject_with_type_error(env, "numberOfChannels is required"),
-    }
-
-    // Validate bitrate if specified
-    if let Some(bitrate) = config.bitrate
-      && bitrate <= 0.0
-    {
-      return reject_with_type_error(env, "bitrate must be greater than 0");
-    }
+    // W3C spec: the returned config only echoes recognized members, with defaults filled
+    let config = config.normalize();
 
-    env.spawn_future(async move {
-      // Parse codec string
-      let codec_id = match parse_audio_codec_string(&codec) {
-        Ok(id) => id,
-        Err(_) => {
-          return Ok(AudioEncoderSupport {
+    // Unknown codecs and telephony limits settle without touching FFmpeg
+    let codec_id = match parse_audio_codec_string(&codec) {
+      Ok(id) if telephony_config_error(id, sample_rate, number_of_channels).is_none() => id,
+      _ => {
+        return resolve_with(
+          env,
+          AudioEncoderSupport {
             supported: false,
             config,
-          });
-        }
-      };
+          },
+        );
+      }
+    };
 
+    env.spawn_future(async move {
       // Try to find encoder
-      let encoder_name = get_audio_encoder_name(codec_id);
-      let result = if let Some(name) = encoder_name {
-        CodecContext::new_encoder_by_name(name).or_else(|_| CodecContext::new_encoder(codec_id))
-      } else {
-        CodecContext::new_encoder(codec_id)
-      };
+      let supported = probe_audio_encoder(codec_id, get_audio_encoder_name(codec_id));
 
-      Ok(AudioEncoderSupport {
-        supported: result.is_ok(),
-        config,
-      })
+      Ok(AudioEncoderSupport { supported, config })
     })
   }
 
@@ -2112,6 +2217,17 @@ fn parse_audio_codec_string(codec: &str) -> Result<AVCodecID> {
     return Ok(AVCodecID::Alac);
   }
 
+  // Telephony: G.711 µ-law / A-law, G.722
+  if codec_lower == "ulaw" {
+    return Ok(AVCodecID::PcmMulaw);
+  }
+  if codec_lower == "alaw" {
+    return Ok(AVCodecID::PcmAlaw);
+  }
+  if codec_lower == "g722" {
+    return Ok(AVCodecID::AdpcmG722);
+  }
+
   Err(Error::new(
     Status::GenericFailure,
     format!("Unsupported audio codec: {}", codec),
@@ -2132,10 +2248,34 @@ fn get_encoder_sample_format(codec_id: AVCodecID) -> AVSampleFormat {
     AVCodecID::PcmF32be => AVSampleFormat::Flt,
     AVCodecID::Ac3 => AVSampleFormat::Fltp,
     AVCodecID::Alac => AVSampleFormat::S16p,
+    AVCodecID::PcmMulaw | AVCodecID::PcmAlaw | AVCodecID::AdpcmG722 => AVSampleFormat::S16,
     _ => AVSampleFormat::Fltp, // Default to float planar
   }
 }
 
+/// Check the fixed sample rate and channel count of a telephony codec
+///
+/// G.711 (ulaw/alaw) runs at 8 kHz and G.722 at 16 kHz; FFmpeg's G.722 codec is
+/// mono only. Returns a NotSupportedError message for a mismatching config.
+pub(crate) fn telephony_config_error(
+  codec_id: AVCodecID,
+  sample_rate: f64,
+  number_of_channels: u32,
+) -> Option<String> {
+  let required_rate = codec_id.fixed_sample_rate()?;
+  let codec = codec_id.to_webcodecs_codec();
+  if sample_rate != required_rate as f64 {
+    return Some(format!(
+      "NotSupportedError: {} requires a sampleRate of {}",
+      codec, required_rate
+    ));
+  }
+  if codec_id == AVCodecID::AdpcmG722 && number_of_channels != 1 {
+    return Some(format!("NotSupportedError: {} requires mono audio", codec));
+  }
+  None
+}
+
 /// Get AAC sample rate index for ADTS header
 /// Returns the index corresponding to the sample rate in the ADTS header's
 /// sampling_frequency_index field (4 bits, values 0-12)
@@ -2166,7 +2306,7 @@ fn get_aac_sample_rate_index(sample_rate: u32) -> u8 {
 /// - "fLaC" magic (4 bytes)
 /// - METADATA_BLOCK_HEADER: last-block-flag(1) + type(7) + length(24) (4 bytes)
 /// - METADATA_BLOCK_DATA (STREAMINFO = 34 bytes)
-fn prepend_flac_header(streaminfo: &[u8]) -> Vec<u8> {
+pub(crate) fn prepend_flac_header(streaminfo: &[u8]) -> Vec<u8> {
   // Only prepend if this looks like raw STREAMINFO (34 bytes, doesn't start with "fLaC")
   if streaminfo.len() != 34 || streaminfo.starts_with(b"fLaC") {
     return streaminfo.to_vec();
@@ -0,0 +1,492 @@
+//! extractAudio / AudioExtractor - decoded PCM from media files
+//!
+//! Demuxes the first audio track of a container (e.g. the AAC track of an
+//! MP4), decodes it, resamples/downmixes to f32-planar and trims the result
+//! to a time range. Everything runs natively on the blocking pool, so
+//! getting PCM out of a video file does not need a JS demux -> decode ->
+//! copyTo round trip per chunk.
+
+use crate::codec::{
+  AudioDecoderConfig, CodecContext, Frame, Resampler,
+  demuxer::{DemuxerContext, MediaType},
+};
+use crate::ffi::{AV_NOPTS_VALUE, AVRational, AVSampleFormat, avutil::av_rescale_q};
+use crate::webcodecs::audio_data::AudioData;
+use crate::webcodecs::buffer_view::BufferView;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::sync::{Arc, Mutex};
+
+/// Default length of each AudioExtractor chunk
+const DEFAULT_CHUNK_DURATION_US: i64 = 1_000_000;
+
+/// Largest result extractAudio() will build in one AudioData (256 MiB of f32 samples)
+const MAX_SINGLE_EXTRACT_BYTES: usize = 256 * 1024 * 1024;
+
+/// Samples requested from the resampler when draining it at end of stream
+const RESAMPLER_FLUSH_SAMPLES: u32 = 4096;
+
+/// Options for extractAudio() and AudioExtractor
+#[napi(object)]
+#[derive(Default)]
+pub struct ExtractAudioOptions {
+  /// Start of the range in microseconds (default: 0)
+  pub start_us: Option<i64>,
+  /// End of the range in microseconds, exclusive (default: end of stream)
+  pub end_us: Option<i64>,
+  /// Output sample rate (default: the track's sample rate)
+  pub target_sample_rate: Option<u32>,
+  /// Downmix to a single channel (default: false)
+  pub mono: Option<bool>,
+  /// Length of each AudioData yielded by AudioExtractor, in microseconds (default: 1000000)
+  pub chunk_duration_us: Option<i64>,
+}
+
+/// Demux/decode/resample pipeline shared by extractAudio() and AudioExtractor
+struct AudioExtractorInner {
+  demuxer: DemuxerContext,
+  decoder: CodecContext,
+  stream_index: i32,
+  time_base: AVRational,
+  /// Created from the first decoded frame, once the decoder's output layout is known
+  resampler: Option<Resampler>,
+  dst_channels: u32,
+  dst_sample_rate: u32,
+  start_us: i64,
+  end_us: Option<i64>,
+  /// Resampled samples inside the range that have not been emitted yet, one Vec per channel
+  pending: Vec<Vec<f32>>,
+  /// Timestamp of the first pending sample
+  pending_start_us: i64,
+  /// Timestamp of the next sample the resampler will produce
+  next_sample_us: Option<i64>,
+  /// No more samples will be produced (EOF, or past end of range)
+  finished: bool,
+}
+
+impl AudioExtractorInner {
+  fn open(source: Either<String, BufferView>, options: &ExtractAudioOptions) -> Result<Self> {
+    let start_us = options.start_us.unwrap_or(0);
+    if start_us < 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "startUs must not be negative",
+      ));
+    }
+    if let Some(end_us) = options.end_us
+      && end_us <= start_us
+    {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "endUs must be greater than startUs",
+      ));
+    }
+    if options.target_sample_rate == Some(0) {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "targetSampleRate must be greater than 0",
+      ));
+    }
+
+    let mut demuxer = match source {
+      Either::A(path) => DemuxerContext::open_file(&path).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open {}: {}", path, e),
+        )
+      })?,
+      Either::B(buffer) => DemuxerContext::open_buffer(buffer.into_inner()).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open buffer: {}", e),
+        )
+      })?,
+    };
+
+    let stream = demuxer
+      .find_best_stream(MediaType::Audio)
+      .cloned()
+      .ok_or_else(|| Error::new(Status::InvalidArg, "Input has no audio track"))?;
+    let src_channels = stream.channels.unwrap_or(0);
+    let src_sample_rate = stream.sample_rate.unwrap_or(0);
+
+    let mut decoder = CodecContext::new_decoder(stream.codec_id).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to create decoder: {}", e),
+      )
+    })?;
+    decoder
+      .configure_audio_decoder(&AudioDecoderConfig {
+        codec_id: stream.codec_id,
+        sample_rate: src_sample_rate,
+        channels: src_channels,
+        thread_count: 0,
+        extradata: stream.extradata.clone(),
+      })
+      .and_then(|_| decoder.open())
+      .map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open decoder: {}", e),
+        )
+      })?;
+
+    let time_base = AVRational {
+      num: stream.time_base.0,
+      den: stream.time_base.1,
+    };
+    if start_us > 0 {
+      let ts = unsafe { av_rescale_q(start_us, AVRational::MICROSECONDS, time_base) };
+      demuxer.seek(stream.index, ts, true).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to seek to {}us: {}", start_us, e),
+        )
+      })?;
+    }
+
+    let dst_channels = if options.mono.unwrap_or(false) {
+      1
+    } else {
+      src_channels.max(1)
+    };
+
+    Ok(Self {
+      demuxer,
+      decoder,
+      stream_index: stream.index,
+      time_base,
+      resampler: None,
+      dst_channels,
+      dst_sample_rate: options.target_sample_rate.unwrap_or(src_sample_rate),
+      start_us,
+      end_us: options.end_us,
+      pending: vec![Vec::new(); dst_channels as usize],
+      pending_start_us: start_us,
+      next_sample_us: None,
+      finished: false,
+    })
+  }
+
+  fn pending_frames(&self) -> usize {
+    self.pending[0].len()
+  }
+
+  /// Convert a duration in microseconds to a sample count at the output rate
+  fn us_to_frames(&self, us: i64) -> usize {
+    (us.max(0) as u128 * self.dst_sample_rate as u128 / 1_000_000) as usize
+  }
+
+  /// Decode until at least `frames` samples are pending or the range is exhausted
+  fn fill(&mut self, frames: usize) -> Result<()> {
+    while !self.finished && self.pending_frames() < frames {
+      match self.demuxer.read_packet().map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to read packet: {}", e),
+        )
+      })? {
+        Some((packet, index)) => {
+          if index != self.stream_index {
+            continue;
+          }
+          let decoded = self.decoder.decode(Some(&packet)).map_err(decode_error)?;
+          for frame in decoded {
+            self.push_decoded(&frame)?;
+          }
+        }
+        None => {
+          let decoded = self.decoder.flush_decoder().map_err(decode_error)?;
+          for frame in decoded {
+            self.push_decoded(&frame)?;
+          }
+          self.drain_resampler()?;
+          self.finished = true;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Resample a decoded frame and keep the part that falls inside the range
+  fn push_decoded(&mut self, frame: &Frame) -> Result<()> {
+    if self.finished {
+      return Ok(());
+    }
+    if self.resampler.is_none() {
+      if self.dst_sample_rate == 0 {
+        self.dst_sample_rate = frame.sample_rate();
+      }
+      let resampler = Resampler::new(
+        frame.channels(),
+        frame.sample_rate(),
+        frame.sample_format(),
+        self.dst_channels,
+        self.dst_sample_rate,
+        AVSampleFormat::Fltp,
+      )
+      .map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to create resampler: {}", e),
+        )
+      })?;
+      self.resampler = Some(resampler);
+    }
+    if self.next_sample_us.is_none() {
+      let pts = frame.pts();
+      self.next_sample_us = Some(if pts == AV_NOPTS_VALUE {
+        0
+      } else {
+        unsafe { av_rescale_q(pts, self.time_base, AVRational::MICROSECONDS) }
+      });
+    }
+
+    let converted = self
+      .resampler
+      .as_mut()
+      .map(|r| r.convert_alloc(frame))
+      .transpose()
+      .map_err(resample_error)?;
+    if let Some(converted) = converted {
+      self.append(&converted);
+    }
+    Ok(())
+  }
+
+  /// Drain the samples the resampler holds back for filtering
+  fn drain_resampler(&mut self) -> Result<()> {
+    if self.resampler.is_none() {
+      return Ok(());
+    }
+    loop {
+      let mut out = Frame::new_audio(
+        RESAMPLER_FLUSH_SAMPLES,
+        self.dst_channels,
+        self.dst_sample_rate,
+        AVSampleFormat::Fltp,
+      )
+      .map_err(resample_error)?;
+      let flushed = match self.resampler.as_mut() {
+        Some(resampler) => resampler.flush(&mut out).map_err(resample_error)?,
+        None => 0,
+      };
+      if flushed == 0 {
+        return Ok(());
+      }
+      self.append(&out);
+      if self.finished {
+        return Ok(());
+      }
+    }
+  }
+
+  /// Append resampled f32-planar samples, dropping those outside [startUs, endUs)
+  fn append(&mut self, frame: &Frame) {
+    let count = frame.nb_samples() as usize;
+    let first_us = self.next_sample_us.unwrap_or(0);
+    let rate = self.dst_sample_rate as i64;
+    self.next_sample_us = Some(first_us + count as i64 * 1_000_000 / rate);
+
+    // Samples before startUs (from seeking to the preceding packet)
+    let skip = if first_us < self.start_us {
+      self.us_to_frames(self.start_us - first_us).min(count)
+    } else {
+      0
+    };
+    let mut take = count - skip;
+    if let Some(end_us) = self.end_us {
+      let begin_us = first_us.max(self.start_us);
+      let remaining = self.us_to_frames(end_us - begin_us);
+      if remaining <= take {
+        take = remaining;
+        self.finished = true;
+      }
+    }
+    if take == 0 {
+      return;
+    }
+    if self.pending_frames() == 0 {
+      self.pending_start_us = first_us.max(self.start_us);
+    }
+
+    for (ch, pending) in self.pending.iter_mut().enumerate() {
+      // SAFETY: each plane of an f32-planar frame holds nb_samples floats
+      let samples =
+        unsafe { std::slice::from_raw_parts(frame.audio_data(ch) as *const f32, count) };
+      pending.extend_from_slice(&samples[skip..skip + take]);
+    }
+  }
+
+  /// Take up to `max_frames` pending samples as one f32-planar AudioData
+  fn take_chunk(&mut self, max_frames: usize) -> Result<Option<AudioData>> {
+    let frames = self.pending_frames().min(max_frames);
+    if frames == 0 {
+      return Ok(None);
+    }
+    let mut out = Frame::new_audio(
+      frames as u32,
+      self.dst_channels,
+      self.dst_sample_rate,
+      AVSampleFormat::Fltp,
+    )
+    .map_err(resample_error)?;
+    for (ch, pending) in self.pending.iter_mut().enumerate() {
+      // SAFETY: the frame was allocated with `frames` samples per plane
+      let dst =
+        unsafe { std::slice::from_raw_parts_mut(out.audio_data_mut(ch) as *mut f32, frames) };
+      dst.copy_from_slice(&pending[..frames]);
+      pending.drain(..frames);
+    }
+
+    let timestamp = self.pending_start_us;
+    out.set_pts(timestamp);
+    self.pending_start_us += frames as i64 * 1_000_000 / self.dst_sample_rate as i64;
+    Ok(Some(AudioData::from_internal(out, timestamp)))
+  }
+
+  /// Decode and return the next chunk of at most `max_frames` samples
+  fn next_chunk(&mut self, max_frames: usize) -> Result<Option<AudioData>> {
+    self.fill(max_frames)?;
+    self.take_chunk(max_frames)
+  }
+
+  /// Decode the whole range into a single AudioData
+  fn extract_all(&mut self) -> Result<AudioData> {
+    let max_frames = MAX_SINGLE_EXTRACT_BYTES / (self.dst_channels as usize * 4);
+    let data = self
+      .next_chunk(max_frames)?
+      .ok_or_else(|| Error::new(Status::InvalidArg, "No audio in the requested range"))?;
+    self.fill(1)?;
+    if self.pending_frames() > 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        format!(
+          "Requested range exceeds {} MiB of PCM; use AudioExtractor to read it in chunks",
+          MAX_SINGLE_EXTRACT_BYTES / (1024 * 1024)
+        ),
+      ));
+    }
+    Ok(data)
+  }
+}
+
+fn decode_error(e: crate::codec::CodecError) -> Error {
+  Error::new(Status::GenericFailure, format!("Decode failed: {}", e))
+}
+
+fn resample_error(e: crate::codec::CodecError) -> Error {
+  Error::new(Status::GenericFailure, format!("Resample failed: {}", e))
+}
+
+/// Decode the audio track of a media file into a single AudioData
+///
+/// The result is f32-planar at `targetSampleRate` (default: the track's rate),
+/// downmixed to one channel when `mono` is set, and trimmed to
+/// `[startUs, endUs)`. Ranges too large to hold in memory at once are rejected;
+/// read those with AudioExtractor instead.
+///
+/// ```javascript
+/// const pcm = await extractAudio('movie.mp4', { startUs: 0, endUs: 2_000_000, targetSampleRate: 16000, mono: true });
+/// ```
+#[napi]
+pub async fn extract_audio(
+  input: Either<String, BufferView>,
+  options: Option<ExtractAudioOptions>,
+) -> Result<AudioData> {
+  let options = options.unwrap_or_default();
+  tokio::task::spawn_blocking(move || AudioExtractorInner::open(input, &options)?.extract_all())
+    .await
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+}
+
+/// Streams the audio track of a media file as AudioData chunks
+///
+/// Accepts the same options as extractAudio(); each chunk holds at most
+/// `chunkDurationUs` of audio, so memory stays bounded for long ranges.
+///
+/// ```javascript
+/// const extractor = new AudioExtractor('movie.mp4', { targetSampleRate: 16000, mono: true });
+/// for await (const chunk of extractor) {
+///   recognizer.feed(chunk);
+///   chunk.close();
+/// }
+/// ```
+#[napi(async_iterator)]
+pub struct AudioExtractor {
+  inner: Arc<Mutex<AudioExtractorInner>>,
+  chunk_duration_us: i64,
+}
+
+impl AsyncGenerator for AudioExtractor {
+  type Yield = AudioData;
+  type Next = ();
+  type Return = ();
+
+  fn next(
+    &mut self,
+    _value: Option<Self::Next>,
+  ) -> impl Future<Output = Result<Option<Self::Yield>>> + Send + 'static {
+    let inner = self.inner.clone();
+    let chunk_duration_us = self.chunk_duration_us;
+
+    async move {
+      tokio::task::spawn_blocking(move || {
+        let mut guard = inner
+          .lock()
+          .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+        let max_frames = guard.us_to_frames(chunk_duration_us).max(1);
+        guard.next_chunk(max_frames)
+      })
+      .await
+      .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+    }
+  }
+}
+
+#[napi]
+impl AudioExtractor {
+  /// Open a media file path or in-memory buffer and select its audio track
+  #[napi(constructor)]
+  pub fn new(
+    input: Either<String, BufferView>,
+    options: Option<ExtractAudioOptions>,
+  ) -> Result<Self> {
+    let options = options.unwrap_or_default();
+    let chunk_duration_us = options
+      .chunk_duration_us
+      .unwrap_or(DEFAULT_CHUNK_DURATION_US);
+    if chunk_duration_us <= 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "chunkDurationUs must be greater than 0",
+      ));
+    }
+    let inner = AudioExtractorInner::open(input, &options)?;
+    Ok(Self {
+      inner: Arc::new(Mutex::new(inner)),
+      chunk_duration_us,
+    })
+  }
+
+  /// Output sample rate of the yielded AudioData
+  #[napi(getter)]
+  pub fn sample_rate(&self) -> Result<u32> {
+    let guard = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(guard.dst_sample_rate)
+  }
+
+  /// Output channel count of the yielded AudioData
+  #[napi(getter)]
+  pub fn number_of_channels(&self) -> Result<u32> {
+    let guard = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(guard.dst_channels)
+  }
+}
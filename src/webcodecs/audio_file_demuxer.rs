@@ -0,0 +1,324 @@
+//! AudioFileDemuxer - WebCodecs-style demuxer for bare audio files
+//!
+//! Reads elementary audio files that have no general-purpose container:
+//! MP3 (with or without ID3 tags), ADTS AAC and native FLAC. Packets are
+//! emitted as EncodedAudioChunks ready for AudioDecoder.
+
+use crate::codec::demuxer::StreamInfo;
+use crate::ffi::AVCodecID;
+use crate::webcodecs::audio_decoder::AudioDecoder;
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::demuxer_base::{
+  AudioOutputCallback, DemuxerAudioDecoderConfig, DemuxerChunk, DemuxerFormat, DemuxerInner,
+  DemuxerTrackInfo, ErrorCallback, RawPacket, analyze_demuxer_track, create_audio_decoder,
+  parse_aac_codec_string, read_demuxer_packet, with_demuxer_inner, with_demuxer_inner_mut,
+};
+use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
+use crate::webcodecs::track_analysis::{TrackAnalysis, TrackAnalysisOptions};
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::UnknownReturnValue;
+use napi_derive::napi;
+use std::sync::{Arc, Mutex};
+
+// ============================================================================
+// AudioFileFormat - Format-specific behavior for bare audio files
+// ============================================================================
+
+/// Bare audio file format implementation
+pub struct AudioFileFormat;
+
+impl DemuxerFormat for AudioFileFormat {
+  fn codec_id_to_video_string(codec_id: AVCodecID, _extradata: Option<&[u8]>) -> String {
+    format!("{:?}", codec_id).to_lowercase()
+  }
+
+  fn codec_id_to_audio_string(codec_id: AVCodecID, extradata: Option<&[u8]>) -> String {
+    match codec_id {
+      AVCodecID::Mp3 => "mp3".to_string(),
+      AVCodecID::Aac => parse_aac_codec_string(extradata),
+      AVCodecID::Flac => "flac".to_string(),
+      AVCodecID::PcmMulaw | AVCodecID::PcmAlaw | AVCodecID::AdpcmG722 => {
+        codec_id.to_webcodecs_codec().to_string()
+      }
+      _ => format!("{:?}", codec_id).to_lowercase(),
+    }
+  }
+
+  fn audio_codec_string(stream: &StreamInfo) -> String {
+    match (stream.codec_id, &stream.extradata, stream.profile) {
+      // ADTS streams have no AudioSpecificConfig; use the probed profile
+      // (FFmpeg's AAC profile is audioObjectType - 1)
+      (AVCodecID::Aac, None, Some(profile)) => format!("mp4a.40.{}", profile + 1),
+      _ => Self::codec_id_to_audio_string(stream.codec_id, stream.extradata.as_deref()),
+    }
+  }
+}
+
+/// Validate a loaded audio file and restrict demuxing to its audio stream
+fn finish_audio_file_load(inner: &mut DemuxerInner<AudioFileFormat>) -> Result<()> {
+  // Embedded cover art (e.g. ID3 APIC) shows up as a video stream
+  inner.selected_video_track = None;
+
+  let codec_id = inner
+    .selected_audio_track
+    .and_then(|index| inner.demuxer.as_ref()?.get_stream(index))
+    .map(|s| s.codec_id);
+
+  let error = match codec_id {
+    Some(AVCodecID::Mp3 | AVCodecID::Aac | AVCodecID::Flac) => return Ok(()),
+    Some(codec_id) => format!("Unsupported audio file codec: {:?}", codec_id),
+    None => "No audio stream found".to_string(),
+  };
+  inner.close();
+  Err(Error::new(Status::GenericFailure, error))
+}
+
+// ============================================================================
+// AudioFileDemuxerInit - Initialization options
+// ============================================================================
+
+/// Initialization options for AudioFileDemuxer
+pub struct AudioFileDemuxerInit {
+  pub audio_output: Option<AudioOutputCallback>,
+  pub error: ErrorCallback,
+}
+
+impl FromNapiValue for AudioFileDemuxerInit {
+  unsafe fn from_napi_value(
+    env: napi::sys::napi_env,
+    value: napi::sys::napi_value,
+  ) -> Result<Self> {
+    let env_wrapper = Env::from_raw(env);
+    let obj = unsafe { Object::from_napi_value(env, value)? };
+
+    // Get optional audio output callback
+    let audio_output: Option<AudioOutputCallback> = match obj
+      .get_named_property::<Option<Function<EncodedAudioChunk, UnknownReturnValue>>>("audioOutput")
+    {
+      Ok(Some(func)) => Some(
+        func
+          .build_threadsafe_function()
+          .callee_handled::<false>()
+          .weak::<true>()
+          .build()?,
+      ),
+      _ => None,
+    };
+
+    // Get required error callback
+    let error_func: Function<Error, UnknownReturnValue> = match obj.get_named_property("error") {
+      Ok(cb) => cb,
+      Err(_) => {
+        env_wrapper.throw_type_error("error callback is required", None)?;
+        return Err(Error::new(Status::InvalidArg, "error callback is required"));
+      }
+    };
+
+    let error: ErrorCallback = error_func
+      .build_threadsafe_function()
+      .callee_handled::<false>()
+      .weak::<true>()
+      .build()?;
+
+    Ok(AudioFileDemuxerInit {
+      audio_output,
+      error,
+    })
+  }
+}
+
+// ============================================================================
+// AudioFileDemuxer - NAPI class wrapper
+// ============================================================================
+
+/// Demuxer for bare audio files (MP3, ADTS AAC, FLAC)
+///
+/// Only the audio stream is demuxed; embedded cover art is ignored.
+#[napi(async_iterator)]
+pub struct AudioFileDemuxer {
+  inner: Arc<Mutex<DemuxerInner<AudioFileFormat>>>,
+}
+
+impl AsyncGenerator for AudioFileDemuxer {
+  type Yield = DemuxerChunk;
+  type Next = ();
+  type Return = ();
+
+  fn next(
+    &mut self,
+    _value: Option<Self::Next>,
+  ) -> impl std::future::Future<Output = Result<Option<Self::Yield>>> + Send + 'static {
+    let inner = self.inner.clone();
+
+    async move {
+      tokio::task::spawn_blocking(move || {
+        let mut guard = inner
+          .lock()
+          .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+        guard.read_next_chunk()
+      })
+      .await
+      .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+    }
+  }
+}
+
+#[napi]
+impl AudioFileDemuxer {
+  #[napi(constructor)]
+  pub fn new(init: AudioFileDemuxerInit) -> Result<Self> {
+    Ok(Self {
+      inner: Arc::new(Mutex::new(DemuxerInner::new(
+        None,
+        init.audio_output,
+        init.error,
+      ))),
+    })
+  }
+
+  #[napi]
+  pub async fn load(&self, path: String) -> Result<()> {
+    let inner = self.inner.clone();
+
+    tokio::task::spawn_blocking(move || {
+      let mut guard = inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+      guard.load_file(&path)?;
+      finish_audio_file_load(&mut guard)
+    })
+    .await
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+  }
+
+  /// Load an audio file from a buffer
+  ///
+  /// This method uses zero-copy buffer loading - the viewed bytes of any BufferSource are passed
+  /// directly to the demuxer without an intermediate copy.
+  #[napi]
+  pub async fn load_buffer(&self, data: BufferView) -> Result<()> {
+    let inner = self.inner.clone();
+
+    tokio::task::spawn_blocking(move || {
+      let mut guard = inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+      guard.load_buffer(data.into_inner())?;
+      finish_audio_file_load(&mut guard)
+    })
+    .await
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+  }
+
+  #[napi(getter)]
+  pub fn tracks(&self) -> Result<Vec<DemuxerTrackInfo>> {
+    let guard = with_demuxer_inner!(self);
+    Ok(guard.get_tracks())
+  }
+
+  #[napi(getter)]
+  pub fn duration(&self) -> Result<Option<i64>> {
+    let guard = with_demuxer_inner!(self);
+    Ok(guard.get_duration())
+  }
+
+  #[napi(getter)]
+  pub fn audio_decoder_config(&self) -> Result<Option<DemuxerAudioDecoderConfig>> {
+    let guard = with_demuxer_inner!(self);
+    Ok(guard.get_audio_decoder_config())
+  }
+
+  #[napi]
+  pub fn select_audio_track(&self, track_index: i32) -> Result<()> {
+    let mut guard = with_demuxer_inner_mut!(self);
+    guard.select_audio_track(track_index)
+  }
+
+  #[napi(
+    ts_args_type = "trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>"
+  )]
+  pub fn create_audio_decoder(
+    &self,
+    env: Env,
+    track_id: i32,
+    init: Object,
+  ) -> Result<AudioDecoder> {
+    create_audio_decoder(&self.inner, env, track_id, init)
+  }
+
+  /// Scan a track's packet timestamps and sizes without decoding
+  ///
+  /// Returns frame interval statistics, an interval histogram, bitrate per time
+  /// window and whether the track is VFR. The scan reads a separate view of the
+  /// source, so it does not move this demuxer's read position.
+  #[napi]
+  pub async fn analyze_track(
+    &self,
+    track_id: i32,
+    options: Option<TrackAnalysisOptions>,
+  ) -> Result<TrackAnalysis> {
+    analyze_demuxer_track(&self.inner, track_id, options.unwrap_or_default()).await
+  }
+
+  /// Read the next packet of the selected tracks for remuxing without decoding
+  ///
+  /// Returns null at end of stream. The record keeps PTS, DTS, duration and
+  /// flags in the track's time base; pass it to a muxer's `writePacket()`.
+  /// Shares the read position with async iteration.
+  #[napi]
+  pub async fn read_packet(&self) -> Result<Option<RawPacket>> {
+    read_demuxer_packet(&self.inner).await
+  }
+
+  #[napi]
+  pub fn demux(&self, count: Option<u32>) -> Result<()> {
+    let inner = self.inner.clone();
+    let max_packets = count.unwrap_or(u32::MAX);
+
+    std::thread::spawn(move || {
+      let mut guard = match inner.lock() {
+        Ok(g) => g,
+        Err(_) => return,
+      };
+      guard.demux_sync(max_packets);
+    });
+
+    Ok(())
+  }
+
+  /// Demux packets asynchronously (awaitable version of demux)
+  #[napi]
+  pub async fn demux_async(&self, count: Option<u32>) -> Result<()> {
+    let inner = self.inner.clone();
+    let max_packets = count.unwrap_or(u32::MAX);
+
+    tokio::task::spawn_blocking(move || {
+      let mut guard = inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+      guard.demux_sync(max_packets);
+      Ok(())
+    })
+    .await
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+  }
+
+  #[napi]
+  pub fn seek(&self, timestamp_us: i64) -> Result<()> {
+    let mut guard = with_demuxer_inner_mut!(self);
+    guard.seek(timestamp_us)
+  }
+
+  #[napi]
+  pub fn close(&self) -> Result<()> {
+    let mut guard = with_demuxer_inner_mut!(self);
+    guard.close();
+    Ok(())
+  }
+
+  #[napi(getter)]
+  pub fn state(&self) -> Result<String> {
+    let guard = with_demuxer_inner!(self);
+    Ok(guard.state_string().to_string())
+  }
+}
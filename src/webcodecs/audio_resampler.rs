@@ -0,0 +1,365 @@
+//! AudioResampler - standalone sample rate, format and channel conversion
+//!
+//! Wraps swresample for AudioData outside the encoders, e.g. to prepare
+//! 16 kHz mono audio for a speech recognition model. The filter state is kept
+//! across process() calls, so consecutive AudioData are converted as one
+//! continuous stream rather than chunk by chunk.
+//!
+//! swresample holds back a few samples per call for filtering; `delay`
+//! reports how many. Output timestamps start at the first input's timestamp
+//! and advance by the output sample count, so they already account for the
+//! held-back samples and stay contiguous across calls.
+
+use crate::codec::{Frame, Resampler};
+use crate::webcodecs::audio_data::{AudioData, AudioSampleFormat};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::sync::Mutex;
+
+/// Options for AudioResampler
+#[napi(object)]
+pub struct AudioResamplerInit {
+  /// Sample rate of the input AudioData in Hz
+  pub input_sample_rate: u32,
+  /// Sample format of the input AudioData
+  pub input_format: AudioSampleFormat,
+  /// Channel count of the input AudioData (default channel layout for the count)
+  pub input_number_of_channels: u32,
+  /// Output sample rate in Hz
+  pub output_sample_rate: u32,
+  /// Output sample format (default: the input format)
+  pub output_format: Option<AudioSampleFormat>,
+  /// Output channel count (default: the input channel count); 1 downmixes to mono
+  pub output_number_of_channels: Option<u32>,
+}
+
+/// Converted stream parameters, fixed at construction
+#[derive(Debug, Clone, Copy)]
+struct ResamplerParams {
+  input_sample_rate: u32,
+  input_format: AudioSampleFormat,
+  input_channels: u32,
+  output_sample_rate: u32,
+  output_format: AudioSampleFormat,
+  output_channels: u32,
+}
+
+impl ResamplerParams {
+  fn create(&self) -> Result<Resampler> {
+    Resampler::new(
+      self.input_channels,
+      self.input_sample_rate,
+      self.input_format.to_av_format(),
+      self.output_channels,
+      self.output_sample_rate,
+      self.output_format.to_av_format(),
+    )
+    .map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to create resampler: {}", e),
+      )
+    })
+  }
+}
+
+/// Output timeline of one converted stream
+#[derive(Debug, Default)]
+struct OutputClock {
+  /// Timestamp of the first input, in microseconds
+  origin: Option<i64>,
+  /// Samples output since the origin
+  samples: u64,
+}
+
+impl OutputClock {
+  /// Timestamp of the next output sample at `sample_rate`
+  fn next_timestamp(&self, sample_rate: u32) -> i64 {
+    // Derived from the running sample count so rounding never accumulates
+    let offset = (self.samples as i128 * 1_000_000 / sample_rate as i128) as i64;
+    self.origin.unwrap_or(0).saturating_add(offset)
+  }
+}
+
+struct AudioResamplerInner {
+  params: ResamplerParams,
+  /// Created on the first process() after construction, flush() or reset()
+  resampler: Option<Resampler>,
+  clock: OutputClock,
+  closed: bool,
+}
+
+impl AudioResamplerInner {
+  /// Wrap converted samples as AudioData on the output timeline
+  fn emit(&mut self, mut frame: Frame) -> Option<AudioData> {
+    let samples = frame.nb_samples();
+    if samples == 0 {
+      return None;
+    }
+    let timestamp = self.clock.next_timestamp(self.params.output_sample_rate);
+    self.clock.samples += samples as u64;
+    frame.set_pts(timestamp);
+    Some(AudioData::from_internal(frame, timestamp))
+  }
+
+  fn check_open(&self) -> Result<()> {
+    if self.closed {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "InvalidStateError: AudioResampler is closed",
+      ));
+    }
+    Ok(())
+  }
+}
+
+/// Converts AudioData between sample rates, sample formats and channel counts
+///
+/// Feed the AudioData of one stream in order to process(); each call returns
+/// the converted samples available so far (or null while the filter is still
+/// filling). flush() returns the held-back tail and starts a new stream.
+///
+/// ```javascript
+/// const resampler = new AudioResampler({
+///   inputSampleRate: 48000, inputFormat: 'f32-planar', inputNumberOfChannels: 2,
+///   outputSampleRate: 16000, outputNumberOfChannels: 1,
+/// });
+/// for (const data of decoded) {
+///   const out = resampler.process(data);
+///   if (out) recognizer.feed(out);
+///   data.close();
+/// }
+/// const tail = resampler.flush();
+/// if (tail) recognizer.feed(tail);
+/// ```
+#[napi]
+pub struct AudioResampler {
+  inner: Mutex<AudioResamplerInner>,
+}
+
+#[napi]
+impl AudioResampler {
+  /// Create a resampler for one input/output conversion
+  #[napi(constructor)]
+  pub fn new(init: AudioResamplerInit) -> Result<Self> {
+    let params = ResamplerParams {
+      input_sample_rate: init.input_sample_rate,
+      input_format: init.input_format,
+      input_channels: init.input_number_of_channels,
+      output_sample_rate: init.output_sample_rate,
+      output_format: init.output_format.unwrap_or(init.input_format),
+      output_channels: init
+        .output_number_of_channels
+        .unwrap_or(init.input_number_of_channels),
+    };
+    for (name, value) in [
+      ("inputSampleRate", params.input_sample_rate),
+      ("inputNumberOfChannels", params.input_channels),
+      ("outputSampleRate", params.output_sample_rate),
+      ("outputNumberOfChannels", params.output_channels),
+    ] {
+      if value == 0 {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!("TypeError: {} must be greater than 0", name),
+        ));
+      }
+    }
+
+    // Fail construction, not the first process(), on an unsupported conversion
+    let resampler = params.create()?;
+    Ok(Self {
+      inner: Mutex::new(AudioResamplerInner {
+        params,
+        resampler: Some(resampler),
+        clock: OutputClock::default(),
+        closed: false,
+      }),
+    })
+  }
+
+  /// Convert the next AudioData of the stream
+  ///
+  /// Returns null when the filter holds back every sample so far. The input
+  /// must match the constructor's input rate, format and channel count.
+  #[napi]
+  pub fn process(&self, data: &AudioData) -> Result<Option<AudioData>> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    inner.check_open()?;
+
+    let params = inner.params;
+    let timestamp = data.timestamp()?;
+    let converted = data.with_frame(|frame| -> Result<Frame> {
+      if frame.sample_rate() != params.input_sample_rate
+        || frame.channels() != params.input_channels
+        || frame.sample_format() != params.input_format.to_av_format()
+      {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!(
+            "TypeError: AudioData ({} Hz, {} channels) does not match the resampler input ({} Hz, {} channels, {:?})",
+            frame.sample_rate(),
+            frame.channels(),
+            params.input_sample_rate,
+            params.input_channels,
+            params.input_format,
+          ),
+        ));
+      }
+      if inner.resampler.is_none() {
+        inner.resampler = Some(params.create()?);
+      }
+      let resampler = inner.resampler.as_mut().expect("resampler was just created");
+      resampler.convert_alloc(frame).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to resample audio: {}", e),
+        )
+      })
+    })??;
+
+    inner.clock.origin.get_or_insert(timestamp);
+    Ok(inner.emit(converted))
+  }
+
+  /// Return the samples held back by the filter and end the stream
+  ///
+  /// The next process() starts a new stream with a fresh filter, timed from
+  /// its own input timestamp. Returns null if nothing was held back.
+  #[napi]
+  pub fn flush(&self) -> Result<Option<AudioData>> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    inner.check_open()?;
+
+    let params = inner.params;
+    let Some(mut resampler) = inner.resampler.take() else {
+      return Ok(None);
+    };
+    let tail = if inner.clock.origin.is_some() {
+      // Room for everything the filter holds, so one flush call drains it
+      let capacity = resampler
+        .get_out_samples(0)
+        .max(resampler.get_delay().max(0) as u32 + 1);
+      let mut out = Frame::new_audio(
+        capacity,
+        params.output_channels,
+        params.output_sample_rate,
+        params.output_format.to_av_format(),
+      )
+      .map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to allocate audio frame: {}", e),
+        )
+      })?;
+      resampler.flush(&mut out).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to flush resampler: {}", e),
+        )
+      })?;
+      inner.emit(out)
+    } else {
+      None
+    };
+    inner.clock = OutputClock::default();
+    Ok(tail)
+  }
+
+  /// Discard the held-back samples and start a new stream
+  #[napi]
+  pub fn reset(&self) -> Result<()> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    inner.check_open()?;
+    inner.resampler = None;
+    inner.clock = OutputClock::default();
+    Ok(())
+  }
+
+  /// Release the resampler
+  #[napi]
+  pub fn close(&self) -> Result<()> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    inner.resampler = None;
+    inner.closed = true;
+    Ok(())
+  }
+
+  /// Samples held back by the filter, counted at the output sample rate
+  ///
+  /// flush() returns about this many samples. The timestamps of returned
+  /// AudioData already account for them.
+  #[napi(getter)]
+  pub fn delay(&self) -> Result<i64> {
+    self.with_inner(|inner| inner.resampler.as_ref().map_or(0, |r| r.get_delay()))
+  }
+
+  /// Timestamp the next output sample will carry, in microseconds (null before the first input)
+  #[napi(getter)]
+  pub fn next_timestamp(&self) -> Result<Option<i64>> {
+    self.with_inner(|inner| {
+      inner
+        .clock
+        .origin
+        .map(|_| inner.clock.next_timestamp(inner.params.output_sample_rate))
+    })
+  }
+
+  /// Output sample rate in Hz
+  #[napi(getter)]
+  pub fn sample_rate(&self) -> Result<u32> {
+    self.with_inner(|inner| inner.params.output_sample_rate)
+  }
+
+  /// Output channel count
+  #[napi(getter)]
+  pub fn number_of_channels(&self) -> Result<u32> {
+    self.with_inner(|inner| inner.params.output_channels)
+  }
+
+  /// Output sample format
+  #[napi(getter)]
+  pub fn format(&self) -> Result<AudioSampleFormat> {
+    self.with_inner(|inner| inner.params.output_format)
+  }
+}
+
+impl AudioResampler {
+  fn with_inner<R>(&self, f: impl FnOnce(&AudioResamplerInner) -> R) -> Result<R> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(f(&inner))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn output_clock_does_not_accumulate_rounding() {
+    let mut clock = OutputClock {
+      origin: Some(1_000),
+      samples: 0,
+    };
+    // 160 samples at 44.1 kHz is 3628.117us; rounding each step would drift
+    for _ in 0..441 {
+      clock.samples += 160;
+    }
+    assert_eq!(clock.next_timestamp(44_100), 1_000 + 1_600_000);
+  }
+}
@@ -0,0 +1,116 @@
+//! Bitrate Stats - Achieved average and peak bitrate of an encoded stream
+//!
+//! Encoders and muxers record the size and media timestamp of every chunk
+//! they output. The summary is computed on demand: the average over the
+//! stream's media duration, and the peak over any 1-second window of chunk
+//! timestamps (wall clock never enters into it).
+
+use napi_derive::napi;
+
+/// Sliding window for `peakBitrate1s` (microseconds)
+const PEAK_WINDOW_US: i64 = 1_000_000;
+
+/// Achieved bitrate of an encoded stream
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BitrateSummary {
+  /// Media duration from the first chunk's timestamp to the end of the last chunk (microseconds)
+  pub duration_us: i64,
+  /// Total encoded bytes
+  pub bytes: i64,
+  /// Average bitrate over `durationUs` (bits per second)
+  pub avg_bitrate: f64,
+  /// Highest bit count over any 1-second window of chunk timestamps (bits per second)
+  #[napi(js_name = "peakBitrate1s")]
+  pub peak_bitrate_1s: f64,
+}
+
+/// Per-track summaries returned by the muxers
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct MuxerBitrateSummary {
+  /// Video track summary (absent without a video track)
+  pub video: Option<BitrateSummary>,
+  /// Audio track summary (absent without an audio track)
+  pub audio: Option<BitrateSummary>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ChunkSample {
+  timestamp_us: i64,
+  duration_us: Option<i64>,
+  bytes: u64,
+}
+
+/// Chunk sizes and timestamps of one output stream
+#[derive(Debug, Default)]
+pub(crate) struct BitrateTracker {
+  samples: Vec<ChunkSample>,
+}
+
+impl BitrateTracker {
+  /// Record one output chunk
+  pub fn record(&mut self, timestamp_us: i64, duration_us: Option<i64>, bytes: u32) {
+    self.samples.push(ChunkSample {
+      timestamp_us,
+      duration_us,
+      bytes: u64::from(bytes),
+    });
+  }
+
+  /// Drop all samples (on configure/reset)
+  pub fn clear(&mut self) {
+    self.samples.clear();
+  }
+
+  /// Compute the summary over all recorded chunks
+  pub fn summary(&self) -> BitrateSummary {
+    if self.samples.is_empty() {
+      return BitrateSummary::default();
+    }
+
+    // Chunks may arrive in decode order (B-frames), so work in presentation order
+    let mut samples = self.samples.clone();
+    samples.sort_by_key(|sample| sample.timestamp_us);
+
+    let bytes: u64 = samples.iter().map(|sample| sample.bytes).sum();
+    let first = samples[0].timestamp_us;
+    let last = samples[samples.len() - 1];
+    // Without a duration on the last chunk, assume it lasts as long as the average gap
+    let last_duration = last.duration_us.unwrap_or_else(|| {
+      if samples.len() > 1 {
+        (last.timestamp_us - first) / (samples.len() as i64 - 1)
+      } else {
+        0
+      }
+    });
+    let duration_us = (last.timestamp_us + last_duration - first).max(0);
+
+    let avg_bitrate = if duration_us > 0 {
+      (bytes * 8) as f64 * 1_000_000.0 / duration_us as f64
+    } else {
+      0.0
+    };
+
+    // Two-pointer sweep: the window [start.timestamp, start.timestamp + 1s)
+    let mut peak_bytes = 0u64;
+    let mut window_bytes = 0u64;
+    let mut end = 0;
+    for start in 0..samples.len() {
+      let window_end = samples[start].timestamp_us.saturating_add(PEAK_WINDOW_US);
+      while end < samples.len() && samples[end].timestamp_us < window_end {
+        window_bytes += samples[end].bytes;
+        end += 1;
+      }
+      peak_bytes = peak_bytes.max(window_bytes);
+      window_bytes -= samples[start].bytes;
+    }
+
+    BitrateSummary {
+      duration_us,
+      bytes: bytes as i64,
+      avg_bitrate,
+      peak_bitrate_1s: (peak_bytes * 8) as f64,
+    }
+  }
+}
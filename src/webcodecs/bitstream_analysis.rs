@@ -0,0 +1,730 @@
+//! analyzeBitstream() - Frame types and GOP structure from headers only
+//!
+//! Checking that an encode has "a key frame every 60 frames and no B-frames"
+//! should not need a decoder. analyzeBitstream() reads just enough of every
+//! chunk to classify it: the NAL unit types and the first slice header of
+//! H.264/H.265, the uncompressed frame header of VP9 and the frame header
+//! OBU of AV1. Nothing past the first few bytes of a picture is touched.
+//!
+//! Frames are reported in input (decode) order, so B-frame runs and GOP
+//! lengths come out as the encoder produced them.
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::codec::demuxer::{DemuxerContext, MediaType};
+use crate::ffi::AVCodecID;
+use crate::ffi::types::AV_NOPTS_VALUE;
+use crate::webcodecs::access_unit::length_prefixed_nals;
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::codec_string::parse_codec_string;
+use crate::webcodecs::demuxer_base::convert_timestamp;
+use crate::webcodecs::encoded_video_chunk::{annexb_nal_ranges, read_leb128};
+use crate::webcodecs::error::{invalid_state_error, not_supported_error};
+use crate::webcodecs::{
+  EncodedVideoChunk, convert_hvcc_extradata_to_annexb, is_av1c_extradata, is_avcc_format,
+  is_hvcc_extradata,
+};
+
+/// Picture type of an analyzed frame
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitstreamFrameType {
+  /// Intra-coded: H.264/H.265 I slice, VP9/AV1 key or intra-only frame
+  #[napi(value = "I")]
+  I,
+  /// Predicted from earlier frames
+  #[napi(value = "P")]
+  P,
+  /// Bi-predicted (H.264/H.265 only)
+  #[napi(value = "B")]
+  B,
+  /// No picture header could be parsed
+  #[napi(value = "unknown")]
+  Unknown,
+}
+
+/// How H.264/H.265 NAL units are delimited
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitstreamNalFormat {
+  /// Start codes (0x000001 / 0x00000001)
+  #[napi(value = "annexb")]
+  AnnexB,
+  /// 4-byte length prefixes (AVCC/HVCC, as stored in MP4)
+  #[napi(value = "lengthPrefixed")]
+  LengthPrefixed,
+}
+
+/// One analyzed chunk or packet
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BitstreamFrame {
+  /// Position in the input
+  pub index: u32,
+  /// Presentation timestamp in microseconds, if known
+  pub timestamp: Option<i64>,
+  /// Size in bytes
+  pub byte_length: u32,
+  /// Picture type of the frame
+  pub frame_type: BitstreamFrameType,
+  /// Starts a GOP: H.264 IDR, H.265 IRAP, or a shown VP9/AV1 key frame
+  pub key: bool,
+}
+
+/// Result of analyzeBitstream()
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BitstreamAnalysis {
+  /// Codec family: "h264", "h265", "vp9" or "av1"
+  pub codec: String,
+  /// NAL unit delimiting of the first H.264/H.265 chunk; unset for VP9/AV1
+  pub nal_format: Option<BitstreamNalFormat>,
+  /// Every chunk, in input order
+  pub frames: Vec<BitstreamFrame>,
+  pub key_frame_count: u32,
+  pub b_frame_count: u32,
+  /// Longest run of consecutive B-frames in decode order
+  pub max_consecutive_b_frames: u32,
+  /// Frames from each key frame up to the next; the last GOP may be
+  /// incomplete and frames before the first key frame are not counted
+  pub gop_lengths: Vec<u32>,
+  /// Indices of the frames carrying in-band parameter sets (H.264 SPS/PPS,
+  /// H.265 VPS/SPS/PPS, AV1 sequence header)
+  pub parameter_set_indices: Vec<u32>,
+}
+
+/// Header fields of one chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ChunkHeader {
+  frame_type: BitstreamFrameType,
+  key: bool,
+  parameter_sets: bool,
+}
+
+impl ChunkHeader {
+  fn new() -> Self {
+    Self {
+      frame_type: BitstreamFrameType::Unknown,
+      key: false,
+      parameter_sets: false,
+    }
+  }
+}
+
+/// MSB-first bit reader over an RBSP
+struct BitReader<'a> {
+  data: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+  fn new(data: &'a [u8]) -> Self {
+    Self { data, pos: 0 }
+  }
+
+  fn bit(&mut self) -> Option<u32> {
+    let byte = *self.data.get(self.pos / 8)?;
+    let bit = (byte >> (7 - self.pos % 8)) & 1;
+    self.pos += 1;
+    Some(bit as u32)
+  }
+
+  fn bits(&mut self, n: u32) -> Option<u32> {
+    (0..n).try_fold(0, |value, _| Some((value << 1) | self.bit()?))
+  }
+
+  /// Exp-Golomb unsigned value
+  fn ue(&mut self) -> Option<u32> {
+    let mut leading_zeros = 0;
+    while self.bit()? == 0 {
+      leading_zeros += 1;
+      if leading_zeros > 31 {
+        return None;
+      }
+    }
+    Some((1 << leading_zeros) - 1 + self.bits(leading_zeros)?)
+  }
+}
+
+/// Bytes of a NAL unit payload needed for slice and PPS headers
+const HEADER_BYTES: usize = 32;
+
+/// First bytes of `payload` with emulation prevention bytes removed
+fn header_rbsp(payload: &[u8]) -> Vec<u8> {
+  let mut rbsp = Vec::with_capacity(HEADER_BYTES);
+  let mut zeros = 0;
+  for &byte in payload {
+    if rbsp.len() == HEADER_BYTES {
+      break;
+    }
+    if zeros >= 2 && byte == 3 {
+      zeros = 0;
+      continue;
+    }
+    zeros = if byte == 0 { zeros + 1 } else { 0 };
+    rbsp.push(byte);
+  }
+  rbsp
+}
+
+/// Codec-specific header parser, carrying state between chunks
+enum Parser {
+  H264,
+  /// num_extra_slice_header_bits of every PPS seen so far
+  H265(HashMap<u32, u32>),
+  Vp9,
+  /// reduced_still_picture_header of the latest sequence header
+  Av1(bool),
+}
+
+impl Parser {
+  fn new(codec_id: AVCodecID) -> Option<Self> {
+    match codec_id {
+      AVCodecID::H264 => Some(Parser::H264),
+      AVCodecID::Hevc => Some(Parser::H265(HashMap::new())),
+      AVCodecID::Vp9 => Some(Parser::Vp9),
+      AVCodecID::Av1 => Some(Parser::Av1(false)),
+      _ => None,
+    }
+  }
+
+  fn codec_name(&self) -> &'static str {
+    match self {
+      Parser::H264 => "h264",
+      Parser::H265(_) => "h265",
+      Parser::Vp9 => "vp9",
+      Parser::Av1(_) => "av1",
+    }
+  }
+
+  fn parse(&mut self, data: &[u8]) -> ChunkHeader {
+    match self {
+      Parser::H264 => parse_h264(data),
+      Parser::H265(pps) => parse_h265(data, pps),
+      Parser::Vp9 => parse_vp9(data),
+      Parser::Av1(reduced_still) => parse_av1(data, reduced_still),
+    }
+  }
+
+  /// Pick up out-of-band parameter sets, e.g. an MP4 track's avcC/hvcC
+  fn read_extradata(&mut self, extradata: &[u8]) {
+    match self {
+      Parser::H265(pps) => {
+        let annexb = if is_hvcc_extradata(extradata) {
+          convert_hvcc_extradata_to_annexb(extradata)
+        } else {
+          Some(extradata.to_vec())
+        };
+        if let Some(annexb) = annexb {
+          parse_h265(&annexb, pps);
+        }
+      }
+      Parser::Av1(reduced_still) => {
+        // av1C: 4-byte record header followed by the config OBUs
+        let obus = if is_av1c_extradata(extradata) {
+          extradata.get(4..).unwrap_or_default()
+        } else {
+          extradata
+        };
+        parse_av1(obus, reduced_still);
+      }
+      Parser::H264 | Parser::Vp9 => {}
+    }
+  }
+}
+
+/// NAL units of an H.264/H.265 chunk in either delimiting
+fn split_nals(data: &[u8]) -> Vec<&[u8]> {
+  if is_avcc_format(data) {
+    length_prefixed_nals(data)
+  } else {
+    annexb_nal_ranges(data)
+      .into_iter()
+      .map(|(start, end)| &data[start..end])
+      .collect()
+  }
+}
+
+fn parse_h264(data: &[u8]) -> ChunkHeader {
+  let mut header = ChunkHeader::new();
+  for nal in split_nals(data).into_iter().filter(|nal| !nal.is_empty()) {
+    match nal[0] & 0x1f {
+      7 | 8 => header.parameter_sets = true,
+      nal_type @ (1 | 5) if header.frame_type == BitstreamFrameType::Unknown => {
+        header.key = nal_type == 5;
+        let rbsp = header_rbsp(&nal[1..]);
+        let mut reader = BitReader::new(&rbsp);
+        // first_mb_in_slice, then slice_type (7.3.3)
+        let slice_type = reader.ue().and_then(|_| reader.ue());
+        header.frame_type = match slice_type.map(|t| t % 5) {
+          Some(0) | Some(3) => BitstreamFrameType::P,
+          Some(1) => BitstreamFrameType::B,
+          Some(2) | Some(4) => BitstreamFrameType::I,
+          _ => BitstreamFrameType::Unknown,
+        };
+      }
+      _ => {}
+    }
+  }
+  header
+}
+
+fn parse_h265(data: &[u8], pps_extra_bits: &mut HashMap<u32, u32>) -> ChunkHeader {
+  let mut header = ChunkHeader::new();
+  for nal in split_nals(data).into_iter().filter(|nal| nal.len() > 2) {
+    let nal_type = (nal[0] >> 1) & 0x3f;
+    let rbsp = header_rbsp(&nal[2..]);
+    let mut reader = BitReader::new(&rbsp);
+    match nal_type {
+      32 | 33 => header.parameter_sets = true,
+      34 => {
+        header.parameter_sets = true;
+        // pps_pic_parameter_set_id, pps_seq_parameter_set_id,
+        // dependent_slice_segments_enabled_flag, output_flag_present_flag,
+        // num_extra_slice_header_bits (7.3.2.3.1)
+        let pps = (|| {
+          let id = reader.ue()?;
+          reader.ue()?;
+          reader.bits(2)?;
+          Some((id, reader.bits(3)?))
+        })();
+        if let Some((id, extra_bits)) = pps {
+          pps_extra_bits.insert(id, extra_bits);
+        }
+      }
+      0..=9 | 16..=21 if header.frame_type == BitstreamFrameType::Unknown => {
+        // Only the first slice segment of a picture is classified; later
+        // ones need SPS fields to find slice_type
+        if reader.bit() != Some(1) {
+          continue;
+        }
+        header.key = (16..=21).contains(&nal_type);
+        let slice_type = (|| {
+          if header.key {
+            // no_output_of_prior_pics_flag
+            reader.bit()?;
+          }
+          let pps_id = reader.ue()?;
+          reader.bits(pps_extra_bits.get(&pps_id).copied().unwrap_or(0))?;
+          reader.ue()
+        })();
+        header.frame_type = match slice_type {
+          Some(0) => BitstreamFrameType::B,
+          Some(1) => BitstreamFrameType::P,
+          Some(2) => BitstreamFrameType::I,
+          _ => BitstreamFrameType::Unknown,
+        };
+      }
+      _ => {}
+    }
+  }
+  header
+}
+
+/// Frames of a VP9 chunk, splitting superframes (Annex B of the VP9 spec)
+fn vp9_frames(data: &[u8]) -> Vec<&[u8]> {
+  let Some(&marker) = data.last() else {
+    return Vec::new();
+  };
+  if marker & 0xe0 == 0xc0 {
+    let frames = (marker & 0x7) as usize + 1;
+    let size_bytes = ((marker >> 3) & 0x3) as usize + 1;
+    let index_len = 2 + size_bytes * frames;
+    if data.len() >= index_len && data[data.len() - index_len] == marker {
+      let index = &data[data.len() - index_len + 1..data.len() - 1];
+      let mut offset = 0;
+      let mut result = Vec::with_capacity(frames);
+      for size in index.chunks(size_bytes) {
+        let size = size
+          .iter()
+          .rev()
+          .fold(0usize, |value, &byte| (value << 8) | byte as usize);
+        let Some(frame) = data.get(offset..offset + size) else {
+          break;
+        };
+        result.push(frame);
+        offset += size;
+      }
+      return result;
+    }
+  }
+  vec![data]
+}
+
+fn parse_vp9(data: &[u8]) -> ChunkHeader {
+  let mut header = ChunkHeader::new();
+  for frame in vp9_frames(data) {
+    let mut reader = BitReader::new(frame);
+    // frame_marker, profile_low_bit, profile_high_bit (6.2)
+    let Some((2, profile)) = (|| Some((reader.bits(2)?, reader.bits(2)?)))() else {
+      continue;
+    };
+    // reserved_zero for profile 3 (profile bits are low bit first)
+    if profile == 3 && reader.bit().is_none() {
+      continue;
+    }
+    // show_existing_frame repeats an earlier frame without coding one
+    if reader.bit() != Some(0) {
+      continue;
+    }
+    let Some((frame_type, show_frame)) = (|| Some((reader.bit()?, reader.bit()?)))() else {
+      continue;
+    };
+    // error_resilient_mode, then intra_only for hidden inter frames
+    let intra_only = frame_type == 1 && show_frame == 0 && {
+      reader.bit();
+      reader.bit() == Some(1)
+    };
+    if frame_type == 0 || intra_only {
+      header.frame_type = BitstreamFrameType::I;
+      header.key |= frame_type == 0 && show_frame == 1;
+    } else if header.frame_type == BitstreamFrameType::Unknown {
+      header.frame_type = BitstreamFrameType::P;
+    }
+  }
+  header
+}
+
+/// OBUs of an AV1 temporal unit as `(obu_type, payload)`
+fn av1_obus(data: &[u8]) -> Vec<(u8, &[u8])> {
+  let mut obus = Vec::new();
+  let mut offset = 0;
+  while offset < data.len() {
+    let header = data[offset];
+    let obu_type = (header >> 3) & 0x0f;
+    let mut payload_start = offset + 1 + ((header >> 2) & 1) as usize;
+    let payload_end = if header & 0x02 != 0 {
+      let Some((size, len)) = data.get(payload_start..).and_then(read_leb128) else {
+        break;
+      };
+      payload_start += len;
+      payload_start.saturating_add(size)
+    } else {
+      data.len()
+    };
+    let Some(payload) = data.get(payload_start..payload_end) else {
+      break;
+    };
+    obus.push((obu_type, payload));
+    offset = payload_end;
+  }
+  obus
+}
+
+fn parse_av1(data: &[u8], reduced_still_picture_header: &mut bool) -> ChunkHeader {
+  let mut header = ChunkHeader::new();
+  for (obu_type, payload) in av1_obus(data) {
+    let mut reader = BitReader::new(payload);
+    match obu_type {
+      // OBU_SEQUENCE_HEADER: seq_profile, still_picture, reduced_still_picture_header
+      1 => {
+        header.parameter_sets = true;
+        if let Some(flag) = reader.bits(4).and_then(|_| reader.bit()) {
+          *reduced_still_picture_header = flag == 1;
+        }
+      }
+      // OBU_FRAME_HEADER or OBU_FRAME; the first frame classifies the unit
+      3 | 6 if header.frame_type == BitstreamFrameType::Unknown => {
+        if *reduced_still_picture_header {
+          header.frame_type = BitstreamFrameType::I;
+          header.key = true;
+          continue;
+        }
+        // show_existing_frame, then frame_type and show_frame (5.9.2)
+        let fields = (|| {
+          if reader.bit()? == 1 {
+            return None;
+          }
+          Some((reader.bits(2)?, reader.bit()?))
+        })();
+        let Some((frame_type, show_frame)) = fields else {
+          header.frame_type = BitstreamFrameType::P;
+          continue;
+        };
+        header.frame_type = match frame_type {
+          0 | 2 => BitstreamFrameType::I,
+          _ => BitstreamFrameType::P,
+        };
+        header.key = frame_type == 0 && show_frame == 1;
+      }
+      _ => {}
+    }
+  }
+  header
+}
+
+/// One input chunk: payload and timestamp in microseconds
+type InputChunk = (Vec<u8>, Option<i64>);
+
+fn analyze(mut parser: Parser, chunks: Vec<InputChunk>) -> BitstreamAnalysis {
+  let is_nal_codec = matches!(parser, Parser::H264 | Parser::H265(_));
+  let nal_format = chunks
+    .iter()
+    .find(|(data, _)| !data.is_empty())
+    .filter(|_| is_nal_codec)
+    .map(|(data, _)| {
+      if is_avcc_format(data) {
+        BitstreamNalFormat::LengthPrefixed
+      } else {
+        BitstreamNalFormat::AnnexB
+      }
+    });
+
+  let mut frames = Vec::with_capacity(chunks.len());
+  let mut parameter_set_indices = Vec::new();
+  let mut gop_lengths: Vec<u32> = Vec::new();
+  let mut b_run = 0;
+  let mut max_consecutive_b_frames = 0;
+  for (index, (data, timestamp)) in chunks.into_iter().enumerate() {
+    let header = parser.parse(&data);
+    if header.parameter_sets {
+      parameter_set_indices.push(index as u32);
+    }
+    if header.key {
+      gop_lengths.push(0);
+    }
+    if let Some(length) = gop_lengths.last_mut() {
+      *length += 1;
+    }
+    if header.frame_type == BitstreamFrameType::B {
+      b_run += 1;
+      max_consecutive_b_frames = max_consecutive_b_frames.max(b_run);
+    } else {
+      b_run = 0;
+    }
+    frames.push(BitstreamFrame {
+      index: index as u32,
+      timestamp,
+      byte_length: data.len() as u32,
+      frame_type: header.frame_type,
+      key: header.key,
+    });
+  }
+
+  BitstreamAnalysis {
+    codec: parser.codec_name().to_string(),
+    nal_format,
+    key_frame_count: frames.iter().filter(|f| f.key).count() as u32,
+    b_frame_count: frames
+      .iter()
+      .filter(|f| f.frame_type == BitstreamFrameType::B)
+      .count() as u32,
+    frames,
+    max_consecutive_b_frames,
+    gop_lengths,
+    parameter_set_indices,
+  }
+}
+
+/// Read the packets of the best video track of a container
+fn analyze_container(mut demuxer: DemuxerContext) -> Result<BitstreamAnalysis> {
+  let stream = demuxer
+    .find_best_stream(MediaType::Video)
+    .cloned()
+    .ok_or_else(|| Error::new(Status::InvalidArg, "Input has no video track"))?;
+  let mut parser = Parser::new(stream.codec_id).ok_or_else(|| {
+    not_supported_error(&format!(
+      "analyzeBitstream does not support {:?}",
+      stream.codec_id
+    ))
+  })?;
+  if let Some(extradata) = &stream.extradata {
+    parser.read_extradata(extradata);
+  }
+
+  let mut chunks = Vec::new();
+  loop {
+    match demuxer.read_packet() {
+      Ok(Some((packet, stream_index))) => {
+        if stream_index != stream.index {
+          continue;
+        }
+        let ts = if packet.pts() != AV_NOPTS_VALUE {
+          packet.pts()
+        } else {
+          packet.dts()
+        };
+        let timestamp =
+          (ts != AV_NOPTS_VALUE).then(|| convert_timestamp(ts, Some(stream.time_base)));
+        chunks.push((packet.as_slice().to_vec(), timestamp));
+      }
+      Ok(None) => break,
+      Err(e) => {
+        return Err(Error::new(
+          Status::GenericFailure,
+          format!("Failed to read packet: {}", e),
+        ));
+      }
+    }
+  }
+  Ok(analyze(parser, chunks))
+}
+
+/// Copy the payloads and timestamps of `chunks` for the blocking pool
+fn copy_chunks(
+  chunks: &[ClassInstance<'_, EncodedVideoChunk>],
+  codec: Option<String>,
+) -> Result<(Parser, Vec<InputChunk>)> {
+  let codec = codec.ok_or_else(|| {
+    Error::new(
+      Status::InvalidArg,
+      "codec is required when analyzing chunks",
+    )
+  })?;
+  let parser = parse_codec_string(&codec)
+    .and_then(|parsed| Parser::new(parsed.codec_id))
+    .ok_or_else(|| not_supported_error(&format!("analyzeBitstream does not support {}", codec)))?;
+  let data = chunks
+    .iter()
+    .map(|chunk| {
+      let data = chunk
+        .get_data_optional(|data| Some(data.to_vec()))
+        .ok_or_else(|| invalid_state_error("EncodedVideoChunk is closed"))?;
+      Ok((data, Some(chunk.timestamp()?)))
+    })
+    .collect::<Result<Vec<_>>>()?;
+  Ok((parser, data))
+}
+
+/// Report frame types, GOP lengths and parameter set locations of an
+/// encoded video stream by parsing headers only
+///
+/// `input` is an array of EncodedVideoChunks (from an encoder or a demuxer),
+/// or a container file path or buffer whose best video track is read. The
+/// codec string is required for chunks and ignored for containers, whose
+/// codec is taken from the track. H.264, H.265, VP9 and AV1 are supported.
+///
+/// ```javascript
+/// const { gopLengths, bFrameCount } = await analyzeBitstream(chunks, 'avc1.42001E');
+/// ```
+#[napi(
+  ts_args_type = "input: EncodedVideoChunk[] | string | BufferSource, codec?: string",
+  ts_return_type = "Promise<BitstreamAnalysis>"
+)]
+pub fn analyze_bitstream<'env>(
+  env: &'env Env,
+  input: Either3<Vec<ClassInstance<'_, EncodedVideoChunk>>, String, BufferView>,
+  codec: Option<String>,
+) -> Result<PromiseRaw<'env, BitstreamAnalysis>> {
+  // Argument errors reject the promise rather than throwing
+  let input = match input {
+    Either3::A(chunks) => copy_chunks(&chunks, codec).map(Either3::A),
+    Either3::B(path) => Ok(Either3::B(path)),
+    Either3::C(buffer) => Ok(Either3::C(buffer.into_inner())),
+  };
+
+  env.spawn_future(async move {
+    let input = input?;
+    tokio::task::spawn_blocking(move || match input {
+      Either3::A((parser, data)) => Ok(analyze(parser, data)),
+      Either3::B(path) => {
+        let demuxer = DemuxerContext::open_file(&path).map_err(|e| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Failed to open {}: {}", path, e),
+          )
+        })?;
+        analyze_container(demuxer)
+      }
+      Either3::C(buffer) => {
+        let demuxer = DemuxerContext::open_buffer(buffer).map_err(|e| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Failed to open buffer: {}", e),
+          )
+        })?;
+        analyze_container(demuxer)
+      }
+    })
+    .await
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn annexb(nals: &[&[u8]]) -> Vec<u8> {
+    nals
+      .iter()
+      .flat_map(|nal| [&[0, 0, 0, 1], *nal].concat())
+      .collect()
+  }
+
+  #[test]
+  fn h264_slice_types_come_from_the_first_slice_header() {
+    let idr = parse_h264(&annexb(&[&[0x67, 0x42], &[0x68, 0xce], &[0x65, 0x88]]));
+    assert_eq!(idr.frame_type, BitstreamFrameType::I);
+    assert!(idr.key && idr.parameter_sets);
+
+    // slice_type 5 (P, all slices) and 6 (B, all slices)
+    let p = parse_h264(&annexb(&[&[0x41, 0x9a]]));
+    assert_eq!((p.frame_type, p.key), (BitstreamFrameType::P, false));
+    let b = parse_h264(&[0, 0, 0, 2, 0x01, 0x9c]);
+    assert_eq!(b.frame_type, BitstreamFrameType::B);
+  }
+
+  #[test]
+  fn h265_irap_and_trailing_pictures() {
+    let mut pps = HashMap::new();
+    let idr = parse_h265(&annexb(&[&[0x26, 0x01, 0xac]]), &mut pps);
+    assert_eq!((idr.frame_type, idr.key), (BitstreamFrameType::I, true));
+    let trail = parse_h265(&annexb(&[&[0x02, 0x01, 0xe0]]), &mut pps);
+    assert_eq!(
+      (trail.frame_type, trail.key),
+      (BitstreamFrameType::B, false)
+    );
+  }
+
+  #[test]
+  fn vp9_superframes_report_their_shown_frame() {
+    let key = parse_vp9(&[0x82, 0x49, 0x83, 0x42]);
+    assert_eq!((key.frame_type, key.key), (BitstreamFrameType::I, true));
+
+    // Hidden inter frame followed by a shown inter frame, 2 one-byte sizes
+    let superframe = [0x84, 0x86, 0xc1, 0x01, 0x01, 0xc1];
+    let inter = parse_vp9(&superframe);
+    assert_eq!(
+      (inter.frame_type, inter.key),
+      (BitstreamFrameType::P, false)
+    );
+  }
+
+  #[test]
+  fn av1_key_frames_and_sequence_headers() {
+    let mut reduced_still = false;
+    // Temporal delimiter, sequence header (profile 0), key frame shown
+    let key = parse_av1(
+      &[0x12, 0x00, 0x0a, 0x01, 0x00, 0x32, 0x01, 0x10],
+      &mut reduced_still,
+    );
+    assert_eq!((key.frame_type, key.key), (BitstreamFrameType::I, true));
+    assert!(key.parameter_sets);
+
+    let inter = parse_av1(&[0x12, 0x00, 0x32, 0x01, 0x30], &mut reduced_still);
+    assert_eq!(
+      (inter.frame_type, inter.key),
+      (BitstreamFrameType::P, false)
+    );
+  }
+
+  #[test]
+  fn gop_lengths_and_b_frame_runs() {
+    let i = annexb(&[&[0x65, 0x88]]);
+    let p = annexb(&[&[0x41, 0x9a]]);
+    let b = annexb(&[&[0x01, 0x9c]]);
+    let stream = [&i, &p, &b, &b, &p, &b, &i, &p]
+      .into_iter()
+      .map(|data| (data.clone(), None))
+      .collect();
+    let analysis = analyze(Parser::H264, stream);
+    assert_eq!(analysis.gop_lengths, vec![6, 2]);
+    assert_eq!(analysis.b_frame_count, 3);
+    assert_eq!(analysis.max_consecutive_b_frames, 2);
+    assert_eq!(analysis.nal_format, Some(BitstreamNalFormat::AnnexB));
+  }
+}
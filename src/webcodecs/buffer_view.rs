@@ -0,0 +1,177 @@
+//! Byte input from any JS BufferSource
+//!
+//! napi's `Uint8Array` only accepts Uint8Array views (including Node
+//! Buffers). `BufferView` also accepts an ArrayBuffer, any other TypedArray or
+//! a DataView, and views exactly the bytes between its byteOffset and
+//! byteOffset + byteLength. Non-Uint8Array inputs are re-wrapped as a
+//! Uint8Array over the same ArrayBuffer, so no bytes are copied and the input
+//! stays alive as long as the view.
+
+use napi::bindgen_prelude::*;
+use napi::sys;
+use std::ops::Deref;
+use std::ptr;
+
+const EXPECTED: &str = "Expected a BufferSource (ArrayBuffer, TypedArray, DataView or Buffer)";
+
+/// Bytes of an ArrayBuffer or ArrayBufferView, borrowed from JS
+pub struct BufferView(Uint8Array);
+
+impl BufferView {
+  /// The bytes as a Uint8Array over the same memory
+  pub fn into_inner(self) -> Uint8Array {
+    self.0
+  }
+}
+
+impl Deref for BufferView {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    self.0.as_ref()
+  }
+}
+
+impl From<BufferView> for Uint8Array {
+  fn from(view: BufferView) -> Self {
+    view.0
+  }
+}
+
+impl TypeName for BufferView {
+  fn type_name() -> &'static str {
+    "ArrayBuffer | ArrayBufferView"
+  }
+
+  fn value_type() -> ValueType {
+    ValueType::Object
+  }
+}
+
+impl ValidateNapiValue for BufferView {}
+
+impl ToNapiValue for BufferView {
+  unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> Result<sys::napi_value> {
+    unsafe { Uint8Array::to_napi_value(env, val.0) }
+  }
+}
+
+/// Size in bytes of one element of a TypedArray type
+fn element_size(array_type: sys::napi_typedarray_type) -> Option<usize> {
+  match array_type {
+    sys::TypedarrayType::int8_array
+    | sys::TypedarrayType::uint8_array
+    | sys::TypedarrayType::uint8_clamped_array => Some(1),
+    sys::TypedarrayType::int16_array | sys::TypedarrayType::uint16_array => Some(2),
+    sys::TypedarrayType::int32_array
+    | sys::TypedarrayType::uint32_array
+    | sys::TypedarrayType::float32_array => Some(4),
+    sys::TypedarrayType::float64_array
+    | sys::TypedarrayType::bigint64_array
+    | sys::TypedarrayType::biguint64_array => Some(8),
+    _ => None,
+  }
+}
+
+/// Create a Uint8Array over `byte_length` bytes of `arraybuffer` at `byte_offset`
+unsafe fn uint8_view(
+  env: sys::napi_env,
+  arraybuffer: sys::napi_value,
+  byte_offset: usize,
+  byte_length: usize,
+) -> Result<Uint8Array> {
+  let mut view = ptr::null_mut();
+  check_status!(
+    unsafe {
+      sys::napi_create_typedarray(
+        env,
+        sys::TypedarrayType::uint8_array,
+        byte_length,
+        arraybuffer,
+        byte_offset,
+        &mut view,
+      )
+    },
+    "Failed to create Uint8Array"
+  )?;
+  unsafe { Uint8Array::from_napi_value(env, view) }
+}
+
+impl FromNapiValue for BufferView {
+  unsafe fn from_napi_value(env: sys::napi_env, value: sys::napi_value) -> Result<Self> {
+    let mut is_typed_array = false;
+    check_status!(unsafe { sys::napi_is_typedarray(env, value, &mut is_typed_array) })?;
+    if is_typed_array {
+      let mut array_type = 0;
+      let mut length = 0;
+      let mut data = ptr::null_mut();
+      let mut arraybuffer = ptr::null_mut();
+      let mut byte_offset = 0;
+      check_status!(unsafe {
+        sys::napi_get_typedarray_info(
+          env,
+          value,
+          &mut array_type,
+          &mut length,
+          &mut data,
+          &mut arraybuffer,
+          &mut byte_offset,
+        )
+      })?;
+      if array_type == sys::TypedarrayType::uint8_array {
+        return Ok(Self(unsafe { Uint8Array::from_napi_value(env, value)? }));
+      }
+      let size = element_size(array_type)
+        .ok_or_else(|| Error::new(Status::InvalidArg, EXPECTED.to_string()))?;
+      let view = unsafe { uint8_view(env, arraybuffer, byte_offset, length * size)? };
+      return Ok(Self(view));
+    }
+
+    let mut is_data_view = false;
+    check_status!(unsafe { sys::napi_is_dataview(env, value, &mut is_data_view) })?;
+    if is_data_view {
+      let mut byte_length = 0;
+      let mut data = ptr::null_mut();
+      let mut arraybuffer = ptr::null_mut();
+      let mut byte_offset = 0;
+      check_status!(unsafe {
+        sys::napi_get_dataview_info(
+          env,
+          value,
+          &mut byte_length,
+          &mut data,
+          &mut arraybuffer,
+          &mut byte_offset,
+        )
+      })?;
+      return Ok(Self(unsafe {
+        uint8_view(env, arraybuffer, byte_offset, byte_length)?
+      }));
+    }
+
+    let mut is_array_buffer = false;
+    check_status!(unsafe { sys::napi_is_arraybuffer(env, value, &mut is_array_buffer) })?;
+    if is_array_buffer {
+      let mut data = ptr::null_mut();
+      let mut byte_length = 0;
+      check_status!(unsafe {
+        sys::napi_get_arraybuffer_info(env, value, &mut data, &mut byte_length)
+      })?;
+      return Ok(Self(unsafe { uint8_view(env, value, 0, byte_length)? }));
+    }
+
+    Err(Error::new(Status::InvalidArg, EXPECTED.to_string()))
+  }
+}
+
+/// Read a required BufferSource member of an init dictionary
+///
+/// Returns the TypeError message when the member is missing or not a
+/// BufferSource; callers throw it their own way.
+pub(crate) fn required_buffer(obj: &Object, key: &str) -> std::result::Result<BufferView, String> {
+  match obj.get::<BufferView>(key) {
+    Ok(Some(view)) => Ok(view),
+    Ok(None) => Err(format!("{} is required", key)),
+    Err(_) => Err(format!("{} must be a BufferSource", key)),
+  }
+}
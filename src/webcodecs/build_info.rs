@@ -0,0 +1,147 @@
+//! Build and runtime introspection
+//!
+//! Reports what the linked FFmpeg was built with and what the CPU supports,
+//! for attaching to performance and bug reports.
+
+use crate::ffi::{
+  accessors::{ff_aom_version, ff_vpx_version, ff_x264_build, ff_x265_version, ffcodec_get_name},
+  avcodec::{
+    av_codec_is_decoder, av_codec_is_encoder, av_codec_iterate, avcodec_configuration,
+    avcodec_version,
+  },
+  avformat::avformat_version,
+  avutil::{av_get_cpu_flags, av_version_info, avutil_version, cpu_flag, format_version},
+  hwaccel::{get_available_hwdevice_types, get_hwdevice_type_name},
+  swresample::swresample_version,
+  swscale::swscale_version,
+};
+use napi_derive::napi;
+use std::ffi::{CStr, c_char};
+
+/// Version of a linked library
+#[napi(object)]
+pub struct LibraryVersion {
+  /// Library name (e.g., "libavcodec", "x264")
+  pub name: String,
+  /// Version string as reported by the library
+  pub version: String,
+}
+
+/// Build and runtime information for the native addon
+#[napi(object)]
+pub struct BuildInfo {
+  /// FFmpeg version (e.g., "7.1")
+  pub ffmpeg_version: String,
+  /// FFmpeg ./configure command line
+  pub ffmpeg_configuration: String,
+  /// FFmpeg and codec library versions
+  pub libraries: Vec<LibraryVersion>,
+  /// Names of all compiled-in encoders
+  pub encoders: Vec<String>,
+  /// Names of all compiled-in decoders
+  pub decoders: Vec<String>,
+  /// Hardware device types compiled into FFmpeg (not necessarily usable on this machine)
+  pub hardware_device_types: Vec<String>,
+  /// SIMD/CPU features detected at runtime (e.g., "avx2", "neon")
+  pub cpu_flags: Vec<String>,
+}
+
+/// Get build and runtime information (FFmpeg version/configuration, codec
+/// library versions, compiled-in codecs and hwaccels, detected CPU features)
+#[napi]
+pub fn get_build_info() -> BuildInfo {
+  let mut libraries = vec![
+    library("libavutil", format_version(unsafe { avutil_version() })),
+    library("libavcodec", format_version(unsafe { avcodec_version() })),
+    library("libavformat", format_version(unsafe { avformat_version() })),
+    library("libswscale", format_version(unsafe { swscale_version() })),
+    library(
+      "libswresample",
+      format_version(unsafe { swresample_version() }),
+    ),
+  ];
+
+  let x264_build = unsafe { ff_x264_build() };
+  if x264_build >= 0 {
+    libraries.push(library("x264", format!("build {}", x264_build)));
+  }
+  let codec_libraries = [
+    ("x265", unsafe { ff_x265_version() }),
+    ("libvpx", unsafe { ff_vpx_version() }),
+    ("libaom", unsafe { ff_aom_version() }),
+  ];
+  for (name, version) in codec_libraries {
+    if let Some(version) = c_string(version) {
+      libraries.push(library(name, version));
+    }
+  }
+
+  let (encoders, decoders) = list_codecs();
+
+  let hardware_device_types = get_available_hwdevice_types()
+    .into_iter()
+    .filter_map(get_hwdevice_type_name)
+    .map(String::from)
+    .collect();
+
+  let flags = unsafe { av_get_cpu_flags() };
+  let cpu_flags = cpu_flag::NAMES
+    .iter()
+    .filter(|(_, bit)| flags & bit != 0)
+    .map(|(name, _)| name.to_string())
+    .collect();
+
+  BuildInfo {
+    ffmpeg_version: c_string(unsafe { av_version_info() }).unwrap_or_default(),
+    ffmpeg_configuration: c_string(unsafe { avcodec_configuration() }).unwrap_or_default(),
+    libraries,
+    encoders,
+    decoders,
+    hardware_device_types,
+    cpu_flags,
+  }
+}
+
+fn library(name: &str, version: String) -> LibraryVersion {
+  LibraryVersion {
+    name: name.to_string(),
+    version,
+  }
+}
+
+/// Convert a static C string owned by FFmpeg/codec libraries
+fn c_string(ptr: *const c_char) -> Option<String> {
+  if ptr.is_null() {
+    return None;
+  }
+  Some(
+    unsafe { CStr::from_ptr(ptr) }
+      .to_string_lossy()
+      .into_owned(),
+  )
+}
+
+/// List names of all registered encoders and decoders
+fn list_codecs() -> (Vec<String>, Vec<String>) {
+  let mut encoders = Vec::new();
+  let mut decoders = Vec::new();
+  let mut opaque = std::ptr::null_mut();
+
+  loop {
+    let codec = unsafe { av_codec_iterate(&mut opaque) };
+    if codec.is_null() {
+      break;
+    }
+    let Some(name) = c_string(unsafe { ffcodec_get_name(codec) }) else {
+      continue;
+    };
+    if unsafe { av_codec_is_encoder(codec) } != 0 {
+      encoders.push(name.clone());
+    }
+    if unsafe { av_codec_is_decoder(codec) } != 0 {
+      decoders.push(name);
+    }
+  }
+
+  (encoders, decoders)
+}
@@ -0,0 +1,342 @@
+//! CfrRegulator - constant frame rate conversion for VideoFrames
+//!
+//! Screen capture and other variable frame rate sources produce frames at
+//! irregular timestamps, while some targets require a constant rate. The
+//! regulator places output frames on a fixed grid of `n / framerate` seconds
+//! from the first frame (or `startTimestamp`) and fills each grid slot with
+//! the input frame shown at the slot's midpoint:
+//!
+//! - an input frame that covers several slots is repeated (duplicated),
+//! - an input frame that covers no slot midpoint is dropped.
+//!
+//! Duplicates share the input frame's pixel data, so no pixels are copied.
+//! Output timestamps depend only on the grid, so the same input always maps
+//! to the same output timestamps and durations.
+
+use crate::webcodecs::video_frame::VideoFrame;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::sync::Mutex;
+
+/// Options for CfrRegulator
+#[napi(object)]
+pub struct CfrRegulatorInit {
+  /// Target frame rate in frames per second (e.g. 30 or 30000 / 1001)
+  pub framerate: f64,
+  /// Timestamp of the first output slot in microseconds (default: first frame's timestamp)
+  pub start_timestamp: Option<i64>,
+}
+
+/// Output slot grid of one regulated stream
+#[derive(Debug)]
+struct CfrGrid {
+  framerate: f64,
+  /// Timestamp of slot 0, fixed by the first frame unless given up front
+  start: Option<i64>,
+  /// Index of the next slot to fill
+  next_slot: u64,
+}
+
+impl CfrGrid {
+  fn new(framerate: f64, start: Option<i64>) -> Self {
+    Self {
+      framerate,
+      start,
+      next_slot: 0,
+    }
+  }
+
+  /// Offset of grid position `n` (in slots) from slot 0, in microseconds
+  fn offset(&self, n: f64) -> i64 {
+    (n * 1_000_000.0 / self.framerate).round() as i64
+  }
+
+  /// Timestamp and duration of slot `n`
+  fn slot(&self, n: u64) -> (i64, i64) {
+    let start = self.start.unwrap_or(0);
+    let timestamp = start + self.offset(n as f64);
+    let next = start + self.offset((n + 1) as f64);
+    (timestamp, next - timestamp)
+  }
+
+  /// Claim the pending slots whose midpoint lies before `boundary`
+  ///
+  /// These are the slots shown by a frame that is on screen until `boundary`.
+  fn take_before(&mut self, boundary: i64) -> Vec<(i64, i64)> {
+    let start = self.start.unwrap_or(0);
+    let mut slots = Vec::new();
+    while start + self.offset(self.next_slot as f64 + 0.5) < boundary {
+      slots.push(self.slot(self.next_slot));
+      self.next_slot += 1;
+    }
+    slots
+  }
+}
+
+/// Input frame currently on screen
+struct HeldFrame {
+  frame: VideoFrame,
+  timestamp: i64,
+  duration: Option<i64>,
+  /// Slots this frame has filled so far
+  outputs: u32,
+}
+
+struct CfrRegulatorInner {
+  grid: CfrGrid,
+  start_timestamp: Option<i64>,
+  held: Option<HeldFrame>,
+  input_frames: u32,
+  output_frames: u32,
+  duplicated_frames: u32,
+  dropped_frames: u32,
+}
+
+impl CfrRegulatorInner {
+  /// Fill `slots` with the held frame
+  fn emit(&mut self, slots: Vec<(i64, i64)>, out: &mut Vec<VideoFrame>) -> Result<()> {
+    let Some(held) = self.held.as_mut() else {
+      return Ok(());
+    };
+    for (timestamp, duration) in slots {
+      out.push(held.frame.share_with_timing(timestamp, Some(duration))?);
+      held.outputs += 1;
+      self.output_frames += 1;
+    }
+    Ok(())
+  }
+
+  /// Release the held frame, counting it as dropped or duplicated
+  fn retire(&mut self) {
+    if let Some(held) = self.held.take() {
+      match held.outputs {
+        0 => self.dropped_frames += 1,
+        n => self.duplicated_frames += n - 1,
+      }
+      let _ = held.frame.close();
+    }
+  }
+}
+
+/// Converts a variable frame rate stream of VideoFrames to a constant rate
+///
+/// Each pushed frame returns the output frames it completes; their timestamps
+/// are exactly `startTimestamp + n / framerate` and their durations one slot.
+/// Output frames share pixel data with the input, so the caller may close the
+/// input as soon as push() returns, and must close each output frame.
+///
+/// ```javascript
+/// const regulator = new CfrRegulator({ framerate: 30 });
+/// for (const frame of capturedFrames) {
+///   for (const out of regulator.push(frame)) {
+///     encoder.encode(out);
+///     out.close();
+///   }
+///   frame.close();
+/// }
+/// for (const out of regulator.flush()) { encoder.encode(out); out.close(); }
+/// console.log(regulator.duplicatedFrames, regulator.droppedFrames);
+/// ```
+#[napi]
+pub struct CfrRegulator {
+  inner: Mutex<CfrRegulatorInner>,
+}
+
+#[napi]
+impl CfrRegulator {
+  /// Create a regulator for a target frame rate
+  #[napi(constructor)]
+  pub fn new(init: CfrRegulatorInit) -> Result<Self> {
+    if !init.framerate.is_finite() || init.framerate <= 0.0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "TypeError: framerate must be a positive number",
+      ));
+    }
+
+    Ok(Self {
+      inner: Mutex::new(CfrRegulatorInner {
+        grid: CfrGrid::new(init.framerate, init.start_timestamp),
+        start_timestamp: init.start_timestamp,
+        held: None,
+        input_frames: 0,
+        output_frames: 0,
+        duplicated_frames: 0,
+        dropped_frames: 0,
+      }),
+    })
+  }
+
+  /// Add the next input frame and return the output frames it completes
+  ///
+  /// Frames must arrive in presentation order; a frame whose timestamp is not
+  /// after the previous one is dropped.
+  #[napi]
+  pub fn push(&self, frame: &VideoFrame) -> Result<Vec<VideoFrame>> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    let timestamp = frame.timestamp()?;
+    let duration = frame.duration()?;
+    let shared = frame.share()?;
+    inner.input_frames += 1;
+
+    if inner
+      .held
+      .as_ref()
+      .is_some_and(|held| timestamp <= held.timestamp)
+    {
+      inner.dropped_frames += 1;
+      let _ = shared.close();
+      return Ok(Vec::new());
+    }
+
+    let mut out = Vec::new();
+    if inner.held.is_some() {
+      // The held frame is on screen until this one
+      let slots = inner.grid.take_before(timestamp);
+      inner.emit(slots, &mut out)?;
+      inner.retire();
+    }
+    inner.grid.start.get_or_insert(timestamp);
+    inner.held = Some(HeldFrame {
+      frame: shared,
+      timestamp,
+      duration,
+      outputs: 0,
+    });
+    // Slots before the first frame (with an earlier startTimestamp) show it too
+    let slots = inner.grid.take_before(timestamp);
+    inner.emit(slots, &mut out)?;
+    Ok(out)
+  }
+
+  /// Fill the slots up to `endTimestamp` with the last frame and return them
+  ///
+  /// Without `endTimestamp`, the last frame is shown for its own duration, or
+  /// for one slot if it has none. The grid continues after flush(), so later
+  /// frames stay on the same timeline.
+  #[napi]
+  pub fn flush(&self, end_timestamp: Option<i64>) -> Result<Vec<VideoFrame>> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    let Some(held) = inner.held.as_ref() else {
+      return Ok(Vec::new());
+    };
+
+    let end = end_timestamp.unwrap_or_else(|| {
+      let step = held
+        .duration
+        .filter(|d| *d > 0)
+        .unwrap_or_else(|| inner.grid.slot(0).1);
+      held.timestamp + step
+    });
+    let mut out = Vec::new();
+    let slots = inner.grid.take_before(end);
+    inner.emit(slots, &mut out)?;
+    inner.retire();
+    Ok(out)
+  }
+
+  /// Drop the held frame and start a new timeline, clearing the counters
+  #[napi]
+  pub fn reset(&self) -> Result<()> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    if let Some(held) = inner.held.take() {
+      let _ = held.frame.close();
+    }
+    inner.grid = CfrGrid::new(inner.grid.framerate, inner.start_timestamp);
+    inner.input_frames = 0;
+    inner.output_frames = 0;
+    inner.duplicated_frames = 0;
+    inner.dropped_frames = 0;
+    Ok(())
+  }
+
+  /// Release the held frame
+  #[napi]
+  pub fn close(&self) -> Result<()> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    if let Some(held) = inner.held.take() {
+      let _ = held.frame.close();
+    }
+    Ok(())
+  }
+
+  /// Target frame rate
+  #[napi(getter)]
+  pub fn framerate(&self) -> Result<f64> {
+    self.with_inner(|inner| inner.grid.framerate)
+  }
+
+  /// Number of frames pushed
+  #[napi(getter)]
+  pub fn input_frames(&self) -> Result<u32> {
+    self.with_inner(|inner| inner.input_frames)
+  }
+
+  /// Number of frames returned
+  #[napi(getter)]
+  pub fn output_frames(&self) -> Result<u32> {
+    self.with_inner(|inner| inner.output_frames)
+  }
+
+  /// Number of extra copies of input frames returned to fill slots
+  #[napi(getter)]
+  pub fn duplicated_frames(&self) -> Result<u32> {
+    self.with_inner(|inner| inner.duplicated_frames)
+  }
+
+  /// Number of input frames that filled no slot
+  #[napi(getter)]
+  pub fn dropped_frames(&self) -> Result<u32> {
+    self.with_inner(|inner| inner.dropped_frames)
+  }
+}
+
+impl CfrRegulator {
+  fn with_inner<R>(&self, f: impl FnOnce(&CfrRegulatorInner) -> R) -> Result<R> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(f(&inner))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn slots_are_exact_multiples_of_the_frame_period() {
+    let grid = CfrGrid::new(30000.0 / 1001.0, Some(1_000));
+    assert_eq!(grid.slot(0), (1_000, 33_367));
+    assert_eq!(grid.slot(1), (34_367, 33_366));
+    assert_eq!(grid.slot(3).0, 1_000 + 100_100);
+    assert_eq!(grid.slot(30000).0, 1_000 + 1_001_000_000);
+  }
+
+  #[test]
+  fn frames_claim_slots_by_midpoint() {
+    let mut grid = CfrGrid::new(10.0, Some(0));
+    // A frame on screen until 40ms covers no slot midpoint
+    assert!(grid.take_before(40_000).is_empty());
+    // One on screen until 330ms covers the slots at 0, 100 and 200ms
+    assert_eq!(
+      grid.take_before(330_000),
+      vec![(0, 100_000), (100_000, 100_000), (200_000, 100_000)]
+    );
+    assert_eq!(grid.take_before(360_000), vec![(300_000, 100_000)]);
+    assert!(grid.take_before(360_000).is_empty());
+  }
+}
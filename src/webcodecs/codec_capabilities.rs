@@ -0,0 +1,133 @@
+//! Encoder input format capabilities
+//!
+//! VideoEncoder converts every VideoFrame to the pixel format its encoder was
+//! opened with. The conversion is refused when libswscale cannot read the input
+//! format, or when it would drop bits of sample depth (e.g. I444P12 into an
+//! 8-bit H.264 encoder), so the loss is never silent.
+//! `getCodecCapabilities()` reports which VideoFrame formats a codec accepts.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::ffi::AVPixelFormat;
+use crate::ffi::swscale::{is_input_supported, is_output_supported};
+
+use super::features::encoder_available;
+use super::video_encoder::{encoder_pixel_format, parse_codec_string};
+
+/// VideoFrame formats that can be passed to encode()
+const VIDEO_FRAME_FORMATS: &[&str] = &[
+  "I420", "I420A", "I422", "I422A", "I444", "I444A", "NV12", "NV21", "RGBA", "RGBX", "BGRA",
+  "BGRX", "I420P10", "I422P10", "I444P10", "I420AP10", "I422AP10", "I444AP10", "I420P12",
+  "I422P12", "I444P12",
+];
+
+/// Encoder capabilities of a codec string
+#[napi(object)]
+pub struct CodecCapabilities {
+  /// The codec string the capabilities describe
+  pub codec: String,
+  /// VideoFrame format the encoder is opened with (without alpha)
+  pub encoder_format: String,
+  /// Bits per sample of `encoderFormat`
+  pub bit_depth: u32,
+  /// VideoFrame formats encode() accepts, converting them to `encoderFormat`
+  pub input_formats: Vec<String>,
+}
+
+/// Get the VideoFrame formats a video encoder codec accepts
+///
+/// Throws NotSupportedError for codec strings VideoEncoder does not support
+/// and for codecs without an encoder in this build.
+#[napi]
+pub fn get_codec_capabilities(codec: String) -> Result<CodecCapabilities> {
+  let codec_id = parse_codec_string(&codec).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("NotSupportedError: Invalid codec: {}", e),
+    )
+  })?;
+  // Keep in step with `features`, which reports the same registry lookup
+  if !encoder_available(codec_id) {
+    return Err(Error::new(
+      Status::GenericFailure,
+      format!("NotSupportedError: No {} encoder in this build", codec),
+    ));
+  }
+  let target = encoder_pixel_format(codec_id, &codec, false);
+
+  let input_formats = VIDEO_FRAME_FORMATS
+    .iter()
+    .filter(|name| {
+      AVPixelFormat::from_webcodecs_format(name)
+        .is_some_and(|format| unsupported_input_reason(format, target).is_none())
+    })
+    .map(|name| name.to_string())
+    .collect();
+
+  Ok(CodecCapabilities {
+    codec,
+    encoder_format: format_name(target),
+    bit_depth: target.bit_depth(),
+    input_formats,
+  })
+}
+
+/// Why frames in `input` cannot be converted to the encoder's `target` format
+///
+/// Returns None when the conversion is supported.
+pub(crate) fn unsupported_input_reason(
+  input: AVPixelFormat,
+  target: AVPixelFormat,
+) -> Option<String> {
+  if input == target || input.without_alpha() == Some(target) {
+    return None;
+  }
+  if !is_input_supported(input) || !is_output_supported(target) {
+    return Some(format!(
+      "{} cannot be converted to {}",
+      format_name(input),
+      format_name(target)
+    ));
+  }
+  if input.bit_depth() > target.bit_depth() {
+    return Some(format!(
+      "{}-bit samples would be truncated to {} bits",
+      input.bit_depth(),
+      target.bit_depth()
+    ));
+  }
+  None
+}
+
+/// WebCodecs name of a pixel format, or the FFmpeg one for formats WebCodecs lacks
+pub(crate) fn format_name(format: AVPixelFormat) -> String {
+  format
+    .to_webcodecs_format()
+    .map(str::to_string)
+    .unwrap_or_else(|| format!("{:?}", format))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_same_depth_conversions_are_supported() {
+    assert!(unsupported_input_reason(AVPixelFormat::Yuv420p, AVPixelFormat::Yuv420p).is_none());
+    assert!(unsupported_input_reason(AVPixelFormat::Yuva420p, AVPixelFormat::Yuv420p).is_none());
+  }
+
+  #[test]
+  fn test_bit_depth_reduction_is_rejected() {
+    let reason =
+      unsupported_input_reason(AVPixelFormat::Yuv444p12le, AVPixelFormat::Yuv420p).unwrap();
+    assert_eq!(reason, "12-bit samples would be truncated to 8 bits");
+  }
+
+  #[test]
+  fn test_format_name() {
+    assert_eq!(format_name(AVPixelFormat::Yuv444p12le), "I444P12");
+    assert_eq!(format_name(AVPixelFormat::Yuv420p), "I420");
+  }
+}
@@ -0,0 +1,301 @@
+//! Validation of video decoder configuration records
+//!
+//! Muxers copy a track's description verbatim into the container (avcC, hvcC
+//! or av1C box / CodecPrivate), so a malformed record only surfaces later as a
+//! file that fails to play. These checks catch the common mistakes up front:
+//! Annex B extradata passed where a configuration record is expected,
+//! truncated records, and records for a different profile than the codec string.
+//!
+//! Levels are not cross-checked: encoders pick the level from resolution and
+//! bitrate, and it routinely differs from the level in the configured codec string.
+
+use crate::ffi::AVCodecID;
+use crate::webcodecs::codec_string::parse_codec_string;
+
+/// Validate a video track description against the track's codec string
+///
+/// Returns a message suitable for a TypeError when the description is unusable.
+pub(crate) fn validate_video_description(
+  codec: &str,
+  codec_id: AVCodecID,
+  description: &[u8],
+) -> Result<(), String> {
+  let expected_profile = parse_codec_string(codec).and_then(|parsed| parsed.profile);
+
+  match codec_id {
+    AVCodecID::H264 => {
+      check_not_annexb(description, "avcC")?;
+      let profile = validate_avcc(description)?;
+      // Encoders may raise the profile (e.g. x264 emits High for a Baseline
+      // codec string), but a lower profile means the record is for another stream
+      if let Some(expected) = expected_profile
+        && profile < expected
+      {
+        return Err(format!(
+          "avcC description has profile_idc {} but codec string {} requires profile_idc {}",
+          profile, codec, expected
+        ));
+      }
+      Ok(())
+    }
+    AVCodecID::Hevc => {
+      check_not_annexb(description, "hvcC")?;
+      let profile = validate_hvcc(description)?;
+      if let Some(expected) = expected_profile
+        && profile != expected
+      {
+        return Err(format!(
+          "hvcC description has general_profile_idc {} but codec string {} specifies profile {}",
+          profile, codec, expected
+        ));
+      }
+      Ok(())
+    }
+    AVCodecID::Av1 => {
+      let profile = validate_av1c(description)?;
+      if let Some(expected) = expected_profile
+        && profile != expected
+      {
+        return Err(format!(
+          "av1C description has seq_profile {} but codec string {} specifies profile {}",
+          profile, codec, expected
+        ));
+      }
+      Ok(())
+    }
+    _ => Ok(()),
+  }
+}
+
+/// Reject Annex B byte streams (start code prefixed NAL units)
+fn check_not_annexb(description: &[u8], record: &str) -> Result<(), String> {
+  if description.starts_with(&[0, 0, 1]) || description.starts_with(&[0, 0, 0, 1]) {
+    return Err(format!(
+      "description looks like Annex B (starts with a start code) but must be an {} record; \
+       use decoderConfig.description from an encoder configured with format 'avc'/'hevc', \
+       or omit description for Annex B streams",
+      record
+    ));
+  }
+  Ok(())
+}
+
+/// Validate an AVCDecoderConfigurationRecord, returning its profile_idc
+fn validate_avcc(data: &[u8]) -> Result<u8, String> {
+  if data.len() < 7 {
+    return Err(format!(
+      "avcC description is truncated: {} bytes, need at least 7",
+      data.len()
+    ));
+  }
+  if data[0] != 1 {
+    return Err(format!(
+      "avcC description has configurationVersion {}, expected 1",
+      data[0]
+    ));
+  }
+  if data[4] & 0x03 == 2 {
+    return Err("avcC description has invalid NAL length size of 3 bytes".to_string());
+  }
+
+  let profile = data[1];
+  let mut reader = RecordReader::new(data, 5, "avcC");
+
+  let num_sps = reader.u8("numOfSequenceParameterSets")? & 0x1f;
+  if num_sps == 0 {
+    return Err("avcC description contains no SPS".to_string());
+  }
+  for _ in 0..num_sps {
+    let sps = reader.nal_unit("SPS")?;
+    if sps.len() < 4 || sps[0] & 0x1f != 7 {
+      return Err("avcC description has a malformed SPS".to_string());
+    }
+    if sps[1] != profile {
+      return Err(format!(
+        "avcC description has profile_idc {} but its SPS has profile_idc {}",
+        profile, sps[1]
+      ));
+    }
+  }
+
+  let num_pps = reader.u8("numOfPictureParameterSets")?;
+  for _ in 0..num_pps {
+    reader.nal_unit("PPS")?;
+  }
+
+  // High profile records may carry chroma/bit-depth extensions after the PPS
+  Ok(profile)
+}
+
+/// Validate an HEVCDecoderConfigurationRecord, returning its general_profile_idc
+fn validate_hvcc(data: &[u8]) -> Result<u8, String> {
+  if data.len() < 23 {
+    return Err(format!(
+      "hvcC description is truncated: {} bytes, need at least 23",
+      data.len()
+    ));
+  }
+  if data[0] != 1 {
+    return Err(format!(
+      "hvcC description has configurationVersion {}, expected 1",
+      data[0]
+    ));
+  }
+  if data[21] & 0x03 == 2 {
+    return Err("hvcC description has invalid NAL length size of 3 bytes".to_string());
+  }
+
+  let profile = data[1] & 0x1f;
+  let mut reader = RecordReader::new(data, 22, "hvcC");
+
+  let num_arrays = reader.u8("numOfArrays")?;
+  for _ in 0..num_arrays {
+    reader.u8("NAL unit type")?;
+    let num_nalus = reader.u16("numNalus")?;
+    for _ in 0..num_nalus {
+      reader.nal_unit("NAL unit")?;
+    }
+  }
+
+  Ok(profile)
+}
+
+/// Validate an AV1CodecConfigurationRecord, returning its seq_profile
+fn validate_av1c(data: &[u8]) -> Result<u8, String> {
+  if data.len() < 4 {
+    return Err(format!(
+      "av1C description is truncated: {} bytes, need at least 4",
+      data.len()
+    ));
+  }
+  if data[0] != 0x81 {
+    return Err(format!(
+      "av1C description must start with marker/version byte 0x81, got 0x{:02x}",
+      data[0]
+    ));
+  }
+  Ok(data[1] >> 5)
+}
+
+/// Bounds-checked cursor over a configuration record
+struct RecordReader<'a> {
+  data: &'a [u8],
+  offset: usize,
+  record: &'static str,
+}
+
+impl<'a> RecordReader<'a> {
+  fn new(data: &'a [u8], offset: usize, record: &'static str) -> Self {
+    Self {
+      data,
+      offset,
+      record,
+    }
+  }
+
+  fn take(&mut self, len: usize, what: &str) -> Result<&'a [u8], String> {
+    let end = self.offset + len;
+    if end > self.data.len() {
+      return Err(format!(
+        "{} description is truncated: {} needs {} bytes at offset {}, but only {} remain",
+        self.record,
+        what,
+        len,
+        self.offset,
+        self.data.len().saturating_sub(self.offset)
+      ));
+    }
+    let bytes = &self.data[self.offset..end];
+    self.offset = end;
+    Ok(bytes)
+  }
+
+  fn u8(&mut self, what: &str) -> Result<u8, String> {
+    Ok(self.take(1, what)?[0])
+  }
+
+  fn u16(&mut self, what: &str) -> Result<u16, String> {
+    let bytes = self.take(2, what)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+  }
+
+  /// Read a 16-bit length prefixed NAL unit
+  fn nal_unit(&mut self, what: &str) -> Result<&'a [u8], String> {
+    let len = self.u16(&format!("{} length", what))? as usize;
+    self.take(len, what)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Minimal Baseline avcC with one 4-byte SPS and one 2-byte PPS
+  const AVCC: [u8; 18] = [
+    1, 0x42, 0xc0, 0x1e, 0xff, 0xe1, 0x00, 0x04, 0x67, 0x42, 0xc0, 0x1e, 0x01, 0x00, 0x02, 0x68,
+    0xce, 0x00,
+  ];
+
+  #[test]
+  fn test_avcc_valid() {
+    assert!(validate_video_description("avc1.42001E", AVCodecID::H264, &AVCC[..17]).is_ok());
+    // Encoder chose a higher profile than the codec string
+    let mut high = AVCC;
+    high[1] = 0x64;
+    high[9] = 0x64;
+    assert!(validate_video_description("avc1.42001E", AVCodecID::H264, &high).is_ok());
+  }
+
+  #[test]
+  fn test_avcc_rejects_annexb() {
+    let annexb = [0, 0, 0, 1, 0x67, 0x42, 0xc0, 0x1e];
+    let err = validate_video_description("avc1.42001E", AVCodecID::H264, &annexb).unwrap_err();
+    assert!(err.contains("Annex B"));
+  }
+
+  #[test]
+  fn test_avcc_rejects_truncated() {
+    let err = validate_video_description("avc1.42001E", AVCodecID::H264, &AVCC[..5]).unwrap_err();
+    assert!(err.contains("truncated"));
+    let err = validate_video_description("avc1.42001E", AVCodecID::H264, &AVCC[..10]).unwrap_err();
+    assert!(err.contains("truncated") && err.contains("SPS"));
+  }
+
+  #[test]
+  fn test_avcc_rejects_lower_profile() {
+    let err = validate_video_description("avc1.64001F", AVCodecID::H264, &AVCC).unwrap_err();
+    assert!(err.contains("profile_idc"));
+  }
+
+  #[test]
+  fn test_hvcc() {
+    let mut hvcc = vec![0u8; 23];
+    hvcc[0] = 1;
+    hvcc[1] = 0x01; // Main
+    hvcc[21] = 0x03;
+    hvcc[22] = 1;
+    hvcc.extend_from_slice(&[0x20, 0x00, 0x01, 0x00, 0x02, 0x40, 0x01]);
+    assert!(validate_video_description("hvc1.1.6.L93.B0", AVCodecID::Hevc, &hvcc).is_ok());
+
+    let err = validate_video_description("hvc1.2.4.L93.B0", AVCodecID::Hevc, &hvcc).unwrap_err();
+    assert!(err.contains("general_profile_idc"));
+
+    let err =
+      validate_video_description("hvc1.1.6.L93.B0", AVCodecID::Hevc, &hvcc[..28]).unwrap_err();
+    assert!(err.contains("truncated"));
+  }
+
+  #[test]
+  fn test_av1c() {
+    let av1c = [0x81, 0x08, 0x0c, 0x00];
+    assert!(validate_video_description("av01.0.08M.08", AVCodecID::Av1, &av1c).is_ok());
+    assert!(validate_video_description("av01.1.08M.08", AVCodecID::Av1, &av1c).is_err());
+    assert!(validate_video_description("av01.0.08M.08", AVCodecID::Av1, &av1c[..3]).is_err());
+    assert!(validate_video_description("av01.0.08M.08", AVCodecID::Av1, &[0x12, 0, 0, 0]).is_err());
+  }
+
+  #[test]
+  fn test_other_codecs_unchecked() {
+    assert!(validate_video_description("vp09.00.10.08", AVCodecID::Vp9, &[0, 0, 1]).is_ok());
+  }
+}
@@ -206,9 +206,11 @@ fn parse_avc(codec: &str) -> Option<ParsedCodec> {
 }
 
 /// Parse HEVC/H.265 codec string: hev1.P.TC.Lxxx.Bx
-/// - P: profile (1=Main, 2=Main10, 3=Main Still Picture)
+/// - P: profile (1=Main, 2=Main10, 3=Main Still Picture, 4=Range Extensions)
 /// - TC: tier and compatibility flags
 /// - Lxxx: level (L120 = level 4.0, L150 = level 5.0)
+/// - Bx: constraint flag bytes (hex); for profile 4 they select the format,
+///   e.g. `hvc1.4.10.L120.9D.08` is Main 4:2:2 10
 fn parse_hevc(codec: &str) -> Option<ParsedCodec> {
   let parts: Vec<&str> = codec.split('.').collect();
 
@@ -218,11 +220,15 @@ fn parse_hevc(codec: &str) -> Option<ParsedCodec> {
     // Profile
     if let Ok(profile) = parts[1].parse::<u8>() {
       parsed.profile = Some(profile);
-      // Infer bit depth from profile
-      if profile == 2 {
-        parsed.bit_depth = Some(10); // Main 10
-      } else {
-        parsed.bit_depth = Some(8);
+      // Infer bit depth and chroma subsampling from profile
+      let format = match profile {
+        2 => Some((10, 420)), // Main 10
+        4 => parse_hevc_rext_constraints(parts.get(4..).unwrap_or_default()),
+        _ => Some((8, 420)),
+      };
+      if let Some((bit_depth, chroma)) = format {
+        parsed.bit_depth = Some(bit_depth);
+        parsed.chroma_subsampling = Some(chroma);
       }
     }
   }
@@ -240,6 +246,40 @@ fn parse_hevc(codec: &str) -> Option<ParsedCodec> {
   Some(parsed)
 }
 
+/// Bit depth and chroma subsampling of an HEVC Range Extensions profile
+///
+/// The first two constraint bytes carry, after the four source flags,
+/// general_max_12bit, max_10bit, max_8bit, max_422chroma, max_420chroma and
+/// max_monochrome (H.265 7.4.4). Returns `None` without constraint bytes or
+/// for monochrome and 16-bit profiles.
+fn parse_hevc_rext_constraints(bytes: &[&str]) -> Option<(u8, u16)> {
+  let first = u8::from_str_radix(bytes.first()?, 16).ok()?;
+  let second = match bytes.get(1) {
+    Some(byte) => u8::from_str_radix(byte, 16).ok()?,
+    None => 0,
+  };
+  if second & 0x40 != 0 {
+    return None; // max_monochrome
+  }
+  let bit_depth = if first & 0x02 != 0 {
+    8
+  } else if first & 0x04 != 0 {
+    10
+  } else if first & 0x08 != 0 {
+    12
+  } else {
+    return None;
+  };
+  let chroma = if second & 0x80 != 0 {
+    420
+  } else if first & 0x01 != 0 {
+    422
+  } else {
+    444
+  };
+  Some((bit_depth, chroma))
+}
+
 /// Get the FFmpeg profile ID for H.264 from the parsed profile value
 pub fn avc_profile_to_ffmpeg(profile_idc: u8) -> i32 {
   match profile_idc {
@@ -385,6 +425,30 @@ mod tests {
     assert_eq!(parsed.bit_depth, Some(10));
   }
 
+  #[test]
+  fn test_parse_hevc_range_extensions() {
+    let parsed = parse_codec_string("hvc1.4.10.L120.9D.08").unwrap();
+    assert_eq!(parsed.profile, Some(4));
+    assert_eq!(parsed.bit_depth, Some(10));
+    assert_eq!(parsed.chroma_subsampling, Some(422));
+
+    // Main 4:4:4 (8-bit) and Main 4:4:4 12
+    let parsed = parse_codec_string("hvc1.4.10.L120.9E.08").unwrap();
+    assert_eq!(
+      (parsed.bit_depth, parsed.chroma_subsampling),
+      (Some(8), Some(444))
+    );
+    let parsed = parse_codec_string("hvc1.4.10.L120.98.08").unwrap();
+    assert_eq!(
+      (parsed.bit_depth, parsed.chroma_subsampling),
+      (Some(12), Some(444))
+    );
+
+    // Without constraint bytes the format is unknown
+    let parsed = parse_codec_string("hvc1.4.10.L120").unwrap();
+    assert_eq!((parsed.bit_depth, parsed.chroma_subsampling), (None, None));
+  }
+
   #[test]
   fn test_parse_vp8() {
     let parsed = parse_codec_string("vp8").unwrap();
@@ -0,0 +1,100 @@
+//! Command Timing - Queue latency tracking for codec worker threads
+//!
+//! Every encode/decode command records how long it waited in the worker
+//! channel and how long the worker spent processing it. Samples go into a
+//! fixed-size lock-free ring, so recording costs two relaxed atomic operations
+//! and the rolling percentiles are only computed when `queueStats()` is called.
+
+use napi_derive::napi;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Number of most recent commands kept for percentile calculation
+const WINDOW: usize = 256;
+
+/// Rolling queue latency statistics for a codec's worker (milliseconds)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct CodecQueueStats {
+  /// Number of commands in the rolling window (up to 256)
+  pub samples: u32,
+  /// Median time a command waited in the queue before the worker picked it up
+  pub queue_wait_p50: f64,
+  /// 95th percentile queue wait
+  pub queue_wait_p95: f64,
+  /// Median time the worker spent processing a command
+  pub processing_p50: f64,
+  /// 95th percentile processing time
+  pub processing_p95: f64,
+}
+
+/// Shared between a codec and its worker thread
+pub(crate) struct CommandTimings {
+  /// Queue wait (high 32 bits) and processing time (low 32 bits) in microseconds
+  samples: [AtomicU64; WINDOW],
+  /// Total number of samples recorded since the last clear
+  recorded: AtomicUsize,
+}
+
+impl CommandTimings {
+  pub fn new() -> Self {
+    Self {
+      samples: std::array::from_fn(|_| AtomicU64::new(0)),
+      recorded: AtomicUsize::new(0),
+    }
+  }
+
+  /// Record one processed command
+  pub fn record(&self, queue_wait: Duration, processing: Duration) {
+    let slot = self.recorded.fetch_add(1, Ordering::Relaxed) % WINDOW;
+    let packed = (saturating_micros(queue_wait) << 32) | saturating_micros(processing);
+    self.samples[slot].store(packed, Ordering::Relaxed);
+  }
+
+  /// Drop all samples (on reset)
+  pub fn clear(&self) {
+    self.recorded.store(0, Ordering::Relaxed);
+  }
+
+  /// Compute percentiles over the current window
+  pub fn snapshot(&self) -> CodecQueueStats {
+    let count = self.recorded.load(Ordering::Relaxed).min(WINDOW);
+    if count == 0 {
+      return CodecQueueStats::default();
+    }
+
+    let (mut waits, mut processing): (Vec<u64>, Vec<u64>) = self.samples[..count]
+      .iter()
+      .map(|sample| {
+        let packed = sample.load(Ordering::Relaxed);
+        (packed >> 32, packed & u64::from(u32::MAX))
+      })
+      .unzip();
+    waits.sort_unstable();
+    processing.sort_unstable();
+
+    CodecQueueStats {
+      samples: count as u32,
+      queue_wait_p50: percentile_ms(&waits, 50),
+      queue_wait_p95: percentile_ms(&waits, 95),
+      processing_p50: percentile_ms(&processing, 50),
+      processing_p95: percentile_ms(&processing, 95),
+    }
+  }
+}
+
+/// Time since `enqueued_at` plus the start instant for processing
+pub(crate) fn begin_command(enqueued_at: Instant) -> (Duration, Instant) {
+  let started = Instant::now();
+  (started.saturating_duration_since(enqueued_at), started)
+}
+
+fn saturating_micros(duration: Duration) -> u64 {
+  duration.as_micros().min(u128::from(u32::MAX)) as u64
+}
+
+/// Nearest-rank percentile of sorted microsecond samples, in milliseconds
+fn percentile_ms(sorted: &[u64], percentile: usize) -> f64 {
+  let rank = (sorted.len() * percentile).div_ceil(100).max(1);
+  sorted[rank - 1] as f64 / 1000.0
+}
@@ -0,0 +1,143 @@
+//! Decode resolution sanity limits
+//!
+//! Crafted input can declare absurd dimensions (e.g. 50000x50000) and make the
+//! decoder attempt multi-gigabyte allocations. These process-wide limits are
+//! enforced by VideoDecoder (configure, FFmpeg `max_pixels`, and decoded output)
+//! and by the demuxers when parsing tracks, rejecting with NotSupportedError.
+//!
+//! The defaults match the encoder's MAX_DIMENSION. Users who legitimately need
+//! larger frames can raise them via `setDecodeLimits()`.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Default maximum coded width/height
+pub const DEFAULT_MAX_DIMENSION: u32 = 16384;
+
+/// Upper bound on bytes per pixel used to estimate a frame's allocation (8-bit RGBA)
+const ESTIMATED_BYTES_PER_PIXEL: u64 = 4;
+
+/// Default maximum estimated allocation per frame (16384x16384 RGBA = 1 GiB)
+pub const DEFAULT_MAX_FRAME_BYTES: u64 =
+  DEFAULT_MAX_DIMENSION as u64 * DEFAULT_MAX_DIMENSION as u64 * ESTIMATED_BYTES_PER_PIXEL;
+
+static MAX_DIMENSION: AtomicU32 = AtomicU32::new(DEFAULT_MAX_DIMENSION);
+static MAX_FRAME_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_MAX_FRAME_BYTES);
+
+/// Decode sanity limits
+#[napi(object)]
+pub struct DecodeLimits {
+  /// Maximum coded width or height in pixels (default: 16384)
+  pub max_dimension: Option<u32>,
+  /// Maximum estimated allocation per decoded frame in bytes, at 4 bytes per pixel
+  /// (default: 1 GiB)
+  pub max_frame_bytes: Option<i64>,
+}
+
+/// Override the process-wide decode limits
+///
+/// Omitted fields keep their current value. Limits apply to decoders
+/// configured and files loaded after the call.
+#[napi]
+pub fn set_decode_limits(limits: DecodeLimits) -> Result<()> {
+  if let Some(max_dimension) = limits.max_dimension {
+    if max_dimension == 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "maxDimension must be greater than 0",
+      ));
+    }
+    MAX_DIMENSION.store(max_dimension, Ordering::Relaxed);
+  }
+  if let Some(max_frame_bytes) = limits.max_frame_bytes {
+    if max_frame_bytes <= 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "maxFrameBytes must be greater than 0",
+      ));
+    }
+    MAX_FRAME_BYTES.store(max_frame_bytes as u64, Ordering::Relaxed);
+  }
+  Ok(())
+}
+
+/// Get the current process-wide decode limits
+#[napi]
+pub fn get_decode_limits() -> DecodeLimits {
+  DecodeLimits {
+    max_dimension: Some(MAX_DIMENSION.load(Ordering::Relaxed)),
+    max_frame_bytes: Some(MAX_FRAME_BYTES.load(Ordering::Relaxed) as i64),
+  }
+}
+
+/// Reset decode limits to their defaults
+#[napi]
+pub fn reset_decode_limits() {
+  MAX_DIMENSION.store(DEFAULT_MAX_DIMENSION, Ordering::Relaxed);
+  MAX_FRAME_BYTES.store(DEFAULT_MAX_FRAME_BYTES, Ordering::Relaxed);
+}
+
+/// Check dimensions against the current limits
+///
+/// Returns a `NotSupportedError: ...` message when the limits are exceeded.
+pub(crate) fn check_dimensions(width: u32, height: u32) -> std::result::Result<(), String> {
+  check(
+    width,
+    height,
+    MAX_DIMENSION.load(Ordering::Relaxed),
+    MAX_FRAME_BYTES.load(Ordering::Relaxed),
+  )
+}
+
+/// Maximum pixel count per frame, for FFmpeg's `max_pixels` decoder option
+pub(crate) fn max_pixels() -> u64 {
+  MAX_FRAME_BYTES.load(Ordering::Relaxed) / ESTIMATED_BYTES_PER_PIXEL
+}
+
+fn check(
+  width: u32,
+  height: u32,
+  max_dimension: u32,
+  max_frame_bytes: u64,
+) -> std::result::Result<(), String> {
+  if width > max_dimension || height > max_dimension {
+    return Err(format!(
+      "NotSupportedError: {}x{} exceeds the maximum decode dimension of {}",
+      width, height, max_dimension
+    ));
+  }
+
+  let frame_bytes = width as u64 * height as u64 * ESTIMATED_BYTES_PER_PIXEL;
+  if frame_bytes > max_frame_bytes {
+    return Err(format!(
+      "NotSupportedError: {}x{} exceeds the maximum decode frame size of {} bytes",
+      width, height, max_frame_bytes
+    ));
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_check_defaults() {
+    let check_default = |w, h| check(w, h, DEFAULT_MAX_DIMENSION, DEFAULT_MAX_FRAME_BYTES).is_ok();
+    assert!(check_default(1920, 1080));
+    assert!(check_default(16384, 16384));
+    assert!(!check_default(16385, 16));
+    assert!(!check_default(50000, 50000));
+  }
+
+  #[test]
+  fn test_check_frame_bytes() {
+    // Within the dimension limit but above the allocation limit
+    let err = check(8192, 8192, 16384, 64 * 1024 * 1024).unwrap_err();
+    assert!(err.starts_with("NotSupportedError:"));
+    assert!(check(4096, 4096, 16384, 64 * 1024 * 1024).is_ok());
+  }
+}
@@ -3,21 +3,42 @@
 //! This module provides common functionality for Mp4Demuxer, WebMDemuxer, and MkvDemuxer
 //! to eliminate code duplication across the three implementations.
 
-use crate::codec::demuxer::{DemuxerContext, MediaType, StreamInfo};
-use crate::codec::io_buffer::BufferSource;
-use crate::ffi::AVCodecID;
+use crate::codec::Packet;
+use crate::codec::demuxer::{
+  DemuxerContext, MediaType, StreamColorInfo, StreamInfo, StreamSideData,
+};
+use crate::codec::io_buffer::{BufferSource, StreamInput};
+use crate::codec::matroska::{self, ClusterInfo};
+use crate::codec::mp4_gapless::{GaplessInfo, GaplessTrim, ITUNSMPB_KEY, gapless_frame_size};
+use crate::ffi::accessors::stream_side_data_kind;
+use crate::ffi::avformat::disposition;
+use crate::ffi::{AV_NOPTS_VALUE, AVCodecID, pkt_side_data_type};
+use crate::webcodecs::audio_decoder::{AudioDecoder, AudioDecoderInit};
+use crate::webcodecs::audio_encoder::prepend_flac_header;
+use crate::webcodecs::decode_limits;
 use crate::webcodecs::encoded_audio_chunk::{
-  EncodedAudioChunk, EncodedAudioChunkInit, EncodedAudioChunkType,
+  AudioDecoderConfig, EncodedAudioChunk, EncodedAudioChunkInit, EncodedAudioChunkType,
 };
 use crate::webcodecs::encoded_video_chunk::{
-  EncodedVideoChunk, EncodedVideoChunkInit, EncodedVideoChunkType,
+  EncodedVideoChunk, EncodedVideoChunkInit, EncodedVideoChunkType, VideoDecoderConfig,
+};
+use crate::webcodecs::key_frame_packets::{KeyFramePacketIterator, KeyFramePacketsOptions};
+use crate::webcodecs::plain_bytes::PlainUint8Array;
+use crate::webcodecs::track_analysis::{TrackAnalysis, TrackAnalysisOptions, analyze_track};
+use crate::webcodecs::video_decoder::{VideoDecoder, VideoDecoderInit};
+use crate::webcodecs::video_frame::{
+  HdrDynamicMetadata, HdrDynamicMetadataType, VideoColorSpaceInit, color_space_from_av,
 };
 use napi::bindgen_prelude::*;
 use napi::threadsafe_function::{
   ThreadsafeFunction, ThreadsafeFunctionCallMode, UnknownReturnValue,
 };
 use napi_derive::napi;
+use std::ffi::CStr;
+use std::fs::File;
+use std::io::Cursor;
 use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
 
 // ============================================================================
 // BufferSource implementation for Uint8Array (zero-copy support)
@@ -120,7 +141,7 @@ impl DemuxerState {
 pub struct DemuxerTrackInfo {
   /// Track index
   pub index: i32,
-  /// Track type ("video" or "audio")
+  /// Track type ("video", "audio", "subtitle" or "data")
   pub track_type: String,
   /// Codec string (WebCodecs format)
   pub codec: String,
@@ -134,6 +155,126 @@ pub struct DemuxerTrackInfo {
   pub sample_rate: Option<u32>,
   /// Number of channels (audio only)
   pub number_of_channels: Option<u32>,
+  /// Stream-level side data to pass to a muxer track's `sideData` when remuxing
+  pub side_data: Vec<TrackSideData>,
+  /// Language tag from the container, if set
+  pub language: Option<String>,
+  /// Subtitle kind (WebVTT subtitle tracks only)
+  pub subtitle_kind: Option<SubtitleKind>,
+}
+
+/// Kind of a WebVTT text track (WebM D_WEBVTT/SUBTITLES or D_WEBVTT/CAPTIONS)
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubtitleKind {
+  /// Translation of the dialogue
+  #[default]
+  #[napi(value = "subtitles")]
+  Subtitles,
+  /// Transcription including sound effects, for the hearing impaired
+  #[napi(value = "captions")]
+  Captions,
+}
+
+impl SubtitleKind {
+  pub(crate) fn from_disposition(disposition: i32) -> Self {
+    if disposition & disposition::CAPTIONS != 0 {
+      SubtitleKind::Captions
+    } else {
+      SubtitleKind::Subtitles
+    }
+  }
+
+  pub(crate) fn to_disposition(self) -> i32 {
+    match self {
+      SubtitleKind::Subtitles => 0,
+      SubtitleKind::Captions => disposition::CAPTIONS,
+    }
+  }
+}
+
+/// A WebVTT cue, as written with `writeSubtitleChunk()` and read with `readSubtitleCues()`
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct SubtitleCue {
+  /// Start time in microseconds
+  pub timestamp: i64,
+  /// Display duration in microseconds
+  pub duration: i64,
+  /// Cue payload (may contain WebVTT markup and line breaks)
+  pub text: String,
+  /// Cue settings following the timing line, e.g. "line:0 position:20% align:start"
+  pub settings: Option<String>,
+  /// Optional cue identifier line
+  pub identifier: Option<String>,
+}
+
+/// Kind of stream-level side data
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackSideDataType {
+  /// 3x3 display transformation matrix (rotation/flip)
+  #[napi(value = "display-matrix")]
+  DisplayMatrix,
+  /// Stereoscopic 3D packing (MP4 st3d, Matroska StereoMode)
+  #[napi(value = "stereo-3d")]
+  Stereo3d,
+  /// Spherical video projection (MP4 sv3d, Matroska Projection)
+  #[napi(value = "spherical")]
+  Spherical,
+  /// HDR mastering display color volume (MP4 mdcv)
+  #[napi(value = "mastering-display")]
+  MasteringDisplay,
+  /// HDR content light level (MP4 clli)
+  #[napi(value = "content-light-level")]
+  ContentLightLevel,
+}
+
+/// Opaque stream-level side data blob
+///
+/// `data` holds FFmpeg's struct for the kind (AVSphericalMapping, AVStereo3D, ...)
+/// and is only meant to be handed back to a muxer, not interpreted.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct TrackSideData {
+  /// Side data kind
+  #[napi(js_name = "type")]
+  pub kind: TrackSideDataType,
+  /// Raw side data bytes
+  #[napi(ts_type = "Uint8Array")]
+  pub data: PlainUint8Array,
+}
+
+impl TrackSideData {
+  fn from_stream(side_data: &StreamSideData) -> Option<Self> {
+    let kind = match side_data.kind {
+      stream_side_data_kind::DISPLAY_MATRIX => TrackSideDataType::DisplayMatrix,
+      stream_side_data_kind::STEREO_3D => TrackSideDataType::Stereo3d,
+      stream_side_data_kind::SPHERICAL => TrackSideDataType::Spherical,
+      stream_side_data_kind::MASTERING_DISPLAY => TrackSideDataType::MasteringDisplay,
+      stream_side_data_kind::CONTENT_LIGHT_LEVEL => TrackSideDataType::ContentLightLevel,
+      _ => return None,
+    };
+    Some(Self {
+      kind,
+      data: PlainUint8Array::from(side_data.data.clone()),
+    })
+  }
+
+  /// Convert to the form the muxer writes
+  pub(crate) fn to_stream(&self) -> StreamSideData {
+    let kind = match self.kind {
+      TrackSideDataType::DisplayMatrix => stream_side_data_kind::DISPLAY_MATRIX,
+      TrackSideDataType::Stereo3d => stream_side_data_kind::STEREO_3D,
+      TrackSideDataType::Spherical => stream_side_data_kind::SPHERICAL,
+      TrackSideDataType::MasteringDisplay => stream_side_data_kind::MASTERING_DISPLAY,
+      TrackSideDataType::ContentLightLevel => stream_side_data_kind::CONTENT_LIGHT_LEVEL,
+    };
+    StreamSideData {
+      kind,
+      data: self.data.to_vec(),
+    }
+  }
 }
 
 /// Video decoder configuration exposed to JavaScript
@@ -146,7 +287,19 @@ pub struct DemuxerVideoDecoderConfig {
   /// Coded height
   pub coded_height: u32,
   /// Codec-specific description data (avcC/hvcC)
-  pub description: Option<Uint8Array>,
+  #[napi(ts_type = "Uint8Array")]
+  pub description: Option<PlainUint8Array>,
+  /// Color space signalled by the container (e.g. MP4 colr/nclx, Matroska Colour)
+  pub color_space: Option<VideoColorSpaceInit>,
+  /// Raw ICC profile from the container (e.g. MP4 colr/prof); not applied by the decoder
+  #[napi(ts_type = "Uint8Array")]
+  pub icc_profile: Option<PlainUint8Array>,
+  /// H.264/HEVC only: whether parameter sets are carried in the bitstream
+  ///
+  /// True for `avc3`/`hev1` sample entries and for tracks whose container has
+  /// no avcC/hvcC. When true and `description` is absent, the absence is
+  /// intentional: the decoder reads the parameter sets from the keyframes.
+  pub parameter_sets_in_band: Option<bool>,
 }
 
 /// Audio decoder configuration exposed to JavaScript
@@ -159,7 +312,8 @@ pub struct DemuxerAudioDecoderConfig {
   /// Number of channels
   pub number_of_channels: u32,
   /// Codec-specific description data
-  pub description: Option<Uint8Array>,
+  #[napi(ts_type = "Uint8Array")]
+  pub description: Option<PlainUint8Array>,
 }
 
 /// Chunk type for async iteration
@@ -206,6 +360,35 @@ impl ToNapiValue for DemuxerChunk {
   }
 }
 
+/// Raw packet record for remuxing without decoding
+///
+/// Timestamps stay in the track's time base (`timeBaseNum / timeBaseDen`
+/// seconds per tick) and include DTS, so packets copied between containers
+/// with `readPacket()` and `writePacket()` keep their timing bit-exact.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct RawPacket {
+  /// Source track index
+  pub track_id: i32,
+  /// Track type ("video" or "audio"); selects the muxer track in writePacket()
+  pub track_type: String,
+  /// Presentation timestamp in time base units
+  pub pts: Option<i64>,
+  /// Decode timestamp in time base units (writePacket() defaults it to pts)
+  pub dts: Option<i64>,
+  /// Duration in time base units
+  pub duration: Option<i64>,
+  /// Time base numerator
+  pub time_base_num: i32,
+  /// Time base denominator
+  pub time_base_den: i32,
+  /// Packet flags (1 = key, 2 = corrupt, 4 = discard, 16 = disposable)
+  pub flags: i32,
+  /// Packet payload
+  #[napi(ts_type = "Uint8Array")]
+  pub data: PlainUint8Array,
+}
+
 // ============================================================================
 // DemuxerFormat Trait - Format-specific behavior
 // ============================================================================
@@ -219,12 +402,28 @@ pub trait DemuxerFormat: Send + Sync + 'static {
   /// profile/level information for more accurate codec strings.
   fn codec_id_to_video_string(codec_id: AVCodecID, extradata: Option<&[u8]>) -> String;
 
+  /// Convert a video stream to WebCodecs codec string
+  ///
+  /// Formats that record the sample entry (e.g. MP4 `hvc1` vs `hev1`) can
+  /// override this to use the stream's codec tag.
+  fn video_codec_string(stream: &StreamInfo) -> String {
+    Self::codec_id_to_video_string(stream.codec_id, stream.extradata.as_deref())
+  }
+
   /// Convert audio codec ID to WebCodecs codec string
   ///
   /// The extradata parameter contains codec-specific configuration data
   /// (e.g., AudioSpecificConfig for AAC) that can be parsed to extract
   /// profile information.
   fn codec_id_to_audio_string(codec_id: AVCodecID, extradata: Option<&[u8]>) -> String;
+
+  /// Convert an audio stream to WebCodecs codec string
+  ///
+  /// Formats whose streams carry no extradata (e.g. ADTS AAC) can override
+  /// this to use other stream properties such as the probed profile.
+  fn audio_codec_string(stream: &StreamInfo) -> String {
+    Self::codec_id_to_audio_string(stream.codec_id, stream.extradata.as_deref())
+  }
 }
 
 // ============================================================================
@@ -249,10 +448,72 @@ pub struct DemuxerInner<F: DemuxerFormat> {
   pub audio_callback: Option<AudioOutputCallback>,
   /// Error callback
   pub error_callback: Option<ErrorCallback>,
+  /// Best-effort recovery of truncated Matroska files
+  pub allow_truncated: bool,
+  /// Result of the recovery scan (set on load when `allow_truncated` is enabled)
+  recovery: Option<RecoveryScan>,
+  /// Loaded file or buffer, for opening independent contexts (analyzeTrack, keyFramePackets)
+  source: Option<DemuxerSource>,
+  /// Appended input whose demuxer is opened on the first read (see `load_stream`)
+  pending_stream: Option<PendingStream>,
+  /// Padding trim from an iTunSMPB tag for the selected audio track
+  ///
+  /// Only valid while reading sequentially from the start; cleared by seeks.
+  gapless_trim: Option<GaplessTrim>,
   /// Phantom data for format type
   _format: PhantomData<F>,
 }
 
+/// Where a demuxer's data came from
+#[derive(Clone)]
+pub(crate) enum DemuxerSource {
+  File(String),
+  Buffer(Arc<dyn BufferSource>),
+  /// Data appended while demuxing; it is read once and cannot be reopened
+  Appended,
+}
+
+/// Appended input waiting to be opened
+struct PendingStream {
+  input: Arc<StreamInput>,
+  /// FFmpeg input format name
+  format: &'static CStr,
+  low_latency: bool,
+}
+
+impl DemuxerSource {
+  /// Open a new demuxer context reading from the start of the source
+  pub(crate) fn open(&self) -> Result<DemuxerContext> {
+    match self {
+      DemuxerSource::File(path) => DemuxerContext::open_file(path),
+      DemuxerSource::Buffer(source) => DemuxerContext::open_buffer(source.clone()),
+      DemuxerSource::Appended => {
+        return Err(Error::new(
+          Status::GenericFailure,
+          "Appended input can only be read once; load a file or buffer instead",
+        ));
+      }
+    }
+    .map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to open source: {}", e),
+      )
+    })
+  }
+}
+
+/// What a recovery scan found in a possibly truncated file
+#[derive(Debug, Clone, Copy)]
+struct RecoveryScan {
+  /// Whether the file ended in a damaged cluster or hit a read error
+  truncated: bool,
+  /// Byte offset of the damaged cluster; packets at or after it are dropped
+  cutoff_pos: Option<i64>,
+  /// End time of the last usable packet in microseconds
+  usable_duration_us: Option<i64>,
+}
+
 impl<F: DemuxerFormat> DemuxerInner<F> {
   /// Create a new demuxer inner state
   pub fn new(
@@ -269,6 +530,11 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       video_callback,
       audio_callback,
       error_callback: Some(error_callback),
+      allow_truncated: false,
+      recovery: None,
+      source: None,
+      pending_stream: None,
+      gapless_trim: None,
       _format: PhantomData,
     }
   }
@@ -282,15 +548,33 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       ));
     }
 
-    let demuxer = DemuxerContext::open_file(path).map_err(|e| {
-      Error::new(
-        Status::GenericFailure,
-        format!("Failed to open file: {}", e),
-      )
-    })?;
+    let open = || {
+      DemuxerContext::open_file_with_options(path, self.allow_truncated).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open file: {}", e),
+        )
+      })
+    };
+    let demuxer = open()?;
 
-    self.finish_load(demuxer);
-    Ok(())
+    if self.allow_truncated {
+      let last_cluster = File::open(path)
+        .and_then(|mut file| {
+          let len = file.metadata()?.len();
+          matroska::find_last_cluster(&mut file, len)
+        })
+        .map_err(|e| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Failed to scan file: {}", e),
+          )
+        })?;
+      self.recovery = Some(scan_for_recovery(open()?, last_cluster));
+    }
+
+    self.source = Some(DemuxerSource::File(path.to_string()));
+    self.finish_load(demuxer)
   }
 
   /// Load from a buffer
@@ -305,22 +589,114 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       ));
     }
 
-    let demuxer = DemuxerContext::open_buffer(source).map_err(|e| {
+    let open_error = |e| {
       Error::new(
         Status::GenericFailure,
         format!("Failed to open buffer: {}", e),
       )
+    };
+
+    let source: Arc<dyn BufferSource> = Arc::new(source);
+    if !self.allow_truncated {
+      let demuxer = DemuxerContext::open_buffer(source.clone()).map_err(open_error)?;
+      self.source = Some(DemuxerSource::Buffer(source));
+      return self.finish_load(demuxer);
+    }
+
+    // Recovery needs a second pass over the same data to find the usable range
+    let (ptr, len) = source.buffer_data();
+    let data = if ptr.is_null() {
+      &[][..]
+    } else {
+      // SAFETY: BufferSource guarantees the pointer is valid while `source` is alive
+      unsafe { std::slice::from_raw_parts(ptr, len) }
+    };
+    let last_cluster =
+      matroska::find_last_cluster(&mut Cursor::new(data), len as u64).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to scan buffer: {}", e),
+        )
+      })?;
+
+    let demuxer =
+      DemuxerContext::open_buffer_with_options(source.clone(), true).map_err(open_error)?;
+    let scan_demuxer =
+      DemuxerContext::open_buffer_with_options(source.clone(), true).map_err(open_error)?;
+    self.recovery = Some(scan_for_recovery(scan_demuxer, last_cluster));
+    self.source = Some(DemuxerSource::Buffer(source));
+
+    self.finish_load(demuxer)
+  }
+
+  /// Load track info for data that is appended while demuxing
+  ///
+  /// `init_segment` holds the container header (e.g. MP4 `ftyp` + `moov`) and
+  /// is parsed on its own, so tracks are known before any media data arrives.
+  /// The demuxer reading `input` is opened on the first read instead, since
+  /// opening it blocks until the first fragment has been appended.
+  pub fn load_stream(
+    &mut self,
+    init_segment: Vec<u8>,
+    input: Arc<StreamInput>,
+    format: &'static CStr,
+    low_latency: bool,
+  ) -> Result<()> {
+    if self.state != DemuxerState::Unloaded {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Demuxer already loaded. Call close() first.",
+      ));
+    }
+
+    // Error-tolerant, as probing finds no samples in a header-only segment
+    let demuxer = DemuxerContext::open_buffer_with_options(init_segment, true).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to open init segment: {}", e),
+      )
     })?;
 
-    self.finish_load(demuxer);
+    self.source = Some(DemuxerSource::Appended);
+    self.pending_stream = Some(PendingStream {
+      input,
+      format,
+      low_latency,
+    });
+    self.finish_load(demuxer)
+  }
+
+  /// Replace the init segment's context with a demuxer reading the appended input
+  ///
+  /// Blocks until the input holds a complete container header.
+  fn open_pending_stream(&mut self) -> Result<()> {
+    let Some(pending) = self.pending_stream.take() else {
+      return Ok(());
+    };
+    let demuxer = DemuxerContext::open_stream(pending.input, pending.format, pending.low_latency)
+      .map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to open appended input: {}", e),
+      )
+    })?;
+    self.demuxer = Some(demuxer);
     Ok(())
   }
 
   /// Complete the load process (shared between file and buffer loading)
-  fn finish_load(&mut self, demuxer: DemuxerContext) {
+  fn finish_load(&mut self, demuxer: DemuxerContext) -> Result<()> {
     // Parse track info using format-specific codec string conversion
     let tracks = parse_tracks::<F>(demuxer.streams());
 
+    // Reject tracks declaring dimensions beyond the decode limits
+    for track in tracks.iter().filter(|t| t.track_type == "video") {
+      if let (Some(w), Some(h)) = (track.coded_width, track.coded_height) {
+        decode_limits::check_dimensions(w, h)
+          .map_err(|msg| Error::new(Status::GenericFailure, msg))?;
+      }
+    }
+
     // Select first video and audio tracks by default
     let selected_video_track = tracks
       .iter()
@@ -335,7 +711,18 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
     self.tracks = tracks;
     self.selected_video_track = selected_video_track;
     self.selected_audio_track = selected_audio_track;
+    self.gapless_trim = self.load_gapless_trim();
     self.state = DemuxerState::Ready;
+    Ok(())
+  }
+
+  /// Trim for the padding declared by an MP4 iTunSMPB tag, which describes
+  /// the first audio track
+  fn load_gapless_trim(&self) -> Option<GaplessTrim> {
+    let demuxer = self.demuxer.as_ref()?;
+    let stream = demuxer.audio_stream()?;
+    let info = GaplessInfo::from_itunsmpb(&demuxer.metadata(ITUNSMPB_KEY)?)?;
+    Some(GaplessTrim::new(info, gapless_frame_size(stream.codec_id)?))
   }
 
   /// Get all tracks
@@ -344,8 +731,28 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
   }
 
   /// Get container duration in microseconds
+  ///
+  /// For truncated files loaded in recovery mode this is the usable duration
+  /// actually found rather than the (possibly missing) declared duration.
   pub fn get_duration(&self) -> Option<i64> {
-    self.demuxer.as_ref().and_then(|d| d.duration_us())
+    let declared = self.demuxer.as_ref().and_then(|d| d.duration_us());
+    match self.recovery {
+      Some(recovery) if recovery.truncated || declared.is_none() => recovery.usable_duration_us,
+      _ => declared,
+    }
+  }
+
+  /// Whether recovery mode found the file truncated or damaged
+  pub fn is_truncated(&self) -> bool {
+    self.recovery.is_some_and(|r| r.truncated)
+  }
+
+  /// Whether a packet lies in the damaged tail found by the recovery scan
+  fn past_recovery_cutoff(&self, packet: &crate::codec::Packet) -> bool {
+    self
+      .recovery
+      .and_then(|r| r.cutoff_pos)
+      .is_some_and(|cutoff| packet.pos() >= cutoff)
   }
 
   /// Get video decoder configuration for the selected video track
@@ -354,14 +761,18 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
     let video_index = self.selected_video_track?;
 
     demuxer.get_stream(video_index).map(|s| {
-      let codec = F::codec_id_to_video_string(s.codec_id, s.extradata.as_deref());
-      let description = s.extradata.as_ref().map(|d| Uint8Array::new(d.clone()));
+      let codec = F::video_codec_string(s);
+      let description =
+        decoder_description(s.codec_id, s.extradata.as_deref()).map(PlainUint8Array::from);
 
       DemuxerVideoDecoderConfig {
         codec,
         coded_width: s.width.unwrap_or(0),
         coded_height: s.height.unwrap_or(0),
         description,
+        color_space: stream_color_space(s),
+        icc_profile: s.icc_profile.clone().map(PlainUint8Array::from),
+        parameter_sets_in_band: parameter_sets_in_band(s),
       }
     })
   }
@@ -372,8 +783,9 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
     let audio_index = self.selected_audio_track?;
 
     demuxer.get_stream(audio_index).map(|s| {
-      let codec = F::codec_id_to_audio_string(s.codec_id, s.extradata.as_deref());
-      let description = s.extradata.as_ref().map(|d| Uint8Array::new(d.clone()));
+      let codec = F::audio_codec_string(s);
+      let description =
+        decoder_description(s.codec_id, s.extradata.as_deref()).map(PlainUint8Array::from);
 
       DemuxerAudioDecoderConfig {
         codec,
@@ -384,6 +796,60 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
     })
   }
 
+  /// Build a ready-to-use VideoDecoderConfig for a video track and select it for demuxing
+  ///
+  /// Unlike `get_video_decoder_config()`, the description is converted to the
+  /// form VideoDecoder expects for the track's codec.
+  pub fn video_decoder_config_for_track(&mut self, track_index: i32) -> Result<VideoDecoderConfig> {
+    self.select_video_track(track_index)?;
+    let stream = self
+      .demuxer
+      .as_ref()
+      .and_then(|d| d.get_stream(track_index))
+      .ok_or_else(|| Error::new(Status::GenericFailure, "Demuxer not loaded"))?;
+
+    Ok(VideoDecoderConfig {
+      codec: Some(F::video_codec_string(stream)),
+      coded_width: stream.width,
+      coded_height: stream.height,
+      display_aspect_width: None,
+      display_aspect_height: None,
+      color_space: stream_color_space(stream),
+      hardware_acceleration: None,
+      optimize_for_latency: None,
+      description: decoder_description(stream.codec_id, stream.extradata.as_deref())
+        .map(Uint8Array::from),
+      rotation: None,
+      flip: None,
+      alpha: None,
+      max_outstanding_frames: None,
+      key_frames_only: None,
+      timestamp_mode: None,
+      start_at_zero: None,
+      input_granularity: None,
+    })
+  }
+
+  /// Build a ready-to-use AudioDecoderConfig for an audio track and select it for demuxing
+  pub fn audio_decoder_config_for_track(&mut self, track_index: i32) -> Result<AudioDecoderConfig> {
+    self.select_audio_track(track_index)?;
+    let stream = self
+      .demuxer
+      .as_ref()
+      .and_then(|d| d.get_stream(track_index))
+      .ok_or_else(|| Error::new(Status::GenericFailure, "Demuxer not loaded"))?;
+
+    Ok(AudioDecoderConfig {
+      codec: Some(F::audio_codec_string(stream)),
+      sample_rate: stream.sample_rate.map(f64::from),
+      number_of_channels: stream.channels,
+      description: decoder_description(stream.codec_id, stream.extradata.as_deref())
+        .map(Uint8Array::from),
+      timestamp_mode: None,
+      start_at_zero: None,
+    })
+  }
+
   /// Select a video track by index
   pub fn select_video_track(&mut self, track_index: i32) -> Result<()> {
     let track = self.tracks.iter().find(|t| t.index == track_index);
@@ -408,6 +874,10 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
     let track = self.tracks.iter().find(|t| t.index == track_index);
     match track {
       Some(t) if t.track_type == "audio" => {
+        if self.selected_audio_track != Some(track_index) {
+          // iTunSMPB describes the first audio track, selected by default
+          self.gapless_trim = None;
+        }
         self.selected_audio_track = Some(track_index);
         Ok(())
       }
@@ -440,6 +910,13 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       return;
     }
 
+    if let Err(e) = self.open_pending_stream() {
+      if let Some(ref err_cb) = self.error_callback {
+        let _ = err_cb.call(e, ThreadsafeFunctionCallMode::NonBlocking);
+      }
+      return;
+    }
+
     self.state = DemuxerState::Demuxing;
 
     let video_index = self.selected_video_track;
@@ -467,6 +944,11 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       };
 
       match demuxer.read_packet() {
+        Ok(Some((packet, _))) if self.past_recovery_cutoff(&packet) => {
+          // Remaining packets belong to the damaged cluster
+          self.state = DemuxerState::EndOfStream;
+          break;
+        }
         Ok(Some((packet, stream_index))) => {
           if Some(stream_index) == video_index {
             // Process video packet
@@ -483,11 +965,20 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
               EncodedVideoChunkType::Delta
             };
 
+            // Containers storing HDR10+ outside the bitstream expose it as side data
+            let hdr_dynamic_metadata = packet.hdr10plus_t35().map(|data| {
+              vec![HdrDynamicMetadata {
+                kind: HdrDynamicMetadataType::ItuTT35,
+                data,
+              }]
+            });
+
             let init = EncodedVideoChunkInit {
               chunk_type,
               timestamp,
               duration,
               data: Either::B(packet),
+              hdr_dynamic_metadata,
             };
 
             match EncodedVideoChunk::new(init) {
@@ -510,6 +1001,10 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
             }
           } else if Some(stream_index) == audio_index {
             // Process audio packet
+            let mut packet = packet;
+            if let Some(trim) = self.gapless_trim.as_mut() {
+              trim.apply(&mut packet);
+            }
             let timestamp = convert_timestamp(packet.pts(), audio_time_base);
             let duration = if packet.duration() > 0 {
               Some(convert_timestamp(packet.duration(), audio_time_base))
@@ -552,6 +1047,11 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
           self.state = DemuxerState::EndOfStream;
           break;
         }
+        Err(_) if self.recovery.is_some() => {
+          // Recovery mode: treat unrecoverable damage as the end of the usable data
+          self.state = DemuxerState::EndOfStream;
+          break;
+        }
         Err(e) => {
           if let Some(ref err_cb) = self.error_callback {
             let _ = err_cb.call(
@@ -567,6 +1067,13 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
 
   /// Seek to a timestamp in microseconds
   pub fn seek(&mut self, timestamp_us: i64) -> Result<()> {
+    if matches!(self.source, Some(DemuxerSource::Appended)) {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Appended input cannot be seeked",
+      ));
+    }
+
     let stream_index = self.selected_video_track.unwrap_or(-1);
 
     let demuxer = self
@@ -601,6 +1108,9 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       .seek(stream_index, timestamp, true)
       .map_err(|e| Error::new(Status::GenericFailure, format!("Seek failed: {}", e)))?;
 
+    // Packet positions are unknown after a seek
+    self.gapless_trim = None;
+
     // Reset state to ready for more demuxing
     if self.state == DemuxerState::EndOfStream {
       self.state = DemuxerState::Ready;
@@ -632,6 +1142,8 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       return Ok(None);
     }
 
+    self.open_pending_stream()?;
+
     let video_index = self.selected_video_track;
     let audio_index = self.selected_audio_track;
 
@@ -661,46 +1173,26 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
       };
 
       match demuxer.read_packet() {
+        Ok(Some((packet, _))) if self.past_recovery_cutoff(&packet) => {
+          // Remaining packets belong to the damaged cluster
+          self.state = DemuxerState::EndOfStream;
+          return Ok(None);
+        }
         Ok(Some((packet, stream_index))) => {
           if Some(stream_index) == video_index {
             // Process video packet
-            let timestamp = convert_timestamp(packet.pts(), video_time_base);
-            let duration = if packet.duration() > 0 {
-              Some(convert_timestamp(packet.duration(), video_time_base))
-            } else {
-              None
-            };
-
-            let chunk_type = if packet.is_key() {
-              EncodedVideoChunkType::Key
-            } else {
-              EncodedVideoChunkType::Delta
-            };
-
-            let init = EncodedVideoChunkInit {
-              chunk_type,
-              timestamp,
-              duration,
-              data: Either::B(packet),
-            };
-
-            match EncodedVideoChunk::new(init) {
-              Ok(chunk) => {
-                return Ok(Some(DemuxerChunk {
-                  chunk_type: "video".to_string(),
-                  video_chunk: Some(chunk),
-                  audio_chunk: None,
-                }));
-              }
-              Err(e) => {
-                return Err(Error::new(
-                  Status::GenericFailure,
-                  format!("Failed to create video chunk: {}", e),
-                ));
-              }
-            }
+            let chunk = video_chunk_from_packet(packet, video_time_base)?;
+            return Ok(Some(DemuxerChunk {
+              chunk_type: "video".to_string(),
+              video_chunk: Some(chunk),
+              audio_chunk: None,
+            }));
           } else if Some(stream_index) == audio_index {
             // Process audio packet
+            let mut packet = packet;
+            if let Some(trim) = self.gapless_trim.as_mut() {
+              trim.apply(&mut packet);
+            }
             let timestamp = convert_timestamp(packet.pts(), audio_time_base);
             let duration = if packet.duration() > 0 {
               Some(convert_timestamp(packet.duration(), audio_time_base))
@@ -738,6 +1230,97 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
           self.state = DemuxerState::EndOfStream;
           return Ok(None);
         }
+        Err(_) if self.recovery.is_some() => {
+          // Recovery mode: treat unrecoverable damage as the end of the usable data
+          self.state = DemuxerState::EndOfStream;
+          return Ok(None);
+        }
+        Err(e) => {
+          return Err(Error::new(
+            Status::GenericFailure,
+            format!("Demuxer error: {}", e),
+          ));
+        }
+      }
+    }
+  }
+
+  /// Read the next packet of the selected tracks as a raw record
+  ///
+  /// Unlike `read_next_chunk`, PTS, DTS and duration stay in the stream time
+  /// base and the packet flags are kept, for remuxing without decoding. Shares
+  /// the read position with chunk iteration.
+  ///
+  /// This is a blocking operation and should be called from a blocking context.
+  pub fn read_next_packet(&mut self) -> Result<Option<RawPacket>> {
+    if self.state != DemuxerState::Ready
+      && self.state != DemuxerState::Demuxing
+      && self.state != DemuxerState::EndOfStream
+    {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Demuxer is not ready. Call load() first.",
+      ));
+    }
+
+    if self.state == DemuxerState::EndOfStream {
+      return Ok(None);
+    }
+
+    self.open_pending_stream()?;
+    self.state = DemuxerState::Demuxing;
+
+    loop {
+      let demuxer = match self.demuxer.as_mut() {
+        Some(d) => d,
+        None => {
+          self.state = DemuxerState::EndOfStream;
+          return Ok(None);
+        }
+      };
+
+      match demuxer.read_packet() {
+        Ok(Some((packet, _))) if self.past_recovery_cutoff(&packet) => {
+          self.state = DemuxerState::EndOfStream;
+          return Ok(None);
+        }
+        Ok(Some((packet, stream_index))) => {
+          let track_type = if Some(stream_index) == self.selected_video_track {
+            "video"
+          } else if Some(stream_index) == self.selected_audio_track {
+            "audio"
+          } else {
+            // Skip packets from unselected tracks
+            continue;
+          };
+
+          let (time_base_num, time_base_den) = self
+            .demuxer
+            .as_ref()
+            .and_then(|d| d.get_stream(stream_index).map(|s| s.time_base))
+            .unwrap_or((1, 1_000_000));
+          let timestamp = |ts: i64| (ts != AV_NOPTS_VALUE).then_some(ts);
+
+          return Ok(Some(RawPacket {
+            track_id: stream_index,
+            track_type: track_type.to_string(),
+            pts: timestamp(packet.pts()),
+            dts: timestamp(packet.dts()),
+            duration: (packet.duration() > 0).then(|| packet.duration()),
+            time_base_num,
+            time_base_den,
+            flags: packet.flags(),
+            data: packet.as_slice().to_vec().into(),
+          }));
+        }
+        Ok(None) => {
+          self.state = DemuxerState::EndOfStream;
+          return Ok(None);
+        }
+        Err(_) if self.recovery.is_some() => {
+          self.state = DemuxerState::EndOfStream;
+          return Ok(None);
+        }
         Err(e) => {
           return Err(Error::new(
             Status::GenericFailure,
@@ -751,6 +1334,10 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
   /// Close the demuxer and release resources
   pub fn close(&mut self) {
     self.demuxer = None;
+    self.recovery = None;
+    self.source = None;
+    self.pending_stream = None;
+    self.gapless_trim = None;
     self.tracks.clear();
     self.selected_video_track = None;
     self.selected_audio_track = None;
@@ -767,6 +1354,55 @@ impl<F: DemuxerFormat> DemuxerInner<F> {
 // Helper Functions
 // ============================================================================
 
+/// Read a damaged file to the end to find where usable data stops
+///
+/// The damaged cluster is the last one if its declared size runs past the
+/// end of the file, or if it has an unknown size and demuxing hit an error.
+fn scan_for_recovery(
+  mut demuxer: DemuxerContext,
+  last_cluster: Option<ClusterInfo>,
+) -> RecoveryScan {
+  let last_cluster_pos = last_cluster.map(|c| c.offset as i64);
+  let mut read_error = false;
+  let mut end_all: Option<i64> = None;
+  let mut end_before_last_cluster: Option<i64> = None;
+
+  loop {
+    match demuxer.read_packet() {
+      Ok(Some((packet, stream_index))) => {
+        if packet.is_corrupt() || packet.pts() == crate::ffi::types::AV_NOPTS_VALUE {
+          continue;
+        }
+        let time_base = demuxer.get_stream(stream_index).map(|s| s.time_base);
+        let end = convert_timestamp(packet.pts() + packet.duration().max(0), time_base);
+        end_all = end_all.max(Some(end));
+        if last_cluster_pos.is_none_or(|pos| packet.pos() < pos) {
+          end_before_last_cluster = end_before_last_cluster.max(Some(end));
+        }
+      }
+      Ok(None) => break,
+      Err(_) => {
+        read_error = true;
+        break;
+      }
+    }
+  }
+
+  let cutoff_pos = last_cluster
+    .filter(|c| c.complete.map_or(read_error, |complete| !complete))
+    .map(|c| c.offset as i64);
+
+  RecoveryScan {
+    truncated: read_error || cutoff_pos.is_some(),
+    cutoff_pos,
+    usable_duration_us: if cutoff_pos.is_some() {
+      end_before_last_cluster
+    } else {
+      end_all
+    },
+  }
+}
+
 /// Parse stream info into track info using format-specific codec conversion
 fn parse_tracks<F: DemuxerFormat>(streams: &[StreamInfo]) -> Vec<DemuxerTrackInfo> {
   streams
@@ -780,9 +1416,11 @@ fn parse_tracks<F: DemuxerFormat>(streams: &[StreamInfo]) -> Vec<DemuxerTrackInf
       };
 
       let codec = if s.media_type == MediaType::Video {
-        F::codec_id_to_video_string(s.codec_id, s.extradata.as_deref())
+        F::video_codec_string(s)
+      } else if s.codec_id == AVCodecID::Webvtt {
+        s.codec_id.to_webcodecs_codec().to_string()
       } else {
-        F::codec_id_to_audio_string(s.codec_id, s.extradata.as_deref())
+        F::audio_codec_string(s)
       };
 
       // Calculate duration in microseconds from stream duration and time base
@@ -808,11 +1446,321 @@ fn parse_tracks<F: DemuxerFormat>(streams: &[StreamInfo]) -> Vec<DemuxerTrackInf
         coded_height: s.height,
         sample_rate: s.sample_rate,
         number_of_channels: s.channels,
+        side_data: s
+          .side_data
+          .iter()
+          .filter_map(TrackSideData::from_stream)
+          .collect(),
+        language: s.language.clone(),
+        subtitle_kind: (s.codec_id == AVCodecID::Webvtt)
+          .then(|| SubtitleKind::from_disposition(s.disposition)),
       }
     })
     .collect()
 }
 
+/// Container color description as a VideoColorSpaceInit, if one is signalled
+fn stream_color_space(stream: &StreamInfo) -> Option<VideoColorSpaceInit> {
+  (stream.color != StreamColorInfo::default()).then(|| color_space_from_av(&stream.color).to_init())
+}
+
+/// Convert container extradata into a WebCodecs decoder `description`
+///
+/// - H.264/HEVC: avcC/hvcC pass through; Annex B extradata is dropped so the
+///   decoder reads parameter sets in-band
+/// - AV1: av1C passes through; raw sequence header OBUs are dropped
+/// - VP8/VP9/MP3: no description
+/// - FLAC: bare STREAMINFO gets the "fLaC" stream header the spec requires
+/// - Everything else (AAC AudioSpecificConfig, OpusHead, ...) passes through
+fn decoder_description(codec_id: AVCodecID, extradata: Option<&[u8]>) -> Option<Vec<u8>> {
+  let extradata = extradata.filter(|d| !d.is_empty())?;
+  if codec_id == AVCodecID::Flac {
+    return Some(prepend_flac_header(extradata));
+  }
+  let keep = match codec_id {
+    // avcC/hvcC start with configurationVersion = 1
+    AVCodecID::H264 | AVCodecID::Hevc => extradata[0] == 0x01,
+    // av1C starts with marker (1) + version (1)
+    AVCodecID::Av1 => extradata[0] == 0x81,
+    AVCodecID::Vp8 | AVCodecID::Vp9 | AVCodecID::Mp3 => false,
+    _ => true,
+  };
+  keep.then(|| extradata.to_vec())
+}
+
+/// Whether an H.264/HEVC stream carries its parameter sets in-band
+///
+/// `avc3`/`hev1` sample entries allow parameter sets in the samples, and a
+/// track without avcC/hvcC has nowhere else to keep them. None for other codecs.
+fn parameter_sets_in_band(stream: &StreamInfo) -> Option<bool> {
+  if !matches!(stream.codec_id, AVCodecID::H264 | AVCodecID::Hevc) {
+    return None;
+  }
+  let in_band_entry = matches!(&stream.codec_tag.to_le_bytes(), b"avc3" | b"hev1");
+  Some(in_band_entry || decoder_description(stream.codec_id, stream.extradata.as_deref()).is_none())
+}
+
+/// Create a VideoDecoder configured for a demuxer video track
+///
+/// `init` carries the decoder callbacks plus optional VideoDecoderConfig
+/// overrides (e.g. `hardwareAcceleration`), which take precedence over the
+/// values derived from the track.
+pub(crate) fn create_video_decoder<F: DemuxerFormat>(
+  inner: &Mutex<DemuxerInner<F>>,
+  env: Env,
+  track_index: i32,
+  init: Object,
+) -> Result<VideoDecoder> {
+  let track_config = inner
+    .lock()
+    .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?
+    .video_decoder_config_for_track(track_index)?;
+
+  let decoder_init = unsafe { VideoDecoderInit::from_napi_value(env.raw(), init.raw())? };
+  let overrides = unsafe { VideoDecoderConfig::from_napi_value(env.raw(), init.raw())? };
+  let config = VideoDecoderConfig {
+    codec: overrides.codec.or(track_config.codec),
+    coded_width: overrides.coded_width.or(track_config.coded_width),
+    coded_height: overrides.coded_height.or(track_config.coded_height),
+    description: overrides.description.or(track_config.description),
+    ..overrides
+  };
+
+  let mut decoder = VideoDecoder::new(env, decoder_init)?;
+  decoder.configure(env, config)?;
+  Ok(decoder)
+}
+
+/// Create an AudioDecoder configured for a demuxer audio track
+///
+/// `init` carries the decoder callbacks plus optional AudioDecoderConfig
+/// overrides, which take precedence over the values derived from the track.
+pub(crate) fn create_audio_decoder<F: DemuxerFormat>(
+  inner: &Mutex<DemuxerInner<F>>,
+  env: Env,
+  track_index: i32,
+  init: Object,
+) -> Result<AudioDecoder> {
+  let track_config = inner
+    .lock()
+    .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?
+    .audio_decoder_config_for_track(track_index)?;
+
+  let decoder_init = unsafe { AudioDecoderInit::from_napi_value(env.raw(), init.raw())? };
+  let overrides = unsafe { AudioDecoderConfig::from_napi_value(env.raw(), init.raw())? };
+  let config = AudioDecoderConfig {
+    codec: overrides.codec.or(track_config.codec),
+    sample_rate: overrides.sample_rate.or(track_config.sample_rate),
+    number_of_channels: overrides
+      .number_of_channels
+      .or(track_config.number_of_channels),
+    description: overrides.description.or(track_config.description),
+    ..overrides
+  };
+
+  let mut decoder = AudioDecoder::new(env, decoder_init)?;
+  decoder.configure(env, config)?;
+  Ok(decoder)
+}
+
+/// Scan a track's packet timing on a separate demuxer context
+///
+/// The demuxer lock is only held to look up the source, so demuxing and the
+/// read position are unaffected by the scan.
+pub(crate) async fn analyze_demuxer_track<F: DemuxerFormat>(
+  inner: &Arc<Mutex<DemuxerInner<F>>>,
+  track_index: i32,
+  options: TrackAnalysisOptions,
+) -> Result<TrackAnalysis> {
+  let source = {
+    let guard = inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    if !guard.tracks.iter().any(|t| t.index == track_index) {
+      return Err(Error::new(
+        Status::InvalidArg,
+        format!("Invalid track index: {}", track_index),
+      ));
+    }
+    guard
+      .source
+      .clone()
+      .ok_or_else(|| Error::new(Status::GenericFailure, "Demuxer not loaded"))?
+  };
+
+  tokio::task::spawn_blocking(move || analyze_track(source.open()?, track_index, &options))
+    .await
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+}
+
+/// Read every cue of a WebVTT track on a separate demuxer context
+/// (shared `readSubtitleCues()` implementation)
+pub(crate) async fn read_demuxer_subtitle_cues<F: DemuxerFormat>(
+  inner: &Arc<Mutex<DemuxerInner<F>>>,
+  track_index: i32,
+) -> Result<Vec<SubtitleCue>> {
+  let source = {
+    let guard = inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    match guard.tracks.iter().find(|t| t.index == track_index) {
+      Some(track) if track.subtitle_kind.is_some() => {}
+      Some(_) => {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!("Track {} is not a WebVTT track", track_index),
+        ));
+      }
+      None => {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!("Invalid track index: {}", track_index),
+        ));
+      }
+    }
+    guard
+      .source
+      .clone()
+      .ok_or_else(|| Error::new(Status::GenericFailure, "Demuxer not loaded"))?
+  };
+
+  tokio::task::spawn_blocking(move || {
+    let mut demuxer = source.open()?;
+    let time_base = demuxer
+      .get_stream(track_index)
+      .map(|stream| stream.time_base);
+    let side_data_text = |packet: &Packet, kind| {
+      packet
+        .side_data(kind)
+        .map(|data| String::from_utf8_lossy(data).into_owned())
+    };
+
+    let mut cues = Vec::new();
+    loop {
+      let (packet, stream_index) = match demuxer.read_packet() {
+        Ok(Some(read)) => read,
+        Ok(None) => break,
+        Err(e) => {
+          return Err(Error::new(
+            Status::GenericFailure,
+            format!("Demuxer error: {}", e),
+          ));
+        }
+      };
+      if stream_index != track_index {
+        continue;
+      }
+      let ts = if packet.pts() != AV_NOPTS_VALUE {
+        packet.pts()
+      } else {
+        packet.dts()
+      };
+      cues.push(SubtitleCue {
+        timestamp: convert_timestamp(ts, time_base),
+        duration: convert_timestamp(packet.duration().max(0), time_base),
+        text: String::from_utf8_lossy(packet.as_slice()).into_owned(),
+        settings: side_data_text(&packet, pkt_side_data_type::AV_PKT_DATA_WEBVTT_SETTINGS),
+        identifier: side_data_text(&packet, pkt_side_data_type::AV_PKT_DATA_WEBVTT_IDENTIFIER),
+      });
+    }
+    Ok(cues)
+  })
+  .await
+  .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+}
+
+/// Iterate a video track's key frames on a separate demuxer context
+/// (shared `keyFramePackets()` implementation)
+pub(crate) fn key_frame_packets<F: DemuxerFormat>(
+  inner: &Arc<Mutex<DemuxerInner<F>>>,
+  track_index: i32,
+  options: Option<KeyFramePacketsOptions>,
+) -> Result<KeyFramePacketIterator> {
+  let source = {
+    let guard = inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    match guard.tracks.iter().find(|t| t.index == track_index) {
+      Some(track) if track.track_type == "video" => {}
+      Some(_) => {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!("Track {} is not a video track", track_index),
+        ));
+      }
+      None => {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!("Invalid track index: {}", track_index),
+        ));
+      }
+    }
+    guard
+      .source
+      .clone()
+      .ok_or_else(|| Error::new(Status::GenericFailure, "Demuxer not loaded"))?
+  };
+
+  KeyFramePacketIterator::new(source, track_index, options.unwrap_or_default())
+}
+
+/// Read the next raw packet on a blocking thread (shared `readPacket()` implementation)
+pub(crate) async fn read_demuxer_packet<F: DemuxerFormat>(
+  inner: &Arc<Mutex<DemuxerInner<F>>>,
+) -> Result<Option<RawPacket>> {
+  let inner = inner.clone();
+  tokio::task::spawn_blocking(move || {
+    let mut guard = inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    guard.read_next_packet()
+  })
+  .await
+  .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+}
+
+/// Wrap a demuxed video packet as an EncodedVideoChunk without copying its data
+pub(crate) fn video_chunk_from_packet(
+  packet: Packet,
+  time_base: Option<(i32, i32)>,
+) -> Result<EncodedVideoChunk> {
+  let timestamp = convert_timestamp(packet.pts(), time_base);
+  let duration = if packet.duration() > 0 {
+    Some(convert_timestamp(packet.duration(), time_base))
+  } else {
+    None
+  };
+
+  let chunk_type = if packet.is_key() {
+    EncodedVideoChunkType::Key
+  } else {
+    EncodedVideoChunkType::Delta
+  };
+
+  // Containers storing HDR10+ outside the bitstream expose it as side data
+  let hdr_dynamic_metadata = packet.hdr10plus_t35().map(|data| {
+    vec![HdrDynamicMetadata {
+      kind: HdrDynamicMetadataType::ItuTT35,
+      data,
+    }]
+  });
+
+  let init = EncodedVideoChunkInit {
+    chunk_type,
+    timestamp,
+    duration,
+    data: Either::B(packet),
+    hdr_dynamic_metadata,
+  };
+
+  EncodedVideoChunk::new(init).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to create video chunk: {}", e),
+    )
+  })
+}
+
 /// Convert timestamp from stream time base to microseconds
 ///
 /// Uses checked arithmetic to prevent overflow for large timestamps.
@@ -855,6 +1803,23 @@ pub fn parse_h264_codec_string(extradata: Option<&[u8]>) -> String {
 ///
 /// hvcC format: [configurationVersion, general_profile_space/tier_flag/profile_idc, ...]
 pub fn parse_hevc_codec_string(extradata: Option<&[u8]>) -> String {
+  hevc_codec_string("hev1", extradata)
+}
+
+/// Generate an HEVC codec string for an MP4 sample entry
+///
+/// The codec string prefix must match the sample entry: `hvc1` keeps the
+/// parameter sets in hvcC only, `hev1` may also carry them in-band. Other
+/// tags (or none) fall back to `hev1`.
+pub fn hevc_codec_string_for_tag(codec_tag: u32, extradata: Option<&[u8]>) -> String {
+  let prefix = match &codec_tag.to_le_bytes() {
+    b"hvc1" => "hvc1",
+    _ => "hev1",
+  };
+  hevc_codec_string(prefix, extradata)
+}
+
+fn hevc_codec_string(prefix: &str, extradata: Option<&[u8]>) -> String {
   // hvcC structure: configurationVersion (1) + general_profile_space/tier/idc (1) + ...
   // Minimum 23 bytes for full header, but we can parse partial
   if let Some(data) = extradata.filter(|d| d.len() >= 13 && d[0] == 1) {
@@ -865,11 +1830,11 @@ pub fn parse_hevc_codec_string(extradata: Option<&[u8]>) -> String {
     let general_level_idc = data[12];
     let tier = if general_tier_flag == 1 { "H" } else { "L" };
     return format!(
-      "hev1.{}.6.{}{}.B0",
-      general_profile_idc, tier, general_level_idc
+      "{}.{}.6.{}{}.B0",
+      prefix, general_profile_idc, tier, general_level_idc
     );
   }
-  "hev1.1.6.L93.B0".to_string() // Default fallback
+  format!("{}.1.6.L93.B0", prefix) // Default fallback
 }
 
 /// Parse VP9 extradata to generate codec string
@@ -960,6 +1925,78 @@ mod tests {
     assert_eq!(parse_hevc_codec_string(None), "hev1.1.6.L93.B0");
   }
 
+  /// Minimal hvcC header: Main profile, Main tier, level 4.0 (120), no arrays
+  fn hvcc_header() -> Vec<u8> {
+    let mut hvcc = vec![0u8; 23];
+    hvcc[0] = 1;
+    hvcc[1] = 0x01;
+    hvcc[12] = 120;
+    hvcc
+  }
+
+  #[test]
+  fn test_hevc_codec_string_follows_sample_entry() {
+    let hvcc = hvcc_header();
+    let hvc1 = u32::from_le_bytes(*b"hvc1");
+    let hev1 = u32::from_le_bytes(*b"hev1");
+    assert_eq!(
+      hevc_codec_string_for_tag(hvc1, Some(&hvcc)),
+      "hvc1.1.6.L120.B0"
+    );
+    assert_eq!(
+      hevc_codec_string_for_tag(hev1, Some(&hvcc)),
+      "hev1.1.6.L120.B0"
+    );
+    assert_eq!(hevc_codec_string_for_tag(hvc1, None), "hvc1.1.6.L93.B0");
+    assert_eq!(hevc_codec_string_for_tag(0, None), "hev1.1.6.L93.B0");
+  }
+
+  fn hevc_stream(codec_tag: &[u8; 4], extradata: Option<Vec<u8>>) -> StreamInfo {
+    StreamInfo {
+      index: 0,
+      media_type: MediaType::Video,
+      codec_id: AVCodecID::Hevc,
+      codec_tag: u32::from_le_bytes(*codec_tag),
+      width: Some(64),
+      height: Some(64),
+      pixel_format: None,
+      sample_rate: None,
+      channels: None,
+      sample_format: None,
+      time_base: (1, 90_000),
+      duration: None,
+      extradata,
+      profile: None,
+      color: StreamColorInfo::default(),
+      icc_profile: None,
+      side_data: Vec::new(),
+      disposition: 0,
+      language: None,
+    }
+  }
+
+  #[test]
+  fn test_parameter_sets_in_band() {
+    // hvc1 keeps parameter sets in hvcC
+    let hvc1 = hevc_stream(b"hvc1", Some(hvcc_header()));
+    assert_eq!(parameter_sets_in_band(&hvc1), Some(false));
+    assert!(decoder_description(hvc1.codec_id, hvc1.extradata.as_deref()).is_some());
+
+    // hev1 still surfaces its hvcC, but the samples may carry parameter sets
+    let hev1 = hevc_stream(b"hev1", Some(hvcc_header()));
+    assert_eq!(parameter_sets_in_band(&hev1), Some(true));
+    assert!(decoder_description(hev1.codec_id, hev1.extradata.as_deref()).is_some());
+
+    // Annex B extradata is no description: parameter sets come in-band
+    let annexb = hevc_stream(b"hev1", Some(vec![0, 0, 0, 1, 0x40, 0x01]));
+    assert_eq!(parameter_sets_in_band(&annexb), Some(true));
+    assert!(decoder_description(annexb.codec_id, annexb.extradata.as_deref()).is_none());
+
+    let mut vp9 = hevc_stream(b"vp09", None);
+    vp9.codec_id = AVCodecID::Vp9;
+    assert_eq!(parameter_sets_in_band(&vp9), None);
+  }
+
   #[test]
   fn test_parse_vp9_codec_string() {
     // Default without extradata
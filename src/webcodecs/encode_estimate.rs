@@ -0,0 +1,540 @@
+//! estimateEncode() - Projected size and quality of an encode from samples
+//!
+//! Before committing to a long encode, estimateEncode() encodes a few short
+//! segments of the input with a candidate VideoEncoderConfig and projects the
+//! full-length result from them. Segments are spread evenly over the input and
+//! each one starts at the key frame at or before its target time, so every
+//! segment decodes cleanly and is encoded by a fresh encoder from a key frame,
+//! like the start of a real encode.
+//!
+//! Everything runs natively on the blocking pool. Decoded frames are converted,
+//! encoded and dropped one at a time and encoded packets are only counted, so
+//! memory stays bounded by the codecs' own buffering (e.g. encoder lookahead)
+//! no matter how long the input or the segments are. Nothing is written to disk.
+
+use crate::codec::{
+  BitrateMode as CodecBitrateMode, CodecContext, DecoderConfig, EncoderConfig, Frame, Packet,
+  Scaler,
+  demuxer::{DemuxerContext, MediaType},
+  scaler::ScaleAlgorithm,
+};
+use crate::ffi::{
+  AV_NOPTS_VALUE, AVCodecID, AVPictureType, AVRational, avutil::av_rescale_q,
+  pkt_side_data_type::AV_PKT_DATA_QUALITY_STATS,
+};
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::decode_limits;
+use crate::webcodecs::encoder_defaults::video_encoder_options;
+use crate::webcodecs::video_encoder::{
+  FF_QP2LAMBDA, are_dimensions_valid, encoder_framerate, encoder_pixel_format, encoder_profile,
+  get_default_gop_settings, option_pairs, parse_codec_string,
+};
+use crate::webcodecs::{LatencyMode, VideoEncoderBitrateMode, VideoEncoderConfig};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Default number of sampled segments
+const DEFAULT_SAMPLES: u32 = 5;
+
+/// Default length of each sampled segment
+const DEFAULT_SAMPLE_DURATION_MS: u32 = 2_000;
+
+/// Most segments one estimate will encode
+const MAX_SAMPLES: u32 = 64;
+
+/// Longest segment one estimate will encode
+const MAX_SAMPLE_DURATION_MS: u32 = 60_000;
+
+/// Options for estimateEncode()
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct EstimateEncodeOptions {
+  /// Number of segments to encode, spread evenly over the input (default: 5, max: 64)
+  pub samples: Option<u32>,
+  /// Length of each segment in milliseconds (default: 2000, max: 60000)
+  pub sample_duration_ms: Option<u32>,
+}
+
+/// Encode statistics of one sampled segment
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct EncodeEstimateSample {
+  /// Timestamp of the segment's first frame (microseconds)
+  pub start_us: i64,
+  /// Media duration covered by the segment (microseconds)
+  pub duration_us: i64,
+  /// Number of frames encoded
+  pub frames: u32,
+  /// Encoded bytes
+  pub bytes: i64,
+  /// Encoded bitrate over `durationUs` (bits per second)
+  pub bitrate: f64,
+  /// Mean quantizer reported by the encoder, absent if it reports none
+  pub average_qp: Option<f64>,
+}
+
+/// Result of estimateEncode()
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct EncodeEstimate {
+  /// Per-segment statistics, in input order
+  pub samples: Vec<EncodeEstimateSample>,
+  /// Duration of the input (microseconds)
+  pub duration_us: i64,
+  /// Bitrate over all sampled segments (bits per second)
+  pub projected_bitrate: f64,
+  /// Projected size of the encoded video track for the whole input
+  pub projected_bytes: i64,
+  /// Mean quantizer over all sampled frames, absent if the encoder reports none
+  pub average_qp: Option<f64>,
+  /// FFmpeg encoder used for the samples (e.g. "libx264")
+  pub encoder: String,
+}
+
+/// Encoder settings derived once from the candidate config
+struct EstimateEncoder {
+  codec_id: AVCodecID,
+  config: EncoderConfig,
+  realtime: bool,
+  options: Vec<(String, String)>,
+}
+
+impl EstimateEncoder {
+  fn from_config(config: &VideoEncoderConfig) -> Result<Self> {
+    if let Some(message) = config.validity_error() {
+      return Err(Error::new(
+        Status::InvalidArg,
+        format!("TypeError: {}", message),
+      ));
+    }
+    let config = config.clone().with_default_bitrate();
+    let codec = config.codec.clone().unwrap_or_default();
+    let codec_id = parse_codec_string(&codec)?;
+    let width = config.width.unwrap_or_default();
+    let height = config.height.unwrap_or_default();
+    if !are_dimensions_valid(width, height) {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "NotSupportedError: Dimensions exceed maximum supported size",
+      ));
+    }
+
+    let realtime = matches!(config.latency_mode, Some(LatencyMode::Realtime));
+    let (gop_size, max_b_frames) = get_default_gop_settings(realtime);
+    let bitrate_mode = match config.bitrate_mode {
+      Some(VideoEncoderBitrateMode::Constant) | None => CodecBitrateMode::Constant,
+      Some(VideoEncoderBitrateMode::Variable) => CodecBitrateMode::Variable,
+      Some(VideoEncoderBitrateMode::Quantizer) => CodecBitrateMode::Quantizer,
+    };
+    let options = video_encoder_options(config.ffmpeg_options.as_ref());
+    let pixel_format = encoder_pixel_format(codec_id, &codec, false);
+
+    let (framerate_num, framerate_den) = encoder_framerate(config.framerate);
+    Ok(Self {
+      codec_id,
+      config: EncoderConfig {
+        width,
+        height,
+        pixel_format,
+        bitrate: config.bitrate.unwrap_or(5_000_000.0) as u64,
+        framerate_num,
+        framerate_den,
+        gop_size,
+        max_b_frames,
+        thread_count: 0,
+        profile: encoder_profile(codec_id, pixel_format),
+        level: None,
+        bitrate_mode,
+        rc_max_rate: None,
+        rc_buffer_size: None,
+        crf: None,
+        pass: None,
+        sample_aspect_ratio: None,
+      },
+      realtime,
+      options: option_pairs(&options)
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect(),
+    })
+  }
+
+  /// Open a fresh software encoder, so each segment starts from a key frame
+  fn open(&self) -> Result<(CodecContext, String)> {
+    let result = CodecContext::new_encoder_with_hw_info(self.codec_id, None).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to create encoder: {}", e),
+      )
+    })?;
+    let mut context = result.context;
+    context.configure_encoder(&self.config).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to configure encoder: {}", e),
+      )
+    })?;
+    context.apply_sw_encoder_options(&result.encoder_name, self.realtime);
+    context.set_options(
+      self
+        .options
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str())),
+    );
+    context.open().map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to open encoder: {}", e),
+      )
+    })?;
+    Ok((context, result.encoder_name))
+  }
+}
+
+/// Running totals of one segment's encoded packets
+#[derive(Default)]
+struct PacketTally {
+  bytes: u64,
+  qp_sum: f64,
+  qp_count: u32,
+}
+
+impl PacketTally {
+  fn add(&mut self, packets: Vec<Packet>) {
+    for packet in packets {
+      self.bytes += packet.size().max(0) as u64;
+      // AV_PKT_DATA_QUALITY_STATS starts with the frame quality as u32le, in lambda units
+      if let Some(stats) = packet.side_data(AV_PKT_DATA_QUALITY_STATS)
+        && stats.len() >= 4
+      {
+        let quality = u32::from_le_bytes([stats[0], stats[1], stats[2], stats[3]]);
+        if quality > 0 {
+          self.qp_sum += quality as f64 / FF_QP2LAMBDA as f64;
+          self.qp_count += 1;
+        }
+      }
+    }
+  }
+}
+
+fn encode_error(e: crate::codec::CodecError) -> Error {
+  Error::new(Status::GenericFailure, format!("Encode failed: {}", e))
+}
+
+fn decode_error(e: crate::codec::CodecError) -> Error {
+  Error::new(Status::GenericFailure, format!("Decode failed: {}", e))
+}
+
+/// Demux/decode side of the estimate: the input's video track
+struct EstimateSource {
+  demuxer: DemuxerContext,
+  decoder: CodecContext,
+  stream_index: i32,
+  time_base: AVRational,
+  duration_us: i64,
+}
+
+impl EstimateSource {
+  fn open(input: Either<String, BufferView>) -> Result<Self> {
+    let demuxer = match input {
+      Either::A(path) => DemuxerContext::open_file(&path).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open {}: {}", path, e),
+        )
+      })?,
+      Either::B(buffer) => DemuxerContext::open_buffer(buffer.into_inner()).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open buffer: {}", e),
+        )
+      })?,
+    };
+    let stream = demuxer
+      .find_best_stream(MediaType::Video)
+      .cloned()
+      .ok_or_else(|| Error::new(Status::InvalidArg, "Input has no video track"))?;
+    let time_base = AVRational {
+      num: stream.time_base.0,
+      den: stream.time_base.1,
+    };
+    let duration_us = stream
+      .duration
+      .filter(|d| *d > 0)
+      .map(|d| unsafe { av_rescale_q(d, time_base, AVRational::MICROSECONDS) })
+      .or_else(|| demuxer.duration_us())
+      .ok_or_else(|| Error::new(Status::InvalidArg, "Input duration is unknown"))?;
+
+    let mut decoder = CodecContext::new_decoder(stream.codec_id).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to create decoder: {}", e),
+      )
+    })?;
+    decoder
+      .configure_decoder(&DecoderConfig {
+        codec_id: stream.codec_id,
+        thread_count: 0,
+        extradata: stream.extradata.clone(),
+        low_latency: false,
+        width: stream.width,
+        height: stream.height,
+        max_pixels: Some(decode_limits::max_pixels()),
+      })
+      .and_then(|_| decoder.open())
+      .map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open decoder: {}", e),
+        )
+      })?;
+
+    Ok(Self {
+      demuxer,
+      decoder,
+      stream_index: stream.index,
+      time_base,
+      duration_us,
+    })
+  }
+
+  fn to_us(&self, pts: i64) -> i64 {
+    unsafe { av_rescale_q(pts, self.time_base, AVRational::MICROSECONDS) }
+  }
+
+  /// Encode the segment starting at the key frame at or before `target_us`
+  fn encode_segment(
+    &mut self,
+    encoder: &EstimateEncoder,
+    target_us: i64,
+    sample_duration_us: i64,
+  ) -> Result<(EncodeEstimateSample, PacketTally, String)> {
+    let target = unsafe { av_rescale_q(target_us, AVRational::MICROSECONDS, self.time_base) };
+    self
+      .demuxer
+      .seek(self.stream_index, target, true)
+      .map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to seek to {}us: {}", target_us, e),
+        )
+      })?;
+    self.decoder.flush();
+
+    let (mut context, encoder_name) = encoder.open()?;
+    let encoder_time_base = context.time_base();
+    let mut scaler: Option<Scaler> = None;
+    let mut tally = PacketTally::default();
+    let mut start_us: Option<i64> = None;
+    let mut frames = 0u32;
+    let mut done = false;
+
+    while !done {
+      let decoded = match self.demuxer.read_packet().map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to read packet: {}", e),
+        )
+      })? {
+        Some((packet, index)) if index == self.stream_index => {
+          self.decoder.decode(Some(&packet)).map_err(decode_error)?
+        }
+        Some(_) => continue,
+        None => {
+          done = true;
+          self.decoder.flush_decoder().map_err(decode_error)?
+        }
+      };
+
+      for frame in decoded {
+        let pts = frame.pts();
+        let pts_us = if pts == AV_NOPTS_VALUE {
+          start_us.unwrap_or(target_us)
+        } else {
+          self.to_us(pts)
+        };
+        let first_us = *start_us.get_or_insert(pts_us);
+        if pts_us >= first_us + sample_duration_us {
+          done = true;
+          break;
+        }
+
+        let mut input = Self::convert(&mut scaler, frame, &encoder.config)?;
+        // Key frames follow the encoder's own GOP, not the source's
+        input.set_pict_type(AVPictureType::None);
+        input.set_pts(unsafe {
+          av_rescale_q(
+            pts_us - first_us,
+            AVRational::MICROSECONDS,
+            encoder_time_base,
+          )
+        });
+        tally.add(context.encode(Some(&input)).map_err(encode_error)?);
+        frames += 1;
+      }
+    }
+    tally.add(context.flush_encoder().map_err(encode_error)?);
+
+    let start_us = start_us.unwrap_or(target_us);
+    let duration_us = (self.duration_us.min(start_us + sample_duration_us) - start_us).max(1);
+    let sample = EncodeEstimateSample {
+      start_us,
+      duration_us,
+      frames,
+      bytes: tally.bytes as i64,
+      bitrate: tally.bytes as f64 * 8.0 * 1_000_000.0 / duration_us as f64,
+      average_qp: (tally.qp_count > 0).then(|| tally.qp_sum / tally.qp_count as f64),
+    };
+    Ok((sample, tally, encoder_name))
+  }
+
+  /// Convert a decoded frame to the encoder's size and pixel format
+  fn convert(scaler: &mut Option<Scaler>, frame: Frame, config: &EncoderConfig) -> Result<Frame> {
+    if frame.width() == config.width
+      && frame.height() == config.height
+      && frame.format() == config.pixel_format
+    {
+      return Ok(frame);
+    }
+    let scaler = match scaler {
+      Some(scaler) => scaler,
+      None => scaler.insert(
+        Scaler::new(
+          frame.width(),
+          frame.height(),
+          frame.format(),
+          config.width,
+          config.height,
+          config.pixel_format,
+          ScaleAlgorithm::Bilinear,
+        )
+        .map_err(|e| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Failed to create scaler: {}", e),
+          )
+        })?,
+      ),
+    };
+    scaler.scale_alloc(&frame).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to convert frame: {}", e),
+      )
+    })
+  }
+}
+
+/// Segment start times: `samples` windows centred in equal slices of the input
+fn segment_targets(duration_us: i64, samples: u32, sample_duration_us: i64) -> Vec<i64> {
+  if duration_us <= samples as i64 * sample_duration_us {
+    // The segments would cover the whole input anyway: encode it as one segment
+    return vec![0];
+  }
+  let slice_us = duration_us / samples as i64;
+  (0..samples as i64)
+    .map(|i| (i * slice_us + (slice_us - sample_duration_us) / 2).max(0))
+    .collect()
+}
+
+fn run_estimate(
+  input: Either<String, BufferView>,
+  config: VideoEncoderConfig,
+  options: EstimateEncodeOptions,
+) -> Result<EncodeEstimate> {
+  let samples = options.samples.unwrap_or(DEFAULT_SAMPLES);
+  if samples == 0 || samples > MAX_SAMPLES {
+    return Err(Error::new(
+      Status::InvalidArg,
+      format!("TypeError: samples must be between 1 and {}", MAX_SAMPLES),
+    ));
+  }
+  let sample_duration_ms = options
+    .sample_duration_ms
+    .unwrap_or(DEFAULT_SAMPLE_DURATION_MS);
+  if sample_duration_ms == 0 || sample_duration_ms > MAX_SAMPLE_DURATION_MS {
+    return Err(Error::new(
+      Status::InvalidArg,
+      format!(
+        "TypeError: sampleDurationMs must be between 1 and {}",
+        MAX_SAMPLE_DURATION_MS
+      ),
+    ));
+  }
+
+  let encoder = EstimateEncoder::from_config(&config)?;
+  let mut source = EstimateSource::open(input)?;
+  let duration_us = source.duration_us;
+  let targets = segment_targets(duration_us, samples, sample_duration_ms as i64 * 1_000);
+  let sample_duration_us = if targets.len() == 1 && samples > 1 {
+    duration_us
+  } else {
+    sample_duration_ms as i64 * 1_000
+  };
+
+  let mut results = Vec::with_capacity(targets.len());
+  let mut total = PacketTally::default();
+  let mut sampled_us = 0i64;
+  let mut encoder_name = String::new();
+  for target_us in targets {
+    let (sample, tally, name) = source.encode_segment(&encoder, target_us, sample_duration_us)?;
+    total.bytes += tally.bytes;
+    total.qp_sum += tally.qp_sum;
+    total.qp_count += tally.qp_count;
+    sampled_us += sample.duration_us;
+    encoder_name = name;
+    results.push(sample);
+  }
+
+  let projected_bitrate = total.bytes as f64 * 8.0 * 1_000_000.0 / sampled_us.max(1) as f64;
+  Ok(EncodeEstimate {
+    samples: results,
+    duration_us,
+    projected_bitrate,
+    projected_bytes: (projected_bitrate * duration_us as f64 / 8_000_000.0).round() as i64,
+    average_qp: (total.qp_count > 0).then(|| total.qp_sum / total.qp_count as f64),
+    encoder: encoder_name,
+  })
+}
+
+/// Estimate the encoded size and quantizer of a video for an encoder config
+///
+/// Encodes `samples` segments of `sampleDurationMs` each, spread evenly over
+/// the input's video track, and projects the bitrate measured over them to
+/// the whole input. Samples are always encoded in software, so the estimate
+/// matches a software encode of the same config. Nothing is written to disk.
+///
+/// ```javascript
+/// const estimate = await estimateEncode('movie.mp4', { codec: 'avc1.640028', width: 1920, height: 1080, bitrate: 4_000_000 }, { samples: 8 });
+/// console.log(estimate.projectedBytes, estimate.averageQp);
+/// ```
+#[napi(
+  ts_args_type = "input: string | BufferSource, config: VideoEncoderConfig, options?: EstimateEncodeOptions"
+)]
+pub async fn estimate_encode(
+  input: Either<String, BufferView>,
+  config: VideoEncoderConfig,
+  options: Option<EstimateEncodeOptions>,
+) -> Result<EncodeEstimate> {
+  let options = options.unwrap_or_default();
+  tokio::task::spawn_blocking(move || run_estimate(input, config, options))
+    .await
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn segments_are_centred_in_equal_slices() {
+    assert_eq!(
+      segment_targets(10_000_000, 4, 1_000_000),
+      vec![750_000, 3_250_000, 5_750_000, 8_250_000]
+    );
+  }
+
+  #[test]
+  fn short_input_is_encoded_whole() {
+    assert_eq!(segment_targets(3_000_000, 4, 1_000_000), vec![0]);
+  }
+}
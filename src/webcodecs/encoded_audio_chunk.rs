@@ -4,8 +4,14 @@
 //! See: https://developer.mozilla.org/en-US/docs/Web/API/EncodedAudioChunk
 
 use crate::codec::Packet;
+use crate::webcodecs::audio_bitstream::create_adts_header;
+use crate::webcodecs::buffer_view::{BufferView, required_buffer};
 use crate::webcodecs::encoded_video_chunk::InternalSlice;
-use crate::webcodecs::error::{enforce_range_long_long, enforce_range_long_long_optional};
+use crate::webcodecs::encoder_defaults::{FfmpegOptions, FfmpegOptionsInit, to_ffmpeg_options};
+use crate::webcodecs::error::{
+  enforce_range_long_long, enforce_range_long_long_optional, get_unsigned_long_member,
+};
+use crate::webcodecs::output_timeline::TimestampMode;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use std::sync::{Arc, RwLock};
@@ -88,49 +94,11 @@ impl FromNapiValue for EncodedAudioChunkInit {
 
     // Validate data - required field, accept BufferSource (ArrayBuffer, TypedArray, DataView)
     // Per W3C spec, chunk data must be independent of the original source, so a copy is required.
-    // We optimize by using slice access where possible to avoid double-copying.
-    let data = if let Ok(Some(buffer)) = obj.get::<&[u8]>("data") {
-      buffer.to_vec()
-    } else if let Ok(Some(array_buffer)) = obj.get::<ArrayBuffer>("data") {
-      Uint8ArraySlice::from_arraybuffer(&array_buffer, 0, array_buffer.len())?.to_vec()
-    } else {
-      // Check if data property exists but is undefined/null
-      let has_data = obj.has_named_property("data")?;
-      if !has_data {
-        env_wrapper.throw_type_error("data is required", None)?;
-        return Err(Error::new(Status::InvalidArg, "data is required"));
-      }
-
-      // Try getting as object and check for buffer/byteLength properties (DataView, other TypedArrays)
-      if let Ok(Some(data_obj)) = obj.get::<Object>("data") {
-        let byte_length: Option<u32> = data_obj.get("byteLength").ok().flatten();
-        let byte_offset: u32 = data_obj.get("byteOffset").ok().flatten().unwrap_or(0);
-
-        if let (Some(len), Ok(Some(buffer))) = (byte_length, data_obj.get::<ArrayBuffer>("buffer"))
-        {
-          let offset = byte_offset as usize;
-          let length = len as usize;
-          // Use Uint8ArraySlice to copy directly from the slice (single copy)
-          // instead of copying entire buffer then slicing (double copy)
-          if offset + length <= buffer.len() {
-            Uint8ArraySlice::from_arraybuffer(&buffer, offset, length)?.to_vec()
-          } else {
-            env_wrapper.throw_type_error("data must be a valid BufferSource", None)?;
-            return Err(Error::new(
-              Status::InvalidArg,
-              "data must be a valid BufferSource",
-            ));
-          }
-        } else {
-          env_wrapper.throw_type_error("data must be a BufferSource", None)?;
-          return Err(Error::new(
-            Status::InvalidArg,
-            "data must be a BufferSource",
-          ));
-        }
-      } else {
-        env_wrapper.throw_type_error("data is required", None)?;
-        return Err(Error::new(Status::InvalidArg, "data is required"));
+    let data = match required_buffer(&obj, "data") {
+      Ok(view) => view.to_vec(),
+      Err(msg) => {
+        env_wrapper.throw_type_error(&msg, None)?;
+        return Err(Error::new(Status::InvalidArg, msg));
       }
     };
 
@@ -408,44 +376,10 @@ impl EncodedAudioChunk {
 // ADTS Header Generation for AAC
 // ============================================================================
 
-/// Create an ADTS header for an AAC frame
-/// ADTS header is 7 bytes for no CRC (protection_absent = 1)
-fn create_adts_header(frame_len: usize, sample_rate_index: u8, channel_config: u8) -> [u8; 7] {
-  let frame_length = frame_len + 7; // Include header in length
-
-  // profile = AAC-LC (2), but stored as profile-1 = 1 in ADTS
-  let profile = 1u8;
-
-  let mut header = [0u8; 7];
-
-  // Byte 0: syncword high byte (0xFF)
-  header[0] = 0xFF;
-
-  // Byte 1: syncword low 4 bits (0xF) + ID(0) + layer(00) + protection_absent(1) = 0xF1
-  header[1] = 0xF1;
-
-  // Byte 2: profile(2 bits) + sampling_frequency_index(4 bits) + private_bit(1) + channel_config high bit(1)
-  header[2] =
-    ((profile & 0x03) << 6) | ((sample_rate_index & 0x0F) << 2) | ((channel_config & 0x04) >> 2);
-
-  // Byte 3: channel_config low 2 bits + original_copy(0) + home(0) + copyright_id_bit(0) + copyright_id_start(0) + frame_length high 2 bits
-  header[3] = ((channel_config & 0x03) << 6) | ((frame_length >> 11) & 0x03) as u8;
-
-  // Byte 4: frame_length middle 8 bits
-  header[4] = ((frame_length >> 3) & 0xFF) as u8;
-
-  // Byte 5: frame_length low 3 bits + buffer_fullness high 5 bits (0x7FF for VBR)
-  header[5] = (((frame_length & 0x07) << 5) | 0x1F) as u8;
-
-  // Byte 6: buffer_fullness low 6 bits + number_of_raw_data_blocks(0)
-  header[6] = 0xFC;
-
-  header
-}
-
 /// Prepend ADTS header to raw AAC frame data
 fn prepend_adts_header(frame_data: &[u8], sample_rate_index: u8, channel_config: u8) -> Vec<u8> {
-  let header = create_adts_header(frame_data.len(), sample_rate_index, channel_config);
+  // Encoder output is always AAC-LC (audio object type 2)
+  let header = create_adts_header(frame_data.len(), 2, sample_rate_index, channel_config);
   let mut result = Vec::with_capacity(7 + frame_data.len());
   result.extend_from_slice(&header);
   result.extend_from_slice(frame_data);
@@ -581,6 +515,10 @@ pub struct AudioEncoderConfig {
   pub aac: Option<AacEncoderConfig>,
   /// FLAC codec-specific configuration
   pub flac: Option<FlacEncoderConfig>,
+  /// FFmpeg AVOptions for the codec context, over setDefaultEncoderOptions() (non-standard)
+  pub ffmpeg_options: Option<FfmpegOptions>,
+  /// TypeError message for a numberOfChannels outside the `unsigned long` range
+  pub(crate) range_error: Option<String>,
 }
 
 impl FromNapiValue for AudioEncoderConfig {
@@ -593,12 +531,17 @@ impl FromNapiValue for AudioEncoderConfig {
     // All fields stored as Option - validation happens in configure() or isConfigSupported()
     let codec: Option<String> = obj.get("codec")?;
     let sample_rate: Option<f64> = obj.get("sampleRate")?;
-    let number_of_channels: Option<u32> = obj.get("numberOfChannels")?;
+    let (number_of_channels, range_error) =
+      match get_unsigned_long_member(&obj, "numberOfChannels")? {
+        Ok(channels) => (channels, None),
+        Err(message) => (None, Some(message)),
+      };
     let bitrate: Option<f64> = obj.get("bitrate")?;
     let bitrate_mode: Option<BitrateMode> = obj.get("bitrateMode")?;
     let opus: Option<OpusEncoderConfig> = obj.get("opus")?;
     let aac: Option<AacEncoderConfig> = obj.get("aac")?;
     let flac: Option<FlacEncoderConfig> = obj.get("flac")?;
+    let ffmpeg_options: Option<FfmpegOptionsInit> = obj.get("ffmpegOptions")?;
 
     Ok(AudioEncoderConfig {
       codec,
@@ -609,6 +552,8 @@ impl FromNapiValue for AudioEncoderConfig {
       opus,
       aac,
       flac,
+      ffmpeg_options: ffmpeg_options.map(to_ffmpeg_options),
+      range_error,
     })
   }
 }
@@ -630,6 +575,10 @@ pub struct AudioDecoderConfig {
   pub number_of_channels: Option<u32>,
   /// Codec-specific description data (e.g., AudioSpecificConfig for AAC) - BufferSource per spec
   pub description: Option<Uint8Array>,
+  /// Source of output timestamps (extension, default "container")
+  pub timestamp_mode: Option<TimestampMode>,
+  /// Subtract the first output timestamp from all outputs (extension)
+  pub start_at_zero: Option<bool>,
 }
 
 impl FromNapiValue for AudioDecoderConfig {
@@ -643,17 +592,117 @@ impl FromNapiValue for AudioDecoderConfig {
     let codec: Option<String> = obj.get("codec")?;
     let sample_rate: Option<f64> = obj.get("sampleRate")?;
     let number_of_channels: Option<u32> = obj.get("numberOfChannels")?;
-    let description: Option<Uint8Array> = obj.get("description")?;
+    let description: Option<Uint8Array> = obj
+      .get::<BufferView>("description")?
+      .map(BufferView::into_inner);
+    let timestamp_mode: Option<TimestampMode> = obj.get("timestampMode")?;
+    let start_at_zero: Option<bool> = obj.get("startAtZero")?;
 
     Ok(AudioDecoderConfig {
       codec,
       sample_rate,
       number_of_channels,
       description,
+      timestamp_mode,
+      start_at_zero,
     })
   }
 }
 
+impl AudioEncoderConfig {
+  /// Normalize the config for the isConfigSupported() result
+  ///
+  /// W3C spec "Clone Configuration": only recognized members are copied, dictionary
+  /// defaults are filled in, and codec-specific blocks are kept only when they
+  /// apply to the configured codec.
+  pub(crate) fn normalize(self) -> Self {
+    let codec_lower = self.codec.as_deref().unwrap_or_default().to_lowercase();
+    let is_opus = codec_lower == "opus";
+    let is_aac = codec_lower.starts_with("mp4a.40") || codec_lower == "aac";
+    let is_flac = codec_lower == "flac";
+
+    AudioEncoderConfig {
+      codec: self.codec,
+      sample_rate: self.sample_rate,
+      number_of_channels: self.number_of_channels,
+      bitrate: self.bitrate,
+      bitrate_mode: Some(self.bitrate_mode.unwrap_or_default()),
+      opus: self.opus.filter(|_| is_opus).map(|opus| OpusEncoderConfig {
+        format: Some(opus.format.unwrap_or_default()),
+        signal: Some(opus.signal.unwrap_or_default()),
+        application: Some(opus.application.unwrap_or_default()),
+        frame_duration: Some(opus.frame_duration.unwrap_or(20000.0)),
+        complexity: opus.complexity,
+        packetlossperc: Some(opus.packetlossperc.unwrap_or(0)),
+        useinbandfec: Some(opus.useinbandfec.unwrap_or(false)),
+        usedtx: Some(opus.usedtx.unwrap_or(false)),
+      }),
+      aac: self.aac.filter(|_| is_aac).map(|aac| AacEncoderConfig {
+        format: Some(aac.format.unwrap_or_default()),
+      }),
+      flac: self.flac.filter(|_| is_flac).map(|flac| FlacEncoderConfig {
+        block_size: Some(flac.block_size.unwrap_or(0)),
+        compress_level: Some(flac.compress_level.unwrap_or(5)),
+      }),
+      ffmpeg_options: self.ffmpeg_options,
+      range_error: None,
+    }
+  }
+
+  /// W3C spec "Check Configuration Validity" shared by configure() and isConfigSupported()
+  ///
+  /// Returns the TypeError message for a structurally invalid config. An unknown
+  /// codec passes here and fails the asynchronous support check instead.
+  pub(crate) fn validity_error(&self) -> Option<String> {
+    if let Some(ref message) = self.range_error {
+      return Some(message.clone());
+    }
+    if self
+      .codec
+      .as_deref()
+      .is_none_or(|codec| codec.trim().is_empty())
+    {
+      return Some("codec is required".into());
+    }
+    match self.sample_rate {
+      None => return Some("sampleRate is required".into()),
+      Some(rate) if !rate.is_finite() || rate <= 0.0 => {
+        return Some("sampleRate must be greater than 0".into());
+      }
+      Some(_) => {}
+    }
+    match self.number_of_channels {
+      None => return Some("numberOfChannels is required".into()),
+      Some(0) => return Some("numberOfChannels must be greater than 0".into()),
+      Some(_) => {}
+    }
+    if self
+      .bitrate
+      .is_some_and(|bitrate| !bitrate.is_finite() || bitrate <= 0.0)
+    {
+      return Some("bitrate must be greater than 0".into());
+    }
+    None
+  }
+}
+
+impl AudioDecoderConfig {
+  /// Normalize the config for the isConfigSupported() result
+  ///
+  /// W3C spec "Clone Configuration": `description` is deep-copied so the result
+  /// does not alias the caller's buffer.
+  pub(crate) fn normalize(self) -> Self {
+    AudioDecoderConfig {
+      codec: self.codec,
+      sample_rate: self.sample_rate,
+      number_of_channels: self.number_of_channels,
+      description: self.description.map(|d| Uint8Array::from(d.to_vec())),
+      timestamp_mode: self.timestamp_mode,
+      start_at_zero: self.start_at_zero,
+    }
+  }
+}
+
 impl ToNapiValue for AudioEncoderConfig {
   unsafe fn to_napi_value(env: napi::sys::napi_env, val: Self) -> Result<napi::sys::napi_value> {
     let env_wrapper = Env::from_raw(env);
@@ -683,6 +732,9 @@ impl ToNapiValue for AudioEncoderConfig {
     if let Some(flac) = val.flac {
       obj.set("flac", flac)?;
     }
+    if let Some(ffmpeg_options) = val.ffmpeg_options {
+      obj.set("ffmpegOptions", ffmpeg_options)?;
+    }
 
     unsafe { Object::to_napi_value(env, obj) }
   }
@@ -705,6 +757,12 @@ impl ToNapiValue for AudioDecoderConfig {
     if let Some(description) = val.description {
       obj.set("description", description)?;
     }
+    if let Some(timestamp_mode) = val.timestamp_mode {
+      obj.set("timestampMode", timestamp_mode)?;
+    }
+    if let Some(start_at_zero) = val.start_at_zero {
+      obj.set("startAtZero", start_at_zero)?;
+    }
 
     unsafe { Object::to_napi_value(env, obj) }
   }
@@ -5,7 +5,16 @@
 
 use crate::codec::Packet;
 use crate::ffi::{AVRational, avutil::av_rescale_q};
-use crate::webcodecs::error::{enforce_range_long_long, enforce_range_long_long_optional};
+use crate::webcodecs::access_unit::InputGranularity;
+use crate::webcodecs::buffer_view::{BufferView, required_buffer};
+use crate::webcodecs::encoder_defaults::{FfmpegOptions, FfmpegOptionsInit, to_ffmpeg_options};
+use crate::webcodecs::error::{
+  enforce_range_long_long, enforce_range_long_long_optional, get_unsigned_long_member,
+  invalid_state_error,
+};
+use crate::webcodecs::output_timeline::TimestampMode;
+use crate::webcodecs::video_encoder::{EncodeOutputStats, default_bitrate, parse_codec_string};
+use crate::webcodecs::video_frame::HdrDynamicMetadata;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use std::sync::{Arc, RwLock};
@@ -92,6 +101,8 @@ pub struct EncodedVideoChunkInit {
   pub duration: Option<i64>,
   /// Encoded data (BufferSource per spec)
   pub data: Either<Vec<u8>, Packet>,
+  /// Per-frame HDR dynamic metadata to pass through to the decoded frame (extension)
+  pub hdr_dynamic_metadata: Option<Vec<HdrDynamicMetadata>>,
 }
 
 impl FromNapiValue for EncodedVideoChunkInit {
@@ -133,56 +144,23 @@ impl FromNapiValue for EncodedVideoChunkInit {
     let duration = enforce_range_long_long_optional(&env_wrapper, duration_f64, "duration")?;
 
     // Validate data - required field, accept BufferSource (ArrayBuffer, TypedArray, DataView)
-    // Try different buffer types in order of preference
-    // per W3C spec, chunk data should be independent of the original data, so data copy is not avoidable here
-    let data = if let Ok(Some(buffer)) = obj.get::<&[u8]>("data") {
-      buffer.to_vec()
-    } else if let Ok(Some(array_buffer)) = obj.get::<ArrayBuffer>("data") {
-      Uint8ArraySlice::from_arraybuffer(&array_buffer, 0, array_buffer.len())?.to_vec()
-    } else {
-      // Check if data property exists but is undefined/null
-      let has_data = obj.has_named_property("data")?;
-      if !has_data {
-        env_wrapper.throw_type_error("data is required", None)?;
-        return Err(Error::new(Status::InvalidArg, "data is required"));
-      }
-
-      // Try getting as object and check for buffer/byteLength properties (DataView, other TypedArrays)
-      if let Ok(Some(data_obj)) = obj.get::<Object>("data") {
-        let byte_length: Option<u32> = data_obj.get("byteLength").ok().flatten();
-        let byte_offset: u32 = data_obj.get("byteOffset").ok().flatten().unwrap_or(0);
-
-        if let (Some(len), Ok(Some(buffer))) = (byte_length, data_obj.get::<ArrayBuffer>("buffer"))
-        {
-          let offset = byte_offset as usize;
-          let length = len as usize;
-          if offset + length <= buffer.len() {
-            Uint8ArraySlice::from_arraybuffer(&buffer, offset, length)?.to_vec()
-          } else {
-            env_wrapper.throw_type_error("data must be a valid BufferSource", None)?;
-            return Err(Error::new(
-              Status::InvalidArg,
-              "data must be a valid BufferSource",
-            ));
-          }
-        } else {
-          env_wrapper.throw_type_error("data must be a BufferSource", None)?;
-          return Err(Error::new(
-            Status::InvalidArg,
-            "data must be a BufferSource",
-          ));
-        }
-      } else {
-        env_wrapper.throw_type_error("data is required", None)?;
-        return Err(Error::new(Status::InvalidArg, "data is required"));
+    // Per W3C spec, chunk data must be independent of the original source, so a copy is required.
+    let data = match required_buffer(&obj, "data") {
+      Ok(view) => view.to_vec(),
+      Err(msg) => {
+        env_wrapper.throw_type_error(&msg, None)?;
+        return Err(Error::new(Status::InvalidArg, msg));
       }
     };
 
+    let hdr_dynamic_metadata: Option<Vec<HdrDynamicMetadata>> = obj.get("hdrDynamicMetadata")?;
+
     Ok(EncodedVideoChunkInit {
       chunk_type,
       timestamp,
       duration,
       data: Either::A(data),
+      hdr_dynamic_metadata,
     })
   }
 }
@@ -222,6 +200,8 @@ pub(crate) struct EncodedVideoChunkInner {
   /// Used alongside dts_us for correct B-frame muxing.
   /// When Some, muxer should use this pair instead of timestamp_us.
   pub(crate) original_pts: Option<i64>,
+  /// Per-frame HDR dynamic metadata (HDR10+ T.35, Dolby Vision RPU)
+  pub(crate) hdr_dynamic_metadata: Vec<HdrDynamicMetadata>,
 }
 
 // SAFETY: EncodedVideoChunkInner can be safely sent and shared between threads.
@@ -260,6 +240,7 @@ impl EncodedVideoChunk {
       duration_us: init.duration,
       dts_us: None,       // No DTS info from JS API
       original_pts: None, // No original PTS from JS API
+      hdr_dynamic_metadata: init.hdr_dynamic_metadata.unwrap_or_default(),
     };
 
     Ok(Self {
@@ -278,6 +259,7 @@ impl EncodedVideoChunk {
     explicit_timestamp: Option<i64>,
     use_avcc: bool,
     encoder_time_base: AVRational,
+    output_stats: &mut EncodeOutputStats,
   ) -> Self {
     let chunk_type = if packet.is_key() {
       EncodedVideoChunkType::Key
@@ -321,8 +303,27 @@ impl EncodedVideoChunk {
       None
     };
 
+    // Rewrite start codes inside the packet buffer when possible, so the
+    // chunk shares the encoder's packet instead of copying it
     let data = if use_avcc {
-      Either::A(convert_annexb_to_avcc(packet.as_slice()))
+      let mut packet = packet;
+      match convert_packet_annexb_to_avcc(&mut packet) {
+        PacketConversion::InPlace => {
+          output_stats.rewritten_chunks += 1;
+          Either::B(packet)
+        }
+        PacketConversion::CopiedShared => {
+          output_stats.copied_chunks += 1;
+          output_stats.copied_bytes += packet.size() as i64;
+          Either::B(packet)
+        }
+        PacketConversion::Unsupported => {
+          let data = convert_annexb_to_avcc(packet.as_slice());
+          output_stats.copied_chunks += 1;
+          output_stats.copied_bytes += data.len() as i64;
+          Either::A(data)
+        }
+      }
     } else {
       Either::B(packet)
     };
@@ -337,6 +338,7 @@ impl EncodedVideoChunk {
       duration_us,
       dts_us,
       original_pts,
+      hdr_dynamic_metadata: Vec::new(),
     };
 
     Self {
@@ -362,6 +364,23 @@ impl EncodedVideoChunk {
     self.with_inner(|inner| Ok(inner.duration_us))
   }
 
+  /// Get the per-frame HDR dynamic metadata carried by this chunk (extension)
+  #[napi(getter, ts_return_type = "Array<HdrDynamicMetadata> | undefined")]
+  pub fn hdr_dynamic_metadata(&self) -> Result<Option<Vec<HdrDynamicMetadata>>> {
+    self.with_inner(|inner| {
+      Ok((!inner.hdr_dynamic_metadata.is_empty()).then(|| inner.hdr_dynamic_metadata.clone()))
+    })
+  }
+
+  /// Attach per-frame HDR dynamic metadata (used by encoders and demuxers)
+  pub(crate) fn set_hdr_dynamic_metadata(&self, metadata: Vec<HdrDynamicMetadata>) {
+    if let Ok(mut guard) = self.inner.write()
+      && let Some(inner) = guard.as_mut()
+    {
+      inner.hdr_dynamic_metadata = metadata;
+    }
+  }
+
   /// Get the internal decode timestamp (DTS) in microseconds.
   /// This is NOT part of the WebCodecs API, but used internally for B-frame support.
   /// Returns None if DTS equals PTS (no B-frames).
@@ -452,6 +471,20 @@ impl EncodedVideoChunk {
       .unwrap_or(false)
   }
 
+  /// Whether the chunk was closed by decodeAndClose()
+  pub(crate) fn is_closed(&self) -> bool {
+    self
+      .inner
+      .read()
+      .map(|guard| guard.is_none())
+      .unwrap_or(true)
+  }
+
+  /// Move the chunk state out, leaving the chunk closed (decodeAndClose)
+  pub(crate) fn detach(&self) -> Option<EncodedVideoChunkInner> {
+    self.inner.write().ok().and_then(|mut guard| guard.take())
+  }
+
   /// Get a copy of the raw data (internal use only, for extracting SPS/PPS)
   pub(crate) fn get_data_optional<R, F: FnOnce(&[u8]) -> Option<R>>(&self, f: F) -> Option<R> {
     self
@@ -508,25 +541,15 @@ impl EncodedVideoChunk {
 
     match guard.as_ref() {
       Some(inner) => f(inner),
-      None => Err(Error::new(
-        Status::GenericFailure,
-        "EncodedVideoChunk is closed",
-      )),
+      None => Err(invalid_state_error("EncodedVideoChunk is closed")),
     }
   }
 }
 
-/// Convert H.264/H.265 Annex B format to AVCC/HVCC format (length-prefixed NALUs)
+/// Locate the NAL units of an Annex B bitstream as `(start, end)` byte ranges
 ///
-/// Annex B uses start codes (0x00000001 or 0x000001) to delimit NAL units.
-/// AVCC/HVCC uses 4-byte big-endian length prefixes instead.
-///
-/// This function scans for start codes and replaces them with the NAL unit length.
-fn convert_annexb_to_avcc(data: &[u8]) -> Vec<u8> {
-  if data.is_empty() {
-    return Vec::new();
-  }
-
+/// Ranges exclude the 3-byte (0x000001) or 4-byte (0x00000001) start codes.
+pub(crate) fn annexb_nal_ranges(data: &[u8]) -> Vec<(usize, usize)> {
   // Find all NAL unit boundaries (positions after start codes)
   let mut nal_starts: Vec<usize> = Vec::new();
   let mut i = 0;
@@ -549,7 +572,41 @@ fn convert_annexb_to_avcc(data: &[u8]) -> Vec<u8> {
     i += 1;
   }
 
-  if nal_starts.is_empty() {
+  nal_starts
+    .iter()
+    .enumerate()
+    .map(|(idx, &start)| {
+      // Find the end of this NAL unit (start of next, or end of data)
+      let end = if idx + 1 < nal_starts.len() {
+        // Find where the next start code begins (scan backwards from next NAL start)
+        let next_nal_start = nal_starts[idx + 1];
+        // The start code is either 3 or 4 bytes before next_nal_start
+        if next_nal_start >= 4 && data[next_nal_start - 4] == 0 && data[next_nal_start - 3] == 0 {
+          next_nal_start - 4 // 4-byte start code
+        } else {
+          next_nal_start - 3 // 3-byte start code
+        }
+      } else {
+        data.len()
+      };
+      (start, end)
+    })
+    .collect()
+}
+
+/// Convert H.264/H.265 Annex B format to AVCC/HVCC format (length-prefixed NALUs)
+///
+/// Annex B uses start codes (0x00000001 or 0x000001) to delimit NAL units.
+/// AVCC/HVCC uses 4-byte big-endian length prefixes instead.
+///
+/// This function scans for start codes and replaces them with the NAL unit length.
+fn convert_annexb_to_avcc(data: &[u8]) -> Vec<u8> {
+  if data.is_empty() {
+    return Vec::new();
+  }
+
+  let nals = annexb_nal_ranges(data);
+  if nals.is_empty() {
     // No start codes found - might already be in AVCC format or invalid data
     return data.to_vec();
   }
@@ -557,21 +614,7 @@ fn convert_annexb_to_avcc(data: &[u8]) -> Vec<u8> {
   // Build AVCC output
   let mut result = Vec::with_capacity(data.len());
 
-  for (idx, &start) in nal_starts.iter().enumerate() {
-    // Find the end of this NAL unit (start of next, or end of data)
-    let end = if idx + 1 < nal_starts.len() {
-      // Find where the next start code begins (scan backwards from next NAL start)
-      let next_nal_start = nal_starts[idx + 1];
-      // The start code is either 3 or 4 bytes before next_nal_start
-      if next_nal_start >= 4 && data[next_nal_start - 4] == 0 && data[next_nal_start - 3] == 0 {
-        next_nal_start - 4 // 4-byte start code
-      } else {
-        next_nal_start - 3 // 3-byte start code
-      }
-    } else {
-      data.len()
-    };
-
+  for (start, end) in nals {
     let nal_data = &data[start..end];
     let nal_len = nal_data.len() as u32;
 
@@ -584,6 +627,93 @@ fn convert_annexb_to_avcc(data: &[u8]) -> Vec<u8> {
   result
 }
 
+/// Result of converting a packet to AVCC/HVCC inside its own buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PacketConversion {
+  /// Rewritten in the packet's buffer
+  InPlace,
+  /// The buffer was shared with another reference and copied before the rewrite
+  CopiedShared,
+  /// Left untouched; the caller converts a copy instead
+  Unsupported,
+}
+
+/// Convert an Annex B packet to AVCC/HVCC inside the packet's own buffer
+///
+/// Each NAL unit is moved to its length-prefixed position within the buffer,
+/// so encoder output keeps being backed by the refcounted AVPacket instead of
+/// a fresh Vec. With only 4-byte start codes (the usual encoder output) the
+/// layout is identical and nothing is moved or allocated; 3-byte start codes
+/// grow the packet by one byte each. Leaves the packet untouched if the buffer
+/// can't be made writable or the NAL units would have to move in both
+/// directions (data before the first start code).
+fn convert_packet_annexb_to_avcc(packet: &mut Packet) -> PacketConversion {
+  let nals = annexb_nal_ranges(packet.as_slice());
+  if nals.is_empty() {
+    // Nothing to rewrite, same as convert_annexb_to_avcc()
+    return PacketConversion::InPlace;
+  }
+
+  let in_len = packet.as_slice().len();
+  let mut dst_starts = Vec::with_capacity(nals.len());
+  let mut out_len = 0;
+  for &(start, end) in &nals {
+    dst_starts.push(out_len + 4);
+    out_len += 4 + (end - start);
+  }
+
+  // Moving every NAL unit towards the end (back to front) or towards the
+  // start (front to back) never overwrites data that hasn't been moved yet
+  let moves_back = nals
+    .iter()
+    .zip(&dst_starts)
+    .all(|(&(start, _), &dst)| dst >= start);
+  let moves_front = nals
+    .iter()
+    .zip(&dst_starts)
+    .all(|(&(start, _), &dst)| dst <= start);
+  if !moves_back && !moves_front {
+    return PacketConversion::Unsupported;
+  }
+
+  // make_writable() only copies a buffer other references still hold
+  let buffer = packet.data();
+  if packet.make_writable().is_err() {
+    return PacketConversion::Unsupported;
+  }
+  let conversion = if packet.data() == buffer {
+    PacketConversion::InPlace
+  } else {
+    PacketConversion::CopiedShared
+  };
+  if out_len > in_len && packet.resize(out_len).is_err() {
+    return PacketConversion::Unsupported;
+  }
+
+  let data = packet.as_mut_slice();
+  let mut write = |idx: usize| {
+    let (start, end) = nals[idx];
+    let dst = dst_starts[idx];
+    // Move the payload before writing the prefix: the prefix may overlap the
+    // payload's old position
+    if dst != start {
+      data.copy_within(start..end, dst);
+    }
+    data[dst - 4..dst].copy_from_slice(&((end - start) as u32).to_be_bytes());
+  };
+  if moves_back {
+    (0..nals.len()).rev().for_each(&mut write);
+  } else {
+    (0..nals.len()).for_each(&mut write);
+  }
+
+  if out_len < in_len {
+    // Shrinking never fails
+    let _ = packet.resize(out_len);
+  }
+  conversion
+}
+
 /// Convert AVCC/HVCC format (length-prefixed NALUs) to Annex B format
 ///
 /// AVCC uses 4-byte big-endian length prefixes to delimit NAL units.
@@ -1401,6 +1531,66 @@ pub fn convert_hvcc_extradata_to_annexb(data: &[u8]) -> Option<Vec<u8>> {
   Some(result)
 }
 
+/// Collect the parameter set NAL units (H.264 SPS/PPS, H.265 VPS/SPS/PPS) of a
+/// packet in Annex B form
+///
+/// Accepts Annex B and 4-byte length-prefixed packets. Returns None when the
+/// packet carries no parameter sets.
+pub(crate) fn packet_parameter_sets(data: &[u8], is_h265: bool) -> Option<Vec<u8>> {
+  let nals: Vec<&[u8]> = if is_avcc_format(data) {
+    let mut nals = Vec::new();
+    let mut offset = 0;
+    while offset + 4 <= data.len() {
+      let len = u32::from_be_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+      ]) as usize;
+      if len == 0 || len > data.len() - offset - 4 {
+        break;
+      }
+      nals.push(&data[offset + 4..offset + 4 + len]);
+      offset += 4 + len;
+    }
+    nals
+  } else {
+    annexb_nal_ranges(data)
+      .into_iter()
+      .map(|(start, end)| &data[start..end])
+      .collect()
+  };
+
+  let mut result = Vec::new();
+  for nal in nals.into_iter().filter(|nal| !nal.is_empty()) {
+    let is_parameter_set = if is_h265 {
+      matches!((nal[0] >> 1) & 0x3F, 32..=34)
+    } else {
+      matches!(nal[0] & 0x1F, 7 | 8)
+    };
+    if is_parameter_set {
+      result.extend_from_slice(&[0, 0, 0, 1]);
+      result.extend_from_slice(nal);
+    }
+  }
+  (!result.is_empty()).then_some(result)
+}
+
+/// Collect the parameter set NAL units of H.264/H.265 extradata in Annex B form
+///
+/// Unpacks avcC/hvcC records, so descriptions in either format compare equal
+/// when they hold the same parameter sets.
+pub(crate) fn extradata_parameter_sets(data: &[u8], is_h265: bool) -> Option<Vec<u8>> {
+  let annexb = if is_h265 && is_hvcc_extradata(data) {
+    convert_hvcc_extradata_to_annexb(data)?
+  } else if !is_h265 && is_avcc_extradata(data) {
+    convert_avcc_extradata_to_annexb(data)?
+  } else {
+    data.to_vec()
+  };
+  packet_parameter_sets(&annexb, is_h265)
+}
+
 // ============================================================================
 // AV1 OBU to av1C Conversion
 // ============================================================================
@@ -1421,7 +1611,7 @@ pub fn is_av1c_extradata(data: &[u8]) -> bool {
 /// Read LEB128 variable-length unsigned integer
 ///
 /// Returns (value, bytes_consumed) or None if invalid
-fn read_leb128(data: &[u8]) -> Option<(usize, usize)> {
+pub(crate) fn read_leb128(data: &[u8]) -> Option<(usize, usize)> {
   let mut value: usize = 0;
   let mut bytes_read = 0;
 
@@ -1739,6 +1929,27 @@ pub struct VideoEncoderConfig {
   pub avc: Option<AvcEncoderConfig>,
   /// HEVC (H.265) codec-specific configuration
   pub hevc: Option<HevcEncoderConfig>,
+  /// Two-pass rate control pass (1 = collect statistics, 2 = consume them)
+  pub pass: Option<u32>,
+  /// First-pass statistics for pass 2 (from VideoEncoder.stats())
+  pub stats_data: Option<Vec<u8>>,
+  /// Replacement for the x264/x265 info SEI ("" removes it, absent keeps it)
+  pub encoder_info: Option<String>,
+  /// Report PSNR/SSIM of every chunk against its source frame (software encoders only)
+  pub compute_quality_metrics: Option<bool>,
+  /// Force key frames at scene cuts found by comparing luma histograms of consecutive frames
+  pub scene_cut_detection: Option<bool>,
+  /// Attach actual bits and encoder rate-control state to every chunk's metadata
+  pub report_rate_control_stats: Option<bool>,
+  /// Drop frames whose format cannot be encoded instead of closing the encoder
+  pub skip_unsupported_frames: Option<bool>,
+  /// Rotate/flip frame pixels upright before encoding instead of passing the
+  /// orientation through as metadata
+  pub apply_rotation: Option<bool>,
+  /// FFmpeg AVOptions for the codec context, over setDefaultEncoderOptions() (non-standard)
+  pub ffmpeg_options: Option<FfmpegOptions>,
+  /// TypeError message for a dimension outside the `unsigned long` range
+  pub(crate) range_error: Option<String>,
 }
 
 impl FromNapiValue for VideoEncoderConfig {
@@ -1750,10 +1961,20 @@ impl FromNapiValue for VideoEncoderConfig {
 
     // All fields stored as Option - validation happens in configure() or isConfigSupported()
     let codec: Option<String> = obj.get("codec")?;
-    let width: Option<u32> = obj.get("width")?;
-    let height: Option<u32> = obj.get("height")?;
-    let display_width: Option<u32> = obj.get("displayWidth")?;
-    let display_height: Option<u32> = obj.get("displayHeight")?;
+    // Dimensions are [EnforceRange] unsigned long: keep the first range error for validation
+    let mut range_error = None;
+    let mut dimension = |name: &str| -> Result<Option<u32>> {
+      Ok(
+        get_unsigned_long_member(&obj, name)?.unwrap_or_else(|message| {
+          range_error.get_or_insert(message);
+          None
+        }),
+      )
+    };
+    let width = dimension("width")?;
+    let height = dimension("height")?;
+    let display_width = dimension("displayWidth")?;
+    let display_height = dimension("displayHeight")?;
     let bitrate: Option<f64> = obj.get("bitrate")?;
     let framerate: Option<f64> = obj.get("framerate")?;
     let hardware_acceleration: Option<HardwareAcceleration> = obj.get("hardwareAcceleration")?;
@@ -1764,6 +1985,15 @@ impl FromNapiValue for VideoEncoderConfig {
     let content_hint: Option<String> = obj.get("contentHint")?;
     let avc: Option<AvcEncoderConfig> = obj.get("avc")?;
     let hevc: Option<HevcEncoderConfig> = obj.get("hevc")?;
+    let pass: Option<u32> = obj.get("pass")?;
+    let stats_data: Option<Uint8Array> = obj.get("statsData")?;
+    let encoder_info: Option<String> = obj.get("encoderInfo")?;
+    let compute_quality_metrics: Option<bool> = obj.get("computeQualityMetrics")?;
+    let scene_cut_detection: Option<bool> = obj.get("sceneCutDetection")?;
+    let report_rate_control_stats: Option<bool> = obj.get("reportRateControlStats")?;
+    let skip_unsupported_frames: Option<bool> = obj.get("skipUnsupportedFrames")?;
+    let apply_rotation: Option<bool> = obj.get("applyRotation")?;
+    let ffmpeg_options: Option<FfmpegOptionsInit> = obj.get("ffmpegOptions")?;
 
     Ok(VideoEncoderConfig {
       codec,
@@ -1781,6 +2011,16 @@ impl FromNapiValue for VideoEncoderConfig {
       content_hint,
       avc,
       hevc,
+      pass,
+      stats_data: stats_data.map(|d| d.to_vec()),
+      encoder_info,
+      compute_quality_metrics,
+      scene_cut_detection,
+      report_rate_control_stats,
+      skip_unsupported_frames,
+      apply_rotation,
+      ffmpeg_options: ffmpeg_options.map(to_ffmpeg_options),
+      range_error,
     })
   }
 }
@@ -1814,6 +2054,19 @@ pub struct VideoDecoderConfig {
   pub rotation: Option<f64>,
   /// Horizontal flip per W3C spec
   pub flip: Option<bool>,
+  /// Alpha handling for decoded frames (extension, default "keep")
+  /// "discard" outputs the non-alpha format (e.g. I420 instead of I420A)
+  pub alpha: Option<AlphaOption>,
+  /// Maximum output frames JS may hold unclosed before decoding pauses (extension)
+  pub max_outstanding_frames: Option<u32>,
+  /// Decode only key chunks and silently drop delta chunks (extension)
+  pub key_frames_only: Option<bool>,
+  /// Source of output frame timestamps (extension, default "container")
+  pub timestamp_mode: Option<TimestampMode>,
+  /// Subtract the first output timestamp from all outputs (extension)
+  pub start_at_zero: Option<bool>,
+  /// Whether chunks hold whole pictures or H.264 NAL units (extension, default "accessUnit")
+  pub input_granularity: Option<InputGranularity>,
 }
 
 impl FromNapiValue for VideoDecoderConfig {
@@ -1836,15 +2089,11 @@ impl FromNapiValue for VideoDecoderConfig {
     let optimize_for_latency: Option<bool> = obj.get("optimizeForLatency")?;
 
     // Handle description as BufferSource (ArrayBuffer, TypedArray, or DataView)
-    // Try to get as Uint8Array first, then try to handle DataView/ArrayBuffer
-    let description: Option<Uint8Array> = match obj.get::<Uint8Array>("description") {
-      Ok(Some(arr)) => Some(arr),
-      Ok(None) => None,
+    let description: Option<Uint8Array> = match obj.get::<BufferView>("description") {
+      Ok(view) => view.map(BufferView::into_inner),
       Err(_) => {
-        // Not a Uint8Array directly - might be DataView or ArrayBuffer
-        // For these cases, the NAPI binding doesn't support direct conversion,
-        // so we accept None. The test expects isConfigSupported to still work
-        // with unsupported description types (it just won't use the description).
+        // Not a BufferSource - isConfigSupported still works without the
+        // description, it just won't be used
         None
       }
     };
@@ -1852,6 +2101,12 @@ impl FromNapiValue for VideoDecoderConfig {
     // Rotation and flip for VideoFrame orientation (W3C WebCodecs spec)
     let rotation: Option<f64> = obj.get("rotation")?;
     let flip: Option<bool> = obj.get("flip")?;
+    let alpha: Option<AlphaOption> = obj.get("alpha")?;
+    let max_outstanding_frames: Option<u32> = obj.get("maxOutstandingFrames")?;
+    let key_frames_only: Option<bool> = obj.get("keyFramesOnly")?;
+    let timestamp_mode: Option<TimestampMode> = obj.get("timestampMode")?;
+    let start_at_zero: Option<bool> = obj.get("startAtZero")?;
+    let input_granularity: Option<InputGranularity> = obj.get("inputGranularity")?;
 
     Ok(VideoDecoderConfig {
       codec,
@@ -1865,10 +2120,168 @@ impl FromNapiValue for VideoDecoderConfig {
       description,
       rotation,
       flip,
+      alpha,
+      max_outstanding_frames,
+      key_frames_only,
+      timestamp_mode,
+      start_at_zero,
+      input_granularity,
     })
   }
 }
 
+impl VideoEncoderConfig {
+  /// Normalize the config for the isConfigSupported() result
+  ///
+  /// W3C spec "Clone Configuration": only recognized members are copied, dictionary
+  /// defaults are filled in, and codec-specific blocks are kept only when they
+  /// apply to the configured codec. A missing `bitrate` is reported as the default
+  /// configure() would pick.
+  pub(crate) fn normalize(self) -> Self {
+    let codec = self.codec.as_deref().unwrap_or_default();
+    let is_avc = codec.starts_with("avc1") || codec.starts_with("avc3");
+    let is_hevc = codec.starts_with("hev1") || codec.starts_with("hvc1");
+
+    VideoEncoderConfig {
+      codec: self.codec,
+      width: self.width,
+      height: self.height,
+      display_width: self.display_width,
+      display_height: self.display_height,
+      bitrate: self.bitrate,
+      framerate: self.framerate,
+      hardware_acceleration: Some(self.hardware_acceleration.unwrap_or_default()),
+      latency_mode: Some(self.latency_mode.unwrap_or_default()),
+      bitrate_mode: Some(self.bitrate_mode.unwrap_or_default()),
+      alpha: Some(self.alpha.unwrap_or_default()),
+      scalability_mode: self.scalability_mode,
+      content_hint: self.content_hint,
+      avc: self.avc.filter(|_| is_avc).map(|avc| AvcEncoderConfig {
+        format: Some(avc.format.unwrap_or_default()),
+      }),
+      hevc: self.hevc.filter(|_| is_hevc).map(|hevc| HevcEncoderConfig {
+        format: Some(hevc.format.unwrap_or_default()),
+      }),
+      pass: self.pass,
+      stats_data: self.stats_data,
+      encoder_info: self.encoder_info,
+      compute_quality_metrics: self.compute_quality_metrics,
+      scene_cut_detection: self.scene_cut_detection,
+      report_rate_control_stats: self.report_rate_control_stats,
+      skip_unsupported_frames: self.skip_unsupported_frames,
+      apply_rotation: self.apply_rotation,
+      ffmpeg_options: self.ffmpeg_options,
+      range_error: None,
+    }
+    .with_default_bitrate()
+  }
+
+  /// Fill in a resolution- and framerate-based `bitrate` when none is given
+  ///
+  /// Left unset with `bitrateMode: 'quantizer'`, where quality comes from the
+  /// per-frame quantizer, and for a codec string that does not parse.
+  pub(crate) fn with_default_bitrate(mut self) -> Self {
+    if self.bitrate.is_some() || self.bitrate_mode == Some(VideoEncoderBitrateMode::Quantizer) {
+      return self;
+    }
+    let codec_id = self
+      .codec
+      .as_deref()
+      .and_then(|codec| parse_codec_string(codec).ok());
+    if let (Some(codec_id), Some(width), Some(height)) = (codec_id, self.width, self.height) {
+      self.bitrate = Some(default_bitrate(
+        codec_id,
+        width,
+        height,
+        self.framerate.unwrap_or(30.0),
+      ));
+    }
+    self
+  }
+
+  /// W3C spec "Check Configuration Validity" shared by configure() and isConfigSupported()
+  ///
+  /// Returns the TypeError message for a structurally invalid config. A valid but
+  /// unsupported config (unknown codec, oversized dimensions, unknown scalability
+  /// mode) passes here and fails the asynchronous support check instead.
+  pub(crate) fn validity_error(&self) -> Option<String> {
+    if let Some(ref message) = self.range_error {
+      return Some(message.clone());
+    }
+    if self
+      .codec
+      .as_deref()
+      .is_none_or(|codec| codec.trim().is_empty())
+    {
+      return Some("codec is required".into());
+    }
+    match self.width {
+      None => return Some("width is required".into()),
+      Some(0) => return Some("width must be greater than 0".into()),
+      Some(_) => {}
+    }
+    match self.height {
+      None => return Some("height is required".into()),
+      Some(0) => return Some("height must be greater than 0".into()),
+      Some(_) => {}
+    }
+    if self.display_width == Some(0) {
+      return Some("displayWidth must be greater than 0".into());
+    }
+    if self.display_height == Some(0) {
+      return Some("displayHeight must be greater than 0".into());
+    }
+    if self
+      .bitrate
+      .is_some_and(|bitrate| !bitrate.is_finite() || bitrate <= 0.0)
+    {
+      return Some("bitrate must be greater than 0".into());
+    }
+    if self
+      .framerate
+      .is_some_and(|framerate| !framerate.is_finite() || framerate <= 0.0)
+    {
+      return Some("framerate must be greater than 0".into());
+    }
+    match self.pass {
+      None | Some(1) => None,
+      Some(2) if self.stats_data.as_ref().is_none_or(|d| d.is_empty()) => {
+        Some("statsData is required for pass 2".into())
+      }
+      Some(2) => None,
+      Some(_) => Some("pass must be 1 or 2".into()),
+    }
+  }
+}
+
+impl VideoDecoderConfig {
+  /// Normalize the config for the isConfigSupported() result
+  ///
+  /// W3C spec "Clone Configuration": dictionary defaults are filled in and
+  /// `description` is deep-copied so the result does not alias the caller's buffer.
+  pub(crate) fn normalize(self) -> Self {
+    VideoDecoderConfig {
+      codec: self.codec,
+      coded_width: self.coded_width,
+      coded_height: self.coded_height,
+      display_aspect_width: self.display_aspect_width,
+      display_aspect_height: self.display_aspect_height,
+      color_space: self.color_space,
+      hardware_acceleration: Some(self.hardware_acceleration.unwrap_or_default()),
+      optimize_for_latency: self.optimize_for_latency,
+      description: self.description.map(|d| Uint8Array::from(d.to_vec())),
+      rotation: Some(self.rotation.unwrap_or(0.0)),
+      flip: Some(self.flip.unwrap_or(false)),
+      alpha: self.alpha,
+      max_outstanding_frames: self.max_outstanding_frames,
+      key_frames_only: self.key_frames_only,
+      timestamp_mode: self.timestamp_mode,
+      start_at_zero: self.start_at_zero,
+      input_granularity: self.input_granularity,
+    }
+  }
+}
+
 impl ToNapiValue for VideoEncoderConfig {
   unsafe fn to_napi_value(env: napi::sys::napi_env, val: Self) -> Result<napi::sys::napi_value> {
     let env_wrapper = Env::from_raw(env);
@@ -1919,6 +2332,33 @@ impl ToNapiValue for VideoEncoderConfig {
     if let Some(hevc) = val.hevc {
       obj.set("hevc", hevc)?;
     }
+    if let Some(pass) = val.pass {
+      obj.set("pass", pass)?;
+    }
+    if let Some(stats_data) = val.stats_data {
+      obj.set("statsData", Uint8Array::from(stats_data))?;
+    }
+    if let Some(encoder_info) = val.encoder_info {
+      obj.set("encoderInfo", encoder_info)?;
+    }
+    if let Some(compute_quality_metrics) = val.compute_quality_metrics {
+      obj.set("computeQualityMetrics", compute_quality_metrics)?;
+    }
+    if let Some(scene_cut_detection) = val.scene_cut_detection {
+      obj.set("sceneCutDetection", scene_cut_detection)?;
+    }
+    if let Some(report_rate_control_stats) = val.report_rate_control_stats {
+      obj.set("reportRateControlStats", report_rate_control_stats)?;
+    }
+    if let Some(skip_unsupported_frames) = val.skip_unsupported_frames {
+      obj.set("skipUnsupportedFrames", skip_unsupported_frames)?;
+    }
+    if let Some(apply_rotation) = val.apply_rotation {
+      obj.set("applyRotation", apply_rotation)?;
+    }
+    if let Some(ffmpeg_options) = val.ffmpeg_options {
+      obj.set("ffmpegOptions", ffmpeg_options)?;
+    }
 
     unsafe { Object::to_napi_value(env, obj) }
   }
@@ -1962,7 +2402,137 @@ impl ToNapiValue for VideoDecoderConfig {
     if let Some(flip) = val.flip {
       obj.set("flip", flip)?;
     }
+    if let Some(alpha) = val.alpha {
+      obj.set("alpha", alpha)?;
+    }
+    if let Some(max_outstanding_frames) = val.max_outstanding_frames {
+      obj.set("maxOutstandingFrames", max_outstanding_frames)?;
+    }
+    if let Some(key_frames_only) = val.key_frames_only {
+      obj.set("keyFramesOnly", key_frames_only)?;
+    }
+    if let Some(timestamp_mode) = val.timestamp_mode {
+      obj.set("timestampMode", timestamp_mode)?;
+    }
+    if let Some(start_at_zero) = val.start_at_zero {
+      obj.set("startAtZero", start_at_zero)?;
+    }
+    if let Some(input_granularity) = val.input_granularity {
+      obj.set("inputGranularity", input_granularity)?;
+    }
 
     unsafe { Object::to_napi_value(env, obj) }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn packet_from(data: &[u8]) -> Packet {
+    let mut packet = Packet::new().unwrap();
+    packet.copy_data_from(data).unwrap();
+    packet
+  }
+
+  /// A typical encoder access unit: SPS, PPS and a slice with 4-byte start codes
+  fn access_unit(frame: u8) -> Vec<u8> {
+    let mut data = vec![0, 0, 0, 1, 0x67, 0x42, 0xC0, 0x1E];
+    data.extend_from_slice(&[0, 0, 0, 1, 0x68, 0xCE, 0x3C, 0x80]);
+    data.extend_from_slice(&[0, 0, 0, 1, 0x65]);
+    data.extend(std::iter::repeat_n(frame, 1500));
+    data
+  }
+
+  #[test]
+  fn test_packet_avcc_conversion_reuses_buffer() {
+    // Compare against the copying conversion over 1000 frames: the in-place
+    // path must produce identical bytes without a new allocation per frame
+    let mut reallocated = 0;
+    for frame in 0..1000u32 {
+      let data = access_unit(frame as u8);
+      let expected = convert_annexb_to_avcc(&data);
+
+      let mut packet = packet_from(&data);
+      let ptr = packet.data();
+      assert_eq!(
+        convert_packet_annexb_to_avcc(&mut packet),
+        PacketConversion::InPlace
+      );
+      if packet.data() != ptr {
+        reallocated += 1;
+      }
+      assert_eq!(packet.as_slice(), expected.as_slice());
+    }
+    assert_eq!(reallocated, 0);
+  }
+
+  #[test]
+  fn test_packet_avcc_conversion_three_byte_start_codes() {
+    // x264 uses 3-byte start codes for slices after the first
+    let data = [
+      0, 0, 0, 1, 0x65, 1, 2, 3, 0, 0, 1, 0x41, 4, 5, 0, 0, 1, 0x41, 6,
+    ];
+    let mut packet = packet_from(&data);
+    assert_eq!(
+      convert_packet_annexb_to_avcc(&mut packet),
+      PacketConversion::InPlace
+    );
+    assert_eq!(packet.as_slice(), convert_annexb_to_avcc(&data).as_slice());
+    assert_eq!(packet.size() as usize, data.len() + 2);
+  }
+
+  #[test]
+  fn test_packet_avcc_conversion_leading_bytes() {
+    // Leading bytes with only 3-byte start codes shift everything forward
+    let data = [0xAA, 0xBB, 0, 0, 1, 0x65, 1, 2, 0, 0, 1, 0x41, 3];
+    let mut packet = packet_from(&data);
+    assert_eq!(
+      convert_packet_annexb_to_avcc(&mut packet),
+      PacketConversion::InPlace
+    );
+    assert_eq!(packet.as_slice(), convert_annexb_to_avcc(&data).as_slice());
+
+    // Units that would move in both directions are left to the copying path
+    let data = [
+      0xAA, 0xBB, 0, 0, 1, 0x65, 1, 0, 0, 1, 0x41, 2, 0, 0, 1, 0x41, 3, 0, 0, 1, 0x41, 4,
+    ];
+    let mut packet = packet_from(&data);
+    assert_eq!(
+      convert_packet_annexb_to_avcc(&mut packet),
+      PacketConversion::Unsupported
+    );
+    assert_eq!(packet.as_slice(), &data);
+  }
+
+  #[test]
+  fn test_packet_avcc_conversion_shared_buffer() {
+    // A buffer shared with another reference is copied, never modified under it
+    let data = access_unit(7);
+    let mut packet = packet_from(&data);
+    let other = packet.shallow_clone().unwrap();
+    assert_eq!(
+      convert_packet_annexb_to_avcc(&mut packet),
+      PacketConversion::CopiedShared
+    );
+    assert_eq!(other.as_slice(), data.as_slice());
+    assert_eq!(packet.as_slice(), convert_annexb_to_avcc(&data).as_slice());
+  }
+
+  #[test]
+  fn test_parameter_sets_match_across_formats() {
+    let data = access_unit(7);
+    let in_band = packet_parameter_sets(&data, false).unwrap();
+    assert_eq!(in_band, data[..16].to_vec());
+    assert_eq!(
+      packet_parameter_sets(&convert_annexb_to_avcc(&data), false),
+      Some(in_band.clone())
+    );
+
+    let avcc = convert_annexb_extradata_to_avcc(&in_band).unwrap();
+    assert_eq!(extradata_parameter_sets(&avcc, false), Some(in_band));
+
+    // A slice on its own carries no parameter sets
+    assert_eq!(packet_parameter_sets(&data[16..], false), None);
+  }
+}
@@ -0,0 +1,144 @@
+//! Process-wide FFmpeg option defaults for encoders
+//!
+//! `setDefaultEncoderOptions()` stores AVOptions (e.g. `threads`, `preset`)
+//! that every VideoEncoder/AudioEncoder configured afterwards applies to its
+//! codec context. The instance's own `ffmpegOptions` are merged on top, so an
+//! option set on the instance wins. The merged set is captured at configure
+//! time: changing the defaults does not touch codecs that are already
+//! configured, including their later software fallbacks.
+//!
+//! Options a codec does not define are skipped, so one set of defaults can
+//! carry encoder-specific options such as the x264 `preset`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// FFmpeg AVOptions by name, applied in name order
+pub type FfmpegOptions = BTreeMap<String, String>;
+
+/// AVOption values as given from JavaScript (numbers are formatted as strings)
+pub type FfmpegOptionsInit = HashMap<String, Either<String, f64>>;
+
+struct EncoderDefaults {
+  video: FfmpegOptions,
+  audio: FfmpegOptions,
+}
+
+static DEFAULTS: RwLock<EncoderDefaults> = RwLock::new(EncoderDefaults {
+  video: BTreeMap::new(),
+  audio: BTreeMap::new(),
+});
+
+/// Default FFmpeg options for encoders
+#[napi(object)]
+pub struct EncoderOptionDefaults {
+  /// AVOptions applied to every VideoEncoder
+  pub video: Option<FfmpegOptionsInit>,
+  /// AVOptions applied to every AudioEncoder
+  pub audio: Option<FfmpegOptionsInit>,
+}
+
+/// Replace the process-wide default FFmpeg options for encoders
+///
+/// Only codecs configured afterwards use the new defaults. A missing `video`
+/// or `audio` member clears that kind's defaults.
+#[napi]
+pub fn set_default_encoder_options(options: EncoderOptionDefaults) {
+  let mut defaults = DEFAULTS.write().unwrap_or_else(|e| e.into_inner());
+  defaults.video = options.video.map(to_ffmpeg_options).unwrap_or_default();
+  defaults.audio = options.audio.map(to_ffmpeg_options).unwrap_or_default();
+}
+
+/// Get the process-wide default FFmpeg options for encoders
+#[napi]
+pub fn get_default_encoder_options() -> EncoderOptionDefaults {
+  let defaults = DEFAULTS.read().unwrap_or_else(|e| e.into_inner());
+  let to_init = |options: &FfmpegOptions| -> FfmpegOptionsInit {
+    options
+      .iter()
+      .map(|(key, value)| (key.clone(), Either::A(value.clone())))
+      .collect()
+  };
+  EncoderOptionDefaults {
+    video: Some(to_init(&defaults.video)),
+    audio: Some(to_init(&defaults.audio)),
+  }
+}
+
+/// Normalize option values from JavaScript to the strings FFmpeg parses
+pub(crate) fn to_ffmpeg_options(init: FfmpegOptionsInit) -> FfmpegOptions {
+  init
+    .into_iter()
+    .map(|(key, value)| {
+      let value = match value {
+        Either::A(text) => text,
+        Either::B(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+          format!("{}", number as i64)
+        }
+        Either::B(number) => number.to_string(),
+      };
+      (key, value)
+    })
+    .collect()
+}
+
+/// Default video options with the instance's options on top
+pub(crate) fn video_encoder_options(instance: Option<&FfmpegOptions>) -> FfmpegOptions {
+  let defaults = DEFAULTS.read().unwrap_or_else(|e| e.into_inner());
+  merge_options(&defaults.video, instance)
+}
+
+/// Default audio options with the instance's options on top
+pub(crate) fn audio_encoder_options(instance: Option<&FfmpegOptions>) -> FfmpegOptions {
+  let defaults = DEFAULTS.read().unwrap_or_else(|e| e.into_inner());
+  merge_options(&defaults.audio, instance)
+}
+
+fn merge_options(defaults: &FfmpegOptions, instance: Option<&FfmpegOptions>) -> FfmpegOptions {
+  let mut merged = defaults.clone();
+  if let Some(instance) = instance {
+    merged.extend(instance.iter().map(|(k, v)| (k.clone(), v.clone())));
+  }
+  merged
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn options(pairs: &[(&str, &str)]) -> FfmpegOptions {
+    pairs
+      .iter()
+      .map(|(k, v)| (k.to_string(), v.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn test_instance_options_win() {
+    let defaults = options(&[("threads", "4"), ("preset", "medium")]);
+    let instance = options(&[("threads", "2")]);
+    assert_eq!(
+      merge_options(&defaults, Some(&instance)),
+      options(&[("threads", "2"), ("preset", "medium")])
+    );
+    assert_eq!(merge_options(&defaults, None), defaults);
+  }
+
+  #[test]
+  fn test_numbers_are_formatted_for_ffmpeg() {
+    let init: FfmpegOptionsInit = [
+      ("threads".to_string(), Either::B(4.0)),
+      ("qcomp".to_string(), Either::B(0.5)),
+      ("preset".to_string(), Either::A("slow".to_string())),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+      to_ffmpeg_options(init),
+      options(&[("threads", "4"), ("qcomp", "0.5"), ("preset", "slow")])
+    );
+  }
+}
@@ -0,0 +1,259 @@
+//! Encoder identification SEI rewriting
+//!
+//! x264 and x265 write a user data unregistered SEI carrying their version and
+//! full option string into the first keyframe. `VideoEncoderConfig.encoderInfo`
+//! removes that SEI or replaces its text, so output does not leak encoder
+//! details and stays byte-reproducible across encoder builds.
+
+use crate::ffi::AVCodecID;
+
+/// SEI payload type for user data unregistered (H.264 D.1.6, H.265 D.2.6)
+const USER_DATA_UNREGISTERED: u32 = 5;
+/// Leading text of the info strings written by x264 and x265
+const ENCODER_INFO_PREFIXES: [&[u8]; 2] = [b"x264 ", b"x265 "];
+/// Length of the UUID that starts a user data unregistered payload
+const UUID_LEN: usize = 16;
+
+/// Strip (`replacement` empty) or replace the encoder info SEI in an Annex B packet
+///
+/// Returns None when the packet has no encoder info SEI, so callers can keep the
+/// original packet untouched. The replacement keeps the encoder's UUID.
+pub(crate) fn rewrite_encoder_info_sei(
+  data: &[u8],
+  codec_id: AVCodecID,
+  replacement: &str,
+) -> Option<Vec<u8>> {
+  let header_len = match codec_id {
+    AVCodecID::H264 => 1,
+    AVCodecID::Hevc => 2,
+    _ => return None,
+  };
+
+  let nals = split_annexb(data);
+  let mut found = false;
+  let mut result = Vec::with_capacity(data.len());
+
+  for nal in nals {
+    let uuid = (is_prefix_sei(nal, codec_id) && nal.len() > header_len)
+      .then(|| encoder_info_uuid(&unescape_rbsp(&nal[header_len..])))
+      .flatten();
+
+    match uuid {
+      Some(uuid) => {
+        found = true;
+        if !replacement.is_empty() {
+          result.extend_from_slice(&[0, 0, 0, 1]);
+          result.extend_from_slice(&nal[..header_len]);
+          result.extend_from_slice(&build_sei_rbsp(&uuid, replacement));
+        }
+      }
+      None => {
+        result.extend_from_slice(&[0, 0, 0, 1]);
+        result.extend_from_slice(nal);
+      }
+    }
+  }
+
+  found.then_some(result)
+}
+
+/// Split Annex B data into NAL units (without start codes)
+fn split_annexb(data: &[u8]) -> Vec<&[u8]> {
+  let mut starts = Vec::new();
+  let mut i = 0;
+  while i + 3 <= data.len() {
+    if data[i] == 0 && data[i + 1] == 0 && data[i + 2] == 1 {
+      starts.push(i + 3);
+      i += 3;
+    } else {
+      i += 1;
+    }
+  }
+
+  starts
+    .iter()
+    .enumerate()
+    .map(|(idx, &start)| {
+      let mut end = starts.get(idx + 1).map_or(data.len(), |&next| next - 3);
+      // Zero bytes before a start code belong to it (4-byte start code, trailing_zero_8bits)
+      while end > start && data[end - 1] == 0 && idx + 1 < starts.len() {
+        end -= 1;
+      }
+      &data[start..end]
+    })
+    .collect()
+}
+
+fn is_prefix_sei(nal: &[u8], codec_id: AVCodecID) -> bool {
+  match (codec_id, nal.first()) {
+    (AVCodecID::H264, Some(&b)) => b & 0x1f == 6,
+    (AVCodecID::Hevc, Some(&b)) => (b >> 1) & 0x3f == 39,
+    _ => false,
+  }
+}
+
+/// Return the UUID if every message in the SEI RBSP is an x264/x265 info string
+fn encoder_info_uuid(rbsp: &[u8]) -> Option<[u8; UUID_LEN]> {
+  let mut uuid = None;
+  let mut pos = 0;
+
+  // Messages continue until the rbsp_trailing_bits byte
+  while pos < rbsp.len() && rbsp[pos] != 0x80 {
+    let payload_type = read_sei_value(rbsp, &mut pos)?;
+    let payload_size = read_sei_value(rbsp, &mut pos)? as usize;
+    let payload = rbsp.get(pos..pos + payload_size)?;
+    pos += payload_size;
+
+    if payload_type != USER_DATA_UNREGISTERED || payload.len() < UUID_LEN {
+      return None;
+    }
+    let text = &payload[UUID_LEN..];
+    if !ENCODER_INFO_PREFIXES.iter().any(|p| text.starts_with(p)) {
+      return None;
+    }
+    uuid.get_or_insert_with(|| {
+      let mut id = [0u8; UUID_LEN];
+      id.copy_from_slice(&payload[..UUID_LEN]);
+      id
+    });
+  }
+
+  uuid
+}
+
+/// Read an SEI payloadType/payloadSize value (0xFF-extended)
+fn read_sei_value(rbsp: &[u8], pos: &mut usize) -> Option<u32> {
+  let mut value = 0u32;
+  loop {
+    let byte = *rbsp.get(*pos)?;
+    *pos += 1;
+    value += byte as u32;
+    if byte != 0xff {
+      return Some(value);
+    }
+  }
+}
+
+fn write_sei_value(out: &mut Vec<u8>, mut value: usize) {
+  while value >= 0xff {
+    out.push(0xff);
+    value -= 0xff;
+  }
+  out.push(value as u8);
+}
+
+/// Build an escaped SEI NAL body with one user data unregistered message
+fn build_sei_rbsp(uuid: &[u8; UUID_LEN], text: &str) -> Vec<u8> {
+  // NUL-terminated like the x264/x265 info strings
+  let payload_size = UUID_LEN + text.len() + 1;
+  let mut rbsp = Vec::with_capacity(payload_size + 8);
+  write_sei_value(&mut rbsp, USER_DATA_UNREGISTERED as usize);
+  write_sei_value(&mut rbsp, payload_size);
+  rbsp.extend_from_slice(uuid);
+  rbsp.extend_from_slice(text.as_bytes());
+  rbsp.push(0);
+  rbsp.push(0x80);
+  escape_rbsp(&rbsp)
+}
+
+/// Remove emulation prevention bytes (00 00 03 -> 00 00)
+fn unescape_rbsp(data: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(data.len());
+  let mut zeros = 0;
+  for &byte in data {
+    if zeros >= 2 && byte == 3 {
+      zeros = 0;
+      continue;
+    }
+    zeros = if byte == 0 { zeros + 1 } else { 0 };
+    out.push(byte);
+  }
+  out
+}
+
+/// Insert emulation prevention bytes after two zeros followed by a byte <= 3
+fn escape_rbsp(data: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(data.len() + 4);
+  let mut zeros = 0;
+  for &byte in data {
+    if zeros >= 2 && byte <= 3 {
+      out.push(3);
+      zeros = 0;
+    }
+    zeros = if byte == 0 { zeros + 1 } else { 0 };
+    out.push(byte);
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const X264_UUID: [u8; UUID_LEN] = [
+    0xdc, 0x45, 0xe9, 0xbd, 0xe6, 0xd9, 0x48, 0xb7, 0x96, 0x2c, 0xd8, 0x20, 0xd9, 0x23, 0xee, 0xef,
+  ];
+
+  fn h264_access_unit(sei_text: &str) -> Vec<u8> {
+    let mut data = vec![0, 0, 0, 1, 0x67, 0x42, 0xc0, 0x1e];
+    data.extend_from_slice(&[0, 0, 0, 1, 0x06]);
+    data.extend_from_slice(&build_sei_rbsp(&X264_UUID, sei_text));
+    data.extend_from_slice(&[0, 0, 1, 0x65, 0x88, 0x84, 0x00]);
+    data
+  }
+
+  #[test]
+  fn test_strip_x264_info() {
+    let data = h264_access_unit("x264 - core 164 - H.264/MPEG-4 AVC codec");
+    let stripped = rewrite_encoder_info_sei(&data, AVCodecID::H264, "").unwrap();
+    assert_eq!(
+      stripped,
+      [
+        0, 0, 0, 1, 0x67, 0x42, 0xc0, 0x1e, 0, 0, 0, 1, 0x65, 0x88, 0x84, 0x00
+      ]
+    );
+  }
+
+  #[test]
+  fn test_replace_x264_info() {
+    let data = h264_access_unit("x264 - core 164");
+    let replaced = rewrite_encoder_info_sei(&data, AVCodecID::H264, "my-app").unwrap();
+    let nals = split_annexb(&replaced);
+    assert_eq!(nals.len(), 3);
+    assert_eq!(nals[1][0], 0x06);
+    let rbsp = unescape_rbsp(&nals[1][1..]);
+    assert_eq!(rbsp[0], 5);
+    assert_eq!(rbsp[1] as usize, UUID_LEN + 7);
+    assert_eq!(&rbsp[2..18], &X264_UUID);
+    assert_eq!(&rbsp[18..25], b"my-app\0");
+    assert_eq!(rbsp[25], 0x80);
+  }
+
+  #[test]
+  fn test_leaves_other_sei_untouched() {
+    let data = h264_access_unit("caller payload");
+    assert!(rewrite_encoder_info_sei(&data, AVCodecID::H264, "").is_none());
+    assert!(rewrite_encoder_info_sei(&data, AVCodecID::Vp9, "").is_none());
+  }
+
+  #[test]
+  fn test_strip_x265_info() {
+    let mut data = vec![0, 0, 0, 1, 0x40, 0x01, 0x0c];
+    data.extend_from_slice(&[0, 0, 0, 1, 0x4e, 0x01]);
+    data.extend_from_slice(&build_sei_rbsp(&X264_UUID, "x265 (build 199) - 3.6"));
+    data.extend_from_slice(&[0, 0, 0, 1, 0x26, 0x01, 0xaf]);
+    let stripped = rewrite_encoder_info_sei(&data, AVCodecID::Hevc, "").unwrap();
+    assert_eq!(
+      stripped,
+      [0, 0, 0, 1, 0x40, 0x01, 0x0c, 0, 0, 0, 1, 0x26, 0x01, 0xaf]
+    );
+  }
+
+  #[test]
+  fn test_rbsp_escaping_round_trip() {
+    let raw = [0, 0, 1, 0, 0, 0, 0, 3, 5];
+    let escaped = escape_rbsp(&raw);
+    assert_eq!(escaped, [0, 0, 3, 1, 0, 0, 3, 0, 0, 3, 3, 5]);
+    assert_eq!(unescape_rbsp(&escaped), raw);
+  }
+}
@@ -0,0 +1,179 @@
+//! Memoized encoder instantiation for isConfigSupported
+//!
+//! Capability detection probes dozens of configs at startup, and each probe
+//! used to open an encoder (plus a hardware device for prefer-hardware),
+//! which takes seconds on machines with slow hardware enumeration. Whether
+//! an encoder can be created depends only on the codec and the hardware
+//! preference, so the outcome is cached for the process lifetime.
+//!
+//! Failures are cached as well, so a cached answer is always the one a fresh
+//! probe gave. `resetHardwareFallbackState()` clears the cache.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::codec::CodecContext;
+use crate::ffi::{AVCodecID, AVHWDeviceType, AVPixelFormat};
+
+/// Outcome of a successful encoder instantiation, without the context
+#[derive(Debug, Clone)]
+pub(crate) struct EncoderProbe {
+  /// Whether a hardware encoder was selected
+  pub is_hardware: bool,
+  /// Name of the selected encoder (e.g. "h264_videotoolbox", "libx264")
+  pub encoder_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ProbeKey {
+  /// `CodecContext::new_encoder_with_hw_info`, optionally requiring the
+  /// selected encoder to accept a pixel format
+  Video(AVCodecID, Option<AVHWDeviceType>, Option<AVPixelFormat>),
+  /// The preferred audio encoder, falling back to any encoder for the codec
+  Audio(AVCodecID),
+}
+
+static PROBES: OnceLock<Mutex<HashMap<ProbeKey, Option<EncoderProbe>>>> = OnceLock::new();
+
+fn probes() -> &'static Mutex<HashMap<ProbeKey, Option<EncoderProbe>>> {
+  PROBES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Return the cached outcome for `key`, running `probe` on a miss
+///
+/// The lock is not held while probing; concurrent misses on the same key
+/// both probe and store the same outcome.
+fn cached(key: ProbeKey, probe: impl FnOnce() -> Option<EncoderProbe>) -> Option<EncoderProbe> {
+  if let Some(hit) = probes()
+    .lock()
+    .ok()
+    .and_then(|cache| cache.get(&key).cloned())
+  {
+    return hit;
+  }
+  let outcome = probe();
+  if let Ok(mut cache) = probes().lock() {
+    cache.insert(key, outcome.clone());
+  }
+  outcome
+}
+
+/// Whether a video encoder can be created for `codec_id` with the given
+/// hardware preference, and which one would be picked
+pub(crate) fn probe_video_encoder(
+  codec_id: AVCodecID,
+  hw_type: Option<AVHWDeviceType>,
+  pixel_format: Option<AVPixelFormat>,
+) -> Option<EncoderProbe> {
+  cached(ProbeKey::Video(codec_id, hw_type, pixel_format), || {
+    let result = CodecContext::new_encoder_with_hw_info(codec_id, hw_type).ok()?;
+    if let Some(format) = pixel_format
+      && !result.context.supports_pixel_format(format)
+    {
+      return None;
+    }
+    Some(EncoderProbe {
+      is_hardware: result.is_hardware,
+      encoder_name: result.encoder_name,
+    })
+  })
+}
+
+/// Whether an audio encoder can be created for `codec_id`, trying the
+/// preferred encoder `name` first
+pub(crate) fn probe_audio_encoder(codec_id: AVCodecID, name: Option<&str>) -> bool {
+  cached(ProbeKey::Audio(codec_id), || {
+    let encoder_name = match name.filter(|name| CodecContext::new_encoder_by_name(name).is_ok()) {
+      Some(name) => name.to_string(),
+      None => {
+        CodecContext::new_encoder(codec_id).ok()?;
+        format!("codec_{:?}", codec_id)
+      }
+    };
+    Some(EncoderProbe {
+      is_hardware: false,
+      encoder_name,
+    })
+  })
+  .is_some()
+}
+
+/// Forget every cached probe
+pub(crate) fn clear_encoder_probes() {
+  if let Ok(mut cache) = probes().lock() {
+    cache.clear();
+  }
+}
+
+/// Serializes tests that fill or clear the process-wide probe cache
+#[cfg(test)]
+pub(crate) fn probe_cache_test_lock() -> std::sync::MutexGuard<'static, ()> {
+  static LOCK: Mutex<()> = Mutex::new(());
+  LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::webcodecs::hw_fallback::reset_hardware_fallback_state;
+
+  fn probe(name: &str) -> Option<EncoderProbe> {
+    Some(EncoderProbe {
+      is_hardware: false,
+      encoder_name: name.to_string(),
+    })
+  }
+
+  #[test]
+  fn test_cached_probe_is_returned_without_probing() {
+    let _lock = probe_cache_test_lock();
+    clear_encoder_probes();
+    let key = ProbeKey::Video(AVCodecID::H264, None, None);
+
+    let first = cached(key, || probe("libx264"));
+    let hit = cached(key, || panic!("cache miss for {key:?}"));
+    assert_eq!(hit.map(|p| p.encoder_name), first.map(|p| p.encoder_name));
+
+    // Keys differing only in the hardware preference are probed separately
+    let hw_key = ProbeKey::Video(AVCodecID::H264, Some(AVHWDeviceType::Videotoolbox), None);
+    assert!(cached(hw_key, || None).is_none());
+    assert!(cached(key, || None).is_some());
+  }
+
+  #[test]
+  fn test_failed_probe_is_cached() {
+    let _lock = probe_cache_test_lock();
+    let key = ProbeKey::Audio(AVCodecID::None);
+    let mut calls = 0;
+    for _ in 0..2 {
+      assert!(
+        cached(key, || {
+          calls += 1;
+          None
+        })
+        .is_none()
+      );
+    }
+    assert_eq!(calls, 1);
+
+    clear_encoder_probes();
+    assert!(cached(key, || None).is_none());
+  }
+
+  #[test]
+  fn test_reset_hardware_fallback_state_clears_probes() {
+    let _lock = probe_cache_test_lock();
+    let key = ProbeKey::Audio(AVCodecID::None);
+    assert!(cached(key, || probe("first")).is_some());
+
+    reset_hardware_fallback_state();
+
+    let mut calls = 0;
+    let reprobed = cached(key, || {
+      calls += 1;
+      probe("second")
+    });
+    assert_eq!(calls, 1);
+    assert_eq!(reprobed.map(|p| p.encoder_name).as_deref(), Some("second"));
+  }
+}
@@ -41,6 +41,8 @@ pub enum DOMExceptionName {
   TypeError,
   /// Constraint not satisfied
   ConstraintError,
+  /// Storage quota exhausted
+  QuotaExceededError,
 }
 
 impl DOMExceptionName {
@@ -53,6 +55,7 @@ impl DOMExceptionName {
       DOMExceptionName::AbortError => "AbortError",
       DOMExceptionName::TypeError => "TypeError",
       DOMExceptionName::ConstraintError => "ConstraintError",
+      DOMExceptionName::QuotaExceededError => "QuotaExceededError",
     }
   }
 }
@@ -199,6 +202,13 @@ pub fn constraint_error(message: &str) -> Error {
   dom_exception(DOMExceptionName::ConstraintError, message)
 }
 
+/// Helper to create QuotaExceededError for exhausted storage
+///
+/// Use when an operation needs more storage than it is allowed.
+pub fn quota_exceeded_error(message: &str) -> Error {
+  dom_exception(DOMExceptionName::QuotaExceededError, message)
+}
+
 /// Convert an Error with DOMException-style message to native DOMException and throw it
 ///
 /// Parses error messages like "EncodingError: Decode failed" and throws the corresponding
@@ -428,3 +438,56 @@ pub fn enforce_range_unsigned_long_long_optional(
     None => Ok(None),
   }
 }
+
+/// Read an optional `[EnforceRange] unsigned long` dictionary member without throwing
+///
+/// Codec config dictionaries are validated after conversion: configure() throws the
+/// TypeError while isConfigSupported() rejects with it. An out-of-range value (NaN,
+/// ±∞, negative or above 2^32 - 1) is therefore returned as the inner `Err` message.
+pub fn get_unsigned_long_member(
+  obj: &Object,
+  field_name: &str,
+) -> Result<std::result::Result<Option<u32>, String>> {
+  let Some(value) = obj.get::<f64>(field_name)? else {
+    return Ok(Ok(None));
+  };
+  let truncated = value.trunc();
+  if value.is_finite() && (0.0..=f64::from(u32::MAX)).contains(&truncated) {
+    Ok(Ok(Some(truncated as u32)))
+  } else {
+    Ok(Err(format!(
+      "{} is out of range for unsigned long",
+      field_name
+    )))
+  }
+}
+
+/// Panic while encoding the frame whose timestamp is in
+/// `WEBCODECS_PANIC_AT_TIMESTAMP` (`test-hooks` builds only)
+///
+/// Called on the worker thread with the encoder's state lock held, so the
+/// panic exercises the same recovery as a panic inside FFmpeg glue code.
+#[cfg(feature = "test-hooks")]
+pub(crate) fn check_injected_encoder_panic(timestamp: i64) {
+  let target = std::env::var("WEBCODECS_PANIC_AT_TIMESTAMP")
+    .ok()
+    .and_then(|value| value.parse::<i64>().ok());
+  if target == Some(timestamp) {
+    panic!("injected at timestamp {timestamp}");
+  }
+}
+
+#[cfg(not(feature = "test-hooks"))]
+#[inline(always)]
+pub(crate) fn check_injected_encoder_panic(_timestamp: i64) {}
+
+/// Message of a panic caught with `catch_unwind`
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+  if let Some(message) = payload.downcast_ref::<&str>() {
+    message
+  } else if let Some(message) = payload.downcast_ref::<String>() {
+    message
+  } else {
+    "unknown panic"
+  }
+}
@@ -0,0 +1,167 @@
+//! Feature detection for capability sniffing
+//!
+//! `getFeatures()` returns a frozen object of flags read once from the
+//! libavcodec/libavformat registries, so consumers can branch with
+//! `if (getFeatures().hevcEncode)` without probing anything. No codec or
+//! device is opened to compute them.
+
+use std::ffi::{CStr, CString};
+use std::ptr;
+use std::sync::OnceLock;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::codec::muxer::ContainerFormat;
+use crate::ffi::AVCodecID;
+use crate::ffi::avcodec::{avcodec_find_decoder, avcodec_find_encoder};
+use crate::ffi::avformat::{av_find_input_format, av_guess_format};
+use crate::ffi::hwaccel::{get_available_hwdevice_types, get_hwdevice_type_name};
+
+/// Codecs, containers and hardware accelerators compiled into this build
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Features {
+  /// An H.264 encoder is registered
+  pub h264_encode: bool,
+  /// An H.264 decoder is registered
+  pub h264_decode: bool,
+  /// An H.265/HEVC encoder is registered
+  pub hevc_encode: bool,
+  /// An H.265/HEVC decoder is registered
+  pub hevc_decode: bool,
+  /// A VP8 encoder is registered
+  pub vp8_encode: bool,
+  /// A VP8 decoder is registered
+  pub vp8_decode: bool,
+  /// A VP9 encoder is registered
+  pub vp9_encode: bool,
+  /// A VP9 decoder is registered
+  pub vp9_decode: bool,
+  /// An AV1 encoder is registered
+  pub av1_encode: bool,
+  /// An AV1 decoder is registered
+  pub av1_decode: bool,
+  /// An Opus encoder is registered
+  pub opus_encode: bool,
+  /// An Opus decoder is registered
+  pub opus_decode: bool,
+  /// An AAC encoder is registered
+  pub aac_encode: bool,
+  /// An AAC decoder is registered
+  pub aac_decode: bool,
+  /// An MP3 encoder is registered
+  pub mp3_encode: bool,
+  /// An MP3 decoder is registered
+  pub mp3_decode: bool,
+  /// A FLAC encoder is registered
+  pub flac_encode: bool,
+  /// A FLAC decoder is registered
+  pub flac_decode: bool,
+  /// A Vorbis encoder is registered
+  pub vorbis_encode: bool,
+  /// A Vorbis decoder is registered
+  pub vorbis_decode: bool,
+  /// An MP4 muxer is registered
+  pub mp4_mux: bool,
+  /// An MP4 demuxer is registered
+  pub mp4_demux: bool,
+  /// A WebM muxer is registered
+  pub webm_mux: bool,
+  /// A WebM demuxer is registered
+  pub webm_demux: bool,
+  /// A Matroska muxer is registered
+  pub mkv_mux: bool,
+  /// A Matroska demuxer is registered
+  pub mkv_demux: bool,
+  /// Hardware device types compiled into FFmpeg (e.g. "videotoolbox", "cuda");
+  /// use getAvailableHardwareAccelerators() to check that a device can be opened
+  #[napi(ts_type = "ReadonlyArray<string>")]
+  pub hardware_accelerators: Vec<String>,
+}
+
+/// Whether an encoder for `codec_id` is registered
+///
+/// Encoders are opened by name first but fall back to this lookup, so it
+/// decides whether VideoEncoder/AudioEncoder can encode the codec at all.
+pub(crate) fn encoder_available(codec_id: AVCodecID) -> bool {
+  !unsafe { avcodec_find_encoder(codec_id.as_raw()) }.is_null()
+}
+
+/// Whether a decoder for `codec_id` is registered
+pub(crate) fn decoder_available(codec_id: AVCodecID) -> bool {
+  !unsafe { avcodec_find_decoder(codec_id.as_raw()) }.is_null()
+}
+
+fn muxer_available(format: ContainerFormat) -> bool {
+  let Ok(name) = CString::new(format.short_name()) else {
+    return false;
+  };
+  !unsafe { av_guess_format(name.as_ptr(), ptr::null(), ptr::null()) }.is_null()
+}
+
+fn demuxer_available(name: &CStr) -> bool {
+  !unsafe { av_find_input_format(name.as_ptr()) }.is_null()
+}
+
+/// Read the feature flags from the FFmpeg registries
+pub(crate) fn detect_features() -> Features {
+  Features {
+    h264_encode: encoder_available(AVCodecID::H264),
+    h264_decode: decoder_available(AVCodecID::H264),
+    hevc_encode: encoder_available(AVCodecID::Hevc),
+    hevc_decode: decoder_available(AVCodecID::Hevc),
+    vp8_encode: encoder_available(AVCodecID::Vp8),
+    vp8_decode: decoder_available(AVCodecID::Vp8),
+    vp9_encode: encoder_available(AVCodecID::Vp9),
+    vp9_decode: decoder_available(AVCodecID::Vp9),
+    av1_encode: encoder_available(AVCodecID::Av1),
+    av1_decode: decoder_available(AVCodecID::Av1),
+    opus_encode: encoder_available(AVCodecID::Opus),
+    opus_decode: decoder_available(AVCodecID::Opus),
+    aac_encode: encoder_available(AVCodecID::Aac),
+    aac_decode: decoder_available(AVCodecID::Aac),
+    mp3_encode: encoder_available(AVCodecID::Mp3),
+    mp3_decode: decoder_available(AVCodecID::Mp3),
+    flac_encode: encoder_available(AVCodecID::Flac),
+    flac_decode: decoder_available(AVCodecID::Flac),
+    vorbis_encode: encoder_available(AVCodecID::Vorbis),
+    vorbis_decode: decoder_available(AVCodecID::Vorbis),
+    mp4_mux: muxer_available(ContainerFormat::Mp4),
+    // The mov demuxer registers "mp4" among its names
+    mp4_demux: demuxer_available(c"mp4"),
+    webm_mux: muxer_available(ContainerFormat::WebM),
+    webm_demux: demuxer_available(c"webm"),
+    mkv_mux: muxer_available(ContainerFormat::Mkv),
+    mkv_demux: demuxer_available(c"matroska"),
+    hardware_accelerators: get_available_hwdevice_types()
+      .into_iter()
+      .filter_map(get_hwdevice_type_name)
+      .map(String::from)
+      .collect(),
+  }
+}
+
+/// Codecs, containers and hardware accelerators compiled into this build
+///
+/// The flags are read from the FFmpeg registries once; every call returns a
+/// frozen copy. A codec flag is true when an encoder or decoder for it is
+/// registered; getCodecCapabilities() throws for exactly the video codecs
+/// whose encode flag is false.
+#[napi(ts_return_type = "Readonly<Features>")]
+pub fn get_features(env: &Env) -> Result<Object<'_>> {
+  static FEATURES: OnceLock<Features> = OnceLock::new();
+  let features = FEATURES.get_or_init(detect_features).clone();
+
+  let freeze = env
+    .get_global()?
+    .get_named_property_unchecked::<Object>("Object")?
+    .get_named_property_unchecked::<Function<Object, Object>>("freeze")?;
+  let features = unsafe {
+    let value = Features::to_napi_value(env.raw(), features)?;
+    Object::from_napi_value(env.raw(), value)?
+  };
+  // Object.freeze is shallow, so freeze the accelerator list too
+  freeze.call(features.get_named_property_unchecked::<Object>("hardwareAccelerators")?)?;
+  freeze.call(features)
+}
@@ -0,0 +1,164 @@
+//! Frame Budget - Outstanding output frame accounting for VideoDecoder
+//!
+//! Every VideoFrame a decoder hands to its output callback carries an
+//! `OutputSlot`. The slot is released when the frame is closed or garbage
+//! collected, so the budget always knows which decoded frames JS is still
+//! holding. With `maxOutstandingFrames` configured, the decoder worker waits
+//! for a slot to be released before pulling the next command from its queue,
+//! and `close({ detachFrames: true })` closes every frame still held. A plain
+//! `close()` instead keeps every held frame valid, downloading any frame still
+//! backed by a hardware surface before the decoder releases its device.
+//!
+//! Frames produced while a flush() is pending are held by the decoder until the
+//! flush resolves, so JS cannot close them yet. They take their slot at
+//! delivery, and the cap is suspended for the duration of the flush so that it
+//! can never deadlock.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use crate::webcodecs::VideoFrame;
+use crate::webcodecs::video_frame::VideoFrameHandle;
+
+/// How often a waiting worker re-checks its cancellation flags
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Shared between a decoder, its worker thread and the frames it has output
+pub(crate) struct FrameBudget {
+  /// Frames output and not yet closed or collected
+  outstanding: AtomicU32,
+  /// Maximum outstanding frames (0 = unlimited)
+  limit: AtomicU32,
+  /// Set while a flush() is pending (cap not enforced)
+  suspended: AtomicBool,
+  /// Outstanding frames by slot id
+  frames: Mutex<HashMap<u64, VideoFrameHandle>>,
+  next_slot_id: AtomicU64,
+  /// Paired with `released` so a waiting worker does not miss a wakeup
+  lock: Mutex<()>,
+  released: Condvar,
+}
+
+impl FrameBudget {
+  pub fn new() -> Self {
+    Self {
+      outstanding: AtomicU32::new(0),
+      limit: AtomicU32::new(0),
+      suspended: AtomicBool::new(false),
+      frames: Mutex::new(HashMap::new()),
+      next_slot_id: AtomicU64::new(0),
+      lock: Mutex::new(()),
+      released: Condvar::new(),
+    }
+  }
+
+  /// Set the cap from the decoder config (None = unlimited)
+  pub fn set_limit(&self, limit: Option<u32>) {
+    self.limit.store(limit.unwrap_or(0), Ordering::SeqCst);
+    self.notify();
+  }
+
+  /// Suspend the cap while a flush() is pending, or restore it
+  pub fn set_suspended(&self, suspended: bool) {
+    self.suspended.store(suspended, Ordering::SeqCst);
+    self.notify();
+  }
+
+  /// Number of output frames JS has not closed yet
+  pub fn outstanding(&self) -> u32 {
+    self.outstanding.load(Ordering::SeqCst)
+  }
+
+  /// Count a frame being handed to JS until it is closed or collected
+  ///
+  /// Never blocks: every frame produced by a decode is delivered, and the cap
+  /// is enforced before the next command is pulled.
+  pub fn track(self: &Arc<Self>, frame: &VideoFrame) {
+    let id = self.next_slot_id.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut frames) = self.frames.lock() {
+      frames.insert(id, frame.handle());
+    }
+    self.outstanding.fetch_add(1, Ordering::SeqCst);
+    frame.attach_output_slot(OutputSlot {
+      budget: self.clone(),
+      id,
+    });
+  }
+
+  /// Close every output frame JS still holds
+  pub fn close_outstanding(&self) {
+    let frames = match self.frames.lock() {
+      Ok(mut frames) => std::mem::take(&mut *frames),
+      Err(_) => return,
+    };
+    // Closing a frame releases its slot, which takes the map lock again
+    for handle in frames.values() {
+      handle.close();
+    }
+  }
+
+  /// Make every output frame JS still holds independent of the decoder's device
+  pub fn transfer_outstanding_to_cpu(&self) {
+    // Collect first: closing a frame whose download fails releases its slot,
+    // which takes the map lock again
+    let handles: Vec<VideoFrameHandle> = match self.frames.lock() {
+      Ok(frames) => frames.values().cloned().collect(),
+      Err(_) => return,
+    };
+    for handle in &handles {
+      handle.transfer_to_cpu();
+    }
+  }
+
+  /// Block until the outstanding count is below the cap
+  ///
+  /// Returns early once any of `cancel_flags` is set so that reset(), close()
+  /// and environment teardown never wait on frames JS may never close.
+  pub fn wait_for_capacity(&self, cancel_flags: &[&AtomicBool]) {
+    let mut guard = match self.lock.lock() {
+      Ok(g) => g,
+      Err(_) => return,
+    };
+    loop {
+      let limit = self.limit.load(Ordering::SeqCst);
+      if limit == 0 || self.outstanding() < limit || self.suspended.load(Ordering::SeqCst) {
+        return;
+      }
+      if cancel_flags.iter().any(|flag| flag.load(Ordering::SeqCst)) {
+        return;
+      }
+      guard = match self.released.wait_timeout(guard, CANCEL_POLL_INTERVAL) {
+        Ok((g, _)) => g,
+        Err(_) => return,
+      };
+    }
+  }
+
+  /// Wake a waiting worker (after a release, a limit change or cancellation)
+  pub fn notify(&self) {
+    let _guard = self.lock.lock();
+    self.released.notify_all();
+  }
+
+  fn release(&self, id: u64) {
+    if let Ok(mut frames) = self.frames.lock() {
+      frames.remove(&id);
+    }
+    self.outstanding.fetch_sub(1, Ordering::SeqCst);
+    self.notify();
+  }
+}
+
+/// One outstanding output frame, released on drop
+pub(crate) struct OutputSlot {
+  budget: Arc<FrameBudget>,
+  id: u64,
+}
+
+impl Drop for OutputSlot {
+  fn drop(&mut self) {
+    self.budget.release(self.id);
+  }
+}
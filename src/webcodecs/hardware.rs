@@ -1,8 +1,17 @@
 //! Hardware acceleration utilities
 //!
-//! Provides JavaScript-accessible functions for querying hardware acceleration support.
+//! Provides JavaScript-accessible functions for querying hardware acceleration support,
+//! and tracks the hardware encoder sessions open in this process per accelerator.
+//!
+//! Drivers cap concurrent encode sessions (NVENC on consumer GPUs allows 3 to 5),
+//! and the encoder that exceeds the cap fails to open. Each failure records how
+//! many sessions were open at the time, so schedulers can read the cap back from
+//! `getHardwareSessionCounts()` instead of over-allocating.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
 
-use crate::codec::HwDeviceContext;
+use crate::codec::{CodecError, HwDeviceContext, set_device_sharing};
 use crate::ffi::AVHWDeviceType;
 use napi_derive::napi;
 
@@ -124,3 +133,170 @@ pub fn get_preferred_hardware_accelerator() -> Option<String> {
 
   None
 }
+
+/// Share one hardware device per accelerator between all codecs (default: true)
+///
+/// Call with false before configuring codecs that need an isolated device.
+/// Codecs configured earlier keep the device they already hold.
+#[napi]
+pub fn set_hardware_device_sharing(enabled: bool) {
+  set_device_sharing(enabled);
+}
+
+/// Hardware encoder sessions of one accelerator
+#[napi(object)]
+pub struct HardwareSessionCount {
+  /// Accelerator name, as in getHardwareAccelerators()
+  pub accelerator: String,
+  /// Hardware encoders currently open in this process
+  pub active_encoders: u32,
+  /// Encoders that were open the last time the driver refused another session
+  pub max_encoders: Option<u32>,
+  /// Number of encoder opens refused by the driver's session limit
+  pub limit_hits: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct SessionStats {
+  active: u32,
+  max_observed: Option<u32>,
+  limit_hits: u32,
+}
+
+static HW_SESSIONS: Mutex<BTreeMap<&'static str, SessionStats>> = Mutex::new(BTreeMap::new());
+
+fn with_sessions<R>(accelerator: &'static str, f: impl FnOnce(&mut SessionStats) -> R) -> R {
+  let mut sessions = HW_SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+  f(sessions.entry(accelerator).or_default())
+}
+
+/// Get the hardware encoder sessions open in this process, per accelerator
+///
+/// Only accelerators that opened an encoder or refused one are listed.
+#[napi]
+pub fn get_hardware_session_counts() -> Vec<HardwareSessionCount> {
+  let sessions = HW_SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+  sessions
+    .iter()
+    .map(|(accelerator, stats)| HardwareSessionCount {
+      accelerator: accelerator.to_string(),
+      active_encoders: stats.active,
+      max_encoders: stats.max_observed,
+      limit_hits: stats.limit_hits,
+    })
+    .collect()
+}
+
+/// Accelerator an FFmpeg hardware encoder runs on (None for software encoders)
+pub(crate) fn encoder_accelerator(encoder_name: &str) -> Option<&'static str> {
+  match encoder_name.rsplit('_').next()? {
+    "nvenc" => Some("cuda"),
+    "vaapi" => Some("vaapi"),
+    "qsv" => Some("qsv"),
+    "videotoolbox" => Some("videotoolbox"),
+    "amf" | "mf" => Some("d3d11va"),
+    "vulkan" => Some("vulkan"),
+    _ => None,
+  }
+}
+
+/// One open hardware encoder session, counted until dropped
+#[derive(Debug)]
+pub(crate) struct HwEncoderSession {
+  accelerator: &'static str,
+}
+
+impl HwEncoderSession {
+  /// Count an opened encoder; None for software encoders
+  pub fn open(encoder_name: &str) -> Option<Self> {
+    let accelerator = encoder_accelerator(encoder_name)?;
+    with_sessions(accelerator, |stats| stats.active += 1);
+    Some(Self { accelerator })
+  }
+}
+
+impl Drop for HwEncoderSession {
+  fn drop(&mut self) {
+    with_sessions(self.accelerator, |stats| {
+      stats.active = stats.active.saturating_sub(1)
+    });
+  }
+}
+
+/// QuotaExceededError message when a hardware encoder hit the driver's session cap
+///
+/// Kept apart from NotSupportedError: the configuration is fine and opening
+/// succeeds again once another encoder closes. Records the refusal against the
+/// encoder's accelerator.
+pub(crate) fn session_limit_message(e: &CodecError, encoder_name: &str) -> Option<String> {
+  if !matches!(e, CodecError::SessionLimit(_)) {
+    return None;
+  }
+  let accelerator = encoder_accelerator(encoder_name).unwrap_or("hardware");
+  let active = with_sessions(accelerator, |stats| {
+    stats.limit_hits += 1;
+    if stats.active > 0 {
+      stats.max_observed = Some(stats.active);
+    }
+    stats.active
+  });
+  Some(format!(
+    "QuotaExceededError: {} refused another encode session with {} already open in this process; \
+     close an encoder or use hardwareAcceleration: 'prefer-software' ({})",
+    encoder_name, active, e
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn stats(accelerator: &'static str) -> SessionStats {
+    with_sessions(accelerator, |stats| *stats)
+  }
+
+  #[test]
+  fn encoder_names_map_to_accelerators() {
+    assert_eq!(encoder_accelerator("h264_nvenc"), Some("cuda"));
+    assert_eq!(
+      encoder_accelerator("hevc_videotoolbox"),
+      Some("videotoolbox")
+    );
+    assert_eq!(encoder_accelerator("av1_qsv"), Some("qsv"));
+    assert_eq!(encoder_accelerator("h264_mf"), Some("d3d11va"));
+    assert_eq!(encoder_accelerator("libx264"), None);
+    assert_eq!(encoder_accelerator("libvpx-vp9"), None);
+  }
+
+  #[test]
+  fn sessions_are_counted_until_dropped() {
+    let before = stats("vaapi").active;
+    let first = HwEncoderSession::open("h264_vaapi");
+    let second = HwEncoderSession::open("hevc_vaapi");
+    assert!(HwEncoderSession::open("libx264").is_none());
+    assert_eq!(stats("vaapi").active, before + 2);
+    drop(first);
+    drop(second);
+    assert_eq!(stats("vaapi").active, before);
+  }
+
+  #[test]
+  fn session_limit_maps_to_quota_exceeded() {
+    let sessions: Vec<_> = (0..3)
+      .filter_map(|_| HwEncoderSession::open("h264_nvenc"))
+      .collect();
+    let refused = CodecError::SessionLimit(
+      "OpenEncodeSessionEx failed: incompatible client key (21): (no details)".to_string(),
+    );
+    let message = session_limit_message(&refused, "h264_nvenc").unwrap();
+    assert!(message.starts_with("QuotaExceededError: h264_nvenc"));
+    let cuda = stats("cuda");
+    assert!(cuda.limit_hits >= 1);
+    assert!(cuda.max_observed.is_some_and(|max| max >= 3));
+    drop(sessions);
+
+    let invalid =
+      CodecError::Fatal("Provided device doesn't support required NVENC features".into());
+    assert_eq!(session_limit_message(&invalid, "h264_nvenc"), None);
+  }
+}
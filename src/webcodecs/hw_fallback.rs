@@ -20,6 +20,8 @@ use std::time::{Duration, Instant};
 
 use napi_derive::napi;
 
+use crate::webcodecs::encoder_probe::clear_encoder_probes;
+
 const GLOBAL_FAILURE_THRESHOLD: u32 = 3;
 const FORGIVENESS_INTERVAL: Duration = Duration::from_secs(60);
 
@@ -39,7 +41,8 @@ static HW_STATE: Mutex<HwFallbackState> = Mutex::new(HwFallbackState {
 
 /// Reset all hardware fallback state.
 ///
-/// This clears all failure counts and re-enables hardware acceleration.
+/// This clears all failure counts, re-enables hardware acceleration and
+/// forgets the encoders probed by isConfigSupported().
 /// Useful for:
 /// - Test isolation (call in beforeEach)
 /// - Error recovery after fixing hardware issues
@@ -51,6 +54,7 @@ pub fn reset_hardware_fallback_state() {
     state.encoding_failure_count = 0;
     state.encoding_disabled_at = None;
   }
+  clear_encoder_probes();
 }
 
 /// Check if hardware encoding is currently disabled due to failures.
@@ -104,15 +108,21 @@ pub fn record_hw_encoding_success() {
 
 #[cfg(test)]
 mod tests {
+  use std::sync::MutexGuard;
+
   use super::*;
+  use crate::webcodecs::encoder_probe::probe_cache_test_lock;
 
-  fn reset_state() {
+  /// Reset the shared state, holding the probe cache lock for the whole test
+  fn reset_state() -> MutexGuard<'static, ()> {
+    let lock = probe_cache_test_lock();
     reset_hardware_fallback_state();
+    lock
   }
 
   #[test]
   fn test_encoding_failure_threshold() {
-    reset_state();
+    let _lock = reset_state();
 
     assert!(!is_hw_encoding_disabled());
 
@@ -126,7 +136,7 @@ mod tests {
 
   #[test]
   fn test_encoding_success_resets_count() {
-    reset_state();
+    let _lock = reset_state();
 
     // Record some failures (but not enough to disable)
     record_hw_encoding_failure();
@@ -144,7 +154,7 @@ mod tests {
 
   #[test]
   fn test_reset_clears_state() {
-    reset_state();
+    let _lock = reset_state();
 
     // Disable encoding
     for _ in 0..GLOBAL_FAILURE_THRESHOLD {
@@ -161,7 +171,7 @@ mod tests {
 
   #[test]
   fn test_additional_failures_dont_restart_timer() {
-    reset_state();
+    let _lock = reset_state();
 
     // Record failures to hit threshold
     for _ in 0..GLOBAL_FAILURE_THRESHOLD {
@@ -4,17 +4,21 @@
 //! See: <https://developer.mozilla.org/en-US/docs/Web/API/ImageDecoder>
 
 use crate::codec::{CodecContext, DecoderConfig, Frame, Packet, ScaleAlgorithm, Scaler};
-use crate::ffi::AVCodecID;
-use crate::webcodecs::VideoFrame;
+use crate::ffi::{AVCodecID, AVPixelFormat};
+use crate::webcodecs::buffer_view::BufferView;
 use crate::webcodecs::error::{invalid_state_error, throw_invalid_state_error};
-use futures::stream::{StreamExt, TryStreamExt};
+use crate::webcodecs::plain_bytes::PlainUint8Array;
+use crate::webcodecs::{VideoFrame, VideoPixelFormat};
+use futures::stream::TryStreamExt;
 use napi::bindgen_prelude::*;
 use napi::tokio::sync::Notify;
 use napi_derive::napi;
 use parking_lot::RwLock as ParkingLotRwLock;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::{
   Arc, Mutex,
-  atomic::{AtomicBool, Ordering},
+  atomic::{AtomicBool, AtomicUsize, Ordering},
 };
 
 const COMPLETED_PROMISE: &str = "[[completed]]";
@@ -35,9 +39,9 @@ pub enum ColorSpaceConversion {
 
 /// Data source for ImageDecoder - stores the encoded image data
 pub enum ImageDecoderData {
-  /// Buffered data (Uint8Array from constructor)
+  /// Buffered data (BufferSource from constructor, viewed as a Uint8Array)
   Buffer(Uint8Array),
-  /// Collected stream data (Vec<u8> after stream is fully read)
+  /// Stream or appendData() bytes received so far
   Vec(Vec<u8>),
   /// Empty (data has been consumed or not yet available)
   Empty,
@@ -47,7 +51,8 @@ pub enum ImageDecoderData {
 /// Per W3C spec, `data` can be either a BufferSource or a ReadableStream
 pub struct ImageDecoderInit<'env> {
   /// The image data (encoded bytes or stream) - BufferSource | ReadableStream per spec
-  pub data: Unknown<'env>,
+  /// None when bytes are supplied with appendData() (non-standard)
+  pub data: Option<Unknown<'env>>,
   /// MIME type of the image (e.g., "image/png", "image/jpeg")
   pub mime_type: String,
   /// Color space conversion mode (default: "default")
@@ -119,7 +124,7 @@ impl<'env> FromNapiValue for ImageDecoderInit<'env> {
       ));
     }
 
-    // Get data - try Uint8Array first, then ReadableStream
+    // Get data - try BufferSource first, then ReadableStream
     let data_napi_value: napi::sys::napi_value = {
       let mut result = std::ptr::null_mut();
       napi::check_status!(
@@ -132,6 +137,10 @@ impl<'env> FromNapiValue for ImageDecoderInit<'env> {
     };
 
     let data = unsafe { Unknown::from_raw_unchecked(env, data_napi_value) };
+    let data = match data.get_type()? {
+      ValueType::Undefined | ValueType::Null => None,
+      _ => Some(data),
+    };
 
     Ok(ImageDecoderInit {
       data,
@@ -152,6 +161,13 @@ pub struct ImageDecodeOptions {
   pub frame_index: Option<u32>,
   /// Whether to only decode complete frames
   pub complete_frames_only: Option<bool>,
+  /// Pixel format of the returned frame, e.g. "I420" for frames passed to
+  /// VideoEncoder (extension)
+  ///
+  /// The conversion runs once per frame and format on the decode thread and is
+  /// the same one VideoEncoder.encode() applies, so the encoder can then use the
+  /// frame without converting or copying it.
+  pub preferred_format: Option<VideoPixelFormat>,
 }
 
 /// Image decode result
@@ -180,6 +196,55 @@ impl ImageDecodeResult {
   }
 }
 
+/// Options for ImageDecoder.decodeAll() (extension)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ImageDecodeAllOptions {
+  /// MIME type of every image; when omitted, each image's format is detected
+  /// from its signature (JPEG, PNG, GIF, WebP or BMP)
+  #[napi(js_name = "type")]
+  pub mime_type: Option<String>,
+  /// Color space conversion mode (default: "default")
+  pub color_space_conversion: Option<ColorSpaceConversion>,
+  /// Scale every image to this width - must be paired with desiredHeight
+  pub desired_width: Option<u32>,
+  /// Scale every image to this height - must be paired with desiredWidth
+  pub desired_height: Option<u32>,
+  /// Pixel format of the returned frames (see ImageDecodeOptions.preferredFormat)
+  pub preferred_format: Option<VideoPixelFormat>,
+  /// Maximum number of images decoded at once, which bounds the memory held
+  /// by in-flight decodes (default: number of CPUs)
+  pub concurrency: Option<u32>,
+}
+
+/// One image of an ImageDecoder.decodeAll() batch (extension)
+///
+/// Holds either the decoded image or the reason it failed to decode.
+#[napi]
+pub struct ImageDecodeAllResult {
+  image: Option<VideoFrame>,
+  error: Option<String>,
+}
+
+#[napi]
+impl ImageDecodeAllResult {
+  /// The decoded image, or null if this image failed to decode
+  #[napi(getter)]
+  pub fn image(&self, env: Env) -> Result<Option<VideoFrame>> {
+    self
+      .image
+      .as_ref()
+      .map(|image| image.clone_frame(env))
+      .transpose()
+  }
+
+  /// Why this image failed to decode, or null on success
+  #[napi(getter)]
+  pub fn error(&self) -> Option<String> {
+    self.error.clone()
+  }
+}
+
 /// Internal track data (shared between ImageTrack instances)
 #[derive(Debug, Clone)]
 struct ImageTrackData {
@@ -189,6 +254,8 @@ struct ImageTrackData {
   frame_count: u32,
   /// Number of times the animation repeats (Infinity for infinite)
   repetition_count: f64,
+  /// Embedded ICC profile (known once frames are decoded)
+  icc_profile: Option<Vec<u8>>,
 }
 
 /// Internal state for ImageTrackList (shared with ImageTrack instances)
@@ -257,6 +324,25 @@ impl ImageTrack {
     )
   }
 
+  /// Embedded ICC profile (PNG iCCP, JPEG APP2, WebP ICCP), or null if none
+  ///
+  /// Available once `tracks.ready` resolves. Decoded frames are not color
+  /// managed; applying the profile is left to the caller.
+  #[napi(getter, ts_return_type = "Uint8Array | null")]
+  pub fn icc_profile(&self) -> Result<Option<PlainUint8Array>> {
+    let inner = self
+      .track_list_inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(
+      inner
+        .tracks
+        .get(self.index)
+        .and_then(|t| t.icc_profile.clone())
+        .map(PlainUint8Array::from),
+    )
+  }
+
   /// Whether this track is currently selected (W3C spec - writable)
   #[napi(getter)]
   pub fn selected(&self) -> Result<bool> {
@@ -403,6 +489,10 @@ struct ImageDecoderInner {
   context: Option<CodecContext>,
   /// Whether data is fully buffered (true for Buffer, becomes true for Stream when finished)
   complete: Arc<AtomicBool>,
+  /// Whether bytes arrive through appendData() (constructed without `data`)
+  appendable: bool,
+  /// Wakes the appendData() completion task on endOfData() or close()
+  data_notify: Arc<Notify>,
   /// Track list
   tracks: ImageTrackList,
   /// Whether decoder is closed
@@ -410,6 +500,8 @@ struct ImageDecoderInner {
   /// Cached decoded frames (for animated images, populated on first decode)
   /// Cached decoded frames wrapped in Arc for efficient sharing
   cached_frames: Option<Vec<Arc<ParkingLotRwLock<Frame>>>>,
+  /// Cached frames converted to a decode() `preferredFormat`, by frame index and format
+  converted_frames: HashMap<(usize, AVPixelFormat), Arc<ParkingLotRwLock<Frame>>>,
   /// Color space conversion mode (W3C spec)
   color_space_conversion: ColorSpaceConversion,
   /// Desired width for scaling (W3C spec - must be paired with desired_height)
@@ -442,7 +534,7 @@ pub struct ImageDecoder {
 #[napi]
 impl ImageDecoder {
   /// Create a new ImageDecoder
-  /// Supports both Uint8Array and ReadableStream as data source per W3C spec
+  /// Supports both BufferSource and ReadableStream as data source per W3C spec
   #[napi(constructor)]
   pub fn new<'env>(env: &'env Env, mut this: This, init: ImageDecoderInit<'env>) -> Result<Self> {
     // Parse MIME type to codec ID - accept invalid types (will fail at decode time per W3C spec)
@@ -464,6 +556,7 @@ impl ImageDecoder {
         animated,
         frame_count,
         repetition_count: if animated { f64::INFINITY } else { 0.0 },
+        icc_profile: None,
       }],
       selected_index: Some(0),
     }));
@@ -475,28 +568,35 @@ impl ImageDecoder {
     };
 
     // Create inner state first so we can share it with async tasks
+    let data_notify = Arc::new(Notify::new());
     let inner = Arc::new(Mutex::new(ImageDecoderInner {
       data: ImageDecoderData::Empty,
       mime_type: init.mime_type.clone(),
       codec_id,
       context: None,
       complete: complete.clone(),
+      appendable: init.data.is_none(),
+      data_notify: data_notify.clone(),
       tracks: tracks.clone(),
       closed: false,
       cached_frames: None,
+      converted_frames: HashMap::new(),
       color_space_conversion: init.color_space_conversion,
       desired_width: init.desired_width,
       desired_height: init.desired_height,
       prefer_animation: init.prefer_animation,
     }));
 
-    if let Ok(buf) = unsafe { init.data.cast::<Uint8Array>() } {
+    if let Some(buf) = init
+      .data
+      .and_then(|data| unsafe { data.cast::<BufferView>() }.ok())
+    {
       // Buffer data: store immediately and mark complete
       {
         let mut inner_guard = inner
           .lock()
           .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-        inner_guard.data = ImageDecoderData::Buffer(buf);
+        inner_guard.data = ImageDecoderData::Buffer(buf.into_inner());
       }
       complete.store(true, Ordering::Release);
 
@@ -528,7 +628,10 @@ impl ImageDecoder {
           .with_value(&ready_promise)
           .with_property_attributes(PropertyAttributes::default()),
       ])?;
-    } else if let Ok(stream) = unsafe { init.data.cast::<ReadableStream<Uint8Array>>() } {
+    } else if let Some(stream) = init
+      .data
+      .and_then(|data| unsafe { data.cast::<ReadableStream<Uint8Array>>() }.ok())
+    {
       // Stream data: start collecting asynchronously
       let reader = stream.read()?;
       let inner_clone = inner.clone();
@@ -536,17 +639,20 @@ impl ImageDecoder {
       // For stream data: combined promise that does collection + pre-parse
       // Both completed and ready resolve when this completes (Option A: simpler)
       let combined_promise = env.spawn_future(async move {
-        // Collect all stream data
-        let stream_data = reader.map(|s| s.map(|c| c.to_vec())).try_concat().await;
+        // Append chunks as they arrive so static image tracks are ready early
+        let stream_data = reader
+          .try_for_each(|chunk| {
+            if let Ok(mut inner_guard) = inner_clone.lock() {
+              append_image_data(&mut inner_guard, &chunk);
+            }
+            futures::future::ready(Ok(()))
+          })
+          .await;
 
         match stream_data {
-          Ok(collected_data) => {
-            // Store collected data in inner
-            {
-              if let Ok(mut inner_guard) = inner_clone.lock() {
-                inner_guard.data = ImageDecoderData::Vec(collected_data);
-                inner_guard.complete.store(true, Ordering::Release);
-              }
+          Ok(()) => {
+            if let Ok(inner_guard) = inner_clone.lock() {
+              inner_guard.complete.store(true, Ordering::Release);
             }
 
             // Pre-parse metadata and cache frames
@@ -570,6 +676,35 @@ impl ImageDecoder {
       })?;
 
       // Store the combined promise for both completed and ready
+      this.define_properties(&[
+        Property::new()
+          .with_utf8_name(COMPLETED_PROMISE)?
+          .with_value(&combined_promise)
+          .with_property_attributes(PropertyAttributes::default()),
+        Property::new()
+          .with_utf8_name(READY_PROMISE)?
+          .with_value(&combined_promise)
+          .with_property_attributes(PropertyAttributes::default()),
+      ])?;
+    } else if init.data.is_none() {
+      // Appended data: decodable once endOfData() is called
+      let inner_clone = inner.clone();
+      let combined_promise = env.spawn_future(async move {
+        // notify_one() stores a permit, so an endOfData() before this point is not lost
+        if !complete.load(Ordering::Acquire) {
+          data_notify.notified().await;
+        }
+
+        let result = spawn_blocking(move || pre_parse_and_cache_frames(&inner_clone)).await;
+        match result {
+          Ok(Ok(())) | Ok(Err(_)) | Err(_) => {
+            // Always signal ready (done in pre_parse_and_cache_frames)
+          }
+        }
+
+        Ok(())
+      })?;
+
       this.define_properties(&[
         Property::new()
           .with_utf8_name(COMPLETED_PROMISE)?
@@ -587,6 +722,55 @@ impl ImageDecoder {
     Ok(Self { inner })
   }
 
+  /// Append encoded bytes (non-standard, only when constructed without `data`)
+  ///
+  /// The tracks of a static image (JPEG, PNG, BMP) are ready as soon as its
+  /// header has arrived. decode() waits for endOfData() unless
+  /// `completeFramesOnly` is false.
+  #[napi]
+  pub fn append_data(&self, env: Env, data: BufferView) -> Result<()> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+    if inner.closed {
+      return throw_invalid_state_error(&env, "ImageDecoder is closed");
+    }
+    if !inner.appendable {
+      return throw_invalid_state_error(&env, "ImageDecoder was constructed with data");
+    }
+    if inner.complete.load(Ordering::Acquire) {
+      return throw_invalid_state_error(&env, "endOfData() has already been called");
+    }
+
+    append_image_data(&mut inner, &data);
+    Ok(())
+  }
+
+  /// Mark the appended data as complete (non-standard)
+  ///
+  /// Resolves `completed` once the image has been parsed. Further calls do nothing.
+  #[napi]
+  pub fn end_of_data(&self, env: Env) -> Result<()> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+    if inner.closed {
+      return throw_invalid_state_error(&env, "ImageDecoder is closed");
+    }
+    if !inner.appendable {
+      return throw_invalid_state_error(&env, "ImageDecoder was constructed with data");
+    }
+    if !inner.complete.swap(true, Ordering::AcqRel) {
+      inner.data_notify.notify_one();
+    }
+
+    Ok(())
+  }
+
   /// Whether the data is fully buffered
   #[napi(getter)]
   pub fn complete(&self) -> Result<bool> {
@@ -665,6 +849,28 @@ impl ImageDecoder {
         }
       }
 
+      // With completeFramesOnly: false, decode what has arrived of a static image
+      let complete_frames_only = options
+        .as_ref()
+        .and_then(|o| o.complete_frames_only)
+        .unwrap_or(true);
+      let first_frame = options.as_ref().and_then(|o| o.frame_index).unwrap_or(0) == 0;
+      if !complete_frames_only && first_frame {
+        let inner = inner.clone();
+        let preferred_format = options.as_ref().and_then(|o| o.preferred_format);
+        let partial = spawn_blocking(move || decode_partial_image(&inner, preferred_format))
+          .await
+          .map_err(|join_error| {
+            Error::new(
+              Status::GenericFailure,
+              format!("Decode task failed: {}", join_error),
+            )
+          })??;
+        if let Some(result) = partial {
+          return Ok(result);
+        }
+      }
+
       // Wait for ready promise (ensures initial pre-parsing is complete)
       ready_promise.await?;
 
@@ -704,37 +910,7 @@ impl ImageDecoder {
 
           // Create decoder context if needed
           if inner.context.is_none() {
-            let mut context = CodecContext::new_decoder(codec_id).map_err(|e| {
-              Error::new(
-                Status::GenericFailure,
-                format!("Failed to create decoder: {}", e),
-              )
-            })?;
-
-            let decoder_config = DecoderConfig {
-              codec_id,
-              thread_count: 0,
-              extradata: None,
-              low_latency: false,
-              width: None,
-              height: None,
-            };
-
-            context.configure_decoder(&decoder_config).map_err(|e| {
-              Error::new(
-                Status::GenericFailure,
-                format!("Failed to configure decoder: {}", e),
-              )
-            })?;
-
-            context.open().map_err(|e| {
-              Error::new(
-                Status::GenericFailure,
-                format!("Failed to open decoder: {}", e),
-              )
-            })?;
-
-            inner.context = Some(context);
+            inner.context = Some(open_image_decoder(codec_id)?);
           }
 
           // Decode all frames
@@ -752,35 +928,15 @@ impl ImageDecoder {
             }
           }
 
+          // Scaling drops frame side data, so record the ICC profile first
+          record_icc_profile(&inner.tracks, &frames);
+
           // Apply desiredWidth/desiredHeight scaling if both are specified
           let frames = if let (Some(dw), Some(dh)) = (inner.desired_width, inner.desired_height) {
-            let mut scaled_frames = Vec::with_capacity(frames.len());
-            for frame in frames {
-              let scaler = Scaler::new(
-                frame.width(),
-                frame.height(),
-                frame.format(),
-                dw,
-                dh,
-                frame.format(),
-                ScaleAlgorithm::Lanczos,
-              )
-              .map_err(|e| {
-                Error::new(
-                  Status::GenericFailure,
-                  format!("Failed to create scaler: {}", e),
-                )
-              })?;
-
-              let scaled = scaler.scale_alloc(&frame).map_err(|e| {
-                Error::new(
-                  Status::GenericFailure,
-                  format!("Failed to scale frame: {}", e),
-                )
-              })?;
-              scaled_frames.push(scaled);
-            }
-            scaled_frames
+            frames
+              .iter()
+              .map(|frame| scale_image_frame(frame, dw, dh))
+              .collect::<Result<Vec<_>>>()?
           } else {
             frames
           };
@@ -827,7 +983,25 @@ impl ImageDecoder {
         }
 
         // Clone the Arc to share the frame data (no pixel copy needed)
-        let frame_arc = frames[frame_index].clone();
+        let mut frame_arc = frames[frame_index].clone();
+        let preferred_format = options
+          .as_ref()
+          .and_then(|o| o.preferred_format)
+          .map(|format| format.to_av_format());
+        if let Some(format) = preferred_format
+          && frame_arc.read().format() != format
+        {
+          frame_arc = match inner.converted_frames.get(&(frame_index, format)) {
+            Some(converted) => converted.clone(),
+            None => {
+              let converted = convert_image_frame(&frame_arc.read(), format)?.into_shared();
+              inner
+                .converted_frames
+                .insert((frame_index, format), converted.clone());
+              converted
+            }
+          };
+        }
         let pts = frame_arc.read().pts();
 
         // Per Chromium behavior: "default" extracts color space, "none" ignores it
@@ -865,6 +1039,7 @@ impl ImageDecoder {
 
     inner.context = None;
     inner.cached_frames = None;
+    inner.converted_frames.clear();
 
     // Reset frame_count for animated formats (will be re-detected on next decode)
     if let Ok(mut track_inner) = inner.tracks.inner.lock()
@@ -887,10 +1062,12 @@ impl ImageDecoder {
 
     inner.context = None;
     inner.cached_frames = None;
+    inner.converted_frames.clear();
     inner.closed = true;
 
     // Wake any waiters so they can check closed state
     inner.tracks.ready_notify.notify_waiters();
+    inner.data_notify.notify_one();
 
     Ok(())
   }
@@ -910,6 +1087,183 @@ impl ImageDecoder {
   pub async fn is_type_supported(mime_type: String) -> bool {
     parse_mime_type(&mime_type).is_ok()
   }
+
+  /// Decode a batch of independent images on a pool of threads (extension)
+  ///
+  /// Each thread keeps one decoder per image format and reuses it for every
+  /// image of that format it picks up, so sprite sheets, thumbnails and burst
+  /// sequences skip the per-ImageDecoder setup. Results are in input order.
+  /// An image that fails to decode reports an `error` without failing the
+  /// batch. Animated images yield their first frame.
+  ///
+  /// ```javascript
+  /// const results = await ImageDecoder.decodeAll(jpegs, { type: 'image/jpeg', concurrency: 4 });
+  /// const frames = results.map((result) => result.image);
+  /// ```
+  #[napi]
+  pub async fn decode_all(
+    buffers: Vec<Uint8Array>,
+    options: Option<ImageDecodeAllOptions>,
+  ) -> Result<Vec<ImageDecodeAllResult>> {
+    let options = options.unwrap_or_default();
+    if options.desired_width.is_some() != options.desired_height.is_some() {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "Both desiredWidth and desiredHeight must be specified, or neither",
+      ));
+    }
+    if options.concurrency == Some(0) {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "concurrency must be greater than 0",
+      ));
+    }
+    let codec_id = options
+      .mime_type
+      .as_deref()
+      .map(parse_mime_type)
+      .transpose()?;
+
+    spawn_blocking(move || {
+      let inputs: Vec<&[u8]> = buffers.iter().map(|buffer| buffer.as_ref()).collect();
+      decode_batch(&inputs, codec_id, &options)
+        .into_iter()
+        .map(|result| match result {
+          Ok(image) => ImageDecodeAllResult {
+            image: Some(image),
+            error: None,
+          },
+          Err(e) => ImageDecodeAllResult {
+            image: None,
+            error: Some(e.reason.clone()),
+          },
+        })
+        .collect()
+    })
+    .await
+    .map_err(|join_error| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Decode task failed: {}", join_error),
+      )
+    })
+  }
+}
+
+/// Decode `inputs` on up to `options.concurrency` threads, keeping input order
+fn decode_batch(
+  inputs: &[&[u8]],
+  codec_id: Option<AVCodecID>,
+  options: &ImageDecodeAllOptions,
+) -> Vec<Result<VideoFrame>> {
+  let concurrency = options
+    .concurrency
+    .map(|n| n as usize)
+    .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+    .clamp(1, inputs.len().max(1));
+  let next = AtomicUsize::new(0);
+
+  let mut decoded: Vec<(usize, Result<VideoFrame>)> = std::thread::scope(|scope| {
+    let workers: Vec<_> = (0..concurrency)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut decoders = HashMap::new();
+          let mut results = Vec::new();
+          loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(data) = inputs.get(index) else {
+              break;
+            };
+            results.push((
+              index,
+              decode_batch_image(&mut decoders, data, codec_id, options),
+            ));
+          }
+          results
+        })
+      })
+      .collect();
+    workers
+      .into_iter()
+      .flat_map(|worker| worker.join().unwrap_or_default())
+      .collect()
+  });
+  decoded.sort_by_key(|(index, _)| *index);
+
+  // A worker that panicked leaves holes; report them per image
+  let mut decoded = decoded.into_iter().peekable();
+  (0..inputs.len())
+    .map(|index| match decoded.next_if(|(i, _)| *i == index) {
+      Some((_, result)) => result,
+      None => Err(Error::new(Status::GenericFailure, "Decode task failed")),
+    })
+    .collect()
+}
+
+/// Decode the first frame of one image of a decodeAll() batch
+///
+/// `decoders` holds the calling thread's decoder per format; a decoder that
+/// failed is dropped so the next image of that format starts from a fresh one.
+fn decode_batch_image(
+  decoders: &mut HashMap<AVCodecID, CodecContext>,
+  data: &[u8],
+  codec_id: Option<AVCodecID>,
+  options: &ImageDecodeAllOptions,
+) -> Result<VideoFrame> {
+  let codec_id = codec_id
+    .or_else(|| detect_image_type(data))
+    .ok_or_else(|| Error::new(Status::GenericFailure, "Unrecognized image format"))?;
+  let context = match decoders.entry(codec_id) {
+    Entry::Occupied(entry) => entry.into_mut(),
+    Entry::Vacant(entry) => entry.insert(open_image_decoder(codec_id)?),
+  };
+
+  let decoded = decode_image_data(context, data);
+  if decoded.is_ok() {
+    // decode_image_data() drains the decoder; make it accept the next image
+    context.flush();
+  } else {
+    decoders.remove(&codec_id);
+  }
+  let frame = decoded?
+    .into_iter()
+    .next()
+    .ok_or_else(|| Error::new(Status::GenericFailure, "No frames decoded from image"))?;
+
+  let frame = match (options.desired_width, options.desired_height) {
+    (Some(dw), Some(dh)) => scale_image_frame(&frame, dw, dh)?,
+    _ => frame,
+  };
+  let frame = match options.preferred_format.map(|format| format.to_av_format()) {
+    Some(format) if frame.format() != format => convert_image_frame(&frame, format)?,
+    _ => frame,
+  };
+  let pts = frame.pts();
+  let extract_color_space =
+    options.color_space_conversion.unwrap_or_default() == ColorSpaceConversion::Default;
+  Ok(VideoFrame::from_internal_arc_with_color_space(
+    frame.into_shared(),
+    pts,
+    None,
+    extract_color_space,
+  ))
+}
+
+/// Identify an image format from its leading bytes
+fn detect_image_type(data: &[u8]) -> Option<AVCodecID> {
+  if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+    Some(AVCodecID::Mjpeg)
+  } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+    Some(AVCodecID::Png)
+  } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+    Some(AVCodecID::Gif)
+  } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+    Some(AVCodecID::Webp)
+  } else if data.starts_with(b"BM") {
+    Some(AVCodecID::Bmp)
+  } else {
+    None
+  }
 }
 
 /// Pre-parse image and cache decoded frames
@@ -966,6 +1320,7 @@ fn pre_parse_and_cache_frames(inner: &Arc<Mutex<ImageDecoderInner>>) -> Result<(
     low_latency: false,
     width: None,
     height: None,
+    max_pixels: None,
   };
 
   context.configure_decoder(&decoder_config).map_err(|e| {
@@ -1004,6 +1359,9 @@ fn pre_parse_and_cache_frames(inner: &Arc<Mutex<ImageDecoderInner>>) -> Result<(
     }
   }
 
+  // Scaling drops frame side data, so record the ICC profile first
+  record_icc_profile(&inner_guard.tracks, &frames);
+
   // Apply desiredWidth/desiredHeight scaling if both are specified
   let desired_width = inner_guard.desired_width;
   let desired_height = inner_guard.desired_height;
@@ -1065,6 +1423,236 @@ fn pre_parse_and_cache_frames(inner: &Arc<Mutex<ImageDecoderInner>>) -> Result<(
   Ok(())
 }
 
+/// Store the ICC profile of the first decoded frame on the primary track
+fn record_icc_profile(tracks: &ImageTrackList, frames: &[Frame]) {
+  if let Some(icc_profile) = frames.first().and_then(|f| f.icc_profile())
+    && let Ok(mut track_inner) = tracks.inner.lock()
+    && let Some(track) = track_inner.tracks.get_mut(0)
+  {
+    track.icc_profile = Some(icc_profile.to_vec());
+  }
+}
+
+/// Add bytes received from a stream or appendData()
+///
+/// Static images have a single frame, so their track is complete as soon as
+/// the header has arrived and `tracks.ready` resolves early. Animated images
+/// stay unready until the frame count is known from the full data.
+fn append_image_data(inner: &mut ImageDecoderInner, bytes: &[u8]) {
+  match &mut inner.data {
+    ImageDecoderData::Vec(data) => data.extend_from_slice(bytes),
+    _ => inner.data = ImageDecoderData::Vec(bytes.to_vec()),
+  }
+
+  if !inner.tracks.ready.load(Ordering::Acquire)
+    && let (Some(codec_id), ImageDecoderData::Vec(data)) = (inner.codec_id, &inner.data)
+    && static_image_header_available(codec_id, data)
+  {
+    inner.tracks.ready.store(true, Ordering::Release);
+    inner.tracks.ready_notify.notify_waiters();
+  }
+}
+
+/// Whether `data` holds the header (including dimensions) of a static image
+fn static_image_header_available(codec_id: AVCodecID, data: &[u8]) -> bool {
+  match codec_id {
+    // Signature, IHDR length/type and the 13-byte IHDR payload
+    AVCodecID::Png => data.len() >= 8 + 8 + 13 && data.starts_with(b"\x89PNG\r\n\x1a\n"),
+    // File header and the DIB header up to width/height
+    AVCodecID::Bmp => data.len() >= 14 + 12 && data.starts_with(b"BM"),
+    AVCodecID::Mjpeg => jpeg_frame_header_available(data),
+    _ => false,
+  }
+}
+
+/// Whether a JPEG start-of-frame segment has arrived in full
+fn jpeg_frame_header_available(data: &[u8]) -> bool {
+  if !data.starts_with(&[0xFF, 0xD8]) {
+    return false;
+  }
+
+  let mut pos = 2;
+  while pos + 1 < data.len() {
+    if data[pos] != 0xFF {
+      return false;
+    }
+    let marker = data[pos + 1];
+    match marker {
+      // Fill bytes before a marker
+      0xFF => pos += 1,
+      // Standalone markers without a length
+      0x01 | 0xD0..=0xD7 => pos += 2,
+      // Start of scan before any SOF: not a valid JPEG header
+      0xDA => return false,
+      _ => {
+        let Some(length) = data.get(pos + 2..pos + 4) else {
+          return false;
+        };
+        let segment_end = pos + 2 + u16::from_be_bytes([length[0], length[1]]) as usize;
+        // SOF0-SOF15, excluding DHT (C4), JPG (C8) and DAC (CC)
+        if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+          return data.len() >= segment_end;
+        }
+        pos = segment_end;
+      }
+    }
+  }
+  false
+}
+
+/// Decode the first frame of an incomplete static image
+///
+/// Returns None when the data is already complete (the regular path applies),
+/// the header has not arrived, or FFmpeg cannot produce a frame from the
+/// bytes so far. Progressive JPEG and interlaced PNG yield a full-size
+/// picture of lower quality; other images are decoded as far as they go.
+fn decode_partial_image(
+  inner: &Arc<Mutex<ImageDecoderInner>>,
+  preferred_format: Option<VideoPixelFormat>,
+) -> Result<Option<ImageDecodeResult>> {
+  let inner = inner
+    .lock()
+    .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+  if inner.closed {
+    return Err(invalid_state_error("ImageDecoder is closed"));
+  }
+  if inner.complete.load(Ordering::Acquire) {
+    return Ok(None);
+  }
+  let (Some(codec_id), ImageDecoderData::Vec(data)) = (inner.codec_id, &inner.data) else {
+    return Ok(None);
+  };
+  if !static_image_header_available(codec_id, data) {
+    return Ok(None);
+  }
+
+  let Ok(mut context) = open_image_decoder(codec_id) else {
+    return Ok(None);
+  };
+  let Some(frame) = decode_image_data(&mut context, data)
+    .ok()
+    .and_then(|frames| frames.into_iter().next())
+  else {
+    return Ok(None);
+  };
+
+  let frame = match (inner.desired_width, inner.desired_height) {
+    (Some(dw), Some(dh)) => scale_image_frame(&frame, dw, dh)?,
+    _ => frame,
+  };
+  let frame = match preferred_format.map(|format| format.to_av_format()) {
+    Some(format) if frame.format() != format => convert_image_frame(&frame, format)?,
+    _ => frame,
+  };
+  let pts = frame.pts();
+  let extract_color_space = inner.color_space_conversion == ColorSpaceConversion::Default;
+  let image = VideoFrame::from_internal_arc_with_color_space(
+    frame.into_shared(),
+    pts,
+    None,
+    extract_color_space,
+  );
+
+  Ok(Some(ImageDecodeResult {
+    image,
+    complete: false,
+  }))
+}
+
+/// Create and open a decoder for an image codec
+fn open_image_decoder(codec_id: AVCodecID) -> Result<CodecContext> {
+  let mut context = CodecContext::new_decoder(codec_id).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to create decoder: {}", e),
+    )
+  })?;
+
+  let decoder_config = DecoderConfig {
+    codec_id,
+    thread_count: 0,
+    extradata: None,
+    low_latency: false,
+    width: None,
+    height: None,
+    max_pixels: None,
+  };
+
+  context.configure_decoder(&decoder_config).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to configure decoder: {}", e),
+    )
+  })?;
+
+  context.open().map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to open decoder: {}", e),
+    )
+  })?;
+
+  Ok(context)
+}
+
+/// Scale a decoded frame to desiredWidth x desiredHeight
+fn scale_image_frame(frame: &Frame, width: u32, height: u32) -> Result<Frame> {
+  let scaler = Scaler::new(
+    frame.width(),
+    frame.height(),
+    frame.format(),
+    width,
+    height,
+    frame.format(),
+    ScaleAlgorithm::Lanczos,
+  )
+  .map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to create scaler: {}", e),
+    )
+  })?;
+
+  scaler.scale_alloc(frame).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to scale frame: {}", e),
+    )
+  })
+}
+
+/// Convert a decoded frame to the `preferredFormat` given to decode()
+///
+/// Uses the bilinear conversion VideoEncoder applies to input in another format,
+/// so encoding the converted frame produces the same chunks as encoding the original.
+fn convert_image_frame(frame: &Frame, format: AVPixelFormat) -> Result<Frame> {
+  let scaler = Scaler::new(
+    frame.width(),
+    frame.height(),
+    frame.format(),
+    frame.width(),
+    frame.height(),
+    format,
+    ScaleAlgorithm::Bilinear,
+  )
+  .map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to create scaler: {}", e),
+    )
+  })?;
+
+  let mut converted = scaler.scale_alloc(frame).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to convert frame: {}", e),
+    )
+  })?;
+  converted.set_pts(frame.pts());
+  Ok(converted)
+}
+
 /// Parse MIME type to FFmpeg codec ID
 fn parse_mime_type(mime_type: &str) -> Result<AVCodecID> {
   let mime_lower = mime_type.to_lowercase();
@@ -1126,3 +1714,66 @@ fn decode_image_data(context: &mut CodecContext, data: &[u8]) -> Result<Vec<crat
 
   Ok(all_frames)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn jpeg_header() -> Vec<u8> {
+    let mut data = vec![0xFF, 0xD8];
+    // APP0 (JFIF) segment
+    data.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x10]);
+    data.extend_from_slice(b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+    // SOF2 (progressive): 8-bit, 240x320, 3 components
+    data.extend_from_slice(&[0xFF, 0xC2, 0x00, 0x11, 0x08, 0x00, 0xF0, 0x01, 0x40, 0x03]);
+    data.extend_from_slice(&[1, 0x22, 0, 2, 0x11, 1, 3, 0x11, 1]);
+    data
+  }
+
+  #[test]
+  fn test_jpeg_header_needs_complete_sof() {
+    let data = jpeg_header();
+    assert!(jpeg_frame_header_available(&data));
+    assert!(!jpeg_frame_header_available(&data[..data.len() - 1]));
+    assert!(!jpeg_frame_header_available(&data[..10]));
+    assert!(!jpeg_frame_header_available(b"GIF89a"));
+  }
+
+  #[test]
+  fn test_jpeg_header_skips_dht() {
+    let mut data = vec![0xFF, 0xD8, 0xFF, 0xC4, 0x00, 0x03, 0x00];
+    assert!(!jpeg_frame_header_available(&data));
+    data.extend_from_slice(&jpeg_header()[2..]);
+    assert!(jpeg_frame_header_available(&data));
+  }
+
+  #[test]
+  fn test_detect_image_type_from_signature() {
+    assert_eq!(detect_image_type(&jpeg_header()), Some(AVCodecID::Mjpeg));
+    assert_eq!(
+      detect_image_type(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR"),
+      Some(AVCodecID::Png)
+    );
+    assert_eq!(detect_image_type(b"GIF89a\x01\x00"), Some(AVCodecID::Gif));
+    assert_eq!(
+      detect_image_type(b"RIFF\x24\0\0\0WEBPVP8 "),
+      Some(AVCodecID::Webp)
+    );
+    assert_eq!(detect_image_type(b"RIFF\x24\0\0\0WAVEfmt "), None);
+    assert_eq!(detect_image_type(b"\0\0\0\x1cftypavif"), None);
+  }
+
+  #[test]
+  fn test_static_header_only_for_static_formats() {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend_from_slice(&[0, 0, 0, 13]);
+    png.extend_from_slice(b"IHDR");
+    assert!(!static_image_header_available(AVCodecID::Png, &png));
+    png.extend_from_slice(&[0; 13]);
+    assert!(static_image_header_available(AVCodecID::Png, &png));
+    assert!(!static_image_header_available(
+      AVCodecID::Gif,
+      b"GIF89a\x01\x00\x01\x00\x00\x00\x00"
+    ));
+  }
+}
@@ -0,0 +1,184 @@
+//! Key Frame Packets - Sync-sample iteration for thumbnail strips
+//!
+//! `keyFramePackets()` walks a video track's key frames by seeking from one
+//! sync sample to the next on a second demuxer context over the same source,
+//! so the delta packets in between are never read and the demuxer's own read
+//! position is untouched. With `intervalUs` each step jumps straight to the
+//! first key frame at or after the next multiple of the interval, which is how
+//! a filmstrip of a long file reads only the frames it shows.
+
+use std::sync::{Arc, Mutex};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::codec::demuxer::DemuxerContext;
+use crate::ffi::types::AV_NOPTS_VALUE;
+use crate::webcodecs::demuxer_base::{DemuxerSource, convert_timestamp, video_chunk_from_packet};
+use crate::webcodecs::encoded_video_chunk::EncodedVideoChunk;
+
+/// Options for keyFramePackets()
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct KeyFramePacketsOptions {
+  /// Timestamp to start from in microseconds (default: 0)
+  pub start_us: Option<i64>,
+  /// Minimum distance between yielded key frames in microseconds (default: every key frame)
+  ///
+  /// Each key frame yielded is the first one at or after `startUs + n * intervalUs`.
+  pub interval_us: Option<i64>,
+}
+
+/// Seek position of a key frame iteration
+struct KeyFrameCursor {
+  source: DemuxerSource,
+  /// Opened on the first step, on the blocking thread
+  demuxer: Option<DemuxerContext>,
+  track_index: i32,
+  time_base: Option<(i32, i32)>,
+  /// Earliest presentation time of the next key frame, in microseconds
+  next_target_us: i64,
+  interval_us: i64,
+  done: bool,
+}
+
+impl KeyFrameCursor {
+  fn next_key_frame(&mut self) -> Result<Option<EncodedVideoChunk>> {
+    if self.done {
+      return Ok(None);
+    }
+
+    let demuxer = match self.demuxer.as_mut() {
+      Some(demuxer) => demuxer,
+      None => {
+        let demuxer = self.source.open()?;
+        self.time_base = demuxer
+          .get_stream(self.track_index)
+          .map(|stream| stream.time_base);
+        self.demuxer.insert(demuxer)
+      }
+    };
+
+    // A forward seek lands on the first sync sample at or after the target;
+    // failing means there is no key frame left
+    let target = us_to_stream_time(self.next_target_us, self.time_base);
+    if demuxer.seek(self.track_index, target, false).is_err() {
+      self.done = true;
+      return Ok(None);
+    }
+
+    loop {
+      match demuxer.read_packet() {
+        Ok(Some((packet, stream_index))) => {
+          if stream_index != self.track_index || !packet.is_key() {
+            continue;
+          }
+          let ts = if packet.pts() != AV_NOPTS_VALUE {
+            packet.pts()
+          } else {
+            packet.dts()
+          };
+          let ts_us = convert_timestamp(ts, self.time_base);
+          // Containers that seek by decode time or to the nearest index entry
+          // can land just before the target
+          if ts == AV_NOPTS_VALUE || ts_us < self.next_target_us {
+            continue;
+          }
+          self.next_target_us = ts_us
+            .saturating_add(1)
+            .max(self.next_target_us.saturating_add(self.interval_us));
+          return video_chunk_from_packet(packet, self.time_base).map(Some);
+        }
+        Ok(None) => {
+          self.done = true;
+          return Ok(None);
+        }
+        Err(e) => {
+          self.done = true;
+          return Err(Error::new(
+            Status::GenericFailure,
+            format!("Demuxer error: {}", e),
+          ));
+        }
+      }
+    }
+  }
+}
+
+/// Convert microseconds to a stream time base for seeking
+fn us_to_stream_time(timestamp_us: i64, time_base: Option<(i32, i32)>) -> i64 {
+  match time_base {
+    Some((num, den)) if num > 0 && den > 0 => {
+      ((timestamp_us as i128) * (den as i128) / (1_000_000i128 * num as i128)) as i64
+    }
+    _ => timestamp_us,
+  }
+}
+
+/// Key frames of a video track, yielded as EncodedVideoChunks
+///
+/// Returned by a demuxer's `keyFramePackets()`; use with `for await...of`.
+///
+/// ```javascript
+/// const duration = demuxer.duration;
+/// const keyFrames = demuxer.keyFramePackets(trackId, { intervalUs: duration / 20 });
+/// for await (const chunk of keyFrames) {
+///   thumbnailDecoder.decode(chunk);
+/// }
+/// ```
+#[napi(async_iterator)]
+pub struct KeyFramePacketIterator {
+  inner: Arc<Mutex<KeyFrameCursor>>,
+}
+
+impl AsyncGenerator for KeyFramePacketIterator {
+  type Yield = EncodedVideoChunk;
+  type Next = ();
+  type Return = ();
+
+  fn next(
+    &mut self,
+    _value: Option<Self::Next>,
+  ) -> impl Future<Output = Result<Option<Self::Yield>>> + Send + 'static {
+    let inner = self.inner.clone();
+
+    async move {
+      tokio::task::spawn_blocking(move || {
+        let mut guard = inner
+          .lock()
+          .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+        guard.next_key_frame()
+      })
+      .await
+      .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+    }
+  }
+}
+
+impl KeyFramePacketIterator {
+  pub(crate) fn new(
+    source: DemuxerSource,
+    track_index: i32,
+    options: KeyFramePacketsOptions,
+  ) -> Result<Self> {
+    let interval_us = options.interval_us.unwrap_or(0);
+    if interval_us < 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "intervalUs must not be negative",
+      ));
+    }
+
+    Ok(Self {
+      inner: Arc::new(Mutex::new(KeyFrameCursor {
+        source,
+        demuxer: None,
+        track_index,
+        time_base: None,
+        next_target_us: options.start_us.unwrap_or(0).max(0),
+        interval_us,
+        done: false,
+      })),
+    })
+  }
+}
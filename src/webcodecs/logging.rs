@@ -0,0 +1,294 @@
+//! Runtime logging control
+//!
+//! Messages from this crate (`webcodecs` target) and from FFmpeg (`ffmpeg`
+//! target) go through `tracing`. `WEBCODECS_LOG` (e.g.
+//! `ffmpeg=debug,webcodecs=info`) sets the per-target filter when the module
+//! loads; `setLogLevel()` changes it afterwards through a reload handle, so a
+//! long-running process can raise verbosity while debugging and lower it
+//! again without restarting.
+//!
+//! FFmpeg drops messages above its own av_log level before they reach the
+//! tracing bridge, so changing the filter also raises that level when the
+//! `ffmpeg` target asks for debug or trace output. It never goes below info,
+//! which codec error reporting relies on.
+//!
+//! `setLogCallback()` additionally forwards every message that passes the
+//! filter to a JavaScript function, from every codec in the process.
+
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::{Registry, reload};
+
+use crate::ffi;
+use crate::webcodecs::error::throw_type_error_unit;
+
+/// Target that matches every message without a more specific setting
+const DEFAULT_TARGET: &str = "*";
+
+/// Log verbosity for a tracing target
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+  /// No messages
+  #[napi(value = "off")]
+  Off,
+  /// Errors only
+  #[napi(value = "error")]
+  Error,
+  /// Warnings and errors
+  #[napi(value = "warn")]
+  Warn,
+  /// Informational messages and above
+  #[napi(value = "info")]
+  Info,
+  /// Debug messages and above
+  #[napi(value = "debug")]
+  Debug,
+  /// Every message
+  #[napi(value = "trace")]
+  Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+  fn from(level: LogLevel) -> Self {
+    match level {
+      LogLevel::Off => LevelFilter::OFF,
+      LogLevel::Error => LevelFilter::ERROR,
+      LogLevel::Warn => LevelFilter::WARN,
+      LogLevel::Info => LevelFilter::INFO,
+      LogLevel::Debug => LevelFilter::DEBUG,
+      LogLevel::Trace => LevelFilter::TRACE,
+    }
+  }
+}
+
+impl From<Level> for LogLevel {
+  fn from(level: Level) -> Self {
+    match level {
+      Level::ERROR => LogLevel::Error,
+      Level::WARN => LogLevel::Warn,
+      Level::INFO => LogLevel::Info,
+      Level::DEBUG => LogLevel::Debug,
+      Level::TRACE => LogLevel::Trace,
+    }
+  }
+}
+
+/// Log message delivered to the setLogCallback() function
+#[napi(object)]
+pub struct LogMessage {
+  /// Message level
+  pub level: LogLevel,
+  /// Tracing target, e.g. "ffmpeg" or "webcodecs"
+  pub target: String,
+  /// Message text, followed by any structured fields as `key=value`
+  pub message: String,
+}
+
+type LogCallback =
+  ThreadsafeFunction<LogMessage, UnknownReturnValue, LogMessage, Status, false, true>;
+
+/// Reload handle of the filter installed at module init
+static FILTER: OnceLock<reload::Handle<Targets, Registry>> = OnceLock::new();
+
+/// Function registered with setLogCallback()
+static CALLBACK: RwLock<Option<Arc<LogCallback>>> = RwLock::new(None);
+
+/// Wrap the initial filter so setLogLevel() can replace it later
+pub(crate) fn reloadable_filter(initial: Targets) -> reload::Layer<Targets, Registry> {
+  sync_ffmpeg_level(&initial);
+  let (layer, handle) = reload::Layer::new(initial);
+  let _ = FILTER.set(handle);
+  layer
+}
+
+/// Set the level of a tracing target at runtime
+///
+/// `target` is a target prefix such as "ffmpeg" or "webcodecs", or "*" for
+/// the default level of targets without their own setting. Raising "ffmpeg"
+/// to debug or trace also raises FFmpeg's av_log level so those messages are
+/// produced at all.
+#[napi]
+pub fn set_log_level(env: Env, target: String, level: LogLevel) -> Result<()> {
+  let target = target.trim();
+  if target.is_empty() {
+    return throw_type_error_unit(&env, "target must be a target name or \"*\"");
+  }
+  let handle = filter_handle()?;
+  handle
+    .modify(|targets| {
+      let current = std::mem::take(targets);
+      *targets = if target == DEFAULT_TARGET {
+        current.with_default(level)
+      } else {
+        current.with_target(target, level)
+      };
+      sync_ffmpeg_level(targets);
+    })
+    .map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to update log filter: {}", e),
+      )
+    })
+}
+
+/// Get the effective level of a tracing target (default: "*")
+#[napi]
+pub fn get_log_level(target: Option<String>) -> Result<LogLevel> {
+  let target = target.as_deref().unwrap_or(DEFAULT_TARGET);
+  let handle = filter_handle()?;
+  handle
+    .with_current(|targets| effective_level(targets, target))
+    .map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to read log filter: {}", e),
+      )
+    })
+}
+
+/// Forward every message that passes the filter to `callback` (null to stop)
+///
+/// Messages keep going to stderr as well. The callback runs on the main
+/// thread after the message was logged, so it cannot change the outcome of
+/// the operation that logged it.
+#[napi(ts_args_type = "callback: ((message: LogMessage) => void) | null")]
+pub fn set_log_callback(callback: Option<Function<LogMessage, UnknownReturnValue>>) -> Result<()> {
+  let callback = match callback {
+    Some(function) => Some(Arc::new(
+      function
+        .build_threadsafe_function()
+        .callee_handled::<false>()
+        .weak::<true>()
+        .build()?,
+    )),
+    None => None,
+  };
+  *CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = callback;
+  Ok(())
+}
+
+fn filter_handle() -> Result<&'static reload::Handle<Targets, Registry>> {
+  FILTER
+    .get()
+    .ok_or_else(|| Error::new(Status::GenericFailure, "Logging is not initialized"))
+}
+
+/// Most verbose level `targets` enables for `target`
+fn effective_level(targets: &Targets, target: &str) -> LogLevel {
+  if target == DEFAULT_TARGET {
+    return match targets.default_level() {
+      Some(LevelFilter::TRACE) => LogLevel::Trace,
+      Some(LevelFilter::DEBUG) => LogLevel::Debug,
+      Some(LevelFilter::INFO) => LogLevel::Info,
+      Some(LevelFilter::WARN) => LogLevel::Warn,
+      Some(LevelFilter::ERROR) => LogLevel::Error,
+      _ => LogLevel::Off,
+    };
+  }
+  [
+    Level::TRACE,
+    Level::DEBUG,
+    Level::INFO,
+    Level::WARN,
+    Level::ERROR,
+  ]
+  .into_iter()
+  .find(|level| targets.would_enable(target, level))
+  .map_or(LogLevel::Off, LogLevel::from)
+}
+
+/// Let FFmpeg produce the messages the `ffmpeg` target enables
+fn sync_ffmpeg_level(targets: &Targets) {
+  // Mirrors the level mapping of the av_log callback in lib.rs
+  let level = match effective_level(targets, "ffmpeg") {
+    LogLevel::Trace => ffi::avutil::log_level::TRACE,
+    LogLevel::Debug => ffi::avutil::log_level::VERBOSE,
+    _ => ffi::avutil::log_level::INFO,
+  };
+  unsafe { ffi::avutil::av_log_set_level(level) };
+}
+
+/// Layer delivering filtered messages to the setLogCallback() function
+pub(crate) struct CallbackLayer;
+
+impl<S: Subscriber> Layer<S> for CallbackLayer {
+  fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+    let Some(callback) = CALLBACK.read().ok().and_then(|c| c.clone()) else {
+      return;
+    };
+    let mut visitor = MessageVisitor::default();
+    event.record(&mut visitor);
+    let metadata = event.metadata();
+    callback.call(
+      LogMessage {
+        level: LogLevel::from(*metadata.level()),
+        target: metadata.target().to_string(),
+        message: visitor.finish(),
+      },
+      ThreadsafeFunctionCallMode::NonBlocking,
+    );
+  }
+}
+
+/// Collects an event's message and structured fields
+#[derive(Default)]
+struct MessageVisitor {
+  message: String,
+  fields: Vec<String>,
+}
+
+impl MessageVisitor {
+  fn finish(mut self) -> String {
+    for field in self.fields {
+      if !self.message.is_empty() {
+        self.message.push(' ');
+      }
+      self.message.push_str(&field);
+    }
+    self.message
+  }
+}
+
+impl Visit for MessageVisitor {
+  fn record_str(&mut self, field: &Field, value: &str) {
+    if field.name() == "message" {
+      self.message = value.to_string();
+    } else {
+      self.fields.push(format!("{}={}", field.name(), value));
+    }
+  }
+
+  fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+    if field.name() == "message" {
+      self.message = format!("{:?}", value);
+    } else {
+      self.fields.push(format!("{}={:?}", field.name(), value));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::str::FromStr;
+
+  #[test]
+  fn effective_level_follows_the_most_specific_target() {
+    let targets = Targets::from_str("info,ffmpeg=debug,webcodecs=off").unwrap();
+    assert_eq!(effective_level(&targets, "ffmpeg"), LogLevel::Debug);
+    assert_eq!(effective_level(&targets, "webcodecs"), LogLevel::Off);
+    assert_eq!(effective_level(&targets, "other"), LogLevel::Info);
+    assert_eq!(effective_level(&targets, DEFAULT_TARGET), LogLevel::Info);
+    assert_eq!(effective_level(&Targets::new(), "ffmpeg"), LogLevel::Off);
+  }
+}
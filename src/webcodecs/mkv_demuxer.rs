@@ -4,14 +4,20 @@
 //! MKV is a flexible container that supports almost any video and audio codec.
 
 use crate::ffi::AVCodecID;
+use crate::webcodecs::audio_decoder::AudioDecoder;
+use crate::webcodecs::buffer_view::BufferView;
 use crate::webcodecs::demuxer_base::{
   AudioOutputCallback, DemuxerAudioDecoderConfig, DemuxerChunk, DemuxerFormat, DemuxerInner,
-  DemuxerTrackInfo, DemuxerVideoDecoderConfig, ErrorCallback, VideoOutputCallback,
+  DemuxerTrackInfo, DemuxerVideoDecoderConfig, ErrorCallback, RawPacket, VideoOutputCallback,
+  analyze_demuxer_track, create_audio_decoder, create_video_decoder, key_frame_packets,
   parse_aac_codec_string, parse_h264_codec_string, parse_hevc_codec_string, parse_vp9_codec_string,
-  with_demuxer_inner, with_demuxer_inner_mut,
+  read_demuxer_packet, with_demuxer_inner, with_demuxer_inner_mut,
 };
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
 use crate::webcodecs::encoded_video_chunk::EncodedVideoChunk;
+use crate::webcodecs::key_frame_packets::{KeyFramePacketIterator, KeyFramePacketsOptions};
+use crate::webcodecs::track_analysis::{TrackAnalysis, TrackAnalysisOptions};
+use crate::webcodecs::video_decoder::VideoDecoder;
 use napi::bindgen_prelude::*;
 use napi::threadsafe_function::UnknownReturnValue;
 use napi_derive::napi;
@@ -57,6 +63,8 @@ pub struct MkvDemuxerInit {
   pub video_output: Option<VideoOutputCallback>,
   pub audio_output: Option<AudioOutputCallback>,
   pub error: ErrorCallback,
+  /// Open truncated/damaged files best-effort (see `MkvDemuxer.truncated`)
+  pub allow_truncated: bool,
 }
 
 impl FromNapiValue for MkvDemuxerInit {
@@ -110,10 +118,17 @@ impl FromNapiValue for MkvDemuxerInit {
       .weak::<true>()
       .build()?;
 
+    let allow_truncated = obj
+      .get_named_property::<Option<bool>>("allowTruncated")
+      .ok()
+      .flatten()
+      .unwrap_or(false);
+
     Ok(MkvDemuxerInit {
       video_output,
       audio_output,
       error,
+      allow_truncated,
     })
   }
 }
@@ -158,12 +173,10 @@ impl AsyncGenerator for MkvDemuxer {
 impl MkvDemuxer {
   #[napi(constructor)]
   pub fn new(init: MkvDemuxerInit) -> Result<Self> {
+    let mut inner = DemuxerInner::new(init.video_output, init.audio_output, init.error);
+    inner.allow_truncated = init.allow_truncated;
     Ok(Self {
-      inner: Arc::new(Mutex::new(DemuxerInner::new(
-        init.video_output,
-        init.audio_output,
-        init.error,
-      ))),
+      inner: Arc::new(Mutex::new(inner)),
     })
   }
 
@@ -183,18 +196,18 @@ impl MkvDemuxer {
 
   /// Load an MKV from a buffer
   ///
-  /// This method uses zero-copy buffer loading - the Uint8Array data is passed
+  /// This method uses zero-copy buffer loading - the viewed bytes of any BufferSource are passed
   /// directly to the demuxer without an intermediate copy.
   #[napi]
-  pub async fn load_buffer(&self, data: Uint8Array) -> Result<()> {
+  pub async fn load_buffer(&self, data: BufferView) -> Result<()> {
     let inner = self.inner.clone();
-    // Zero-copy: pass Uint8Array directly (it implements BufferSource)
+    // Zero-copy: the view shares memory with the JS buffer
 
     tokio::task::spawn_blocking(move || {
       let mut guard = inner
         .lock()
         .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-      guard.load_buffer(data)
+      guard.load_buffer(data.into_inner())
     })
     .await
     .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
@@ -212,6 +225,16 @@ impl MkvDemuxer {
     Ok(guard.get_duration())
   }
 
+  /// Whether the file was found truncated or damaged (requires `allowTruncated`)
+  ///
+  /// When true, `duration` reports the usable duration and demuxing stops
+  /// before the damaged cluster.
+  #[napi(getter)]
+  pub fn truncated(&self) -> Result<bool> {
+    let guard = with_demuxer_inner!(self);
+    Ok(guard.is_truncated())
+  }
+
   #[napi(getter)]
   pub fn video_decoder_config(&self) -> Result<Option<DemuxerVideoDecoderConfig>> {
     let guard = with_demuxer_inner!(self);
@@ -236,6 +259,70 @@ impl MkvDemuxer {
     guard.select_audio_track(track_index)
   }
 
+  #[napi(
+    ts_args_type = "trackId: number, init: { output: (frame: VideoFrame) => void; error: (error: Error) => void } & Partial<VideoDecoderConfig>"
+  )]
+  pub fn create_video_decoder(
+    &self,
+    env: Env,
+    track_id: i32,
+    init: Object,
+  ) -> Result<VideoDecoder> {
+    create_video_decoder(&self.inner, env, track_id, init)
+  }
+
+  #[napi(
+    ts_args_type = "trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>"
+  )]
+  pub fn create_audio_decoder(
+    &self,
+    env: Env,
+    track_id: i32,
+    init: Object,
+  ) -> Result<AudioDecoder> {
+    create_audio_decoder(&self.inner, env, track_id, init)
+  }
+
+  /// Scan a track's packet timestamps and sizes without decoding
+  ///
+  /// Returns frame interval statistics, an interval histogram, bitrate per time
+  /// window and whether the track is VFR. The scan reads a separate view of the
+  /// source, so it does not move this demuxer's read position.
+  #[napi]
+  pub async fn analyze_track(
+    &self,
+    track_id: i32,
+    options: Option<TrackAnalysisOptions>,
+  ) -> Result<TrackAnalysis> {
+    analyze_demuxer_track(&self.inner, track_id, options.unwrap_or_default()).await
+  }
+
+  /// Iterate a video track's key frames, seeking from one to the next
+  ///
+  /// Only sync samples are read, optionally no closer together than
+  /// `intervalUs`. Pair with a `VideoDecoder` configured with
+  /// `keyFramesOnly: true` to build thumbnail strips without decoding the
+  /// frames in between. Uses a separate view of the source, so it does not
+  /// move this demuxer's read position.
+  #[napi]
+  pub fn key_frame_packets(
+    &self,
+    track_id: i32,
+    options: Option<KeyFramePacketsOptions>,
+  ) -> Result<KeyFramePacketIterator> {
+    key_frame_packets(&self.inner, track_id, options)
+  }
+
+  /// Read the next packet of the selected tracks for remuxing without decoding
+  ///
+  /// Returns null at end of stream. The record keeps PTS, DTS, duration and
+  /// flags in the track's time base; pass it to a muxer's `writePacket()`.
+  /// Shares the read position with async iteration.
+  #[napi]
+  pub async fn read_packet(&self) -> Result<Option<RawPacket>> {
+    read_demuxer_packet(&self.inner).await
+  }
+
   #[napi]
   pub fn demux(&self, count: Option<u32>) -> Result<()> {
     let inner = self.inner.clone();
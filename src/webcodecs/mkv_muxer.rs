@@ -5,14 +5,20 @@
 
 use crate::codec::muxer::{ContainerFormat, MuxerOptions};
 use crate::ffi::AVCodecID;
+use crate::webcodecs::bitrate_stats::MuxerBitrateSummary;
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::codec_description::validate_video_description;
 use crate::webcodecs::codec_string::parse_codec_string;
+use crate::webcodecs::demuxer_base::{RawPacket, TrackSideData};
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
 use crate::webcodecs::encoded_video_chunk::EncodedVideoChunk;
+use crate::webcodecs::error::throw_type_error_unit;
 use crate::webcodecs::muxer_base::{
   EncodedAudioChunkMetadataJs, EncodedVideoChunkMetadataJs, GenericAudioTrackConfig,
   GenericVideoTrackConfig, MuxerFormat, MuxerInner, StreamingMuxerOptions, lock_muxer_inner,
-  lock_muxer_inner_mut,
+  lock_muxer_inner_mut, track_sample_aspect_ratio,
 };
+use crate::webcodecs::video_frame::VideoColorSpaceInit;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use std::sync::Mutex;
@@ -93,6 +99,9 @@ impl MuxerFormat for MkvFormat {
 pub struct MkvMuxerOptions {
   /// Enable live streaming mode
   pub live: Option<bool>,
+  /// Omit the "Lavf" writing-application tag, write the bare "Lavf" muxing
+  /// application and deterministic UIDs (default: false)
+  pub omit_encoder_tags: Option<bool>,
   /// Enable streaming output mode
   pub streaming: Option<StreamingMuxerOptions>,
 }
@@ -110,10 +119,18 @@ pub struct MkvVideoTrackConfig {
   pub width: u32,
   /// Video height in pixels
   pub height: u32,
-  /// Frame rate (frames per second)
+  /// Frame rate (frames per second); 29.97, 23.976 and 59.94 are taken as N*1000/1001
   pub framerate: Option<f64>,
   /// Codec-specific description data
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
+  /// Color space to signal in the container (MP4 colr/nclx, Matroska Colour)
+  pub color_space: Option<VideoColorSpaceInit>,
+  /// Display width in pixels (defaults to width); written as the track's presentation width
+  pub display_width: Option<u32>,
+  /// Display height in pixels (defaults to height); written as the track's presentation height
+  pub display_height: Option<u32>,
+  /// Stream-level side data, e.g. `DemuxerTrackInfo.sideData` when remuxing
+  pub side_data: Option<Vec<TrackSideData>>,
 }
 
 /// Audio track configuration for MKV muxer
@@ -126,7 +143,7 @@ pub struct MkvAudioTrackConfig {
   /// Number of audio channels
   pub number_of_channels: u32,
   /// Codec-specific description data
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
 }
 
 // ============================================================================
@@ -166,6 +183,7 @@ impl MkvMuxer {
     // Create muxer options with live streaming support
     let muxer_options = MuxerOptions {
       live: opts.live.unwrap_or(false),
+      omit_encoder_tags: opts.omit_encoder_tags.unwrap_or(false),
       ..Default::default()
     };
 
@@ -186,12 +204,28 @@ impl MkvMuxer {
   ///
   /// MKV supports H.264, H.265, VP8, VP9, AV1, and many other video codecs.
   #[napi]
-  pub fn add_video_track(&self, config: MkvVideoTrackConfig) -> Result<()> {
+  pub fn add_video_track(&self, env: Env, config: MkvVideoTrackConfig) -> Result<()> {
     lock_muxer_inner_mut!(self => _guard, inner);
 
     // Parse codec (MKV accepts most codecs)
     let codec_id = MkvFormat::parse_video_codec(&config.codec)?;
 
+    if let Some(description) = config.description.as_deref()
+      && let Err(msg) = validate_video_description(&config.codec, codec_id, description)
+    {
+      return throw_type_error_unit(&env, &msg);
+    }
+
+    let sample_aspect_ratio = match track_sample_aspect_ratio(
+      config.width,
+      config.height,
+      config.display_width,
+      config.display_height,
+    ) {
+      Ok(sar) => sar,
+      Err(msg) => return throw_type_error_unit(&env, &msg),
+    };
+
     let generic_config = GenericVideoTrackConfig {
       codec: config.codec,
       codec_id,
@@ -200,11 +234,37 @@ impl MkvMuxer {
       framerate: config.framerate.unwrap_or(30.0),
       extradata: config.description.as_ref().map(|d| d.to_vec()),
       has_alpha: false, // TODO: Add alpha support for MKV if needed
+      color_space: config.color_space,
+      icc_profile: None,
+      sample_aspect_ratio,
+      // Matroska timestamps are always in milliseconds
+      timescale: None,
+      side_data: config
+        .side_data
+        .as_ref()
+        .map(|side_data| side_data.iter().map(TrackSideData::to_stream).collect())
+        .unwrap_or_default(),
     };
 
     inner.add_video_track(generic_config)
   }
 
+  /// Replace the video track description before the first chunk is added
+  ///
+  /// For encoders that only deliver the description with the first keyframe.
+  #[napi]
+  pub fn update_video_description(&self, env: Env, description: BufferView) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+
+    if let Some(track) = inner.video_track_info.as_ref()
+      && let Err(msg) = validate_video_description(&track.codec, track.codec_id, &description)
+    {
+      return throw_type_error_unit(&env, &msg);
+    }
+
+    inner.update_video_description(&description)
+  }
+
   /// Add an audio track to the muxer
   ///
   /// MKV supports AAC, Opus, Vorbis, FLAC, MP3, AC3, and many other audio codecs.
@@ -222,6 +282,8 @@ impl MkvMuxer {
       channels: config.number_of_channels,
       frame_size: MkvFormat::get_audio_frame_size(codec_id),
       extradata: config.description.as_ref().map(|d| d.to_vec()),
+      encoder_delay: None,
+      padding: None,
     };
 
     inner.add_audio_track(generic_config)
@@ -249,6 +311,16 @@ impl MkvMuxer {
     inner.add_audio_chunk(chunk, metadata.as_ref())
   }
 
+  /// Write a raw packet with explicit DTS, bypassing EncodedVideoChunk/EncodedAudioChunk
+  ///
+  /// Use with a demuxer's `readPacket()` to remux without decoding: PTS, DTS,
+  /// duration and flags are kept as given (rescaled to the track's time base).
+  #[napi]
+  pub fn write_packet(&self, packet: RawPacket) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    inner.write_raw_packet(&packet)
+  }
+
   /// Flush any buffered data
   #[napi]
   pub fn flush(&self) -> Result<()> {
@@ -264,6 +336,16 @@ impl MkvMuxer {
     Ok(Uint8Array::new(data))
   }
 
+  /// Achieved bitrate per track over the chunks added so far
+  ///
+  /// Call after `finalize()` for the final numbers. The peak is the largest
+  /// bit count over any 1-second window of chunk timestamps.
+  #[napi]
+  pub fn bitrate_summary(&self) -> Result<MuxerBitrateSummary> {
+    lock_muxer_inner!(self => _guard, inner);
+    Ok(inner.bitrate_summary())
+  }
+
   /// Read available data from streaming buffer (streaming mode only)
   ///
   /// Returns available data, or null if no data is ready yet.
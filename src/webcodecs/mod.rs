@@ -3,35 +3,73 @@
 //! Provides spec-compliant WebCodecs API surface for Node.js via NAPI-RS.
 //! See: https://developer.mozilla.org/en-US/docs/Web/API/WebCodecs_API
 
+pub(crate) mod access_unit;
+pub(crate) mod audio_bitstream;
 mod audio_data;
 mod audio_decoder;
 mod audio_encoder;
+mod audio_extract;
+mod audio_file_demuxer;
+mod audio_resampler;
+pub(crate) mod bitrate_stats;
+mod bitstream_analysis;
+pub(crate) mod buffer_view;
+mod build_info;
+mod cfr_regulator;
+pub(crate) mod codec_capabilities;
+pub(crate) mod codec_description;
 pub(crate) mod codec_pressure;
 pub mod codec_string;
+pub(crate) mod command_timing;
+pub(crate) mod decode_limits;
 pub mod demuxer_base;
+mod encode_estimate;
 mod encoded_audio_chunk;
 mod encoded_video_chunk;
+pub(crate) mod encoder_defaults;
+pub(crate) mod encoder_info;
+pub(crate) mod encoder_probe;
 pub mod error;
+pub(crate) mod features;
+pub(crate) mod frame_budget;
 mod hardware;
 pub(crate) mod hw_fallback;
 mod image_decoder;
+pub(crate) mod key_frame_packets;
+pub(crate) mod logging;
 mod mkv_demuxer;
 mod mkv_muxer;
 mod mp4_demuxer;
 mod mp4_muxer;
 pub mod muxer_base;
+pub(crate) mod output_timeline;
+pub(crate) mod parameter_sets;
+pub(crate) mod plain_bytes;
 mod promise_reject;
+pub(crate) mod quality_metrics;
+pub(crate) mod rate_control;
+mod raw_video;
+pub(crate) mod reference_control;
+pub(crate) mod scene_cut;
+mod segmented_mp4_muxer;
+pub(crate) mod shutdown;
+pub(crate) mod track_analysis;
+mod track_extract;
 mod video_decoder;
 mod video_encoder;
 mod video_frame;
 mod webm_demuxer;
 mod webm_muxer;
 
+pub use audio_bitstream::{
+  AdtsToRawResult, OpusBandwidth, OpusMode, OpusToc, adts_to_raw, parse_toc, raw_to_adts,
+};
 pub use audio_data::{AudioData, AudioDataCopyToOptions, AudioDataInit, AudioSampleFormat};
 pub use audio_decoder::AudioDecoder;
 pub use audio_encoder::{
   AudioDecoderConfigOutput, AudioEncoder, AudioEncoderEncodeOptions, EncodedAudioChunkMetadata,
 };
+pub use audio_extract::{AudioExtractor, ExtractAudioOptions, extract_audio};
 pub use encoded_audio_chunk::{
   AacBitstreamFormat, AacEncoderConfig, AudioDecoderConfig, AudioDecoderSupport,
   AudioEncoderConfig, AudioEncoderSupport, BitrateMode, EncodedAudioChunk, EncodedAudioChunkInit,
@@ -48,35 +86,72 @@ pub(crate) use encoded_video_chunk::{
   convert_annexb_extradata_to_avcc, convert_annexb_extradata_to_hvcc,
   convert_avcc_extradata_to_annexb, convert_avcc_to_annexb, convert_hvcc_extradata_to_annexb,
   convert_obu_extradata_to_av1c, extract_avcc_from_avcc_packet, extract_hvcc_from_hvcc_packet,
-  is_av1c_extradata, is_avcc_extradata, is_avcc_format, is_hvcc_extradata,
+  extradata_parameter_sets, is_av1c_extradata, is_avcc_extradata, is_avcc_format,
+  is_hvcc_extradata, packet_parameter_sets,
 };
 pub use hardware::{
-  HardwareAccelerator, get_available_hardware_accelerators, get_hardware_accelerators,
-  get_preferred_hardware_accelerator, is_hardware_accelerator_available,
+  HardwareAccelerator, HardwareSessionCount, get_available_hardware_accelerators,
+  get_hardware_accelerators, get_hardware_session_counts, get_preferred_hardware_accelerator,
+  is_hardware_accelerator_available, set_hardware_device_sharing,
 };
 pub use hw_fallback::reset_hardware_fallback_state;
 pub use image_decoder::{
-  ImageDecodeOptions, ImageDecodeResult, ImageDecoder, ImageDecoderInit, ImageTrack, ImageTrackList,
+  ImageDecodeAllOptions, ImageDecodeAllResult, ImageDecodeOptions, ImageDecodeResult, ImageDecoder,
+  ImageDecoderInit, ImageTrack, ImageTrackList,
 };
 pub use mkv_muxer::{MkvAudioTrackConfig, MkvMuxer, MkvMuxerOptions, MkvVideoTrackConfig};
 pub use mp4_muxer::{Mp4AudioTrackConfig, Mp4Muxer, Mp4MuxerOptions, Mp4VideoTrackConfig};
-pub use video_decoder::{VideoDecoder, VideoDecoderSupport};
+pub use segmented_mp4_muxer::{Mp4Segment, SegmentedMp4Muxer, SegmentedMp4MuxerInit};
+pub use video_decoder::{DecodeInputStats, VideoDecoder, VideoDecoderSupport};
 pub use video_encoder::{
-  CodecState, EncodedVideoChunkMetadata, SvcOutputMetadata, VideoDecoderConfigOutput, VideoEncoder,
-  VideoEncoderEncodeOptions, VideoEncoderEncodeOptionsForAv1, VideoEncoderEncodeOptionsForAvc,
+  CodecState, EncodeOutputStats, EncodedVideoChunkMetadata, SvcOutputMetadata,
+  VideoDecoderConfigOutput, VideoEncoder, VideoEncoderEncodeOptions,
+  VideoEncoderEncodeOptionsForAv1, VideoEncoderEncodeOptionsForAvc,
   VideoEncoderEncodeOptionsForHevc, VideoEncoderEncodeOptionsForVp9, VideoEncoderSupport,
 };
 pub use video_frame::{
-  DOMRectReadOnly, VideoColorPrimaries, VideoColorSpace, VideoColorSpaceInit, VideoFrame,
+  DOMRectReadOnly, HdrDynamicMetadata, HdrDynamicMetadataType, VideoChromaLocation,
+  VideoColorPrimaries, VideoColorSpace, VideoColorSpaceInit, VideoDitherMode, VideoFrame,
   VideoFrameBufferInit, VideoFrameCopyToOptions, VideoFrameInit, VideoFrameMetadata,
-  VideoFrameRect, VideoMatrixCoefficients, VideoPixelFormat, VideoTransferCharacteristics,
+  VideoFrameRect, VideoMatrixCoefficients, VideoPixelFormat, VideoScaleAlgorithm,
+  VideoTransferCharacteristics,
+};
+pub use webm_muxer::{
+  WebMAudioTrackConfig, WebMMuxer, WebMMuxerOptions, WebMSubtitleTrackConfig, WebMVideoTrackConfig,
 };
-pub use webm_muxer::{WebMAudioTrackConfig, WebMMuxer, WebMMuxerOptions, WebMVideoTrackConfig};
 // Demuxer types
+pub use access_unit::InputGranularity;
+pub use audio_file_demuxer::{AudioFileDemuxer, AudioFileDemuxerInit};
+pub use audio_resampler::{AudioResampler, AudioResamplerInit};
+pub use bitrate_stats::{BitrateSummary, MuxerBitrateSummary};
+pub use bitstream_analysis::{
+  BitstreamAnalysis, BitstreamFrame, BitstreamFrameType, BitstreamNalFormat, analyze_bitstream,
+};
+pub use build_info::{BuildInfo, LibraryVersion, get_build_info};
+pub use cfr_regulator::{CfrRegulator, CfrRegulatorInit};
+pub use codec_capabilities::{CodecCapabilities, get_codec_capabilities};
+pub use command_timing::CodecQueueStats;
+pub use decode_limits::{DecodeLimits, get_decode_limits, reset_decode_limits, set_decode_limits};
 pub use demuxer_base::{
-  DemuxerAudioDecoderConfig, DemuxerChunk, DemuxerTrackInfo, DemuxerVideoDecoderConfig,
+  DemuxerAudioDecoderConfig, DemuxerChunk, DemuxerTrackInfo, DemuxerVideoDecoderConfig, RawPacket,
+  SubtitleCue, SubtitleKind, TrackSideData, TrackSideDataType,
+};
+pub use encode_estimate::{
+  EncodeEstimate, EncodeEstimateSample, EstimateEncodeOptions, estimate_encode,
+};
+pub use encoder_defaults::{
+  EncoderOptionDefaults, get_default_encoder_options, set_default_encoder_options,
 };
+pub use features::{Features, get_features};
+pub use key_frame_packets::{KeyFramePacketIterator, KeyFramePacketsOptions};
+pub use logging::{LogLevel, LogMessage, get_log_level, set_log_callback, set_log_level};
 pub use mkv_demuxer::{MkvDemuxer, MkvDemuxerInit};
 pub use mp4_demuxer::{Mp4Demuxer, Mp4DemuxerInit};
 pub use muxer_base::StreamingMuxerOptions;
+pub use output_timeline::TimestampMode;
+pub use parameter_sets::{ParameterSetChange, ParameterSetSource};
+pub use rate_control::RateControlStats;
+pub use raw_video::{RawVideoReader, RawVideoReaderInit, RawVideoWriter, RawVideoWriterInit};
+pub use track_analysis::{BitrateWindow, FrameIntervalBucket, TrackAnalysis, TrackAnalysisOptions};
+pub use track_extract::{ExtractTrackFormat, ExtractTrackOptions, extract_track};
 pub use webm_demuxer::{WebMDemuxer, WebMDemuxerInit};
@@ -2,16 +2,29 @@
 //!
 //! Provides a JavaScript-friendly API for demuxing MP4 container files
 //! into encoded video and audio chunks.
+//!
+//! Besides files and buffers, fragmented MP4 can be appended piece by piece
+//! (e.g. LL-HLS parts): tracks load once the init segment is complete and
+//! each fragment's samples are emitted as soon as the fragment arrives.
 
+use crate::codec::demuxer::StreamInfo;
+use crate::codec::io_buffer::StreamInput;
 use crate::ffi::AVCodecID;
+use crate::webcodecs::audio_decoder::AudioDecoder;
+use crate::webcodecs::buffer_view::BufferView;
 use crate::webcodecs::demuxer_base::{
   AudioOutputCallback, DemuxerAudioDecoderConfig, DemuxerChunk, DemuxerFormat, DemuxerInner,
-  DemuxerTrackInfo, DemuxerVideoDecoderConfig, ErrorCallback, VideoOutputCallback,
-  parse_aac_codec_string, parse_h264_codec_string, parse_hevc_codec_string, parse_vp9_codec_string,
-  with_demuxer_inner, with_demuxer_inner_mut,
+  DemuxerState, DemuxerTrackInfo, DemuxerVideoDecoderConfig, ErrorCallback, RawPacket,
+  VideoOutputCallback, analyze_demuxer_track, create_audio_decoder, create_video_decoder,
+  hevc_codec_string_for_tag, key_frame_packets, parse_aac_codec_string, parse_h264_codec_string,
+  parse_hevc_codec_string, parse_vp9_codec_string, read_demuxer_packet, with_demuxer_inner,
+  with_demuxer_inner_mut,
 };
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
 use crate::webcodecs::encoded_video_chunk::EncodedVideoChunk;
+use crate::webcodecs::key_frame_packets::{KeyFramePacketIterator, KeyFramePacketsOptions};
+use crate::webcodecs::track_analysis::{TrackAnalysis, TrackAnalysisOptions};
+use crate::webcodecs::video_decoder::VideoDecoder;
 use napi::bindgen_prelude::*;
 use napi::threadsafe_function::UnknownReturnValue;
 use napi_derive::napi;
@@ -36,6 +49,14 @@ impl DemuxerFormat for Mp4Format {
     }
   }
 
+  fn video_codec_string(stream: &StreamInfo) -> String {
+    match stream.codec_id {
+      // hvc1 and hev1 sample entries need matching codec string prefixes
+      AVCodecID::Hevc => hevc_codec_string_for_tag(stream.codec_tag, stream.extradata.as_deref()),
+      codec_id => Self::codec_id_to_video_string(codec_id, stream.extradata.as_deref()),
+    }
+  }
+
   fn codec_id_to_audio_string(codec_id: AVCodecID, extradata: Option<&[u8]>) -> String {
     match codec_id {
       AVCodecID::Aac => parse_aac_codec_string(extradata),
@@ -57,6 +78,8 @@ pub struct Mp4DemuxerInit {
   pub video_output: Option<VideoOutputCallback>,
   pub audio_output: Option<AudioOutputCallback>,
   pub error: ErrorCallback,
+  /// Read appended input without probing ahead (see `Mp4Demuxer.appendBuffer`)
+  pub low_latency: bool,
 }
 
 impl FromNapiValue for Mp4DemuxerInit {
@@ -110,10 +133,17 @@ impl FromNapiValue for Mp4DemuxerInit {
       .weak::<true>()
       .build()?;
 
+    let low_latency = obj
+      .get_named_property::<Option<bool>>("lowLatency")
+      .ok()
+      .flatten()
+      .unwrap_or(false);
+
     Ok(Mp4DemuxerInit {
       video_output,
       audio_output,
       error,
+      low_latency,
     })
   }
 }
@@ -153,6 +183,11 @@ impl FromNapiValue for Mp4DemuxerInit {
 #[napi(async_iterator)]
 pub struct Mp4Demuxer {
   inner: Arc<Mutex<DemuxerInner<Mp4Format>>>,
+  /// Data passed to appendBuffer()
+  input: Arc<StreamInput>,
+  /// Data appended before the init segment was complete; None once its tracks are loaded
+  init_segment: Mutex<Option<Vec<u8>>>,
+  low_latency: bool,
 }
 
 impl AsyncGenerator for Mp4Demuxer {
@@ -191,6 +226,9 @@ impl Mp4Demuxer {
         init.audio_output,
         init.error,
       ))),
+      input: Arc::new(StreamInput::new()),
+      init_segment: Mutex::new(Some(Vec::new())),
+      low_latency: init.low_latency,
     })
   }
 
@@ -211,23 +249,77 @@ impl Mp4Demuxer {
 
   /// Load an MP4 from a buffer
   ///
-  /// This method uses zero-copy buffer loading - the Uint8Array data is passed
+  /// This method uses zero-copy buffer loading - the viewed bytes of any BufferSource are passed
   /// directly to the demuxer without an intermediate copy.
   #[napi]
-  pub async fn load_buffer(&self, data: Uint8Array) -> Result<()> {
+  pub async fn load_buffer(&self, data: BufferView) -> Result<()> {
     let inner = self.inner.clone();
-    // Zero-copy: pass Uint8Array directly (it implements BufferSource)
+    // Zero-copy: the view shares memory with the JS buffer
 
     tokio::task::spawn_blocking(move || {
       let mut guard = inner
         .lock()
         .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-      guard.load_buffer(data)
+      guard.load_buffer(data.into_inner())
     })
     .await
     .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
   }
 
+  /// Append fragmented MP4 data, e.g. live CMAF/LL-HLS segments
+  ///
+  /// Tracks and decoder configs are available once the init segment (`ftyp`
+  /// and `moov`) has been appended. Reads then emit each fragment's samples
+  /// as soon as its `moof` and `mdat` have arrived; with `lowLatency: true`
+  /// nothing is read ahead, so the first chunk needs only the first fragment.
+  /// Call endOfStream() after the last fragment.
+  ///
+  /// Reads wait for more data instead of ending, and hold the demuxer while
+  /// they wait: keep appending while a read is pending, and avoid other
+  /// demuxer calls until it resolves. Seeking is not supported.
+  #[napi]
+  pub fn append_buffer(&self, data: BufferView) -> Result<()> {
+    let mut init_segment = self
+      .init_segment
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    if self.input.is_ended() {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Cannot append data after endOfStream() or close()",
+      ));
+    }
+
+    let Some(head) = init_segment.as_mut() else {
+      self.input.append(&data);
+      return Ok(());
+    };
+    let mut guard = with_demuxer_inner_mut!(self);
+    if guard.state != DemuxerState::Unloaded {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Demuxer already loaded. Call close() first.",
+      ));
+    }
+    self.input.append(&data);
+    head.extend_from_slice(&data);
+    if let Some(len) = init_segment_len(head) {
+      head.truncate(len);
+      let head = std::mem::take(head);
+      *init_segment = None;
+      guard.load_stream(head, self.input.clone(), c"mp4", self.low_latency)?;
+    }
+    Ok(())
+  }
+
+  /// Signal that no more data will be appended
+  ///
+  /// Reading ends once the appended data has been consumed.
+  #[napi]
+  pub fn end_of_stream(&self) {
+    self.input.end();
+  }
+
   /// Get all tracks
   #[napi(getter)]
   pub fn tracks(&self) -> Result<Vec<DemuxerTrackInfo>> {
@@ -270,6 +362,81 @@ impl Mp4Demuxer {
     guard.select_audio_track(track_index)
   }
 
+  /// Create a VideoDecoder configured for a video track
+  ///
+  /// The track's codec string, dimensions and description are converted for
+  /// VideoDecoder automatically; any VideoDecoderConfig fields in `init`
+  /// (e.g. `hardwareAcceleration`) override them. The track is also selected
+  /// for demuxing.
+  #[napi(
+    ts_args_type = "trackId: number, init: { output: (frame: VideoFrame) => void; error: (error: Error) => void } & Partial<VideoDecoderConfig>"
+  )]
+  pub fn create_video_decoder(
+    &self,
+    env: Env,
+    track_id: i32,
+    init: Object,
+  ) -> Result<VideoDecoder> {
+    create_video_decoder(&self.inner, env, track_id, init)
+  }
+
+  /// Create an AudioDecoder configured for an audio track
+  ///
+  /// The track's codec string, sample rate, channel count and description are
+  /// converted for AudioDecoder automatically; any AudioDecoderConfig fields in
+  /// `init` override them. The track is also selected for demuxing.
+  #[napi(
+    ts_args_type = "trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>"
+  )]
+  pub fn create_audio_decoder(
+    &self,
+    env: Env,
+    track_id: i32,
+    init: Object,
+  ) -> Result<AudioDecoder> {
+    create_audio_decoder(&self.inner, env, track_id, init)
+  }
+
+  /// Scan a track's packet timestamps and sizes without decoding
+  ///
+  /// Returns frame interval statistics, an interval histogram, bitrate per time
+  /// window and whether the track is VFR. The scan reads a separate view of the
+  /// source, so it does not move this demuxer's read position.
+  #[napi]
+  pub async fn analyze_track(
+    &self,
+    track_id: i32,
+    options: Option<TrackAnalysisOptions>,
+  ) -> Result<TrackAnalysis> {
+    analyze_demuxer_track(&self.inner, track_id, options.unwrap_or_default()).await
+  }
+
+  /// Iterate a video track's key frames, seeking from one to the next
+  ///
+  /// Only sync samples are read, optionally no closer together than
+  /// `intervalUs`. Pair with a `VideoDecoder` configured with
+  /// `keyFramesOnly: true` to build thumbnail strips without decoding the
+  /// frames in between. Uses a separate view of the source, so it does not
+  /// move this demuxer's read position.
+  #[napi]
+  pub fn key_frame_packets(
+    &self,
+    track_id: i32,
+    options: Option<KeyFramePacketsOptions>,
+  ) -> Result<KeyFramePacketIterator> {
+    key_frame_packets(&self.inner, track_id, options)
+  }
+
+  /// Read the next packet of the selected tracks for remuxing without decoding
+  ///
+  /// Returns null at end of stream. The record keeps PTS, DTS, duration and
+  /// flags in the track's time base; pass it to a muxer's `writePacket()`.
+  /// Shares the read position with async iteration.
+  #[napi]
+  pub async fn read_packet(&self) -> Result<Option<RawPacket>> {
+    read_demuxer_packet(&self.inner).await
+  }
+
   /// Start demuxing packets
   ///
   /// If count is specified, reads up to that many packets.
@@ -327,6 +494,8 @@ impl Mp4Demuxer {
   /// Close the demuxer and release resources
   #[napi]
   pub fn close(&self) -> Result<()> {
+    // Wake a read waiting for appended data so the demuxer can be locked
+    self.input.close();
     let mut guard = with_demuxer_inner_mut!(self);
     guard.close();
     Ok(())
@@ -340,6 +509,29 @@ impl Mp4Demuxer {
   }
 }
 
+/// Length of the init segment at the start of fragmented MP4 data
+///
+/// Returns the end of the `moov` box once it has been appended completely.
+fn init_segment_len(data: &[u8]) -> Option<usize> {
+  let mut offset = 0usize;
+  while data.len() - offset >= 8 {
+    let header = &data[offset..];
+    let size = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+      // 64-bit size follows the type
+      1 => u64::from_be_bytes(header.get(8..16)?.try_into().ok()?),
+      // Box extends to the end of the file
+      0 => return None,
+      size => size as u64,
+    };
+    let end = offset.checked_add(usize::try_from(size).ok()?.max(8))?;
+    if &header[4..8] == b"moov" {
+      return (end <= data.len()).then_some(end);
+    }
+    offset = end.min(data.len());
+  }
+  None
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -371,4 +563,23 @@ mod tests {
       "mp3"
     );
   }
+
+  fn mp4_box(box_type: &[u8; 4], payload_len: usize) -> Vec<u8> {
+    let mut data = ((payload_len + 8) as u32).to_be_bytes().to_vec();
+    data.extend_from_slice(box_type);
+    data.resize(payload_len + 8, 0);
+    data
+  }
+
+  #[test]
+  fn test_init_segment_ends_after_moov() {
+    let mut data = mp4_box(b"ftyp", 16);
+    data.extend(mp4_box(b"moov", 100));
+    let init_len = data.len();
+    data.extend(mp4_box(b"moof", 40));
+
+    assert_eq!(init_segment_len(&data), Some(init_len));
+    assert_eq!(init_segment_len(&data[..init_len - 1]), None);
+    assert_eq!(init_segment_len(&data[..20]), None);
+  }
 }
@@ -4,17 +4,25 @@
 //! chunks into MP4 container format.
 
 use crate::codec::muxer::{ContainerFormat, MuxerOptions};
+use crate::codec::temp_file::TempFileStore;
 use crate::ffi::AVCodecID;
+use crate::webcodecs::bitrate_stats::MuxerBitrateSummary;
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::codec_description::validate_video_description;
 use crate::webcodecs::codec_string::parse_codec_string;
+use crate::webcodecs::demuxer_base::{RawPacket, TrackSideData};
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
 use crate::webcodecs::encoded_video_chunk::EncodedVideoChunk;
+use crate::webcodecs::error::throw_type_error_unit;
 use crate::webcodecs::muxer_base::{
   EncodedAudioChunkMetadataJs, EncodedVideoChunkMetadataJs, GenericAudioTrackConfig,
   GenericVideoTrackConfig, MuxerFormat, MuxerInner, StreamingMuxerOptions, lock_muxer_inner,
-  lock_muxer_inner_mut,
+  lock_muxer_inner_mut, track_sample_aspect_ratio,
 };
+use crate::webcodecs::video_frame::VideoColorSpaceInit;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use std::path::PathBuf;
 use std::sync::Mutex;
 
 // ============================================================================
@@ -32,6 +40,8 @@ impl MuxerFormat for Mp4Format {
       fast_start: false,
       fragmented: false,
       live: false,
+      omit_encoder_tags: false,
+      preserve_timestamps: false,
     }
   }
 
@@ -62,6 +72,16 @@ impl MuxerFormat for Mp4Format {
     }
   }
 
+  fn video_codec_tag(codec: &str) -> Option<u32> {
+    // The HEVC sample entry follows the codec string: hvc1 keeps parameter
+    // sets in hvcC only, hev1 allows them in-band as well
+    match codec.get(..4)? {
+      "hvc1" => Some(u32::from_le_bytes(*b"hvc1")),
+      "hev1" => Some(u32::from_le_bytes(*b"hev1")),
+      _ => None,
+    }
+  }
+
   fn parse_audio_codec(codec: &str) -> Result<AVCodecID> {
     let codec_lower = codec.to_lowercase();
 
@@ -116,8 +136,16 @@ pub struct Mp4MuxerOptions {
   /// Use fragmented MP4 for streaming output
   /// When true, uses frag_keyframe+empty_moov+default_base_moof
   pub fragmented: Option<bool>,
+  /// Omit the "Lavf" encoder tag and write in bitexact mode (default: false)
+  pub omit_encoder_tags: Option<bool>,
   /// Enable streaming output mode
   pub streaming: Option<StreamingMuxerOptions>,
+  /// Directory for the temporary file fastStart writes the output to before
+  /// moving the moov atom (default: the OS temp directory)
+  pub temp_dir: Option<String>,
+  /// Maximum bytes the temporary file may hold; a larger output fails with
+  /// QuotaExceededError (default: unlimited)
+  pub max_temp_bytes: Option<i64>,
 }
 
 // ============================================================================
@@ -133,10 +161,25 @@ pub struct Mp4VideoTrackConfig {
   pub width: u32,
   /// Video height in pixels
   pub height: u32,
-  /// Frame rate (frames per second)
+  /// Frame rate (frames per second); 29.97, 23.976 and 59.94 are taken as N*1000/1001
   pub framerate: Option<f64>,
+  /// Track timescale in ticks per second (e.g. 30000 or 90000)
+  ///
+  /// Defaults to a multiple of the framerate numerator, so every frame lasts a
+  /// whole number of ticks and timestamps do not drift over long recordings.
+  pub timescale: Option<u32>,
   /// Codec-specific description data (avcC/hvcC/av1C from encoder metadata)
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
+  /// Color space to signal in the container (MP4 colr/nclx, Matroska Colour)
+  pub color_space: Option<VideoColorSpaceInit>,
+  /// ICC profile to write as an MP4 colr/prof box (takes precedence over colorSpace)
+  pub icc_profile: Option<BufferView>,
+  /// Display width in pixels (defaults to width); written as the track's presentation width
+  pub display_width: Option<u32>,
+  /// Display height in pixels (defaults to height); written as the track's presentation height
+  pub display_height: Option<u32>,
+  /// Stream-level side data, e.g. `DemuxerTrackInfo.sideData` when remuxing
+  pub side_data: Option<Vec<TrackSideData>>,
 }
 
 /// Audio track configuration for MP4 muxer
@@ -149,7 +192,120 @@ pub struct Mp4AudioTrackConfig {
   /// Number of audio channels
   pub number_of_channels: u32,
   /// Codec-specific description data (esds for AAC, etc.)
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
+  /// Priming samples at the start of the track, for gapless playback (AAC, MP3)
+  ///
+  /// Written as an edit list and iTunSMPB tag. Defaults to the encoder delay
+  /// AudioEncoder marks on its first chunk.
+  pub encoder_delay: Option<u32>,
+  /// Padding samples after the last real sample (AAC, MP3)
+  ///
+  /// Defaults to the padding AudioEncoder marks on its last chunk.
+  pub padding: Option<u32>,
+}
+
+impl Mp4VideoTrackConfig {
+  /// Validate the track for MP4 and convert it for the base muxer
+  ///
+  /// Returns None after throwing a TypeError for an unusable description,
+  /// display size or timescale.
+  pub(crate) fn into_generic(self, env: &Env) -> Result<Option<GenericVideoTrackConfig>> {
+    // Parse codec and validate
+    let codec_id = Mp4Format::parse_video_codec(&self.codec)?;
+
+    // Validate MP4-specific codec support (H.264, H.265, AV1 only for MP4)
+    if !matches!(codec_id, AVCodecID::H264 | AVCodecID::Hevc | AVCodecID::Av1) {
+      return Err(Error::new(
+        Status::GenericFailure,
+        format!(
+          "Video codec {} is not supported in MP4 container",
+          self.codec
+        ),
+      ));
+    }
+
+    if let Some(description) = self.description.as_deref()
+      && let Err(msg) = validate_video_description(&self.codec, codec_id, description)
+    {
+      throw_type_error_unit(env, &msg)?;
+      return Ok(None);
+    }
+
+    let sample_aspect_ratio = match track_sample_aspect_ratio(
+      self.width,
+      self.height,
+      self.display_width,
+      self.display_height,
+    ) {
+      Ok(sar) => sar,
+      Err(msg) => {
+        throw_type_error_unit(env, &msg)?;
+        return Ok(None);
+      }
+    };
+
+    if let Some(timescale) = self.timescale
+      && (timescale == 0 || timescale > i32::MAX as u32)
+    {
+      throw_type_error_unit(
+        env,
+        &format!("timescale must be between 1 and {}", i32::MAX),
+      )?;
+      return Ok(None);
+    }
+
+    Ok(Some(GenericVideoTrackConfig {
+      codec: self.codec,
+      codec_id,
+      width: self.width,
+      height: self.height,
+      framerate: self.framerate.unwrap_or(30.0),
+      extradata: self.description.as_ref().map(|d| d.to_vec()),
+      has_alpha: false, // TODO: Add alpha support for MKV if needed
+      color_space: self.color_space,
+      icc_profile: self.icc_profile.as_ref().map(|d| d.to_vec()),
+      sample_aspect_ratio,
+      timescale: self.timescale,
+      side_data: self
+        .side_data
+        .as_ref()
+        .map(|side_data| side_data.iter().map(TrackSideData::to_stream).collect())
+        .unwrap_or_default(),
+    }))
+  }
+}
+
+impl Mp4AudioTrackConfig {
+  /// Validate the track for MP4 and convert it for the base muxer
+  pub(crate) fn into_generic(self) -> Result<GenericAudioTrackConfig> {
+    // Parse codec and validate
+    let codec_id = Mp4Format::parse_audio_codec(&self.codec)?;
+
+    // Validate MP4-specific codec support
+    if !matches!(
+      codec_id,
+      AVCodecID::Aac | AVCodecID::Mp3 | AVCodecID::Flac | AVCodecID::Opus
+    ) {
+      return Err(Error::new(
+        Status::GenericFailure,
+        format!(
+          "Audio codec {} is not supported in MP4 container",
+          self.codec
+        ),
+      ));
+    }
+
+    Ok(GenericAudioTrackConfig {
+      codec: self.codec,
+      codec_id,
+      sample_rate: self.sample_rate,
+      channels: self.number_of_channels,
+      frame_size: Mp4Format::get_audio_frame_size(codec_id),
+      extradata: self.description.as_ref().map(|d| d.to_vec()),
+      encoder_delay: self.encoder_delay,
+      padding: self.padding,
+    })
+  }
 }
 
 // ============================================================================
@@ -191,18 +347,34 @@ impl Mp4Muxer {
         "fastStart is not compatible with streaming mode. Use fragmented: true for streaming.",
       ));
     }
+    if opts.max_temp_bytes.is_some_and(|bytes| bytes <= 0) {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "maxTempBytes must be greater than 0",
+      ));
+    }
 
     // Create muxer options
     let muxer_options = MuxerOptions {
       fast_start: opts.fast_start.unwrap_or(false),
       fragmented: opts.fragmented.unwrap_or(false),
       live: false, // Not applicable for MP4
+      omit_encoder_tags: opts.omit_encoder_tags.unwrap_or(false),
+      preserve_timestamps: false,
     };
 
     // Create inner based on output mode
     let inner = if let Some(streaming_opts) = opts.streaming {
       let capacity = streaming_opts.buffer_capacity.unwrap_or(256 * 1024) as usize;
       MuxerInner::<Mp4Format>::new_streaming(muxer_options, capacity)?
+    } else if muxer_options.fast_start {
+      // The faststart rewrite needs the whole file, so spool it to disk
+      // instead of holding the recording in memory
+      let temp_files = TempFileStore::new(
+        opts.temp_dir.map(PathBuf::from),
+        opts.max_temp_bytes.map(|bytes| bytes as u64),
+      );
+      MuxerInner::<Mp4Format>::new_temp_file(muxer_options, temp_files)?
     } else {
       MuxerInner::<Mp4Format>::new_buffer(muxer_options)?
     };
@@ -216,34 +388,28 @@ impl Mp4Muxer {
   ///
   /// Must be called before adding any chunks.
   #[napi]
-  pub fn add_video_track(&self, config: Mp4VideoTrackConfig) -> Result<()> {
+  pub fn add_video_track(&self, env: Env, config: Mp4VideoTrackConfig) -> Result<()> {
     lock_muxer_inner_mut!(self => _guard, inner);
+    let Some(generic_config) = config.into_generic(&env)? else {
+      return Ok(());
+    };
+    inner.add_video_track(generic_config)
+  }
 
-    // Parse codec and validate
-    let codec_id = Mp4Format::parse_video_codec(&config.codec)?;
+  /// Replace the video track description before the first chunk is added
+  ///
+  /// For encoders that only deliver the description with the first keyframe.
+  #[napi]
+  pub fn update_video_description(&self, env: Env, description: BufferView) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
 
-    // Validate MP4-specific codec support (H.264, H.265, AV1 only for MP4)
-    if !matches!(codec_id, AVCodecID::H264 | AVCodecID::Hevc | AVCodecID::Av1) {
-      return Err(Error::new(
-        Status::GenericFailure,
-        format!(
-          "Video codec {} is not supported in MP4 container",
-          config.codec
-        ),
-      ));
+    if let Some(track) = inner.video_track_info.as_ref()
+      && let Err(msg) = validate_video_description(&track.codec, track.codec_id, &description)
+    {
+      return throw_type_error_unit(&env, &msg);
     }
 
-    let generic_config = GenericVideoTrackConfig {
-      codec: config.codec,
-      codec_id,
-      width: config.width,
-      height: config.height,
-      framerate: config.framerate.unwrap_or(30.0),
-      extradata: config.description.as_ref().map(|d| d.to_vec()),
-      has_alpha: false, // TODO: Add alpha support for MKV if needed
-    };
-
-    inner.add_video_track(generic_config)
+    inner.update_video_description(&description)
   }
 
   /// Add an audio track to the muxer
@@ -252,34 +418,7 @@ impl Mp4Muxer {
   #[napi]
   pub fn add_audio_track(&self, config: Mp4AudioTrackConfig) -> Result<()> {
     lock_muxer_inner_mut!(self => _guard, inner);
-
-    // Parse codec and validate
-    let codec_id = Mp4Format::parse_audio_codec(&config.codec)?;
-
-    // Validate MP4-specific codec support
-    if !matches!(
-      codec_id,
-      AVCodecID::Aac | AVCodecID::Mp3 | AVCodecID::Flac | AVCodecID::Opus
-    ) {
-      return Err(Error::new(
-        Status::GenericFailure,
-        format!(
-          "Audio codec {} is not supported in MP4 container",
-          config.codec
-        ),
-      ));
-    }
-
-    let generic_config = GenericAudioTrackConfig {
-      codec: config.codec,
-      codec_id,
-      sample_rate: config.sample_rate,
-      channels: config.number_of_channels,
-      frame_size: Mp4Format::get_audio_frame_size(codec_id),
-      extradata: config.description.as_ref().map(|d| d.to_vec()),
-    };
-
-    inner.add_audio_track(generic_config)
+    inner.add_audio_track(config.into_generic()?)
   }
 
   /// Add an encoded video chunk to the muxer
@@ -310,6 +449,16 @@ impl Mp4Muxer {
     inner.add_audio_chunk(chunk, metadata.as_ref())
   }
 
+  /// Write a raw packet with explicit DTS, bypassing EncodedVideoChunk/EncodedAudioChunk
+  ///
+  /// Use with a demuxer's `readPacket()` to remux without decoding: PTS, DTS,
+  /// duration and flags are kept as given (rescaled to the track's time base).
+  #[napi]
+  pub fn write_packet(&self, packet: RawPacket) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    inner.write_raw_packet(&packet)
+  }
+
   /// Flush any buffered data
   #[napi]
   pub fn flush(&self) -> Result<()> {
@@ -328,6 +477,16 @@ impl Mp4Muxer {
     Ok(Uint8Array::new(data))
   }
 
+  /// Achieved bitrate per track over the chunks added so far
+  ///
+  /// Call after `finalize()` for the final numbers. The peak is the largest
+  /// bit count over any 1-second window of chunk timestamps.
+  #[napi]
+  pub fn bitrate_summary(&self) -> Result<MuxerBitrateSummary> {
+    lock_muxer_inner!(self => _guard, inner);
+    Ok(inner.bitrate_summary())
+  }
+
   /// Read available data from streaming buffer (streaming mode only)
   ///
   /// Returns available data, or null if no data is ready yet.
@@ -397,6 +556,19 @@ impl Mp4Muxer {
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_hevc_sample_entry_follows_codec_string() {
+    assert_eq!(
+      Mp4Format::video_codec_tag("hvc1.1.6.L93.B0"),
+      Some(u32::from_le_bytes(*b"hvc1"))
+    );
+    assert_eq!(
+      Mp4Format::video_codec_tag("hev1.1.6.L93.B0"),
+      Some(u32::from_le_bytes(*b"hev1"))
+    );
+    assert_eq!(Mp4Format::video_codec_tag("avc1.42001E"), None);
+  }
+
   #[test]
   fn test_parse_video_codec() {
     assert!(matches!(
@@ -3,15 +3,31 @@
 //! This module provides common functionality for Mp4Muxer, WebMMuxer, and MkvMuxer
 //! to eliminate code duplication across the three implementations.
 
+use crate::codec::demuxer::StreamSideData;
 use crate::codec::io_buffer::StreamingBufferHandle;
+use crate::codec::mp4_gapless::{GaplessTracker, gapless_frame_size, insert_itunsmpb};
 use crate::codec::muxer::{
-  AudioStreamConfig, ContainerFormat, MuxerContext, MuxerOptions, MuxerOutput, VideoStreamConfig,
+  AudioStreamConfig, ContainerFormat, MuxerContext, MuxerOptions, MuxerOutput,
+  SubtitleStreamConfig, VideoStreamConfig,
 };
-use crate::ffi::{AVCodecID, AVPixelFormat, AVRational, AVSampleFormat};
+use crate::codec::temp_file::TempFileStore;
+use crate::codec::{Packet, framerate_rational};
+use crate::ffi::{
+  AV_NOPTS_VALUE, AVCodecID, AVPixelFormat, AVRational, AVSampleFormat, avutil::av_rescale_q,
+  pkt_flag, pkt_side_data_type,
+};
+use crate::webcodecs::bitrate_stats::{BitrateTracker, MuxerBitrateSummary};
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::demuxer_base::{RawPacket, SubtitleCue, SubtitleKind};
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
 use crate::webcodecs::encoded_video_chunk::{EncodedVideoChunk, EncodedVideoChunkType};
+use crate::webcodecs::error::quota_exceeded_error;
+use crate::webcodecs::video_frame::{
+  HdrDynamicMetadataType, VideoColorSpaceInit, color_space_to_av,
+};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use std::io::{Read, Seek, SeekFrom};
 use std::marker::PhantomData;
 
 // ============================================================================
@@ -86,6 +102,7 @@ impl MuxerState {
 #[derive(Debug, Clone)]
 pub struct StoredVideoTrackInfo {
   pub codec: String,
+  pub codec_id: AVCodecID,
   pub width: u32,
   pub height: u32,
   pub framerate: f64,
@@ -114,7 +131,7 @@ pub struct EncodedVideoChunkMetadataJs {
   /// Alpha channel side data (for VP9 alpha support)
   /// This contains the encoded alpha channel data that should be written
   /// as BlockAdditions in WebM/MKV containers.
-  pub alpha_side_data: Option<Uint8Array>,
+  pub alpha_side_data: Option<BufferView>,
 }
 
 /// JavaScript-facing decoder config type
@@ -124,7 +141,7 @@ pub struct VideoDecoderConfigJs {
   /// Codec string
   pub codec: Option<String>,
   /// Codec-specific description
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
   /// Coded width
   pub coded_width: Option<u32>,
   /// Coded height
@@ -158,7 +175,7 @@ pub struct AudioDecoderConfigJs {
   /// Number of channels
   pub number_of_channels: Option<u32>,
   /// Codec-specific description
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
 }
 
 // ============================================================================
@@ -178,6 +195,7 @@ pub struct StreamingMuxerOptions {
 // ============================================================================
 
 /// Generic video track configuration passed to base implementation
+#[derive(Clone)]
 pub struct GenericVideoTrackConfig {
   pub codec: String,
   pub codec_id: AVCodecID,
@@ -187,9 +205,43 @@ pub struct GenericVideoTrackConfig {
   pub extradata: Option<Vec<u8>>,
   /// Whether this track has alpha channel (VP9 alpha support)
   pub has_alpha: bool,
+  /// Color space to signal in the container
+  pub color_space: Option<VideoColorSpaceInit>,
+  /// ICC profile to write (MP4 only)
+  pub icc_profile: Option<Vec<u8>>,
+  /// Sample aspect ratio from the track's display size (None = square pixels)
+  pub sample_aspect_ratio: Option<(i32, i32)>,
+  /// Explicit track timescale (ticks per second); None derives one from the framerate
+  pub timescale: Option<u32>,
+  /// Stream-level side data to write (spherical, stereo 3D, HDR mastering, ...)
+  pub side_data: Vec<StreamSideData>,
+}
+
+/// Sample aspect ratio for a video track's optional display size
+///
+/// Returns a message suitable for a TypeError when the display size is unusable.
+pub(crate) fn track_sample_aspect_ratio(
+  width: u32,
+  height: u32,
+  display_width: Option<u32>,
+  display_height: Option<u32>,
+) -> std::result::Result<Option<(i32, i32)>, String> {
+  match (display_width, display_height) {
+    (None, None) => Ok(None),
+    (Some(0), _) => Err("displayWidth must be greater than 0".to_string()),
+    (_, Some(0)) => Err("displayHeight must be greater than 0".to_string()),
+    (Some(display_width), Some(display_height)) => Ok(crate::codec::sample_aspect_ratio(
+      width,
+      height,
+      display_width,
+      display_height,
+    )),
+    _ => Err("displayWidth and displayHeight must be specified together".to_string()),
+  }
 }
 
 /// Generic audio track configuration passed to base implementation
+#[derive(Clone)]
 pub struct GenericAudioTrackConfig {
   pub codec: String,
   pub codec_id: AVCodecID,
@@ -197,6 +249,10 @@ pub struct GenericAudioTrackConfig {
   pub channels: u32,
   pub frame_size: Option<u32>,
   pub extradata: Option<Vec<u8>>,
+  /// Priming samples to skip for gapless playback (MP4 only)
+  pub encoder_delay: Option<u32>,
+  /// Padding samples after the last real sample (MP4 only)
+  pub padding: Option<u32>,
 }
 
 // ============================================================================
@@ -216,6 +272,11 @@ pub trait MuxerFormat: Send + Sync + 'static {
   /// Parse video codec string to AVCodecID
   fn parse_video_codec(codec: &str) -> Result<AVCodecID>;
 
+  /// Codec tag to write for a video codec string (None = container default)
+  fn video_codec_tag(_codec: &str) -> Option<u32> {
+    None
+  }
+
   /// Parse audio codec string to AVCodecID
   fn parse_audio_codec(codec: &str) -> Result<AVCodecID>;
 
@@ -250,24 +311,37 @@ pub struct MuxerInner<F: MuxerFormat> {
   pub is_streaming: bool,
   /// Format-specific options holder (without fast_start - that's handled separately)
   pub muxer_options: MuxerOptions,
+  /// Microseconds subtracted from chunk timestamps before they are written
+  ///
+  /// Lets a segmented muxer start every file at zero.
+  pub timestamp_offset: i64,
   /// Whether to apply fastStart post-processing (MP4 only)
   /// We handle this ourselves because FFmpeg's faststart doesn't work with custom I/O
   apply_faststart: bool,
+  /// Store the output temp file was created in (temp file mode only)
+  temp_files: Option<TempFileStore>,
   /// Last video PTS written (to ensure monotonically increasing)
   last_video_pts: i64,
   /// Last audio PTS written (to ensure monotonically increasing)
   last_audio_pts: i64,
   /// Video frame counter (for precise PTS calculation)
   video_frame_count: u64,
-  /// Ticks per video frame in stream time base (set after header written)
-  /// For 30fps with timescale=57600: ticks_per_frame = 1920
-  video_ticks_per_frame: Option<u64>,
+  /// Duration of one video frame in seconds, as an exact rational (set after header written)
+  /// For 29.97fps: 1001/30000
+  video_frame_period: Option<AVRational>,
   /// DTS shift for B-frame support (MP4 only)
   /// FFmpeg requires pts >= dts. For B-frames where pts < dts, we shift all DTS
   /// values by this amount to satisfy the constraint while preserving timing.
   video_dts_shift: i64,
   /// Last written video DTS (to ensure monotonically increasing after shift)
   last_video_dts: i64,
+  /// Size and timestamp of every video chunk written
+  video_bitrate: BitrateTracker,
+  /// Size and timestamp of every audio chunk written
+  audio_bitrate: BitrateTracker,
+  /// Encoder delay and padding of an AAC/MP3 track, written as an edit list
+  /// and iTunSMPB tag (complete MP4 output only)
+  audio_gapless: Option<GaplessTracker>,
   /// Phantom data for format type
   _format: PhantomData<F>,
 }
@@ -275,7 +349,35 @@ pub struct MuxerInner<F: MuxerFormat> {
 impl<F: MuxerFormat> MuxerInner<F> {
   /// Create a new muxer with buffer output mode
   pub fn new_buffer(options: MuxerOptions) -> Result<Self> {
-    let muxer = MuxerContext::new(F::FORMAT, MuxerOutput::Buffer).map_err(|e| {
+    Self::new_with_output(MuxerOutput::Buffer, options, None)
+  }
+
+  /// Create a new muxer writing to a temporary file in `temp_files`
+  ///
+  /// finalize() reads the file back, applying fastStart if requested. The
+  /// file is deleted once read, when a write exceeds the store's size cap,
+  /// or when the muxer is dropped.
+  pub fn new_temp_file(options: MuxerOptions, temp_files: TempFileStore) -> Result<Self> {
+    let file = temp_files.create().map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!(
+          "Failed to create temporary file in {}: {}",
+          temp_files.dir().display(),
+          e
+        ),
+      )
+    })?;
+    Self::new_with_output(MuxerOutput::TempFile(file), options, Some(temp_files))
+  }
+
+  /// Create a new muxer writing complete output to a buffer or temp file
+  fn new_with_output(
+    output: MuxerOutput,
+    options: MuxerOptions,
+    temp_files: Option<TempFileStore>,
+  ) -> Result<Self> {
+    let muxer = MuxerContext::new(F::FORMAT, output).map_err(|e| {
       Error::new(
         Status::GenericFailure,
         format!("Failed to create muxer: {}", e),
@@ -300,13 +402,18 @@ impl<F: MuxerFormat> MuxerInner<F> {
       streaming_handle: None,
       is_streaming: false,
       muxer_options: ffmpeg_options,
+      timestamp_offset: 0,
       apply_faststart,
+      temp_files,
       last_video_pts: -1,
       last_audio_pts: -1,
       video_frame_count: 0,
-      video_ticks_per_frame: None,
+      video_frame_period: None,
       video_dts_shift: 0,
       last_video_dts: i64::MIN,
+      video_bitrate: BitrateTracker::default(),
+      audio_bitrate: BitrateTracker::default(),
+      audio_gapless: None,
       _format: PhantomData,
     })
   }
@@ -339,13 +446,18 @@ impl<F: MuxerFormat> MuxerInner<F> {
       streaming_handle,
       is_streaming: true,
       muxer_options: ffmpeg_options,
+      timestamp_offset: 0,
       apply_faststart: false, // Never apply in streaming mode
+      temp_files: None,
       last_video_pts: -1,
       last_audio_pts: -1,
       video_frame_count: 0,
-      video_ticks_per_frame: None,
+      video_frame_period: None,
       video_dts_shift: 0,
       last_video_dts: i64::MIN,
+      video_bitrate: BitrateTracker::default(),
+      audio_bitrate: BitrateTracker::default(),
+      audio_gapless: None,
       _format: PhantomData,
     })
   }
@@ -374,25 +486,26 @@ impl<F: MuxerFormat> MuxerInner<F> {
     };
 
     // Calculate time_base for precise timing using FFmpeg's algorithm:
-    // Start with fps as timescale, then double until >= 10000
-    // This ensures millisecond-level precision while keeping timescale reasonable
+    // Start with the framerate numerator as timescale, then double until >= 10000
+    // Every frame then lasts a whole number of ticks, so timestamps never drift
     // For 30fps: 30 -> 60 -> 120 -> 240 -> 480 -> 960 -> 1920 -> 3840 -> 7680 -> 15360
-    let time_base = if config.framerate > 0.0 && config.framerate.is_finite() {
-      let fps = config.framerate;
+    // For 29.97fps (30000/1001): 30000, with 1001 ticks per frame
+    let time_base = if let Some(timescale) = config.timescale {
+      AVRational::new(1, timescale as i32)
+    } else {
       const MIN_FPS: f64 = 1.0;
-      if fps >= MIN_FPS {
-        // FFmpeg's algorithm: double until >= 10000
-        let mut timescale = fps.round() as i32;
-        while timescale < 10000 {
-          timescale *= 2;
+      match framerate_rational(config.framerate) {
+        Some((num, _)) if config.framerate >= MIN_FPS => {
+          // FFmpeg's algorithm: double until >= 10000
+          let mut timescale = num;
+          while timescale < 10000 {
+            timescale *= 2;
+          }
+          AVRational::new(1, timescale)
         }
-        AVRational::new(1, timescale)
-      } else {
-        // Fallback to microseconds for very low framerates
-        AVRational::MICROSECONDS
+        // Fallback to microseconds for very low or unknown framerates
+        _ => AVRational::MICROSECONDS,
       }
-    } else {
-      AVRational::MICROSECONDS
     };
 
     // Create video stream config
@@ -404,6 +517,15 @@ impl<F: MuxerFormat> MuxerInner<F> {
       time_base,
       bitrate: None,
       extradata: config.extradata,
+      codec_tag: F::video_codec_tag(&config.codec),
+      color: config
+        .color_space
+        .as_ref()
+        .map(color_space_to_av)
+        .unwrap_or_default(),
+      icc_profile: config.icc_profile,
+      sample_aspect_ratio: config.sample_aspect_ratio,
+      side_data: config.side_data,
     };
 
     self.muxer.add_video_stream(&stream_config).map_err(|e| {
@@ -415,6 +537,7 @@ impl<F: MuxerFormat> MuxerInner<F> {
 
     self.video_track_info = Some(StoredVideoTrackInfo {
       codec: config.codec,
+      codec_id: config.codec_id,
       width: config.width,
       height: config.height,
       framerate: config.framerate,
@@ -423,6 +546,28 @@ impl<F: MuxerFormat> MuxerInner<F> {
     Ok(())
   }
 
+  /// Replace the video track description before the first chunk is written
+  ///
+  /// Supports encoders that only produce the description with the first keyframe.
+  pub fn update_video_description(&mut self, description: &[u8]) -> Result<()> {
+    if self.video_track_info.is_none() {
+      return Err(Error::new(Status::GenericFailure, "No video track added"));
+    }
+    if self.state != MuxerState::ConfiguringTracks {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Cannot update description after muxing has started",
+      ));
+    }
+
+    self.muxer.update_video_extradata(description).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to update video description: {}", e),
+      )
+    })
+  }
+
   /// Add an audio track to the muxer
   pub fn add_audio_track(&mut self, config: GenericAudioTrackConfig) -> Result<()> {
     if self.state != MuxerState::ConfiguringTracks {
@@ -458,6 +603,21 @@ impl<F: MuxerFormat> MuxerInner<F> {
       )
     })?;
 
+    // Fragmented and streaming output cannot be rewritten after the trailer
+    if F::FORMAT == ContainerFormat::Mp4
+      && !self.muxer_options.fragmented
+      && !self.is_streaming
+      && let Some(frame_size) = gapless_frame_size(config.codec_id)
+    {
+      // Keep the shifted priming timestamps negative so the edit list skips them
+      self.muxer_options.preserve_timestamps = true;
+      self.audio_gapless = Some(GaplessTracker::new(
+        frame_size,
+        config.encoder_delay,
+        config.padding,
+      ));
+    }
+
     self.audio_track_info = Some(StoredAudioTrackInfo {
       codec: config.codec,
       sample_rate: config.sample_rate,
@@ -467,38 +627,96 @@ impl<F: MuxerFormat> MuxerInner<F> {
     Ok(())
   }
 
+  /// Add a WebVTT subtitle track to the muxer
+  pub fn add_subtitle_track(&mut self, kind: SubtitleKind, language: Option<String>) -> Result<()> {
+    if self.state != MuxerState::ConfiguringTracks {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Cannot add track after muxing has started",
+      ));
+    }
+
+    if self.muxer.subtitle_stream_index().is_some() {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Subtitle track already added",
+      ));
+    }
+
+    let stream_config = SubtitleStreamConfig {
+      codec_id: AVCodecID::Webvtt,
+      // Matroska timestamps are always in milliseconds
+      time_base: AVRational::new(1, 1000),
+      disposition: kind.to_disposition(),
+      language,
+    };
+
+    self
+      .muxer
+      .add_subtitle_stream(&stream_config)
+      .map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to add subtitle stream: {}", e),
+        )
+      })?;
+
+    Ok(())
+  }
+
+  /// Error for a failed muxer write
+  ///
+  /// Reports a write refused by the temp file size cap as QuotaExceededError.
+  fn write_error(&self, action: &str, e: impl std::fmt::Display) -> Error {
+    match &self.temp_files {
+      Some(store) if store.quota_exceeded() => quota_exceeded_error(&format!(
+        "{}: muxer output exceeds maxTempBytes ({} bytes)",
+        action,
+        store.max_bytes().unwrap_or_default()
+      )),
+      _ => Error::new(Status::GenericFailure, format!("{}: {}", action, e)),
+    }
+  }
+
   /// Ensure header is written, transitioning state if needed
   fn ensure_header_written(&mut self) -> Result<()> {
     if self.state == MuxerState::ConfiguringTracks {
       self
         .muxer
         .write_header(Some(&self.muxer_options))
-        .map_err(|e| {
-          Error::new(
-            Status::GenericFailure,
-            format!("Failed to write header: {}", e),
-          )
-        })?;
+        .map_err(|e| self.write_error("Failed to write header", e))?;
       self.state = MuxerState::Muxing;
 
-      // Calculate ticks per frame for precise PTS calculation
+      // Keep the frame period as an exact rational for precise PTS calculation
       // This avoids floating point cumulative errors
-      if let (Some(tb), Some(track_info)) = (self.muxer.video_time_base(), &self.video_track_info) {
-        // ticks_per_frame = time_base_den / fps
-        // For 30fps with tb=1/57600: ticks_per_frame = 57600 / 30 = 1920
-        let fps = track_info.framerate;
+      if self.muxer.video_time_base().is_some()
+        && let Some(track_info) = &self.video_track_info
+      {
         // Use minimum fps threshold to avoid extremely large tick values from division
         // 1.0 fps is a reasonable lower bound for any practical video
-        // Also check is_finite() to guard against NaN/Infinity
+        let fps = track_info.framerate;
         const MIN_FPS: f64 = 1.0;
-        if fps.is_finite() && fps >= MIN_FPS {
-          self.video_ticks_per_frame = Some((tb.den as f64 / fps).round() as u64);
+        if fps >= MIN_FPS
+          && let Some((num, den)) = framerate_rational(fps)
+        {
+          self.video_frame_period = Some(AVRational::new(den, num));
         }
       }
     }
     Ok(())
   }
 
+  /// Stream timestamp of a video frame index at the configured framerate
+  ///
+  /// The whole index is rescaled rather than multiplying a rounded per-frame tick
+  /// count, so a timescale the frame period does not divide rounds each frame by
+  /// at most half a tick (e.g. Matroska's 1ms) without accumulating drift.
+  fn video_frame_ts(&self, frame_idx: u64) -> Option<i64> {
+    let period = self.video_frame_period?;
+    let tb = self.muxer.video_time_base()?;
+    Some(unsafe { av_rescale_q(frame_idx as i64, period, tb) })
+  }
+
   /// Add an encoded video chunk to the muxer
   pub fn add_video_chunk(
     &mut self,
@@ -511,6 +729,22 @@ impl<F: MuxerFormat> MuxerInner<F> {
       .video_stream_index()
       .ok_or_else(|| Error::new(Status::GenericFailure, "No video track added"))?;
 
+    // Apply a description delivered with the chunk before the header is written,
+    // so containers that store codec config in the header pick it up
+    if let Some(description) = metadata
+      .as_ref()
+      .and_then(|m| m.decoder_config.as_ref())
+      .and_then(|c| c.description.as_ref())
+    {
+      let desc_data: &[u8] = description;
+      if !desc_data.is_empty() {
+        // Update extradata dynamically if available
+        if let Err(e) = self.muxer.update_video_extradata(desc_data) {
+          tracing::warn!(target: "webcodecs", "Failed to update video extradata: {}", e);
+        }
+      }
+    }
+
     // Write header if needed
     self.ensure_header_written()?;
 
@@ -559,8 +793,9 @@ impl<F: MuxerFormat> MuxerInner<F> {
         use crate::ffi::avutil::av_rescale_q;
 
         // Scale both PTS and DTS from microseconds to target timebase
-        let scaled_pts = unsafe { av_rescale_q(orig_pts, src_tb, dst_tb) };
-        let scaled_dts = unsafe { av_rescale_q(orig_dts, src_tb, dst_tb) };
+        let offset = self.timestamp_offset;
+        let scaled_pts = unsafe { av_rescale_q(orig_pts - offset, src_tb, dst_tb) };
+        let scaled_dts = unsafe { av_rescale_q(orig_dts - offset, src_tb, dst_tb) };
 
         // For B-frame videos, don't apply any offset - let FFmpeg handle negative DTS
         // FFmpeg's MP4 muxer will automatically create an edit list (edts atom) when
@@ -571,13 +806,18 @@ impl<F: MuxerFormat> MuxerInner<F> {
         let final_pts = scaled_pts;
         let final_dts = scaled_dts;
 
-        // Calculate duration from ticks_per_frame or from chunk duration
-        let dur = if let Some(tpf) = self.video_ticks_per_frame {
-          tpf as i64
+        // Prefer the chunk's own duration so variable frame rate input keeps
+        // its exact timing; fall back to the frame period when it has none
+        let frame_idx = self.video_frame_count - 1;
+        let dur = if let Some(d) = duration.filter(|&d| d > 0) {
+          unsafe { av_rescale_q(d, src_tb, dst_tb) }
+        } else if let (Some(start), Some(end)) = (
+          self.video_frame_ts(frame_idx),
+          self.video_frame_ts(frame_idx + 1),
+        ) {
+          end - start
         } else {
-          duration
-            .map(|d| unsafe { av_rescale_q(d, src_tb, dst_tb) })
-            .unwrap_or(0)
+          0
         };
 
         (final_pts, final_dts, dur)
@@ -585,18 +825,20 @@ impl<F: MuxerFormat> MuxerInner<F> {
         // Fallback: use original values
         (orig_pts, orig_dts, duration.unwrap_or(0))
       }
-    } else if let Some(ticks_per_frame) = self.video_ticks_per_frame {
+    } else if let (Some(pts), Some(next_pts)) = (
+      self.video_frame_ts(self.video_frame_count - 1),
+      self.video_frame_ts(self.video_frame_count),
+    ) {
       // No B-frames: use frame counter for precise timing
       // Use (video_frame_count - 1) since we already incremented at function start
-      let frame_idx = self.video_frame_count - 1;
-      let pts = (frame_idx * ticks_per_frame) as i64;
-      (pts, pts, ticks_per_frame as i64)
+      (pts, pts, next_pts - pts)
     } else {
       // Fallback: convert from microseconds (may have precision loss)
-      let pts = if timestamp <= self.last_video_pts {
+      let rebased = timestamp - self.timestamp_offset;
+      let pts = if rebased <= self.last_video_pts {
         self.last_video_pts + 1
       } else {
-        timestamp
+        rebased
       };
       self.last_video_pts = pts;
 
@@ -675,11 +917,11 @@ impl<F: MuxerFormat> MuxerInner<F> {
       // This is acceptable for MKV/WebM where B-frame timing is less critical.
       // Use (video_frame_count - 1) since we already incremented at function start
       let frame_idx = self.video_frame_count - 1;
-      let sequential_ts = if let Some(tpf) = self.video_ticks_per_frame {
-        (frame_idx as i64) * (tpf as i64)
+      let sequential_ts = if let Some(ts) = self.video_frame_ts(frame_idx) {
+        ts
       } else {
-        // If no ticks_per_frame, use frame count with default ~33333µs (~30fps)
-        // Time base is microseconds when ticks_per_frame is None
+        // If no frame period, use frame count with default ~33333µs (~30fps)
+        // Time base is microseconds when the frame period is None
         (frame_idx as i64) * 33333
       };
       // Override PTS to match DTS for MKV B-frame compatibility
@@ -696,21 +938,6 @@ impl<F: MuxerFormat> MuxerInner<F> {
       packet.set_flags(crate::ffi::pkt_flag::KEY);
     }
 
-    // Handle metadata - extract description if present
-    if let Some(description) = metadata
-      .as_ref()
-      .and_then(|m| m.decoder_config.as_ref())
-      .and_then(|c| c.description.as_ref())
-    {
-      let desc_data: &[u8] = description;
-      if !desc_data.is_empty() {
-        // Update extradata dynamically if available
-        if let Err(e) = self.muxer.update_video_extradata(desc_data) {
-          tracing::warn!(target: "webcodecs", "Failed to update video extradata: {}", e);
-        }
-      }
-    }
-
     // Handle alpha side data for VP9 alpha support
     // This adds the alpha channel data as BlockAdditional side data
     if let Some(alpha_data) = metadata.as_ref().and_then(|m| m.alpha_side_data.as_ref()) {
@@ -722,13 +949,23 @@ impl<F: MuxerFormat> MuxerInner<F> {
       }
     }
 
+    // Pass HDR10+ metadata through as packet side data (Matroska/WebM store it
+    // as a BlockAdditional; other containers ignore it)
+    for entry in chunk.hdr_dynamic_metadata()?.unwrap_or_default() {
+      if entry.kind == HdrDynamicMetadataType::ItuTT35 && !packet.set_hdr10plus_t35(&entry.data) {
+        tracing::warn!(target: "webcodecs", "Ignoring invalid HDR10+ T.35 payload");
+      }
+    }
+
     // Write packet
-    self.muxer.write_packet(&mut packet).map_err(|e| {
-      Error::new(
-        Status::GenericFailure,
-        format!("Failed to write packet: {}", e),
-      )
-    })?;
+    self
+      .muxer
+      .write_packet(&mut packet)
+      .map_err(|e| self.write_error("Failed to write packet", e))?;
+
+    self
+      .video_bitrate
+      .record(timestamp, duration, chunk.byte_length()?);
 
     Ok(())
   }
@@ -773,7 +1010,7 @@ impl<F: MuxerFormat> MuxerInner<F> {
       .as_ref()
       .map(|c| c.sample_rate)
       .unwrap_or(48000) as i64;
-    let pts_in_samples = timestamp * sample_rate / 1_000_000;
+    let pts_in_samples = (timestamp - self.timestamp_offset) * sample_rate / 1_000_000;
 
     // Ensure monotonically increasing PTS (audio time base is 1/sample_rate)
     let pts = if pts_in_samples <= self.last_audio_pts {
@@ -783,6 +1020,12 @@ impl<F: MuxerFormat> MuxerInner<F> {
     };
     self.last_audio_pts = pts;
 
+    // Priming samples go before zero, where the MP4 edit list skips them
+    let pts = match self.audio_gapless.as_mut() {
+      Some(gapless) => pts - gapless.record(&packet) as i64,
+      None => pts,
+    };
+
     packet.set_pts(pts);
     packet.set_dts(pts); // Audio has no B-frames, DTS always equals PTS
 
@@ -809,23 +1052,193 @@ impl<F: MuxerFormat> MuxerInner<F> {
     packet.set_flags(crate::ffi::pkt_flag::KEY);
 
     // Write packet
-    self.muxer.write_packet(&mut packet).map_err(|e| {
+    self
+      .muxer
+      .write_packet(&mut packet)
+      .map_err(|e| self.write_error("Failed to write packet", e))?;
+
+    self
+      .audio_bitrate
+      .record(timestamp, duration, chunk.byte_length()?);
+
+    Ok(())
+  }
+
+  /// Write a raw packet (e.g. from a demuxer's `readPacket()`) with explicit DTS
+  ///
+  /// PTS, DTS and duration are rescaled from the packet's time base to the
+  /// stream's, which is exact when the time bases are compatible. None of the
+  /// chunk path's timestamp synthesis applies, and a muxer whose first packet
+  /// is raw keeps negative DTS instead of shifting every timestamp.
+  pub fn write_raw_packet(&mut self, raw: &RawPacket) -> Result<()> {
+    let is_video = match raw.track_type.as_str() {
+      "video" => true,
+      "audio" => false,
+      other => {
+        return Err(Error::new(
+          Status::InvalidArg,
+          format!("TypeError: Invalid trackType '{}'", other),
+        ));
+      }
+    };
+    if raw.time_base_num <= 0 || raw.time_base_den <= 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "TypeError: timeBaseNum and timeBaseDen must be positive",
+      ));
+    }
+    let stream_index = if is_video {
+      self.muxer.video_stream_index()
+    } else {
+      self.muxer.audio_stream_index()
+    }
+    .ok_or_else(|| {
       Error::new(
         Status::GenericFailure,
-        format!("Failed to write packet: {}", e),
+        format!("No {} track added", raw.track_type),
       )
     })?;
 
+    if self.state == MuxerState::ConfiguringTracks {
+      self.muxer_options.preserve_timestamps = true;
+    }
+    self.ensure_header_written()?;
+
+    if self.state != MuxerState::Muxing {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Muxer is not in muxing state",
+      ));
+    }
+
+    let src_tb = AVRational::new(raw.time_base_num, raw.time_base_den);
+    let dst_tb = if is_video {
+      self.muxer.video_time_base()
+    } else {
+      self.muxer.audio_time_base()
+    }
+    .unwrap_or(src_tb);
+    let rescale = |ts: i64| unsafe { av_rescale_q(ts, src_tb, dst_tb) };
+
+    let mut packet = Packet::new().map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to allocate packet: {}", e),
+      )
+    })?;
+    packet.copy_data_from(&raw.data).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to copy packet data: {}", e),
+      )
+    })?;
+    packet.set_stream_index(stream_index);
+    packet.set_pts(raw.pts.map_or(AV_NOPTS_VALUE, rescale));
+    packet.set_dts(raw.dts.or(raw.pts).map_or(AV_NOPTS_VALUE, rescale));
+    packet.set_duration(raw.duration.map_or(0, rescale));
+    packet.set_flags(raw.flags);
+
+    self
+      .muxer
+      .write_packet(&mut packet)
+      .map_err(|e| self.write_error("Failed to write packet", e))?;
+
+    let to_us = |ts: i64| unsafe { av_rescale_q(ts, src_tb, AVRational::MICROSECONDS) };
+    let tracker = if is_video {
+      &mut self.video_bitrate
+    } else {
+      &mut self.audio_bitrate
+    };
+    tracker.record(
+      raw.pts.map_or(0, to_us),
+      raw.duration.map(to_us),
+      raw.data.len() as u32,
+    );
+
     Ok(())
   }
 
+  /// Write a WebVTT cue to the subtitle track
+  ///
+  /// The settings and identifier travel as packet side data; the Matroska
+  /// muxer stores them in the WebM WebVTT block layout.
+  pub fn write_subtitle_cue(&mut self, cue: &SubtitleCue) -> Result<()> {
+    let stream_index = self
+      .muxer
+      .subtitle_stream_index()
+      .ok_or_else(|| Error::new(Status::GenericFailure, "No subtitle track added"))?;
+    if cue.timestamp < 0 || cue.duration <= 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "TypeError: cue timestamp must be non-negative and duration positive",
+      ));
+    }
+
+    self.ensure_header_written()?;
+
+    if self.state != MuxerState::Muxing {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Muxer is not in muxing state",
+      ));
+    }
+
+    let tb = self
+      .muxer
+      .subtitle_time_base()
+      .unwrap_or(AVRational::new(1, 1000));
+    let rescale = |ts: i64| unsafe { av_rescale_q(ts, AVRational::MICROSECONDS, tb) };
+
+    let mut packet = Packet::new().map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to allocate packet: {}", e),
+      )
+    })?;
+    packet.copy_data_from(cue.text.as_bytes()).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to copy cue text: {}", e),
+      )
+    })?;
+    for (kind, line) in [
+      (
+        pkt_side_data_type::AV_PKT_DATA_WEBVTT_SETTINGS,
+        &cue.settings,
+      ),
+      (
+        pkt_side_data_type::AV_PKT_DATA_WEBVTT_IDENTIFIER,
+        &cue.identifier,
+      ),
+    ] {
+      if let Some(line) = line.as_deref().filter(|line| !line.is_empty()) {
+        packet.add_side_data(kind, line.as_bytes()).map_err(|e| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Failed to attach cue side data: {}", e),
+          )
+        })?;
+      }
+    }
+    packet.set_stream_index(stream_index);
+    packet.set_pts(rescale(cue.timestamp));
+    packet.set_dts(rescale(cue.timestamp));
+    packet.set_duration(rescale(cue.duration).max(1));
+    packet.set_flags(pkt_flag::KEY);
+
+    self
+      .muxer
+      .write_packet(&mut packet)
+      .map_err(|e| self.write_error("Failed to write subtitle cue", e))
+  }
+
   /// Flush any buffered data
   pub fn flush(&mut self) -> Result<()> {
     if self.state == MuxerState::Muxing {
       self
         .muxer
         .flush()
-        .map_err(|e| Error::new(Status::GenericFailure, format!("Failed to flush: {}", e)))?;
+        .map_err(|e| self.write_error("Failed to flush", e))?;
     }
     Ok(())
   }
@@ -857,10 +1270,29 @@ impl<F: MuxerFormat> MuxerInner<F> {
     self
       .muxer
       .finalize()
-      .map_err(|e| Error::new(Status::GenericFailure, format!("Failed to finalize: {}", e)))?;
+      .map_err(|e| self.write_error("Failed to finalize", e))?;
 
     self.state = MuxerState::Finalized;
 
+    // In temp file mode, read the file back; it is deleted when dropped here
+    if let Some(mut file) = self.muxer.take_temp_file() {
+      let data = if self.apply_faststart {
+        crate::codec::mp4_faststart::apply_faststart_file(&mut file)
+      } else {
+        let mut data = Vec::with_capacity(file.len() as usize);
+        file
+          .seek(SeekFrom::Start(0))
+          .and_then(|_| file.read_to_end(&mut data))
+          .map(|_| data)
+      };
+      return data.map(|data| self.with_gapless_tag(data)).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to read temporary file: {}", e),
+        )
+      });
+    }
+
     // In streaming mode, signal EOF and return empty vec
     // Remaining data should be read via read()
     if self.is_streaming {
@@ -881,7 +1313,29 @@ impl<F: MuxerFormat> MuxerInner<F> {
       data = crate::codec::mp4_faststart::apply_faststart(data);
     }
 
-    Ok(data)
+    Ok(self.with_gapless_tag(data))
+  }
+
+  /// Add the iTunSMPB tag describing the audio track's priming and padding
+  fn with_gapless_tag(&self, data: Vec<u8>) -> Vec<u8> {
+    match self.audio_gapless.as_ref().and_then(|g| g.info()) {
+      Some(info) => insert_itunsmpb(data, &info),
+      None => data,
+    }
+  }
+
+  /// Achieved bitrate per track, over the chunks written so far
+  pub fn bitrate_summary(&self) -> MuxerBitrateSummary {
+    MuxerBitrateSummary {
+      video: self
+        .video_track_info
+        .is_some()
+        .then(|| self.video_bitrate.summary()),
+      audio: self
+        .audio_track_info
+        .is_some()
+        .then(|| self.audio_bitrate.summary()),
+    }
   }
 
   /// Read available data from streaming buffer (for streaming mode)
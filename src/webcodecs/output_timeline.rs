@@ -0,0 +1,186 @@
+//! Output Timeline - Decoder output timestamp mapping
+//!
+//! Decoders stamp each output with the timestamp of the chunk it came from,
+//! which is the container PTS when chunks come from a demuxer. The
+//! `timestampMode` and `startAtZero` decoder config options remap that on the
+//! worker thread as outputs are produced:
+//!
+//! - `"container"` (default) keeps the chunk timestamps.
+//! - `"normalized"` renumbers outputs back to back from the first one: video
+//!   frames step by the frame duration, audio by the decoded sample count.
+//! - `startAtZero: true` subtracts the first output's timestamp from every
+//!   output, in either mode.
+//!
+//! The timeline lives as long as the decoder and survives flush(), reset() and
+//! reconfiguration, so timestamps stay on one axis across seeks. It restarts
+//! only when a configure() changes `timestampMode` or `startAtZero`.
+
+use napi_derive::napi;
+
+/// Source of decoder output timestamps (extension)
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampMode {
+  /// Keep the timestamp of the chunk each output was decoded from
+  #[default]
+  #[napi(value = "container")]
+  Container,
+  /// Renumber outputs contiguously from the first output
+  #[napi(value = "normalized")]
+  Normalized,
+}
+
+/// Maps decoder output timestamps for one decoder
+#[derive(Debug, Default)]
+pub(crate) struct OutputTimeline {
+  mode: TimestampMode,
+  start_at_zero: bool,
+  /// Chunk timestamp of the first output, in microseconds
+  origin: Option<i64>,
+  /// Normalized offset of the next output from the origin, in microseconds
+  offset: i64,
+  /// Normalized video: chunk timestamp and duration of the previous frame
+  previous: Option<(i64, Option<i64>)>,
+  /// Normalized video: last step between frames, used when durations are missing
+  frame_step: Option<i64>,
+  /// Normalized audio: samples output since the origin
+  samples: u64,
+}
+
+impl OutputTimeline {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Apply the decoder config options, restarting the timeline if they changed
+  pub fn configure(&mut self, mode: Option<TimestampMode>, start_at_zero: Option<bool>) {
+    let mode = mode.unwrap_or_default();
+    let start_at_zero = start_at_zero.unwrap_or(false);
+    if mode != self.mode || start_at_zero != self.start_at_zero {
+      *self = Self {
+        mode,
+        start_at_zero,
+        ..Self::default()
+      };
+    }
+  }
+
+  /// Timestamp the first output is mapped to
+  fn base(&mut self, timestamp: i64) -> (i64, i64) {
+    let origin = *self.origin.get_or_insert(timestamp);
+    let base = if self.start_at_zero { 0 } else { origin };
+    (origin, base)
+  }
+
+  /// Map a decoded video frame's chunk timestamp and duration
+  pub fn map_video(&mut self, timestamp: i64, duration: Option<i64>) -> (i64, Option<i64>) {
+    let (origin, base) = self.base(timestamp);
+    match self.mode {
+      TimestampMode::Container => (timestamp - origin + base, duration),
+      TimestampMode::Normalized => {
+        // The step after a frame is its own duration when the chunk carried one,
+        // otherwise the last known step, or the distance to the next chunk
+        // timestamp when no step is known yet
+        if let Some((previous_timestamp, previous_duration)) = self.previous {
+          let step = previous_duration
+            .filter(|d| *d > 0)
+            .or(self.frame_step)
+            .or(Some(timestamp - previous_timestamp).filter(|d| *d > 0))
+            .unwrap_or(0);
+          self.frame_step = Some(step);
+          self.offset = self.offset.saturating_add(step);
+        }
+        self.previous = Some((timestamp, duration));
+        (
+          base.saturating_add(self.offset),
+          duration.or(self.frame_step),
+        )
+      }
+    }
+  }
+
+  /// Map decoded audio starting at `timestamp` with `samples` frames at `sample_rate`
+  pub fn map_audio(&mut self, timestamp: i64, samples: u32, sample_rate: u32) -> i64 {
+    let (origin, base) = self.base(timestamp);
+    match self.mode {
+      TimestampMode::Container => timestamp - origin + base,
+      TimestampMode::Normalized => {
+        // Derived from the running sample count so rounding never accumulates
+        let offset = (self.samples as i128 * 1_000_000)
+          .checked_div(sample_rate as i128)
+          .unwrap_or(0) as i64;
+        self.samples += samples as u64;
+        base.saturating_add(offset)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn container_mode_keeps_timestamps() {
+    let mut timeline = OutputTimeline::new();
+    timeline.configure(None, None);
+    assert_eq!(
+      timeline.map_video(1_400_000, Some(33_333)),
+      (1_400_000, Some(33_333))
+    );
+    assert_eq!(
+      timeline.map_video(1_433_333, Some(33_333)),
+      (1_433_333, Some(33_333))
+    );
+  }
+
+  #[test]
+  fn start_at_zero_subtracts_first_timestamp() {
+    let mut timeline = OutputTimeline::new();
+    timeline.configure(Some(TimestampMode::Container), Some(true));
+    assert_eq!(timeline.map_video(1_400_000, None).0, 0);
+    assert_eq!(timeline.map_video(1_500_000, None).0, 100_000);
+    // A later seek stays on the same axis
+    assert_eq!(timeline.map_video(1_200_000, None).0, -200_000);
+  }
+
+  #[test]
+  fn normalized_video_steps_by_duration() {
+    let mut timeline = OutputTimeline::new();
+    timeline.configure(Some(TimestampMode::Normalized), None);
+    // Jittery container timestamps are renumbered by the chunk duration
+    assert_eq!(timeline.map_video(1_400_000, Some(40_000)).0, 1_400_000);
+    assert_eq!(timeline.map_video(1_441_000, Some(40_000)).0, 1_440_000);
+    assert_eq!(timeline.map_video(1_479_000, Some(40_000)).0, 1_480_000);
+  }
+
+  #[test]
+  fn normalized_video_learns_step_without_durations() {
+    let mut timeline = OutputTimeline::new();
+    timeline.configure(Some(TimestampMode::Normalized), Some(true));
+    assert_eq!(timeline.map_video(1_400_000, None), (0, None));
+    assert_eq!(timeline.map_video(1_440_000, None), (40_000, Some(40_000)));
+    // A gap in the container timestamps does not leave a gap in the output
+    assert_eq!(timeline.map_video(2_000_000, None), (80_000, Some(40_000)));
+  }
+
+  #[test]
+  fn normalized_audio_counts_samples() {
+    let mut timeline = OutputTimeline::new();
+    timeline.configure(Some(TimestampMode::Normalized), Some(true));
+    assert_eq!(timeline.map_audio(1_400_000, 1024, 48_000), 0);
+    assert_eq!(timeline.map_audio(1_421_000, 1024, 48_000), 21_333);
+    assert_eq!(timeline.map_audio(1_443_000, 1024, 48_000), 42_666);
+  }
+
+  #[test]
+  fn configure_restarts_only_when_options_change() {
+    let mut timeline = OutputTimeline::new();
+    timeline.configure(None, Some(true));
+    assert_eq!(timeline.map_video(1_400_000, None).0, 0);
+    timeline.configure(None, Some(true));
+    assert_eq!(timeline.map_video(1_500_000, None).0, 100_000);
+    timeline.configure(None, None);
+    assert_eq!(timeline.map_video(1_500_000, None).0, 1_500_000);
+  }
+}
@@ -0,0 +1,189 @@
+//! Parameter Set Tracking - out-of-band vs in-band H.264/H.265 parameter sets
+//!
+//! A decoder configured with a `description` (avcC/hvcC) can still receive
+//! SPS/PPS (and VPS for H.265) inside the bitstream. After an ad-insertion
+//! splice those in-band sets usually differ from the description. As in
+//! H.264/H.265 itself, the most recently received parameter sets win: in-band
+//! sets override the description from the key chunk that carries them on, and
+//! later key chunks without parameter sets keep using them.
+//!
+//! The tracker only inspects key chunks, which is where encoders and
+//! splicers place parameter sets, so delta chunks are never scanned.
+
+use napi_derive::napi;
+
+use crate::webcodecs::plain_bytes::PlainUint8Array;
+use crate::webcodecs::{
+  convert_annexb_extradata_to_avcc, convert_annexb_extradata_to_hvcc, extradata_parameter_sets,
+  packet_parameter_sets,
+};
+
+/// Where the parameter sets a decoder used came from (extension)
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterSetSource {
+  /// The `description` of the decoder config
+  #[napi(value = "description")]
+  Description,
+  /// SPS/PPS carried in an earlier chunk
+  #[napi(value = "in-band")]
+  InBand,
+}
+
+/// In-band parameter sets that replaced the active ones (extension)
+///
+/// Passed to the `parameterSetChange` callback of the VideoDecoder init dictionary.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ParameterSetChange {
+  /// Codec string of the decoder config
+  pub codec: String,
+  /// Timestamp of the chunk carrying the new parameter sets, in microseconds
+  pub timestamp: i64,
+  /// Where the replaced parameter sets came from
+  pub previous_source: ParameterSetSource,
+  /// Replaced parameter sets as Annex B NAL units
+  #[napi(ts_type = "Uint8Array")]
+  pub previous: PlainUint8Array,
+  /// New parameter sets as Annex B NAL units
+  #[napi(ts_type = "Uint8Array")]
+  pub current: PlainUint8Array,
+  /// avcC/hvcC record built from the new parameter sets, for a fresh decoder
+  /// config or a muxer's `updateVideoDescription()`
+  #[napi(ts_type = "Uint8Array")]
+  pub description: Option<PlainUint8Array>,
+}
+
+/// Tracks the active parameter sets of one configured H.264/H.265 decoder
+#[derive(Debug)]
+pub(crate) struct ParameterSetTracker {
+  codec: String,
+  is_h265: bool,
+  /// Active parameter sets as Annex B NAL units
+  active: Option<Vec<u8>>,
+  /// Whether `active` came from the bitstream rather than the description
+  in_band: bool,
+}
+
+impl ParameterSetTracker {
+  /// Start tracking for a decoder config; None for codecs without parameter sets
+  pub(crate) fn new(codec: &str, description: Option<&[u8]>) -> Option<Self> {
+    let is_h265 = codec.starts_with("hvc1") || codec.starts_with("hev1");
+    let is_h264 = codec.starts_with("avc1") || codec.starts_with("avc3");
+    if !is_h264 && !is_h265 {
+      return None;
+    }
+    Some(Self {
+      codec: codec.to_string(),
+      is_h265,
+      active: description.and_then(|d| extradata_parameter_sets(d, is_h265)),
+      in_band: false,
+    })
+  }
+
+  /// In-band parameter sets that override the description, as Annex B NAL units
+  pub(crate) fn in_band_parameter_sets(&self) -> Option<&[u8]> {
+    self.active.as_deref().filter(|_| self.in_band)
+  }
+
+  /// Inspect a key chunk, returning the change if it carries parameter sets
+  /// that differ from the active ones
+  ///
+  /// The first in-band sets of a decoder configured without a description
+  /// become active without a change being reported.
+  pub(crate) fn observe(&mut self, data: &[u8], timestamp: i64) -> Option<ParameterSetChange> {
+    let current = packet_parameter_sets(data, self.is_h265)?;
+    let previous_source = if self.in_band {
+      ParameterSetSource::InBand
+    } else {
+      ParameterSetSource::Description
+    };
+    let previous = self.active.replace(current.clone());
+    self.in_band = true;
+
+    let previous = previous?;
+    if previous == current {
+      return None;
+    }
+    let description = if self.is_h265 {
+      convert_annexb_extradata_to_hvcc(&current)
+    } else {
+      convert_annexb_extradata_to_avcc(&current)
+    };
+    Some(ParameterSetChange {
+      codec: self.codec.clone(),
+      timestamp,
+      previous_source,
+      previous: previous.into(),
+      current: current.into(),
+      description: description.map(PlainUint8Array::from),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SPS_A: &[u8] = &[0x67, 0x42, 0xc0, 0x1e, 0xda, 0x02, 0x80];
+  const SPS_B: &[u8] = &[0x67, 0x64, 0x00, 0x1f, 0xac, 0xd9, 0x40];
+  const PPS: &[u8] = &[0x68, 0xce, 0x3c, 0x80];
+  const IDR: &[u8] = &[0x65, 0x88, 0x84, 0x00];
+
+  fn annexb(nals: &[&[u8]]) -> Vec<u8> {
+    nals
+      .iter()
+      .flat_map(|nal| [&[0, 0, 0, 1][..], nal].concat())
+      .collect()
+  }
+
+  #[test]
+  fn matching_in_band_sets_are_not_a_change() {
+    let description = convert_annexb_extradata_to_avcc(&annexb(&[SPS_A, PPS])).unwrap();
+    let mut tracker = ParameterSetTracker::new("avc1.42c01e", Some(&description)).unwrap();
+    assert!(tracker.observe(&annexb(&[SPS_A, PPS, IDR]), 0).is_none());
+  }
+
+  #[test]
+  fn differing_in_band_sets_override_the_description() {
+    let description = convert_annexb_extradata_to_avcc(&annexb(&[SPS_A, PPS])).unwrap();
+    let mut tracker = ParameterSetTracker::new("avc1.42c01e", Some(&description)).unwrap();
+    assert!(tracker.in_band_parameter_sets().is_none());
+
+    let change = tracker
+      .observe(&annexb(&[SPS_B, PPS, IDR]), 40_000)
+      .unwrap();
+    assert_eq!(change.timestamp, 40_000);
+    assert_eq!(change.previous_source, ParameterSetSource::Description);
+    assert_eq!(&*change.previous, annexb(&[SPS_A, PPS]).as_slice());
+    assert_eq!(&*change.current, annexb(&[SPS_B, PPS]).as_slice());
+    assert_eq!(change.description.as_deref().map(|d| d[1]), Some(0x64));
+    assert_eq!(
+      tracker.in_band_parameter_sets(),
+      Some(annexb(&[SPS_B, PPS]).as_slice())
+    );
+
+    // Key chunks without parameter sets keep the in-band ones
+    assert!(tracker.observe(&annexb(&[IDR]), 80_000).is_none());
+    let change = tracker
+      .observe(&annexb(&[SPS_A, PPS, IDR]), 120_000)
+      .unwrap();
+    assert_eq!(change.previous_source, ParameterSetSource::InBand);
+  }
+
+  #[test]
+  fn first_in_band_sets_without_description_are_not_a_change() {
+    let mut tracker = ParameterSetTracker::new("avc1.42c01e", None).unwrap();
+    assert!(tracker.observe(&annexb(&[SPS_A, PPS, IDR]), 0).is_none());
+    assert!(
+      tracker
+        .observe(&annexb(&[SPS_B, PPS, IDR]), 40_000)
+        .is_some()
+    );
+  }
+
+  #[test]
+  fn codecs_without_parameter_sets_are_not_tracked() {
+    assert!(ParameterSetTracker::new("vp09.00.10.08", None).is_none());
+  }
+}
@@ -0,0 +1,81 @@
+//! Byte arrays for metadata objects that cross thread boundaries
+//!
+//! Owned `Uint8Array` values are handed to JS as external ArrayBuffers that
+//! point at Rust memory. Those cannot be transferred, and some runtimes reject
+//! them outright, so metadata passed through `postMessage` (decoder configs,
+//! alpha side data, HDR metadata) uses `PlainUint8Array` instead: its bytes
+//! are copied into a regular ArrayBuffer owned by the JS heap.
+
+use crate::webcodecs::buffer_view::BufferView;
+use napi::bindgen_prelude::*;
+use napi::sys;
+use std::ops::Deref;
+use std::ptr;
+
+/// Byte array exposed to JS as a Uint8Array over a plain (non-external) ArrayBuffer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlainUint8Array(Vec<u8>);
+
+impl From<Vec<u8>> for PlainUint8Array {
+  fn from(data: Vec<u8>) -> Self {
+    Self(data)
+  }
+}
+
+impl Deref for PlainUint8Array {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    &self.0
+  }
+}
+
+impl TypeName for PlainUint8Array {
+  fn type_name() -> &'static str {
+    "Uint8Array"
+  }
+
+  fn value_type() -> ValueType {
+    ValueType::Object
+  }
+}
+
+impl ValidateNapiValue for PlainUint8Array {}
+
+impl FromNapiValue for PlainUint8Array {
+  unsafe fn from_napi_value(env: sys::napi_env, value: sys::napi_value) -> Result<Self> {
+    let view = unsafe { BufferView::from_napi_value(env, value)? };
+    Ok(Self(view.to_vec()))
+  }
+}
+
+impl ToNapiValue for PlainUint8Array {
+  unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> Result<sys::napi_value> {
+    let len = val.0.len();
+    let mut data = ptr::null_mut();
+    let mut arraybuffer = ptr::null_mut();
+    check_status!(
+      unsafe { sys::napi_create_arraybuffer(env, len, &mut data, &mut arraybuffer) },
+      "Failed to create ArrayBuffer"
+    )?;
+    if len > 0 {
+      unsafe { ptr::copy_nonoverlapping(val.0.as_ptr(), data.cast::<u8>(), len) };
+    }
+
+    let mut typed_array = ptr::null_mut();
+    check_status!(
+      unsafe {
+        sys::napi_create_typedarray(
+          env,
+          sys::TypedarrayType::uint8_array,
+          len,
+          arraybuffer,
+          0,
+          &mut typed_array,
+        )
+      },
+      "Failed to create Uint8Array"
+    )?;
+    Ok(typed_array)
+  }
+}
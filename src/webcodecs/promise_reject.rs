@@ -1,12 +1,36 @@
 use std::ptr;
 
 use napi::{
-  bindgen_prelude::{Env, FnArgs, Function, JsObjectValue, PromiseRaw, Result},
+  bindgen_prelude::{Env, FnArgs, Function, JsObjectValue, PromiseRaw, Result, ToNapiValue},
   check_status, sys,
 };
 
 use super::error::DOMExceptionName;
 
+/// Create a promise already resolved with `value`
+///
+/// For results known up front, so no future has to be spawned.
+pub(crate) fn resolve_with<'env, T: ToNapiValue>(
+  env: &'env Env,
+  value: T,
+) -> Result<PromiseRaw<'env, T>> {
+  let mut deferred = ptr::null_mut();
+  let mut promise = ptr::null_mut();
+
+  check_status!(
+    unsafe { sys::napi_create_promise(env.raw(), &mut deferred, &mut promise) },
+    "Failed to create promise"
+  )?;
+
+  let value = unsafe { T::to_napi_value(env.raw(), value)? };
+  check_status!(
+    unsafe { sys::napi_resolve_deferred(env.raw(), deferred, value) },
+    "Failed to resolve promise"
+  )?;
+
+  Ok(PromiseRaw::new(env.raw(), promise))
+}
+
 /// Reject a promise with a native TypeError
 ///
 /// Creates a proper JavaScript TypeError instance and rejects the promise with it.
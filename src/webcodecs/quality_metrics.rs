@@ -0,0 +1,272 @@
+//! Quality Metrics - PSNR/SSIM of encoded chunks against their source frames
+//!
+//! With `computeQualityMetrics` enabled, the VideoEncoder keeps each
+//! pre-encode frame, decodes every produced packet with a paired software
+//! decoder and compares the two. PSNR is computed over all three YUV planes
+//! (and for luma alone); SSIM is computed on luma over 8x8 windows with a
+//! stride of 4 samples, matching the usual "fast SSIM" approximation.
+
+use napi_derive::napi;
+
+use crate::codec::Frame;
+use crate::ffi::AVPixelFormat;
+
+/// SSIM window size in samples
+const SSIM_WINDOW: usize = 8;
+/// Distance between the origins of consecutive SSIM windows
+const SSIM_STRIDE: usize = 4;
+
+/// Quality of an encoded chunk measured against its source frame
+#[napi(object)]
+#[derive(Debug, Clone, Copy)]
+pub struct QualityMetrics {
+  /// PSNR over the Y, U and V planes in dB (Infinity when lossless)
+  pub psnr: f64,
+  /// PSNR of the luma plane in dB (Infinity when lossless)
+  pub psnr_y: f64,
+  /// Mean luma SSIM in [0, 1] (1 when lossless)
+  pub ssim: f64,
+}
+
+/// One plane of samples, 8-bit or 16-bit little-endian
+struct Plane<'a> {
+  data: &'a [u8],
+  stride: usize,
+  width: usize,
+  height: usize,
+  wide: bool,
+}
+
+impl Plane<'_> {
+  #[inline]
+  fn sample(&self, x: usize, y: usize) -> u32 {
+    if self.wide {
+      let offset = y * self.stride + x * 2;
+      u16::from_le_bytes([self.data[offset], self.data[offset + 1]]) as u32
+    } else {
+      self.data[y * self.stride + x] as u32
+    }
+  }
+}
+
+/// Measure `decoded` against `reference`
+///
+/// Returns None unless both frames are 4:2:0 planar with the same size and bit
+/// depth (8-bit or 10-bit), which covers every software encoder output format.
+pub(crate) fn measure(reference: &Frame, decoded: &Frame) -> Option<QualityMetrics> {
+  let bit_depth = sample_bit_depth(reference.format())?;
+  if sample_bit_depth(decoded.format())? != bit_depth
+    || reference.width() != decoded.width()
+    || reference.height() != decoded.height()
+  {
+    return None;
+  }
+
+  let width = reference.width() as usize;
+  let height = reference.height() as usize;
+  if width == 0 || height == 0 {
+    return None;
+  }
+
+  let mut sse = [0u64; 3];
+  let mut samples = [0u64; 3];
+  let mut luma = None;
+  for plane in 0..3 {
+    let (plane_width, plane_height) = if plane == 0 {
+      (width, height)
+    } else {
+      (width.div_ceil(2), height.div_ceil(2))
+    };
+    let a = frame_plane(reference, plane, plane_width, plane_height, bit_depth)?;
+    let b = frame_plane(decoded, plane, plane_width, plane_height, bit_depth)?;
+    sse[plane] = plane_sse(&a, &b);
+    samples[plane] = (plane_width * plane_height) as u64;
+    if plane == 0 {
+      luma = Some((a, b));
+    }
+  }
+
+  let peak = ((1u32 << bit_depth) - 1) as f64;
+  let (luma_ref, luma_dec) = luma?;
+  Some(QualityMetrics {
+    psnr: psnr(sse.iter().sum(), samples.iter().sum(), peak),
+    psnr_y: psnr(sse[0], samples[0], peak),
+    ssim: luma_ssim(&luma_ref, &luma_dec, peak),
+  })
+}
+
+/// Bits per sample of the supported 4:2:0 planar formats
+fn sample_bit_depth(format: AVPixelFormat) -> Option<u32> {
+  match format {
+    AVPixelFormat::Yuv420p | AVPixelFormat::Yuva420p => Some(8),
+    AVPixelFormat::Yuv420p10le | AVPixelFormat::Yuva420p10le => Some(10),
+    _ => None,
+  }
+}
+
+fn frame_plane(
+  frame: &Frame,
+  plane: usize,
+  width: usize,
+  height: usize,
+  bit_depth: u32,
+) -> Option<Plane<'_>> {
+  let ptr = frame.data(plane);
+  let stride = frame.linesize(plane);
+  if ptr.is_null() || stride <= 0 {
+    return None;
+  }
+  let stride = stride as usize;
+  let wide = bit_depth > 8;
+  if stride < width * if wide { 2 } else { 1 } {
+    return None;
+  }
+  // SAFETY: FFmpeg allocates `linesize * height` bytes for each plane
+  let data = unsafe { std::slice::from_raw_parts(ptr, stride * height) };
+  Some(Plane {
+    data,
+    stride,
+    width,
+    height,
+    wide,
+  })
+}
+
+/// Sum of squared differences between two planes of the same size
+fn plane_sse(a: &Plane, b: &Plane) -> u64 {
+  let mut sse = 0u64;
+  for y in 0..a.height {
+    for x in 0..a.width {
+      let diff = a.sample(x, y) as i64 - b.sample(x, y) as i64;
+      sse += (diff * diff) as u64;
+    }
+  }
+  sse
+}
+
+/// PSNR in dB from a sum of squared errors, Infinity when there is no error
+fn psnr(sse: u64, samples: u64, peak: f64) -> f64 {
+  if sse == 0 || samples == 0 {
+    return f64::INFINITY;
+  }
+  let mse = sse as f64 / samples as f64;
+  10.0 * (peak * peak / mse).log10()
+}
+
+/// Mean SSIM over 8x8 windows spaced 4 samples apart
+fn luma_ssim(a: &Plane, b: &Plane, peak: f64) -> f64 {
+  if a.width < SSIM_WINDOW || a.height < SSIM_WINDOW {
+    return if plane_sse(a, b) == 0 { 1.0 } else { 0.0 };
+  }
+
+  let c1 = (0.01 * peak) * (0.01 * peak);
+  let c2 = (0.03 * peak) * (0.03 * peak);
+  let n = (SSIM_WINDOW * SSIM_WINDOW) as f64;
+
+  let mut total = 0.0;
+  let mut windows = 0u64;
+  for y0 in (0..=a.height - SSIM_WINDOW).step_by(SSIM_STRIDE) {
+    for x0 in (0..=a.width - SSIM_WINDOW).step_by(SSIM_STRIDE) {
+      let (mut sum_a, mut sum_b, mut sum_aa, mut sum_bb, mut sum_ab) =
+        (0u64, 0u64, 0u64, 0u64, 0u64);
+      for y in y0..y0 + SSIM_WINDOW {
+        for x in x0..x0 + SSIM_WINDOW {
+          let va = a.sample(x, y) as u64;
+          let vb = b.sample(x, y) as u64;
+          sum_a += va;
+          sum_b += vb;
+          sum_aa += va * va;
+          sum_bb += vb * vb;
+          sum_ab += va * vb;
+        }
+      }
+      let mean_a = sum_a as f64 / n;
+      let mean_b = sum_b as f64 / n;
+      let var_a = sum_aa as f64 / n - mean_a * mean_a;
+      let var_b = sum_bb as f64 / n - mean_b * mean_b;
+      let cov = sum_ab as f64 / n - mean_a * mean_b;
+      total += ((2.0 * mean_a * mean_b + c1) * (2.0 * cov + c2))
+        / ((mean_a * mean_a + mean_b * mean_b + c1) * (var_a + var_b + c2));
+      windows += 1;
+    }
+  }
+  total / windows as f64
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn plane(data: &[u8], width: usize, height: usize) -> Plane<'_> {
+    Plane {
+      data,
+      stride: width,
+      width,
+      height,
+      wide: false,
+    }
+  }
+
+  fn gradient(width: usize, height: usize) -> Vec<u8> {
+    (0..width * height)
+      .map(|i| ((i % width) * 255 / width) as u8)
+      .collect()
+  }
+
+  #[test]
+  fn test_identical_planes() {
+    let data = gradient(32, 16);
+    let a = plane(&data, 32, 16);
+    assert_eq!(plane_sse(&a, &a), 0);
+    assert_eq!(psnr(0, 512, 255.0), f64::INFINITY);
+    assert!((luma_ssim(&a, &a, 255.0) - 1.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_psnr_of_constant_error() {
+    // An error of 1 on every sample gives MSE 1, so PSNR = 20 * log10(255)
+    let reference = vec![100u8; 64];
+    let shifted = vec![101u8; 64];
+    let sse = plane_sse(&plane(&reference, 8, 8), &plane(&shifted, 8, 8));
+    assert_eq!(sse, 64);
+    assert!((psnr(sse, 64, 255.0) - 48.1308).abs() < 1e-3);
+  }
+
+  #[test]
+  fn test_more_noise_lowers_scores() {
+    let reference = gradient(32, 32);
+    let noisy = |amplitude: i32| -> Vec<u8> {
+      reference
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+          let noise = if i % 2 == 0 { amplitude } else { -amplitude };
+          (v as i32 + noise).clamp(0, 255) as u8
+        })
+        .collect()
+    };
+    let light = noisy(2);
+    let heavy = noisy(12);
+    let a = plane(&reference, 32, 32);
+    let (l, h) = (plane(&light, 32, 32), plane(&heavy, 32, 32));
+    assert!(psnr(plane_sse(&a, &l), 1024, 255.0) > psnr(plane_sse(&a, &h), 1024, 255.0));
+    assert!(luma_ssim(&a, &l, 255.0) > luma_ssim(&a, &h, 255.0));
+  }
+
+  #[test]
+  fn test_wide_samples() {
+    let data: Vec<u8> = [512u16, 1023]
+      .iter()
+      .flat_map(|v| v.to_le_bytes())
+      .collect();
+    let p = Plane {
+      data: &data,
+      stride: 4,
+      width: 2,
+      height: 1,
+      wide: true,
+    };
+    assert_eq!(p.sample(0, 0), 512);
+    assert_eq!(p.sample(1, 0), 1023);
+  }
+}
@@ -0,0 +1,144 @@
+//! Rate Control Stats - per-chunk bit budget and encoder rate-control state
+//!
+//! With `reportRateControlStats` enabled, every video chunk's metadata carries
+//! the bits it actually used. Values the encoder reports on the packet are
+//! passed through when present:
+//!
+//! - `AV_PKT_DATA_QUALITY_STATS` (libx264, libx265, libvpx, libaom, ...) gives
+//!   the frame quantizer.
+//! - `AV_PKT_DATA_CPB_PROPERTIES` gives the VBV/CPB delay of the picture,
+//!   from which the buffer fullness follows.
+//!
+//! Fields without a source are left out rather than estimated. The per-frame
+//! bit target stays internal to the rate controllers FFmpeg wraps, so it is
+//! not reported; a share of the configured bitrate would be a guess, which
+//! callers can compute themselves.
+
+use napi_derive::napi;
+
+use crate::codec::Packet;
+use crate::ffi::pkt_side_data_type::{AV_PKT_DATA_CPB_PROPERTIES, AV_PKT_DATA_QUALITY_STATS};
+use crate::webcodecs::video_encoder::FF_QP2LAMBDA;
+
+/// Clock of AVCPBProperties.vbv_delay (Hz)
+const VBV_DELAY_CLOCK: f64 = 27_000_000.0;
+
+/// Rate-control outcome of one encoded chunk
+#[napi(object)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateControlStats {
+  /// Bits in the chunk
+  pub actual_bits: f64,
+  /// Quantizer the encoder reported for the frame
+  pub qp: Option<f64>,
+  /// VBV/CPB buffer fullness in [0, 1] when the frame is removed for decoding,
+  /// if the encoder reported its buffer delay
+  pub buffer_fullness: Option<f64>,
+}
+
+/// Rate-control side data read from an output packet before it becomes a chunk
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PacketRateControl {
+  qp: Option<f64>,
+  buffer_fullness: Option<f64>,
+}
+
+impl PacketRateControl {
+  pub(crate) fn from_packet(packet: &Packet) -> Self {
+    Self {
+      qp: packet
+        .side_data(AV_PKT_DATA_QUALITY_STATS)
+        .and_then(quality_stats_qp),
+      buffer_fullness: packet
+        .side_data(AV_PKT_DATA_CPB_PROPERTIES)
+        .and_then(cpb_buffer_fullness),
+    }
+  }
+
+  /// Complete the stats with the delivered chunk's size
+  pub(crate) fn stats(self, bytes: u32) -> RateControlStats {
+    RateControlStats {
+      actual_bits: f64::from(bytes) * 8.0,
+      qp: self.qp,
+      buffer_fullness: self.buffer_fullness,
+    }
+  }
+}
+
+/// Frame quantizer from AV_PKT_DATA_QUALITY_STATS (u32le quality in lambda units first)
+fn quality_stats_qp(stats: &[u8]) -> Option<f64> {
+  let quality = u32::from_le_bytes(stats.get(..4)?.try_into().ok()?);
+  (quality > 0).then(|| f64::from(quality) / f64::from(FF_QP2LAMBDA))
+}
+
+/// Buffer fullness from AVCPBProperties: vbv_delay worth of bits at the peak
+/// (or average) rate, over the buffer size
+fn cpb_buffer_fullness(props: &[u8]) -> Option<f64> {
+  let field =
+    |index: usize| -> Option<[u8; 8]> { props.get(index * 8..index * 8 + 8)?.try_into().ok() };
+  let max_bitrate = i64::from_ne_bytes(field(0)?);
+  let avg_bitrate = i64::from_ne_bytes(field(2)?);
+  let buffer_size = i64::from_ne_bytes(field(3)?);
+  let vbv_delay = u64::from_ne_bytes(field(4)?);
+  let rate = if max_bitrate > 0 {
+    max_bitrate
+  } else {
+    avg_bitrate
+  };
+  if rate <= 0 || buffer_size <= 0 || vbv_delay == 0 || vbv_delay == u64::MAX {
+    return None;
+  }
+  let bits = vbv_delay as f64 / VBV_DELAY_CLOCK * rate as f64;
+  Some((bits / buffer_size as f64).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn cpb(max: i64, avg: i64, buffer_size: i64, vbv_delay: u64) -> Vec<u8> {
+    [max, 0, avg, buffer_size]
+      .iter()
+      .flat_map(|v| v.to_ne_bytes())
+      .chain(vbv_delay.to_ne_bytes())
+      .collect()
+  }
+
+  #[test]
+  fn actual_bits_come_from_the_chunk_size() {
+    let stats = PacketRateControl::default().stats(1000);
+    assert_eq!(stats.actual_bits, 8000.0);
+    assert_eq!(stats.qp, None);
+    assert_eq!(stats.buffer_fullness, None);
+  }
+
+  #[test]
+  fn qp_comes_from_quality_stats() {
+    let stats = (26 * FF_QP2LAMBDA as u32).to_le_bytes();
+    assert_eq!(
+      quality_stats_qp(&[&stats[..], &[1, 0, 0, 0]].concat()),
+      Some(26.0)
+    );
+    assert_eq!(quality_stats_qp(&0u32.to_le_bytes()), None);
+    assert_eq!(quality_stats_qp(&[1, 2]), None);
+  }
+
+  #[test]
+  fn buffer_fullness_comes_from_vbv_delay() {
+    // Half a second of a 2 Mbit/s peak rate in a 2 Mbit buffer
+    let fullness = cpb_buffer_fullness(&cpb(2_000_000, 0, 2_000_000, 13_500_000));
+    assert_eq!(fullness, Some(0.5));
+    // Falls back to the average rate, and clamps at a full buffer
+    assert_eq!(
+      cpb_buffer_fullness(&cpb(0, 2_000_000, 1_000_000, 27_000_000)),
+      Some(1.0)
+    );
+    // Unknown delay or buffer size
+    assert_eq!(
+      cpb_buffer_fullness(&cpb(2_000_000, 0, 2_000_000, u64::MAX)),
+      None
+    );
+    assert_eq!(cpb_buffer_fullness(&cpb(2_000_000, 0, 0, 13_500_000)), None);
+    assert_eq!(cpb_buffer_fullness(&[0; 16]), None);
+  }
+}
@@ -0,0 +1,395 @@
+//! RawVideoReader / RawVideoWriter - headerless raw video file I/O
+//!
+//! Reads and writes tightly packed frames (as in `foreman_cif.yuv` style test
+//! sequences) without going through a container or codec. Useful as a
+//! dependency-free frame source for benchmarks and tests.
+
+use crate::codec::{Frame, Scaler};
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::video_frame::{VideoFrame, VideoPixelFormat};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Default frame rate used to generate timestamps
+const DEFAULT_FRAMERATE: f64 = 30.0;
+
+/// Options for reading raw video
+#[napi(object)]
+pub struct RawVideoReaderInit {
+  /// Frame width in pixels
+  pub width: u32,
+  /// Frame height in pixels
+  pub height: u32,
+  /// Pixel format of the stored frames
+  pub format: VideoPixelFormat,
+  /// Frame rate used to generate timestamps (default: 30)
+  pub framerate: Option<f64>,
+  /// Index of the first frame to read (default: 0)
+  pub start_frame: Option<u32>,
+  /// Maximum number of frames to read (default: until end of input)
+  pub frame_count: Option<u32>,
+}
+
+/// Options for writing raw video
+#[napi(object)]
+pub struct RawVideoWriterInit {
+  /// Pixel format to store; frames in other formats are converted
+  pub format: VideoPixelFormat,
+}
+
+enum RawVideoSource {
+  File(File),
+  Buffer(Vec<u8>),
+}
+
+struct RawVideoReaderInner {
+  source: RawVideoSource,
+  width: u32,
+  height: u32,
+  format: VideoPixelFormat,
+  frame_size: usize,
+  framerate: f64,
+  /// Absolute index of the next frame to read
+  next_frame: u64,
+  /// Absolute index one past the last frame to read
+  end_frame: u64,
+}
+
+impl RawVideoReaderInner {
+  fn read_next_frame(&mut self) -> Result<Option<VideoFrame>> {
+    if self.next_frame >= self.end_frame {
+      return Ok(None);
+    }
+
+    let offset = self.next_frame as usize * self.frame_size;
+    let mut file_data = Vec::new();
+    let data: &[u8] = match &mut self.source {
+      RawVideoSource::Buffer(buffer) => &buffer[offset..offset + self.frame_size],
+      RawVideoSource::File(file) => {
+        file_data.resize(self.frame_size, 0);
+        file
+          .seek(SeekFrom::Start(offset as u64))
+          .and_then(|_| file.read_exact(&mut file_data))
+          .map_err(|e| {
+            Error::new(
+              Status::GenericFailure,
+              format!("Failed to read frame {}: {}", self.next_frame, e),
+            )
+          })?;
+        &file_data
+      }
+    };
+
+    let mut frame =
+      Frame::new_video(self.width, self.height, self.format.to_av_format()).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to create frame: {}", e),
+        )
+      })?;
+    VideoFrame::copy_data_to_frame(&mut frame, data, self.format, self.width, self.height, None)?;
+
+    let timestamp = frame_time_us(self.next_frame, self.framerate);
+    let duration = frame_time_us(self.next_frame + 1, self.framerate) - timestamp;
+    frame.set_pts(timestamp);
+    frame.set_duration(duration);
+    self.next_frame += 1;
+
+    Ok(Some(VideoFrame::from_internal(
+      frame,
+      timestamp,
+      Some(duration),
+    )))
+  }
+}
+
+/// Timestamp in microseconds of a frame index at the given frame rate
+fn frame_time_us(index: u64, framerate: f64) -> i64 {
+  (index as f64 * 1_000_000.0 / framerate).round() as i64
+}
+
+/// Reader for headerless raw video (e.g. `.yuv` test sequences)
+///
+/// Frames are yielded as VideoFrames with timestamps generated from the frame
+/// index and frame rate, so a range selected with `startFrame` keeps the
+/// timestamps it has in the full sequence.
+///
+/// ```javascript
+/// const reader = new RawVideoReader('foreman_cif.yuv', { width: 352, height: 288, format: 'I420' });
+/// for await (const frame of reader) {
+///   encoder.encode(frame);
+///   frame.close();
+/// }
+/// ```
+#[napi(async_iterator)]
+pub struct RawVideoReader {
+  inner: Arc<Mutex<RawVideoReaderInner>>,
+  frame_count: u32,
+}
+
+impl AsyncGenerator for RawVideoReader {
+  type Yield = VideoFrame;
+  type Next = ();
+  type Return = ();
+
+  fn next(
+    &mut self,
+    _value: Option<Self::Next>,
+  ) -> impl Future<Output = Result<Option<Self::Yield>>> + Send + 'static {
+    let inner = self.inner.clone();
+
+    async move {
+      // File reads are blocking I/O
+      tokio::task::spawn_blocking(move || {
+        let mut guard = inner
+          .lock()
+          .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+        guard.read_next_frame()
+      })
+      .await
+      .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+    }
+  }
+}
+
+#[napi]
+impl RawVideoReader {
+  /// Open raw video from a file path or an in-memory buffer
+  #[napi(constructor)]
+  pub fn new(source: Either<String, BufferView>, init: RawVideoReaderInit) -> Result<Self> {
+    if init.width == 0 || init.height == 0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "width and height must be greater than 0",
+      ));
+    }
+    let framerate = init.framerate.unwrap_or(DEFAULT_FRAMERATE);
+    if !framerate.is_finite() || framerate <= 0.0 {
+      return Err(Error::new(
+        Status::InvalidArg,
+        "framerate must be a positive number",
+      ));
+    }
+
+    let frame_size =
+      VideoFrame::calculate_buffer_size(init.format, init.width, init.height) as usize;
+
+    let (source, total_bytes) = match source {
+      Either::A(path) => {
+        let file = File::open(&path).map_err(|e| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Failed to open {}: {}", path, e),
+          )
+        })?;
+        let len = file
+          .metadata()
+          .map_err(|e| Error::new(Status::GenericFailure, e.to_string()))?
+          .len();
+        (RawVideoSource::File(file), len)
+      }
+      Either::B(buffer) => {
+        let data = buffer.to_vec();
+        let len = data.len() as u64;
+        (RawVideoSource::Buffer(data), len)
+      }
+    };
+
+    // A trailing partial frame is ignored
+    let total_frames = total_bytes / frame_size as u64;
+    let start_frame = init.start_frame.unwrap_or(0) as u64;
+    if start_frame > total_frames {
+      return Err(Error::new(
+        Status::InvalidArg,
+        format!(
+          "startFrame {} is beyond the last frame ({} frames available)",
+          start_frame, total_frames
+        ),
+      ));
+    }
+    let end_frame = match init.frame_count {
+      Some(count) => (start_frame + count as u64).min(total_frames),
+      None => total_frames,
+    };
+
+    Ok(Self {
+      inner: Arc::new(Mutex::new(RawVideoReaderInner {
+        source,
+        width: init.width,
+        height: init.height,
+        format: init.format,
+        frame_size,
+        framerate,
+        next_frame: start_frame,
+        end_frame,
+      })),
+      frame_count: (end_frame - start_frame) as u32,
+    })
+  }
+
+  /// Number of frames in the selected range
+  #[napi(getter)]
+  pub fn frame_count(&self) -> u32 {
+    self.frame_count
+  }
+}
+
+struct RawVideoWriterInner {
+  writer: BufWriter<File>,
+  format: VideoPixelFormat,
+  /// Cached converter, reused while input format and size stay the same
+  scaler: Option<Scaler>,
+  buffer: Vec<u8>,
+}
+
+impl RawVideoWriterInner {
+  fn write_frame(&mut self, frame: &Frame) -> Result<()> {
+    let target_format = self.format.to_av_format();
+    let converted;
+    let frame = if frame.format() == target_format {
+      frame
+    } else {
+      let reusable = self.scaler.as_ref().is_some_and(|s| {
+        s.src_format() == frame.format()
+          && s.src_width() == frame.width()
+          && s.src_height() == frame.height()
+      });
+      if !reusable {
+        self.scaler = Some(
+          Scaler::new_converter(frame.width(), frame.height(), frame.format(), target_format)
+            .map_err(|e| {
+              Error::new(
+                Status::GenericFailure,
+                format!("Failed to create converter: {}", e),
+              )
+            })?,
+        );
+      }
+      let scaler = self.scaler.as_ref().expect("scaler initialized above");
+      converted = scaler.scale_alloc(frame).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to convert frame: {}", e),
+        )
+      })?;
+      &converted
+    };
+
+    let size = VideoFrame::calculate_buffer_size(self.format, frame.width(), frame.height());
+    self.buffer.resize(size as usize, 0);
+    let written = frame.copy_to_buffer(&mut self.buffer).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to copy frame data: {}", e),
+      )
+    })?;
+    self
+      .writer
+      .write_all(&self.buffer[..written])
+      .map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to write frame: {}", e),
+        )
+      })?;
+    Ok(())
+  }
+}
+
+/// Writer for headerless raw video
+///
+/// Appends the visible rect of each frame, tightly packed, in the configured
+/// format.
+#[napi]
+pub struct RawVideoWriter {
+  inner: Mutex<Option<RawVideoWriterInner>>,
+  frames_written: AtomicU32,
+}
+
+#[napi]
+impl RawVideoWriter {
+  /// Create (or truncate) a raw video file
+  #[napi(constructor)]
+  pub fn new(path: String, init: RawVideoWriterInit) -> Result<Self> {
+    let file = File::create(&path).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to create {}: {}", path, e),
+      )
+    })?;
+
+    Ok(Self {
+      inner: Mutex::new(Some(RawVideoWriterInner {
+        writer: BufWriter::new(file),
+        format: init.format,
+        scaler: None,
+        buffer: Vec::new(),
+      })),
+      frames_written: AtomicU32::new(0),
+    })
+  }
+
+  /// Append a frame's visible rect, converting it to the writer's format if needed
+  #[napi]
+  pub fn write(&self, frame: &VideoFrame) -> Result<()> {
+    let mut guard = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    let inner = guard
+      .as_mut()
+      .ok_or_else(|| Error::new(Status::GenericFailure, "RawVideoWriter is closed"))?;
+
+    // Only the visible rect is written; padding rows/columns of the coded
+    // frame are cropped off a reference (the shared pixel data is untouched)
+    let visible_crop = frame.visible_crop();
+    frame.with_frame(|f| match visible_crop {
+      Some((left, top, width, height)) => {
+        let cropped = f
+          .shallow_clone()
+          .and_then(|mut cropped| {
+            cropped.crop(left, top, width, height)?;
+            Ok(cropped)
+          })
+          .map_err(|e| {
+            Error::new(
+              Status::GenericFailure,
+              format!("Failed to crop frame: {}", e),
+            )
+          })?;
+        inner.write_frame(&cropped)
+      }
+      None => inner.write_frame(f),
+    })??;
+    self.frames_written.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+  }
+
+  /// Number of frames written so far
+  #[napi(getter)]
+  pub fn frames_written(&self) -> u32 {
+    self.frames_written.load(Ordering::Relaxed)
+  }
+
+  /// Flush buffered data and close the file
+  #[napi]
+  pub fn close(&self) -> Result<()> {
+    let mut guard = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    if let Some(mut inner) = guard.take() {
+      inner.writer.flush().map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to flush raw video file: {}", e),
+        )
+      })?;
+    }
+    Ok(())
+  }
+}
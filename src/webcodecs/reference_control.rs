@@ -0,0 +1,170 @@
+//! Reference Control - Long-term reference frames for VideoEncoder
+//!
+//! Real-time senders recover from packet loss by marking a frame as a
+//! long-term reference (LTR) and later encoding a frame predicted only from it,
+//! instead of a full key frame. libvpx exposes three reference buffers (LAST,
+//! GOLDEN, ALTREF); GOLDEN and ALTREF are used as two long-term slots and LAST
+//! keeps following the previous frame. Per-frame buffer flags reach libvpx
+//! through FFmpeg's "vp8-flags" frame metadata entry.
+//!
+//! Once a long-term reference exists, every frame stops updating the golden
+//! and altref buffers on its own and stops updating the entropy context, so a
+//! receiver that dropped the frames in between decodes a recovery frame the
+//! same way the sender encoded it.
+
+use napi_derive::napi;
+
+use crate::ffi::AVCodecID;
+
+// libvpx per-frame encode flags (vpx_encoder.h / vp8cx.h)
+const VP8_EFLAG_NO_REF_LAST: u32 = 1 << 16;
+const VP8_EFLAG_NO_REF_GF: u32 = 1 << 17;
+const VP8_EFLAG_NO_UPD_ENTROPY: u32 = 1 << 20;
+const VP8_EFLAG_NO_REF_ARF: u32 = 1 << 21;
+const VP8_EFLAG_NO_UPD_GF: u32 = 1 << 22;
+const VP8_EFLAG_NO_UPD_ARF: u32 = 1 << 23;
+
+/// Per-slot (GOLDEN, ALTREF) "don't reference" and "don't update" flags
+const SLOT_FLAGS: [(u32, u32); 2] = [
+  (VP8_EFLAG_NO_REF_GF, VP8_EFLAG_NO_UPD_GF),
+  (VP8_EFLAG_NO_REF_ARF, VP8_EFLAG_NO_UPD_ARF),
+];
+
+/// Reference frame usage of one chunk (reference control extension)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceControlMetadata {
+  /// Long-term reference id this chunk was stored as (`markAsLongTermReference`)
+  pub long_term_reference: Option<u32>,
+  /// Long-term reference ids this chunk is predicted from
+  pub references: Vec<u32>,
+  /// Whether this chunk is also predicted from the previous frame
+  pub references_previous_frame: bool,
+}
+
+/// Check if an encoder accepts per-frame reference control (libvpx VP8/VP9)
+pub(crate) fn supports_reference_control(codec_id: AVCodecID, encoder_name: &str) -> bool {
+  matches!(codec_id, AVCodecID::Vp8 | AVCodecID::Vp9) && encoder_name.starts_with("libvpx")
+}
+
+/// Encoder flags and reported metadata for one frame
+pub(crate) struct ReferencePlan {
+  /// Value for the "vp8-flags" frame metadata entry (0 = encoder default)
+  pub flags: u32,
+  /// Encode as a key frame because none of the requested references is held
+  pub force_key_frame: bool,
+  pub metadata: ReferenceControlMetadata,
+}
+
+/// Long-term reference slots of one encoder (worker thread)
+#[derive(Default)]
+pub(crate) struct LongTermReferences {
+  /// LTR id held by the GOLDEN and ALTREF buffers
+  slots: [Option<u32>; 2],
+  /// Slot replaced by the next mark when both are taken
+  next_slot: usize,
+  /// Set once a frame was marked; the slots are then managed explicitly
+  active: bool,
+}
+
+impl LongTermReferences {
+  /// Whether chunk metadata should carry `referenceControl`
+  pub fn is_active(&self) -> bool {
+    self.active
+  }
+
+  /// Plan the buffer flags for a frame
+  ///
+  /// `reference_only` ids that are no longer held (replaced by a later mark or
+  /// a key frame) are skipped; if none is left the frame becomes a key frame.
+  pub fn plan(
+    &mut self,
+    mark: Option<u32>,
+    reference_only: Option<&[u32]>,
+    key_frame: bool,
+  ) -> ReferencePlan {
+    let mut force_key_frame = false;
+    let mut metadata = ReferenceControlMetadata::default();
+
+    let referenced_slots: Option<Vec<usize>> = reference_only.map(|ids| {
+      (0..self.slots.len())
+        .filter(|&slot| self.slots[slot].is_some_and(|held| ids.contains(&held)))
+        .collect()
+    });
+    if !key_frame
+      && referenced_slots
+        .as_ref()
+        .is_some_and(|slots| slots.is_empty())
+    {
+      force_key_frame = true;
+    }
+
+    let is_key = key_frame || force_key_frame;
+    if is_key {
+      // A key frame refreshes every reference buffer
+      self.slots = [None, None];
+      self.next_slot = 0;
+    }
+
+    let mut flags = 0;
+    if self.active || mark.is_some() {
+      flags |= VP8_EFLAG_NO_UPD_ENTROPY;
+      for (_, no_update) in SLOT_FLAGS {
+        flags |= no_update;
+      }
+    }
+
+    if !is_key {
+      match &referenced_slots {
+        Some(slots) => {
+          flags |= VP8_EFLAG_NO_REF_LAST;
+          for (slot, (no_ref, _)) in SLOT_FLAGS.iter().enumerate() {
+            if slots.contains(&slot) {
+              metadata.references.extend(self.slots[slot]);
+            } else {
+              flags |= no_ref;
+            }
+          }
+        }
+        None => {
+          metadata.references_previous_frame = true;
+          if self.active || mark.is_some() {
+            // Plain frames only predict from the previous frame, so the
+            // metadata is exact
+            for (no_ref, _) in SLOT_FLAGS {
+              flags |= no_ref;
+            }
+          }
+        }
+      }
+    }
+
+    if let Some(id) = mark {
+      let slot = self
+        .slots
+        .iter()
+        .position(|held| *held == Some(id))
+        .or_else(|| self.slots.iter().position(Option::is_none))
+        .unwrap_or(self.next_slot);
+      self.slots[slot] = Some(id);
+      self.next_slot = (slot + 1) % self.slots.len();
+      flags &= !SLOT_FLAGS[slot].1;
+      self.active = true;
+      metadata.long_term_reference = Some(id);
+    }
+
+    ReferencePlan {
+      flags,
+      force_key_frame,
+      metadata,
+    }
+  }
+
+  /// An encoder-chosen key frame replaced every long-term reference
+  ///
+  /// `marked` is the id the key frame itself was stored as, if any.
+  pub fn on_key_frame(&mut self, marked: Option<u32>) {
+    self.slots = [marked, None];
+    self.next_slot = if marked.is_some() { 1 } else { 0 };
+  }
+}
@@ -0,0 +1,151 @@
+//! Scene Cut Detection - key frames at hard cuts for `sceneCutDetection`
+//!
+//! Encoders differ in whether they place key frames at scene changes, and
+//! most hardware encoders never do. The detector runs on the encoder worker
+//! before each frame is submitted and compares a luma histogram with the
+//! previous frame's. When the histograms differ by more than a threshold the
+//! frame is forced to a key frame, so the behaviour is the same for every
+//! encoder.
+//!
+//! Histograms ignore where pixels are, so pans and moving objects barely
+//! change them, while a cut to a different shot usually changes most bins.
+//! The luma plane is sampled on a 2x2 grid, which keeps the cost well below
+//! a scaler pass.
+
+use crate::codec::Frame;
+use crate::ffi::AVPixelFormat;
+
+/// Number of luma histogram bins
+const HISTOGRAM_BINS: usize = 64;
+
+/// Histogram distance in [0, 1] above which a frame starts a new scene
+const SCENE_CUT_THRESHOLD: f64 = 0.4;
+
+/// Frames after a scene cut before another one may be detected
+///
+/// Suppresses repeated key frames for flashes and fades, where several
+/// consecutive frames each differ strongly from the one before.
+const MIN_SCENE_CUT_INTERVAL: u32 = 8;
+
+type Histogram = [u32; HISTOGRAM_BINS];
+
+/// Luma histogram comparison between consecutive frames
+#[derive(Debug, Default)]
+pub(crate) struct SceneCutDetector {
+  /// Histogram of the previous frame
+  previous: Option<Histogram>,
+  /// Frames since the last detected cut (None before the first)
+  since_cut: Option<u32>,
+}
+
+impl SceneCutDetector {
+  /// Whether `frame` starts a new scene
+  ///
+  /// Frames whose luma cannot be read (GPU frames, packed RGB) are skipped
+  /// and do not replace the previous histogram.
+  pub(crate) fn detect(&mut self, frame: &Frame) -> bool {
+    let Some(histogram) = luma_histogram(frame) else {
+      return false;
+    };
+    let distance = self
+      .previous
+      .as_ref()
+      .map_or(0.0, |previous| histogram_distance(previous, &histogram));
+    self.previous = Some(histogram);
+
+    let ready = self
+      .since_cut
+      .is_none_or(|frames| frames >= MIN_SCENE_CUT_INTERVAL);
+    if distance > SCENE_CUT_THRESHOLD && ready {
+      self.since_cut = Some(1);
+      true
+    } else {
+      self.since_cut = self.since_cut.map(|frames| frames.saturating_add(1));
+      false
+    }
+  }
+}
+
+/// Bits per luma sample of the formats with a readable luma plane 0
+fn luma_bit_depth(format: AVPixelFormat) -> Option<u32> {
+  match format {
+    AVPixelFormat::Yuv420p
+    | AVPixelFormat::Yuva420p
+    | AVPixelFormat::Yuv422p
+    | AVPixelFormat::Yuv444p
+    | AVPixelFormat::Nv12 => Some(8),
+    AVPixelFormat::Yuv420p10le | AVPixelFormat::Yuva420p10le => Some(10),
+    _ => None,
+  }
+}
+
+fn luma_histogram(frame: &Frame) -> Option<Histogram> {
+  let bit_depth = luma_bit_depth(frame.format())?;
+  let width = frame.width() as usize;
+  let height = frame.height() as usize;
+  let ptr = frame.data(0);
+  let stride = frame.linesize(0);
+  let bytes_per_sample = if bit_depth > 8 { 2 } else { 1 };
+  if ptr.is_null() || stride <= 0 || width == 0 || height == 0 {
+    return None;
+  }
+  let stride = stride as usize;
+  if stride < width * bytes_per_sample {
+    return None;
+  }
+  // SAFETY: FFmpeg allocates `linesize * height` bytes for the luma plane
+  let data = unsafe { std::slice::from_raw_parts(ptr, stride * height) };
+
+  let shift = bit_depth - HISTOGRAM_BINS.trailing_zeros();
+  let mut histogram = [0u32; HISTOGRAM_BINS];
+  for y in (0..height).step_by(2) {
+    let row = &data[y * stride..];
+    for x in (0..width).step_by(2) {
+      let sample = if bytes_per_sample == 2 {
+        u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]) as u32
+      } else {
+        row[x] as u32
+      };
+      histogram[(sample >> shift).min(HISTOGRAM_BINS as u32 - 1) as usize] += 1;
+    }
+  }
+  Some(histogram)
+}
+
+/// Share of samples that would have to move bins to turn `a` into `b`
+fn histogram_distance(a: &Histogram, b: &Histogram) -> f64 {
+  let total: u64 = a.iter().map(|&n| n as u64).sum();
+  if total == 0 {
+    return 0.0;
+  }
+  let difference: u64 = a
+    .iter()
+    .zip(b)
+    .map(|(&x, &y)| (x as i64 - y as i64).unsigned_abs())
+    .sum();
+  difference as f64 / (2 * total) as f64
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn histogram(values: &[(usize, u32)]) -> Histogram {
+    let mut h = [0u32; HISTOGRAM_BINS];
+    for &(bin, count) in values {
+      h[bin] = count;
+    }
+    h
+  }
+
+  #[test]
+  fn distance_is_the_share_of_moved_samples() {
+    let a = histogram(&[(10, 100)]);
+    assert_eq!(histogram_distance(&a, &a), 0.0);
+    assert_eq!(histogram_distance(&a, &histogram(&[(50, 100)])), 1.0);
+    assert_eq!(
+      histogram_distance(&a, &histogram(&[(10, 75), (11, 25)])),
+      0.25
+    );
+  }
+}
@@ -0,0 +1,465 @@
+//! SegmentedMp4Muxer - MP4 recording rotated into files of a fixed duration
+//!
+//! Long recordings are split into independent MP4 files. A segment only ends
+//! right before a key chunk, so every file starts with a key frame and no
+//! chunk is dropped or duplicated at the boundary. Each new file gets the
+//! same track configs, including the latest codec description seen in chunk
+//! metadata, and its timestamps start at zero.
+//!
+//! The muxer cannot make an encoder produce a key frame. `rotationPending`
+//! tells the caller that the open segment is waiting for one, so the next
+//! `encode()` can pass `keyFrame: true` instead of waiting for the encoder's
+//! next scheduled key frame.
+
+use crate::codec::muxer::MuxerOptions;
+use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
+use crate::webcodecs::encoded_video_chunk::{EncodedVideoChunk, EncodedVideoChunkType};
+use crate::webcodecs::mp4_muxer::{Mp4AudioTrackConfig, Mp4Format, Mp4VideoTrackConfig};
+use crate::webcodecs::muxer_base::{
+  EncodedAudioChunkMetadataJs, EncodedVideoChunkMetadataJs, GenericAudioTrackConfig,
+  GenericVideoTrackConfig, MuxerInner, lock_muxer_inner, lock_muxer_inner_mut,
+};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::sync::Mutex;
+
+// ============================================================================
+// SegmentedMp4MuxerInit - Initialization options
+// ============================================================================
+
+/// Initialization options for SegmentedMp4Muxer
+pub struct SegmentedMp4MuxerInit {
+  /// Target segment duration in microseconds
+  pub segment_duration: i64,
+  /// Path for each segment, called with the segment index
+  pub filename: Option<FunctionRef<u32, String>>,
+  /// Move the moov atom of every segment to the beginning
+  pub fast_start: bool,
+  /// Omit the "Lavf" encoder tag and write in bitexact mode
+  pub omit_encoder_tags: bool,
+}
+
+impl FromNapiValue for SegmentedMp4MuxerInit {
+  unsafe fn from_napi_value(
+    env: napi::sys::napi_env,
+    value: napi::sys::napi_value,
+  ) -> Result<Self> {
+    let env_wrapper = Env::from_raw(env);
+    let obj = unsafe { Object::from_napi_value(env, value)? };
+
+    let segment_duration = match obj.get_named_property::<Option<f64>>("segmentDuration") {
+      Ok(Some(duration)) if duration.is_finite() && duration >= 1.0 => duration as i64,
+      _ => {
+        env_wrapper.throw_type_error("segmentDuration must be a positive number", None)?;
+        return Err(Error::new(
+          Status::InvalidArg,
+          "segmentDuration must be a positive number",
+        ));
+      }
+    };
+
+    let filename = match obj.get_named_property::<Option<Function<u32, String>>>("filename") {
+      Ok(Some(func)) => Some(func.create_ref()?),
+      _ => None,
+    };
+
+    let fast_start = obj
+      .get_named_property::<Option<bool>>("fastStart")?
+      .unwrap_or(false);
+    let omit_encoder_tags = obj
+      .get_named_property::<Option<bool>>("omitEncoderTags")?
+      .unwrap_or(false);
+
+    Ok(SegmentedMp4MuxerInit {
+      segment_duration,
+      filename,
+      fast_start,
+      omit_encoder_tags,
+    })
+  }
+}
+
+/// A finished segment of a SegmentedMp4Muxer recording
+#[napi(object)]
+pub struct Mp4Segment {
+  /// Segment index, starting at 0
+  pub index: u32,
+  /// Timestamp of the first chunk in microseconds, in the input's timeline
+  pub start_timestamp: i64,
+  /// Segment duration in microseconds
+  pub duration: i64,
+  /// File the segment was written to (when `filename` is set)
+  pub path: Option<String>,
+  /// MP4 data of the segment (when `filename` is not set)
+  pub data: Option<Uint8Array>,
+}
+
+/// Segment taken out of the muxer, before it is written or returned
+struct FinishedSegment {
+  index: u32,
+  start_timestamp: i64,
+  duration: i64,
+  data: Vec<u8>,
+}
+
+// ============================================================================
+// Segmented muxer state
+// ============================================================================
+
+struct SegmentedInner {
+  /// Muxer of the open segment
+  muxer: MuxerInner<Mp4Format>,
+  muxer_options: MuxerOptions,
+  /// Track configs replayed into every segment
+  video_track: Option<GenericVideoTrackConfig>,
+  audio_track: Option<GenericAudioTrackConfig>,
+  /// Index of the open segment
+  index: u32,
+  /// Timestamp of the first chunk of the open segment (None before any chunk)
+  segment_start: Option<i64>,
+  /// End of the latest chunk of the open segment
+  segment_end: i64,
+  /// rotate() was called and the next key chunk starts a new segment
+  rotate_requested: bool,
+}
+
+impl SegmentedInner {
+  fn new(muxer_options: MuxerOptions) -> Result<Self> {
+    Ok(Self {
+      muxer: MuxerInner::new_buffer(muxer_options.clone())?,
+      muxer_options,
+      video_track: None,
+      audio_track: None,
+      index: 0,
+      segment_start: None,
+      segment_end: 0,
+      rotate_requested: false,
+    })
+  }
+
+  /// Whether a key chunk at `timestamp` ends the open segment
+  fn should_rotate(&self, timestamp: i64, segment_duration: i64) -> bool {
+    self
+      .segment_start
+      .is_some_and(|start| self.rotate_requested || timestamp - start >= segment_duration)
+  }
+
+  /// Start the first segment or account for a chunk in the open one
+  fn record_chunk(&mut self, timestamp: i64, duration: Option<i64>) {
+    if self.segment_start.is_none() {
+      self.segment_start = Some(timestamp);
+      self.muxer.timestamp_offset = timestamp;
+    }
+    self.segment_end = self
+      .segment_end
+      .max(timestamp + duration.unwrap_or(0).max(0));
+  }
+
+  /// Finalize the open segment and open the next one starting at `next_start`
+  fn rotate(&mut self, next_start: i64) -> Result<FinishedSegment> {
+    let mut muxer = MuxerInner::<Mp4Format>::new_buffer(self.muxer_options.clone())?;
+    muxer.timestamp_offset = next_start;
+    if let Some(config) = &self.video_track {
+      muxer.add_video_track(config.clone())?;
+    }
+    if let Some(config) = &self.audio_track {
+      muxer.add_audio_track(config.clone())?;
+    }
+
+    let mut finished = std::mem::replace(&mut self.muxer, muxer);
+    let start = self.segment_start.unwrap_or(next_start);
+    let segment = FinishedSegment {
+      index: self.index,
+      start_timestamp: start,
+      duration: next_start - start,
+      data: finished.finalize()?,
+    };
+
+    self.index += 1;
+    self.segment_start = Some(next_start);
+    self.segment_end = next_start;
+    self.rotate_requested = false;
+    Ok(segment)
+  }
+}
+
+// ============================================================================
+// SegmentedMp4Muxer - NAPI class wrapper
+// ============================================================================
+
+/// MP4 muxer that rotates output into independent files of a fixed duration
+///
+/// Usage:
+/// ```javascript
+/// const muxer = new SegmentedMp4Muxer({
+///   segmentDuration: 10 * 60 * 1_000_000,
+///   filename: (index) => `recording-${index}.mp4`,
+/// });
+/// muxer.addVideoTrack({ codec: 'avc1.42001E', width: 1920, height: 1080 });
+///
+/// encoder.configure({
+///   output: (chunk, metadata) => muxer.addVideoChunk(chunk, metadata)
+/// });
+/// encoder.encode(frame, { keyFrame: muxer.rotationPending });
+///
+/// // Write the last segment
+/// muxer.finalize();
+/// ```
+#[napi]
+pub struct SegmentedMp4Muxer {
+  inner: Mutex<Option<SegmentedInner>>,
+  segment_duration: i64,
+  filename: Option<FunctionRef<u32, String>>,
+}
+
+#[napi]
+impl SegmentedMp4Muxer {
+  /// Create a new segmented MP4 muxer
+  #[napi(constructor)]
+  pub fn new(init: SegmentedMp4MuxerInit) -> Result<Self> {
+    let muxer_options = MuxerOptions {
+      fast_start: init.fast_start,
+      fragmented: false,
+      live: false,
+      omit_encoder_tags: init.omit_encoder_tags,
+      preserve_timestamps: false,
+    };
+
+    Ok(Self {
+      inner: Mutex::new(Some(SegmentedInner::new(muxer_options)?)),
+      segment_duration: init.segment_duration,
+      filename: init.filename,
+    })
+  }
+
+  /// Add a video track, repeated in every segment
+  ///
+  /// Must be called before adding any chunks.
+  #[napi]
+  pub fn add_video_track(&self, env: Env, config: Mp4VideoTrackConfig) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    let Some(generic_config) = config.into_generic(&env)? else {
+      return Ok(());
+    };
+    inner.muxer.add_video_track(generic_config.clone())?;
+    inner.video_track = Some(generic_config);
+    Ok(())
+  }
+
+  /// Add an audio track, repeated in every segment
+  ///
+  /// Must be called before adding any chunks.
+  #[napi]
+  pub fn add_audio_track(&self, config: Mp4AudioTrackConfig) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    let generic_config = config.into_generic()?;
+    // Explicit padding belongs to the last segment; each segment otherwise
+    // takes its priming and padding from the skip marks of its own chunks
+    let generic_config = GenericAudioTrackConfig {
+      padding: None,
+      ..generic_config
+    };
+    inner.muxer.add_audio_track(generic_config.clone())?;
+    inner.audio_track = Some(GenericAudioTrackConfig {
+      encoder_delay: None,
+      ..generic_config
+    });
+    Ok(())
+  }
+
+  /// Add an encoded video chunk
+  ///
+  /// A key chunk at or past the segment duration (or after `rotate()`)
+  /// finishes the open segment and starts the next one; the finished segment
+  /// is returned. Returns null otherwise.
+  #[napi]
+  pub fn add_video_chunk(
+    &self,
+    env: Env,
+    chunk: &EncodedVideoChunk,
+    metadata: Option<EncodedVideoChunkMetadataJs>,
+  ) -> Result<Option<Mp4Segment>> {
+    let finished = {
+      lock_muxer_inner_mut!(self => _guard, inner);
+      let timestamp = chunk.timestamp()?;
+      let is_key = chunk.chunk_type()? == EncodedVideoChunkType::Key;
+
+      // Later segments start with the latest description, not the initial one
+      if let Some(description) = metadata
+        .as_ref()
+        .and_then(|m| m.decoder_config.as_ref())
+        .and_then(|c| c.description.as_ref())
+        .filter(|d| !d.is_empty())
+        && let Some(track) = inner.video_track.as_mut()
+      {
+        track.extradata = Some(description.to_vec());
+      }
+
+      let finished = if is_key && inner.should_rotate(timestamp, self.segment_duration) {
+        Some(inner.rotate(timestamp)?)
+      } else {
+        None
+      };
+      inner.record_chunk(timestamp, chunk.duration()?);
+      inner.muxer.add_video_chunk(chunk, metadata.as_ref())?;
+      finished
+    };
+
+    finished
+      .map(|segment| self.deliver(&env, segment))
+      .transpose()
+  }
+
+  /// Add an encoded audio chunk to the open segment
+  ///
+  /// With a video track, segments follow the video key chunks and audio
+  /// chunks go to the segment that is open when they are added, so add them
+  /// interleaved with the video in timestamp order. An audio-only recording
+  /// rotates on audio chunks, which are all key chunks.
+  #[napi]
+  pub fn add_audio_chunk(
+    &self,
+    env: Env,
+    chunk: &EncodedAudioChunk,
+    metadata: Option<EncodedAudioChunkMetadataJs>,
+  ) -> Result<Option<Mp4Segment>> {
+    let finished = {
+      lock_muxer_inner_mut!(self => _guard, inner);
+      let timestamp = chunk.timestamp()?;
+
+      if let Some(description) = metadata
+        .as_ref()
+        .and_then(|m| m.decoder_config.as_ref())
+        .and_then(|c| c.description.as_ref())
+        .filter(|d| !d.is_empty())
+        && let Some(track) = inner.audio_track.as_mut()
+      {
+        track.extradata = Some(description.to_vec());
+      }
+
+      let audio_only = inner.video_track.is_none();
+      let finished = if audio_only && inner.should_rotate(timestamp, self.segment_duration) {
+        Some(inner.rotate(timestamp)?)
+      } else {
+        None
+      };
+      inner.record_chunk(timestamp, chunk.duration()?);
+      inner.muxer.add_audio_chunk(chunk, metadata.as_ref())?;
+      finished
+    };
+
+    finished
+      .map(|segment| self.deliver(&env, segment))
+      .transpose()
+  }
+
+  /// Start a new segment at the next key chunk, regardless of duration
+  #[napi]
+  pub fn rotate(&self) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    if inner.segment_start.is_some() {
+      inner.rotate_requested = true;
+    }
+    Ok(())
+  }
+
+  /// Whether the open segment is waiting for a key chunk to end
+  ///
+  /// True after `rotate()` or once the segment has reached its duration.
+  /// Encode the next frame with `keyFrame: true` to end it right away.
+  #[napi(getter)]
+  pub fn rotation_pending(&self) -> Result<bool> {
+    lock_muxer_inner!(self => _guard, inner);
+    Ok(inner.segment_start.is_some_and(|start| {
+      inner.rotate_requested || inner.segment_end - start >= self.segment_duration
+    }))
+  }
+
+  /// Index of the open segment
+  #[napi(getter)]
+  pub fn segment_index(&self) -> Result<u32> {
+    lock_muxer_inner!(self => _guard, inner);
+    Ok(inner.index)
+  }
+
+  /// Finish the open segment and return it
+  ///
+  /// Returns null when no chunk was added since the last rotation.
+  #[napi]
+  pub fn finalize(&self, env: Env) -> Result<Option<Mp4Segment>> {
+    let finished = {
+      let mut guard = self
+        .inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+      let Some(mut inner) = guard.take() else {
+        return Err(Error::new(Status::GenericFailure, "Muxer is closed"));
+      };
+      let Some(start) = inner.segment_start else {
+        return Ok(None);
+      };
+      FinishedSegment {
+        index: inner.index,
+        start_timestamp: start,
+        duration: inner.segment_end - start,
+        data: inner.muxer.finalize()?,
+      }
+    };
+
+    self.deliver(&env, finished).map(Some)
+  }
+
+  /// Close the muxer and discard the open segment
+  #[napi]
+  pub fn close(&self) -> Result<()> {
+    let mut guard = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    *guard = None;
+    Ok(())
+  }
+
+  /// Get the state of the open segment's muxer ("closed" after finalize or close)
+  #[napi(getter)]
+  pub fn state(&self) -> Result<String> {
+    let guard = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+    let state = match guard.as_ref() {
+      Some(inner) => inner.muxer.state_string(),
+      None => "closed",
+    };
+
+    Ok(state.to_string())
+  }
+
+  /// Write a finished segment to its file, or hand back its data
+  ///
+  /// Runs without the lock held, so the filename callback may use the muxer.
+  fn deliver(&self, env: &Env, segment: FinishedSegment) -> Result<Mp4Segment> {
+    let (path, data) = match &self.filename {
+      Some(filename) => {
+        let path = filename.borrow_back(env)?.call(segment.index)?;
+        std::fs::write(&path, &segment.data).map_err(|e| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Failed to write segment {}: {}", path, e),
+          )
+        })?;
+        (Some(path), None)
+      }
+      None => (None, Some(Uint8Array::new(segment.data))),
+    };
+
+    Ok(Mp4Segment {
+      index: segment.index,
+      start_timestamp: segment.start_timestamp,
+      duration: segment.duration,
+      path,
+      data,
+    })
+  }
+}
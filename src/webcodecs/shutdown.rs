@@ -0,0 +1,64 @@
+//! Codec teardown when the Node.js environment exits
+//!
+//! Codec workers deliver output through threadsafe functions. Once the
+//! environment is being torn down those calls can no longer reach JS, and
+//! joining a worker that is still busy (e.g. flushing AV1) holds up process
+//! exit. Codecs therefore share a per-environment flag, set from an env cleanup
+//! hook, that tells their workers to skip queued work, and bound how long
+//! `Drop` waits for a worker before detaching it.
+
+use napi::bindgen_prelude::*;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How long `Drop` waits for a codec worker before detaching it
+const WORKER_JOIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+thread_local! {
+  /// Shutdown flag of the environment running on this thread
+  static ENV_SHUTDOWN: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Get the shutdown flag of the current environment
+///
+/// Each environment (the main thread or a worker_threads Worker) runs on its
+/// own thread, so the flag and its cleanup hook are created once per thread.
+pub(crate) fn env_shutdown_flag(env: &Env) -> Result<Arc<AtomicBool>> {
+  ENV_SHUTDOWN.with(|cell| {
+    if let Some(flag) = cell.borrow().as_ref() {
+      return Ok(flag.clone());
+    }
+
+    let flag = Arc::new(AtomicBool::new(false));
+    env.add_env_cleanup_hook(flag.clone(), |flag| {
+      flag.store(true, Ordering::SeqCst);
+    })?;
+    *cell.borrow_mut() = Some(flag.clone());
+    Ok(flag)
+  })
+}
+
+/// Join a codec worker, detaching it if it does not finish in time
+///
+/// Returns false if the worker was left running. It still owns its share of
+/// the codec state and releases it when the current command completes.
+pub(crate) fn join_worker(handle: JoinHandle<()>, codec: &str) -> bool {
+  let deadline = Instant::now() + WORKER_JOIN_TIMEOUT;
+  while !handle.is_finished() {
+    if Instant::now() >= deadline {
+      tracing::warn!(
+        target: "webcodecs",
+        codec,
+        "Worker did not stop within {:?}, detaching",
+        WORKER_JOIN_TIMEOUT
+      );
+      return false;
+    }
+    std::thread::sleep(Duration::from_millis(1));
+  }
+  let _ = handle.join();
+  true
+}
@@ -0,0 +1,240 @@
+//! Track Analysis - Packet timing and bitrate statistics for demuxer tracks
+//!
+//! `analyzeTrack()` reads a track's packet timestamps and sizes without
+//! decoding, from a second demuxer context over the same source so the
+//! demuxer's own read position is untouched. Container frame rates
+//! (avg_frame_rate) describe VFR content such as screen recordings poorly;
+//! the interval distribution shows the real frame timing.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::codec::demuxer::DemuxerContext;
+use crate::ffi::types::AV_NOPTS_VALUE;
+use crate::webcodecs::demuxer_base::convert_timestamp;
+
+/// Packets scanned when `maxPackets` is not given
+const DEFAULT_MAX_PACKETS: u32 = 100_000;
+
+/// Bitrate window length when `bitrateWindowUs` is not given
+const DEFAULT_BITRATE_WINDOW_US: i64 = 1_000_000;
+
+/// Histogram bucket width for frame intervals
+const HISTOGRAM_BUCKET_US: i64 = 1000;
+
+/// Options for analyzeTrack()
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct TrackAnalysisOptions {
+  /// Maximum number of the track's packets to scan (default: 100000)
+  pub max_packets: Option<u32>,
+  /// Length of each bitrate window in microseconds (default: 1000000)
+  pub bitrate_window_us: Option<i64>,
+}
+
+/// Number of frame intervals in one histogram bucket
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct FrameIntervalBucket {
+  /// Interval rounded to the nearest millisecond, in microseconds
+  pub interval_us: i64,
+  /// Number of intervals in this bucket
+  pub count: u32,
+}
+
+/// Bitrate of the packets presented in one time window
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BitrateWindow {
+  /// Window start in microseconds
+  pub start_us: i64,
+  /// Window length in microseconds (the last window may be shorter)
+  pub duration_us: i64,
+  /// Bits per second
+  pub bitrate: f64,
+}
+
+/// Packet timing and bitrate statistics of a track
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct TrackAnalysis {
+  /// Track index
+  pub track_id: i32,
+  /// Number of packets scanned
+  pub packet_count: u32,
+  /// Whether the scan reached the end of the track (false when stopped by maxPackets)
+  pub complete: bool,
+  /// Shortest interval between consecutive presentation timestamps, in microseconds
+  pub min_interval_us: Option<i64>,
+  /// Longest interval between consecutive presentation timestamps, in microseconds
+  pub max_interval_us: Option<i64>,
+  /// Mean interval between consecutive presentation timestamps, in microseconds
+  pub mean_interval_us: Option<f64>,
+  /// Frame interval distribution, sorted by interval
+  pub interval_histogram: Vec<FrameIntervalBucket>,
+  /// Average bitrate over the scanned range in bits per second
+  pub average_bitrate: Option<f64>,
+  /// Bitrate per time window, in presentation order
+  pub bitrate_windows: Vec<BitrateWindow>,
+  /// Whether frame intervals vary by more than timestamp rounding
+  pub is_variable_frame_rate: bool,
+}
+
+/// Scan up to `max_packets` packets of a track and summarize their timing
+pub(crate) fn analyze_track(
+  mut demuxer: DemuxerContext,
+  track_index: i32,
+  options: &TrackAnalysisOptions,
+) -> Result<TrackAnalysis> {
+  let max_packets = options.max_packets.unwrap_or(DEFAULT_MAX_PACKETS);
+  if max_packets == 0 {
+    return Err(Error::new(
+      Status::InvalidArg,
+      "maxPackets must be greater than 0",
+    ));
+  }
+  let window_us = options
+    .bitrate_window_us
+    .unwrap_or(DEFAULT_BITRATE_WINDOW_US);
+  if window_us <= 0 {
+    return Err(Error::new(
+      Status::InvalidArg,
+      "bitrateWindowUs must be greater than 0",
+    ));
+  }
+
+  let time_base = demuxer
+    .get_stream(track_index)
+    .map(|s| s.time_base)
+    .ok_or_else(|| {
+      Error::new(
+        Status::InvalidArg,
+        format!("Invalid track index: {}", track_index),
+      )
+    })?;
+
+  // (presentation timestamp, duration, size), in microseconds and bytes
+  let mut packets: Vec<(i64, i64, u64)> = Vec::new();
+  let mut complete = false;
+  while packets.len() < max_packets as usize {
+    match demuxer.read_packet() {
+      Ok(Some((packet, stream_index))) => {
+        if stream_index != track_index {
+          continue;
+        }
+        let ts = if packet.pts() != AV_NOPTS_VALUE {
+          packet.pts()
+        } else {
+          packet.dts()
+        };
+        if ts == AV_NOPTS_VALUE {
+          continue;
+        }
+        packets.push((
+          convert_timestamp(ts, Some(time_base)),
+          convert_timestamp(packet.duration().max(0), Some(time_base)),
+          packet.size().max(0) as u64,
+        ));
+      }
+      Ok(None) => {
+        complete = true;
+        break;
+      }
+      Err(e) => {
+        return Err(Error::new(
+          Status::GenericFailure,
+          format!("Failed to read packet: {}", e),
+        ));
+      }
+    }
+  }
+
+  // Packets come in decode order; intervals are measured in presentation order
+  packets.sort_by_key(|&(pts, _, _)| pts);
+  let intervals: Vec<i64> = packets.windows(2).map(|w| w[1].0 - w[0].0).collect();
+
+  let mut interval_histogram: Vec<FrameIntervalBucket> = Vec::new();
+  let mut rounded: Vec<i64> = intervals
+    .iter()
+    .map(|&i| (i + HISTOGRAM_BUCKET_US / 2).div_euclid(HISTOGRAM_BUCKET_US) * HISTOGRAM_BUCKET_US)
+    .collect();
+  rounded.sort_unstable();
+  for interval_us in rounded {
+    match interval_histogram.last_mut() {
+      Some(bucket) if bucket.interval_us == interval_us => bucket.count += 1,
+      _ => interval_histogram.push(FrameIntervalBucket {
+        interval_us,
+        count: 1,
+      }),
+    }
+  }
+
+  let (start_us, end_us) = match (packets.first(), packets.last()) {
+    (Some(&(first, _, _)), Some(&(last, last_duration, _))) => {
+      // Without a packet duration, assume the last frame lasts one mean interval
+      let last_frame_us = if last_duration > 0 || intervals.is_empty() {
+        last_duration
+      } else {
+        (last - first) / intervals.len() as i64
+      };
+      (first, last + last_frame_us)
+    }
+    _ => (0, 0),
+  };
+  let total_bytes: u64 = packets.iter().map(|&(_, _, size)| size).sum();
+  let average_bitrate =
+    (end_us > start_us).then(|| total_bytes as f64 * 8.0 * 1e6 / (end_us - start_us) as f64);
+
+  let mut bitrate_windows: Vec<BitrateWindow> = Vec::new();
+  if end_us > start_us {
+    let window_count = ((end_us - start_us) + window_us - 1) / window_us;
+    let mut window_bytes = vec![0u64; window_count as usize];
+    for &(pts, _, size) in &packets {
+      let index = ((pts - start_us) / window_us).min(window_count - 1);
+      window_bytes[index as usize] += size;
+    }
+    for (index, bytes) in window_bytes.into_iter().enumerate() {
+      let window_start = start_us + index as i64 * window_us;
+      let duration_us = window_us.min(end_us - window_start);
+      bitrate_windows.push(BitrateWindow {
+        start_us: window_start,
+        duration_us,
+        bitrate: bytes as f64 * 8.0 * 1e6 / duration_us as f64,
+      });
+    }
+  }
+
+  Ok(TrackAnalysis {
+    track_id: track_index,
+    packet_count: packets.len() as u32,
+    complete,
+    min_interval_us: intervals.iter().copied().min(),
+    max_interval_us: intervals.iter().copied().max(),
+    mean_interval_us: (!intervals.is_empty())
+      .then(|| intervals.iter().sum::<i64>() as f64 / intervals.len() as f64),
+    is_variable_frame_rate: is_variable_frame_rate(&intervals),
+    interval_histogram,
+    average_bitrate,
+    bitrate_windows,
+  })
+}
+
+/// Whether intervals vary beyond timestamp rounding
+///
+/// CFR content rounded to the container time base jitters by a tick or so.
+/// A track is VFR when more than 1% of its intervals differ from the median
+/// by more than 10%.
+fn is_variable_frame_rate(intervals: &[i64]) -> bool {
+  if intervals.len() < 2 {
+    return false;
+  }
+  let mut sorted = intervals.to_vec();
+  sorted.sort_unstable();
+  let median = sorted[sorted.len() / 2];
+  let tolerance = (median / 10).max(1);
+  let deviating = intervals
+    .iter()
+    .filter(|&&i| (i - median).abs() > tolerance)
+    .count();
+  deviating * 100 > intervals.len()
+}
@@ -0,0 +1,232 @@
+//! extractTrack - lossless single-track extraction into a new container
+//!
+//! Stream-copies the packets of one track (e.g. the AAC track of an MP4 into
+//! an .m4a) without decoding. Payloads, PTS/DTS, flags and the codec
+//! extradata are carried over unchanged. Negative start timestamps from
+//! encoder priming are kept rather than shifted, so the MP4 muxer writes them
+//! back as an edit list.
+
+use crate::codec::demuxer::{DemuxerContext, MediaType, StreamInfo};
+use crate::codec::muxer::{
+  AudioStreamConfig, ContainerFormat, MuxerContext, MuxerOptions, MuxerOutput, VideoStreamConfig,
+};
+use crate::ffi::{AV_NOPTS_VALUE, AVPixelFormat, AVRational, AVSampleFormat, avutil::av_rescale_q};
+use crate::webcodecs::error::not_supported_error;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Container written by extractTrack()
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractTrackFormat {
+  /// MP4 (H.264, HEVC, AV1, AAC, MP3, FLAC, Opus)
+  #[napi(value = "mp4")]
+  Mp4,
+  /// MP4 holding a single audio track
+  #[napi(value = "m4a")]
+  M4a,
+  /// Matroska (any codec)
+  #[napi(value = "mkv")]
+  Mkv,
+  /// WebM (VP8, VP9, AV1, Opus, Vorbis)
+  #[napi(value = "webm")]
+  Webm,
+}
+
+impl ExtractTrackFormat {
+  fn container(self) -> ContainerFormat {
+    match self {
+      ExtractTrackFormat::Mp4 | ExtractTrackFormat::M4a => ContainerFormat::Mp4,
+      ExtractTrackFormat::Mkv => ContainerFormat::Mkv,
+      ExtractTrackFormat::Webm => ContainerFormat::WebM,
+    }
+  }
+}
+
+/// Options for extractTrack()
+#[napi(object)]
+#[derive(Default)]
+pub struct ExtractTrackOptions {
+  /// Output container (default: "m4a" for audio tracks, "mp4" for video tracks)
+  pub format: Option<ExtractTrackFormat>,
+}
+
+/// Copy every packet of `track_id` from `demuxer` into a new container
+fn extract(
+  mut demuxer: DemuxerContext,
+  track_id: i32,
+  format: Option<ExtractTrackFormat>,
+) -> Result<Vec<u8>> {
+  let stream = demuxer
+    .get_stream(track_id)
+    .cloned()
+    .ok_or_else(|| Error::new(Status::InvalidArg, format!("Track {} not found", track_id)))?;
+  let is_video = match stream.media_type {
+    MediaType::Video => true,
+    MediaType::Audio => false,
+    other => {
+      return Err(not_supported_error(&format!(
+        "Cannot extract {:?} track {}",
+        other, track_id
+      )));
+    }
+  };
+  let format = format.unwrap_or(if is_video {
+    ExtractTrackFormat::Mp4
+  } else {
+    ExtractTrackFormat::M4a
+  });
+  if is_video && format == ExtractTrackFormat::M4a {
+    return Err(not_supported_error("m4a holds audio tracks only"));
+  }
+
+  let container = format.container();
+  let mut muxer = MuxerContext::new(container, MuxerOutput::Buffer).map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to create muxer: {}", e),
+    )
+  })?;
+  let src_tb = AVRational::new(stream.time_base.0, stream.time_base.1);
+  let added = if is_video {
+    muxer.add_video_stream(&video_stream_config(&stream, container, src_tb))
+  } else {
+    muxer.add_audio_stream(&audio_stream_config(&stream, src_tb))
+  };
+  // The only way adding a stream fails before the header is an unsupported codec
+  let out_index = added.map_err(|_| {
+    not_supported_error(&format!(
+      "{:?} cannot be stored in {}",
+      stream.codec_id,
+      container.extension()
+    ))
+  })?;
+
+  muxer
+    .write_header(Some(&MuxerOptions {
+      preserve_timestamps: true,
+      ..Default::default()
+    }))
+    .map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to write header: {}", e),
+      )
+    })?;
+  // The muxer may pick its own time base for the track while writing the header
+  let dst_tb = if is_video {
+    muxer.video_time_base()
+  } else {
+    muxer.audio_time_base()
+  }
+  .unwrap_or(src_tb);
+  let rescale = |ts: i64| {
+    if ts == AV_NOPTS_VALUE {
+      ts
+    } else {
+      unsafe { av_rescale_q(ts, src_tb, dst_tb) }
+    }
+  };
+
+  while let Some((mut packet, index)) = demuxer.read_packet().map_err(|e| {
+    Error::new(
+      Status::GenericFailure,
+      format!("Failed to read packet: {}", e),
+    )
+  })? {
+    if index != track_id {
+      continue;
+    }
+    packet.set_stream_index(out_index);
+    packet.set_pts(rescale(packet.pts()));
+    packet.set_dts(rescale(packet.dts()));
+    packet.set_duration(rescale(packet.duration()));
+    muxer.write_packet(&mut packet).map_err(|e| {
+      Error::new(
+        Status::GenericFailure,
+        format!("Failed to write packet: {}", e),
+      )
+    })?;
+  }
+
+  muxer
+    .finalize()
+    .map_err(|e| Error::new(Status::GenericFailure, format!("Failed to finalize: {}", e)))?;
+  muxer
+    .take_buffer()
+    .ok_or_else(|| Error::new(Status::GenericFailure, "Muxer produced no output"))
+}
+
+fn video_stream_config(
+  stream: &StreamInfo,
+  container: ContainerFormat,
+  time_base: AVRational,
+) -> VideoStreamConfig {
+  VideoStreamConfig {
+    codec_id: stream.codec_id,
+    width: stream.width.unwrap_or(0),
+    height: stream.height.unwrap_or(0),
+    pixel_format: stream.pixel_format.unwrap_or(AVPixelFormat::Yuv420p),
+    time_base,
+    bitrate: None,
+    extradata: stream.extradata.clone(),
+    // Sample entry fourccs (avc1 vs avc3, hvc1 vs hev1) only mean something to MP4
+    codec_tag: (container == ContainerFormat::Mp4 && stream.codec_tag != 0)
+      .then_some(stream.codec_tag),
+    color: stream.color,
+    icc_profile: stream.icc_profile.clone(),
+    sample_aspect_ratio: None,
+    side_data: stream.side_data.clone(),
+  }
+}
+
+fn audio_stream_config(stream: &StreamInfo, time_base: AVRational) -> AudioStreamConfig {
+  AudioStreamConfig {
+    codec_id: stream.codec_id,
+    sample_rate: stream.sample_rate.unwrap_or(0),
+    channels: stream.channels.unwrap_or(0),
+    sample_format: stream.sample_format.unwrap_or(AVSampleFormat::Fltp),
+    time_base,
+    bitrate: None,
+    frame_size: None,
+    extradata: stream.extradata.clone(),
+  }
+}
+
+/// Losslessly copy one track of a media file into a new container
+///
+/// Packets are stream-copied without decoding, keeping their bytes, timing
+/// and the track's codec configuration. `trackId` is a track `index` as
+/// listed by a demuxer's `tracks`. Rejects with NotSupportedError when the
+/// codec cannot be stored in the requested format (e.g. AAC in WebM).
+///
+/// ```javascript
+/// const m4a = await extractTrack('movie.mp4', audioTrack.index, { format: 'm4a' });
+/// ```
+#[napi]
+pub async fn extract_track(
+  input: Either<String, Uint8Array>,
+  track_id: i32,
+  options: Option<ExtractTrackOptions>,
+) -> Result<Uint8Array> {
+  let format = options.unwrap_or_default().format;
+  tokio::task::spawn_blocking(move || {
+    let demuxer = match input {
+      Either::A(path) => DemuxerContext::open_file(&path).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open {}: {}", path, e),
+        )
+      })?,
+      Either::B(buffer) => DemuxerContext::open_buffer(buffer).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to open buffer: {}", e),
+        )
+      })?,
+    };
+    extract(demuxer, track_id, format).map(Uint8Array::from)
+  })
+  .await
+  .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
+}
@@ -5,15 +5,22 @@
 
 use crate::codec::{CodecContext, DecoderConfig, Frame, Packet, download_hw_frame};
 use crate::ffi::{AVCodecID, AVHWDeviceType, accessors::ffctx_set_hw_get_format};
+use crate::webcodecs::access_unit::{AccessUnitAssembler, InputGranularity};
+use crate::webcodecs::command_timing::{CodecQueueStats, CommandTimings, begin_command};
+use crate::webcodecs::decode_limits;
 use crate::webcodecs::encoded_video_chunk::InternalSlice;
 use crate::webcodecs::error::{
   DOMExceptionName, throw_data_error, throw_invalid_state_error, throw_type_error_unit,
 };
+use crate::webcodecs::frame_budget::FrameBudget;
+use crate::webcodecs::output_timeline::OutputTimeline;
+use crate::webcodecs::parameter_sets::{ParameterSetChange, ParameterSetTracker};
 use crate::webcodecs::promise_reject::{reject_with_dom_exception_async, reject_with_type_error};
-use crate::webcodecs::video_frame::VideoColorSpaceInit;
+use crate::webcodecs::shutdown;
+use crate::webcodecs::video_frame::{HdrDynamicMetadata, VideoColorSpaceInit, VideoFrameMetadata};
 use crate::webcodecs::{
-  CodecState, EncodedVideoChunk, EncodedVideoChunkInner, HardwareAcceleration, VideoDecoderConfig,
-  VideoFrame, convert_avcc_extradata_to_annexb, convert_avcc_to_annexb,
+  AlphaOption, CodecState, EncodedVideoChunk, EncodedVideoChunkInner, HardwareAcceleration,
+  VideoDecoderConfig, VideoFrame, convert_avcc_extradata_to_annexb, convert_avcc_to_annexb,
   convert_hvcc_extradata_to_annexb, is_avcc_extradata, is_avcc_format, is_hvcc_extradata,
 };
 use crossbeam::channel::{self, Receiver, Sender};
@@ -23,22 +30,37 @@ use napi::threadsafe_function::{
 };
 use napi_derive::napi;
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread::JoinHandle;
+use std::time::Instant;
 
 /// Type alias for output callback (takes VideoFrame)
 /// Using CalleeHandled: false for direct callbacks without error-first convention
 type OutputCallback =
   ThreadsafeFunction<VideoFrame, UnknownReturnValue, VideoFrame, Status, false, true>;
 
+/// Type alias for output callback reference (synchronous calls from main thread)
+type OutputCallbackRef = FunctionRef<VideoFrame, UnknownReturnValue>;
+
 /// Type alias for error callback (takes Error object)
 /// Using CalleeHandled: false because WebCodecs error callback receives Error directly,
 /// not error-first (err, result) style
 type ErrorCallback = ThreadsafeFunction<Error, UnknownReturnValue, Error, Status, false, true>;
 
+/// Type alias for the optional parameterSetChange callback (extension)
+type ParameterSetChangeCallback = ThreadsafeFunction<
+  ParameterSetChange,
+  UnknownReturnValue,
+  ParameterSetChange,
+  Status,
+  false,
+  true,
+>;
+
 // Note: For ondequeue, we use FunctionRef instead of ThreadsafeFunction
 // to support both getter and setter per WebCodecs spec
 
@@ -103,10 +125,25 @@ pub struct VideoDecoderEventListenerOptions {
   pub capture: Option<bool>,
 }
 
+/// Options for close() (extension)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct VideoDecoderCloseOptions {
+  /// Invalidate every output VideoFrame of this decoder that has not been closed yet,
+  /// freeing its pixel data now (default: false)
+  pub detach_frames: Option<bool>,
+}
+
 /// Commands sent to the worker thread
 enum WorkerCommand {
   /// Decode a video chunk
-  Decode(Arc<RwLock<Option<EncodedVideoChunkInner>>>),
+  Decode {
+    chunk: Arc<RwLock<Option<EncodedVideoChunkInner>>>,
+    /// Chunk timestamp, for the per-command tracing span
+    timestamp: i64,
+    /// When decode() was called (for queue latency tracking)
+    enqueued_at: Instant,
+  },
   /// Flush the decoder and send result back via response channel
   Flush(Sender<Result<()>>),
   /// Reconfigure the decoder with new config (W3C spec: control message)
@@ -123,6 +160,8 @@ pub struct VideoDecoderInit {
   pub error: ErrorCallback,
   /// Error callback reference - prevents GC from collecting the error callback
   pub error_ref: FunctionRef<Error, UnknownReturnValue>,
+  /// Called when in-band parameter sets replace the active ones (extension)
+  pub parameter_set_change: Option<ParameterSetChangeCallback>,
 }
 
 impl FromNapiValue for VideoDecoderInit {
@@ -176,11 +215,27 @@ impl FromNapiValue for VideoDecoderInit {
       .weak::<true>()
       .build()?;
 
+    // Get optional parameterSetChange callback (extension)
+    let parameter_set_change: Option<ParameterSetChangeCallback> = match obj
+      .get_named_property::<Option<Function<ParameterSetChange, UnknownReturnValue>>>(
+        "parameterSetChange",
+      ) {
+      Ok(Some(func)) => Some(
+        func
+          .build_threadsafe_function()
+          .callee_handled::<false>()
+          .weak::<true>()
+          .build()?,
+      ),
+      _ => None,
+    };
+
     Ok(VideoDecoderInit {
       output,
       output_ref,
       error,
       error_ref,
+      parameter_set_change,
     })
   }
 }
@@ -194,6 +249,18 @@ pub struct VideoDecoderSupport {
   pub config: VideoDecoderConfig,
 }
 
+/// How decode() inputs reached FFmpeg (extension)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct DecodeInputStats {
+  /// Chunks whose bytes were copied into a new packet
+  pub copied_chunks: i64,
+  /// Bytes copied for those chunks
+  pub copied_bytes: i64,
+  /// Chunks decoded from the packet they were demuxed into, without a copy
+  pub shared_chunks: i64,
+}
+
 /// Threshold for detecting silent decoder failure (no output after N chunks)
 /// Set to 10 to accommodate H.264/HEVC B-frame buffering (typically 4-8 frames)
 /// while still detecting genuinely failing decoders within ~333ms at 30fps.
@@ -212,8 +279,21 @@ struct VideoDecoderInner {
   output_callback: OutputCallback,
   /// Error callback (required per spec)
   error_callback: ErrorCallback,
+  /// parameterSetChange callback from the init dictionary (extension)
+  parameter_set_change_callback: Option<ParameterSetChangeCallback>,
+  /// Active H.264/H.265 parameter sets, description vs in-band
+  parameter_sets: Option<ParameterSetTracker>,
+  /// Additional output sinks registered via addOutputSink(), keyed by sink id
+  /// Each sink receives its own VideoFrame sharing the decoded pixel data
+  output_sinks: Vec<(u32, OutputCallback)>,
   /// Whether a keyframe has been received (for delta frame validation)
   keyframe_received: bool,
+  /// keyFramesOnly: delta chunks are dropped in decode() instead of queued
+  key_frames_only: bool,
+  /// inputGranularity "nal": NAL units are collected into access units before decoding
+  access_units: Option<AccessUnitAssembler>,
+  /// timestampMode / startAtZero mapping of output timestamps
+  timeline: OutputTimeline,
   /// Whether an error has occurred during decoding (for flush error propagation)
   had_error: bool,
   /// Pending flush response senders (for AbortError on reset)
@@ -221,6 +301,8 @@ struct VideoDecoderInner {
   /// Queue of input timestamps for correlation with output frames
   /// (needed because FFmpeg may buffer frames internally and modify PTS)
   timestamp_queue: std::collections::VecDeque<(i64, Option<i64>)>,
+  /// HDR dynamic metadata carried by input chunks, keyed by timestamp
+  chunk_dynamic_metadata: HashMap<i64, Vec<HdrDynamicMetadata>>,
   /// Atomic flag for flush abort - set by reset() to signal pending flush to abort
   flush_abort_flag: Option<Arc<AtomicBool>>,
   /// Queue of decoded frames waiting to be delivered via output callback
@@ -251,12 +333,19 @@ struct VideoDecoderInner {
   config_rotation: f64,
   /// Horizontal flip from config
   config_flip: bool,
+  /// Drop the alpha plane of decoded frames (config alpha: "discard")
+  config_discard_alpha: bool,
 
   // ========================================================================
   // Color space metadata (W3C WebCodecs VideoFrame colorSpace)
   // ========================================================================
   /// Color space from decoder config - applied to decoded frames
   config_color_space: Option<VideoColorSpaceInit>,
+
+  /// Output frames JS has not closed yet, capped by maxOutstandingFrames
+  frame_budget: Arc<FrameBudget>,
+  /// Copied vs shared decode inputs, reported by inputStats()
+  input_stats: DecodeInputStats,
 }
 
 /// Get the preferred hardware device type for the current platform
@@ -310,6 +399,11 @@ pub struct VideoDecoder {
   /// Wrapped in Rc to allow sharing with spawn_future_with_callback closure
   /// (Rc is !Send but that's OK - the callback runs on the main thread)
   output_callback_ref: Rc<FunctionRef<VideoFrame, UnknownReturnValue>>,
+  /// Output sink references - used for synchronous delivery in the flush resolver
+  /// and to prevent GC of sink callbacks (their ThreadsafeFunctions are weak)
+  output_sink_refs: Rc<RefCell<Vec<(u32, OutputCallbackRef)>>>,
+  /// Next id handed out by addOutputSink()
+  next_output_sink_id: u32,
   /// Error callback reference - prevents GC from collecting the error callback
   /// (weak ThreadsafeFunction alone can be collected on slow platforms like armv7 QEMU)
   #[allow(dead_code)]
@@ -320,16 +414,28 @@ pub struct VideoDecoder {
   worker_handle: Option<JoinHandle<()>>,
   /// Reset abort flag - set by reset() to signal worker to skip pending decodes
   reset_flag: Arc<AtomicBool>,
+  /// Environment teardown flag, shared with every worker this codec spawns
+  shutdown_flag: Arc<AtomicBool>,
+  /// Queue wait / processing time samples, shared with every worker this codec spawns
+  command_timings: Arc<CommandTimings>,
+  /// Outstanding output frame accounting, shared with every worker this codec spawns
+  frame_budget: Arc<FrameBudget>,
 }
 
 impl Drop for VideoDecoder {
   fn drop(&mut self) {
-    // Signal worker to stop
+    // Signal worker to stop, skipping any queued work
+    self.reset_flag.store(true, Ordering::SeqCst);
+    self.frame_budget.set_limit(None);
     self.command_sender = None;
 
-    // Wait for worker to finish (brief block, necessary for safety)
-    if let Some(handle) = self.worker_handle.take() {
-      let _ = handle.join();
+    // Wait for worker to finish. The wait is bounded so a busy worker (e.g. an
+    // AV1 flush) cannot hold up process exit; a detached worker still owns the
+    // codec state and releases it when its current command completes.
+    if let Some(handle) = self.worker_handle.take()
+      && !shutdown::join_worker(handle, "VideoDecoder")
+    {
+      return;
     }
 
     // Drain decoder to ensure libaom/AV1 threads finish before context drops.
@@ -353,9 +459,13 @@ impl VideoDecoder {
   /// @param init - Init dictionary containing output and error callbacks
   #[napi(constructor)]
   pub fn new(
-    #[napi(ts_arg_type = "{ output: (frame: VideoFrame) => void, error: (error: Error) => void }")]
+    env: Env,
+    #[napi(
+      ts_arg_type = "{ output: (frame: VideoFrame) => void, error: (error: Error) => void, parameterSetChange?: (change: ParameterSetChange) => void }"
+    )]
     init: VideoDecoderInit,
   ) -> Result<Self> {
+    let frame_budget = Arc::new(FrameBudget::new());
     let inner = VideoDecoderInner {
       state: CodecState::Unconfigured,
       config: None,
@@ -365,10 +475,17 @@ impl VideoDecoder {
       decode_queue_size: 0,
       output_callback: init.output,
       error_callback: init.error,
+      parameter_set_change_callback: init.parameter_set_change,
+      parameter_sets: None,
+      output_sinks: Vec::new(),
       keyframe_received: false,
+      key_frames_only: false,
+      access_units: None,
+      timeline: OutputTimeline::new(),
       had_error: false,
       pending_flush_senders: Vec::new(),
       timestamp_queue: std::collections::VecDeque::new(),
+      chunk_dynamic_metadata: HashMap::new(),
       flush_abort_flag: None,
       pending_frames: Vec::new(),
       inside_flush: false,
@@ -381,8 +498,11 @@ impl VideoDecoder {
       // Orientation metadata (default: no rotation/flip)
       config_rotation: 0.0,
       config_flip: false,
+      config_discard_alpha: false,
       // Color space from config (None = extract from FFmpeg frame)
       config_color_space: None,
+      frame_budget: frame_budget.clone(),
+      input_stats: DecodeInputStats::default(),
     };
 
     let inner = Arc::new(Mutex::new(inner));
@@ -396,16 +516,25 @@ impl VideoDecoder {
     // Create reset abort flag
     let reset_flag = Arc::new(AtomicBool::new(false));
 
+    // Set when the environment tears down, so the worker skips queued work
+    let shutdown_flag = shutdown::env_shutdown_flag(&env)?;
+
+    let command_timings = Arc::new(CommandTimings::new());
+
     // Spawn worker thread
     let worker_inner = inner.clone();
     let worker_event_state = event_state.clone();
     let worker_reset_flag = reset_flag.clone();
+    let worker_shutdown_flag = shutdown_flag.clone();
+    let worker_timings = command_timings.clone();
     let worker_handle = std::thread::spawn(move || {
       Self::worker_loop(
         worker_inner,
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
+        worker_timings,
       );
     });
 
@@ -414,10 +543,15 @@ impl VideoDecoder {
       event_state,
       dequeue_callback: None,
       output_callback_ref: Rc::new(init.output_ref),
+      output_sink_refs: Rc::new(RefCell::new(Vec::new())),
+      next_output_sink_id: 1,
       error_callback_ref: Rc::new(init.error_ref),
       command_sender: Some(Arc::new(sender)),
       worker_handle: Some(worker_handle),
       reset_flag,
+      shutdown_flag,
+      command_timings,
+      frame_budget,
     })
   }
 
@@ -427,11 +561,24 @@ impl VideoDecoder {
     event_state: Arc<RwLock<EventListenerState>>,
     receiver: Receiver<WorkerCommand>,
     reset_flag: Arc<AtomicBool>,
+    shutdown_flag: Arc<AtomicBool>,
+    command_timings: Arc<CommandTimings>,
   ) {
-    while let Ok(command) = receiver.recv() {
-      // Check reset flag before processing each command
-      // If reset() was called, skip remaining decode commands
-      if reset_flag.load(Ordering::SeqCst) {
+    let frame_budget = match inner.lock() {
+      Ok(guard) => guard.frame_budget.clone(),
+      Err(_) => return,
+    };
+
+    loop {
+      // maxOutstandingFrames: don't pull more work while JS holds too many frames
+      frame_budget.wait_for_capacity(&[&reset_flag, &shutdown_flag]);
+      let Ok(command) = receiver.recv() else {
+        break;
+      };
+
+      // Check reset and shutdown flags before processing each command
+      // If reset() was called or the environment is shutting down, skip remaining decode commands
+      if reset_flag.load(Ordering::SeqCst) || shutdown_flag.load(Ordering::SeqCst) {
         // Still process flush commands to send responses, but skip decodes
         if let WorkerCommand::Flush(response_sender) = command {
           let _ = response_sender.send(Err(Error::new(
@@ -452,8 +599,22 @@ impl VideoDecoder {
       }
 
       match command {
-        WorkerCommand::Decode(chunk) => {
+        WorkerCommand::Decode {
+          chunk,
+          timestamp,
+          enqueued_at,
+        } => {
+          let _span = tracing::debug_span!(target: "webcodecs", "decode", timestamp).entered();
+          let (queue_wait, started) = begin_command(enqueued_at);
           Self::process_decode(&inner, &event_state, chunk);
+          let processing = started.elapsed();
+          command_timings.record(queue_wait, processing);
+          tracing::debug!(
+            target: "webcodecs",
+            queue_wait_us = queue_wait.as_micros() as u64,
+            processing_us = processing.as_micros() as u64,
+            "Decode command processed"
+          );
         }
         WorkerCommand::Flush(response_sender) => {
           let result = Self::process_flush(&inner, &event_state);
@@ -519,6 +680,16 @@ impl VideoDecoder {
     let duration = encoded_chunk.duration_us;
     let is_keyframe = encoded_chunk.chunk_type == crate::webcodecs::EncodedVideoChunkType::Key;
 
+    // In-band parameter sets take precedence over the description (H.264/H.265)
+    if is_keyframe
+      && let Some(change) = guard
+        .parameter_sets
+        .as_mut()
+        .and_then(|tracker| tracker.observe(encoded_chunk.data.as_slice(), timestamp))
+    {
+      Self::report_parameter_set_change(&guard, change);
+    }
+
     // Handle packet data format based on decoder type:
     // - Hardware decoders (VideoToolbox, etc.) expect AVCC/HVCC format (length-prefixed NALUs)
     // - Software decoders expect Annex B format (start code prefixed NALUs)
@@ -539,13 +710,19 @@ impl VideoDecoder {
 
         // Prepend SPS/PPS/VPS from extradata to keyframes
         // This is needed because FFmpeg may not properly use extradata for H.264/H.265
+        // Once in-band parameter sets have replaced the description, those are
+        // prepended instead so a later keyframe does not bring the stale ones back
         if is_keyframe
           && let Some(config) = guard.config.as_ref()
-          && let Some(extradata) = &config.extradata
+          && let Some(extradata) = guard
+            .parameter_sets
+            .as_ref()
+            .and_then(ParameterSetTracker::in_band_parameter_sets)
+            .or(config.extradata.as_deref())
         {
           // Extradata should already be in Annex B format (converted in configure)
           // Prepend it to the keyframe data
-          let mut with_extradata = extradata.clone();
+          let mut with_extradata = extradata.to_vec();
           with_extradata.append(&mut converted);
           converted = with_extradata;
         }
@@ -559,11 +736,26 @@ impl VideoDecoder {
     // (FFmpeg may buffer frames internally and modify PTS)
     guard.timestamp_queue.push_back((timestamp, duration));
 
+    // Keep chunk-carried HDR dynamic metadata for the frame with this timestamp
+    if !encoded_chunk.hdr_dynamic_metadata.is_empty() {
+      guard
+        .chunk_dynamic_metadata
+        .insert(timestamp, encoded_chunk.hdr_dynamic_metadata.clone());
+    }
+
     // Buffer chunk during silent failure detection period (for re-decoding on fallback)
     if guard.is_hardware && !guard.first_output_produced {
       guard.pending_chunks.push(chunk.clone());
     }
 
+    let shared = shared_packet(&encoded_chunk.data, &data);
+    if shared.is_some() {
+      guard.input_stats.shared_chunks += 1;
+    } else {
+      guard.input_stats.copied_chunks += 1;
+      guard.input_stats.copied_bytes += data.len() as i64;
+    }
+
     // Get context
     let context = match guard.context.as_mut() {
       Some(ctx) => ctx,
@@ -579,7 +771,7 @@ impl VideoDecoder {
     };
 
     // Decode
-    let frames = match decode_chunk_data(context, &data, timestamp, duration) {
+    let frames = match decode_chunk_data(context, shared, &data, timestamp, duration) {
       Ok(f) => f,
       Err(e) => {
         // Handle decode error - may trigger fallback for hardware decoder
@@ -689,8 +881,14 @@ impl VideoDecoder {
         .pop_front()
         .unwrap_or((timestamp, duration));
 
+      // Streams may switch resolution mid-stream, so re-check every decoded frame
+      if let Err(msg) = decode_limits::check_dimensions(frame.width(), frame.height()) {
+        Self::report_error(&mut guard, &msg);
+        return;
+      }
+
       // Download hardware frames to CPU memory if needed
-      let output_frame = if frame.format().is_hardware() {
+      let mut output_frame = if frame.format().is_hardware() {
         match download_hw_frame(&frame) {
           Ok(sw_frame) => sw_frame,
           Err(e) => {
@@ -705,23 +903,53 @@ impl VideoDecoder {
         frame
       };
 
+      // alpha: "discard" - release the alpha plane before the frame reaches JS
+      if guard.config_discard_alpha {
+        output_frame.drop_alpha_plane();
+      }
+
+      let metadata = take_frame_metadata(&mut guard, &output_frame, output_timestamp);
+      let (frame_timestamp, frame_duration) =
+        guard.timeline.map_video(output_timestamp, output_duration);
       let video_frame = VideoFrame::from_internal_with_orientation(
         output_frame,
-        output_timestamp,
-        output_duration,
+        frame_timestamp,
+        frame_duration,
         guard.config_rotation,
         guard.config_flip,
         guard.config_color_space.as_ref(),
       );
+      video_frame.set_metadata(metadata);
 
-      // During flush, queue frames for synchronous delivery in resolver
-      // Otherwise, use NonBlocking callback for immediate delivery
-      if guard.inside_flush {
-        guard.pending_frames.push(video_frame);
-      } else {
-        guard
-          .output_callback
-          .call(video_frame, ThreadsafeFunctionCallMode::NonBlocking);
+      Self::deliver_frame(&mut guard, video_frame);
+    }
+  }
+
+  /// Deliver a decoded frame to the output callback and all output sinks
+  ///
+  /// During flush, frames are queued for synchronous delivery in the resolver.
+  /// Otherwise, NonBlocking callbacks are used for immediate delivery.
+  fn deliver_frame(guard: &mut VideoDecoderInner, video_frame: VideoFrame) {
+    if guard.inside_flush {
+      guard.pending_frames.push(video_frame);
+      return;
+    }
+
+    guard.frame_budget.track(&video_frame);
+
+    // Share before handing the frame to the primary output, which may close it
+    let copies: Vec<Option<VideoFrame>> = guard
+      .output_sinks
+      .iter()
+      .map(|_| video_frame.share().ok())
+      .collect();
+
+    guard
+      .output_callback
+      .call(video_frame, ThreadsafeFunctionCallMode::NonBlocking);
+    for ((_, sink), copy) in guard.output_sinks.iter().zip(copies) {
+      if let Some(copy) = copy {
+        sink.call(copy, ThreadsafeFunctionCallMode::NonBlocking);
       }
     }
   }
@@ -824,7 +1052,8 @@ impl VideoDecoder {
         None => return,
       };
 
-      let frames = match decode_chunk_data(context, &data, timestamp, duration) {
+      let shared = shared_packet(raw_data, &data);
+      let frames = match decode_chunk_data(context, shared, &data, timestamp, duration) {
         Ok(f) => f,
         Err(_) => continue, // Skip failed chunks during re-decode
       };
@@ -839,9 +1068,14 @@ impl VideoDecoder {
 
       // Deliver frames (queue during flush, NonBlocking otherwise)
       for frame in frames {
+        if let Err(msg) = decode_limits::check_dimensions(frame.width(), frame.height()) {
+          Self::report_error(&mut guard, &msg);
+          return;
+        }
+
         // Download hardware frames to CPU memory if needed
         // (shouldn't happen in fallback path but handle for safety)
-        let output_frame = if frame.format().is_hardware() {
+        let mut output_frame = if frame.format().is_hardware() {
           match download_hw_frame(&frame) {
             Ok(sw_frame) => sw_frame,
             Err(_) => continue, // Skip failed frame downloads during re-decode
@@ -850,21 +1084,23 @@ impl VideoDecoder {
           frame
         };
 
+        // alpha: "discard" - release the alpha plane before the frame reaches JS
+        if guard.config_discard_alpha {
+          output_frame.drop_alpha_plane();
+        }
+
+        let metadata = take_frame_metadata(&mut guard, &output_frame, timestamp);
+        let (frame_timestamp, frame_duration) = guard.timeline.map_video(timestamp, duration);
         let video_frame = VideoFrame::from_internal_with_orientation(
           output_frame,
-          timestamp,
-          duration,
+          frame_timestamp,
+          frame_duration,
           guard.config_rotation,
           guard.config_flip,
           guard.config_color_space.as_ref(),
         );
-        if guard.inside_flush {
-          guard.pending_frames.push(video_frame);
-        } else {
-          guard
-            .output_callback
-            .call(video_frame, ThreadsafeFunctionCallMode::NonBlocking);
-        }
+        video_frame.set_metadata(metadata);
+        Self::deliver_frame(&mut guard, video_frame);
       }
     }
   }
@@ -933,8 +1169,13 @@ impl VideoDecoder {
           (pts, dur)
         });
 
+      if let Err(msg) = decode_limits::check_dimensions(frame.width(), frame.height()) {
+        Self::report_error(&mut guard, &msg);
+        return Err(Error::new(Status::GenericFailure, msg));
+      }
+
       // Download hardware frames to CPU memory if needed
-      let output_frame = if frame.format().is_hardware() {
+      let mut output_frame = if frame.format().is_hardware() {
         match download_hw_frame(&frame) {
           Ok(sw_frame) => sw_frame,
           Err(e) => {
@@ -950,20 +1191,30 @@ impl VideoDecoder {
         frame
       };
 
+      // alpha: "discard" - release the alpha plane before the frame reaches JS
+      if guard.config_discard_alpha {
+        output_frame.drop_alpha_plane();
+      }
+
+      let metadata = take_frame_metadata(&mut guard, &output_frame, output_timestamp);
+      let (frame_timestamp, frame_duration) =
+        guard.timeline.map_video(output_timestamp, output_duration);
       let video_frame = VideoFrame::from_internal_with_orientation(
         output_frame,
-        output_timestamp,
-        output_duration,
+        frame_timestamp,
+        frame_duration,
         guard.config_rotation,
         guard.config_flip,
         guard.config_color_space.as_ref(),
       );
+      video_frame.set_metadata(metadata);
       // Always queue during flush for synchronous delivery in resolver
       guard.pending_frames.push(video_frame);
     }
 
     // Clear any remaining timestamps in queue after flush
     guard.timestamp_queue.clear();
+    guard.chunk_dynamic_metadata.clear();
 
     // Reset decoder state so it can accept more data (per W3C spec, flush should leave
     // decoder in configured state, ready for more decode() calls)
@@ -997,6 +1248,7 @@ impl VideoDecoder {
     // Clear work-related state
     guard.decode_queue_size = 0;
     guard.timestamp_queue.clear();
+    guard.chunk_dynamic_metadata.clear();
     guard.keyframe_received = false;
     guard.silent_decode_count = 0;
     guard.first_output_produced = false;
@@ -1095,6 +1347,7 @@ impl VideoDecoder {
       low_latency: config.optimize_for_latency.unwrap_or(false),
       width: config.coded_width,
       height: config.coded_height,
+      max_pixels: Some(decode_limits::max_pixels()),
     };
 
     if let Err(e) = context.configure_decoder(&decoder_config) {
@@ -1135,6 +1388,7 @@ impl VideoDecoder {
     // Update inner state
     guard.context = Some(context);
     guard.config = Some(decoder_config);
+    guard.parameter_sets = ParameterSetTracker::new(&codec, config.description.as_deref());
     guard.codec_string = codec;
     guard.is_hardware = is_hardware;
     guard.hw_preference = hw_preference;
@@ -1142,9 +1396,34 @@ impl VideoDecoder {
     // Store orientation from config
     guard.config_rotation = config.rotation.unwrap_or(0.0);
     guard.config_flip = config.flip.unwrap_or(false);
+    guard.config_discard_alpha = config.alpha == Some(AlphaOption::Discard);
 
     // Store colorSpace from config
     guard.config_color_space = config.color_space;
+
+    // Frames decoded before this point were mapped with the previous options
+    guard
+      .timeline
+      .configure(config.timestamp_mode, config.start_at_zero);
+  }
+
+  /// Report in-band parameter sets that differ from the active ones
+  ///
+  /// Logged as a warning and passed to the parameterSetChange callback, if any.
+  /// Decoding continues with the new parameter sets.
+  fn report_parameter_set_change(inner: &VideoDecoderInner, change: ParameterSetChange) {
+    tracing::warn!(
+      target: "webcodecs",
+      codec = change.codec.as_str(),
+      timestamp = change.timestamp,
+      previous_source = ?change.previous_source,
+      previous_bytes = change.previous.len(),
+      current_bytes = change.current.len(),
+      "In-band parameter sets differ from the active ones; using the in-band sets"
+    );
+    if let Some(callback) = &inner.parameter_set_change_callback {
+      callback.call(change, ThreadsafeFunctionCallMode::NonBlocking);
+    }
   }
 
   /// Report an error via callback and close the decoder
@@ -1161,6 +1440,41 @@ impl VideoDecoder {
     inner.state = CodecState::Closed;
   }
 
+  /// Chunks to queue on the worker for one decode() input, with their timestamps
+  ///
+  /// With inputGranularity "nal" these are the access units the input
+  /// completes, which may be none; otherwise the input itself. `detach`
+  /// closes the input chunk, moving its data to the worker.
+  fn decode_inputs(
+    inner: &mut VideoDecoderInner,
+    chunk: &EncodedVideoChunk,
+    detach: bool,
+  ) -> Vec<(Arc<RwLock<Option<EncodedVideoChunkInner>>>, i64)> {
+    let timestamp = chunk.timestamp().unwrap_or_default();
+    let Some(assembler) = inner.access_units.as_mut() else {
+      let chunk_inner = if detach {
+        Arc::new(RwLock::new(chunk.detach()))
+      } else {
+        chunk.inner.clone()
+      };
+      return vec![(chunk_inner, timestamp)];
+    };
+    let duration = chunk.duration().ok().flatten();
+    let access_units = chunk
+      .get_data_optional(|data| Some(assembler.push(data, timestamp, duration)))
+      .unwrap_or_default();
+    if detach {
+      chunk.detach();
+    }
+    access_units
+      .into_iter()
+      .map(|access_unit| {
+        let timestamp = access_unit.timestamp;
+        (access_unit.into_chunk(), timestamp)
+      })
+      .collect()
+  }
+
   /// Fire dequeue event - uses separate RwLock to avoid blocking addEventListener
   /// Also dispatches to EventTarget listeners registered via addEventListener
   fn fire_dequeue_event(event_state: &Arc<RwLock<EventListenerState>>) -> Result<()> {
@@ -1324,6 +1638,9 @@ impl VideoDecoder {
     {
       return throw_type_error_unit(&env, "displayAspectHeight must be greater than 0");
     }
+    if config.max_outstanding_frames == Some(0) {
+      return throw_type_error_unit(&env, "maxOutstandingFrames must be greater than 0");
+    }
 
     let mut inner = self
       .inner
@@ -1335,6 +1652,36 @@ impl VideoDecoder {
       return throw_invalid_state_error(&env, "Decoder is closed");
     }
 
+    // Applies immediately, also to work already queued before a reconfigure
+    self.frame_budget.set_limit(config.max_outstanding_frames);
+    inner.key_frames_only = config.key_frames_only.unwrap_or(false);
+
+    // inputGranularity "nal" needs H.264 slice headers to find picture boundaries
+    inner.access_units = match config.input_granularity.unwrap_or_default() {
+      InputGranularity::AccessUnit => None,
+      InputGranularity::Nal if codec.starts_with("avc1") || codec.starts_with("avc3") => {
+        Some(AccessUnitAssembler::default())
+      }
+      InputGranularity::Nal => {
+        Self::report_error(
+          &mut inner,
+          &format!(
+            "NotSupportedError: inputGranularity \"nal\" is not supported for {}",
+            codec
+          ),
+        );
+        return Ok(());
+      }
+    };
+
+    // Reject absurd coded dimensions before any allocation is attempted
+    if let (Some(w), Some(h)) = (config.coded_width, config.coded_height)
+      && let Err(msg) = decode_limits::check_dimensions(w, h)
+    {
+      Self::report_error(&mut inner, &msg);
+      return Ok(());
+    }
+
     // W3C spec: If already configured, queue reconfigure via microtask
     // This ensures FIFO ordering with pending decode commands
     if inner.state == CodecState::Configured {
@@ -1462,6 +1809,7 @@ impl VideoDecoder {
       low_latency: config.optimize_for_latency.unwrap_or(false),
       width: config.coded_width,
       height: config.coded_height,
+      max_pixels: Some(decode_limits::max_pixels()),
     };
 
     if let Err(e) = context.configure_decoder(&decoder_config) {
@@ -1506,6 +1854,7 @@ impl VideoDecoder {
 
     inner.context = Some(context);
     inner.config = Some(decoder_config);
+    inner.parameter_sets = ParameterSetTracker::new(&codec, config.description.as_deref());
     inner.codec_string = codec;
     inner.state = CodecState::Configured;
     inner.frame_count = 0;
@@ -1522,11 +1871,17 @@ impl VideoDecoder {
     // Store orientation metadata from config (W3C WebCodecs spec)
     inner.config_rotation = config.rotation.unwrap_or(0.0);
     inner.config_flip = config.flip.unwrap_or(false);
+    inner.config_discard_alpha = config.alpha == Some(AlphaOption::Discard);
 
     // Store colorSpace from config (W3C WebCodecs spec)
     // If provided, this colorSpace will be applied to all decoded frames
     inner.config_color_space = config.color_space;
 
+    // Kept across reset() so a seek stays on the same output timeline
+    inner
+      .timeline
+      .configure(config.timestamp_mode, config.start_at_zero);
+
     // Create new channel and worker if needed (after reconfiguration)
     if self.command_sender.is_none() {
       let (sender, receiver) = channel::unbounded();
@@ -1534,6 +1889,8 @@ impl VideoDecoder {
       let worker_inner = self.inner.clone();
       let worker_event_state = self.event_state.clone();
       let worker_reset_flag = self.reset_flag.clone();
+      let worker_shutdown_flag = self.shutdown_flag.clone();
+      let worker_timings = self.command_timings.clone();
       drop(inner); // Release lock before spawning thread
       self.worker_handle = Some(std::thread::spawn(move || {
         Self::worker_loop(
@@ -1541,6 +1898,8 @@ impl VideoDecoder {
           worker_event_state,
           receiver,
           worker_reset_flag,
+          worker_shutdown_flag,
+          worker_timings,
         );
       }));
     }
@@ -1551,8 +1910,24 @@ impl VideoDecoder {
   /// Decode an encoded video chunk
   #[napi]
   pub fn decode(&self, env: Env, chunk: &EncodedVideoChunk) -> Result<()> {
+    self.queue_decode(env, chunk, false)
+  }
+
+  /// Decode an encoded video chunk and close it (extension)
+  ///
+  /// The chunk's reference to its data is transferred to the decoder, so a
+  /// demuxer chunk's packet is decoded without copying its bytes. Afterwards
+  /// the chunk is closed and its getters and `copyTo()` throw
+  /// InvalidStateError.
+  #[napi]
+  pub fn decode_and_close(&self, env: Env, chunk: &EncodedVideoChunk) -> Result<()> {
+    self.queue_decode(env, chunk, true)
+  }
+
+  /// Queue one chunk for decoding, taking its data out of it if `detach`
+  fn queue_decode(&self, env: Env, chunk: &EncodedVideoChunk, detach: bool) -> Result<()> {
     // Increment queue size first (under lock)
-    {
+    let inputs = {
       let mut inner = self
         .inner
         .lock()
@@ -1565,9 +1940,20 @@ impl VideoDecoder {
       if inner.state != CodecState::Configured {
         return throw_invalid_state_error(&env, "Cannot decode with an unconfigured codec");
       }
+      if chunk.is_closed() {
+        return throw_invalid_state_error(&env, "EncodedVideoChunk is closed");
+      }
 
-      // W3C spec: throw DataError if first chunk is not a keyframe
+      // keyFramesOnly: delta chunks are dropped without an error or a queue entry
       let is_key = chunk.is_key();
+      if inner.key_frames_only && !is_key {
+        if detach {
+          chunk.detach();
+        }
+        return Ok(());
+      }
+
+      // W3C spec: throw DataError if first chunk is not a keyframe
       if !inner.keyframe_received {
         if is_key {
           inner.keyframe_received = true;
@@ -1577,22 +1963,127 @@ impl VideoDecoder {
         }
       }
 
-      inner.decode_queue_size += 1;
-    }
+      let inputs = Self::decode_inputs(&mut inner, chunk, detach);
+      inner.decode_queue_size += inputs.len() as u32;
+      inputs
+    };
 
     // Send decode command to worker thread via microtask for W3C spec FIFO ordering
     // This ensures all commands (decode, configure, flush) are ordered correctly
     // Use Weak reference to allow close() to immediately close channel without deadlock
     if let Some(ref sender) = self.command_sender {
+      if inputs.is_empty() {
+        return Ok(());
+      }
       let weak_sender = Arc::downgrade(sender);
       let reset_flag = self.reset_flag.clone();
-      let chunk_inner = chunk.inner.clone();
+      let enqueued_at = Instant::now();
       PromiseRaw::resolve(&env, ())?.then(move |_| {
         // Check reset flag first, then check if decoder hasn't been closed
         if !reset_flag.load(Ordering::SeqCst)
           && let Some(sender) = weak_sender.upgrade()
         {
-          let _ = sender.send(WorkerCommand::Decode(chunk_inner));
+          for (chunk, timestamp) in inputs {
+            let _ = sender.send(WorkerCommand::Decode {
+              chunk,
+              timestamp,
+              enqueued_at,
+            });
+          }
+        }
+        Ok(())
+      })?;
+    } else {
+      return Err(Error::new(
+        Status::GenericFailure,
+        "Decoder has been closed",
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// Decode several encoded video chunks in one call
+  ///
+  /// Equivalent to calling `decode()` for each chunk in order, with one native
+  /// call and one microtask hop for the whole batch. `decodeQueueSize` grows by
+  /// the batch size and a dequeue event still fires per decoded chunk.
+  #[napi(ts_args_type = "chunks: EncodedVideoChunk[]")]
+  pub fn decode_batch(
+    &self,
+    env: Env,
+    chunks: Vec<ClassInstance<'_, EncodedVideoChunk>>,
+  ) -> Result<()> {
+    if chunks.is_empty() {
+      return Ok(());
+    }
+
+    // Increment queue size first (under lock)
+    let batch = {
+      let mut inner = self
+        .inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+      // W3C spec: throw InvalidStateError if not configured or closed
+      if inner.state == CodecState::Closed {
+        return throw_invalid_state_error(&env, "Cannot decode with a closed codec");
+      }
+      if inner.state != CodecState::Configured {
+        return throw_invalid_state_error(&env, "Cannot decode with an unconfigured codec");
+      }
+      if chunks.iter().any(|chunk| chunk.is_closed()) {
+        return throw_invalid_state_error(&env, "EncodedVideoChunk is closed");
+      }
+
+      // keyFramesOnly: delta chunks are dropped without an error or a queue entry
+      let chunks: Vec<_> = if inner.key_frames_only {
+        chunks.into_iter().filter(|chunk| chunk.is_key()).collect()
+      } else {
+        chunks
+      };
+      if chunks.is_empty() {
+        return Ok(());
+      }
+
+      // W3C spec: throw DataError if first chunk is not a keyframe
+      // (only the first chunk of the batch can be the first chunk overall)
+      if !inner.keyframe_received {
+        if chunks[0].is_key() {
+          inner.keyframe_received = true;
+        } else {
+          return throw_data_error(&env, "First chunk must be a keyframe");
+        }
+      }
+
+      let batch: Vec<_> = chunks
+        .iter()
+        .flat_map(|chunk| Self::decode_inputs(&mut inner, chunk, false))
+        .collect();
+      inner.decode_queue_size += batch.len() as u32;
+      batch
+    };
+
+    // Send all decode commands from a single microtask, preserving FIFO order
+    if let Some(ref sender) = self.command_sender {
+      if batch.is_empty() {
+        return Ok(());
+      }
+      let weak_sender = Arc::downgrade(sender);
+      let reset_flag = self.reset_flag.clone();
+      let enqueued_at = Instant::now();
+      PromiseRaw::resolve(&env, ())?.then(move |_| {
+        // Check reset flag first, then check if decoder hasn't been closed
+        if !reset_flag.load(Ordering::SeqCst)
+          && let Some(sender) = weak_sender.upgrade()
+        {
+          for (chunk, timestamp) in batch {
+            let _ = sender.send(WorkerCommand::Decode {
+              chunk,
+              timestamp,
+              enqueued_at,
+            });
+          }
         }
         Ok(())
       })?;
@@ -1619,7 +2110,7 @@ impl VideoDecoder {
 
     // W3C spec: Check state upfront and return rejected promise with appropriate error
     // (not throw synchronously - flush() should always return a promise)
-    {
+    let last_access_unit = {
       let mut inner = self
         .inner
         .lock()
@@ -1651,7 +2142,16 @@ impl VideoDecoder {
       inner.flush_abort_flag = Some(flush_abort_flag.clone());
       // Set inside_flush flag so worker queues frames instead of calling NonBlocking callback
       inner.inside_flush = true;
-    }
+
+      // inputGranularity "nal": no later NAL unit will end the buffered access unit
+      let last_access_unit = inner.access_units.as_mut().and_then(|a| a.finish());
+      if last_access_unit.is_some() {
+        inner.decode_queue_size += 1;
+      }
+      last_access_unit
+    };
+    // Queued frames can't be closed until the flush resolves, so lift the cap meanwhile
+    self.frame_budget.set_suspended(true);
 
     // Create a response channel
     let (response_sender, response_receiver) = channel::bounded::<Result<()>>(1);
@@ -1671,12 +2171,20 @@ impl VideoDecoder {
     if let Some(ref sender) = self.command_sender {
       let weak_sender = Arc::downgrade(sender);
       let reset_flag = self.reset_flag.clone();
+      let enqueued_at = Instant::now();
       PromiseRaw::resolve(env, ())?.then(move |_| {
         // Check reset flag first, then check if decoder hasn't been closed
         // (flush Promise is already rejected with AbortError by reset())
         if !reset_flag.load(Ordering::SeqCst)
           && let Some(sender) = weak_sender.upgrade()
         {
+          if let Some(access_unit) = last_access_unit {
+            let _ = sender.send(WorkerCommand::Decode {
+              timestamp: access_unit.timestamp,
+              chunk: access_unit.into_chunk(),
+              enqueued_at,
+            });
+          }
           let _ = sender.send(WorkerCommand::Flush(response_sender));
         }
         Ok(())
@@ -1688,6 +2196,8 @@ impl VideoDecoder {
     // Clone references for the callback closure
     let inner_clone = self.inner.clone();
     let output_callback_ref = self.output_callback_ref.clone();
+    let output_sink_refs = self.output_sink_refs.clone();
+    let frame_budget = self.frame_budget.clone();
 
     env.spawn_future_with_callback(
       async move {
@@ -1726,7 +2236,27 @@ impl VideoDecoder {
           if abort_flag.load(Ordering::SeqCst) {
             break;
           }
+
+          // Snapshot sinks and share the frame before the primary output may close it.
+          // The RefCell borrow is released before any JS runs, so callbacks may
+          // add or remove sinks.
+          let mut sinks = Vec::new();
+          for (id, sink_ref) in output_sink_refs.borrow().iter() {
+            sinks.push((*id, sink_ref.borrow_back(env)?, frame.share()?));
+          }
+
+          frame_budget.track(&frame);
           callback.call(frame)?;
+          for (id, sink, copy) in sinks {
+            // Skip sinks removed by an earlier callback for this frame
+            let still_registered = output_sink_refs
+              .borrow()
+              .iter()
+              .any(|(sink_id, _)| *sink_id == id);
+            if still_registered {
+              sink.call(copy)?;
+            }
+          }
         }
 
         // Clean up flags
@@ -1737,6 +2267,7 @@ impl VideoDecoder {
           guard.flush_abort_flag = None;
           guard.inside_flush = false;
         }
+        frame_budget.set_suspended(false);
 
         // Check abort flag after draining all frames
         if abort_flag.load(Ordering::SeqCst) {
@@ -1752,6 +2283,31 @@ impl VideoDecoder {
     )
   }
 
+  /// How decode inputs have reached FFmpeg since the decoder was created (extension)
+  ///
+  /// Chunks from a demuxer are decoded from the packet they were read into;
+  /// other chunks, and chunks whose bytes have to be rewritten (e.g. AVCC to
+  /// Annex B), are copied into a new packet.
+  #[napi]
+  pub fn input_stats(&self) -> Result<DecodeInputStats> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(inner.input_stats.clone())
+  }
+
+  /// Rolling queue latency statistics for the decode worker
+  ///
+  /// Covers the most recent 256 decode commands: time from `decode()` until
+  /// the worker started on the chunk, and time spent decoding it. With
+  /// `WEBCODECS_LOG=debug` each command is also logged in a `decode` span
+  /// keyed by chunk timestamp.
+  #[napi]
+  pub fn queue_stats(&self) -> CodecQueueStats {
+    self.command_timings.snapshot()
+  }
+
   /// Reset the decoder
   #[napi]
   pub fn reset(&mut self, env: Env) -> Result<()> {
@@ -1777,6 +2333,8 @@ impl VideoDecoder {
     // Set reset flag to signal worker to skip remaining pending decodes
     // This must be done BEFORE dropping the command sender
     self.reset_flag.store(true, Ordering::SeqCst);
+    // Wake a worker waiting on maxOutstandingFrames; configure() sets the cap again
+    self.frame_budget.set_limit(None);
 
     // W3C spec: Abort all pending flushes with AbortError BEFORE dropping sender
     {
@@ -1828,13 +2386,17 @@ impl VideoDecoder {
     inner.first_output_produced = false;
     inner.pending_chunks.clear();
     inner.timestamp_queue.clear();
+    inner.chunk_dynamic_metadata.clear();
+    inner.access_units = None;
 
     // Clear flush-related state
     inner.inside_flush = false;
     inner.pending_frames.clear();
+    self.frame_budget.set_suspended(false);
 
     // Reset the abort flag for new worker
     self.reset_flag.store(false, Ordering::SeqCst);
+    self.command_timings.clear();
 
     // Create new channel and worker for future decode operations
     let (sender, receiver) = channel::unbounded();
@@ -1842,6 +2404,8 @@ impl VideoDecoder {
     let worker_inner = self.inner.clone();
     let worker_event_state = self.event_state.clone();
     let worker_reset_flag = self.reset_flag.clone();
+    let worker_shutdown_flag = self.shutdown_flag.clone();
+    let worker_timings = self.command_timings.clone();
 
     // Create synchronization channel to wait for worker to be ready
     let (ready_sender, ready_receiver) = channel::bounded::<()>(1);
@@ -1855,6 +2419,8 @@ impl VideoDecoder {
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
+        worker_timings,
       );
     }));
 
@@ -1865,8 +2431,13 @@ impl VideoDecoder {
   }
 
   /// Close the decoder
+  ///
+  /// Decoder resources are freed before this returns. Output frames the application
+  /// still holds stay valid: any frame still backed by a hardware surface is downloaded
+  /// to CPU memory first. With `detachFrames`, those frames are closed instead and their
+  /// pixel data freed, so a later copyTo() throws InvalidStateError.
   #[napi]
-  pub fn close(&mut self, env: Env) -> Result<()> {
+  pub fn close(&mut self, env: Env, options: Option<VideoDecoderCloseOptions>) -> Result<()> {
     // Check state first - W3C spec: throw InvalidStateError if already closed
     {
       let inner = self
@@ -1883,6 +2454,8 @@ impl VideoDecoder {
     // With Weak references in microtasks, dropping Arc<Sender> immediately closes the channel
     // even if there are pending microtasks (they use Weak which can't keep the channel alive).
     self.command_sender = None;
+    // A worker waiting on maxOutstandingFrames must not block the join
+    self.frame_budget.set_limit(None);
 
     // Now safe to join worker - channel is closed, worker will see recv() Err and exit.
     // This prevents resource contention where old worker is still holding FFmpeg resources
@@ -1891,6 +2464,12 @@ impl VideoDecoder {
       let _ = handle.join();
     }
 
+    let detach_frames = options.and_then(|o| o.detach_frames).unwrap_or(false);
+    // Held frames must not outlive the device their surfaces belong to
+    if !detach_frames {
+      self.frame_budget.transfer_outstanding_to_cpu();
+    }
+
     let mut inner = self
       .inner
       .lock()
@@ -1910,15 +2489,92 @@ impl VideoDecoder {
     inner.state = CodecState::Closed;
     inner.decode_queue_size = 0;
 
+    // A closed decoder never produces output again, release the sinks
+    inner.output_sinks.clear();
+    self.output_sink_refs.borrow_mut().clear();
+
     // Reset hardware tracking state
     inner.is_hardware = false;
     inner.silent_decode_count = 0;
     inner.first_output_produced = false;
-    inner.pending_chunks.clear();
+    inner.pending_chunks = Vec::new();
+
+    // Frames and metadata that will never be delivered
+    inner.pending_frames = Vec::new();
+    inner.timestamp_queue = std::collections::VecDeque::new();
+    inner.chunk_dynamic_metadata = HashMap::new();
+    drop(inner);
+
+    if detach_frames {
+      self.frame_budget.close_outstanding();
+    }
 
     Ok(())
   }
 
+  /// Add an additional output sink that receives every decoded frame
+  ///
+  /// Each sink gets its own VideoFrame referencing the same pixel data as the frame
+  /// passed to the `output` callback, so every consumer must close its frame independently.
+  /// Sinks persist across reset() and configure(), and are released by close().
+  ///
+  /// @returns Sink id to pass to removeOutputSink()
+  #[napi]
+  pub fn add_output_sink(
+    &mut self,
+    env: Env,
+    #[napi(ts_arg_type = "(frame: VideoFrame) => void")] callback: Function<
+      VideoFrame,
+      UnknownReturnValue,
+    >,
+  ) -> Result<u32> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+    if inner.state == CodecState::Closed {
+      return throw_invalid_state_error(&env, "Cannot add an output sink to a closed codec");
+    }
+
+    // Weak TSF for worker delivery, FunctionRef for flush delivery and GC protection
+    let sink: OutputCallback = callback
+      .build_threadsafe_function()
+      .callee_handled::<false>()
+      .weak::<true>()
+      .build()?;
+    let sink_ref = callback.create_ref()?;
+
+    let id = self.next_output_sink_id;
+    self.next_output_sink_id += 1;
+
+    inner.output_sinks.push((id, sink));
+    self.output_sink_refs.borrow_mut().push((id, sink_ref));
+    Ok(id)
+  }
+
+  /// Remove an output sink added with addOutputSink()
+  ///
+  /// Safe to call at any time, including from within an output callback.
+  /// Frames already queued for the sink may still be delivered.
+  ///
+  /// @returns true if the sink was registered
+  #[napi]
+  pub fn remove_output_sink(&mut self, id: u32) -> Result<bool> {
+    let mut inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+    let before = inner.output_sinks.len();
+    inner.output_sinks.retain(|(sink_id, _)| *sink_id != id);
+    self
+      .output_sink_refs
+      .borrow_mut()
+      .retain(|(sink_id, _)| *sink_id != id);
+    Ok(inner.output_sinks.len() != before)
+  }
+
   /// Check if a configuration is supported
   /// Returns a Promise that resolves with support information
   ///
@@ -1962,11 +2618,22 @@ impl VideoDecoder {
     {
       return reject_with_type_error(env, "displayAspectHeight must be greater than 0");
     }
+    if config.max_outstanding_frames == Some(0) {
+      return reject_with_type_error(env, "maxOutstandingFrames must be greater than 0");
+    }
+
+    // W3C spec: the returned config only echoes recognized members, with defaults filled
+    let config = config.normalize();
 
     // Validate dimensions if specified
     let width = config.coded_width.unwrap_or(0);
     let height = config.coded_height.unwrap_or(0);
-    if width > 0 && height > 0 && !are_dimensions_valid(width, height) {
+    // inputGranularity "nal" is only implemented for H.264
+    let nal_unsupported = config.input_granularity == Some(InputGranularity::Nal)
+      && !(codec.starts_with("avc1") || codec.starts_with("avc3"));
+    if nal_unsupported
+      || (width > 0 && height > 0 && decode_limits::check_dimensions(width, height).is_err())
+    {
       return env.spawn_future(async move {
         Ok(VideoDecoderSupport {
           supported: false,
@@ -2164,9 +2831,6 @@ const MAX_AV1_LEVEL: u8 = 23;
 /// Valid HEVC profiles
 const VALID_HEVC_PROFILES: &[u8] = &[1, 2, 3, 4];
 
-/// Maximum dimension (width/height) for decoder
-const MAX_DIMENSION: u32 = 16384;
-
 /// Validate H.264/AVC codec string format and parameters
 /// Format: avc1.PPCCLL or avc3.PPCCLL where PP=profile, CC=constraint, LL=level (hex)
 fn validate_avc_codec(codec: &str) -> bool {
@@ -2362,11 +3026,6 @@ fn has_valid_codec_casing(codec: &str) -> bool {
   true
 }
 
-/// Check if dimensions are within valid range
-fn are_dimensions_valid(width: u32, height: u32) -> bool {
-  width <= MAX_DIMENSION && height <= MAX_DIMENSION
-}
-
 /// Parse WebCodecs codec string to FFmpeg codec ID
 /// Returns error for unsupported or invalid codec strings
 fn parse_codec_string(codec: &str) -> Result<AVCodecID> {
@@ -2441,9 +3100,43 @@ fn parse_codec_string(codec: &str) -> Result<AVCodecID> {
   ))
 }
 
+/// Metadata for a decoded frame
+///
+/// HDR dynamic metadata passed through on the input chunk takes precedence;
+/// otherwise whatever the decoder extracted from the bitstream is used.
+/// Closed captions always come from the bitstream.
+fn take_frame_metadata(
+  guard: &mut VideoDecoderInner,
+  frame: &Frame,
+  timestamp: i64,
+) -> VideoFrameMetadata {
+  let dynamic_metadata = guard
+    .chunk_dynamic_metadata
+    .remove(&timestamp)
+    .unwrap_or_else(|| HdrDynamicMetadata::from_frame(frame));
+  VideoFrameMetadata::decoded(dynamic_metadata, frame)
+}
+
+/// The chunk's own packet, referenced rather than copied, when its bytes are
+/// decoded unchanged
+///
+/// Demuxer chunks wrap the AVPacket they were read into. `av_packet_ref` only
+/// bumps the buffer's reference count, and the buffer stays alive until both
+/// the chunk and the decoder have dropped it.
+fn shared_packet(source: &Either<Vec<u8>, Packet>, data: &Cow<'_, [u8]>) -> Option<Packet> {
+  match (source, data) {
+    (Either::B(packet), Cow::Borrowed(_)) => packet.shallow_clone().ok(),
+    _ => None,
+  }
+}
+
 /// Decode chunk data using FFmpeg
+///
+/// `shared` is the chunk's own packet (see `shared_packet`); without one,
+/// `data` is copied into a new packet.
 fn decode_chunk_data(
   context: &mut CodecContext,
+  shared: Option<Packet>,
   data: &[u8],
   timestamp: i64,
   duration: Option<i64>,
@@ -2456,23 +3149,29 @@ fn decode_chunk_data(
     ));
   }
 
-  // Create a packet and fill it with data
-  let mut packet = Packet::new().map_err(|e| {
-    Error::new(
-      Status::GenericFailure,
-      format!("Failed to create packet: {}", e),
-    )
-  })?;
+  let mut packet = match shared {
+    Some(packet) => packet,
+    None => {
+      // Create a packet and fill it with data
+      let mut packet = Packet::new().map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to create packet: {}", e),
+        )
+      })?;
 
-  // Allocate and copy data to packet using safe wrapper
-  // NOTE: This must be done BEFORE setting timestamps because copy_data_from
-  // calls unref() internally which would reset timestamps to AV_NOPTS_VALUE.
-  packet.copy_data_from(data).map_err(|e| {
-    Error::new(
-      Status::GenericFailure,
-      format!("Failed to copy packet data: {}", e),
-    )
-  })?;
+      // Allocate and copy data to packet using safe wrapper
+      // NOTE: This must be done BEFORE setting timestamps because copy_data_from
+      // calls unref() internally which would reset timestamps to AV_NOPTS_VALUE.
+      packet.copy_data_from(data).map_err(|e| {
+        Error::new(
+          Status::GenericFailure,
+          format!("Failed to copy packet data: {}", e),
+        )
+      })?;
+      packet
+    }
+  };
 
   // Set packet timestamps AFTER copying data (unref in copy_data_from resets timestamps)
   packet.set_pts(timestamp);
@@ -3,26 +3,50 @@
 //! Provides video encoding functionality using FFmpeg.
 //! See: https://w3c.github.io/webcodecs/#videoencoder-interface
 
+use crate::codec::orientation::orient_frame;
 use crate::codec::{
-  BitrateMode as CodecBitrateMode, CodecContext, EncoderConfig, EncoderCreationResult, Frame,
-  HwDeviceContext, HwFrameConfig, HwFrameContext, Packet, Scaler,
+  BitrateMode as CodecBitrateMode, CodecContext, DecoderConfig, EncoderConfig,
+  EncoderCreationResult, EncoderPass, Frame, HwDeviceContext, HwFrameConfig, HwFrameContext,
+  Packet, Scaler, framerate_rational,
 };
 use crate::ffi::{
   AVCodecID, AVHWDeviceType, AVPictureType, AVPixelFormat, AVRational, avutil::av_rescale_q,
+  pkt_side_data_type::AV_PKT_DATA_NEW_EXTRADATA,
 };
+use crate::webcodecs::bitrate_stats::{BitrateSummary, BitrateTracker};
+use crate::webcodecs::codec_capabilities::{format_name, unsupported_input_reason};
 use crate::webcodecs::codec_pressure;
+use crate::webcodecs::command_timing::{CodecQueueStats, CommandTimings, begin_command};
+use crate::webcodecs::encoder_defaults::{FfmpegOptions, video_encoder_options};
+use crate::webcodecs::encoder_info::rewrite_encoder_info_sei;
+use crate::webcodecs::encoder_probe::probe_video_encoder;
 use crate::webcodecs::error::DOMExceptionName;
-use crate::webcodecs::error::{throw_invalid_state_error, throw_type_error_unit};
+use crate::webcodecs::error::{
+  check_injected_encoder_panic, panic_message, throw_invalid_state_error,
+  throw_not_supported_error, throw_type_error_unit,
+};
+use crate::webcodecs::hardware::{HwEncoderSession, session_limit_message};
 use crate::webcodecs::hw_fallback::{
   is_hw_encoding_disabled, record_hw_encoding_failure, record_hw_encoding_success,
 };
-use crate::webcodecs::promise_reject::{reject_with_dom_exception_async, reject_with_type_error};
+use crate::webcodecs::plain_bytes::PlainUint8Array;
+use crate::webcodecs::promise_reject::{
+  reject_with_dom_exception_async, reject_with_type_error, resolve_with,
+};
+use crate::webcodecs::quality_metrics::{self, QualityMetrics};
+use crate::webcodecs::rate_control::{PacketRateControl, RateControlStats};
+use crate::webcodecs::reference_control::{
+  LongTermReferences, ReferenceControlMetadata, supports_reference_control,
+};
+use crate::webcodecs::scene_cut::SceneCutDetector;
+use crate::webcodecs::shutdown;
 use crate::webcodecs::{
-  AlphaOption, AvcBitstreamFormat, EncodedVideoChunk, HardwareAcceleration, HevcBitstreamFormat,
-  LatencyMode, VideoColorSpaceInit, VideoEncoderBitrateMode, VideoEncoderConfig, VideoFrame,
-  convert_annexb_extradata_to_avcc, convert_annexb_extradata_to_hvcc,
-  convert_obu_extradata_to_av1c, extract_avcc_from_avcc_packet, extract_hvcc_from_hvcc_packet,
-  is_av1c_extradata,
+  AlphaOption, AvcBitstreamFormat, EncodedVideoChunk, HardwareAcceleration, HdrDynamicMetadata,
+  HdrDynamicMetadataType, HevcBitstreamFormat, LatencyMode, VideoColorSpaceInit,
+  VideoEncoderBitrateMode, VideoEncoderConfig, VideoFrame, convert_annexb_extradata_to_avcc,
+  convert_annexb_extradata_to_hvcc, convert_obu_extradata_to_av1c, extract_avcc_from_avcc_packet,
+  extract_hvcc_from_hvcc_packet, extradata_parameter_sets, is_av1c_extradata,
+  packet_parameter_sets,
 };
 use crossbeam::channel::{self, Receiver, Sender};
 use napi::bindgen_prelude::*;
@@ -31,11 +55,13 @@ use napi::threadsafe_function::{
 };
 use napi_derive::napi;
 use parking_lot::RwLock as ParkingLotRwLock;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, RwLock};
-use std::thread::JoinHandle;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread::{JoinHandle, ThreadId};
+use std::time::Instant;
 
 /// Encoder state per WebCodecs spec
 #[napi(string_enum)]
@@ -61,6 +87,9 @@ pub struct SvcOutputMetadata {
   pub temporal_layer_id: Option<u32>,
 }
 
+/// User correlation tag carried from encode() options to output metadata
+pub type EncodeTag = Either<f64, String>;
+
 /// Output callback metadata per WebCodecs spec
 #[napi(object)]
 pub struct EncodedVideoChunkMetadata {
@@ -69,7 +98,35 @@ pub struct EncodedVideoChunkMetadata {
   /// SVC metadata (temporal layer info)
   pub svc: Option<SvcOutputMetadata>,
   /// Alpha channel side data (when alpha option is "keep")
-  pub alpha_side_data: Option<Uint8Array>,
+  #[napi(ts_type = "Uint8Array")]
+  pub alpha_side_data: Option<PlainUint8Array>,
+  /// Correlation tag passed to encode() for the frame this chunk encodes
+  #[napi(ts_type = "number | string")]
+  pub tag: Option<EncodeTag>,
+  /// HDR dynamic metadata of the frame this chunk encodes
+  #[napi(ts_type = "Array<HdrDynamicMetadata>")]
+  pub hdr_dynamic_metadata: Option<Vec<HdrDynamicMetadata>>,
+  /// Byte budget outcome (only present when encode() was given maxChunkBytes)
+  pub chunk_budget: Option<ChunkBudgetMetadata>,
+  /// References this chunk was stored as and predicted from (only present once
+  /// encode() was given markAsLongTermReference or referenceOnly)
+  pub reference_control: Option<ReferenceControlMetadata>,
+  /// PSNR/SSIM against the source frame (only present with computeQualityMetrics)
+  pub quality: Option<QualityMetrics>,
+  /// True when sceneCutDetection made this chunk a key frame at a detected scene cut
+  pub scene_cut: Option<bool>,
+  /// Actual bits and encoder rate-control state (only present with reportRateControlStats)
+  pub rate_control: Option<RateControlStats>,
+}
+
+/// Outcome of enforcing the `maxChunkBytes` encode option for one chunk
+#[napi(object)]
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkBudgetMetadata {
+  /// Number of re-encodes at a higher quantizer
+  pub retries: u32,
+  /// True if the chunk still exceeds the budget and was delivered anyway
+  pub exceeded: bool,
 }
 
 /// Decoder configuration output (for passing to decoder)
@@ -82,7 +139,8 @@ pub struct VideoDecoderConfigOutput {
   /// Coded height
   pub coded_height: Option<u32>,
   /// Codec description (e.g., avcC for H.264) - Uint8Array per spec
-  pub description: Option<Uint8Array>,
+  #[napi(ts_type = "Uint8Array")]
+  pub description: Option<PlainUint8Array>,
   /// Color space information for the video content
   pub color_space: Option<VideoColorSpaceInit>,
   /// Display aspect width (for non-square pixels)
@@ -145,6 +203,30 @@ pub struct VideoEncoderEncodeOptions {
   pub vp9: Option<VideoEncoderEncodeOptionsForVp9>,
   /// AV1 codec-specific options
   pub av1: Option<VideoEncoderEncodeOptionsForAv1>,
+  /// Opaque correlation tag returned in the metadata of this frame's output chunk
+  /// (extension, not part of W3C WebCodecs)
+  #[napi(ts_type = "number | string")]
+  pub tag: Option<EncodeTag>,
+  /// Maximum size of this frame's chunk in bytes (extension, not part of W3C WebCodecs)
+  ///
+  /// Oversized key frames are re-encoded at higher quantizers when
+  /// `VideoEncoder.isMaxChunkBytesSupported()` reports support for the config.
+  pub max_chunk_bytes: Option<u32>,
+  /// Store this frame as the long-term reference with the given id
+  /// (extension, see `VideoEncoder.isReferenceControlSupported()`)
+  pub mark_as_long_term_reference: Option<u32>,
+  /// Predict this frame only from these long-term reference ids (extension)
+  ///
+  /// Ids the encoder no longer holds are skipped; if none is left the frame
+  /// is encoded as a key frame.
+  pub reference_only: Option<Vec<u32>>,
+  /// A53 closed captions (cc_data triplets) to write as a caption SEI (extension)
+  ///
+  /// Defaults to the frame's `metadata().closedCaptions`. Written by H.264
+  /// and H.265 encoders with A53 support (libx264, libx265, NVENC,
+  /// VideoToolbox); other encoders drop them.
+  #[napi(ts_type = "BufferSource")]
+  pub closed_captions: Option<PlainUint8Array>,
 }
 
 /// Result of isConfigSupported per WebCodecs spec
@@ -157,6 +239,18 @@ pub struct VideoEncoderSupport {
   pub config: VideoEncoderConfig,
 }
 
+/// How H.264/HEVC output reached its AVCC/HVCC chunks (extension)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct EncodeOutputStats {
+  /// Chunks whose start codes were rewritten inside the encoder's packet buffer
+  pub rewritten_chunks: i64,
+  /// Chunks whose bytes were copied into a new buffer during the conversion
+  pub copied_chunks: i64,
+  /// Bytes copied for those chunks
+  pub copied_bytes: i64,
+}
+
 /// Output callback type - uses FnArgs to spread tuple members as separate callback arguments
 /// This matches the WebCodecs spec: output(chunk, metadata) instead of output([chunk, metadata])
 type OutputCallback = ThreadsafeFunction<
@@ -176,6 +270,17 @@ type ErrorCallback = ThreadsafeFunction<Error, UnknownReturnValue, Error, Status
 // Note: For ondequeue, we use FunctionRef instead of ThreadsafeFunction
 // to support both getter and setter per WebCodecs spec
 
+/// Frame buffered for re-encoding on software fallback
+/// Tuple: (Frame, timestamp, options, rotation, flip, HDR dynamic metadata)
+type PendingFrame = (
+  Frame,
+  i64,
+  Option<VideoEncoderEncodeOptions>,
+  f64,
+  bool,
+  Vec<HdrDynamicMetadata>,
+);
+
 /// Commands sent to the worker thread
 enum EncoderCommand {
   /// Encode a video frame
@@ -188,11 +293,19 @@ enum EncoderCommand {
     rotation: f64,
     /// Flip from input VideoFrame (for metadata output)
     flip: bool,
+    /// HDR dynamic metadata from input VideoFrame (passed through to output)
+    hdr_dynamic_metadata: Vec<HdrDynamicMetadata>,
+    /// Visible rect (left, top, width, height) when smaller than the coded frame
+    visible_crop: Option<(u32, u32, u32, u32)>,
+    /// When encode() was called (for queue latency tracking)
+    enqueued_at: Instant,
   },
   /// Flush the encoder and send result back via response channel
   Flush(Sender<Result<()>>),
   /// Reconfigure the encoder with new config (W3C spec: control message)
   Reconfigure(VideoEncoderConfig),
+  /// Resolve a configureAsync() once the commands queued before it have run
+  ConfigureDone(Sender<Result<()>>),
 }
 
 /// VideoEncoder init dictionary per WebCodecs spec
@@ -344,17 +457,22 @@ struct VideoEncoderInner {
   scaler: Option<Scaler>,
   frame_count: u64,
   extradata_sent: bool,
+  /// H.264/H.265 parameter sets (Annex B) behind the last emitted decoderConfig
+  described_parameter_sets: Option<Vec<u8>>,
   /// Number of pending encode operations (for encodeQueueSize)
   encode_queue_size: u32,
-  /// Output callback (required per spec)
-  output_callback: OutputCallback,
+  /// Output callback (required per spec); shared so the worker can call it
+  /// after releasing the lock
+  output_callback: Arc<OutputCallback>,
   /// Error callback (required per spec)
   error_callback: ErrorCallback,
   /// Pending flush response senders (for AbortError on reset)
   pending_flush_senders: Vec<Sender<Result<()>>>,
-  /// Queue of input timestamps for correlation with output packets
+  /// Pending configureAsync() response senders, in call order
+  pending_configure_senders: Vec<Sender<Result<()>>>,
+  /// Queue of in-flight inputs for correlation with output packets
   /// (needed because FFmpeg may buffer frames internally and reorder)
-  timestamp_queue: std::collections::VecDeque<i64>,
+  timestamp_queue: VecDeque<PendingInput>,
 
   // ========================================================================
   // Hardware acceleration tracking (for Chromium-aligned fallback behavior)
@@ -370,8 +488,7 @@ struct VideoEncoderInner {
   /// Whether first output has been produced (disables silent failure detection after)
   first_output_produced: bool,
   /// Buffered frames during silent failure detection period (for re-encoding on fallback)
-  /// Tuple: (Frame, timestamp, options, rotation, flip)
-  pending_frames: Vec<(Frame, i64, Option<VideoEncoderEncodeOptions>, f64, bool)>,
+  pending_frames: Vec<PendingFrame>,
   /// Atomic flag for flush abort - set by reset() to signal pending flush to abort
   flush_abort_flag: Option<Arc<AtomicBool>>,
   /// Queue of encoded chunks waiting to be delivered via output callback
@@ -402,6 +519,22 @@ struct VideoEncoderInner {
   /// Counter for output frames used to compute temporal layer ID
   /// Reset on configure() and reset()
   output_frame_count: u64,
+  /// Long-term reference slots (markAsLongTermReference / referenceOnly)
+  long_term_references: LongTermReferences,
+
+  // ========================================================================
+  // Quality metrics (computeQualityMetrics)
+  // ========================================================================
+  /// Software decoder reconstructing each output packet, created on first use
+  quality_decoder: Option<CodecContext>,
+  /// Output chunks waiting for their reconstructed frame, in output order
+  quality_holds: VecDeque<QualityHold>,
+
+  // ========================================================================
+  // Scene cut detection (sceneCutDetection)
+  // ========================================================================
+  /// Luma histogram of the previous frame, compared on the worker before encoding
+  scene_cut_detector: SceneCutDetector,
 
   // ========================================================================
   // Bitstream format conversion
@@ -416,6 +549,9 @@ struct VideoEncoderInner {
   // ========================================================================
   /// Color space from the first input frame (used in decoderConfig metadata)
   input_color_space: Option<VideoColorSpaceInit>,
+  /// Display size from the first input frame (used in decoderConfig metadata
+  /// when the config doesn't set displayWidth/displayHeight)
+  input_display_size: Option<(u32, u32)>,
 
   // ========================================================================
   // Alpha channel support
@@ -426,6 +562,9 @@ struct VideoEncoderInner {
   /// Pixel format for encoding (YUV420P, YUVA420P, or YUVA420P10LE for 10-bit HEVC alpha)
   /// Stored during configure to ensure consistent format across encode, flush, and fallback paths
   pixel_format: AVPixelFormat,
+  /// FFmpeg options (defaults merged with ffmpegOptions) captured at configure,
+  /// reapplied when the flush and fallback paths recreate the context
+  ffmpeg_options: FfmpegOptions,
 
   // ========================================================================
   // Codec identification (for per-frame QP handling)
@@ -440,6 +579,22 @@ struct VideoEncoderInner {
   /// Whether we acquired a hardware encoder slot from the pressure gauge
   /// Must be released on close/drop/fallback to avoid resource leaks
   acquired_hw_slot: bool,
+  /// Counts this encoder in getHardwareSessionCounts() while it holds a hardware session
+  hw_session: Option<HwEncoderSession>,
+
+  // ========================================================================
+  // Two-pass encoding
+  // ========================================================================
+  /// First-pass statistics published by the encoder on the last flush
+  pass_stats: Option<Vec<u8>>,
+
+  // ========================================================================
+  // Achieved bitrate
+  // ========================================================================
+  /// Size and timestamp of every output chunk since configure()
+  output_bitrate: BitrateTracker,
+  /// How AVCC/HVCC chunks were produced since the encoder was created
+  output_stats: EncodeOutputStats,
 }
 
 /// Get default GOP settings based on latency mode.
@@ -466,7 +621,7 @@ struct VideoEncoderInner {
 ///
 /// - Small GOP (10): Ensures frequent keyframes for seeking/recovery
 /// - No B-frames (0): Eliminates encoding latency (B-frames require future frames)
-fn get_default_gop_settings(realtime: bool) -> (Option<u32>, Option<u32>) {
+pub(crate) fn get_default_gop_settings(realtime: bool) -> (Option<u32>, Option<u32>) {
   if realtime {
     (Some(10), Some(0)) // Low latency: small GOP, no B-frames
   } else {
@@ -474,6 +629,289 @@ fn get_default_gop_settings(realtime: bool) -> (Option<u32>, Option<u32>) {
   }
 }
 
+/// Encoder frame rate as an exact rational, e.g. 30000/1001 for 29.97
+///
+/// Truncating to an integer would configure 29 fps for NTSC input, skewing
+/// rate control; without a framerate the encoder assumes 30.
+pub(crate) fn encoder_framerate(framerate: Option<f64>) -> (u32, u32) {
+  framerate
+    .and_then(framerate_rational)
+    .map(|(num, den)| (num as u32, den as u32))
+    .unwrap_or((30, 1))
+}
+
+/// Whether the codec's FFmpeg software encoders support two-pass encoding
+///
+/// libvpx, libaom and rav1e exchange statistics via `stats_out`/`stats_in`;
+/// libx264/libx265 go through a private stats file (see `codec::pass_stats`).
+fn supports_two_pass(codec_id: AVCodecID) -> bool {
+  matches!(
+    codec_id,
+    AVCodecID::Vp8 | AVCodecID::Vp9 | AVCodecID::Av1 | AVCodecID::H264 | AVCodecID::Hevc
+  )
+}
+
+/// Map the `pass`/`statsData` config members to the codec-layer pass
+fn encoder_pass(config: &VideoEncoderConfig) -> Option<EncoderPass> {
+  match config.pass {
+    Some(1) => Some(EncoderPass::First),
+    Some(2) => Some(EncoderPass::Second(
+      config.stats_data.clone().unwrap_or_default(),
+    )),
+    _ => None,
+  }
+}
+
+/// Check that a two-pass config can be encoded, returning a NotSupportedError message
+fn check_two_pass_support(config: &VideoEncoderConfig, codec_id: AVCodecID) -> Option<String> {
+  config.pass?;
+  if !supports_two_pass(codec_id) {
+    return Some(
+      "NotSupportedError: Two-pass encoding is only supported for H.264, HEVC, VP8, VP9 and AV1"
+        .to_string(),
+    );
+  }
+  if config.hardware_acceleration == Some(HardwareAcceleration::PreferHardware) {
+    return Some("NotSupportedError: Two-pass encoding requires a software encoder".to_string());
+  }
+  // x264/x265 reject a constant rate factor in the second pass
+  if matches!(codec_id, AVCodecID::H264 | AVCodecID::Hevc)
+    && config.bitrate_mode == Some(VideoEncoderBitrateMode::Quantizer)
+  {
+    return Some(
+      "NotSupportedError: Two-pass H.264/HEVC encoding requires a target bitrate".to_string(),
+    );
+  }
+  None
+}
+
+/// Strip or replace the x264/x265 info SEI according to `encoderInfo`
+///
+/// Packets without an info SEI (and any packet when `encoderInfo` is unset) are
+/// returned as-is.
+fn apply_encoder_info(inner: &VideoEncoderInner, packet: Packet) -> Packet {
+  let (Some(replacement), Some(codec_id)) = (
+    inner
+      .config
+      .as_ref()
+      .and_then(|c| c.encoder_info.as_deref()),
+    inner.codec_id,
+  ) else {
+    return packet;
+  };
+  let Some(data) = rewrite_encoder_info_sei(packet.as_slice(), codec_id, replacement) else {
+    return packet;
+  };
+
+  let Ok(mut rewritten) = Packet::new() else {
+    return packet;
+  };
+  if rewritten.copy_data_from(&data).is_err() {
+    return packet;
+  }
+  // copy_data_from resets timing and flags
+  rewritten.set_pts(packet.pts());
+  rewritten.set_dts(packet.dts());
+  rewritten.set_duration(packet.duration());
+  rewritten.set_flags(packet.flags());
+  rewritten
+}
+
+/// Maximum picture width/height for an HEVC general_level_idc (H.265 Table A.8)
+///
+/// The spec bounds each dimension by sqrt(8 * MaxLumaPs).
+fn hevc_level_max_dimension(level_idc: u8) -> Option<u32> {
+  let max_luma_ps: u64 = match level_idc {
+    30 => 36_864,
+    60 => 122_880,
+    63 => 245_760,
+    90 => 552_960,
+    93 => 983_040,
+    120 | 123 => 2_228_224,
+    150 | 153 | 156 => 8_912_896,
+    180 | 183 | 186 => 35_651_584,
+    _ => return None,
+  };
+  Some((8 * max_luma_ps).isqrt() as u32)
+}
+
+/// Maximum picture width and height for an AV1 seq_level_idx (AV1 Annex A.3)
+fn av1_level_max_size(seq_level_idx: u8) -> Option<(u32, u32)> {
+  match seq_level_idx {
+    0 => Some((2048, 1152)),
+    1 => Some((2816, 1584)),
+    4 => Some((4352, 2448)),
+    5 => Some((5504, 3096)),
+    8 | 9 => Some((6144, 3456)),
+    12..=15 => Some((8192, 4352)),
+    16..=19 => Some((16384, 8704)),
+    _ => None,
+  }
+}
+
+/// Check parameter combinations libx265/libaom fail hard on, returning a
+/// NotSupportedError message
+///
+/// A picture larger than the codec string's level allows makes the encoder's
+/// level conformance checks fail inside avcodec_open2 (or abort, for some
+/// library builds), so it is rejected before an encoder is created.
+fn check_encoder_parameters(config: &VideoEncoderConfig, codec_id: AVCodecID) -> Option<String> {
+  let (width, height) = (config.width?, config.height?);
+  let level = crate::webcodecs::codec_string::parse_codec_string(config.codec.as_deref()?)?.level?;
+  let (max_width, max_height, level_name) = match codec_id {
+    AVCodecID::Hevc => {
+      let max = hevc_level_max_dimension(level)?;
+      (max, max, format!("{}.{}", level / 30, level % 30 / 3))
+    }
+    AVCodecID::Av1 => {
+      let (max_width, max_height) = av1_level_max_size(level)?;
+      (
+        max_width,
+        max_height,
+        format!("{}.{}", 2 + level / 4, level % 4),
+      )
+    }
+    _ => return None,
+  };
+  if width > max_width || height > max_height {
+    return Some(format!(
+      "NotSupportedError: {}x{} exceeds the maximum picture size of level {} ({}x{})",
+      width, height, level_name, max_width, max_height
+    ));
+  }
+  None
+}
+
+/// Smallest picture libx265 encodes: one CTU, 32x32 with the ultrafast preset
+const X265_MIN_DIMENSION: u32 = 32;
+
+/// Smallest picture assumed for hardware encoders other than NVENC
+///
+/// VAAPI, QSV, AMF and VideoToolbox report driver-dependent minimums of up
+/// to one 64x64 HEVC CTU. Using the largest lets no-preference fall back to
+/// software before a driver rejects (or silently corrupts) a tiny picture.
+const HW_MIN_DIMENSION: u32 = 64;
+
+/// Check the picture size against the software encoder's lower limits,
+/// returning a NotSupportedError message
+///
+/// libx264 and libx265 need dimensions divisible by the chroma subsampling,
+/// and libx265 at least one CTU. libvpx and libaom encode anything from 1x1,
+/// so only the subsampling applies to them (and they pad odd sizes themselves).
+fn check_min_picture_size(config: &VideoEncoderConfig, codec_id: AVCodecID) -> Option<String> {
+  let (width, height) = (config.width?, config.height?);
+  let (encoder, min_dimension) = match codec_id {
+    AVCodecID::H264 => ("libx264", 1),
+    AVCodecID::Hevc => ("libx265", X265_MIN_DIMENSION),
+    _ => return None,
+  };
+  let (log2_w, log2_h) =
+    encoder_pixel_format(codec_id, config.codec.as_deref()?, false).chroma_subsampling();
+  let (min_width, min_height) = (
+    min_dimension.max(1 << log2_w),
+    min_dimension.max(1 << log2_h),
+  );
+  if width < min_width || height < min_height {
+    return Some(format!(
+      "NotSupportedError: {}x{} is below the minimum picture size of {} ({}x{})",
+      width, height, encoder, min_width, min_height
+    ));
+  }
+  if width % (1 << log2_w) != 0 || height % (1 << log2_h) != 0 {
+    return Some(format!(
+      "NotSupportedError: {} needs a width divisible by {} and a height divisible by {} (got {}x{})",
+      encoder,
+      1 << log2_w,
+      1 << log2_h,
+      width,
+      height
+    ));
+  }
+  None
+}
+
+/// Check the picture size against a hardware encoder's lower limits,
+/// returning a NotSupportedError message
+///
+/// NVENC minimums are from NVIDIA's NVENC application note (NV_ENC_CAPS_WIDTH_MIN
+/// and NV_ENC_CAPS_HEIGHT_MIN); other hardware uses HW_MIN_DIMENSION.
+fn check_hardware_min_picture_size(
+  width: u32,
+  height: u32,
+  codec_id: AVCodecID,
+  encoder_name: &str,
+) -> Option<String> {
+  let (min_width, min_height) = if encoder_name.ends_with("_nvenc") {
+    match codec_id {
+      AVCodecID::H264 => (145, 49),
+      _ => (129, 33),
+    }
+  } else {
+    (HW_MIN_DIMENSION, HW_MIN_DIMENSION)
+  };
+  if width < min_width || height < min_height {
+    return Some(format!(
+      "NotSupportedError: {}x{} is below the minimum picture size of {} ({}x{})",
+      width, height, encoder_name, min_width, min_height
+    ));
+  }
+  None
+}
+
+/// Largest pixel aspect stretch (either direction) accepted from displayWidth/displayHeight
+const MAX_PIXEL_ASPECT_STRETCH: f64 = 8.0;
+
+/// Sample aspect ratio implied by displayWidth/displayHeight (None = square pixels)
+fn display_sample_aspect_ratio(config: &VideoEncoderConfig) -> Option<(i32, i32)> {
+  let width = config.width?;
+  let height = config.height?;
+  crate::codec::sample_aspect_ratio(
+    width,
+    height,
+    config.display_width.unwrap_or(width),
+    config.display_height.unwrap_or(height),
+  )
+}
+
+/// Check that the display size can be signalled as a pixel aspect ratio,
+/// returning a NotSupportedError message
+fn check_display_aspect_support(config: &VideoEncoderConfig) -> Option<String> {
+  let (width, height) = (config.width? as f64, config.height? as f64);
+  let display_width = config.display_width.map_or(width, |w| w as f64);
+  let display_height = config.display_height.map_or(height, |h| h as f64);
+  let stretch = (display_width * height) / (display_height * width);
+  if !(1.0 / MAX_PIXEL_ASPECT_STRETCH..=MAX_PIXEL_ASPECT_STRETCH).contains(&stretch) {
+    return Some(format!(
+      "NotSupportedError: Display size {}x{} is inconsistent with coded size {}x{}",
+      display_width, display_height, width, height
+    ));
+  }
+  None
+}
+
+/// Codec of a config that passes every isConfigSupported() check made
+/// without FFmpeg: dimensions, display aspect, scalability mode, codec
+/// string, level limits and software minimum picture size
+fn support_codec_id(config: &VideoEncoderConfig, codec: &str) -> Option<AVCodecID> {
+  if !are_dimensions_valid(config.width.unwrap_or(0), config.height.unwrap_or(0))
+    || check_display_aspect_support(config).is_some()
+  {
+    return None;
+  }
+  if let Some(ref mode) = config.scalability_mode
+    && !is_valid_scalability_mode(mode)
+  {
+    return None;
+  }
+  let codec_id = parse_codec_string(codec).ok()?;
+  if check_encoder_parameters(config, codec_id).is_some()
+    || check_min_picture_size(config, codec_id).is_some()
+  {
+    return None;
+  }
+  Some(codec_id)
+}
+
 /// Get the preferred hardware device type for the current platform
 fn get_platform_hw_type() -> AVHWDeviceType {
   #[cfg(target_os = "macos")]
@@ -539,16 +977,30 @@ pub struct VideoEncoder {
   worker_handle: Option<JoinHandle<()>>,
   /// Reset abort flag - set by reset() to signal worker to skip pending encodes
   reset_flag: Arc<AtomicBool>,
+  /// Environment teardown flag, shared with every worker this codec spawns
+  shutdown_flag: Arc<AtomicBool>,
+  /// Queue wait / processing time samples, shared with every worker this codec spawns
+  command_timings: Arc<CommandTimings>,
+  /// JS thread that created the encoder; calls from any other thread are rejected
+  owner_thread: ThreadId,
 }
 
+/// Message for calls made from a thread other than the encoder's own
+const WRONG_THREAD_MESSAGE: &str = "VideoEncoder can only be used from the thread that created it";
+
 impl Drop for VideoEncoder {
   fn drop(&mut self) {
-    // Signal worker to stop
+    // Signal worker to stop, skipping any queued work
+    self.reset_flag.store(true, Ordering::SeqCst);
     self.command_sender = None;
 
-    // Wait for worker to finish (brief block, necessary for safety)
-    if let Some(handle) = self.worker_handle.take() {
-      let _ = handle.join();
+    // Wait for worker to finish. The wait is bounded so a busy worker (e.g. an
+    // AV1 flush) cannot hold up process exit; a detached worker still owns the
+    // codec state and releases it when its current command completes.
+    if let Some(handle) = self.worker_handle.take()
+      && !shutdown::join_worker(handle, "VideoEncoder")
+    {
+      return;
     }
 
     // Drain encoder to ensure libaom/AV1 threads finish before context drops.
@@ -567,6 +1019,7 @@ impl Drop for VideoEncoder {
         codec_pressure::gauge().release_hw_encoder();
         inner.acquired_hw_slot = false;
       }
+      inner.hw_session = None;
     }
   }
 }
@@ -578,6 +1031,7 @@ impl VideoEncoder {
   /// @param init - Init dictionary containing output and error callbacks
   #[napi(constructor)]
   pub fn new(
+    env: Env,
     #[napi(
       ts_arg_type = "{ output: (chunk: EncodedVideoChunk, metadata?: EncodedVideoChunkMetadata) => void, error: (error: Error) => void }"
     )]
@@ -590,11 +1044,13 @@ impl VideoEncoder {
       scaler: None,
       frame_count: 0,
       extradata_sent: false,
+      described_parameter_sets: None,
       encode_queue_size: 0,
-      output_callback: init.output,
+      output_callback: Arc::new(init.output),
       error_callback: init.error,
       pending_flush_senders: Vec::new(),
-      timestamp_queue: std::collections::VecDeque::new(),
+      pending_configure_senders: Vec::new(),
+      timestamp_queue: VecDeque::new(),
       // Hardware acceleration tracking
       is_hardware: false,
       encoder_name: String::new(),
@@ -612,18 +1068,30 @@ impl VideoEncoder {
       nv12_scaler: None,
       // Temporal SVC tracking
       temporal_layer_count: None,
+      long_term_references: LongTermReferences::default(),
       output_frame_count: 0,
+      // Quality metrics (created on first measured packet)
+      quality_decoder: None,
+      quality_holds: VecDeque::new(),
+      scene_cut_detector: SceneCutDetector::default(),
       // Bitstream format conversion (set during configure)
       use_avcc_format: false,
       // Input colorSpace tracking
       input_color_space: None,
+      input_display_size: None,
       // Alpha channel support (set during configure)
       use_alpha: false,
       pixel_format: AVPixelFormat::Yuv420p,
+      ffmpeg_options: FfmpegOptions::new(),
       // Codec identification (set during configure)
       codec_id: None,
       // Hardware encoder pressure tracking (managed by codec_pressure gauge)
       acquired_hw_slot: false,
+      hw_session: None,
+      // Two-pass statistics (collected on flush in pass 1)
+      pass_stats: None,
+      output_bitrate: BitrateTracker::default(),
+      output_stats: EncodeOutputStats::default(),
     };
 
     let inner = Arc::new(Mutex::new(inner));
@@ -637,16 +1105,25 @@ impl VideoEncoder {
     // Create reset abort flag
     let reset_flag = Arc::new(AtomicBool::new(false));
 
+    // Set when the environment tears down, so the worker skips queued work
+    let shutdown_flag = shutdown::env_shutdown_flag(&env)?;
+
+    let command_timings = Arc::new(CommandTimings::new());
+
     // Spawn worker thread
     let worker_inner = inner.clone();
     let worker_event_state = event_state.clone();
     let worker_reset_flag = reset_flag.clone();
+    let worker_shutdown_flag = shutdown_flag.clone();
+    let worker_timings = command_timings.clone();
     let worker_handle = std::thread::spawn(move || {
       Self::worker_loop(
         worker_inner,
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
+        worker_timings,
       );
     });
 
@@ -659,20 +1136,33 @@ impl VideoEncoder {
       command_sender: Some(Arc::new(sender)),
       worker_handle: Some(worker_handle),
       reset_flag,
+      shutdown_flag,
+      command_timings,
+      owner_thread: std::thread::current().id(),
     })
   }
 
+  /// Whether the caller runs on the JS thread that created this encoder
+  ///
+  /// The inner state assumes calls from one JS thread; output callbacks and
+  /// flush resolvers run there too, so reentrant calls from them are allowed.
+  fn on_owner_thread(&self) -> bool {
+    std::thread::current().id() == self.owner_thread
+  }
+
   /// Worker loop that processes commands from the channel
   fn worker_loop(
     inner: Arc<Mutex<VideoEncoderInner>>,
     event_state: Arc<RwLock<EventListenerState>>,
     receiver: Receiver<EncoderCommand>,
     reset_flag: Arc<AtomicBool>,
+    shutdown_flag: Arc<AtomicBool>,
+    command_timings: Arc<CommandTimings>,
   ) {
     while let Ok(command) = receiver.recv() {
-      // Check reset flag before processing each command
-      // If reset() was called, skip remaining encode commands
-      if reset_flag.load(Ordering::SeqCst) {
+      // Check reset and shutdown flags before processing each command
+      // If reset() was called or the environment is shutting down, skip remaining encode commands
+      if reset_flag.load(Ordering::SeqCst) || shutdown_flag.load(Ordering::SeqCst) {
         // Still process flush commands to send responses, but skip encodes
         if let EncoderCommand::Flush(response_sender) = command {
           let _ = response_sender.send(Err(Error::new(
@@ -692,14 +1182,25 @@ impl VideoEncoder {
         continue;
       }
 
-      match command {
+      // A panic while processing a command closes the encoder with an
+      // OperationError instead of unwinding out of the worker thread
+      let flush_responder = match &command {
+        EncoderCommand::Flush(response_sender) => Some(response_sender.clone()),
+        _ => None,
+      };
+      let outcome = panic::catch_unwind(AssertUnwindSafe(|| match command {
         EncoderCommand::Encode {
           frame,
           timestamp,
           options,
           rotation,
           flip,
+          hdr_dynamic_metadata,
+          visible_crop,
+          enqueued_at,
         } => {
+          let _span = tracing::debug_span!(target: "webcodecs", "encode", timestamp).entered();
+          let (queue_wait, started) = begin_command(enqueued_at);
           Self::process_encode(
             &inner,
             &event_state,
@@ -708,6 +1209,16 @@ impl VideoEncoder {
             options,
             rotation,
             flip,
+            hdr_dynamic_metadata,
+            visible_crop,
+          );
+          let processing = started.elapsed();
+          command_timings.record(queue_wait, processing);
+          tracing::debug!(
+            target: "webcodecs",
+            queue_wait_us = queue_wait.as_micros() as u64,
+            processing_us = processing.as_micros() as u64,
+            "Encode command processed"
           );
         }
         EncoderCommand::Flush(response_sender) => {
@@ -715,13 +1226,27 @@ impl VideoEncoder {
           let _ = response_sender.send(result);
         }
         EncoderCommand::Reconfigure(config) => {
-          Self::process_reconfigure(&inner, config);
+          Self::process_reconfigure(&inner, &event_state, config);
+        }
+        EncoderCommand::ConfigureDone(response_sender) => {
+          Self::process_configure_done(&inner, &response_sender);
+        }
+      }));
+      if let Err(payload) = outcome {
+        let message = format!(
+          "OperationError: Encoder worker panicked: {}",
+          panic_message(&*payload)
+        );
+        Self::recover_from_panic(&inner, &message);
+        if let Some(response_sender) = flush_responder {
+          let _ = response_sender.send(Err(Error::new(Status::GenericFailure, message)));
         }
       }
     }
   }
 
   /// Process an encode command on the worker thread
+  #[allow(clippy::too_many_arguments)]
   fn process_encode(
     inner: &Arc<Mutex<VideoEncoderInner>>,
     event_state: &Arc<RwLock<EventListenerState>>,
@@ -730,11 +1255,14 @@ impl VideoEncoder {
     options: Option<VideoEncoderEncodeOptions>,
     rotation: f64,
     flip: bool,
+    hdr_dynamic_metadata: Vec<HdrDynamicMetadata>,
+    visible_crop: Option<(u32, u32, u32, u32)>,
   ) {
     let mut guard = match inner.lock() {
       Ok(g) => g,
       Err(_) => return, // Lock poisoned
     };
+    check_injected_encoder_panic(timestamp);
 
     // Check if encoder is still configured
     if guard.state != CodecState::Configured {
@@ -751,13 +1279,16 @@ impl VideoEncoder {
 
     // Get config info (unwrap validated config values)
     let (width, height, codec_string, display_width, display_height) = match guard.config.as_ref() {
-      Some(config) => (
-        config.width.unwrap_or(0),
-        config.height.unwrap_or(0),
-        config.codec.clone().unwrap_or_default(),
-        config.display_width,
-        config.display_height,
-      ),
+      Some(config) => {
+        let (display_width, display_height) = display_aspect(config, guard.input_display_size);
+        (
+          config.width.unwrap_or(0),
+          config.height.unwrap_or(0),
+          config.codec.clone().unwrap_or_default(),
+          display_width,
+          display_height,
+        )
+      }
       None => {
         let old_size = guard.encode_queue_size;
         guard.encode_queue_size = old_size.saturating_sub(1);
@@ -773,14 +1304,110 @@ impl VideoEncoder {
     let target_format = guard.pixel_format;
 
     // Acquire read lock on the shared frame
-    let frame_guard = frame_arc.read();
+    let shared_frame = frame_arc.read();
+
+    // Restrict a reference to the visible rect so padding rows/columns of the
+    // coded frame are never encoded (the shared pixel data is untouched)
+    let cropped_frame = match visible_crop {
+      Some((left, top, crop_width, crop_height)) => {
+        match shared_frame.shallow_clone().and_then(|mut cropped| {
+          cropped.crop(left, top, crop_width, crop_height)?;
+          Ok(cropped)
+        }) {
+          Ok(cropped) => Some(cropped),
+          Err(e) => {
+            drop(shared_frame);
+            let old_size = guard.encode_queue_size;
+            guard.encode_queue_size = old_size.saturating_sub(1);
+            if old_size > 0 {
+              let _ = Self::fire_dequeue_event(event_state);
+            }
+            Self::report_error(&mut guard, &format!("Failed to crop frame: {}", e));
+            return;
+          }
+        }
+      }
+      None => None,
+    };
+
+    // applyRotation: encode an upright copy, so the configured width/height
+    // describe the rotated picture and no orientation metadata is output
+    let bake_rotation = (rotation != 0.0 || flip)
+      && guard
+        .config
+        .as_ref()
+        .is_some_and(|c| c.apply_rotation == Some(true));
+    let oriented_frame = if bake_rotation {
+      match orient_frame(
+        cropped_frame.as_ref().unwrap_or(&shared_frame),
+        rotation as u32,
+        flip,
+      ) {
+        Ok(oriented) => Some(oriented),
+        Err(e) => {
+          drop(cropped_frame);
+          drop(shared_frame);
+          let old_size = guard.encode_queue_size;
+          guard.encode_queue_size = old_size.saturating_sub(1);
+          if old_size > 0 {
+            let _ = Self::fire_dequeue_event(event_state);
+          }
+          Self::report_error(&mut guard, &format!("Failed to rotate frame: {}", e));
+          return;
+        }
+      }
+    } else {
+      None
+    };
+    let (rotation, flip) = if bake_rotation {
+      (0.0, false)
+    } else {
+      (rotation, flip)
+    };
+    let frame_guard: &Frame = oriented_frame
+      .as_ref()
+      .or(cropped_frame.as_ref())
+      .unwrap_or(&shared_frame);
 
-    // Check if frame needs conversion
+    // Check if frame needs conversion (visible dimensions vs configured size)
+    // With alpha "discard", planar YUVA input whose colour planes already match the
+    // target format only needs its alpha plane dropped, not a full conversion
     let frame_format = frame_guard.format();
-    let needs_conversion = frame_format != target_format
+    let strip_alpha_only = !guard.use_alpha && frame_format.without_alpha() == Some(target_format);
+    let needs_conversion = (frame_format != target_format && !strip_alpha_only)
       || frame_guard.width() != width
       || frame_guard.height() != height;
 
+    // Refuse formats the scaler cannot convert, or only with lost bit depth,
+    // before touching the scaler
+    if let Some(reason) = unsupported_input_reason(frame_format, target_format) {
+      drop(shared_frame);
+      let old_size = guard.encode_queue_size;
+      guard.encode_queue_size = old_size.saturating_sub(1);
+      if old_size > 0 {
+        let _ = Self::fire_dequeue_event(event_state);
+      }
+      let message = format!(
+        "NotSupportedError: Cannot encode {} VideoFrame with {}: {} (the encoder accepts {} input). \
+         Convert the frame to a format listed by getCodecCapabilities() or configure a codec with a \
+         higher bit depth",
+        format_name(frame_format),
+        codec_string,
+        reason,
+        format_name(target_format)
+      );
+      if guard
+        .config
+        .as_ref()
+        .is_some_and(|c| c.skip_unsupported_frames == Some(true))
+      {
+        Self::report_frame_error(&guard, &message);
+      } else {
+        Self::report_error(&mut guard, &message);
+      }
+      return;
+    }
+
     // Convert frame if needed, or deep copy if we need to mutate it
     let mut frame_to_encode = if needs_conversion {
       // Create scaler if needed
@@ -796,7 +1423,7 @@ impl VideoEncoder {
         ) {
           Ok(scaler) => guard.scaler = Some(scaler),
           Err(e) => {
-            drop(frame_guard);
+            drop(shared_frame);
             let old_size = guard.encode_queue_size;
             guard.encode_queue_size = old_size.saturating_sub(1);
             if old_size > 0 {
@@ -809,10 +1436,10 @@ impl VideoEncoder {
       }
 
       let scaler = guard.scaler.as_ref().unwrap();
-      match scaler.scale_alloc(&frame_guard) {
+      match scaler.scale_alloc(frame_guard) {
         Ok(scaled) => scaled,
         Err(e) => {
-          drop(frame_guard);
+          drop(shared_frame);
           let old_size = guard.encode_queue_size;
           guard.encode_queue_size = old_size.saturating_sub(1);
           if old_size > 0 {
@@ -830,7 +1457,7 @@ impl VideoEncoder {
       match frame_guard.shallow_clone() {
         Ok(shallow) => shallow,
         Err(e) => {
-          drop(frame_guard);
+          drop(shared_frame);
           let old_size = guard.encode_queue_size;
           guard.encode_queue_size = old_size.saturating_sub(1);
           if old_size > 0 {
@@ -843,7 +1470,14 @@ impl VideoEncoder {
     };
 
     // Release the read lock now that we have an owned frame
-    drop(frame_guard);
+    drop(oriented_frame);
+    drop(cropped_frame);
+    drop(shared_frame);
+
+    // Drop the alpha plane from our reference only (the caller's VideoFrame keeps it)
+    if strip_alpha_only && !needs_conversion {
+      frame_to_encode.drop_alpha_plane();
+    }
 
     // Set frame PTS - convert from microseconds to encoder time_base units
     // FFmpeg expects frame->pts in time_base units, not microseconds
@@ -856,10 +1490,38 @@ impl VideoEncoder {
     frame_to_encode.set_pts(pts_in_timebase);
 
     // Force keyframe if requested via encode options (W3C WebCodecs spec)
-    if options.as_ref().is_some_and(|o| o.key_frame == Some(true)) {
+    let key_frame = options.as_ref().is_some_and(|o| o.key_frame == Some(true));
+    // ...or at a detected scene cut (sceneCutDetection); every frame updates the detector
+    let scene_cut = scene_cut_detection_enabled(&guard)
+      && guard.scene_cut_detector.detect(&frame_to_encode)
+      && !key_frame;
+    if key_frame || scene_cut {
       frame_to_encode.set_pict_type(AVPictureType::I);
     }
 
+    // Long-term reference control (libvpx buffer flags via frame metadata)
+    let mark = options.as_ref().and_then(|o| o.mark_as_long_term_reference);
+    let reference_only = options.as_ref().and_then(|o| o.reference_only.as_deref());
+    let reference_control =
+      if (mark.is_some() || reference_only.is_some() || guard.long_term_references.is_active())
+        && guard
+          .codec_id
+          .is_some_and(|codec_id| supports_reference_control(codec_id, &guard.encoder_name))
+      {
+        let plan = guard
+          .long_term_references
+          .plan(mark, reference_only, key_frame || scene_cut);
+        if plan.force_key_frame {
+          frame_to_encode.set_pict_type(AVPictureType::I);
+        }
+        if plan.flags != 0 {
+          frame_to_encode.set_metadata(c"vp8-flags", &plan.flags.to_string());
+        }
+        Some(plan.metadata)
+      } else {
+        None
+      };
+
     // Apply per-frame quantizer if specified in encode options.
     // This enables W3C WebCodecs per-frame QP control with bitrateMode: 'quantizer'.
     //
@@ -886,6 +1548,34 @@ impl VideoEncoder {
       }
     }
 
+    // Attach HDR10+ metadata for encoders that write it into the bitstream
+    // (e.g. libx265, libsvtav1). Every blob is also passed through on the output chunk.
+    for entry in &hdr_dynamic_metadata {
+      if entry.kind == HdrDynamicMetadataType::ItuTT35 {
+        frame_to_encode.set_hdr10plus_t35(&entry.data);
+      }
+    }
+
+    // Closed captions come from the encode options (filled from the frame
+    // metadata), replacing any caption side data a referenced frame carries
+    let closed_captions = options.as_ref().and_then(|o| o.closed_captions.as_deref());
+    frame_to_encode.set_a53_cc(closed_captions);
+
+    // Keep a reference to key frames with a byte budget so an oversized chunk can be
+    // re-encoded on its own; delta frames depend on the encoder's reference state
+    let chunk_budget = options
+      .as_ref()
+      .and_then(|o| o.max_chunk_bytes)
+      .map(|max_bytes| ChunkBudget {
+        max_bytes,
+        frame: (options.as_ref().is_some_and(|o| o.key_frame == Some(true))
+          && guard.codec_id.is_some_and(|codec_id| {
+            supports_chunk_budget(codec_id, guard.is_hardware, guard.use_alpha)
+          }))
+        .then(|| frame_to_encode.shallow_clone().ok())
+        .flatten(),
+      });
+
     // Before GPU upload, save original CPU frame for potential fallback.
     // When hardware encoding fails, we need the CPU frame (with valid linesize)
     // for software fallback. GPU frames have linesize=0 and can't be encoded by software.
@@ -895,19 +1585,40 @@ impl VideoEncoder {
       None
     };
 
+    // Keep the pre-encode frame to measure the decoded chunk against
+    let quality_reference = if quality_metrics_enabled(&guard) {
+      frame_to_encode.shallow_clone().ok()
+    } else {
+      None
+    };
+
     // Upload frame to GPU if hardware frame context is available
     // This provides zero-copy encoding for hardware encoders
     if guard.use_hw_frames && guard.hw_frame_ctx.is_some() {
       let hw_upload_result = Self::try_upload_to_gpu(&mut guard, &frame_to_encode);
       if let Some(hw_frame) = hw_upload_result {
         frame_to_encode = hw_frame;
+        // The NV12 conversion before the upload drops side data
+        frame_to_encode.set_a53_cc(closed_captions);
       }
       // If upload failed, use_hw_frames is set to false and we continue with CPU frame
     }
 
-    // Push timestamp to queue for correlation with output packets
+    // Push timestamp and tag to queue for correlation with output packets
     // (FFmpeg may modify PTS internally, so we track input timestamps separately)
-    guard.timestamp_queue.push_back(timestamp);
+    push_pending_input(
+      &mut guard.timestamp_queue,
+      PendingInput {
+        pts: pts_in_timebase,
+        timestamp,
+        tag: options.as_ref().and_then(|o| o.tag.clone()),
+        hdr_dynamic_metadata: hdr_dynamic_metadata.clone(),
+        chunk_budget,
+        reference_control,
+        quality_reference,
+        scene_cut,
+      },
+    );
 
     // Encode the frame
     let context = match guard.context.as_mut() {
@@ -938,17 +1649,29 @@ impl VideoEncoder {
             .and_then(|f| f.shallow_clone().ok())
             .or_else(|| frame_to_encode.shallow_clone().ok());
           if let Some(buffered) = frame_to_buffer {
-            guard
-              .pending_frames
-              .push((buffered, timestamp, options.clone(), rotation, flip));
+            guard.pending_frames.push((
+              buffered,
+              timestamp,
+              options.clone(),
+              rotation,
+              flip,
+              hdr_dynamic_metadata.clone(),
+            ));
           }
           let pending_frames = std::mem::take(&mut guard.pending_frames);
 
           if Self::fallback_to_software(&mut guard) {
+            let _ = Self::fire_fallback_event(event_state);
             // Re-encode all buffered frames with software encoder
             let sw_encoder_time_base = guard.context.as_ref().map(|ctx| ctx.time_base());
-            for (buffered_frame, buffered_ts, _buffered_opts, buffered_rotation, buffered_flip) in
-              pending_frames
+            for (
+              buffered_frame,
+              buffered_ts,
+              buffered_opts,
+              buffered_rotation,
+              buffered_flip,
+              buffered_metadata,
+            ) in pending_frames
             {
               let mut frame_to_reencode = buffered_frame;
               // Convert microseconds to encoder time_base units
@@ -973,18 +1696,25 @@ impl VideoEncoder {
                     None
                   };
                   let packet_is_key = packet.is_key();
+                  let tag = buffered_opts.as_ref().and_then(|o| o.tag.clone());
+                  let rate_control = rate_control_sample(&guard, &packet);
                   // Use buffered_ts (the original input timestamp) instead of packet.pts()
                   let chunk = EncodedVideoChunk::from_packet_with_format(
-                    packet,
+                    apply_encoder_info(&guard, packet),
                     Some(buffered_ts),
                     guard.use_avcc_format,
                     enc_tb,
+                    &mut guard.output_stats,
                   );
+                  let hdr_dynamic_metadata =
+                    attach_dynamic_metadata(&chunk, buffered_metadata.clone());
 
                   // Create SVC metadata if temporal layers are configured
                   let svc =
                     create_svc_metadata(guard.temporal_layer_count, guard.output_frame_count);
                   guard.output_frame_count += 1;
+                  record_output_chunk(&mut guard.output_bitrate, &chunk);
+                  let rate_control = rate_control_stats(rate_control, &chunk);
 
                   let metadata = if !guard.extradata_sent && packet_is_key {
                     guard.extradata_sent = true;
@@ -993,10 +1723,9 @@ impl VideoEncoder {
                         codec: codec_string.clone(),
                         coded_width: Some(width),
                         coded_height: Some(height),
-                        description: guard
-                          .context
-                          .as_ref()
-                          .and_then(|ctx| ctx.extradata().map(|d| Uint8Array::from(d.to_vec()))),
+                        description: guard.context.as_ref().and_then(|ctx| {
+                          ctx.extradata().map(|d| PlainUint8Array::from(d.to_vec()))
+                        }),
                         color_space: guard.input_color_space.clone(),
                         display_aspect_width: display_width,
                         display_aspect_height: display_height,
@@ -1009,12 +1738,26 @@ impl VideoEncoder {
                       }),
                       svc,
                       alpha_side_data,
+                      tag,
+                      hdr_dynamic_metadata,
+                      chunk_budget: None,
+                      reference_control: None,
+                      quality: None,
+                      scene_cut: None,
+                      rate_control,
                     }
                   } else {
                     EncodedVideoChunkMetadata {
                       decoder_config: None,
                       svc,
                       alpha_side_data,
+                      tag,
+                      hdr_dynamic_metadata,
+                      chunk_budget: None,
+                      reference_control: None,
+                      quality: None,
+                      scene_cut: None,
+                      rate_control,
                     }
                   };
                   // During fallback re-encoding, always buffer chunks to pending_chunks.
@@ -1091,9 +1834,14 @@ impl VideoEncoder {
         .and_then(|f| f.shallow_clone().ok())
         .or_else(|| frame_to_encode.shallow_clone().ok());
       if let Some(buffered) = frame_to_buffer {
-        guard
-          .pending_frames
-          .push((buffered, timestamp, options.clone(), rotation, flip));
+        guard.pending_frames.push((
+          buffered,
+          timestamp,
+          options.clone(),
+          rotation,
+          flip,
+          hdr_dynamic_metadata.clone(),
+        ));
       }
       guard.silent_encode_count += 1;
 
@@ -1128,10 +1876,17 @@ impl VideoEncoder {
             let pending_frames = std::mem::take(&mut guard.pending_frames);
 
             if Self::fallback_to_software(&mut guard) {
+              let _ = Self::fire_fallback_event(event_state);
               // Re-encode all buffered frames with software encoder
               let sw_encoder_time_base = guard.context.as_ref().map(|ctx| ctx.time_base());
-              for (buffered_frame, buffered_ts, _buffered_opts, buffered_rotation, buffered_flip) in
-                pending_frames
+              for (
+                buffered_frame,
+                buffered_ts,
+                buffered_opts,
+                buffered_rotation,
+                buffered_flip,
+                buffered_metadata,
+              ) in pending_frames
               {
                 let mut frame_to_reencode = buffered_frame;
                 // Convert microseconds to encoder time_base units
@@ -1157,19 +1912,26 @@ impl VideoEncoder {
                       None
                     };
                     let packet_is_key = packet.is_key();
+                    let tag = buffered_opts.as_ref().and_then(|o| o.tag.clone());
+                    let rate_control = rate_control_sample(&guard, &packet);
 
                     // Use buffered_ts (the original input timestamp) instead of packet.pts()
                     let chunk = EncodedVideoChunk::from_packet_with_format(
-                      packet,
+                      apply_encoder_info(&guard, packet),
                       Some(buffered_ts),
                       guard.use_avcc_format,
                       enc_tb,
+                      &mut guard.output_stats,
                     );
+                    let hdr_dynamic_metadata =
+                      attach_dynamic_metadata(&chunk, buffered_metadata.clone());
 
                     // Create SVC metadata if temporal layers are configured
                     let svc =
                       create_svc_metadata(guard.temporal_layer_count, guard.output_frame_count);
                     guard.output_frame_count += 1;
+                    record_output_chunk(&mut guard.output_bitrate, &chunk);
+                    let rate_control = rate_control_stats(rate_control, &chunk);
 
                     let metadata = if !guard.extradata_sent && packet_is_key {
                       guard.extradata_sent = true;
@@ -1178,10 +1940,9 @@ impl VideoEncoder {
                           codec: codec_string.clone(),
                           coded_width: Some(width),
                           coded_height: Some(height),
-                          description: guard
-                            .context
-                            .as_ref()
-                            .and_then(|ctx| ctx.extradata().map(|d| Uint8Array::from(d.to_vec()))),
+                          description: guard.context.as_ref().and_then(|ctx| {
+                            ctx.extradata().map(|d| PlainUint8Array::from(d.to_vec()))
+                          }),
                           color_space: guard.input_color_space.clone(),
                           display_aspect_width: display_width,
                           display_aspect_height: display_height,
@@ -1194,12 +1955,26 @@ impl VideoEncoder {
                         }),
                         svc,
                         alpha_side_data,
+                        tag,
+                        hdr_dynamic_metadata,
+                        chunk_budget: None,
+                        reference_control: None,
+                        quality: None,
+                        scene_cut: None,
+                        rate_control,
                       }
                     } else {
                       EncodedVideoChunkMetadata {
                         decoder_config: None,
                         svc,
                         alpha_side_data,
+                        tag,
+                        hdr_dynamic_metadata,
+                        chunk_budget: None,
+                        reference_control: None,
+                        quality: None,
+                        scene_cut: None,
+                        rate_control,
                       }
                     };
                     // During fallback re-encoding, always buffer chunks to pending_chunks.
@@ -1274,11 +2049,31 @@ impl VideoEncoder {
       .map(|ctx| ctx.time_base())
       .unwrap_or(AVRational::MICROSECONDS);
 
-    // Process output packets - call callback for each
+    // Process output packets, collecting chunks for delivery once the lock is released
+    let mut outputs = Vec::with_capacity(packets.len());
     for packet in packets {
-      // Pop timestamp from queue to preserve original input timestamp
+      // Match the packet to its input to preserve the original timestamp and tag
       // (FFmpeg may modify PTS internally during encoding)
-      let output_timestamp = guard.timestamp_queue.pop_front();
+      let mut input = take_pending_input(&mut guard.timestamp_queue, packet.pts());
+      let output_timestamp = input.as_ref().map(|i| i.timestamp);
+      let (packet, chunk_budget) = match input.as_mut().and_then(|i| i.chunk_budget.take()) {
+        Some(budget) => {
+          let (packet, outcome) = Self::enforce_chunk_budget(&guard, packet, budget);
+          (packet, Some(outcome))
+        }
+        None => (packet, None),
+      };
+      let (tag, dynamic_metadata, reference_control, quality_reference, scene_cut) = input
+        .map(|i| {
+          (
+            i.tag,
+            i.hdr_dynamic_metadata,
+            i.reference_control,
+            i.quality_reference,
+            i.scene_cut,
+          )
+        })
+        .unwrap_or_default();
 
       // Extract alpha side data for VP9 only (HEVC alpha is embedded in bitstream)
       let alpha_side_data = if guard.codec_id == Some(AVCodecID::Vp9) {
@@ -1287,21 +2082,41 @@ impl VideoEncoder {
         None
       };
       let packet_is_key = packet.is_key();
+      let key_parameter_sets = key_frame_parameter_sets(guard.codec_id, &packet);
+      let reference_control = settle_reference_metadata(
+        &mut guard.long_term_references,
+        reference_control,
+        packet_is_key,
+      );
+      let scene_cut = (scene_cut && packet_is_key).then_some(true);
+
+      let quality_frames = if quality_metrics_enabled(&guard) {
+        Self::decode_for_quality(&mut guard, Some(&packet))
+      } else {
+        Vec::new()
+      };
+      let packet_pts = packet.pts();
 
+      let rate_control = rate_control_sample(&guard, &packet);
       let chunk = EncodedVideoChunk::from_packet_with_format(
-        packet,
+        apply_encoder_info(&guard, packet),
         output_timestamp,
         guard.use_avcc_format,
         encoder_time_base,
+        &mut guard.output_stats,
       );
+      let hdr_dynamic_metadata = attach_dynamic_metadata(&chunk, dynamic_metadata);
 
       // Create SVC metadata if temporal layers are configured
       let svc = create_svc_metadata(guard.temporal_layer_count, guard.output_frame_count);
       guard.output_frame_count += 1;
+      record_output_chunk(&mut guard.output_bitrate, &chunk);
+      let rate_control = rate_control_stats(rate_control, &chunk);
 
       // Create metadata
       // Note: extradata must be fetched AFTER encoding, as FFmpeg only sets it after first encode
       // Only include decoder_config if we actually have extradata (for codecs that need it)
+      let new_parameter_sets = parameter_sets_changed(&mut guard, key_parameter_sets.as_deref());
       let metadata = if !guard.extradata_sent && packet_is_key {
         // Check codec type for format conversion
         let is_h264 = codec_string.starts_with("avc1")
@@ -1313,43 +2128,46 @@ impl VideoEncoder {
         let is_av1 = codec_string.starts_with("av01") || codec_string == "av1";
 
         // Get extradata and optionally convert to avcC/hvcC/av1C format for container muxing
-        let description = guard.context.as_ref().and_then(|ctx| {
-          ctx.extradata().and_then(|extradata| {
-            // AV1 always needs av1C format for container muxing (WebM/MKV/MP4)
-            // Check BEFORE use_avcc_format since that flag is only for H.264/H.265
-            if is_av1 {
-              // rav1e produces av1C directly, libaom produces raw OBUs
-              if is_av1c_extradata(extradata) {
-                Some(Uint8Array::from(extradata.to_vec()))
+        // Parameter sets that changed mid-session supersede the context's extradata
+        let extradata_source = key_parameter_sets
+          .as_deref()
+          .filter(|_| new_parameter_sets)
+          .or_else(|| guard.context.as_ref().and_then(|ctx| ctx.extradata()));
+        let description = extradata_source.and_then(|extradata| {
+          // AV1 always needs av1C format for container muxing (WebM/MKV/MP4)
+          // Check BEFORE use_avcc_format since that flag is only for H.264/H.265
+          if is_av1 {
+            // rav1e produces av1C directly, libaom produces raw OBUs
+            if is_av1c_extradata(extradata) {
+              Some(PlainUint8Array::from(extradata.to_vec()))
+            } else {
+              convert_obu_extradata_to_av1c(extradata).map(PlainUint8Array::from)
+            }
+          } else if guard.use_avcc_format {
+            // Convert Annex B extradata to avcC/hvcC box format
+            if is_h264 {
+              // Check if extradata is already in avcC format (starts with 0x01 = config version)
+              // VideoToolbox produces avcC directly, libx264 produces Annex B
+              if !extradata.is_empty() && extradata[0] == 0x01 {
+                Some(PlainUint8Array::from(extradata.to_vec()))
               } else {
-                convert_obu_extradata_to_av1c(extradata).map(Uint8Array::from)
+                convert_annexb_extradata_to_avcc(extradata).map(PlainUint8Array::from)
               }
-            } else if guard.use_avcc_format {
-              // Convert Annex B extradata to avcC/hvcC box format
-              if is_h264 {
-                // Check if extradata is already in avcC format (starts with 0x01 = config version)
-                // VideoToolbox produces avcC directly, libx264 produces Annex B
-                if !extradata.is_empty() && extradata[0] == 0x01 {
-                  Some(Uint8Array::from(extradata.to_vec()))
-                } else {
-                  convert_annexb_extradata_to_avcc(extradata).map(Uint8Array::from)
-                }
-              } else if is_h265 {
-                // Check if extradata is already in hvcC format (starts with 0x01 = config version)
-                // VideoToolbox produces hvcC directly, libx265 produces Annex B
-                if !extradata.is_empty() && extradata[0] == 0x01 {
-                  Some(Uint8Array::from(extradata.to_vec()))
-                } else {
-                  convert_annexb_extradata_to_hvcc(extradata).map(Uint8Array::from)
-                }
+            } else if is_h265 {
+              // Check if extradata is already in hvcC format (starts with 0x01 = config version)
+              // VideoToolbox produces hvcC directly, libx265 produces Annex B
+              if !extradata.is_empty() && extradata[0] == 0x01 {
+                Some(PlainUint8Array::from(extradata.to_vec()))
               } else {
-                Some(Uint8Array::from(extradata.to_vec()))
+                convert_annexb_extradata_to_hvcc(extradata).map(PlainUint8Array::from)
               }
             } else {
-              // Annex B mode - use extradata as-is
-              Some(Uint8Array::from(extradata.to_vec()))
+              Some(PlainUint8Array::from(extradata.to_vec()))
             }
-          })
+          } else {
+            // Annex B mode - use extradata as-is
+            Some(PlainUint8Array::from(extradata.to_vec()))
+          }
         });
 
         // Fallback: If extradata is not available but we're in AVCC/HVCC mode,
@@ -1358,14 +2176,18 @@ impl VideoEncoder {
         // instead of populating the codec context's extradata field.
         // For AV1: libaom may embed sequence header in first keyframe instead of extradata.
         let description = if description.is_none() && is_av1 {
-          chunk.get_data_optional(|data| convert_obu_extradata_to_av1c(data).map(Uint8Array::from))
+          chunk.get_data_optional(|data| {
+            convert_obu_extradata_to_av1c(data).map(PlainUint8Array::from)
+          })
         } else if description.is_none() && guard.use_avcc_format {
           if is_h264 {
-            chunk
-              .get_data_optional(|data| extract_avcc_from_avcc_packet(data).map(Uint8Array::from))
+            chunk.get_data_optional(|data| {
+              extract_avcc_from_avcc_packet(data).map(PlainUint8Array::from)
+            })
           } else if is_h265 {
-            chunk
-              .get_data_optional(|data| extract_hvcc_from_hvcc_packet(data).map(Uint8Array::from))
+            chunk.get_data_optional(|data| {
+              extract_hvcc_from_hvcc_packet(data).map(PlainUint8Array::from)
+            })
           } else {
             description
           }
@@ -1384,10 +2206,23 @@ impl VideoEncoder {
             decoder_config: None,
             svc,
             alpha_side_data,
+            tag,
+            hdr_dynamic_metadata,
+            chunk_budget,
+            reference_control,
+            quality: None,
+            scene_cut,
+            rate_control,
           }
         } else {
           // Either we have description, or this codec doesn't require it
           guard.extradata_sent = true;
+          if is_h264 || is_h265 {
+            guard.described_parameter_sets = description
+              .as_deref()
+              .and_then(|d| extradata_parameter_sets(d, is_h265))
+              .or(key_parameter_sets);
+          }
           EncodedVideoChunkMetadata {
             decoder_config: Some(VideoDecoderConfigOutput {
               codec: codec_string.clone(),
@@ -1406,6 +2241,13 @@ impl VideoEncoder {
             }),
             svc,
             alpha_side_data,
+            tag,
+            hdr_dynamic_metadata,
+            chunk_budget,
+            reference_control,
+            quality: None,
+            scene_cut,
+            rate_control,
           }
         }
       } else {
@@ -1413,19 +2255,58 @@ impl VideoEncoder {
           decoder_config: None,
           svc,
           alpha_side_data,
+          tag,
+          hdr_dynamic_metadata,
+          chunk_budget,
+          reference_control,
+          quality: None,
+          scene_cut,
+          rate_control,
         }
       };
 
-      // During flush, queue chunks for synchronous delivery in resolver
-      // Otherwise, use Blocking callback for immediate delivery
-      if guard.inside_flush {
-        guard.pending_chunks.push((chunk, metadata));
+      // With quality metrics, chunks wait (in order) for their reconstructed frame
+      let ready = if quality_metrics_enabled(&guard) {
+        let hold = QualityHold {
+          pts: packet_pts,
+          reference: quality_reference,
+          chunk,
+          metadata,
+        };
+        Self::hold_for_quality(&mut guard, hold, quality_frames)
       } else {
-        guard.output_callback.call(
-          (chunk, metadata).into(),
-          ThreadsafeFunctionCallMode::NonBlocking,
-        );
-      }
+        vec![(chunk, metadata)]
+      };
+
+      outputs.extend(ready);
+    }
+
+    Self::deliver_outputs(guard, outputs);
+  }
+
+  /// Hand encoded chunks to the output callback, releasing the lock first
+  ///
+  /// The callback is never invoked while the worker holds the inner lock, so
+  /// it may call back into the encoder (e.g. encode() from the output callback).
+  /// During flush, chunks are queued for synchronous delivery in the resolver.
+  fn deliver_outputs(
+    mut guard: MutexGuard<'_, VideoEncoderInner>,
+    outputs: Vec<(EncodedVideoChunk, EncodedVideoChunkMetadata)>,
+  ) {
+    if outputs.is_empty() {
+      return;
+    }
+    if guard.inside_flush {
+      guard.pending_chunks.extend(outputs);
+      return;
+    }
+    let callback = guard.output_callback.clone();
+    drop(guard);
+    for (chunk, metadata) in outputs {
+      callback.call(
+        (chunk, metadata).into(),
+        ThreadsafeFunctionCallMode::NonBlocking,
+      );
     }
   }
 
@@ -1453,7 +2334,108 @@ impl VideoEncoder {
       return Ok(());
     }
 
-    // Capture extradata BEFORE flush, as FFmpeg may clear it during drain mode
+    // Queue remaining packets for synchronous delivery in resolver
+    let Some(chunks) = Self::drain_encoder(&mut guard) else {
+      return Ok(());
+    };
+    guard.pending_chunks.extend(chunks);
+
+    // Clear any remaining timestamps in queue after flush
+    guard.timestamp_queue.clear();
+
+    // Reset encoder state so it can accept more frames
+    // Some encoders (like libvpx) don't properly support reuse after flush_encoder().
+    // The encoder enters "EOF" state and avcodec_flush_buffers() doesn't always reset it.
+    // Per W3C spec, flush() should leave encoder in configured state ready for new encodes.
+    // We recreate the encoder context to ensure clean state.
+    if let Some(ref config) = guard.config.clone() {
+      // Get codec info from current config
+      let codec_string = config.codec.clone().unwrap_or_default();
+      if let Ok(codec_id) = parse_codec_string(&codec_string) {
+        // Determine hardware type based on stored preference
+        let hw_type = match guard.hw_preference {
+          HardwareAcceleration::PreferHardware => Some(get_platform_hw_type()),
+          HardwareAcceleration::NoPreference => {
+            if is_hw_encoding_disabled() || !guard.is_hardware {
+              None
+            } else {
+              Some(get_platform_hw_type())
+            }
+          }
+          HardwareAcceleration::PreferSoftware => None,
+        };
+
+        // Recreate encoder context
+        if let Ok(result) = CodecContext::new_encoder_with_hw_info(codec_id, hw_type) {
+          let mut new_context = result.context;
+
+          // Configure encoder with same settings
+          let bitrate_mode = match config.bitrate_mode {
+            Some(VideoEncoderBitrateMode::Constant) => CodecBitrateMode::Constant,
+            Some(VideoEncoderBitrateMode::Variable) => CodecBitrateMode::Variable,
+            Some(VideoEncoderBitrateMode::Quantizer) => CodecBitrateMode::Quantizer,
+            None => CodecBitrateMode::Constant,
+          };
+
+          let realtime = matches!(config.latency_mode, Some(LatencyMode::Realtime));
+          let (gop_size, max_b_frames) = get_default_gop_settings(realtime);
+
+          // Use the stored pixel format (correctly handles 10-bit HEVC alpha)
+          let pixel_format = guard.pixel_format;
+
+          let (framerate_num, framerate_den) = encoder_framerate(config.framerate);
+          let encoder_config = EncoderConfig {
+            width: config.width.unwrap_or(0),
+            height: config.height.unwrap_or(0),
+            pixel_format,
+            bitrate: config.bitrate.unwrap_or(5_000_000.0) as u64,
+            framerate_num,
+            framerate_den,
+            gop_size,
+            max_b_frames,
+            thread_count: 0,
+            profile: encoder_profile(codec_id, pixel_format),
+            level: None,
+            bitrate_mode,
+            rc_max_rate: None,
+            rc_buffer_size: None,
+            crf: None,
+            pass: encoder_pass(config),
+            sample_aspect_ratio: display_sample_aspect_ratio(config),
+          };
+
+          if new_context.configure_encoder(&encoder_config).is_ok() {
+            // Apply encoder-specific options based on latency mode
+            if result.is_hardware {
+              // Hardware encoders: VideoToolbox, NVENC, VAAPI, QSV
+              new_context.apply_hw_encoder_options(&result.encoder_name, realtime);
+            } else {
+              // Software encoders: libx264, libx265, libvpx, libaom
+              new_context.apply_sw_encoder_options(&result.encoder_name, realtime);
+            }
+            new_context.set_options(option_pairs(&guard.ffmpeg_options));
+            if new_context.open().is_ok() {
+              // Drop old context and replace with new one
+              guard.context = Some(new_context);
+              guard.extradata_sent = false;
+              guard.described_parameter_sets = None;
+              guard.frame_count = 0;
+            }
+          }
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Drain the current encoder context and convert its remaining packets to chunks
+  ///
+  /// Returns None if draining failed (the error has already been reported).
+  fn drain_encoder(
+    guard: &mut VideoEncoderInner,
+  ) -> Option<Vec<(EncodedVideoChunk, EncodedVideoChunkMetadata)>> {
+    // Capture extradata BEFORE flush, as FFmpeg may clear it during drain mode
     // This is critical for when all output comes during flush (e.g., B-frame encoding)
     let cached_extradata = if !guard.extradata_sent {
       guard
@@ -1467,8 +2449,8 @@ impl VideoEncoder {
     let context = match guard.context.as_mut() {
       Some(ctx) => ctx,
       None => {
-        Self::report_error(&mut guard, "No encoder context");
-        return Ok(());
+        Self::report_error(guard, "No encoder context");
+        return None;
       }
     };
 
@@ -1476,11 +2458,16 @@ impl VideoEncoder {
     let packets = match context.flush_encoder() {
       Ok(pkts) => pkts,
       Err(e) => {
-        Self::report_error(&mut guard, &format!("Flush failed: {}", e));
-        return Ok(());
+        Self::report_error(guard, &format!("Flush failed: {}", e));
+        return None;
       }
     };
 
+    // First-pass encoders publish their statistics once drained
+    if let Some(stats) = context.stats_out() {
+      guard.pass_stats = Some(stats);
+    }
+
     // Try to capture extradata again after flush - VideoToolbox may populate it now
     let cached_extradata = if cached_extradata.is_none() && !guard.extradata_sent {
       guard
@@ -1491,7 +2478,6 @@ impl VideoEncoder {
       cached_extradata
     };
 
-    // Queue remaining packets for synchronous delivery in resolver
     // Get encoder time base for timestamp conversion
     let encoder_time_base = guard
       .context
@@ -1499,9 +2485,29 @@ impl VideoEncoder {
       .map(|ctx| ctx.time_base())
       .unwrap_or(AVRational::MICROSECONDS);
 
+    let mut chunks = Vec::with_capacity(packets.len());
     for packet in packets {
-      // Pop timestamp from queue to preserve original input timestamp
-      let output_timestamp = guard.timestamp_queue.pop_front();
+      // Match the packet to its input to preserve the original timestamp and tag
+      let mut input = take_pending_input(&mut guard.timestamp_queue, packet.pts());
+      let output_timestamp = input.as_ref().map(|i| i.timestamp);
+      let (packet, chunk_budget) = match input.as_mut().and_then(|i| i.chunk_budget.take()) {
+        Some(budget) => {
+          let (packet, outcome) = Self::enforce_chunk_budget(guard, packet, budget);
+          (packet, Some(outcome))
+        }
+        None => (packet, None),
+      };
+      let (tag, dynamic_metadata, reference_control, quality_reference, scene_cut) = input
+        .map(|i| {
+          (
+            i.tag,
+            i.hdr_dynamic_metadata,
+            i.reference_control,
+            i.quality_reference,
+            i.scene_cut,
+          )
+        })
+        .unwrap_or_default();
       // Extract alpha side data for VP9 only (HEVC alpha is embedded in bitstream)
       let alpha_side_data = if guard.codec_id == Some(AVCodecID::Vp9) {
         extract_alpha_side_data(&packet, guard.use_alpha)
@@ -1509,38 +2515,62 @@ impl VideoEncoder {
         None
       };
       let packet_is_key = packet.is_key();
+      let key_parameter_sets = key_frame_parameter_sets(guard.codec_id, &packet);
+      let reference_control = settle_reference_metadata(
+        &mut guard.long_term_references,
+        reference_control,
+        packet_is_key,
+      );
+      let scene_cut = (scene_cut && packet_is_key).then_some(true);
 
+      let quality_frames = if quality_metrics_enabled(guard) {
+        Self::decode_for_quality(guard, Some(&packet))
+      } else {
+        Vec::new()
+      };
+      let packet_pts = packet.pts();
+
+      let rate_control = rate_control_sample(guard, &packet);
       let chunk = EncodedVideoChunk::from_packet_with_format(
-        packet,
+        apply_encoder_info(guard, packet),
         output_timestamp,
         guard.use_avcc_format,
         encoder_time_base,
+        &mut guard.output_stats,
       );
+      let hdr_dynamic_metadata = attach_dynamic_metadata(&chunk, dynamic_metadata);
 
       // Create SVC metadata if temporal layers are configured
       let svc = create_svc_metadata(guard.temporal_layer_count, guard.output_frame_count);
       guard.output_frame_count += 1;
+      record_output_chunk(&mut guard.output_bitrate, &chunk);
+      let rate_control = rate_control_stats(rate_control, &chunk);
 
       // Create metadata (include decoder_config if not sent yet and this is a key frame)
+      let new_parameter_sets = parameter_sets_changed(guard, key_parameter_sets.as_deref());
       let metadata = if !guard.extradata_sent && packet_is_key {
         // Get config values for metadata
         let (codec_string, width, height, display_width, display_height) = guard
           .config
           .as_ref()
           .map_or((String::new(), 0, 0, None, None), |c| {
+            let (display_width, display_height) = display_aspect(c, guard.input_display_size);
             (
               c.codec.clone().unwrap_or_default(),
               c.width.unwrap_or(0),
               c.height.unwrap_or(0),
-              c.display_width,
-              c.display_height,
+              display_width,
+              display_height,
             )
           });
 
         // Get extradata - first try cached (captured before flush), then try context
         // This handles the case where FFmpeg clears extradata during drain mode
-        let extradata_source = cached_extradata
+        // Parameter sets that changed mid-session supersede the context's extradata
+        let extradata_source = key_parameter_sets
           .as_deref()
+          .filter(|_| new_parameter_sets)
+          .or(cached_extradata.as_deref())
           .or_else(|| guard.context.as_ref().and_then(|ctx| ctx.extradata()));
 
         // Check codec type for format conversion
@@ -1559,9 +2589,9 @@ impl VideoEncoder {
           if is_av1 {
             // rav1e produces av1C directly, libaom produces raw OBUs
             if is_av1c_extradata(extradata) {
-              Some(Uint8Array::from(extradata.to_vec()))
+              Some(PlainUint8Array::from(extradata.to_vec()))
             } else {
-              convert_obu_extradata_to_av1c(extradata).map(Uint8Array::from)
+              convert_obu_extradata_to_av1c(extradata).map(PlainUint8Array::from)
             }
           } else if guard.use_avcc_format {
             // Convert Annex B extradata to avcC/hvcC box format
@@ -1569,24 +2599,24 @@ impl VideoEncoder {
               // Check if extradata is already in avcC format (starts with 0x01 = config version)
               // VideoToolbox produces avcC directly, libx264 produces Annex B
               if !extradata.is_empty() && extradata[0] == 0x01 {
-                Some(Uint8Array::from(extradata.to_vec()))
+                Some(PlainUint8Array::from(extradata.to_vec()))
               } else {
-                convert_annexb_extradata_to_avcc(extradata).map(Uint8Array::from)
+                convert_annexb_extradata_to_avcc(extradata).map(PlainUint8Array::from)
               }
             } else if is_h265 {
               // Check if extradata is already in hvcC format (starts with 0x01 = config version)
               // VideoToolbox produces hvcC directly, libx265 produces Annex B
               if !extradata.is_empty() && extradata[0] == 0x01 {
-                Some(Uint8Array::from(extradata.to_vec()))
+                Some(PlainUint8Array::from(extradata.to_vec()))
               } else {
-                convert_annexb_extradata_to_hvcc(extradata).map(Uint8Array::from)
+                convert_annexb_extradata_to_hvcc(extradata).map(PlainUint8Array::from)
               }
             } else {
-              Some(Uint8Array::from(extradata.to_vec()))
+              Some(PlainUint8Array::from(extradata.to_vec()))
             }
           } else {
             // Annex B mode - use extradata as-is
-            Some(Uint8Array::from(extradata.to_vec()))
+            Some(PlainUint8Array::from(extradata.to_vec()))
           }
         });
 
@@ -1596,14 +2626,18 @@ impl VideoEncoder {
         // instead of populating the codec context's extradata field.
         // For AV1: libaom may embed sequence header in first keyframe instead of extradata.
         let description = if description.is_none() && is_av1 {
-          chunk.get_data_optional(|data| convert_obu_extradata_to_av1c(data).map(Uint8Array::from))
+          chunk.get_data_optional(|data| {
+            convert_obu_extradata_to_av1c(data).map(PlainUint8Array::from)
+          })
         } else if description.is_none() && guard.use_avcc_format {
           if is_h264 {
-            chunk
-              .get_data_optional(|data| extract_avcc_from_avcc_packet(data).map(Uint8Array::from))
+            chunk.get_data_optional(|data| {
+              extract_avcc_from_avcc_packet(data).map(PlainUint8Array::from)
+            })
           } else if is_h265 {
-            chunk
-              .get_data_optional(|data| extract_hvcc_from_hvcc_packet(data).map(Uint8Array::from))
+            chunk.get_data_optional(|data| {
+              extract_hvcc_from_hvcc_packet(data).map(PlainUint8Array::from)
+            })
           } else {
             description
           }
@@ -1622,10 +2656,23 @@ impl VideoEncoder {
             decoder_config: None,
             svc,
             alpha_side_data,
+            tag,
+            hdr_dynamic_metadata,
+            chunk_budget,
+            reference_control,
+            quality: None,
+            scene_cut,
+            rate_control,
           }
         } else {
           // Either we have description, or this codec doesn't require it
           guard.extradata_sent = true;
+          if is_h264 || is_h265 {
+            guard.described_parameter_sets = description
+              .as_deref()
+              .and_then(|d| extradata_parameter_sets(d, is_h265))
+              .or(key_parameter_sets);
+          }
           EncodedVideoChunkMetadata {
             decoder_config: Some(VideoDecoderConfigOutput {
               codec: codec_string,
@@ -1640,6 +2687,13 @@ impl VideoEncoder {
             }),
             svc,
             alpha_side_data,
+            tag,
+            hdr_dynamic_metadata,
+            chunk_budget,
+            reference_control,
+            quality: None,
+            scene_cut,
+            rate_control,
           }
         }
       } else {
@@ -1647,100 +2701,159 @@ impl VideoEncoder {
           decoder_config: None,
           svc,
           alpha_side_data,
+          tag,
+          hdr_dynamic_metadata,
+          chunk_budget,
+          reference_control,
+          quality: None,
+          scene_cut,
+          rate_control,
         }
       };
 
-      // Always queue during flush for synchronous delivery
-      guard.pending_chunks.push((chunk, metadata));
+      if quality_metrics_enabled(guard) {
+        let hold = QualityHold {
+          pts: packet_pts,
+          reference: quality_reference,
+          chunk,
+          metadata,
+        };
+        chunks.extend(Self::hold_for_quality(guard, hold, quality_frames));
+      } else {
+        chunks.push((chunk, metadata));
+      }
     }
 
-    // Clear any remaining timestamps in queue after flush
-    guard.timestamp_queue.clear();
-
-    // Reset encoder state so it can accept more frames
-    // Some encoders (like libvpx) don't properly support reuse after flush_encoder().
-    // The encoder enters "EOF" state and avcodec_flush_buffers() doesn't always reset it.
-    // Per W3C spec, flush() should leave encoder in configured state ready for new encodes.
-    // We recreate the encoder context to ensure clean state.
-    if let Some(ref config) = guard.config.clone() {
-      // Get codec info from current config
-      let codec_string = config.codec.clone().unwrap_or_default();
-      if let Ok(codec_id) = parse_codec_string(&codec_string) {
-        // Determine hardware type based on stored preference
-        let hw_type = match guard.hw_preference {
-          HardwareAcceleration::PreferHardware => Some(get_platform_hw_type()),
-          HardwareAcceleration::NoPreference => {
-            if is_hw_encoding_disabled() || !guard.is_hardware {
-              None
-            } else {
-              Some(get_platform_hw_type())
-            }
-          }
-          HardwareAcceleration::PreferSoftware => None,
-        };
+    // The paired decoder gives up its reordered frames once drained
+    if guard.quality_decoder.is_some() || !guard.quality_holds.is_empty() {
+      chunks.extend(Self::release_quality_holds(guard));
+    }
 
-        // Recreate encoder context
-        if let Ok(result) = CodecContext::new_encoder_with_hw_info(codec_id, hw_type) {
-          let mut new_context = result.context;
+    Some(chunks)
+  }
 
-          // Configure encoder with same settings
-          let bitrate_mode = match config.bitrate_mode {
-            Some(VideoEncoderBitrateMode::Constant) => CodecBitrateMode::Constant,
-            Some(VideoEncoderBitrateMode::Variable) => CodecBitrateMode::Variable,
-            Some(VideoEncoderBitrateMode::Quantizer) => CodecBitrateMode::Quantizer,
-            None => CodecBitrateMode::Constant,
-          };
+  /// Decode an output packet (or drain with None) with the paired quality decoder
+  ///
+  /// The decoder is created on first use from the encoder's extradata. Decode
+  /// failures only cost the affected chunks their metrics.
+  fn decode_for_quality(inner: &mut VideoEncoderInner, packet: Option<&Packet>) -> Vec<Frame> {
+    if inner.quality_decoder.is_none() {
+      let Some(codec_id) = inner.codec_id else {
+        return Vec::new();
+      };
+      if packet.is_none() {
+        return Vec::new();
+      }
+      let decoder_config = DecoderConfig {
+        codec_id,
+        // Frame threading would delay output by one frame per thread
+        thread_count: 1,
+        extradata: inner
+          .context
+          .as_ref()
+          .and_then(|ctx| ctx.extradata().map(|e| e.to_vec())),
+        low_latency: true,
+        ..Default::default()
+      };
+      let decoder = CodecContext::new_decoder(codec_id).and_then(|mut decoder| {
+        decoder.configure_decoder(&decoder_config)?;
+        decoder.open()?;
+        Ok(decoder)
+      });
+      match decoder {
+        Ok(decoder) => inner.quality_decoder = Some(decoder),
+        Err(e) => {
+          tracing::warn!(target: "webcodecs", "Quality metrics decoder unavailable: {}", e);
+          return Vec::new();
+        }
+      }
+    }
 
-          let realtime = matches!(config.latency_mode, Some(LatencyMode::Realtime));
-          let (gop_size, max_b_frames) = get_default_gop_settings(realtime);
+    let Some(decoder) = inner.quality_decoder.as_mut() else {
+      return Vec::new();
+    };
+    let frames = match packet {
+      Some(packet) => decoder.decode(Some(packet)),
+      None => decoder.flush_decoder(),
+    };
+    frames.unwrap_or_else(|e| {
+      tracing::debug!(target: "webcodecs", "Quality metrics decode failed: {}", e);
+      Vec::new()
+    })
+  }
 
-          // Use the stored pixel format (correctly handles 10-bit HEVC alpha)
-          let pixel_format = guard.pixel_format;
+  /// Queue a chunk until its frame is reconstructed, returning the chunks now ready
+  ///
+  /// Decoders output frames in presentation order, so with B-frames a chunk's
+  /// frame can appear several packets later. Chunks are released in output
+  /// order once measured; a chunk whose frame never appears is released without
+  /// metrics after MAX_QUALITY_HOLDS newer chunks.
+  fn hold_for_quality(
+    inner: &mut VideoEncoderInner,
+    hold: QualityHold,
+    decoded: Vec<Frame>,
+  ) -> Vec<(EncodedVideoChunk, EncodedVideoChunkMetadata)> {
+    inner.quality_holds.push_back(hold);
+    measure_quality_holds(&mut inner.quality_holds, decoded);
+
+    let mut ready = Vec::new();
+    while let Some(front) = inner.quality_holds.front() {
+      if front.reference.is_some() && inner.quality_holds.len() <= MAX_QUALITY_HOLDS {
+        break;
+      }
+      if let Some(hold) = inner.quality_holds.pop_front() {
+        ready.push((hold.chunk, hold.metadata));
+      }
+    }
+    ready
+  }
 
-          let encoder_config = EncoderConfig {
-            width: config.width.unwrap_or(0),
-            height: config.height.unwrap_or(0),
-            pixel_format,
-            bitrate: config.bitrate.unwrap_or(5_000_000.0) as u64,
-            framerate_num: config.framerate.unwrap_or(30.0) as u32,
-            framerate_den: 1,
-            gop_size,
-            max_b_frames,
-            thread_count: 0,
-            profile: None,
-            level: None,
-            bitrate_mode,
-            rc_max_rate: None,
-            rc_buffer_size: None,
-            crf: None,
-          };
+  /// Drain the paired decoder and release every held chunk
+  ///
+  /// The decoder is dropped afterwards: the encoder context is recreated after
+  /// a flush and restarts with a key frame.
+  fn release_quality_holds(
+    inner: &mut VideoEncoderInner,
+  ) -> Vec<(EncodedVideoChunk, EncodedVideoChunkMetadata)> {
+    let decoded = Self::decode_for_quality(inner, None);
+    measure_quality_holds(&mut inner.quality_holds, decoded);
+    inner.quality_decoder = None;
+    inner
+      .quality_holds
+      .drain(..)
+      .map(|hold| (hold.chunk, hold.metadata))
+      .collect()
+  }
 
-          if new_context.configure_encoder(&encoder_config).is_ok() {
-            // Apply encoder-specific options based on latency mode
-            if result.is_hardware {
-              // Hardware encoders: VideoToolbox, NVENC, VAAPI, QSV
-              new_context.apply_hw_encoder_options(&result.encoder_name, realtime);
-            } else {
-              // Software encoders: libx264, libx265, libvpx, libaom
-              new_context.apply_sw_encoder_options(&result.encoder_name, realtime);
-            }
-            if new_context.open().is_ok() {
-              // Drop old context and replace with new one
-              guard.context = Some(new_context);
-              guard.extradata_sent = false;
-              guard.frame_count = 0;
-            }
-          }
-        }
+  /// Resolve configureAsync() calls up to and including `response_sender`
+  ///
+  /// Every configure queued before this command has been applied by now. If one
+  /// of them failed, report_error() already rejected and removed the sender.
+  fn process_configure_done(
+    inner: &Arc<Mutex<VideoEncoderInner>>,
+    response_sender: &Sender<Result<()>>,
+  ) {
+    let Ok(mut guard) = inner.lock() else {
+      return;
+    };
+    if let Some(pos) = guard
+      .pending_configure_senders
+      .iter()
+      .position(|sender| sender.same_channel(response_sender))
+    {
+      for sender in guard.pending_configure_senders.drain(..=pos) {
+        let _ = sender.send(Ok(()));
       }
     }
-
-    Ok(())
   }
 
   /// Process a reconfigure command on the worker thread
   /// Drains old context and creates new one with updated config
-  fn process_reconfigure(inner: &Arc<Mutex<VideoEncoderInner>>, config: VideoEncoderConfig) {
+  fn process_reconfigure(
+    inner: &Arc<Mutex<VideoEncoderInner>>,
+    event_state: &Arc<RwLock<EventListenerState>>,
+    config: VideoEncoderConfig,
+  ) {
     let mut guard = match inner.lock() {
       Ok(g) => g,
       Err(_) => return, // Lock poisoned
@@ -1751,8 +2864,24 @@ impl VideoEncoder {
       return;
     }
 
-    // Drain old context (libaom/AV1 thread safety)
-    if let Some(ctx) = guard.context.as_mut() {
+    // Drain the old context and deliver its remaining packets before switching.
+    // Encodes queued ahead of configure() belong to the old configuration, so their
+    // chunks must reach the output callback before any chunk from the new context.
+    if guard.state == CodecState::Configured && guard.frame_count > 0 {
+      let Some(chunks) = Self::drain_encoder(&mut guard) else {
+        return;
+      };
+      Self::deliver_outputs(guard, chunks);
+      guard = match inner.lock() {
+        Ok(g) => g,
+        Err(_) => return, // Lock poisoned
+      };
+      // reset() or close() while the chunks were delivered cancels the reconfigure
+      if guard.state != CodecState::Configured {
+        return;
+      }
+    } else if let Some(ctx) = guard.context.as_mut() {
+      // Nothing to deliver, but still drain (libaom/AV1 thread safety)
       ctx.flush();
       let _ = ctx.send_frame(None);
       while ctx.receive_packet().ok().flatten().is_some() {}
@@ -1763,13 +2892,19 @@ impl VideoEncoder {
       codec_pressure::gauge().release_hw_encoder();
       guard.acquired_hw_slot = false;
     }
+    guard.hw_session = None;
 
     // Clear work-related state
     guard.encode_queue_size = 0;
     guard.timestamp_queue.clear();
     guard.frame_count = 0;
     guard.extradata_sent = false;
+    guard.described_parameter_sets = None;
     guard.output_frame_count = 0;
+    guard.long_term_references = LongTermReferences::default();
+    guard.quality_decoder = None;
+    guard.quality_holds.clear();
+    guard.scene_cut_detector = SceneCutDetector::default();
     guard.pending_frames.clear();
 
     // Parse codec to get codec_id
@@ -1792,11 +2927,31 @@ impl VideoEncoder {
       }
     };
 
+    if let Some(msg) = check_display_aspect_support(&config) {
+      Self::report_error(&mut guard, &msg);
+      return;
+    }
+
+    if let Some(msg) = check_encoder_parameters(&config, codec_id) {
+      Self::report_error(&mut guard, &msg);
+      return;
+    }
+
+    if let Some(msg) = check_min_picture_size(&config, codec_id) {
+      Self::report_error(&mut guard, &msg);
+      return;
+    }
+
     // Determine hardware acceleration preference from NEW config (not cached value)
     // This is important for HEVC alpha check - we need to use the new config's preference
-    let hw_preference = config
-      .hardware_acceleration
-      .unwrap_or(HardwareAcceleration::NoPreference);
+    // Two-pass statistics are only exchanged by software encoders
+    let hw_preference = if config.pass.is_some() {
+      HardwareAcceleration::PreferSoftware
+    } else {
+      config
+        .hardware_acceleration
+        .unwrap_or(HardwareAcceleration::NoPreference)
+    };
 
     // Determine hardware type based on preference and pressure gauge
     let (hw_type, mut acquired_hw_slot) = match hw_preference {
@@ -1846,32 +3001,28 @@ impl VideoEncoder {
     // NOTE: HEVC alpha check moved after encoder creation to allow no-preference fallback
 
     // Select pixel format based on alpha and bit depth
-    let pixel_format = if use_alpha {
-      if codec_id == AVCodecID::Hevc && is_hevc_10bit(&codec_string) {
-        AVPixelFormat::Yuva420p10le // 10-bit HEVC with alpha
-      } else {
-        AVPixelFormat::Yuva420p // 8-bit VP9/HEVC with alpha
-      }
-    } else {
-      AVPixelFormat::Yuv420p
-    };
+    let pixel_format = encoder_pixel_format(codec_id, &codec_string, use_alpha);
+    let ffmpeg_options = video_encoder_options(config.ffmpeg_options.as_ref());
 
+    let (framerate_num, framerate_den) = encoder_framerate(config.framerate);
     let encoder_config = EncoderConfig {
       width: config.width.unwrap_or(0),
       height: config.height.unwrap_or(0),
       pixel_format,
       bitrate: config.bitrate.unwrap_or(5_000_000.0) as u64,
-      framerate_num: config.framerate.unwrap_or(30.0) as u32,
-      framerate_den: 1,
+      framerate_num,
+      framerate_den,
       gop_size,
       max_b_frames,
       thread_count: 0,
-      profile: None,
+      profile: encoder_profile(codec_id, pixel_format),
       level: None,
       bitrate_mode,
       rc_max_rate: None,
       rc_buffer_size: None,
       crf: None,
+      pass: encoder_pass(&config),
+      sample_aspect_ratio: display_sample_aspect_ratio(&config),
     };
 
     // NOTE: guard.use_alpha, guard.pixel_format, guard.codec_id are updated AFTER all
@@ -1916,6 +3067,7 @@ impl VideoEncoder {
               &encoder_config,
               use_avcc_format,
               realtime,
+              &ffmpeg_options,
             ) {
               Ok((ctx, name)) => (ctx, false, name),
               Err(e2) => {
@@ -1947,16 +3099,83 @@ impl VideoEncoder {
       acquired_hw_slot = false;
     }
 
+    // Tiny pictures go to the software encoder, which accepts smaller sizes
+    if is_hardware
+      && let Some(msg) = check_hardware_min_picture_size(
+        encoder_config.width,
+        encoder_config.height,
+        codec_id,
+        &encoder_name,
+      )
+    {
+      if acquired_hw_slot {
+        codec_pressure::gauge().release_hw_encoder();
+        acquired_hw_slot = false;
+      }
+      match CodecContext::new_encoder_with_hw_info(codec_id, None) {
+        Ok(result) if hw_preference == HardwareAcceleration::NoPreference => {
+          context = result.context;
+          is_hardware = false;
+          encoder_name = result.encoder_name;
+        }
+        _ => {
+          Self::report_error(&mut guard, &msg);
+          return;
+        }
+      }
+    }
+
+    // 4:2:2 and 4:4:4 output depends on the encoder and the libraries it was built with
+    if encoder_profile(codec_id, pixel_format).is_some()
+      && !context.supports_pixel_format(pixel_format)
+    {
+      if is_hardware
+        && hw_preference == HardwareAcceleration::NoPreference
+        && let Ok(result) = CodecContext::new_encoder_with_hw_info(codec_id, None)
+      {
+        if acquired_hw_slot {
+          codec_pressure::gauge().release_hw_encoder();
+          acquired_hw_slot = false;
+        }
+        context = result.context;
+        is_hardware = false;
+        encoder_name = result.encoder_name;
+      }
+      if !context.supports_pixel_format(pixel_format) {
+        if acquired_hw_slot {
+          codec_pressure::gauge().release_hw_encoder();
+        }
+        Self::report_error(
+          &mut guard,
+          &format!(
+            "NotSupportedError: {} cannot encode {} ({} output)",
+            encoder_name,
+            codec_string,
+            format_name(pixel_format)
+          ),
+        );
+        return;
+      }
+    }
+
     // Configure encoder (with fallback for HW failures)
+    let mut fell_back = false;
     if let Err(e) = context.configure_encoder(&encoder_config) {
       // Fallback to software if HW configure fails
       if hw_preference == HardwareAcceleration::NoPreference && is_hardware {
+        fell_back = true;
         // Release the hardware slot since we're falling back to software
         if acquired_hw_slot {
           codec_pressure::gauge().release_hw_encoder();
           acquired_hw_slot = false;
         }
-        match Self::create_software_encoder(codec_id, &encoder_config, use_avcc_format, realtime) {
+        match Self::create_software_encoder(
+          codec_id,
+          &encoder_config,
+          use_avcc_format,
+          realtime,
+          &ffmpeg_options,
+        ) {
           Ok((ctx, name)) => {
             context = ctx;
             is_hardware = false;
@@ -1986,17 +3205,28 @@ impl VideoEncoder {
       if use_avcc_format {
         context.set_global_header();
       }
+      context.set_options(option_pairs(&ffmpeg_options));
 
       if let Err(e) = context.open() {
         // Fallback to software if HW open fails
         if hw_preference == HardwareAcceleration::NoPreference && is_hardware {
+          // A session cap is expected under load: record it and fall back silently
+          if let Some(message) = session_limit_message(&e, &encoder_name) {
+            tracing::info!(target: "webcodecs", "{}; using software encoder", message);
+          }
+          fell_back = true;
           // Release the hardware slot since we're falling back to software
           if acquired_hw_slot {
             codec_pressure::gauge().release_hw_encoder();
             acquired_hw_slot = false;
           }
-          match Self::create_software_encoder(codec_id, &encoder_config, use_avcc_format, realtime)
-          {
+          match Self::create_software_encoder(
+            codec_id,
+            &encoder_config,
+            use_avcc_format,
+            realtime,
+            &ffmpeg_options,
+          ) {
             Ok((ctx, name)) => {
               context = ctx;
               is_hardware = false;
@@ -2015,10 +3245,9 @@ impl VideoEncoder {
           if acquired_hw_slot {
             codec_pressure::gauge().release_hw_encoder();
           }
-          Self::report_error(
-            &mut guard,
-            &format!("NotSupportedError: Failed to open encoder: {}", e),
-          );
+          let message = session_limit_message(&e, &encoder_name)
+            .unwrap_or_else(|| format!("NotSupportedError: Failed to open encoder: {}", e));
+          Self::report_error(&mut guard, &message);
           return;
         }
       }
@@ -2042,9 +3271,13 @@ impl VideoEncoder {
     // This prevents state corruption if reconfigure fails partway through
     guard.use_alpha = use_alpha;
     guard.pixel_format = pixel_format;
+    guard.ffmpeg_options = ffmpeg_options;
     guard.codec_id = Some(codec_id);
 
     // Update inner state
+    guard.hw_session = is_hardware
+      .then(|| HwEncoderSession::open(&encoder_name))
+      .flatten();
     guard.context = Some(context);
     guard.config = Some(config.clone());
     guard.is_hardware = is_hardware;
@@ -2065,6 +3298,41 @@ impl VideoEncoder {
     guard.hw_frame_ctx = None;
     guard.use_hw_frames = false;
     guard.nv12_scaler = None;
+
+    if fell_back {
+      let _ = Self::fire_fallback_event(event_state);
+    }
+  }
+
+  /// Fail configure() for a valid but unsupported config
+  ///
+  /// W3C spec: configure() only throws for invalid configs. Support is checked in
+  /// a queued task, so the encoder stays "configured" until that task closes it
+  /// with the error; a flush() issued in between rejects with the same error.
+  fn fail_configure(
+    &self,
+    env: &Env,
+    mut inner: MutexGuard<'_, VideoEncoderInner>,
+    error_msg: &str,
+  ) -> Result<()> {
+    inner.state = CodecState::Configured;
+    drop(inner);
+
+    let inner = self.inner.clone();
+    let error_msg = error_msg.to_string();
+    PromiseRaw::resolve(env, ())?.then(move |_| {
+      // reset()/close() before the task runs discards the pending configure
+      if let Ok(mut guard) = inner.lock()
+        && guard.state == CodecState::Configured
+      {
+        for sender in guard.pending_flush_senders.drain(..) {
+          let _ = sender.send(Err(Error::new(Status::GenericFailure, error_msg.clone())));
+        }
+        Self::report_error(&mut guard, &error_msg);
+      }
+      Ok(())
+    })?;
+    Ok(())
   }
 
   /// Report an error via callback and close the encoder
@@ -2072,6 +3340,11 @@ impl VideoEncoder {
     // Log the error at warn level for debugging (visible even if JS callback fails)
     tracing::warn!(target: "webcodecs", codec = "VideoEncoder", error = error_msg, "Codec error reported");
 
+    // A pending configureAsync() rejects with the same error
+    for sender in inner.pending_configure_senders.drain(..) {
+      let _ = sender.send(Err(Error::new(Status::GenericFailure, error_msg)));
+    }
+
     // Create an Error object that will be passed directly to the JS callback
     let error = Error::new(Status::GenericFailure, error_msg);
     inner
@@ -2080,6 +3353,28 @@ impl VideoEncoder {
     inner.state = CodecState::Closed;
   }
 
+  /// Report an error for a dropped frame without closing the encoder (skipUnsupportedFrames)
+  fn report_frame_error(inner: &VideoEncoderInner, error_msg: &str) {
+    tracing::warn!(target: "webcodecs", codec = "VideoEncoder", error = error_msg, "Frame dropped");
+
+    let error = Error::new(Status::GenericFailure, error_msg);
+    inner
+      .error_callback
+      .call(error, ThreadsafeFunctionCallMode::NonBlocking);
+  }
+
+  /// Close the encoder after a panic on the worker thread
+  ///
+  /// The panic may have interrupted an update of the shared state, so the
+  /// lock's poison is cleared and the codec context is dropped, not reused.
+  fn recover_from_panic(inner: &Mutex<VideoEncoderInner>, message: &str) {
+    inner.clear_poison();
+    if let Ok(mut guard) = inner.lock() {
+      guard.context = None;
+      Self::report_error(&mut guard, message);
+    }
+  }
+
   /// Fire dequeue event - uses separate RwLock to avoid blocking addEventListener
   /// Also dispatches to EventTarget listeners registered via addEventListener
   fn fire_dequeue_event(event_state: &Arc<RwLock<EventListenerState>>) -> Result<()> {
@@ -2096,9 +3391,26 @@ impl VideoEncoder {
     }
 
     // 2. Fire EventTarget listeners
-    // For "once" listeners (Strong TSF): use call_with_return_value to drop TSF after callback
-    // For regular listeners (Weak TSF): use simple call()
-    if let Some(listeners) = state.event_listeners.get_mut("dequeue") {
+    Self::fire_listeners(&mut state, "dequeue");
+    Ok(())
+  }
+
+  /// Fire "fallback" listeners after a no-preference encoder switched to software
+  fn fire_fallback_event(event_state: &Arc<RwLock<EventListenerState>>) -> Result<()> {
+    let mut state = match event_state.write() {
+      Ok(s) => s,
+      Err(_) => return Err(Error::new(Status::GenericFailure, "Lock poisoned")),
+    };
+    Self::fire_listeners(&mut state, "fallback");
+    Ok(())
+  }
+
+  /// Fire the EventTarget listeners registered for `event_type`
+  ///
+  /// For "once" listeners (Strong TSF): use call_with_return_value to drop TSF after callback
+  /// For regular listeners (Weak TSF): use simple call()
+  fn fire_listeners(state: &mut EventListenerState, event_type: &str) {
+    if let Some(listeners) = state.event_listeners.get_mut(event_type) {
       // Partition into once and regular listeners
       let (once_listeners, regular_listeners): (Vec<_>, Vec<_>) =
         std::mem::take(listeners).into_iter().partition(|e| e.once);
@@ -2131,11 +3443,9 @@ impl VideoEncoder {
       // Put back regular listeners (once listeners are already consumed/removed)
       *listeners = regular_listeners;
       if listeners.is_empty() {
-        state.event_listeners.remove("dequeue");
+        state.event_listeners.remove(event_type);
       }
     }
-
-    Ok(())
   }
 
   /// Attempt to fall back to software encoder (for no-preference mode)
@@ -2174,28 +3484,32 @@ impl VideoEncoder {
     // Use the stored pixel format (correctly handles 10-bit HEVC alpha)
     let pixel_format = inner.pixel_format;
 
+    let (framerate_num, framerate_den) = encoder_framerate(config.framerate);
     let encoder_config = EncoderConfig {
       width: config.width.unwrap_or(0),
       height: config.height.unwrap_or(0),
       pixel_format,
       bitrate: config.bitrate.unwrap_or(5_000_000.0) as u64,
-      framerate_num: config.framerate.unwrap_or(30.0) as u32,
-      framerate_den: 1,
+      framerate_num,
+      framerate_den,
       gop_size,
       max_b_frames,
       thread_count: 0,
-      profile: None,
+      profile: encoder_profile(codec_id, pixel_format),
       level: None,
       bitrate_mode,
       rc_max_rate: None,
       rc_buffer_size: None,
       crf: None,
+      pass: encoder_pass(config),
+      sample_aspect_ratio: display_sample_aspect_ratio(config),
     };
 
     let mut context = result.context;
     if context.configure_encoder(&encoder_config).is_err() {
       return false;
     }
+    context.set_options(option_pairs(&inner.ffmpeg_options));
 
     if context.open().is_err() {
       return false;
@@ -2206,6 +3520,7 @@ impl VideoEncoder {
       codec_pressure::gauge().release_hw_encoder();
       inner.acquired_hw_slot = false;
     }
+    inner.hw_session = None;
 
     // Replace the hardware context with software
     inner.context = Some(context);
@@ -2214,6 +3529,7 @@ impl VideoEncoder {
     inner.silent_encode_count = 0;
     inner.first_output_produced = false;
     inner.extradata_sent = false;
+    inner.described_parameter_sets = None;
 
     // Disable hardware frame upload - software encoder can't handle GPU frames
     inner.use_hw_frames = false;
@@ -2230,6 +3546,7 @@ impl VideoEncoder {
     encoder_config: &EncoderConfig,
     needs_global_header: bool,
     realtime: bool,
+    ffmpeg_options: &FfmpegOptions,
   ) -> Result<(CodecContext, String)> {
     let result = CodecContext::new_encoder_with_hw_info(codec_id, None).map_err(|e| {
       Error::new(
@@ -2249,6 +3566,7 @@ impl VideoEncoder {
 
     // Apply software encoder options (preset=ultrafast, tune=zerolatency for H.264/H.265)
     context.apply_sw_encoder_options(&result.encoder_name, realtime);
+    context.set_options(option_pairs(ffmpeg_options));
 
     // Set GLOBAL_HEADER for AVCC/HVCC format output
     if needs_global_header {
@@ -2265,6 +3583,99 @@ impl VideoEncoder {
     Ok((context, result.encoder_name))
   }
 
+  /// Enforce a `maxChunkBytes` budget on an output packet
+  ///
+  /// Oversized key frames are re-encoded at increasing quantizers until one fits
+  /// or the retries run out; the smallest attempt is delivered either way.
+  fn enforce_chunk_budget(
+    inner: &VideoEncoderInner,
+    packet: Packet,
+    budget: ChunkBudget,
+  ) -> (Packet, ChunkBudgetMetadata) {
+    let max_bytes = budget.max_bytes as i32;
+    let mut best = packet;
+    let mut retries = 0;
+
+    if best.size() > max_bytes
+      && let (Some(frame), Some(config), Some(codec_id)) =
+        (budget.frame, inner.config.as_ref(), inner.codec_id)
+    {
+      for quantizer in CHUNK_BUDGET_QUANTIZERS {
+        retries += 1;
+        let Some(mut candidate) = Self::encode_at_quantizer(
+          codec_id,
+          config,
+          inner.pixel_format,
+          &inner.ffmpeg_options,
+          &frame,
+          quantizer,
+        ) else {
+          break;
+        };
+        if candidate.size() < best.size() {
+          candidate.set_pts(best.pts());
+          candidate.set_dts(best.dts());
+          candidate.set_duration(best.duration());
+          best = candidate;
+        }
+        if best.size() <= max_bytes {
+          break;
+        }
+      }
+    }
+
+    let exceeded = best.size() > max_bytes;
+    (best, ChunkBudgetMetadata { retries, exceeded })
+  }
+
+  /// Encode a single frame with a one-shot software encoder pinned to `quantizer`
+  ///
+  /// The main encoder's rate control and reference state are left untouched.
+  fn encode_at_quantizer(
+    codec_id: AVCodecID,
+    config: &VideoEncoderConfig,
+    pixel_format: AVPixelFormat,
+    ffmpeg_options: &FfmpegOptions,
+    frame: &Frame,
+    quantizer: i32,
+  ) -> Option<Packet> {
+    let (framerate_num, framerate_den) = encoder_framerate(config.framerate);
+    let encoder_config = EncoderConfig {
+      width: config.width.unwrap_or(0),
+      height: config.height.unwrap_or(0),
+      pixel_format,
+      bitrate: config.bitrate.unwrap_or(5_000_000.0) as u64,
+      framerate_num,
+      framerate_den,
+      gop_size: None,
+      max_b_frames: Some(0),
+      thread_count: 0,
+      profile: encoder_profile(codec_id, pixel_format),
+      level: None,
+      bitrate_mode: CodecBitrateMode::Constant,
+      rc_max_rate: None,
+      rc_buffer_size: None,
+      crf: None,
+      pass: None,
+      sample_aspect_ratio: display_sample_aspect_ratio(config),
+    };
+
+    let result = CodecContext::new_encoder_with_hw_info(codec_id, None).ok()?;
+    let mut context = result.context;
+    context.configure_encoder(&encoder_config).ok()?;
+    context.apply_sw_encoder_options(&result.encoder_name, true);
+    context.set_options(option_pairs(ffmpeg_options));
+    context.set_qmin(quantizer);
+    context.set_qmax(quantizer);
+    context.open().ok()?;
+
+    let mut frame = frame.shallow_clone().ok()?;
+    frame.set_pts(0);
+    let mut packets = context.encode(Some(&frame)).ok()?;
+    packets.extend(context.flush_encoder().ok()?);
+    packets.into_iter().next()
+  }
+
   /// Try to create hardware frame context for zero-copy GPU encoding
   ///
   /// This creates a GPU frame pool that allows uploading CPU frames to GPU memory
@@ -2278,7 +3689,7 @@ impl VideoEncoder {
     height: u32,
   ) -> Result<(HwDeviceContext, HwFrameContext)> {
     // Create hardware device context
-    let device = HwDeviceContext::new(hw_type).map_err(|e| {
+    let device = HwDeviceContext::shared(hw_type).map_err(|e| {
       Error::new(
         Status::GenericFailure,
         format!("Failed to create hardware device context: {}", e),
@@ -2451,57 +3862,36 @@ impl VideoEncoder {
     }
   }
 
+  /// The config from the last configure(), with defaults such as `bitrate` filled in
+  /// (extension; null while unconfigured)
+  #[napi(getter)]
+  pub fn active_configuration(&self) -> Result<Option<VideoEncoderConfig>> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(inner.config.clone())
+  }
+
   /// Configure the encoder
   #[napi]
   pub fn configure(&mut self, env: Env, config: VideoEncoderConfig) -> Result<()> {
-    // W3C WebCodecs spec: Validate config synchronously, throw TypeError for invalid
-    // https://w3c.github.io/webcodecs/#dom-videoencoder-configure
-
-    // Validate codec - must be present and not empty
-    let codec = match &config.codec {
-      Some(c) if !c.is_empty() => c.clone(),
-      _ => return throw_type_error_unit(&env, "codec is required"),
-    };
-
-    // Validate width - must be present and greater than 0
-    let width = match config.width {
-      Some(w) if w > 0 => w,
-      Some(_) => return throw_type_error_unit(&env, "width must be greater than 0"),
-      None => return throw_type_error_unit(&env, "width is required"),
-    };
-
-    // Validate height - must be present and greater than 0
-    let height = match config.height {
-      Some(h) if h > 0 => h,
-      Some(_) => return throw_type_error_unit(&env, "height must be greater than 0"),
-      None => return throw_type_error_unit(&env, "height is required"),
-    };
-
-    // Validate display dimensions if specified
-    if let Some(dw) = config.display_width
-      && dw == 0
-    {
-      return throw_type_error_unit(&env, "displayWidth must be greater than 0");
-    }
-    if let Some(dh) = config.display_height
-      && dh == 0
-    {
-      return throw_type_error_unit(&env, "displayHeight must be greater than 0");
+    if !self.on_owner_thread() {
+      return throw_invalid_state_error(&env, WRONG_THREAD_MESSAGE);
     }
 
-    // Validate bitrate if specified
-    if let Some(bitrate) = config.bitrate
-      && bitrate <= 0.0
-    {
-      return throw_type_error_unit(&env, "bitrate must be greater than 0");
-    }
+    // W3C WebCodecs spec: Validate config synchronously, throw TypeError for invalid
+    // https://w3c.github.io/webcodecs/#dom-videoencoder-configure
 
-    // Validate framerate if specified
-    if let Some(framerate) = config.framerate
-      && framerate <= 0.0
-    {
-      return throw_type_error_unit(&env, "framerate must be greater than 0");
+    // Structural problems throw synchronously; support is checked below and
+    // reported asynchronously through the error callback
+    if let Some(message) = config.validity_error() {
+      return throw_type_error_unit(&env, &message);
     }
+    let config = config.with_default_bitrate();
+    let codec = config.codec.clone().unwrap_or_default();
+    let width = config.width.unwrap_or_default();
+    let height = config.height.unwrap_or_default();
 
     let mut inner = self
       .inner
@@ -2517,14 +3907,14 @@ impl VideoEncoder {
     // This ensures FIFO ordering with pending encode commands
     if inner.state == CodecState::Configured {
       // Validate codec synchronously before queueing
-      let _codec_id = match parse_codec_string(&codec) {
+      let codec_id = match parse_codec_string(&codec) {
         Ok(id) => id,
         Err(e) => {
-          Self::report_error(
-            &mut inner,
+          return self.fail_configure(
+            &env,
+            inner,
             &format!("NotSupportedError: Invalid codec: {}", e),
           );
-          return Ok(());
         }
       };
 
@@ -2532,11 +3922,27 @@ impl VideoEncoder {
       if let Some(ref mode) = config.scalability_mode
         && !is_valid_scalability_mode(mode)
       {
-        Self::report_error(
-          &mut inner,
+        return self.fail_configure(
+          &env,
+          inner,
           &format!("NotSupportedError: Unsupported scalability mode: {}", mode),
         );
-        return Ok(());
+      }
+
+      if let Some(msg) = check_two_pass_support(&config, codec_id) {
+        return self.fail_configure(&env, inner, &msg);
+      }
+
+      if let Some(msg) = check_display_aspect_support(&config) {
+        return self.fail_configure(&env, inner, &msg);
+      }
+
+      if let Some(msg) = check_encoder_parameters(&config, codec_id) {
+        return self.fail_configure(&env, inner, &msg);
+      }
+
+      if let Some(msg) = check_min_picture_size(&config, codec_id) {
+        return self.fail_configure(&env, inner, &msg);
       }
 
       // Store config for immediate property reads and new encode validation
@@ -2563,11 +3969,11 @@ impl VideoEncoder {
     let codec_id = match parse_codec_string(&codec) {
       Ok(id) => id,
       Err(e) => {
-        Self::report_error(
-          &mut inner,
+        return self.fail_configure(
+          &env,
+          inner,
           &format!("NotSupportedError: Invalid codec: {}", e),
         );
-        return Ok(());
       }
     };
 
@@ -2575,20 +3981,36 @@ impl VideoEncoder {
     if let Some(ref mode) = config.scalability_mode
       && !is_valid_scalability_mode(mode)
     {
-      Self::report_error(
-        &mut inner,
+      return self.fail_configure(
+        &env,
+        inner,
         &format!("NotSupportedError: Unsupported scalability mode: {}", mode),
       );
-      return Ok(());
+    }
+
+    if let Some(msg) = check_two_pass_support(&config, codec_id) {
+      return self.fail_configure(&env, inner, &msg);
+    }
+
+    if let Some(msg) = check_display_aspect_support(&config) {
+      return self.fail_configure(&env, inner, &msg);
+    }
+
+    if let Some(msg) = check_encoder_parameters(&config, codec_id) {
+      return self.fail_configure(&env, inner, &msg);
+    }
+
+    if let Some(msg) = check_min_picture_size(&config, codec_id) {
+      return self.fail_configure(&env, inner, &msg);
     }
 
     // Validate dimensions are within reasonable limits
     if !are_dimensions_valid(width, height) {
-      Self::report_error(
-        &mut inner,
+      return self.fail_configure(
+        &env,
+        inner,
         "NotSupportedError: Dimensions exceed maximum supported size",
       );
-      return Ok(());
     }
 
     // Calculate if GLOBAL_HEADER flag is needed for AVCC/HVCC/av1C format
@@ -2621,9 +4043,14 @@ impl VideoEncoder {
     };
 
     // Determine hardware acceleration preference (Chromium-aligned behavior)
-    let hw_preference = config
-      .hardware_acceleration
-      .unwrap_or(HardwareAcceleration::NoPreference);
+    // Two-pass statistics are only exchanged by software encoders
+    let hw_preference = if config.pass.is_some() {
+      HardwareAcceleration::PreferSoftware
+    } else {
+      config
+        .hardware_acceleration
+        .unwrap_or(HardwareAcceleration::NoPreference)
+    };
 
     // Determine hardware type based on preference:
     // - prefer-hardware: Try hardware only, error if fails
@@ -2679,8 +4106,11 @@ impl VideoEncoder {
           match CodecContext::new_encoder_with_hw_info(codec_id, None) {
             Ok(result) => result,
             Err(e2) => {
-              Self::report_error(&mut inner, &format!("Failed to create encoder: {}", e2));
-              return Ok(());
+              return self.fail_configure(
+                &env,
+                inner,
+                &format!("Failed to create encoder: {}", e2),
+              );
             }
           }
         } else {
@@ -2688,8 +4118,7 @@ impl VideoEncoder {
           if acquired_hw_slot {
             codec_pressure::gauge().release_hw_encoder();
           }
-          Self::report_error(&mut inner, &format!("Failed to create encoder: {}", e));
-          return Ok(());
+          return self.fail_configure(&env, inner, &format!("Failed to create encoder: {}", e));
         }
       }
     };
@@ -2730,49 +4159,91 @@ impl VideoEncoder {
       if acquired_hw_slot {
         codec_pressure::gauge().release_hw_encoder();
       }
-      Self::report_error(
-        &mut inner,
-        "NotSupportedError: HEVC alpha encoding requires software encoder. Set hardwareAcceleration to 'prefer-software'",
-      );
-      return Ok(());
+      return self.fail_configure(&env, inner, "NotSupportedError: HEVC alpha encoding requires software encoder. Set hardwareAcceleration to 'prefer-software'");
     }
 
     // NOTE: HEVC alpha check also exists after all fallbacks (configure/open) to catch
     // no-preference cases where hardware fails and software fallback is used
 
     // Select pixel format based on alpha and bit depth
-    let pixel_format = if use_alpha {
-      if codec_id == AVCodecID::Hevc && is_hevc_10bit(&codec) {
-        AVPixelFormat::Yuva420p10le // 10-bit HEVC with alpha
-      } else {
-        AVPixelFormat::Yuva420p // 8-bit VP9/HEVC with alpha
-      }
-    } else {
-      AVPixelFormat::Yuv420p
-    };
+    let pixel_format = encoder_pixel_format(codec_id, &codec, use_alpha);
+    let ffmpeg_options = video_encoder_options(config.ffmpeg_options.as_ref());
 
     // Configure encoder
+    let (framerate_num, framerate_den) = encoder_framerate(config.framerate);
     let encoder_config = EncoderConfig {
       width,
       height,
       pixel_format,
       bitrate: config.bitrate.unwrap_or(5_000_000.0) as u64,
-      framerate_num: config.framerate.unwrap_or(30.0) as u32,
-      framerate_den: 1,
+      framerate_num,
+      framerate_den,
       gop_size,
       max_b_frames,
       thread_count: 0, // Auto
-      profile: None,
+      profile: encoder_profile(codec_id, pixel_format),
       level: None,
       bitrate_mode,
       rc_max_rate: None,
       rc_buffer_size: None,
       crf: None,
+      pass: encoder_pass(&config),
+      sample_aspect_ratio: display_sample_aspect_ratio(&config),
     };
 
+    // Tiny pictures go to the software encoder, which accepts smaller sizes
+    if is_hardware
+      && let Some(msg) = check_hardware_min_picture_size(width, height, codec_id, &encoder_name)
+    {
+      if acquired_hw_slot {
+        codec_pressure::gauge().release_hw_encoder();
+        acquired_hw_slot = false;
+      }
+      match CodecContext::new_encoder_with_hw_info(codec_id, None) {
+        Ok(result) if hw_preference == HardwareAcceleration::NoPreference => {
+          context = result.context;
+          is_hardware = false;
+          encoder_name = result.encoder_name;
+        }
+        _ => return self.fail_configure(&env, inner, &msg),
+      }
+    }
+
+    // 4:2:2 and 4:4:4 output depends on the encoder and the libraries it was built with
+    if encoder_profile(codec_id, pixel_format).is_some()
+      && !context.supports_pixel_format(pixel_format)
+    {
+      if is_hardware
+        && hw_preference == HardwareAcceleration::NoPreference
+        && let Ok(result) = CodecContext::new_encoder_with_hw_info(codec_id, None)
+      {
+        if acquired_hw_slot {
+          codec_pressure::gauge().release_hw_encoder();
+          acquired_hw_slot = false;
+        }
+        context = result.context;
+        is_hardware = false;
+        encoder_name = result.encoder_name;
+      }
+      if !context.supports_pixel_format(pixel_format) {
+        if acquired_hw_slot {
+          codec_pressure::gauge().release_hw_encoder();
+        }
+        let message = format!(
+          "NotSupportedError: {} cannot encode {} ({} output)",
+          encoder_name,
+          codec,
+          format_name(pixel_format)
+        );
+        return self.fail_configure(&env, inner, &message);
+      }
+    }
+
+    let mut fell_back = false;
     if let Err(e) = context.configure_encoder(&encoder_config) {
       // For no-preference, try software fallback if hardware configure fails
       if hw_preference == HardwareAcceleration::NoPreference && is_hardware {
+        fell_back = true;
         // Release the hardware slot since we're falling back to software
         if acquired_hw_slot {
           codec_pressure::gauge().release_hw_encoder();
@@ -2783,6 +4254,7 @@ impl VideoEncoder {
           &encoder_config,
           needs_global_header,
           realtime,
+          &ffmpeg_options,
         ) {
           Ok((sw_ctx, sw_name)) => {
             context = sw_ctx;
@@ -2790,14 +4262,14 @@ impl VideoEncoder {
             encoder_name = sw_name;
           }
           Err(e2) => {
-            Self::report_error(
-              &mut inner,
+            return self.fail_configure(
+              &env,
+              inner,
               &format!(
                 "Failed to configure encoder: {} (software fallback also failed: {})",
                 e, e2
               ),
             );
-            return Ok(());
           }
         }
       } else {
@@ -2805,8 +4277,7 @@ impl VideoEncoder {
         if acquired_hw_slot {
           codec_pressure::gauge().release_hw_encoder();
         }
-        Self::report_error(&mut inner, &format!("Failed to configure encoder: {}", e));
-        return Ok(());
+        return self.fail_configure(&env, inner, &format!("Failed to configure encoder: {}", e));
       }
     }
 
@@ -2819,6 +4290,7 @@ impl VideoEncoder {
       // Sets preset=ultrafast, tune=zerolatency for H.264/H.265 in realtime mode
       context.apply_sw_encoder_options(&encoder_name, realtime);
     }
+    context.set_options(option_pairs(&ffmpeg_options));
 
     // Set GLOBAL_HEADER flag for AVCC/HVCC format output
     // This puts SPS/PPS into extradata instead of embedding in keyframes
@@ -2855,6 +4327,11 @@ impl VideoEncoder {
     if let Err(e) = context.open() {
       // For no-preference, try software fallback if hardware open fails
       if hw_preference == HardwareAcceleration::NoPreference && is_hardware {
+        // A session cap is expected under load: record it and fall back silently
+        if let Some(message) = session_limit_message(&e, &encoder_name) {
+          tracing::info!(target: "webcodecs", "{}; using software encoder", message);
+        }
+        fell_back = true;
         // Release the hardware slot since we're falling back to software
         if acquired_hw_slot {
           codec_pressure::gauge().release_hw_encoder();
@@ -2865,6 +4342,7 @@ impl VideoEncoder {
           &encoder_config,
           needs_global_header,
           realtime,
+          &ffmpeg_options,
         ) {
           Ok((sw_ctx, sw_name)) => {
             context = sw_ctx;
@@ -2876,14 +4354,14 @@ impl VideoEncoder {
             use_hw_frames = false;
           }
           Err(e2) => {
-            Self::report_error(
-              &mut inner,
+            return self.fail_configure(
+              &env,
+              inner,
               &format!(
                 "Failed to open encoder: {} (software fallback also failed: {})",
                 e, e2
               ),
             );
-            return Ok(());
           }
         }
       } else {
@@ -2891,8 +4369,9 @@ impl VideoEncoder {
         if acquired_hw_slot {
           codec_pressure::gauge().release_hw_encoder();
         }
-        Self::report_error(&mut inner, &format!("Failed to open encoder: {}", e));
-        return Ok(());
+        let message = session_limit_message(&e, &encoder_name)
+          .unwrap_or_else(|| format!("Failed to open encoder: {}", e));
+        return self.fail_configure(&env, inner, &message);
       }
     }
 
@@ -2903,17 +4382,14 @@ impl VideoEncoder {
       if acquired_hw_slot {
         codec_pressure::gauge().release_hw_encoder();
       }
-      Self::report_error(
-        &mut inner,
-        "NotSupportedError: HEVC alpha encoding requires software encoder. Set hardwareAcceleration to 'prefer-software'",
-      );
-      return Ok(());
+      return self.fail_configure(&env, inner, "NotSupportedError: HEVC alpha encoding requires software encoder. Set hardwareAcceleration to 'prefer-software'");
     }
 
     inner.context = Some(context);
     inner.config = Some(config);
     inner.state = CodecState::Configured;
     inner.extradata_sent = false;
+    inner.described_parameter_sets = None;
     inner.frame_count = 0;
     inner.encode_queue_size = 0;
 
@@ -2924,10 +4400,15 @@ impl VideoEncoder {
     inner.silent_encode_count = 0;
     inner.first_output_produced = false;
     inner.pending_frames.clear();
+    inner.pass_stats = None;
+    inner.output_bitrate.clear();
     // Track whether we acquired a hardware encoder slot from pressure gauge
     // Must be released on close/drop/fallback. If we fell back to software,
     // the slot was already released during fallback logic.
     inner.acquired_hw_slot = acquired_hw_slot && is_hardware;
+    inner.hw_session = is_hardware
+      .then(|| HwEncoderSession::open(&inner.encoder_name))
+      .flatten();
 
     // Hardware frame context for zero-copy GPU encoding
     inner.hw_device_ctx = hw_device_ctx;
@@ -2942,6 +4423,10 @@ impl VideoEncoder {
       .and_then(|c| c.scalability_mode.as_ref())
       .and_then(|mode| parse_temporal_layer_count(mode));
     inner.output_frame_count = 0;
+    inner.long_term_references = LongTermReferences::default();
+    inner.quality_decoder = None;
+    inner.quality_holds.clear();
+    inner.scene_cut_detector = SceneCutDetector::default();
 
     // Bitstream format conversion - determine if AVCC/HVCC format is needed
     // W3C spec: Default is AVCC/HVCC format (length-prefixed NAL units)
@@ -2973,8 +4458,13 @@ impl VideoEncoder {
     // Alpha channel support - track if we're encoding with alpha
     inner.use_alpha = use_alpha;
     inner.pixel_format = pixel_format;
+    inner.ffmpeg_options = ffmpeg_options;
     inner.codec_id = Some(codec_id);
 
+    if fell_back {
+      let _ = Self::fire_fallback_event(&self.event_state);
+    }
+
     // Create new channel and worker if needed (after reconfiguration)
     if self.command_sender.is_none() {
       let (sender, receiver) = channel::unbounded();
@@ -2982,6 +4472,8 @@ impl VideoEncoder {
       let worker_inner = self.inner.clone();
       let worker_event_state = self.event_state.clone();
       let worker_reset_flag = self.reset_flag.clone();
+      let worker_shutdown_flag = self.shutdown_flag.clone();
+      let worker_timings = self.command_timings.clone();
       drop(inner); // Release lock before spawning thread
       self.worker_handle = Some(std::thread::spawn(move || {
         Self::worker_loop(
@@ -2989,6 +4481,8 @@ impl VideoEncoder {
           worker_event_state,
           receiver,
           worker_reset_flag,
+          worker_shutdown_flag,
+          worker_timings,
         );
       }));
     }
@@ -2996,7 +4490,97 @@ impl VideoEncoder {
     Ok(())
   }
 
+  /// Configure the encoder and wait until it is ready to encode
+  ///
+  /// Behaves exactly like `configure()`, including the state transitions and
+  /// the error callback. The returned Promise resolves once the encoder context
+  /// is open (after hardware frame setup and any software fallback), or rejects
+  /// with the NotSupportedError/OperationError reported to the error callback.
+  #[napi(ts_return_type = "Promise<void>")]
+  pub fn configure_async<'env>(
+    &mut self,
+    env: &'env Env,
+    config: VideoEncoderConfig,
+  ) -> Result<PromiseRaw<'env, ()>> {
+    if !self.on_owner_thread() {
+      return reject_with_dom_exception_async(
+        env,
+        DOMExceptionName::InvalidStateError,
+        WRONG_THREAD_MESSAGE,
+      );
+    }
+    if let Some(message) = config.validity_error() {
+      return reject_with_type_error(env, &message);
+    }
+    {
+      let inner = self
+        .inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+      if inner.state == CodecState::Closed {
+        return reject_with_dom_exception_async(
+          env,
+          DOMExceptionName::InvalidStateError,
+          "Encoder is closed",
+        );
+      }
+    }
+
+    self.configure(*env, config)?;
+
+    let (response_sender, response_receiver) = channel::bounded::<Result<()>>(1);
+    {
+      let mut inner = self
+        .inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+      inner
+        .pending_configure_senders
+        .push(response_sender.clone());
+    }
+
+    // Queued after configure()'s own microtasks: a failed validation has already
+    // rejected through report_error(), and a reconfigure is ahead in the worker queue
+    if let Some(ref sender) = self.command_sender {
+      let weak_sender = Arc::downgrade(sender);
+      let reset_flag = self.reset_flag.clone();
+      PromiseRaw::resolve(env, ())?.then(move |_| {
+        if !reset_flag.load(Ordering::SeqCst)
+          && let Some(sender) = weak_sender.upgrade()
+        {
+          let _ = sender.send(EncoderCommand::ConfigureDone(response_sender));
+        }
+        Ok(())
+      })?;
+    }
+
+    env.spawn_future_with_callback(
+      async move {
+        spawn_blocking(move || {
+          response_receiver
+            .recv()
+            .map_err(|_| Error::new(Status::GenericFailure, "Worker thread terminated"))?
+        })
+        .await
+        .map_err(|join_error| {
+          Error::new(
+            Status::GenericFailure,
+            format!("Configure failed: {}", join_error),
+          )
+        })
+        .flatten()
+      },
+      // Errors keep the DOMException-style message, as in flush()
+      |_env, ()| Ok(()),
+    )
+  }
+
   /// Encode a frame
+  ///
+  /// May be called from inside the output callback: the frame is queued
+  /// behind those already submitted, and its chunks are delivered after the
+  /// chunk being handled. The encoder can only be used from the thread that
+  /// created it.
   #[napi]
   pub fn encode(
     &self,
@@ -3004,13 +4588,28 @@ impl VideoEncoder {
     frame: &VideoFrame,
     options: Option<VideoEncoderEncodeOptions>,
   ) -> Result<()> {
+    if !self.on_owner_thread() {
+      return throw_invalid_state_error(&env, WRONG_THREAD_MESSAGE);
+    }
+
     // W3C spec: throw TypeError if frame is closed
     if frame.closed()? {
       return throw_type_error_unit(&env, "Cannot encode a closed VideoFrame");
     }
 
+    if options.as_ref().and_then(|o| o.max_chunk_bytes) == Some(0) {
+      return throw_type_error_unit(&env, "maxChunkBytes must be positive");
+    }
+    if options
+      .as_ref()
+      .and_then(|o| o.reference_only.as_ref())
+      .is_some_and(|ids| ids.is_empty())
+    {
+      return throw_type_error_unit(&env, "referenceOnly must list at least one reference");
+    }
+
     // Get Arc reference to frame and metadata on main thread (no pixel copy)
-    let (frame_arc, timestamp, rotation, flip) = {
+    let command = {
       let mut inner = self
         .inner
         .lock()
@@ -3023,60 +4622,196 @@ impl VideoEncoder {
       if inner.state != CodecState::Configured {
         return throw_invalid_state_error(&env, "Cannot encode with an unconfigured codec");
       }
+      if options.as_ref().is_some_and(uses_reference_control)
+        && !inner
+          .config
+          .as_ref()
+          .is_some_and(reference_control_supported)
+      {
+        return throw_not_supported_error(
+          &env,
+          "Reference control is not supported for this encoder configuration",
+        );
+      }
 
-      // Get Arc reference to frame data (shares via Rust Arc, no pixel copy)
-      let frame_arc = match frame.frame_arc() {
-        Ok(arc) => arc,
-        Err(e) => {
-          Self::report_error(&mut inner, &format!("Failed to access frame: {}", e));
-          return Ok(());
-        }
-      };
+      match Self::encode_command(&mut inner, frame, options) {
+        Some(command) => command,
+        None => return Ok(()),
+      }
+    };
+
+    self.send_encode_commands(&env, vec![command])
+  }
+
+  /// Encode several frames in one call
+  ///
+  /// Equivalent to calling `encode()` for each frame in order, with one native
+  /// call and one microtask hop for the whole batch. `options`, if given, holds
+  /// the encode options for each frame by index and must match `frames` in length.
+  #[napi(
+    ts_args_type = "frames: VideoFrame[], options?: Array<VideoEncoderEncodeOptions | undefined | null>"
+  )]
+  pub fn encode_batch(
+    &self,
+    env: Env,
+    frames: Vec<ClassInstance<'_, VideoFrame>>,
+    options: Option<Vec<Option<VideoEncoderEncodeOptions>>>,
+  ) -> Result<()> {
+    if !self.on_owner_thread() {
+      return throw_invalid_state_error(&env, WRONG_THREAD_MESSAGE);
+    }
+    let mut options = options.unwrap_or_default();
+    if options.is_empty() {
+      options.resize(frames.len(), None);
+    } else if options.len() != frames.len() {
+      return throw_type_error_unit(&env, "options must have one entry per frame");
+    }
+
+    // Validate every frame before enqueueing any of them
+    for (frame, frame_options) in frames.iter().zip(&options) {
+      if frame.closed()? {
+        return throw_type_error_unit(&env, "Cannot encode a closed VideoFrame");
+      }
+      if frame_options.as_ref().and_then(|o| o.max_chunk_bytes) == Some(0) {
+        return throw_type_error_unit(&env, "maxChunkBytes must be positive");
+      }
+      if frame_options
+        .as_ref()
+        .and_then(|o| o.reference_only.as_ref())
+        .is_some_and(|ids| ids.is_empty())
+      {
+        return throw_type_error_unit(&env, "referenceOnly must list at least one reference");
+      }
+    }
+
+    if frames.is_empty() {
+      return Ok(());
+    }
+
+    let commands = {
+      let mut inner = self
+        .inner
+        .lock()
+        .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+
+      // W3C spec: throw InvalidStateError if not configured or closed
+      if inner.state == CodecState::Closed {
+        return throw_invalid_state_error(&env, "Cannot encode with a closed codec");
+      }
+      if inner.state != CodecState::Configured {
+        return throw_invalid_state_error(&env, "Cannot encode with an unconfigured codec");
+      }
+      if options.iter().flatten().any(uses_reference_control)
+        && !inner
+          .config
+          .as_ref()
+          .is_some_and(reference_control_supported)
+      {
+        return throw_not_supported_error(
+          &env,
+          "Reference control is not supported for this encoder configuration",
+        );
+      }
 
-      // Get timestamp
-      let timestamp = match frame.timestamp() {
-        Ok(ts) => ts,
-        Err(e) => {
-          Self::report_error(&mut inner, &format!("Failed to get frame timestamp: {}", e));
-          return Ok(());
+      let mut commands = Vec::with_capacity(frames.len());
+      for (frame, frame_options) in frames.iter().zip(options) {
+        match Self::encode_command(&mut inner, frame, frame_options) {
+          Some(command) => commands.push(command),
+          // The encoder was closed with an error, so the rest of the batch is dropped
+          None => break,
         }
-      };
+      }
+      commands
+    };
 
-      // Get rotation and flip for metadata output (W3C WebCodecs spec)
-      let rotation = frame.rotation().unwrap_or(0.0);
-      let flip = frame.flip().unwrap_or(false);
+    self.send_encode_commands(&env, commands)
+  }
 
-      // Capture colorSpace from first input frame (for decoderConfig metadata)
-      if inner.input_color_space.is_none()
-        && let Ok(color_space) = frame.color_space()
-      {
-        inner.input_color_space = Some(color_space.to_init());
+  /// Build the worker command for one frame (main thread, encoder lock held)
+  ///
+  /// Captures frame metadata and increments encodeQueueSize. Returns None after
+  /// reporting an error if the frame's data can't be accessed.
+  fn encode_command(
+    inner: &mut VideoEncoderInner,
+    frame: &VideoFrame,
+    options: Option<VideoEncoderEncodeOptions>,
+  ) -> Option<EncoderCommand> {
+    // Get Arc reference to frame data (shares via Rust Arc, no pixel copy)
+    let frame_arc = match frame.frame_arc() {
+      Ok(arc) => arc,
+      Err(e) => {
+        Self::report_error(inner, &format!("Failed to access frame: {}", e));
+        return None;
+      }
+    };
+
+    // Get timestamp
+    let timestamp = match frame.timestamp() {
+      Ok(ts) => ts,
+      Err(e) => {
+        Self::report_error(inner, &format!("Failed to get frame timestamp: {}", e));
+        return None;
       }
+    };
+
+    // Capture colorSpace from first input frame (for decoderConfig metadata)
+    if inner.input_color_space.is_none()
+      && let Ok(color_space) = frame.color_space()
+    {
+      inner.input_color_space = Some(color_space.to_init());
+    }
 
-      // Increment queue size (pending operation)
-      inner.encode_queue_size += 1;
+    // Capture display size from first input frame (for decoderConfig metadata)
+    if inner.input_display_size.is_none()
+      && let (Ok(display_width), Ok(display_height)) =
+        (frame.display_width(), frame.display_height())
+    {
+      inner.input_display_size = Some((display_width, display_height));
+    }
 
-      (frame_arc, timestamp, rotation, flip)
+    // Closed captions travel with the frame unless the options carry their own
+    let options = match frame.closed_captions() {
+      Some(captions) if options.as_ref().is_none_or(|o| o.closed_captions.is_none()) => {
+        Some(VideoEncoderEncodeOptions {
+          closed_captions: Some(captions),
+          ..options.unwrap_or_default()
+        })
+      }
+      _ => options,
     };
 
-    // Send encode command to worker thread via microtask for W3C spec FIFO ordering
-    // This ensures all commands (encode, configure, flush) are ordered correctly
-    // Use Weak reference to allow close() to immediately close channel without deadlock
+    // Increment queue size (pending operation)
+    inner.encode_queue_size += 1;
+
+    Some(EncoderCommand::Encode {
+      frame: frame_arc,
+      timestamp,
+      options,
+      // Get rotation and flip for metadata output (W3C WebCodecs spec)
+      rotation: frame.rotation().unwrap_or(0.0),
+      flip: frame.flip().unwrap_or(false),
+      hdr_dynamic_metadata: frame.hdr_dynamic_metadata(),
+      visible_crop: frame.visible_crop(),
+      enqueued_at: Instant::now(),
+    })
+  }
+
+  /// Send encode commands to the worker thread via microtask for W3C spec FIFO ordering
+  ///
+  /// This ensures all commands (encode, configure, flush) are ordered correctly.
+  /// Uses a Weak reference to allow close() to immediately close the channel without deadlock.
+  fn send_encode_commands(&self, env: &Env, commands: Vec<EncoderCommand>) -> Result<()> {
     if let Some(ref sender) = self.command_sender {
       let weak_sender = Arc::downgrade(sender);
       let reset_flag = self.reset_flag.clone();
-      PromiseRaw::resolve(&env, ())?.then(move |_| {
+      PromiseRaw::resolve(env, ())?.then(move |_| {
         // Check reset flag first, then check if encoder hasn't been closed
         if !reset_flag.load(Ordering::SeqCst)
           && let Some(sender) = weak_sender.upgrade()
         {
-          let _ = sender.send(EncoderCommand::Encode {
-            frame: frame_arc,
-            timestamp,
-            options,
-            rotation,
-            flip,
-          });
+          for command in commands {
+            let _ = sender.send(command);
+          }
         }
         Ok(())
       })?;
@@ -3098,6 +4833,14 @@ impl VideoEncoder {
   /// AFTER the callback returns, allowing flush() to return AbortError.
   #[napi(ts_return_type = "Promise<void>")]
   pub fn flush<'env>(&self, env: &'env Env) -> Result<PromiseRaw<'env, ()>> {
+    if !self.on_owner_thread() {
+      return reject_with_dom_exception_async(
+        env,
+        DOMExceptionName::InvalidStateError,
+        WRONG_THREAD_MESSAGE,
+      );
+    }
+
     // Create abort flag for this flush operation
     let flush_abort_flag = Arc::new(AtomicBool::new(false));
 
@@ -3189,32 +4932,33 @@ impl VideoEncoder {
       },
       move |env, (result, inner, abort_flag)| {
         // Drain pending chunks and call output callback SYNCHRONOUSLY
-        // This runs on the main thread with Env access
-        let chunks = {
-          let mut guard = inner
-            .lock()
-            .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-          std::mem::take(&mut guard.pending_chunks)
-        };
-
-        // Call output callback for each chunk synchronously
-        // If callback calls reset(), abort_flag will be set before next iteration
+        // This runs on the main thread with Env access. The inner lock is never
+        // held while the callback runs, so the callback may call encode() itself;
+        // chunks of those encodes that land in pending_chunks meanwhile are
+        // delivered by the next pass, after the current ones.
         let callback = output_callback_ref.borrow_back(env)?;
-        for (chunk, metadata) in chunks {
-          // Check abort flag before each callback - exit early if reset() was called
-          if abort_flag.load(Ordering::SeqCst) {
-            break;
-          }
-          callback.call((chunk, metadata).into())?;
-        }
+        loop {
+          let chunks = {
+            let mut guard = inner
+              .lock()
+              .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+            if guard.pending_chunks.is_empty() || abort_flag.load(Ordering::SeqCst) {
+              // Clean up flags; later chunks go straight to the output callback
+              guard.flush_abort_flag = None;
+              guard.inside_flush = false;
+              break;
+            }
+            std::mem::take(&mut guard.pending_chunks)
+          };
 
-        // Clean up flags
-        {
-          let mut guard = inner
-            .lock()
-            .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-          guard.flush_abort_flag = None;
-          guard.inside_flush = false;
+          // If callback calls reset(), abort_flag will be set before next iteration
+          for (chunk, metadata) in chunks {
+            // Check abort flag before each callback - exit early if reset() was called
+            if abort_flag.load(Ordering::SeqCst) {
+              break;
+            }
+            callback.call((chunk, metadata).into())?;
+          }
         }
 
         // Check abort flag after draining all chunks
@@ -3231,9 +4975,67 @@ impl VideoEncoder {
     )
   }
 
+  /// How H.264/HEVC output has been converted to AVCC/HVCC chunks since the
+  /// encoder was created (extension)
+  ///
+  /// Start codes are normally rewritten to length prefixes inside the
+  /// encoder's packet buffer; chunks whose buffer was still shared, or whose
+  /// NAL units could not be moved in place, are copied instead. Annex B
+  /// output is not counted.
+  #[napi]
+  pub fn output_stats(&self) -> Result<EncodeOutputStats> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(inner.output_stats.clone())
+  }
+
+  /// Get first-pass rate-control statistics (two-pass encoding)
+  ///
+  /// Available after `flush()` when configured with `pass: 1`. The data is
+  /// opaque; pass it back as `statsData` together with `pass: 2`.
+  /// Returns null if no statistics have been collected.
+  #[napi]
+  pub fn stats(&self) -> Result<Option<Buffer>> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(inner.pass_stats.clone().map(Buffer::from))
+  }
+
+  /// Achieved bitrate of the chunks output since configure()
+  ///
+  /// The average covers the media duration of the output; the peak is the
+  /// largest bit count over any 1-second window of chunk timestamps.
+  #[napi]
+  pub fn bitrate_summary(&self) -> Result<BitrateSummary> {
+    let inner = self
+      .inner
+      .lock()
+      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
+    Ok(inner.output_bitrate.summary())
+  }
+
+  /// Rolling queue latency statistics for the encode worker
+  ///
+  /// Covers the most recent 256 encode commands: time from `encode()` until
+  /// the worker started on the frame, and time spent encoding it. With
+  /// `WEBCODECS_LOG=debug` each command is also logged in an `encode` span
+  /// keyed by frame timestamp.
+  #[napi]
+  pub fn queue_stats(&self) -> CodecQueueStats {
+    self.command_timings.snapshot()
+  }
+
   /// Reset the encoder
   #[napi]
   pub fn reset(&mut self, env: Env) -> Result<()> {
+    if !self.on_owner_thread() {
+      return throw_invalid_state_error(&env, WRONG_THREAD_MESSAGE);
+    }
+
     // Check state first before touching the worker
     {
       let inner = self
@@ -3263,7 +5065,12 @@ impl VideoEncoder {
         .inner
         .lock()
         .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-      for sender in inner.pending_flush_senders.drain(..) {
+      let inner = &mut *inner;
+      for sender in inner
+        .pending_flush_senders
+        .drain(..)
+        .chain(inner.pending_configure_senders.drain(..))
+      {
         let _ = sender.send(Err(Error::new(
           Status::GenericFailure,
           "AbortError: The operation was aborted",
@@ -3297,6 +5104,7 @@ impl VideoEncoder {
     inner.state = CodecState::Unconfigured;
     inner.frame_count = 0;
     inner.extradata_sent = false;
+    inner.described_parameter_sets = None;
     inner.encode_queue_size = 0;
 
     // Release the hardware encoder slot if we acquired one
@@ -3304,6 +5112,7 @@ impl VideoEncoder {
       codec_pressure::gauge().release_hw_encoder();
       inner.acquired_hw_slot = false;
     }
+    inner.hw_session = None;
 
     // Reset hardware tracking state
     inner.is_hardware = false;
@@ -3313,10 +5122,16 @@ impl VideoEncoder {
     inner.first_output_produced = false;
     inner.pending_frames.clear();
     inner.timestamp_queue.clear();
+    inner.pass_stats = None;
+    inner.output_bitrate.clear();
 
     // Reset temporal SVC tracking
     inner.temporal_layer_count = None;
     inner.output_frame_count = 0;
+    inner.long_term_references = LongTermReferences::default();
+    inner.quality_decoder = None;
+    inner.quality_holds.clear();
+    inner.scene_cut_detector = SceneCutDetector::default();
 
     // Reset bitstream format conversion
     inner.use_avcc_format = false;
@@ -3327,6 +5142,7 @@ impl VideoEncoder {
 
     // Reset the abort flag for new worker
     self.reset_flag.store(false, Ordering::SeqCst);
+    self.command_timings.clear();
 
     // Create new channel and worker for future encode operations
     let (sender, receiver) = channel::unbounded();
@@ -3334,6 +5150,8 @@ impl VideoEncoder {
     let worker_inner = self.inner.clone();
     let worker_event_state = self.event_state.clone();
     let worker_reset_flag = self.reset_flag.clone();
+    let worker_shutdown_flag = self.shutdown_flag.clone();
+    let worker_timings = self.command_timings.clone();
     drop(inner); // Release lock before spawning thread
     self.worker_handle = Some(std::thread::spawn(move || {
       Self::worker_loop(
@@ -3341,6 +5159,8 @@ impl VideoEncoder {
         worker_event_state,
         receiver,
         worker_reset_flag,
+        worker_shutdown_flag,
+        worker_timings,
       );
     }));
 
@@ -3350,6 +5170,10 @@ impl VideoEncoder {
   /// Close the encoder
   #[napi]
   pub fn close(&mut self, env: Env) -> Result<()> {
+    if !self.on_owner_thread() {
+      return throw_invalid_state_error(&env, WRONG_THREAD_MESSAGE);
+    }
+
     // Check state first - W3C spec: throw InvalidStateError if already closed
     {
       let inner = self
@@ -3395,13 +5219,28 @@ impl VideoEncoder {
       codec_pressure::gauge().release_hw_encoder();
       inner.acquired_hw_slot = false;
     }
+    inner.hw_session = None;
 
+    // Frame pools and hardware contexts hold their buffers until dropped
     inner.context = None;
     inner.scaler = None;
+    inner.nv12_scaler = None;
+    inner.hw_frame_ctx = None;
+    inner.hw_device_ctx = None;
+    inner.use_hw_frames = false;
+    inner.pending_frames = Vec::new();
+    inner.timestamp_queue = VecDeque::new();
     inner.config = None;
     inner.state = CodecState::Closed;
     inner.encode_queue_size = 0;
 
+    for sender in inner.pending_configure_senders.drain(..) {
+      let _ = sender.send(Err(Error::new(
+        Status::GenericFailure,
+        "AbortError: The operation was aborted",
+      )));
+    }
+
     Ok(())
   }
 
@@ -3411,6 +5250,10 @@ impl VideoEncoder {
 
   /// Add an event listener for the specified event type
   /// Uses separate RwLock to avoid blocking on encode operations
+  ///
+  /// Besides "dequeue", a "fallback" event fires when a `no-preference` encoder
+  /// switches from a failing hardware encoder (e.g. one refused by the driver's
+  /// session limit) to software.
   #[napi]
   pub fn add_event_listener(
     &self,
@@ -3552,79 +5395,39 @@ impl VideoEncoder {
     // - Return { supported: false } for invalid values
     // https://w3c.github.io/webcodecs/#dom-videoencoder-isconfigsupported
 
-    // Validate codec - must be present and not empty
-    let codec = match &config.codec {
-      Some(c) if !c.is_empty() => c.clone(),
-      Some(_) => return reject_with_type_error(env, "codec is required"),
-      None => return reject_with_type_error(env, "codec is required"),
-    };
-
-    // Validate width - must be present (value checked in async block)
-    if config.width.is_none() {
-      return reject_with_type_error(env, "width is required");
-    }
-
-    // Validate height - must be present and non-zero
-    if config.height.is_none() {
-      return reject_with_type_error(env, "height is required");
-    }
-
-    // W3C spec: TypeError for structurally invalid configs (zero values)
-    // These must be checked BEFORE async block to throw synchronously
-    if let Some(w) = config.width
-      && w == 0
-    {
-      return reject_with_type_error(env, "width must be non-zero");
-    }
-
-    if let Some(h) = config.height
-      && h == 0
-    {
-      return reject_with_type_error(env, "height must be non-zero");
-    }
-
-    if let Some(dw) = config.display_width
-      && dw == 0
-    {
-      return reject_with_type_error(env, "displayWidth must be non-zero");
-    }
-
-    if let Some(dh) = config.display_height
-      && dh == 0
-    {
-      return reject_with_type_error(env, "displayHeight must be non-zero");
-    }
-
-    if let Some(bitrate) = config.bitrate
-      && bitrate <= 0.0
-    {
-      return reject_with_type_error(env, "bitrate must be positive");
+    // W3C spec: structurally invalid configs reject with TypeError
+    if let Some(message) = config.validity_error() {
+      return reject_with_type_error(env, &message);
     }
+    let codec = config.codec.clone().unwrap_or_default();
 
-    env.spawn_future(async move {
-      // Validate framerate if specified (return { supported: false } not TypeError)
-      if let Some(framerate) = config.framerate
-        && framerate <= 0.0
-      {
-        return Ok(VideoEncoderSupport {
-          supported: false,
-          config,
-        });
-      }
+    // W3C spec: the returned config only echoes recognized members, with defaults filled
+    let config = config.normalize();
 
-      // Validate dimensions range
-      let width = config.width.unwrap_or(0);
-      let height = config.height.unwrap_or(0);
-      if !are_dimensions_valid(width, height) {
-        return Ok(VideoEncoderSupport {
+    // Configs failing checks on the config alone settle without touching FFmpeg
+    let Some(codec_id) = support_codec_id(&config, &codec) else {
+      return resolve_with(
+        env,
+        VideoEncoderSupport {
           supported: false,
           config,
-        });
-      }
+        },
+      );
+    };
 
-      // Validate scalability mode if specified
-      if let Some(ref mode) = config.scalability_mode
-        && !is_valid_scalability_mode(mode)
+    env.spawn_future(async move {
+      // prefer-hardware cannot fall back to software for pictures below the
+      // hardware encoder's minimum
+      if config.hardware_acceleration == Some(HardwareAcceleration::PreferHardware)
+        && let Some(probe) = probe_video_encoder(codec_id, Some(get_platform_hw_type()), None)
+        && probe.is_hardware
+        && check_hardware_min_picture_size(
+          config.width.unwrap_or(0),
+          config.height.unwrap_or(0),
+          codec_id,
+          &probe.encoder_name,
+        )
+        .is_some()
       {
         return Ok(VideoEncoderSupport {
           supported: false,
@@ -3632,26 +5435,78 @@ impl VideoEncoder {
         });
       }
 
-      // Parse codec string
-      let codec_id = match parse_codec_string(&codec) {
-        Ok(id) => id,
-        Err(_) => {
-          return Ok(VideoEncoderSupport {
-            supported: false,
-            config,
-          });
-        }
-      };
+      // 4:2:2 and 4:4:4 profiles need the encoder configure() would pick to
+      // accept that format (no-preference falls back to software for it)
+      let pixel_format = encoder_pixel_format(codec_id, &codec, false);
+      if encoder_profile(codec_id, pixel_format).is_some() {
+        let hw_type = (config.hardware_acceleration == Some(HardwareAcceleration::PreferHardware))
+          .then(get_platform_hw_type);
+        let supported = probe_video_encoder(codec_id, hw_type, Some(pixel_format)).is_some();
+        return Ok(VideoEncoderSupport { supported, config });
+      }
 
       // Try to create encoder
-      let result = CodecContext::new_encoder(codec_id);
+      let supported = probe_video_encoder(codec_id, None, None).is_some();
 
-      Ok(VideoEncoderSupport {
-        supported: result.is_ok(),
-        config,
-      })
+      Ok(VideoEncoderSupport { supported, config })
     })
   }
+
+  /// Check if oversized chunks can be re-encoded to meet `maxChunkBytes` (extension)
+  ///
+  /// Supported for software VP8, VP9 and AV1 without alpha, and only for frames
+  /// encoded with `keyFrame: true` (all-intra streams). Otherwise oversized chunks
+  /// are delivered as-is with `chunkBudget.exceeded` set in their metadata.
+  #[napi]
+  pub fn is_max_chunk_bytes_supported(config: VideoEncoderConfig) -> VideoEncoderSupport {
+    let config = config.normalize();
+    let supported = config
+      .codec
+      .as_deref()
+      .and_then(|codec| parse_codec_string(codec).ok())
+      .is_some_and(|codec_id| {
+        supports_chunk_budget(
+          codec_id,
+          resolves_to_hardware(&config, codec_id),
+          config.alpha == Some(AlphaOption::Keep),
+        )
+      });
+    VideoEncoderSupport { supported, config }
+  }
+
+  /// Check if encode() accepts `markAsLongTermReference` and `referenceOnly` (extension)
+  ///
+  /// Supported for software VP8 and VP9 (libvpx) without temporal scalability.
+  /// encode() throws NotSupportedError for these options otherwise.
+  #[napi]
+  pub fn is_reference_control_supported(config: VideoEncoderConfig) -> VideoEncoderSupport {
+    let config = config.normalize();
+    let supported = reference_control_supported(&config);
+    VideoEncoderSupport { supported, config }
+  }
+}
+
+/// Check if encode options request long-term reference control
+fn uses_reference_control(options: &VideoEncoderEncodeOptions) -> bool {
+  options.mark_as_long_term_reference.is_some() || options.reference_only.is_some()
+}
+
+/// Check if a config selects an encoder with per-frame reference control
+///
+/// Only software libvpx honors the buffer flags, and the temporal layer ids
+/// reported for scalabilityMode assume the encoder's own reference structure.
+fn reference_control_supported(config: &VideoEncoderConfig) -> bool {
+  config
+    .codec
+    .as_deref()
+    .and_then(|codec| parse_codec_string(codec).ok())
+    .is_some_and(|codec_id| matches!(codec_id, AVCodecID::Vp8 | AVCodecID::Vp9))
+    && config.hardware_acceleration == Some(HardwareAcceleration::PreferSoftware)
+    && config
+      .scalability_mode
+      .as_deref()
+      .and_then(parse_temporal_layer_count)
+      .is_none()
 }
 
 /// Valid H.264/AVC profiles (decimal values)
@@ -3956,6 +5811,272 @@ fn compute_temporal_layer_id(frame_index: u64, temporal_layers: u32) -> u32 {
   (temporal_layers - 1) - trailing_zeros
 }
 
+/// Upper bound on in-flight inputs tracked for output correlation
+///
+/// Encoders that drop frames never produce output for them, so the oldest
+/// entries are discarded once this many are pending.
+const MAX_PENDING_INPUTS: usize = 1024;
+
+/// An encoded input awaiting its output packet
+struct PendingInput {
+  /// Frame PTS in encoder time base (matches the output packet PTS)
+  pts: i64,
+  /// Original input timestamp in microseconds
+  timestamp: i64,
+  /// User correlation tag from encode() options
+  tag: Option<EncodeTag>,
+  /// HDR dynamic metadata of the input frame
+  hdr_dynamic_metadata: Vec<HdrDynamicMetadata>,
+  /// Byte budget from encode() options
+  chunk_budget: Option<ChunkBudget>,
+  /// Planned reference usage (None when reference control is unused)
+  reference_control: Option<ReferenceControlMetadata>,
+  /// Pre-encode frame (only kept with computeQualityMetrics)
+  quality_reference: Option<Frame>,
+  /// Whether sceneCutDetection forced this frame to a key frame
+  scene_cut: bool,
+}
+
+/// Output chunk waiting for the paired decoder to reconstruct its frame
+struct QualityHold {
+  /// Packet PTS in encoder time base (matches the decoded frame PTS)
+  pts: i64,
+  /// Pre-encode frame, taken once the chunk has been measured
+  reference: Option<Frame>,
+  chunk: EncodedVideoChunk,
+  metadata: EncodedVideoChunkMetadata,
+}
+
+/// Most chunks held for quality metrics before the oldest is released unmeasured
+const MAX_QUALITY_HOLDS: usize = 16;
+
+/// Whether chunks are measured against their source frames
+///
+/// Hardware encoders are skipped: their frames may live on the GPU and the
+/// extra software decode would defeat the point of offloading.
+fn quality_metrics_enabled(inner: &VideoEncoderInner) -> bool {
+  !inner.is_hardware
+    && inner
+      .config
+      .as_ref()
+      .is_some_and(|c| c.compute_quality_metrics == Some(true))
+}
+
+/// Whether frames are checked for scene cuts before encoding
+fn scene_cut_detection_enabled(inner: &VideoEncoderInner) -> bool {
+  inner
+    .config
+    .as_ref()
+    .is_some_and(|c| c.scene_cut_detection == Some(true))
+}
+
+/// Rate-control side data of an output packet (only with reportRateControlStats)
+fn rate_control_sample(inner: &VideoEncoderInner, packet: &Packet) -> Option<PacketRateControl> {
+  inner
+    .config
+    .as_ref()
+    .is_some_and(|c| c.report_rate_control_stats == Some(true))
+    .then(|| PacketRateControl::from_packet(packet))
+}
+
+/// Complete a packet's rate-control stats with its chunk
+fn rate_control_stats(
+  sample: Option<PacketRateControl>,
+  chunk: &EncodedVideoChunk,
+) -> Option<RateControlStats> {
+  Some(sample?.stats(chunk.byte_length().ok()?))
+}
+
+/// Attach metrics to the held chunks whose frames were just decoded
+fn measure_quality_holds(holds: &mut VecDeque<QualityHold>, decoded: Vec<Frame>) {
+  for frame in decoded {
+    let Some(hold) = holds
+      .iter_mut()
+      .find(|h| h.pts == frame.pts() && h.reference.is_some())
+    else {
+      continue;
+    };
+    if let Some(reference) = hold.reference.take() {
+      hold.metadata.quality = quality_metrics::measure(&reference, &frame);
+    }
+  }
+}
+
+/// Byte budget requested with the `maxChunkBytes` encode option
+struct ChunkBudget {
+  /// Maximum chunk size in bytes
+  max_bytes: u32,
+  /// Input frame for re-encoding (None when the frame cannot be re-encoded on its own)
+  frame: Option<Frame>,
+}
+
+/// Quantizers (0-63) tried in turn when re-encoding an oversized key frame
+const CHUNK_BUDGET_QUANTIZERS: [i32; 4] = [40, 48, 56, 63];
+
+/// Check if oversized chunks can be re-encoded for a byte budget
+///
+/// A key frame is re-encoded by a one-shot software encoder at a fixed quantizer.
+/// The replacement only decodes correctly when it is self-contained, which holds for
+/// VP8/VP9/AV1 key frames (no out-of-band parameter sets) but not for H.264/HEVC,
+/// hardware encoders or VP9 alpha side data.
+fn supports_chunk_budget(codec_id: AVCodecID, is_hardware: bool, use_alpha: bool) -> bool {
+  matches!(codec_id, AVCodecID::Vp8 | AVCodecID::Vp9 | AVCodecID::Av1) && !is_hardware && !use_alpha
+}
+
+/// Whether configure() would pick a hardware encoder for `config`
+///
+/// Mirrors the preference resolution in configure: two-pass and prefer-software
+/// use software, prefer-hardware uses hardware, and no-preference uses the
+/// platform's hardware encoder only if one exists and hardware encoding is not
+/// disabled. Encoder slot pressure is transient and not taken into account.
+fn resolves_to_hardware(config: &VideoEncoderConfig, codec_id: AVCodecID) -> bool {
+  if config.pass.is_some() {
+    return false;
+  }
+  match config
+    .hardware_acceleration
+    .unwrap_or(HardwareAcceleration::NoPreference)
+  {
+    HardwareAcceleration::PreferHardware => true,
+    HardwareAcceleration::PreferSoftware => false,
+    HardwareAcceleration::NoPreference => {
+      !is_hw_encoding_disabled()
+        && probe_video_encoder(codec_id, Some(get_platform_hw_type()), None)
+          .is_some_and(|probe| probe.is_hardware)
+    }
+  }
+}
+
+/// Track an input for output correlation, discarding the oldest entry when full
+fn push_pending_input(queue: &mut VecDeque<PendingInput>, input: PendingInput) {
+  if queue.len() >= MAX_PENDING_INPUTS {
+    queue.pop_front();
+  }
+  queue.push_back(input);
+}
+
+/// Take the input an output packet belongs to
+///
+/// Matches by PTS so B-frame reordering keeps timestamps and tags attached to
+/// the right frame; falls back to FIFO order for encoders that rewrite PTS.
+fn take_pending_input(queue: &mut VecDeque<PendingInput>, packet_pts: i64) -> Option<PendingInput> {
+  match queue.iter().position(|input| input.pts == packet_pts) {
+    Some(index) => queue.remove(index),
+    None => queue.pop_front(),
+  }
+}
+
+/// Final reference metadata for an output chunk
+///
+/// A key frame chosen by the encoder (rather than requested through encode()
+/// options) refreshes every reference buffer, replacing all long-term references.
+fn settle_reference_metadata(
+  references: &mut LongTermReferences,
+  planned: Option<ReferenceControlMetadata>,
+  packet_is_key: bool,
+) -> Option<ReferenceControlMetadata> {
+  let mut metadata = planned?;
+  let planned_key = metadata.references.is_empty() && !metadata.references_previous_frame;
+  if packet_is_key && !planned_key {
+    references.on_key_frame(metadata.long_term_reference);
+    metadata.references.clear();
+    metadata.references_previous_frame = false;
+  }
+  Some(metadata)
+}
+
+/// Attach an input's HDR dynamic metadata to its output chunk
+///
+/// Returns the value for `EncodedVideoChunkMetadata.hdrDynamicMetadata`.
+fn attach_dynamic_metadata(
+  chunk: &EncodedVideoChunk,
+  metadata: Vec<HdrDynamicMetadata>,
+) -> Option<Vec<HdrDynamicMetadata>> {
+  if metadata.is_empty() {
+    return None;
+  }
+  chunk.set_hdr_dynamic_metadata(metadata.clone());
+  Some(metadata)
+}
+
+/// displayAspectWidth/Height for decoderConfig metadata
+///
+/// The config's displayWidth/displayHeight win. Otherwise the first input
+/// frame's display size is used when its aspect ratio differs from the coded size.
+fn display_aspect(
+  config: &VideoEncoderConfig,
+  input_display_size: Option<(u32, u32)>,
+) -> (Option<u32>, Option<u32>) {
+  if config.display_width.is_some() || config.display_height.is_some() {
+    return (config.display_width, config.display_height);
+  }
+  let (width, height) = (config.width.unwrap_or(0), config.height.unwrap_or(0));
+  match input_display_size {
+    Some((display_width, display_height))
+      if u64::from(display_width) * u64::from(height)
+        != u64::from(display_height) * u64::from(width) =>
+    {
+      (Some(display_width), Some(display_height))
+    }
+    _ => (None, None),
+  }
+}
+
+/// Record an output chunk's size and timing for bitrateSummary()
+fn record_output_chunk(tracker: &mut BitrateTracker, chunk: &EncodedVideoChunk) {
+  if let (Ok(timestamp), Ok(duration), Ok(bytes)) =
+    (chunk.timestamp(), chunk.duration(), chunk.byte_length())
+  {
+    tracker.record(timestamp, duration, bytes);
+  }
+}
+
+/// Parameter sets announced by an H.264/H.265 key frame, in Annex B form
+///
+/// Encoders repeat them in-band ahead of the IDR slice, or publish new
+/// extradata as AV_PKT_DATA_NEW_EXTRADATA side data.
+fn key_frame_parameter_sets(codec_id: Option<AVCodecID>, packet: &Packet) -> Option<Vec<u8>> {
+  let is_h265 = match codec_id {
+    Some(AVCodecID::H264) => false,
+    Some(AVCodecID::Hevc) => true,
+    _ => return None,
+  };
+  if !packet.is_key() {
+    return None;
+  }
+  packet
+    .side_data(AV_PKT_DATA_NEW_EXTRADATA)
+    .and_then(|extradata| extradata_parameter_sets(extradata, is_h265))
+    .or_else(|| packet_parameter_sets(packet.as_slice(), is_h265))
+}
+
+/// Check a key frame's parameter sets against the last decoderConfig
+///
+/// Some hardware encoders issue a new SPS mid-session (e.g. a level change
+/// after a rate change). When the parameter sets differ, the decoderConfig is
+/// marked unsent so the key frame carries a fresh one and muxers pick up the
+/// new description at the change point. Returns whether they differ.
+fn parameter_sets_changed(inner: &mut VideoEncoderInner, parameter_sets: Option<&[u8]>) -> bool {
+  let Some(parameter_sets) = parameter_sets else {
+    return false;
+  };
+  if !inner.extradata_sent {
+    return false;
+  }
+  match inner.described_parameter_sets.as_deref() {
+    Some(described) if described != parameter_sets => {
+      inner.extradata_sent = false;
+      true
+    }
+    Some(_) => false,
+    None => {
+      // The decoderConfig had no description; the first in-band sets are the baseline
+      inner.described_parameter_sets = Some(parameter_sets.to_vec());
+      false
+    }
+  }
+}
+
 /// Create SvcOutputMetadata if temporal layers are configured
 fn create_svc_metadata(layer_count: Option<u32>, frame_idx: u64) -> Option<SvcOutputMetadata> {
   layer_count.map(|layers| SvcOutputMetadata {
@@ -3974,7 +6095,7 @@ fn create_svc_metadata(layer_count: Option<u32>, frame_idx: u64) -> Option<SvcOu
 /// - Remaining bytes: Actual BlockAdditional data
 ///
 /// For VP9 alpha, BlockAddId must be 1 (MATROSKA_BLOCK_ADD_ID_TYPE_OPAQUE)
-fn extract_alpha_side_data(packet: &Packet, use_alpha: bool) -> Option<Uint8Array> {
+fn extract_alpha_side_data(packet: &Packet, use_alpha: bool) -> Option<PlainUint8Array> {
   if !use_alpha {
     return None;
   }
@@ -4016,17 +6137,17 @@ fn extract_alpha_side_data(packet: &Packet, use_alpha: bool) -> Option<Uint8Arra
 
   // Return the complete data (BlockAddId + actual data)
   // This matches FFmpeg's expected format
-  Some(Uint8Array::from(alpha_data.to_vec()))
+  Some(PlainUint8Array::from(alpha_data.to_vec()))
 }
 
 /// Check if dimensions are within valid range
-fn are_dimensions_valid(width: u32, height: u32) -> bool {
+pub(crate) fn are_dimensions_valid(width: u32, height: u32) -> bool {
   width <= MAX_DIMENSION && height <= MAX_DIMENSION
 }
 
 /// FFmpeg's FF_QP2LAMBDA constant (from libavutil/internal.h)
 /// Used to convert QP values to the quality field expected by FFmpeg encoders
-const FF_QP2LAMBDA: i32 = 118;
+pub(crate) const FF_QP2LAMBDA: i32 = 118;
 
 /// Extract per-frame quantizer value from encode options based on codec type
 /// Returns the quantizer value for the codec, or None if not specified
@@ -4089,6 +6210,94 @@ fn codec_uses_q_index(codec_id: AVCodecID) -> bool {
   matches!(codec_id, AVCodecID::Vp9 | AVCodecID::Av1)
 }
 
+/// Lowest bitrate the resolution-based default goes down to
+const MIN_DEFAULT_BITRATE: f64 = 100_000.0;
+
+/// Default bitrate for a config that sets neither `bitrate` nor `bitrateMode: 'quantizer'`
+///
+/// Scales with pixels per second at a bits-per-pixel target for the codec's
+/// generation: ~230 kbps for 320x240@30 H.264, ~25 Mbps for 2160p60 HEVC.
+pub(crate) fn default_bitrate(codec_id: AVCodecID, width: u32, height: u32, framerate: f64) -> f64 {
+  let bits_per_pixel = match codec_id {
+    AVCodecID::Hevc | AVCodecID::Av1 => 0.05,
+    AVCodecID::Vp9 => 0.06,
+    // H.264, VP8
+    _ => 0.1,
+  };
+  let bitrate = width as f64 * height as f64 * framerate * bits_per_pixel;
+  // Whole kbps, so the value echoed in configs reads naturally
+  ((bitrate / 1000.0).round() * 1000.0).max(MIN_DEFAULT_BITRATE)
+}
+
+/// Borrow FFmpeg options as the key/value pairs CodecContext::set_options() takes
+pub(crate) fn option_pairs(options: &FfmpegOptions) -> impl Iterator<Item = (&str, &str)> {
+  options
+    .iter()
+    .map(|(key, value)| (key.as_str(), value.as_str()))
+}
+
+/// Pixel format frames are converted to before encoding
+pub(crate) fn encoder_pixel_format(
+  codec_id: AVCodecID,
+  codec: &str,
+  use_alpha: bool,
+) -> AVPixelFormat {
+  if use_alpha {
+    if codec_id == AVCodecID::Hevc && is_hevc_10bit(codec) {
+      AVPixelFormat::Yuva420p10le // 10-bit HEVC with alpha
+    } else {
+      AVPixelFormat::Yuva420p // 8-bit VP9/HEVC with alpha
+    }
+  } else {
+    profile_pixel_format(codec_id, codec).unwrap_or(AVPixelFormat::Yuv420p)
+  }
+}
+
+/// 4:2:2 or 4:4:4 pixel format selected by an HEVC or AV1 codec string
+///
+/// HEVC Range Extensions (`hvc1.4...` with constraint bytes) and AV1 High or
+/// Professional (`av01.P.LLT.DD.M.CCC`) name their chroma subsampling and bit
+/// depth. Returns `None` for 4:2:0, which keeps the 8-bit default.
+fn profile_pixel_format(codec_id: AVCodecID, codec: &str) -> Option<AVPixelFormat> {
+  if !matches!(codec_id, AVCodecID::Hevc | AVCodecID::Av1) {
+    return None;
+  }
+  let parsed = crate::webcodecs::codec_string::parse_codec_string(codec)?;
+  match (parsed.chroma_subsampling?, parsed.bit_depth.unwrap_or(8)) {
+    (422, 8) => Some(AVPixelFormat::Yuv422p),
+    (422, 10) => Some(AVPixelFormat::Yuv422p10le),
+    (422, 12) => Some(AVPixelFormat::Yuv422p12le),
+    (444, 8) => Some(AVPixelFormat::Yuv444p),
+    (444, 10) => Some(AVPixelFormat::Yuv444p10le),
+    (444, 12) => Some(AVPixelFormat::Yuv444p12le),
+    _ => None,
+  }
+}
+
+/// FFmpeg profile constant matching a 4:2:2 or 4:4:4 encoder pixel format
+///
+/// Encoders that infer the profile from the input format ignore it; the
+/// others need it to signal anything beyond 4:2:0.
+pub(crate) fn encoder_profile(codec_id: AVCodecID, pixel_format: AVPixelFormat) -> Option<i32> {
+  let is_422 = matches!(
+    pixel_format,
+    AVPixelFormat::Yuv422p | AVPixelFormat::Yuv422p10le | AVPixelFormat::Yuv422p12le
+  );
+  let is_444 = matches!(
+    pixel_format,
+    AVPixelFormat::Yuv444p | AVPixelFormat::Yuv444p10le | AVPixelFormat::Yuv444p12le
+  );
+  match codec_id {
+    // FF_PROFILE_HEVC_REXT
+    AVCodecID::Hevc if is_422 || is_444 => Some(4),
+    // FF_PROFILE_AV1_PROFESSIONAL: 4:2:2 and 12-bit 4:4:4
+    AVCodecID::Av1 if is_422 || pixel_format == AVPixelFormat::Yuv444p12le => Some(2),
+    // FF_PROFILE_AV1_HIGH: 8/10-bit 4:4:4
+    AVCodecID::Av1 if is_444 => Some(1),
+    _ => None,
+  }
+}
+
 /// Check if HEVC codec string specifies 10-bit profile (Main 10)
 /// Returns true if profile is 2 (Main 10), false otherwise
 fn is_hevc_10bit(codec: &str) -> bool {
@@ -4111,7 +6320,7 @@ fn is_hevc_10bit(codec: &str) -> bool {
 
 /// Parse WebCodecs codec string to FFmpeg codec ID
 /// Returns error for unsupported or invalid codec strings
-fn parse_codec_string(codec: &str) -> Result<AVCodecID> {
+pub(crate) fn parse_codec_string(codec: &str) -> Result<AVCodecID> {
   // Handle common codec strings
   // https://www.w3.org/TR/webcodecs-codec-registry/
 
@@ -3,18 +3,24 @@
 //! Represents a frame of video data that can be displayed or encoded.
 //! See: https://developer.mozilla.org/en-US/docs/Web/API/VideoFrame
 
-use crate::codec::{Frame, Scaler};
+use crate::codec::demuxer::StreamColorInfo;
+use crate::codec::{
+  ChromaLocation, DitherMode, Frame, ScaleAlgorithm, ScaleOptions, Scaler, download_hw_frame,
+};
 use crate::ffi::{
   AVColorPrimaries, AVColorRange, AVColorSpace, AVColorTransferCharacteristic, AVPixelFormat,
 };
+use crate::webcodecs::buffer_view::BufferView;
 use crate::webcodecs::error::{
   enforce_range_long_long, enforce_range_long_long_optional, invalid_state_error,
   not_supported_error, throw_invalid_state_error, throw_not_supported_error, type_error,
 };
+use crate::webcodecs::frame_budget::OutputSlot;
+use crate::webcodecs::plain_bytes::PlainUint8Array;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use parking_lot::RwLock;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 
 /// Video pixel format (WebCodecs spec)
 #[napi(string_enum)]
@@ -613,7 +619,19 @@ impl VideoColorSpace {
 /// Converts FFmpeg color metadata (primaries, transfer, colorspace, range)
 /// to WebCodecs VideoColorSpace. Used for colorSpaceConversion: "default".
 pub fn color_space_from_frame(frame: &Frame) -> VideoColorSpace {
-  let primaries = match frame.color_primaries() {
+  color_space_from_av(&StreamColorInfo {
+    primaries: frame.color_primaries(),
+    transfer: frame.color_trc(),
+    matrix: frame.colorspace(),
+    range: frame.color_range(),
+  })
+}
+
+/// Convert FFmpeg color metadata to WebCodecs VideoColorSpace
+///
+/// Values without a WebCodecs equivalent map to null.
+pub fn color_space_from_av(color: &StreamColorInfo) -> VideoColorSpace {
+  let primaries = match color.primaries {
     AVColorPrimaries::Bt709 => Some(VideoColorPrimaries::Bt709),
     AVColorPrimaries::Bt470bg => Some(VideoColorPrimaries::Bt470bg),
     AVColorPrimaries::Smpte170m => Some(VideoColorPrimaries::Smpte170m),
@@ -622,16 +640,17 @@ pub fn color_space_from_frame(frame: &Frame) -> VideoColorSpace {
     _ => None, // Unspecified or unsupported
   };
 
-  let transfer = match frame.color_trc() {
+  let transfer = match color.transfer {
     AVColorTransferCharacteristic::Bt709 => Some(VideoTransferCharacteristics::Bt709),
     AVColorTransferCharacteristic::Smpte170m => Some(VideoTransferCharacteristics::Smpte170m),
     AVColorTransferCharacteristic::Iec61966_2_1 => Some(VideoTransferCharacteristics::Iec6196621),
+    AVColorTransferCharacteristic::Linear => Some(VideoTransferCharacteristics::Linear),
     AVColorTransferCharacteristic::Smpte2084 => Some(VideoTransferCharacteristics::Pq),
     AVColorTransferCharacteristic::AribStdB67 => Some(VideoTransferCharacteristics::Hlg),
     _ => None, // Unspecified or unsupported
   };
 
-  let matrix = match frame.colorspace() {
+  let matrix = match color.matrix {
     AVColorSpace::Rgb => Some(VideoMatrixCoefficients::Rgb),
     AVColorSpace::Bt709 => Some(VideoMatrixCoefficients::Bt709),
     AVColorSpace::Bt470bg => Some(VideoMatrixCoefficients::Bt470bg),
@@ -640,7 +659,7 @@ pub fn color_space_from_frame(frame: &Frame) -> VideoColorSpace {
     _ => None, // Unspecified or unsupported
   };
 
-  let full_range = match frame.color_range() {
+  let full_range = match color.range {
     AVColorRange::Jpeg => Some(true),  // Full range (0-255)
     AVColorRange::Mpeg => Some(false), // Limited range (16-235)
     _ => None,                         // Unspecified
@@ -649,6 +668,54 @@ pub fn color_space_from_frame(frame: &Frame) -> VideoColorSpace {
   VideoColorSpace::from_components(primaries, transfer, matrix, full_range)
 }
 
+/// Convert a WebCodecs color space to FFmpeg color metadata
+///
+/// Unset fields map to `Unspecified`.
+pub fn color_space_to_av(init: &VideoColorSpaceInit) -> StreamColorInfo {
+  let primaries = match init.primaries {
+    Some(VideoColorPrimaries::Bt709) => AVColorPrimaries::Bt709,
+    Some(VideoColorPrimaries::Bt470bg) => AVColorPrimaries::Bt470bg,
+    Some(VideoColorPrimaries::Smpte170m) => AVColorPrimaries::Smpte170m,
+    Some(VideoColorPrimaries::Bt2020) => AVColorPrimaries::Bt2020,
+    Some(VideoColorPrimaries::Smpte432) => AVColorPrimaries::Smpte432,
+    None => AVColorPrimaries::Unspecified,
+  };
+
+  let transfer = match init.transfer {
+    Some(VideoTransferCharacteristics::Bt709) => AVColorTransferCharacteristic::Bt709,
+    Some(VideoTransferCharacteristics::Smpte170m) => AVColorTransferCharacteristic::Smpte170m,
+    Some(VideoTransferCharacteristics::Iec6196621 | VideoTransferCharacteristics::Srgb) => {
+      AVColorTransferCharacteristic::Iec61966_2_1
+    }
+    Some(VideoTransferCharacteristics::Linear) => AVColorTransferCharacteristic::Linear,
+    Some(VideoTransferCharacteristics::Pq) => AVColorTransferCharacteristic::Smpte2084,
+    Some(VideoTransferCharacteristics::Hlg) => AVColorTransferCharacteristic::AribStdB67,
+    None => AVColorTransferCharacteristic::Unspecified,
+  };
+
+  let matrix = match init.matrix {
+    Some(VideoMatrixCoefficients::Rgb) => AVColorSpace::Rgb,
+    Some(VideoMatrixCoefficients::Bt709) => AVColorSpace::Bt709,
+    Some(VideoMatrixCoefficients::Bt470bg) => AVColorSpace::Bt470bg,
+    Some(VideoMatrixCoefficients::Smpte170m) => AVColorSpace::Smpte170m,
+    Some(VideoMatrixCoefficients::Bt2020Ncl) => AVColorSpace::Bt2020Ncl,
+    None => AVColorSpace::Unspecified,
+  };
+
+  let range = match init.full_range {
+    Some(true) => AVColorRange::Jpeg,
+    Some(false) => AVColorRange::Mpeg,
+    None => AVColorRange::Unspecified,
+  };
+
+  StreamColorInfo {
+    primaries,
+    transfer,
+    matrix,
+    range,
+  }
+}
+
 /// DOMRectReadOnly - W3C WebCodecs spec compliant rect class
 /// Used for codedRect and visibleRect properties
 #[napi(js_name = "DOMRectReadOnly")]
@@ -733,11 +800,147 @@ impl DOMRectReadOnly {
   }
 }
 
+/// Kind of per-frame HDR dynamic metadata
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HdrDynamicMetadataType {
+  /// ITU-T T.35 registered user data (e.g. HDR10+ / SMPTE ST 2094-40)
+  #[napi(value = "itu-t-t35")]
+  ItuTT35,
+  /// Dolby Vision RPU NAL unit
+  #[napi(value = "dolby-vision-rpu")]
+  DolbyVisionRpu,
+}
+
+/// Opaque per-frame HDR dynamic metadata blob
+///
+/// Carried through decode/encode/mux unchanged; the payload is never interpreted
+/// except to convert HDR10+ to and from FFmpeg side data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HdrDynamicMetadata {
+  /// Metadata kind
+  pub kind: HdrDynamicMetadataType,
+  /// Complete payload (T.35 from the country code on, or the RPU NAL unit)
+  pub data: Vec<u8>,
+}
+
+impl HdrDynamicMetadata {
+  /// Collect the dynamic metadata FFmpeg attached to a decoded frame
+  pub(crate) fn from_frame(frame: &Frame) -> Vec<Self> {
+    let mut metadata = Vec::new();
+    if let Some(data) = frame.hdr10plus_t35() {
+      metadata.push(Self {
+        kind: HdrDynamicMetadataType::ItuTT35,
+        data,
+      });
+    }
+    if let Some(rpu) = frame.dovi_rpu() {
+      metadata.push(Self {
+        kind: HdrDynamicMetadataType::DolbyVisionRpu,
+        data: rpu.to_vec(),
+      });
+    }
+    metadata
+  }
+}
+
+impl TypeName for HdrDynamicMetadata {
+  fn type_name() -> &'static str {
+    "HdrDynamicMetadata"
+  }
+
+  fn value_type() -> ValueType {
+    ValueType::Object
+  }
+}
+
+impl ValidateNapiValue for HdrDynamicMetadata {}
+
+impl FromNapiValue for HdrDynamicMetadata {
+  unsafe fn from_napi_value(
+    env: napi::sys::napi_env,
+    value: napi::sys::napi_value,
+  ) -> Result<Self> {
+    let env_wrapper = Env::from_raw(env);
+    let obj = unsafe { Object::from_napi_value(env, value)? };
+
+    let kind = match obj.get::<String>("type")?.as_deref() {
+      Some("itu-t-t35") => HdrDynamicMetadataType::ItuTT35,
+      Some("dolby-vision-rpu") => HdrDynamicMetadataType::DolbyVisionRpu,
+      Some(other) => {
+        env_wrapper.throw_type_error(
+          &format!("Invalid HDR dynamic metadata type: {}", other),
+          None,
+        )?;
+        return Err(Error::new(
+          Status::InvalidArg,
+          "Invalid HDR dynamic metadata type",
+        ));
+      }
+      None => {
+        env_wrapper.throw_type_error("HDR dynamic metadata type is required", None)?;
+        return Err(Error::new(
+          Status::InvalidArg,
+          "HDR dynamic metadata type is required",
+        ));
+      }
+    };
+
+    let data = match obj.get::<BufferView>("data")? {
+      Some(data) => data.to_vec(),
+      None => {
+        env_wrapper.throw_type_error("HDR dynamic metadata data is required", None)?;
+        return Err(Error::new(
+          Status::InvalidArg,
+          "HDR dynamic metadata data is required",
+        ));
+      }
+    };
+
+    Ok(HdrDynamicMetadata { kind, data })
+  }
+}
+
+impl ToNapiValue for HdrDynamicMetadata {
+  unsafe fn to_napi_value(env: napi::sys::napi_env, val: Self) -> Result<napi::sys::napi_value> {
+    let env_wrapper = Env::from_raw(env);
+    let mut obj = Object::new(&env_wrapper)?;
+    obj.set("type", val.kind)?;
+    obj.set("data", PlainUint8Array::from(val.data))?;
+    unsafe { Object::to_napi_value(env, obj) }
+  }
+}
+
 /// VideoFrameMetadata - metadata associated with a VideoFrame (W3C spec)
-/// Members defined in VideoFrame Metadata Registry - currently empty per spec
+///
+/// The registry defines no members yet; `hdrDynamicMetadata` and
+/// `closedCaptions` are extensions carrying per-frame HDR10+ / Dolby Vision
+/// metadata and A53 captions through the pipeline.
 #[napi(object)]
 #[derive(Debug, Clone, Default)]
-pub struct VideoFrameMetadata {}
+pub struct VideoFrameMetadata {
+  /// Per-frame HDR dynamic metadata (HDR10+ T.35, Dolby Vision RPU)
+  #[napi(ts_type = "Array<HdrDynamicMetadata>")]
+  pub hdr_dynamic_metadata: Option<Vec<HdrDynamicMetadata>>,
+  /// A53 closed captions (CEA-608/708 cc_data triplets) from a caption SEI
+  ///
+  /// Encoding the frame writes them back as a caption SEI (H.264, H.265).
+  #[napi(ts_type = "Uint8Array")]
+  pub closed_captions: Option<PlainUint8Array>,
+}
+
+impl VideoFrameMetadata {
+  /// Metadata of a decoded frame: the given dynamic metadata and the
+  /// frame's closed captions (each None if absent)
+  pub(crate) fn decoded(hdr_dynamic_metadata: Vec<HdrDynamicMetadata>, frame: &Frame) -> Self {
+    Self {
+      hdr_dynamic_metadata: (!hdr_dynamic_metadata.is_empty()).then_some(hdr_dynamic_metadata),
+      closed_captions: frame
+        .a53_cc()
+        .map(|data| PlainUint8Array::from(data.to_vec())),
+    }
+  }
+}
 
 /// Options for creating a VideoFrame from buffer data (VideoFrameBufferInit per spec)
 pub struct VideoFrameBufferInit {
@@ -991,6 +1194,124 @@ pub struct VideoFrameCopyToOptions {
   pub rect: Option<DOMRectInit>,
   /// Layout for output planes
   pub layout: Option<Vec<PlaneLayout>>,
+  /// Interpolation used for chroma upsampling during format conversion (default "bilinear")
+  pub scale_algorithm: Option<VideoScaleAlgorithm>,
+  /// Dithering when converting >8-bit frames to RGBA/BGRA (default "none")
+  pub dither: Option<VideoDitherMode>,
+  /// Chroma siting of the source frame (defaults to the format's conventional siting)
+  pub chroma_location: Option<VideoChromaLocation>,
+  /// Use exact rounding in the YUV to RGB conversion (default false)
+  pub accurate_rounding: Option<bool>,
+  /// Interpolate chroma at full resolution instead of per 2-pixel pair (default false)
+  pub full_chroma_interpolation: Option<bool>,
+}
+
+impl VideoFrameCopyToOptions {
+  /// Conversion settings for the Scaler (defaults match plain `copyTo`)
+  fn scale_options(&self) -> ScaleOptions {
+    ScaleOptions {
+      algorithm: self.scale_algorithm.map(Into::into).unwrap_or_default(),
+      dither: self.dither.map(Into::into).unwrap_or_default(),
+      chroma_location: self.chroma_location.map(Into::into),
+      accurate_rounding: self.accurate_rounding.unwrap_or(false),
+      full_chroma_interpolation: self.full_chroma_interpolation.unwrap_or(false),
+    }
+  }
+}
+
+/// Interpolation algorithm for format conversion
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoScaleAlgorithm {
+  /// Fast bilinear (fastest, lower quality)
+  #[napi(value = "fast-bilinear")]
+  FastBilinear,
+  /// Bilinear (default)
+  #[napi(value = "bilinear")]
+  Bilinear,
+  /// Bicubic
+  #[napi(value = "bicubic")]
+  Bicubic,
+  /// Lanczos (highest quality, slowest)
+  #[napi(value = "lanczos")]
+  Lanczos,
+  /// Nearest neighbor
+  #[napi(value = "point")]
+  Point,
+}
+
+impl From<VideoScaleAlgorithm> for ScaleAlgorithm {
+  fn from(algorithm: VideoScaleAlgorithm) -> Self {
+    match algorithm {
+      VideoScaleAlgorithm::FastBilinear => ScaleAlgorithm::FastBilinear,
+      VideoScaleAlgorithm::Bilinear => ScaleAlgorithm::Bilinear,
+      VideoScaleAlgorithm::Bicubic => ScaleAlgorithm::Bicubic,
+      VideoScaleAlgorithm::Lanczos => ScaleAlgorithm::Lanczos,
+      VideoScaleAlgorithm::Point => ScaleAlgorithm::Point,
+    }
+  }
+}
+
+/// Dithering applied when reducing >8-bit frames to 8-bit RGBA/BGRA
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoDitherMode {
+  /// Round to nearest (default)
+  #[napi(value = "none")]
+  None,
+  /// 8x8 ordered dither
+  #[napi(value = "bayer")]
+  Bayer,
+  /// Floyd-Steinberg error diffusion
+  #[napi(value = "error-diffusion")]
+  ErrorDiffusion,
+}
+
+impl From<VideoDitherMode> for DitherMode {
+  fn from(mode: VideoDitherMode) -> Self {
+    match mode {
+      VideoDitherMode::None => DitherMode::None,
+      VideoDitherMode::Bayer => DitherMode::Bayer,
+      VideoDitherMode::ErrorDiffusion => DitherMode::ErrorDiffusion,
+    }
+  }
+}
+
+/// Chroma sample siting relative to the luma grid
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoChromaLocation {
+  /// Co-sited horizontally, centered vertically (H.264/HEVC default)
+  #[napi(value = "left")]
+  Left,
+  /// Centered (JPEG/MPEG-1)
+  #[napi(value = "center")]
+  Center,
+  /// Co-sited with the top-left luma sample (BT.2020 4:2:0)
+  #[napi(value = "top-left")]
+  TopLeft,
+  /// Centered horizontally, co-sited with the top row
+  #[napi(value = "top")]
+  Top,
+  /// Co-sited with the bottom-left luma sample
+  #[napi(value = "bottom-left")]
+  BottomLeft,
+  /// Centered horizontally, co-sited with the bottom row
+  #[napi(value = "bottom")]
+  Bottom,
+}
+
+impl From<VideoChromaLocation> for ChromaLocation {
+  fn from(location: VideoChromaLocation) -> Self {
+    match location {
+      VideoChromaLocation::Left => ChromaLocation::Left,
+      VideoChromaLocation::Center => ChromaLocation::Center,
+      VideoChromaLocation::TopLeft => ChromaLocation::TopLeft,
+      VideoChromaLocation::Top => ChromaLocation::Top,
+      VideoChromaLocation::BottomLeft => ChromaLocation::BottomLeft,
+      VideoChromaLocation::Bottom => ChromaLocation::Bottom,
+    }
+  }
 }
 
 /// DOMRectInit for specifying regions
@@ -1044,7 +1365,83 @@ struct VideoFrameInner {
   /// Horizontal flip
   flip: bool,
   color_space: VideoColorSpace,
+  /// Frame metadata (VideoFrameMetadata registry members and extensions)
+  metadata: VideoFrameMetadata,
   closed: bool,
+  /// Decoder frame budget slot (maxOutstandingFrames), released on close or drop
+  output_slot: Option<OutputSlot>,
+}
+
+impl VideoFrameInner {
+  /// Close: drop the pixel data reference now instead of when JS garbage-collects
+  /// the VideoFrame
+  ///
+  /// The inner struct is kept to preserve metadata (timestamp, duration, etc.) per
+  /// W3C spec. Frames sharing the pixel data (clone(), output sinks, in-flight
+  /// encodes) hold their own reference and are unaffected.
+  fn release(&mut self) {
+    self.closed = true;
+    self.output_slot = None;
+    if let Ok(empty) = Frame::new() {
+      self.frame = Arc::new(RwLock::new(empty));
+    }
+  }
+}
+
+/// Weak reference to a VideoFrame that can close it from outside JS
+///
+/// Used by `VideoDecoder.close()` to either invalidate the decoder's output
+/// frames that the application has not closed (`detachFrames`), or to make
+/// sure they no longer depend on the decoder's hardware device.
+#[derive(Clone)]
+pub(crate) struct VideoFrameHandle(Weak<Mutex<Option<VideoFrameInner>>>);
+
+impl VideoFrameHandle {
+  /// Close the frame if it is still alive
+  pub fn close(&self) {
+    if let Some(inner) = self.0.upgrade()
+      && let Ok(mut guard) = inner.lock()
+      && let Some(inner) = guard.as_mut()
+    {
+      inner.release();
+    }
+  }
+
+  /// Download the pixel data to CPU memory if it still lives on a hardware device
+  ///
+  /// Frames sharing the pixel data (clone(), output sinks) see the download too.
+  /// A frame whose download fails is closed rather than left pointing at a
+  /// device surface that is about to go away.
+  pub fn transfer_to_cpu(&self) {
+    let Some(inner) = self.0.upgrade() else {
+      return;
+    };
+    let Ok(mut guard) = inner.lock() else {
+      return;
+    };
+    let Some(inner) = guard.as_mut() else {
+      return;
+    };
+    if inner.closed {
+      return;
+    }
+    let mut frame = inner.frame.write();
+    if !frame.format().is_hardware() {
+      return;
+    }
+    match download_hw_frame(&frame) {
+      Ok(sw_frame) => *frame = sw_frame,
+      Err(e) => {
+        tracing::warn!(
+          target: "webcodecs",
+          error = %e,
+          "Closing VideoFrame whose hardware surface could not be downloaded"
+        );
+        drop(frame);
+        inner.release();
+      }
+    }
+  }
 }
 
 /// Get (horizontal_factor, vertical_factor) sub-sampling for chroma planes
@@ -1215,7 +1612,7 @@ impl VideoFrame {
   /// 3. `new VideoFrame(canvas, init)` - from @napi-rs/canvas Canvas (requires timestamp in init)
   #[napi(
     constructor,
-    ts_args_type = "source: VideoFrame | Uint8Array | CanvasLike, init?: VideoFrameBufferInit | VideoFrameInit"
+    ts_args_type = "source: VideoFrame | BufferSource | CanvasLike, init?: VideoFrameBufferInit | VideoFrameInit"
   )]
   pub fn new(env: Env, source: Unknown, init: Option<VideoFrameConstructorInit>) -> Result<Self> {
     // Try VideoFrame first (check for codedWidth property which only VideoFrame has)
@@ -1265,15 +1662,15 @@ impl VideoFrame {
       }
     }
 
-    // Try as Uint8Array/Buffer
-    let data = Uint8ArraySlice::from_unknown(source).map_err(|_| {
+    // Try as BufferSource (ArrayBuffer, TypedArray, DataView or Buffer)
+    let data = unsafe { BufferView::from_napi_value(env.raw(), source.raw()) }.map_err(|_| {
       let _ = env.throw_type_error(
-        "First argument must be a VideoFrame, Canvas, or BufferSource (Uint8Array/Buffer)",
+        "First argument must be a VideoFrame, Canvas, or BufferSource",
         None,
       );
       Error::new(
         Status::InvalidArg,
-        "First argument must be a VideoFrame, Canvas, or BufferSource (Uint8Array/Buffer)",
+        "First argument must be a VideoFrame, Canvas, or BufferSource",
       )
     })?;
 
@@ -1442,7 +1839,9 @@ impl VideoFrame {
       rotation,
       flip,
       color_space,
+      metadata: init.metadata.clone().unwrap_or_default(),
       closed: false,
+      output_slot: None,
     };
 
     Ok(Self {
@@ -1613,40 +2012,22 @@ impl VideoFrame {
         .map(|a| a == "discard")
         .unwrap_or(false);
 
-      // Handle alpha discard: convert to non-alpha format if needed
-      // Otherwise, share the Arc (no pixel data copy)
+      // Handle alpha discard by switching the reported format of a shallow clone:
+      // planar YUVA drops its alpha plane, packed RGBA/BGRA becomes RGBX/BGRX
+      // (the X byte overlays the alpha byte). Pixel data stays shared.
       let (final_frame, final_format) =
         if should_discard_alpha && source_inner.original_format.has_alpha() {
           let target_format = source_inner.original_format.without_alpha();
-          let src_av_format = source_inner.original_format.to_av_format();
-          let dst_av_format = target_format.to_av_format();
-
-          // Need to read from source frame for conversion
-          let source_frame_guard = source_inner.frame.read();
-
-          // Use Scaler to convert format (drops alpha channel)
-          let scaler = Scaler::new_converter(
-            source_frame_guard.width(),
-            source_frame_guard.height(),
-            src_av_format,
-            dst_av_format,
-          )
-          .map_err(|e| {
+          let mut shallow = source_inner.frame.read().shallow_clone().map_err(|e| {
             Error::new(
               Status::GenericFailure,
-              format!("Failed to create converter for alpha discard: {}", e),
+              format!("Failed to reference frame for alpha discard: {}", e),
             )
           })?;
-
-          let converted = scaler.scale_alloc(&source_frame_guard).map_err(|e| {
-            Error::new(
-              Status::GenericFailure,
-              format!("Failed to convert frame for alpha discard: {}", e),
-            )
-          })?;
-
-          // Conversion creates a new frame, wrap it in Arc
-          (converted.into_shared(), target_format)
+          if !shallow.drop_alpha_plane() {
+            shallow.set_format(target_format.to_av_format());
+          }
+          (shallow.into_shared(), target_format)
         } else {
           // No conversion needed - share the same underlying frame data via Arc clone
           (source_inner.frame.clone(), source_inner.original_format)
@@ -1698,30 +2079,49 @@ impl VideoFrame {
       // Per spec: flip is XOR of base and init flip
       let combined_flip = base_flip != init_flip;
 
-      // Per spec: display dimensions scale proportionally with visible rect changes
-      // If not explicitly provided, scale based on ratio of new visible rect to old
-      let display_width = init
-        .as_ref()
-        .and_then(|i| i.display_width)
-        .unwrap_or_else(|| {
-          if source_inner.visible_width > 0 {
-            let scale = visible_width as f64 / source_inner.visible_width as f64;
-            ((source_inner.display_width as f64) * scale).round() as u32
-          } else {
-            visible_width
-          }
-        });
-      let display_height = init
-        .as_ref()
-        .and_then(|i| i.display_height)
-        .unwrap_or_else(|| {
-          if source_inner.visible_height > 0 {
-            let scale = visible_height as f64 / source_inner.visible_height as f64;
-            ((source_inner.display_height as f64) * scale).round() as u32
+      // displayWidth and displayHeight must be given together and be non-zero
+      let init_display = match init.as_ref().map(|i| (i.display_width, i.display_height)) {
+        Some((Some(width), Some(height))) if width > 0 && height > 0 => Some((width, height)),
+        Some((None, None)) | None => None,
+        Some((Some(_), Some(_))) => {
+          return Err(Error::new(
+            Status::InvalidArg,
+            "TypeError: displayWidth and displayHeight must be greater than 0",
+          ));
+        }
+        Some(_) => {
+          return Err(Error::new(
+            Status::InvalidArg,
+            "TypeError: displayWidth and displayHeight must be specified together",
+          ));
+        }
+      };
+
+      // Per spec: display dimensions scale proportionally with visible rect changes.
+      // Display sizes are in rotated orientation, so scale in the unrotated
+      // orientation of the source and re-orient for the combined rotation.
+      let (display_width, display_height) = init_display.unwrap_or_else(|| {
+        let swaps = |rotation: f64| rotation == 90.0 || rotation == 270.0;
+        let (source_width, source_height) = if swaps(base_rotation) {
+          (source_inner.display_height, source_inner.display_width)
+        } else {
+          (source_inner.display_width, source_inner.display_height)
+        };
+        let scale = |display: u32, visible: u32, new_visible: u32| {
+          if visible > 0 {
+            ((display as f64) * new_visible as f64 / visible as f64).round() as u32
           } else {
-            visible_height
+            new_visible
           }
-        });
+        };
+        let width = scale(source_width, source_inner.visible_width, visible_width);
+        let height = scale(source_height, source_inner.visible_height, visible_height);
+        if swaps(combined_rotation) {
+          (height, width)
+        } else {
+          (width, height)
+        }
+      });
 
       let new_inner = VideoFrameInner {
         frame: final_frame,
@@ -1737,7 +2137,12 @@ impl VideoFrame {
         rotation: combined_rotation,
         flip: combined_flip,
         color_space: source_inner.color_space.clone(),
+        metadata: init
+          .as_ref()
+          .and_then(|i| i.metadata.clone())
+          .unwrap_or_else(|| source_inner.metadata.clone()),
         closed: false,
+        output_slot: None,
       };
 
       Ok(VideoFrame {
@@ -1767,7 +2172,9 @@ impl VideoFrame {
       rotation: 0.0,
       flip: false,
       color_space: VideoColorSpace::default(),
+      metadata: VideoFrameMetadata::default(),
       closed: false,
+      output_slot: None,
     };
 
     Self {
@@ -1804,7 +2211,9 @@ impl VideoFrame {
       rotation: 0.0,
       flip: false,
       color_space: VideoColorSpace::default(),
+      metadata: VideoFrameMetadata::default(),
       closed: false,
+      output_slot: None,
     };
 
     Self {
@@ -1870,7 +2279,9 @@ impl VideoFrame {
       rotation: parsed_rotation,
       flip,
       color_space,
+      metadata: VideoFrameMetadata::default(),
       closed: false,
+      output_slot: None,
     };
 
     Self {
@@ -1914,7 +2325,9 @@ impl VideoFrame {
       rotation: 0.0,
       flip: false,
       color_space,
+      metadata: VideoFrameMetadata::default(),
       closed: false,
+      output_slot: None,
     };
 
     Self {
@@ -1959,7 +2372,9 @@ impl VideoFrame {
       rotation: 0.0,
       flip: false,
       color_space,
+      metadata: VideoFrameMetadata::default(),
       closed: false,
+      output_slot: None,
     };
 
     Self {
@@ -2163,10 +2578,9 @@ impl VideoFrame {
   }
 
   /// Get the metadata associated with this VideoFrame - W3C WebCodecs spec
-  /// Currently returns an empty metadata object as members are defined in the registry
   #[napi]
   pub fn metadata(&self) -> Result<VideoFrameMetadata> {
-    self.with_inner(|_inner| Ok(VideoFrameMetadata {}))
+    self.with_inner(|inner| Ok(inner.metadata.clone()))
   }
 
   /// Calculate the allocation size needed for copyTo
@@ -2386,6 +2800,10 @@ impl VideoFrame {
     // Clone inner Arc for the blocking thread
     let inner_clone = self.inner.clone();
     let layout_for_thread = custom_layout.clone();
+    let scale_options = options
+      .as_ref()
+      .map(VideoFrameCopyToOptions::scale_options)
+      .unwrap_or_default();
 
     // Perform the copy in a blocking thread to not block the event loop
     let copied_data = spawn_blocking(move || -> Result<Vec<u8>> {
@@ -2432,11 +2850,14 @@ impl VideoFrame {
         let dst_av_format = format.to_av_format();
 
         // Use Scaler for format conversion (operates on full frame, then crop)
-        let scaler = Scaler::new_converter(
+        let scaler = Scaler::with_options(
           frame_guard.width(),
           frame_guard.height(),
           src_av_format,
+          frame_guard.width(),
+          frame_guard.height(),
           dst_av_format,
+          scale_options,
         )
         .map_err(|e| {
           Error::new(
@@ -2873,36 +3294,10 @@ impl VideoFrame {
   #[napi(js_name = "clone")]
   pub fn clone_frame(&self, env: Env) -> Result<VideoFrame> {
     // Check closed state and throw native DOMException
-    let guard = self
-      .inner
-      .lock()
-      .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-    let inner = match guard.as_ref() {
-      Some(inner) if !inner.closed => inner,
-      _ => return throw_invalid_state_error(&env, "VideoFrame is closed"),
-    };
-
-    // Clone the Arc - shares the same underlying Frame (no pixel data copy)
-    let new_inner = VideoFrameInner {
-      frame: inner.frame.clone(),
-      original_format: inner.original_format,
-      timestamp_us: inner.timestamp_us,
-      duration_us: inner.duration_us,
-      visible_left: inner.visible_left,
-      visible_top: inner.visible_top,
-      visible_width: inner.visible_width,
-      visible_height: inner.visible_height,
-      display_width: inner.display_width,
-      display_height: inner.display_height,
-      rotation: inner.rotation,
-      flip: inner.flip,
-      color_space: inner.color_space.clone(),
-      closed: false,
-    };
-
-    Ok(VideoFrame {
-      inner: Arc::new(Mutex::new(Some(new_inner))),
-    })
+    match self.share() {
+      Ok(frame) => Ok(frame),
+      Err(_) => throw_invalid_state_error(&env, "VideoFrame is closed"),
+    }
   }
 
   /// Close and release resources
@@ -2918,9 +3313,7 @@ impl VideoFrame {
       .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
 
     if let Some(inner) = guard.as_mut() {
-      inner.closed = true;
-      // Note: We keep the inner struct to preserve metadata (timestamp, duration, etc.)
-      // per W3C spec. The FFmpeg Frame memory will be released when VideoFrame is dropped.
+      inner.release();
     }
 
     Ok(())
@@ -2930,6 +3323,113 @@ impl VideoFrame {
   // Internal helpers (crate-visible only)
   // ========================================================================
 
+  /// Get the per-frame HDR dynamic metadata (empty if none)
+  pub(crate) fn hdr_dynamic_metadata(&self) -> Vec<HdrDynamicMetadata> {
+    self
+      .with_inner(|inner| Ok(inner.metadata.hdr_dynamic_metadata.clone()))
+      .ok()
+      .flatten()
+      .unwrap_or_default()
+  }
+
+  /// Visible rect as (left, top, width, height) when it is smaller than the coded frame
+  pub(crate) fn visible_crop(&self) -> Option<(u32, u32, u32, u32)> {
+    self
+      .with_inner(|inner| {
+        let frame = inner.frame.read();
+        let covers_frame = inner.visible_left == 0
+          && inner.visible_top == 0
+          && inner.visible_width == frame.width()
+          && inner.visible_height == frame.height();
+        Ok((!covers_frame).then_some((
+          inner.visible_left,
+          inner.visible_top,
+          inner.visible_width,
+          inner.visible_height,
+        )))
+      })
+      .ok()
+      .flatten()
+  }
+
+  /// Get the A53 closed captions carried in the frame metadata
+  pub(crate) fn closed_captions(&self) -> Option<PlainUint8Array> {
+    self
+      .with_inner(|inner| Ok(inner.metadata.closed_captions.clone()))
+      .ok()
+      .flatten()
+  }
+
+  /// Replace the frame metadata (used by decoders)
+  pub(crate) fn set_metadata(&self, metadata: VideoFrameMetadata) {
+    if let Ok(mut guard) = self.inner.lock()
+      && let Some(inner) = guard.as_mut()
+    {
+      inner.metadata = metadata;
+    }
+  }
+
+  /// Weak handle for closing this frame from outside JS
+  pub(crate) fn handle(&self) -> VideoFrameHandle {
+    VideoFrameHandle(Arc::downgrade(&self.inner))
+  }
+
+  /// Attach a decoder frame budget slot, released when this frame is closed or collected
+  pub(crate) fn attach_output_slot(&self, slot: OutputSlot) {
+    if let Ok(mut guard) = self.inner.lock()
+      && let Some(inner) = guard.as_mut()
+    {
+      inner.output_slot = Some(slot);
+    }
+  }
+
+  /// Create a new VideoFrame sharing this frame's pixel data (crate-internal `clone()`)
+  ///
+  /// Both frames reference the same Arc<RwLock<Frame>>, so each holder can close
+  /// its VideoFrame independently without any pixel data being copied.
+  pub(crate) fn share(&self) -> Result<VideoFrame> {
+    self.with_inner(|inner| {
+      let new_inner = VideoFrameInner {
+        frame: inner.frame.clone(),
+        original_format: inner.original_format,
+        timestamp_us: inner.timestamp_us,
+        duration_us: inner.duration_us,
+        visible_left: inner.visible_left,
+        visible_top: inner.visible_top,
+        visible_width: inner.visible_width,
+        visible_height: inner.visible_height,
+        display_width: inner.display_width,
+        display_height: inner.display_height,
+        rotation: inner.rotation,
+        flip: inner.flip,
+        color_space: inner.color_space.clone(),
+        metadata: inner.metadata.clone(),
+        closed: false,
+        output_slot: None,
+      };
+
+      Ok(VideoFrame {
+        inner: Arc::new(Mutex::new(Some(new_inner))),
+      })
+    })
+  }
+
+  /// Share this frame's pixel data under a different timestamp and duration
+  pub(crate) fn share_with_timing(
+    &self,
+    timestamp_us: i64,
+    duration_us: Option<i64>,
+  ) -> Result<VideoFrame> {
+    let frame = self.share()?;
+    if let Ok(mut guard) = frame.inner.lock()
+      && let Some(inner) = guard.as_mut()
+    {
+      inner.timestamp_us = timestamp_us;
+      inner.duration_us = duration_us;
+    }
+    Ok(frame)
+  }
+
   /// Get a clone of the Arc<RwLock<Frame>> for sharing with other components.
   ///
   /// This is the preferred way to pass frame data to encoders/other consumers
@@ -2967,7 +3467,7 @@ impl VideoFrame {
     }
   }
 
-  fn calculate_buffer_size(format: VideoPixelFormat, width: u32, height: u32) -> u32 {
+  pub(crate) fn calculate_buffer_size(format: VideoPixelFormat, width: u32, height: u32) -> u32 {
     let w = width;
     let h = height;
     let bps = format.bytes_per_sample() as u32; // bytes per sample (1 for 8-bit, 2 for 10/12-bit)
@@ -3032,7 +3532,7 @@ impl VideoFrame {
     }
   }
 
-  fn copy_data_to_frame(
+  pub(crate) fn copy_data_to_frame(
     frame: &mut Frame,
     data: &[u8],
     format: VideoPixelFormat,
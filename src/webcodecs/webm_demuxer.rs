@@ -4,13 +4,20 @@
 //! WebM typically contains VP8, VP9, or AV1 video with Opus or Vorbis audio.
 
 use crate::ffi::AVCodecID;
+use crate::webcodecs::audio_decoder::AudioDecoder;
+use crate::webcodecs::buffer_view::BufferView;
 use crate::webcodecs::demuxer_base::{
   AudioOutputCallback, DemuxerAudioDecoderConfig, DemuxerChunk, DemuxerFormat, DemuxerInner,
-  DemuxerTrackInfo, DemuxerVideoDecoderConfig, ErrorCallback, VideoOutputCallback,
-  parse_vp9_codec_string, with_demuxer_inner, with_demuxer_inner_mut,
+  DemuxerTrackInfo, DemuxerVideoDecoderConfig, ErrorCallback, RawPacket, SubtitleCue,
+  VideoOutputCallback, analyze_demuxer_track, create_audio_decoder, create_video_decoder,
+  key_frame_packets, parse_vp9_codec_string, read_demuxer_packet, read_demuxer_subtitle_cues,
+  with_demuxer_inner, with_demuxer_inner_mut,
 };
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
 use crate::webcodecs::encoded_video_chunk::EncodedVideoChunk;
+use crate::webcodecs::key_frame_packets::{KeyFramePacketIterator, KeyFramePacketsOptions};
+use crate::webcodecs::track_analysis::{TrackAnalysis, TrackAnalysisOptions};
+use crate::webcodecs::video_decoder::VideoDecoder;
 use napi::bindgen_prelude::*;
 use napi::threadsafe_function::UnknownReturnValue;
 use napi_derive::napi;
@@ -177,18 +184,18 @@ impl WebMDemuxer {
 
   /// Load a WebM from a buffer
   ///
-  /// This method uses zero-copy buffer loading - the Uint8Array data is passed
+  /// This method uses zero-copy buffer loading - the viewed bytes of any BufferSource are passed
   /// directly to the demuxer without an intermediate copy.
   #[napi]
-  pub async fn load_buffer(&self, data: Uint8Array) -> Result<()> {
+  pub async fn load_buffer(&self, data: BufferView) -> Result<()> {
     let inner = self.inner.clone();
-    // Zero-copy: pass Uint8Array directly (it implements BufferSource)
+    // Zero-copy: the view shares memory with the JS buffer
 
     tokio::task::spawn_blocking(move || {
       let mut guard = inner
         .lock()
         .map_err(|_| Error::new(Status::GenericFailure, "Lock poisoned"))?;
-      guard.load_buffer(data)
+      guard.load_buffer(data.into_inner())
     })
     .await
     .map_err(|e| Error::new(Status::GenericFailure, format!("Task error: {}", e)))?
@@ -230,6 +237,80 @@ impl WebMDemuxer {
     guard.select_audio_track(track_index)
   }
 
+  #[napi(
+    ts_args_type = "trackId: number, init: { output: (frame: VideoFrame) => void; error: (error: Error) => void } & Partial<VideoDecoderConfig>"
+  )]
+  pub fn create_video_decoder(
+    &self,
+    env: Env,
+    track_id: i32,
+    init: Object,
+  ) -> Result<VideoDecoder> {
+    create_video_decoder(&self.inner, env, track_id, init)
+  }
+
+  #[napi(
+    ts_args_type = "trackId: number, init: { output: (data: AudioData) => void; error: (error: Error) => void } & Partial<AudioDecoderConfig>"
+  )]
+  pub fn create_audio_decoder(
+    &self,
+    env: Env,
+    track_id: i32,
+    init: Object,
+  ) -> Result<AudioDecoder> {
+    create_audio_decoder(&self.inner, env, track_id, init)
+  }
+
+  /// Scan a track's packet timestamps and sizes without decoding
+  ///
+  /// Returns frame interval statistics, an interval histogram, bitrate per time
+  /// window and whether the track is VFR. The scan reads a separate view of the
+  /// source, so it does not move this demuxer's read position.
+  #[napi]
+  pub async fn analyze_track(
+    &self,
+    track_id: i32,
+    options: Option<TrackAnalysisOptions>,
+  ) -> Result<TrackAnalysis> {
+    analyze_demuxer_track(&self.inner, track_id, options.unwrap_or_default()).await
+  }
+
+  /// Read every cue of a WebVTT subtitle track
+  ///
+  /// Cue timestamps and durations are in microseconds, with the settings and
+  /// identifier lines split from the text. Uses a separate view of the
+  /// source, so it does not move this demuxer's read position.
+  #[napi]
+  pub async fn read_subtitle_cues(&self, track_id: i32) -> Result<Vec<SubtitleCue>> {
+    read_demuxer_subtitle_cues(&self.inner, track_id).await
+  }
+
+  /// Iterate a video track's key frames, seeking from one to the next
+  ///
+  /// Only sync samples are read, optionally no closer together than
+  /// `intervalUs`. Pair with a `VideoDecoder` configured with
+  /// `keyFramesOnly: true` to build thumbnail strips without decoding the
+  /// frames in between. Uses a separate view of the source, so it does not
+  /// move this demuxer's read position.
+  #[napi]
+  pub fn key_frame_packets(
+    &self,
+    track_id: i32,
+    options: Option<KeyFramePacketsOptions>,
+  ) -> Result<KeyFramePacketIterator> {
+    key_frame_packets(&self.inner, track_id, options)
+  }
+
+  /// Read the next packet of the selected tracks for remuxing without decoding
+  ///
+  /// Returns null at end of stream. The record keeps PTS, DTS, duration and
+  /// flags in the track's time base; pass it to a muxer's `writePacket()`.
+  /// Shares the read position with async iteration.
+  #[napi]
+  pub async fn read_packet(&self) -> Result<Option<RawPacket>> {
+    read_demuxer_packet(&self.inner).await
+  }
+
   #[napi]
   pub fn demux(&self, count: Option<u32>) -> Result<()> {
     let inner = self.inner.clone();
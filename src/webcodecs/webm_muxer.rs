@@ -5,14 +5,20 @@
 
 use crate::codec::muxer::{ContainerFormat, MuxerOptions};
 use crate::ffi::AVCodecID;
+use crate::webcodecs::bitrate_stats::MuxerBitrateSummary;
+use crate::webcodecs::buffer_view::BufferView;
+use crate::webcodecs::codec_description::validate_video_description;
 use crate::webcodecs::codec_string::parse_codec_string;
+use crate::webcodecs::demuxer_base::{RawPacket, SubtitleCue, SubtitleKind, TrackSideData};
 use crate::webcodecs::encoded_audio_chunk::EncodedAudioChunk;
 use crate::webcodecs::encoded_video_chunk::EncodedVideoChunk;
+use crate::webcodecs::error::throw_type_error_unit;
 use crate::webcodecs::muxer_base::{
   EncodedAudioChunkMetadataJs, EncodedVideoChunkMetadataJs, GenericAudioTrackConfig,
   GenericVideoTrackConfig, MuxerFormat, MuxerInner, StreamingMuxerOptions, lock_muxer_inner,
-  lock_muxer_inner_mut,
+  lock_muxer_inner_mut, track_sample_aspect_ratio,
 };
+use crate::webcodecs::video_frame::VideoColorSpaceInit;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use std::sync::Mutex;
@@ -92,6 +98,9 @@ impl MuxerFormat for WebMFormat {
 pub struct WebMMuxerOptions {
   /// Enable live streaming mode (cluster-at-a-time output)
   pub live: Option<bool>,
+  /// Omit the "Lavf" writing-application tag, write the bare "Lavf" muxing
+  /// application and deterministic UIDs (default: false)
+  pub omit_encoder_tags: Option<bool>,
   /// Enable streaming output mode
   pub streaming: Option<StreamingMuxerOptions>,
 }
@@ -109,12 +118,20 @@ pub struct WebMVideoTrackConfig {
   pub width: u32,
   /// Video height in pixels
   pub height: u32,
-  /// Frame rate (frames per second)
+  /// Frame rate (frames per second); 29.97, 23.976 and 59.94 are taken as N*1000/1001
   pub framerate: Option<f64>,
   /// Codec-specific description data
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
   /// Whether the video has alpha channel (VP9 alpha support)
   pub alpha: Option<bool>,
+  /// Color space to signal in the container (MP4 colr/nclx, Matroska Colour)
+  pub color_space: Option<VideoColorSpaceInit>,
+  /// Display width in pixels (defaults to width); written as the track's presentation width
+  pub display_width: Option<u32>,
+  /// Display height in pixels (defaults to height); written as the track's presentation height
+  pub display_height: Option<u32>,
+  /// Stream-level side data, e.g. `DemuxerTrackInfo.sideData` when remuxing
+  pub side_data: Option<Vec<TrackSideData>>,
 }
 
 /// Audio track configuration for WebM muxer
@@ -127,7 +144,17 @@ pub struct WebMAudioTrackConfig {
   /// Number of audio channels
   pub number_of_channels: u32,
   /// Codec-specific description data
-  pub description: Option<Uint8Array>,
+  pub description: Option<BufferView>,
+}
+
+/// WebVTT subtitle track configuration for WebM muxer
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct WebMSubtitleTrackConfig {
+  /// Track kind, written as D_WEBVTT/SUBTITLES or D_WEBVTT/CAPTIONS (default: "subtitles")
+  pub kind: Option<SubtitleKind>,
+  /// Language tag (e.g. "eng")
+  pub language: Option<String>,
 }
 
 // ============================================================================
@@ -167,6 +194,7 @@ impl WebMMuxer {
     // Create muxer options with live streaming support
     let muxer_options = MuxerOptions {
       live: opts.live.unwrap_or(false),
+      omit_encoder_tags: opts.omit_encoder_tags.unwrap_or(false),
       ..Default::default()
     };
 
@@ -187,12 +215,28 @@ impl WebMMuxer {
   ///
   /// WebM supports VP8, VP9, and AV1 video codecs.
   #[napi]
-  pub fn add_video_track(&self, config: WebMVideoTrackConfig) -> Result<()> {
+  pub fn add_video_track(&self, env: Env, config: WebMVideoTrackConfig) -> Result<()> {
     lock_muxer_inner_mut!(self => _guard, inner);
 
     // Parse codec and validate
     let codec_id = WebMFormat::parse_video_codec(&config.codec)?;
 
+    if let Some(description) = config.description.as_deref()
+      && let Err(msg) = validate_video_description(&config.codec, codec_id, description)
+    {
+      return throw_type_error_unit(&env, &msg);
+    }
+
+    let sample_aspect_ratio = match track_sample_aspect_ratio(
+      config.width,
+      config.height,
+      config.display_width,
+      config.display_height,
+    ) {
+      Ok(sar) => sar,
+      Err(msg) => return throw_type_error_unit(&env, &msg),
+    };
+
     let generic_config = GenericVideoTrackConfig {
       codec: config.codec,
       codec_id,
@@ -201,11 +245,37 @@ impl WebMMuxer {
       framerate: config.framerate.unwrap_or(30.0),
       extradata: config.description.as_ref().map(|d| d.to_vec()),
       has_alpha: config.alpha.unwrap_or(false),
+      color_space: config.color_space,
+      icc_profile: None,
+      sample_aspect_ratio,
+      // Matroska timestamps are always in milliseconds
+      timescale: None,
+      side_data: config
+        .side_data
+        .as_ref()
+        .map(|side_data| side_data.iter().map(TrackSideData::to_stream).collect())
+        .unwrap_or_default(),
     };
 
     inner.add_video_track(generic_config)
   }
 
+  /// Replace the video track description before the first chunk is added
+  ///
+  /// For encoders that only deliver the description with the first keyframe.
+  #[napi]
+  pub fn update_video_description(&self, env: Env, description: BufferView) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+
+    if let Some(track) = inner.video_track_info.as_ref()
+      && let Err(msg) = validate_video_description(&track.codec, track.codec_id, &description)
+    {
+      return throw_type_error_unit(&env, &msg);
+    }
+
+    inner.update_video_description(&description)
+  }
+
   /// Add an audio track to the muxer
   ///
   /// WebM supports Opus and Vorbis audio codecs.
@@ -223,11 +293,21 @@ impl WebMMuxer {
       channels: config.number_of_channels,
       frame_size: WebMFormat::get_audio_frame_size(codec_id),
       extradata: config.description.as_ref().map(|d| d.to_vec()),
+      encoder_delay: None,
+      padding: None,
     };
 
     inner.add_audio_track(generic_config)
   }
 
+  /// Add a WebVTT subtitle track to the muxer
+  #[napi]
+  pub fn add_subtitle_track(&self, config: Option<WebMSubtitleTrackConfig>) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    let config = config.unwrap_or_default();
+    inner.add_subtitle_track(config.kind.unwrap_or_default(), config.language)
+  }
+
   /// Add an encoded video chunk to the muxer
   #[napi]
   pub fn add_video_chunk(
@@ -250,6 +330,27 @@ impl WebMMuxer {
     inner.add_audio_chunk(chunk, metadata.as_ref())
   }
 
+  /// Write a WebVTT cue to the subtitle track
+  ///
+  /// Timestamp and duration are in microseconds. The cue settings and
+  /// identifier are stored with the text in the WebM WebVTT block format.
+  /// Write cues in timestamp order alongside the media chunks.
+  #[napi]
+  pub fn write_subtitle_chunk(&self, cue: SubtitleCue) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    inner.write_subtitle_cue(&cue)
+  }
+
+  /// Write a raw packet with explicit DTS, bypassing EncodedVideoChunk/EncodedAudioChunk
+  ///
+  /// Use with a demuxer's `readPacket()` to remux without decoding: PTS, DTS,
+  /// duration and flags are kept as given (rescaled to the track's time base).
+  #[napi]
+  pub fn write_packet(&self, packet: RawPacket) -> Result<()> {
+    lock_muxer_inner_mut!(self => _guard, inner);
+    inner.write_raw_packet(&packet)
+  }
+
   /// Flush any buffered data
   #[napi]
   pub fn flush(&self) -> Result<()> {
@@ -265,6 +366,16 @@ impl WebMMuxer {
     Ok(Uint8Array::new(data))
   }
 
+  /// Achieved bitrate per track over the chunks added so far
+  ///
+  /// Call after `finalize()` for the final numbers. The peak is the largest
+  /// bit count over any 1-second window of chunk timestamps.
+  #[napi]
+  pub fn bitrate_summary(&self) -> Result<MuxerBitrateSummary> {
+    lock_muxer_inner!(self => _guard, inner);
+    Ok(inner.bitrate_summary())
+  }
+
   /// Read available data from streaming buffer (streaming mode only)
   ///
   /// Returns available data, or null if no data is ready yet.
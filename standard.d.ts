@@ -5,6 +5,8 @@
  * These types match the W3C WebCodecs specification for interoperability.
  */
 
+import type { HdrDynamicMetadata, VideoFrameMetadata } from './index'
+
 // ============================================================================
 // BufferSource Types
 // ============================================================================
@@ -44,6 +46,8 @@ export interface EncodedVideoChunkInit {
   data: BufferSource
   /** ArrayBuffers to transfer (optional, for zero-copy) */
   transfer?: ArrayBuffer[]
+  /** Per-frame HDR dynamic metadata passed through to decoded frames (non-standard) */
+  hdrDynamicMetadata?: HdrDynamicMetadata[]
 }
 
 // ============================================================================
@@ -116,7 +120,10 @@ export interface VideoEncoderConfig {
   displayWidth?: number
   /** Display height (optional, defaults to height) */
   displayHeight?: number
-  /** Target bitrate in bits per second */
+  /**
+   * Target bitrate in bits per second. When omitted (and `bitrateMode` is not 'quantizer'),
+   * a default is derived from the resolution, framerate and codec.
+   */
   bitrate?: number
   /** Target framerate */
   framerate?: number
@@ -134,6 +141,56 @@ export interface VideoEncoderConfig {
   avc?: AvcEncoderConfig
   /** HEVC-specific configuration */
   hevc?: HevcEncoderConfig
+  /**
+   * Two-pass rate control pass (non-standard, software H.264/HEVC/VP8/VP9/AV1 only).
+   * Pass 1 produces no chunks; read its statistics with `VideoEncoder.stats()` after `flush()`.
+   */
+  pass?: 1 | 2
+  /** First-pass statistics from `VideoEncoder.stats()`, required for pass 2 (non-standard) */
+  statsData?: Uint8Array
+  /**
+   * Replace the text of the x264/x265 info SEI, or remove the SEI with `''` (non-standard).
+   * When omitted the encoder's own version and option string is kept.
+   */
+  encoderInfo?: string
+  /**
+   * Attach PSNR/SSIM of every chunk against its source frame to `metadata.quality` (non-standard).
+   * Each chunk is decoded again on the encoder worker, which costs roughly one extra decode plus
+   * a full-frame comparison per frame, and chunks are held back until their frame is reconstructed.
+   * Off by default; ignored by hardware encoders.
+   */
+  computeQualityMetrics?: boolean
+  /**
+   * Force a key frame where a frame's luma histogram differs sharply from the previous frame's,
+   * i.e. at hard scene cuts (non-standard). Works the same for software and hardware encoders;
+   * chunks made key frames this way carry `metadata.sceneCut`. Off by default.
+   */
+  sceneCutDetection?: boolean
+  /**
+   * Attach `metadata.rateControl` to every chunk (non-standard): the chunk's actual bits, plus the
+   * quantizer and VBV buffer fullness when the encoder reports them. Fields the encoder does not
+   * provide are omitted. Off by default.
+   */
+  reportRateControlStats?: boolean
+  /**
+   * Drop a frame whose format cannot be converted to the encoder's (see `getCodecCapabilities()`)
+   * and keep the encoder configured (non-standard). The error callback still receives the
+   * NotSupportedError for every dropped frame. By default such a frame closes the encoder.
+   */
+  skipUnsupportedFrames?: boolean
+  /**
+   * Rotate and flip each frame's pixels by its `rotation`/`flip` before encoding (non-standard),
+   * for players that ignore orientation metadata. `width`/`height` then describe the upright
+   * picture (swapped for 90/270) and chunk metadata carries no rotation. By default the
+   * orientation is passed through as metadata.
+   */
+  applyRotation?: boolean
+  /**
+   * FFmpeg AVOptions for the codec context, e.g. `{ threads: 4, preset: 'slow' }` (non-standard).
+   * Merged over `setDefaultEncoderOptions()`; an option set here wins. Options the codec does
+   * not define are skipped with a warning.
+   */
+  ffmpegOptions?: Record<string, string | number>
 }
 
 /**
@@ -158,6 +215,20 @@ export interface HevcEncoderConfig {
 // VideoDecoder Types
 // ============================================================================
 
+/**
+ * Source of decoder output timestamps (extension)
+ * - 'container': the timestamp of the chunk each output was decoded from
+ * - 'normalized': outputs renumbered back to back from the first one
+ */
+export type TimestampMode = 'container' | 'normalized'
+
+/**
+ * How encoded video chunks map to pictures (extension)
+ * - 'accessUnit': every chunk holds one complete picture
+ * - 'nal': chunks hold one or more H.264 NAL units, e.g. one slice each
+ */
+export type InputGranularity = 'accessUnit' | 'nal'
+
 /**
  * VideoDecoder configuration
  * @see https://w3c.github.io/webcodecs/#dictdef-videodecoderconfig
@@ -185,6 +256,41 @@ export interface VideoDecoderConfig {
   rotation?: number
   /** Horizontal flip - W3C WebCodecs spec */
   flip?: boolean
+  /**
+   * Alpha channel handling for decoded frames (extension, default: 'keep').
+   * 'discard' outputs opaque frames (e.g., I420 instead of I420A).
+   */
+  alpha?: AlphaOption
+  /**
+   * Maximum number of output frames that may be held unclosed (extension).
+   * The decoder pauses pulling queued chunks at this count and resumes as
+   * frames are closed. Not enforced while a flush() is pending.
+   */
+  maxOutstandingFrames?: number
+  /**
+   * Decode only key chunks (extension, default: false).
+   * Delta chunks passed to decode() are dropped without an error, e.g. for
+   * thumbnail strips fed by a demuxer's keyFramePackets().
+   */
+  keyFramesOnly?: boolean
+  /**
+   * Source of output timestamps (extension, default: 'container').
+   * 'normalized' renumbers outputs contiguously from the first output.
+   */
+  timestampMode?: TimestampMode
+  /**
+   * Subtract the first output's timestamp from all outputs (extension, default: false).
+   * Kept across flush() and reset(); restarts when a configure() changes
+   * timestampMode or startAtZero.
+   */
+  startAtZero?: boolean
+  /**
+   * What each chunk passed to decode() holds (extension, default: 'accessUnit').
+   * 'nal' collects H.264 NAL units until the next picture starts and decodes
+   * them as one, stamped with the timestamp of the chunk carrying the first
+   * unit. The last picture is decoded at flush().
+   */
+  inputGranularity?: InputGranularity
 }
 
 // ============================================================================
@@ -216,6 +322,8 @@ export interface AudioEncoderConfig {
   opus?: OpusEncoderConfig
   /** AAC-specific configuration */
   aac?: AacEncoderConfig
+  /** FFmpeg AVOptions for the codec context, merged over `setDefaultEncoderOptions()` (non-standard) */
+  ffmpegOptions?: Record<string, string | number>
 }
 
 /**
@@ -263,6 +371,17 @@ export interface AudioDecoderConfig {
   numberOfChannels: number
   /** Codec-specific description */
   description?: BufferSource
+  /**
+   * Source of output timestamps (extension, default: 'container').
+   * 'normalized' renumbers outputs contiguously from the first output.
+   */
+  timestampMode?: TimestampMode
+  /**
+   * Subtract the first output's timestamp from all outputs (extension, default: false).
+   * Kept across flush() and reset(); restarts when a configure() changes
+   * timestampMode or startAtZero.
+   */
+  startAtZero?: boolean
 }
 
 // ============================================================================
@@ -343,6 +462,8 @@ export interface VideoFrameBufferInit {
   displayHeight?: number
   /** Color space */
   colorSpace?: VideoColorSpaceInit
+  /** Frame metadata */
+  metadata?: VideoFrameMetadata
 }
 
 // ============================================================================
@@ -422,8 +543,12 @@ export interface AudioDataInit {
  * @see https://w3c.github.io/webcodecs/#dictdef-imagedecodeinit
  */
 export interface ImageDecoderInit {
-  /** Image data */
-  data: BufferSource | ReadableStream<Uint8Array>
+  /**
+   * Image data
+   *
+   * (non-standard) May be omitted to supply the bytes with appendData() and endOfData()
+   */
+  data?: BufferSource | ReadableStream<Uint8Array>
   /** MIME type */
   type: string
   /** Color space conversion */